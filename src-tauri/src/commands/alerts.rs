@@ -1,32 +1,132 @@
-use rusqlite::params;
-use tauri::State;
+use rusqlite::{params, Connection, Row};
+use tauri::{AppHandle, Emitter, State};
 
+use crate::commands::notification_settings::resolve_alert_cue;
 use crate::db::Database;
-use crate::models::{Alert, AlertStats, AlertWithDetails, CreateAlertInput};
-use crate::utils::{require_admin, require_edit_permission, require_view_permission, validate_session};
+use crate::error::AppError;
+use crate::models::{Alert, AlertAcknowledgement, AlertStats, AlertWithDetails, CreateAlertInput};
+use crate::utils::{
+    audit_json, count_rows, emit_change, get_int, record_audit, require_permission, require_view_permission,
+    validate_session, PageRequest, Paginated, ACTION_CREATED, ACTION_DELETED, ACTION_UPDATED, ALERT_CHANGED_EVENT,
+    CLEAR_ALERTS_AFTER_DAYS_KEY, DEFAULT_CLEAR_ALERTS_AFTER_DAYS,
+};
+
+/// Event emitted for a newly created alert that should notify the current user.
+/// Every change, notified or not, also goes out as alert:changed.
+pub const ALERT_CREATED_EVENT: &str = "alert:created";
+
+/// Alert columns with is_read and read_at as one user sees them, from the
+/// `ur` join in details_select
+const ALERT_COLUMNS: &str = "a.id, a.alert_type, a.priority, a.title, a.message, a.machine_id, a.project_id,
+     a.created_at, (a.is_read = 1 OR ur.alert_id IS NOT NULL) AS is_read, COALESCE(ur.read_at, a.read_at) AS read_at";
+
+/// Condition for an alert nobody has read yet. Automatic checks use it to
+/// avoid raising the same alert again while the first is still unread.
+pub const UNREAD_BY_ANYONE: &str =
+    "is_read = 0 AND NOT EXISTS (SELECT 1 FROM alert_reads WHERE alert_reads.alert_id = alerts.id)";
+
+/// SELECT ... FROM for alerts `a` with everything AlertWithDetails needs,
+/// read state being `user_id`'s
+fn details_select(user_id: i64) -> String {
+    format!(
+        "SELECT {}, m.name as machine_name, p.name as project_name,
+                ack.id as ack_id, ack.user_id as ack_user_id, COALESCE(au.full_name, au.username) as ack_user_name,
+                ack.comment as ack_comment, ack.acknowledged_at as ack_at
+         FROM alerts a
+         LEFT JOIN alert_reads ur ON ur.alert_id = a.id AND ur.user_id = {}
+         LEFT JOIN machines m ON a.machine_id = m.id
+         LEFT JOIN projects p ON a.project_id = p.id
+         LEFT JOIN alert_acknowledgements ack ON ack.alert_id = a.id
+         LEFT JOIN users au ON au.id = ack.user_id",
+        ALERT_COLUMNS, user_id
+    )
+}
+
+/// Map a row from details_select, resolving the cue for the user and device
+fn alert_with_details(
+    conn: &Connection,
+    row: &Row,
+    user_id: i64,
+    device_name: Option<&str>,
+) -> rusqlite::Result<AlertWithDetails> {
+    let alert = Alert::from_row(row)?;
+    let cue = resolve_alert_cue(conn, user_id, device_name, &alert.priority);
+    let acknowledgement = match row.get::<_, Option<i64>>("ack_id")? {
+        Some(id) => Some(AlertAcknowledgement {
+            id,
+            alert_id: alert.id,
+            user_id: row.get("ack_user_id")?,
+            user_name: row.get("ack_user_name")?,
+            comment: row.get("ack_comment")?,
+            acknowledged_at: row.get("ack_at")?,
+        }),
+        None => None,
+    };
+    Ok(AlertWithDetails {
+        alert,
+        machine_name: row.get("machine_name")?,
+        project_name: row.get("project_name")?,
+        cue: Some(cue),
+        acknowledgement,
+    })
+}
+
+/// Condition for alert `a` being unread by a user. The shared is_read flag
+/// predates per-user reads and still counts as read for everyone.
+pub fn unread_by(user_id: i64) -> String {
+    format!(
+        "a.is_read = 0 AND NOT EXISTS (SELECT 1 FROM alert_reads r WHERE r.alert_id = a.id AND r.user_id = {})",
+        user_id
+    )
+}
+
+/// Condition for alert `a` having been read by every active user
+const READ_BY_EVERYONE: &str = "(a.is_read = 1 OR NOT EXISTS (
+         SELECT 1 FROM users u WHERE u.is_active = 1
+           AND NOT EXISTS (SELECT 1 FROM alert_reads r WHERE r.alert_id = a.id AND r.user_id = u.id)))";
+
+fn alert_ids_where(conn: &Connection, condition: &str) -> Result<Vec<i64>, String> {
+    conn.prepare(&format!("SELECT a.id FROM alerts a WHERE {}", condition))
+        .and_then(|mut stmt| stmt.query_map([], |row| row.get(0))?.collect())
+        .map_err(|e| e.to_string())
+}
+
+/// Record that a user has read an alert. Reading it again keeps the first time.
+pub fn mark_read_by(conn: &Connection, alert_id: i64, user_id: i64) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR IGNORE INTO alert_reads (alert_id, user_id) VALUES (?1, ?2)",
+        params![alert_id, user_id],
+    )
+    .map_err(|e| format!("Failed to mark alert as read: {}", e))?;
+    Ok(())
+}
 
 /// Get all alerts (with optional filters)
+///
+/// Read state is the calling user's: unread_only leaves out what they have read
 #[tauri::command]
 pub fn get_alerts(
     token: String,
     unread_only: Option<bool>,
     alert_type: Option<String>,
-    limit: Option<i32>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    device_name: Option<String>,
     db: State<'_, Database>,
-) -> Result<Vec<AlertWithDetails>, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+) -> Result<Paginated<AlertWithDetails>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
     let mut conditions = Vec::new();
     let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
     if unread_only.unwrap_or(false) {
-        conditions.push("a.is_read = 0");
+        conditions.push(unread_by(user.id));
     }
 
     if let Some(atype) = alert_type {
-        conditions.push("a.alert_type = ?");
+        conditions.push("a.alert_type = ?".to_string());
         params_vec.push(Box::new(atype));
     }
 
@@ -36,20 +136,20 @@ pub fn get_alerts(
         format!("WHERE {}", conditions.join(" AND "))
     };
 
-    let limit_clause = match limit {
-        Some(l) => format!("LIMIT {}", l),
-        None => "LIMIT 100".to_string(),
-    };
+    let page = PageRequest::resolve(&conn, limit, offset, Some(100))?;
+    let count_params: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|v| v.as_ref()).collect();
+    let total = count_rows(
+        &conn,
+        &format!("SELECT COUNT(*) FROM alerts a {}", where_clause),
+        count_params.as_slice(),
+    )?;
+    page.bind(&mut params_vec);
 
     let query = format!(
-        "SELECT a.*, m.name as machine_name, p.name as project_name
-         FROM alerts a
-         LEFT JOIN machines m ON a.machine_id = m.id
-         LEFT JOIN projects p ON a.project_id = p.id
-         {}
-         ORDER BY a.created_at DESC
-         {}",
-        where_clause, limit_clause
+        "{} {} ORDER BY a.created_at DESC{}",
+        details_select(user.id),
+        where_clause,
+        page.sql()
     );
 
     let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
@@ -57,45 +157,30 @@ pub fn get_alerts(
     let params: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|v| v.as_ref()).collect();
 
     let alerts: Vec<AlertWithDetails> = stmt
-        .query_map(params.as_slice(), |row| {
-            let alert = Alert::from_row(row)?;
-            Ok(AlertWithDetails {
-                alert,
-                machine_name: row.get("machine_name")?,
-                project_name: row.get("project_name")?,
-            })
-        })
+        .query_map(params.as_slice(), |row| alert_with_details(&conn, row, user.id, device_name.as_deref()))
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
         .collect();
 
-    Ok(alerts)
+    Ok(Paginated::new(alerts, page, total))
 }
 
 /// Get single alert
 #[tauri::command]
-pub fn get_alert(token: String, id: i64, db: State<'_, Database>) -> Result<AlertWithDetails, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+pub fn get_alert(
+    token: String,
+    id: i64,
+    device_name: Option<String>,
+    db: State<'_, Database>,
+) -> Result<AlertWithDetails, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
-    conn.query_row(
-        "SELECT a.*, m.name as machine_name, p.name as project_name
-         FROM alerts a
-         LEFT JOIN machines m ON a.machine_id = m.id
-         LEFT JOIN projects p ON a.project_id = p.id
-         WHERE a.id = ?1",
-        [id],
-        |row| {
-            let alert = Alert::from_row(row)?;
-            Ok(AlertWithDetails {
-                alert,
-                machine_name: row.get("machine_name")?,
-                project_name: row.get("project_name")?,
-            })
-        },
-    )
-    .map_err(|_| "Alert not found".to_string())
+    conn.query_row(&format!("{} WHERE a.id = ?1", details_select(user.id)), [id], |row| {
+        alert_with_details(&conn, row, user.id, device_name.as_deref())
+    })
+    .map_err(|_| AppError::NotFound("Alert not found".to_string()))
 }
 
 /// Create alert
@@ -103,21 +188,23 @@ pub fn get_alert(token: String, id: i64, db: State<'_, Database>) -> Result<Aler
 pub fn create_alert(
     token: String,
     input: CreateAlertInput,
+    device_name: Option<String>,
+    app: AppHandle,
     db: State<'_, Database>,
-) -> Result<AlertWithDetails, String> {
+) -> Result<AlertWithDetails, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "alerts.manage")?;
 
     // Validate alert type
     if !["info", "warning", "error", "maintenance", "schedule"].contains(&input.alert_type.as_str())
     {
-        return Err("Invalid alert type".to_string());
+        return Err(AppError::invalid_field("alert_type", "invalid alert type"));
     }
 
     // Validate priority
     if !["low", "medium", "high", "critical"].contains(&input.priority.as_str()) {
-        return Err("Invalid priority".to_string());
+        return Err(AppError::invalid_field("priority", "invalid priority"));
     }
 
     conn.execute(
@@ -135,90 +222,221 @@ pub fn create_alert(
     .map_err(|e| format!("Failed to create alert: {}", e))?;
 
     let new_id = conn.last_insert_rowid();
+    let created = conn
+        .query_row("SELECT * FROM alerts WHERE id = ?1", [new_id], Alert::from_row)
+        .map_err(|e| e.to_string())?;
+    record_audit(&conn, &user, "create", "alerts", Some(new_id), None, audit_json(&created).as_deref());
     drop(conn);
-    get_alert(token, new_id, db)
+    let alert = get_alert(token, new_id, device_name, db)?;
+
+    emit_change(&app, ALERT_CHANGED_EVENT, ACTION_CREATED, vec![new_id]);
+    // Let the UI raise a notification only when the user's settings ask for one
+    if alert.cue.as_ref().is_some_and(|cue| cue.notify) {
+        if let Err(e) = app.emit(ALERT_CREATED_EVENT, &alert) {
+            log::warn!("Failed to emit alert notification: {}", e);
+        }
+    }
+
+    Ok(alert)
 }
 
-/// Mark alert as read
+/// Mark alert as read for the current user
 #[tauri::command]
-pub fn mark_alert_read(token: String, id: i64, db: State<'_, Database>) -> Result<(), String> {
+pub fn mark_alert_read(token: String, id: i64, app: AppHandle, db: State<'_, Database>) -> Result<(), AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
     require_view_permission(&user)?;
 
-    conn.execute(
-        "UPDATE alerts SET is_read = 1, read_at = CURRENT_TIMESTAMP WHERE id = ?1",
-        [id],
-    )
-    .map_err(|e| format!("Failed to mark alert as read: {}", e))?;
+    let exists: bool = conn
+        .query_row("SELECT EXISTS (SELECT 1 FROM alerts WHERE id = ?1)", [id], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if !exists {
+        return Err(AppError::NotFound("Alert not found".to_string()));
+    }
+    mark_read_by(&conn, id, user.id)?;
 
+    emit_change(&app, ALERT_CHANGED_EVENT, ACTION_UPDATED, vec![id]);
     Ok(())
 }
 
-/// Mark all alerts as read
+/// Mark all alerts as read for the current user
 #[tauri::command]
-pub fn mark_all_alerts_read(token: String, db: State<'_, Database>) -> Result<i32, String> {
+pub fn mark_all_alerts_read(token: String, app: AppHandle, db: State<'_, Database>) -> Result<i32, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
     require_view_permission(&user)?;
 
+    let unread = unread_by(user.id);
+    let ids = alert_ids_where(&conn, &unread)?;
     let count = conn
         .execute(
-            "UPDATE alerts SET is_read = 1, read_at = CURRENT_TIMESTAMP WHERE is_read = 0",
-            [],
+            &format!(
+                "INSERT OR IGNORE INTO alert_reads (alert_id, user_id) SELECT a.id, ?1 FROM alerts a WHERE {}",
+                unread
+            ),
+            [user.id],
         )
         .map_err(|e| format!("Failed to mark alerts as read: {}", e))?;
 
+    emit_change(&app, ALERT_CHANGED_EVENT, ACTION_UPDATED, ids);
     Ok(count as i32)
 }
 
+/// Acknowledge an alert with a note of what was done about it. An alert is
+/// acknowledged once, and acknowledging also marks it read for that user.
+#[tauri::command]
+pub fn acknowledge_alert(
+    token: String,
+    id: i64,
+    comment: String,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<AlertWithDetails, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "alerts.acknowledge")?;
+
+    let comment = comment.trim();
+    if comment.is_empty() {
+        return Err(AppError::invalid_field("comment", "describe what was done about the alert"));
+    }
+    let existing = conn
+        .query_row("SELECT * FROM alerts WHERE id = ?1", [id], Alert::from_row)
+        .map_err(|_| AppError::NotFound("Alert not found".to_string()))?;
+    let acknowledged: bool = conn
+        .query_row(
+            "SELECT EXISTS (SELECT 1 FROM alert_acknowledgements WHERE alert_id = ?1)",
+            [id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if acknowledged {
+        return Err(AppError::Conflict("Alert has already been acknowledged".to_string()));
+    }
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT INTO alert_acknowledgements (alert_id, user_id, comment) VALUES (?1, ?2, ?3)",
+        params![id, user.id, comment],
+    )
+    .map_err(|e| format!("Failed to acknowledge alert: {}", e))?;
+    mark_read_by(&tx, id, user.id)?;
+    record_audit(
+        &tx,
+        &user,
+        "acknowledge",
+        "alerts",
+        Some(id),
+        None,
+        Some(
+            &serde_json::json!({ "title": existing.title, "priority": existing.priority, "comment": comment })
+                .to_string(),
+        ),
+    );
+    tx.commit().map_err(|e| e.to_string())?;
+    drop(conn);
+
+    emit_change(&app, ALERT_CHANGED_EVENT, ACTION_UPDATED, vec![id]);
+    get_alert(token, id, None, db)
+}
+
+/// Critical alerts nobody has acknowledged yet, oldest first, for the
+/// dashboard to poll
+#[tauri::command]
+pub fn get_unacknowledged_critical_alerts(
+    token: String,
+    db: State<'_, Database>,
+) -> Result<Vec<AlertWithDetails>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "{} WHERE a.priority = 'critical' AND ack.id IS NULL ORDER BY a.created_at, a.id",
+            details_select(user.id)
+        ))
+        .map_err(|e| e.to_string())?;
+    let alerts = stmt
+        .query_map([], |row| alert_with_details(&conn, row, user.id, None))
+        .map_err(|e| e.to_string())?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+    Ok(alerts)
+}
+
 /// Dismiss/delete alert
 #[tauri::command]
-pub fn dismiss_alert(token: String, id: i64, db: State<'_, Database>) -> Result<(), String> {
+pub fn dismiss_alert(token: String, id: i64, app: AppHandle, db: State<'_, Database>) -> Result<(), AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "alerts.manage")?;
 
+    let existing = conn
+        .query_row("SELECT * FROM alerts WHERE id = ?1", [id], Alert::from_row)
+        .map_err(|_| "Alert not found".to_string())?;
     conn.execute("DELETE FROM alerts WHERE id = ?1", [id])
         .map_err(|e| format!("Failed to dismiss alert: {}", e))?;
+    record_audit(&conn, &user, "delete", "alerts", Some(id), audit_json(&existing).as_deref(), None);
 
+    emit_change(&app, ALERT_CHANGED_EVENT, ACTION_DELETED, vec![id]);
     Ok(())
 }
 
-/// Clear all read alerts
+/// Delete alerts every active user has read, and alerts older than the
+/// clear_alerts_after_days setting whether read or not
 #[tauri::command]
-pub fn clear_read_alerts(token: String, db: State<'_, Database>) -> Result<i32, String> {
+pub fn clear_read_alerts(token: String, app: AppHandle, db: State<'_, Database>) -> Result<i32, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_admin(&user)?;
+    require_permission(&conn, &user, "alerts.clear")?;
 
+    let days = get_int(&conn, CLEAR_ALERTS_AFTER_DAYS_KEY, DEFAULT_CLEAR_ALERTS_AFTER_DAYS);
+    let condition = format!("{} OR a.created_at < datetime('now', '-{} days')", READ_BY_EVERYONE, days);
+    let ids = alert_ids_where(&conn, &condition)?;
     let count = conn
-        .execute("DELETE FROM alerts WHERE is_read = 1", [])
+        .execute(
+            &format!("DELETE FROM alerts WHERE id IN (SELECT a.id FROM alerts a WHERE {})", condition),
+            [],
+        )
         .map_err(|e| format!("Failed to clear alerts: {}", e))?;
+    if count > 0 {
+        record_audit(
+            &conn,
+            &user,
+            "clear_read",
+            "alerts",
+            None,
+            None,
+            Some(&serde_json::json!({ "deleted": count, "older_than_days": days }).to_string()),
+        );
+    }
 
+    emit_change(&app, ALERT_CHANGED_EVENT, ACTION_DELETED, ids);
     Ok(count as i32)
 }
 
-/// Get alert statistics
+/// Get alert statistics, with unread counts for the current user
 #[tauri::command]
-pub fn get_alert_stats(token: String, db: State<'_, Database>) -> Result<AlertStats, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+pub fn get_alert_stats(token: String, db: State<'_, Database>) -> Result<AlertStats, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
     let total: i32 = conn
         .query_row("SELECT COUNT(*) FROM alerts", [], |row| row.get(0))
         .unwrap_or(0);
 
+    let unread_condition = unread_by(user.id);
     let unread: i32 = conn
-        .query_row("SELECT COUNT(*) FROM alerts WHERE is_read = 0", [], |row| {
+        .query_row(&format!("SELECT COUNT(*) FROM alerts a WHERE {}", unread_condition), [], |row| {
             row.get(0)
         })
         .unwrap_or(0);
 
     let critical: i32 = conn
         .query_row(
-            "SELECT COUNT(*) FROM alerts WHERE priority = 'critical' AND is_read = 0",
+            &format!("SELECT COUNT(*) FROM alerts a WHERE a.priority = 'critical' AND {}", unread_condition),
             [],
             |row| row.get(0),
         )
@@ -226,14 +444,17 @@ pub fn get_alert_stats(token: String, db: State<'_, Database>) -> Result<AlertSt
 
     let high: i32 = conn
         .query_row(
-            "SELECT COUNT(*) FROM alerts WHERE priority = 'high' AND is_read = 0",
+            &format!("SELECT COUNT(*) FROM alerts a WHERE a.priority = 'high' AND {}", unread_condition),
             [],
             |row| row.get(0),
         )
         .unwrap_or(0);
 
     let by_type: Vec<(String, i32)> = conn
-        .prepare("SELECT alert_type, COUNT(*) FROM alerts WHERE is_read = 0 GROUP BY alert_type")
+        .prepare(&format!(
+            "SELECT a.alert_type, COUNT(*) FROM alerts a WHERE {} GROUP BY a.alert_type",
+            unread_condition
+        ))
         .ok()
         .and_then(|mut stmt| {
             stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
@@ -251,15 +472,15 @@ pub fn get_alert_stats(token: String, db: State<'_, Database>) -> Result<AlertSt
     })
 }
 
-/// Get unread alert count (lightweight for header badge)
+/// Get the current user's unread alert count (lightweight for header badge)
 #[tauri::command]
-pub fn get_unread_alert_count(token: String, db: State<'_, Database>) -> Result<i32, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+pub fn get_unread_alert_count(token: String, db: State<'_, Database>) -> Result<i32, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
     let count: i32 = conn
-        .query_row("SELECT COUNT(*) FROM alerts WHERE is_read = 0", [], |row| {
+        .query_row(&format!("SELECT COUNT(*) FROM alerts a WHERE {}", unread_by(user.id)), [], |row| {
             row.get(0)
         })
         .unwrap_or(0);
@@ -0,0 +1,89 @@
+use rusqlite::{params, Connection};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{ApiToken, CreatedApiToken};
+use crate::utils::{generate_api_token, hash_api_token, record_audit, require_admin, validate_session};
+
+const API_TOKEN_SELECT: &str = "SELECT t.*, u.username FROM api_tokens t JOIN users u ON t.user_id = u.id";
+
+fn fetch_api_token(conn: &Connection, id: i64) -> Result<ApiToken, String> {
+    conn.query_row(&format!("{} WHERE t.id = ?1", API_TOKEN_SELECT), [id], ApiToken::from_row)
+        .map_err(|_| "API token not found".to_string())
+}
+
+/// Create an API token that acts as `user_id` (Admin only). The token is in
+/// the response and cannot be retrieved again.
+#[tauri::command]
+pub fn create_api_token(
+    token: String,
+    user_id: i64,
+    name: String,
+    db: State<'_, Database>,
+) -> Result<CreatedApiToken, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_admin(&conn, &user)?;
+
+    if name.trim().is_empty() {
+        return Err(AppError::validation("token name is required"));
+    }
+    let is_active: bool = conn
+        .query_row("SELECT is_active FROM users WHERE id = ?1", [user_id], |row| row.get(0))
+        .map_err(|_| "User not found".to_string())?;
+    if !is_active {
+        return Err(AppError::validation("cannot create a token for an inactive user"));
+    }
+
+    let secret = generate_api_token();
+    let hint = secret[secret.len() - 4..].to_string();
+    conn.execute(
+        "INSERT INTO api_tokens (user_id, name, token_hash, token_hint, created_by) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![user_id, name.trim(), hash_api_token(&secret), hint, user.id],
+    )
+    .map_err(|e| format!("Failed to create API token: {}", e))?;
+
+    let id = conn.last_insert_rowid();
+    record_audit(&conn, &user, "create", "api_tokens", Some(id), None, Some(name.trim()));
+    Ok(CreatedApiToken { api_token: fetch_api_token(&conn, id)?, token: secret })
+}
+
+/// List API tokens, optionally for one user, newest first (Admin only)
+#[tauri::command]
+pub fn get_api_tokens(token: String, user_id: Option<i64>, db: State<'_, Database>) -> Result<Vec<ApiToken>, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_admin(&conn, &user)?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "{} WHERE (?1 IS NULL OR t.user_id = ?1) ORDER BY t.created_at DESC, t.id DESC",
+            API_TOKEN_SELECT
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let tokens = stmt
+        .query_map([user_id], ApiToken::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(tokens)
+}
+
+/// Revoke an API token so it no longer authenticates (Admin only)
+#[tauri::command]
+pub fn revoke_api_token(token: String, id: i64, db: State<'_, Database>) -> Result<ApiToken, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_admin(&conn, &user)?;
+
+    let existing = fetch_api_token(&conn, id)?;
+    if !existing.revoked {
+        conn.execute("UPDATE api_tokens SET revoked = 1 WHERE id = ?1", [id])
+            .map_err(|e| format!("Failed to revoke API token: {}", e))?;
+        record_audit(&conn, &user, "revoke", "api_tokens", Some(id), Some(&existing.name), None);
+    }
+    Ok(fetch_api_token(&conn, id)?)
+}
@@ -0,0 +1,125 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::utils::{
+    record_audit, require_admin, set_setting, setting_definition, setting_definitions, validate_session,
+    SettingDefinition, SettingKind, WEEKDAY_NAMES,
+};
+
+/// An editable setting with its current value and what it may be set to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingValue {
+    pub key: String,
+    pub value: String,
+    pub default_value: String,
+    /// "integer", "float" or "string"
+    pub value_type: String,
+    pub description: String,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// Accepted values for settings with a fixed list, e.g. weekdays
+    pub options: Vec<String>,
+    pub updated_by: Option<i64>,
+    pub updated_by_name: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+type StoredSetting = (String, Option<i64>, Option<String>, Option<String>);
+
+fn stored_setting(conn: &Connection, key: &str) -> Result<Option<StoredSetting>, String> {
+    conn.query_row(
+        "SELECT s.value, s.updated_by, COALESCE(u.full_name, u.username), s.updated_at
+         FROM settings s LEFT JOIN users u ON s.updated_by = u.id
+         WHERE s.key = ?1",
+        params![key],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+fn setting_value(conn: &Connection, definition: SettingDefinition) -> Result<SettingValue, String> {
+    let stored = stored_setting(conn, definition.key)?;
+    let (min, max, options) = match definition.kind {
+        SettingKind::Int { min, max } => (Some(min as f64), Some(max as f64), Vec::new()),
+        SettingKind::Float { min, max } => (Some(min), Some(max), Vec::new()),
+        SettingKind::Time => (None, None, Vec::new()),
+        SettingKind::Weekday => (None, None, WEEKDAY_NAMES.iter().map(|day| day.to_string()).collect()),
+    };
+    let (value, updated_by, updated_by_name, updated_at) = match stored {
+        Some(stored) => stored,
+        None => (definition.default.clone(), None, None, None),
+    };
+
+    Ok(SettingValue {
+        key: definition.key.to_string(),
+        value,
+        value_type: definition.value_type().to_string(),
+        default_value: definition.default,
+        description: definition.description.to_string(),
+        min,
+        max,
+        options,
+        updated_by,
+        updated_by_name,
+        updated_at,
+    })
+}
+
+/// List every editable setting with its current value (Admin only)
+#[tauri::command]
+pub fn get_settings(token: String, db: State<'_, Database>) -> Result<Vec<SettingValue>, AppError> {
+    {
+        let conn = db.conn.lock();
+        let user = validate_session(&conn, &token)?;
+        require_admin(&conn, &user)?;
+    }
+    let conn = db.read();
+
+    Ok(setting_definitions()
+        .into_iter()
+        .map(|definition| setting_value(&conn, definition))
+        .collect::<Result<Vec<_>, String>>()?)
+}
+
+/// Change one setting (Admin only)
+///
+/// The value is checked against the setting's type and range, e.g.
+/// session_lifetime_hours must be from 1 to 720, and takes effect the next
+/// time the setting is read.
+#[tauri::command]
+pub fn update_setting(
+    token: String,
+    key: String,
+    value: String,
+    db: State<'_, Database>,
+) -> Result<SettingValue, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_admin(&conn, &user)?;
+
+    let definition = setting_definition(key.trim())
+        .ok_or_else(|| AppError::NotFound(format!("Unknown setting '{}'", key.trim())))?;
+    let value = definition
+        .normalize(&value)
+        .map_err(|message| AppError::invalid_field(definition.key, format!("{} {}", definition.key, message)))?;
+
+    let old_value = stored_setting(&conn, definition.key)?.map(|(value, ..)| value);
+    set_setting(&conn, definition.key, &value, definition.value_type(), Some(user.id))?;
+    record_audit(
+        &conn,
+        &user,
+        "update",
+        "settings",
+        None,
+        old_value
+            .map(|old| serde_json::json!({ "key": definition.key, "value": old }).to_string())
+            .as_deref(),
+        Some(&serde_json::json!({ "key": definition.key, "value": value }).to_string()),
+    );
+
+    Ok(setting_value(&conn, definition)?)
+}
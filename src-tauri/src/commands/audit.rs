@@ -1,25 +1,29 @@
-use rusqlite::params;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, Connection};
+use serde_json::{Map, Value};
 use tauri::State;
 
+use crate::commands::schedules::reinstate_entry;
 use crate::db::Database;
+use crate::error::AppError;
 use crate::models::{AuditFilters, AuditLog};
-use crate::utils::{require_admin, validate_session};
+use crate::utils::{
+    count_rows, record_audit, require_admin, validate_session, PageRequest, Paginated, AUDIT_TABLE_AUTH,
+    AUTH_AUDIT_ACTIONS,
+};
 
-/// Get audit logs with optional filters
+/// Get one page of audit logs, newest first, with the total matching the filters
 #[tauri::command]
 pub fn get_audit_logs(
     token: String,
     filters: Option<AuditFilters>,
     db: State<'_, Database>,
-) -> Result<Vec<AuditLog>, String> {
+) -> Result<Paginated<AuditLog>, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_admin(&user)?;
+    require_admin(&conn, &user)?;
 
-    let mut query = String::from(
-        "SELECT id, user_id, username, action, table_name, record_id, old_values, new_values, timestamp
-         FROM audit_log WHERE 1=1"
-    );
+    let mut query = String::from(" FROM audit_log WHERE 1=1");
     let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
     if let Some(ref f) = filters {
@@ -45,18 +49,23 @@ pub fn get_audit_logs(
         }
     }
 
-    query.push_str(" ORDER BY timestamp DESC");
+    let page = PageRequest::resolve(
+        &conn,
+        filters.as_ref().and_then(|f| f.limit),
+        filters.as_ref().and_then(|f| f.offset),
+        Some(100),
+    )?;
+    let count_params: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+    let total = count_rows(&conn, &format!("SELECT COUNT(*){}", query), count_params.as_slice())?;
+    page.bind(&mut params_vec);
 
-    if let Some(ref f) = filters {
-        if let Some(limit) = f.limit {
-            query.push_str(&format!(" LIMIT {}", limit));
-        }
-        if let Some(offset) = f.offset {
-            query.push_str(&format!(" OFFSET {}", offset));
-        }
-    } else {
-        query.push_str(" LIMIT 100"); // Default limit
-    }
+    // Entries written in the same second tie on timestamp; the id keeps pages from overlapping
+    let query = format!(
+        "SELECT id, user_id, username, action, table_name, record_id, old_values, new_values, timestamp{}
+         ORDER BY timestamp DESC, id DESC{}",
+        query,
+        page.sql()
+    );
 
     let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
 
@@ -68,7 +77,7 @@ pub fn get_audit_logs(
         .filter_map(|r| r.ok())
         .collect();
 
-    Ok(logs)
+    Ok(Paginated::new(logs, page, total))
 }
 
 /// Get audit log statistics
@@ -76,45 +85,39 @@ pub fn get_audit_logs(
 pub fn get_audit_stats(
     token: String,
     db: State<'_, Database>,
-) -> Result<AuditStats, String> {
+) -> Result<AuditStats, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_admin(&user)?;
-
-    let total: i64 = conn
-        .query_row("SELECT COUNT(*) FROM audit_log", [], |row| row.get(0))
-        .unwrap_or(0);
+    require_admin(&conn, &user)?;
 
     let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
-    let today_count: i64 = conn
-        .query_row(
-            "SELECT COUNT(*) FROM audit_log WHERE timestamp >= ?",
-            [&today],
-            |row| row.get(0),
-        )
-        .unwrap_or(0);
-
     let week_ago = (chrono::Utc::now() - chrono::Duration::days(7))
         .format("%Y-%m-%d")
         .to_string();
-    let week_count: i64 = conn
-        .query_row(
-            "SELECT COUNT(*) FROM audit_log WHERE timestamp >= ?",
-            [&week_ago],
+
+    // Data changes; sign-ins and denials are counted separately in `auth`
+    let count_since = |since: &str, auth: bool| -> i64 {
+        conn.query_row(
+            "SELECT COUNT(*) FROM audit_log WHERE timestamp >= ?1 AND (table_name = ?2) = ?3",
+            params![since, AUDIT_TABLE_AUTH, auth],
             |row| row.get(0),
         )
-        .unwrap_or(0);
+        .unwrap_or(0)
+    };
+    let total = count_since("", false);
+    let today_count = count_since(&today, false);
+    let week_count = count_since(&week_ago, false);
 
     // Actions breakdown
     let mut stmt = conn
         .prepare(
             "SELECT action, COUNT(*) as count FROM audit_log
-             GROUP BY action ORDER BY count DESC",
+             WHERE table_name != ?1 GROUP BY action ORDER BY count DESC",
         )
         .map_err(|e| e.to_string())?;
 
     let actions_breakdown: Vec<(String, i64)> = stmt
-        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .query_map([AUDIT_TABLE_AUTH], |row| Ok((row.get(0)?, row.get(1)?)))
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
         .collect();
@@ -123,12 +126,12 @@ pub fn get_audit_stats(
     let mut stmt = conn
         .prepare(
             "SELECT table_name, COUNT(*) as count FROM audit_log
-             GROUP BY table_name ORDER BY count DESC",
+             WHERE table_name != ?1 GROUP BY table_name ORDER BY count DESC",
         )
         .map_err(|e| e.to_string())?;
 
     let tables_breakdown: Vec<(String, i64)> = stmt
-        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .query_map([AUDIT_TABLE_AUTH], |row| Ok((row.get(0)?, row.get(1)?)))
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
         .collect();
@@ -137,16 +140,47 @@ pub fn get_audit_stats(
     let mut stmt = conn
         .prepare(
             "SELECT COALESCE(username, 'Unknown') as name, COUNT(*) as count
-             FROM audit_log GROUP BY user_id ORDER BY count DESC LIMIT 10",
+             FROM audit_log WHERE table_name != ?1 GROUP BY user_id ORDER BY count DESC LIMIT 10",
         )
         .map_err(|e| e.to_string())?;
 
     let top_users: Vec<(String, i64)> = stmt
-        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .query_map([AUDIT_TABLE_AUTH], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    // Authentication events
+    let mut stmt = conn
+        .prepare(
+            "SELECT action, COUNT(*) as count FROM audit_log
+             WHERE table_name = ?1 GROUP BY action ORDER BY count DESC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let auth_actions: Vec<(String, i64)> = stmt
+        .query_map([AUDIT_TABLE_AUTH], |row| Ok((row.get(0)?, row.get(1)?)))
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
         .collect();
 
+    let failed_logins_today: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM audit_log
+             WHERE table_name = ?1 AND action = 'login_failed' AND timestamp >= ?2",
+            params![AUDIT_TABLE_AUTH, today],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+
+    let auth = AuthAuditStats {
+        total: count_since("", true),
+        today_count: count_since(&today, true),
+        week_count: count_since(&week_ago, true),
+        failed_logins_today,
+        actions_breakdown: auth_actions,
+    };
+
     Ok(AuditStats {
         total,
         today_count,
@@ -154,6 +188,7 @@ pub fn get_audit_stats(
         actions_breakdown,
         tables_breakdown,
         top_users,
+        auth,
     })
 }
 
@@ -162,16 +197,16 @@ pub fn get_audit_stats(
 pub fn get_audit_filter_options(
     token: String,
     db: State<'_, Database>,
-) -> Result<AuditFilterOptions, String> {
+) -> Result<AuditFilterOptions, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_admin(&user)?;
+    require_admin(&conn, &user)?;
 
     // Get unique table names
     let mut stmt = conn
         .prepare("SELECT DISTINCT table_name FROM audit_log ORDER BY table_name")
         .map_err(|e| e.to_string())?;
-    let tables: Vec<String> = stmt
+    let mut tables: Vec<String> = stmt
         .query_map([], |row| row.get(0))
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
@@ -181,7 +216,7 @@ pub fn get_audit_filter_options(
     let mut stmt = conn
         .prepare("SELECT DISTINCT action FROM audit_log ORDER BY action")
         .map_err(|e| e.to_string())?;
-    let actions: Vec<String> = stmt
+    let mut actions: Vec<String> = stmt
         .query_map([], |row| row.get(0))
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
@@ -205,6 +240,18 @@ pub fn get_audit_filter_options(
         .filter_map(|r| r.ok())
         .collect();
 
+    // Authentication actions are offered even before any have been recorded
+    if !tables.iter().any(|t| t == AUDIT_TABLE_AUTH) {
+        tables.push(AUDIT_TABLE_AUTH.to_string());
+        tables.sort();
+    }
+    for action in AUTH_AUDIT_ACTIONS {
+        if !actions.iter().any(|a| a == action) {
+            actions.push(action.to_string());
+        }
+    }
+    actions.sort();
+
     Ok(AuditFilterOptions {
         tables,
         actions,
@@ -212,6 +259,180 @@ pub fn get_audit_filter_options(
     })
 }
 
+/// Tables whose deleted records can be restored from the audit log
+const RESTORABLE_TABLES: [&str; 5] = ["clients", "machines", "projects", "schedules", "maintenance"];
+
+/// A column of a table, as reported by `PRAGMA table_info`
+pub(crate) struct TableColumn {
+    pub name: String,
+    pub not_null: bool,
+}
+
+pub(crate) fn table_columns(conn: &Connection, table: &str) -> Result<Vec<TableColumn>, String> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", table))
+        .map_err(|e| e.to_string())?;
+    let columns = stmt
+        .query_map([], |row| Ok(TableColumn { name: row.get("name")?, not_null: row.get("notnull")? }))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(columns)
+}
+
+/// Foreign keys of a table as (column, referenced table, referenced column)
+pub(crate) fn foreign_keys(conn: &Connection, table: &str) -> Result<Vec<(String, String, String)>, String> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA foreign_key_list({})", table))
+        .map_err(|e| e.to_string())?;
+    let keys = stmt
+        .query_map([], |row| {
+            let to: Option<String> = row.get("to")?;
+            Ok((row.get("from")?, row.get("table")?, to.unwrap_or_else(|| "id".to_string())))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(keys)
+}
+
+pub(crate) fn sql_value(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(*b as i64),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(text) => SqlValue::Text(text.clone()),
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+/// Recreate a deleted record from the old values of its "delete" audit entry
+/// (Admin only).
+///
+/// Only clients, machines, projects, schedules and maintenance can be
+/// restored, each once. The record keeps its old id unless that has been
+/// reused. A reference to a record that is gone is cleared when the column
+/// allows it, and refuses the restore when it does not. Records deleted along
+/// with it, such as a project's schedule entries, are not brought back. A
+/// restored schedule entry is held to the same week-lock and double-booking
+/// rules as a new one, and its hours count towards its project again.
+#[tauri::command]
+pub fn restore_from_audit(
+    token: String,
+    audit_log_id: i64,
+    db: State<'_, Database>,
+) -> Result<RestoreResult, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_admin(&conn, &user)?;
+
+    let entry = conn
+        .query_row("SELECT * FROM audit_log WHERE id = ?1", [audit_log_id], AuditLog::from_row)
+        .map_err(|_| AppError::NotFound("Audit entry not found".to_string()))?;
+    if entry.action != "delete" {
+        return Err(AppError::validation("only deletions can be restored"));
+    }
+    let table = RESTORABLE_TABLES
+        .iter()
+        .copied()
+        .find(|t| *t == entry.table_name)
+        .ok_or_else(|| AppError::validation(format!("records from '{}' cannot be restored", entry.table_name)))?;
+    let Some(Ok(Value::Object(old_values))) = entry.old_values.as_deref().map(serde_json::from_str::<Value>) else {
+        return Err(AppError::validation("the audit entry does not hold the deleted record"));
+    };
+
+    let already_restored: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM audit_log
+             WHERE action = 'restore' AND table_name = ?1
+               AND json_extract(new_values, '$.restored_from_audit_id') = ?2",
+            params![table, audit_log_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if already_restored > 0 {
+        return Err(AppError::Conflict("This record has already been restored".to_string()));
+    }
+
+    // Keep only values for columns the table still has
+    let columns = table_columns(&conn, table)?;
+    let mut record: Map<String, Value> = old_values
+        .into_iter()
+        .filter(|(key, _)| columns.iter().any(|c| &c.name == key))
+        .collect();
+
+    let original_id = record.get("id").and_then(Value::as_i64).or(entry.record_id);
+    if let Some(id) = original_id {
+        let taken: i64 = conn
+            .query_row(&format!("SELECT COUNT(*) FROM {} WHERE id = ?1", table), [id], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        if taken > 0 {
+            record.remove("id");
+        }
+    }
+
+    let mut cleared_references = Vec::new();
+    let mut missing = Vec::new();
+    for (column, parent_table, parent_column) in foreign_keys(&conn, table)? {
+        let Some(value) = record.get(&column).filter(|v| !v.is_null()) else {
+            continue;
+        };
+        let exists: i64 = conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM {} WHERE {} = ?1", parent_table, parent_column),
+                [sql_value(value)],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if exists > 0 {
+            continue;
+        }
+        let description = format!("{} #{} ({})", parent_table, value, column);
+        if columns.iter().any(|c| c.name == column && c.not_null) {
+            missing.push(description);
+        } else {
+            record.insert(column, Value::Null);
+            cleared_references.push(description);
+        }
+    }
+    if !missing.is_empty() {
+        return Err(AppError::Conflict(format!(
+            "Cannot restore: it depends on records that no longer exist: {}",
+            missing.join(", ")
+        )));
+    }
+
+    let names: Vec<&str> = record.keys().map(String::as_str).collect();
+    let placeholders = vec!["?"; names.len()].join(", ");
+    let values: Vec<SqlValue> = record.values().map(sql_value).collect();
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        &format!("INSERT INTO {} ({}) VALUES ({})", table, names.join(", "), placeholders),
+        rusqlite::params_from_iter(values),
+    )
+    .map_err(|e| format!("Failed to restore record: {}", e))?;
+    let record_id = tx.last_insert_rowid();
+    if table == "schedules" {
+        reinstate_entry(&tx, record_id)?;
+    }
+
+    record.insert("id".to_string(), Value::from(record_id));
+    record.insert("restored_from_audit_id".to_string(), Value::from(audit_log_id));
+    record_audit(&tx, &user, "restore", table, Some(record_id), None, Some(&Value::Object(record).to_string()));
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(RestoreResult {
+        table_name: table.to_string(),
+        record_id,
+        original_id,
+        cleared_references,
+    })
+}
+
 // Response types
 use serde::{Deserialize, Serialize};
 
@@ -223,6 +444,17 @@ pub struct AuditStats {
     pub actions_breakdown: Vec<(String, i64)>,
     pub tables_breakdown: Vec<(String, i64)>,
     pub top_users: Vec<(String, i64)>,
+    /// Sign-ins, sign-outs and permission denials, which the figures above leave out
+    pub auth: AuthAuditStats,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthAuditStats {
+    pub total: i64,
+    pub today_count: i64,
+    pub week_count: i64,
+    pub failed_logins_today: i64,
+    pub actions_breakdown: Vec<(String, i64)>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -231,3 +463,13 @@ pub struct AuditFilterOptions {
     pub actions: Vec<String>,
     pub users: Vec<(i64, String)>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreResult {
+    pub table_name: String,
+    /// Id of the recreated record; differs from `original_id` when that was reused
+    pub record_id: i64,
+    pub original_id: Option<i64>,
+    /// References to since-deleted records that were cleared, e.g. "clients #4 (client_id)"
+    pub cleared_references: Vec<String>,
+}
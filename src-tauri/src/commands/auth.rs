@@ -1,32 +1,82 @@
+use chrono::Utc;
+use rusqlite::{params, Connection};
 use tauri::State;
 
+use crate::commands::DesktopNotifier;
 use crate::db::Database;
-use crate::models::{AuthResponse, UserPublic};
-use crate::utils::{change_password, invalidate_session, login_user, validate_session};
+use crate::error::AppError;
+use crate::models::{AuthResponse, LoginInput, Session, SessionCleanup, SessionPublic, TotpSetup, UserPublic};
+use crate::utils::{
+    change_password, generate_totp_secret, invalidate_session, login_user, open_totp_secret, purge_expired_sessions,
+    record_audit, record_auth_event, require_admin, seal_totp_secret, totp_uri, validate_session, verify_password,
+    verify_totp,
+};
 
-/// Login command
+/// A user's unexpired, unrevoked sessions, newest first
+fn active_sessions(conn: &Connection, user_id: i64, current_token: &str) -> Result<Vec<SessionPublic>, String> {
+    let now = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM sessions WHERE user_id = ?1 AND is_valid = 1 AND expires_at >= ?2
+             ORDER BY created_at DESC, id DESC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let sessions = stmt
+        .query_map(params![user_id, now], Session::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .map(|session| SessionPublic::from_session(session, current_token))
+        .collect();
+
+    Ok(sessions)
+}
+
+/// Login command. `remember_me` keeps an Operator or Viewer signed in for
+/// days, e.g. on a shop-floor display. Fails with code `totp_required` until
+/// a valid `totp_code` is given for users with two-factor login.
 #[tauri::command]
 pub fn login(
     username: String,
     password: String,
+    remember_me: Option<bool>,
+    device_name: Option<String>,
+    totp_code: Option<String>,
+    notifier: State<'_, DesktopNotifier>,
     db: State<'_, Database>,
-) -> Result<AuthResponse, String> {
+) -> Result<AuthResponse, AppError> {
     let conn = db.conn.lock();
-    login_user(&conn, &username, &password)
+    let input = LoginInput { username, password, remember_me, device_name, totp_code };
+    let response = login_user(&conn, &input, &db.secret_key_path())?;
+    notifier.sign_in(&response.token);
+    Ok(response)
 }
 
 /// Logout command
 #[tauri::command]
-pub fn logout(token: String, db: State<'_, Database>) -> Result<(), String> {
+pub fn logout(token: String, notifier: State<'_, DesktopNotifier>, db: State<'_, Database>) -> Result<(), AppError> {
+    notifier.sign_out(&token);
     let conn = db.conn.lock();
-    invalidate_session(&conn, &token)
+    // Signing out of an already expired session is not worth an audit entry
+    if let Ok(user) = validate_session(&conn, &token) {
+        record_auth_event(&conn, Some(user.id), &user.username, "logout", None);
+    }
+    Ok(invalidate_session(&conn, &token)?)
 }
 
 /// Get current user from token
+///
+/// The frontend calls this when restoring a saved session, so it also marks
+/// that session as the one desktop notifications are for
 #[tauri::command]
-pub fn get_current_user(token: String, db: State<'_, Database>) -> Result<UserPublic, String> {
+pub fn get_current_user(
+    token: String,
+    notifier: State<'_, DesktopNotifier>,
+    db: State<'_, Database>,
+) -> Result<UserPublic, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
+    notifier.sign_in(&token);
     Ok(UserPublic::from(user))
 }
 
@@ -37,18 +87,183 @@ pub fn cmd_change_password(
     old_password: String,
     new_password: String,
     db: State<'_, Database>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    change_password(&conn, user.id, &old_password, &new_password)
+    Ok(change_password(&conn, user.id, &old_password, &new_password)?)
 }
 
 /// Validate token (check if still valid)
 #[tauri::command]
-pub fn validate_token(token: String, db: State<'_, Database>) -> Result<bool, String> {
+pub fn validate_token(token: String, db: State<'_, Database>) -> Result<bool, AppError> {
     let conn = db.conn.lock();
     match validate_session(&conn, &token) {
         Ok(_) => Ok(true),
         Err(_) => Ok(false),
     }
 }
+
+/// List the signed-in user's active sessions, marking the current one
+#[tauri::command]
+pub fn get_my_sessions(token: String, db: State<'_, Database>) -> Result<Vec<SessionPublic>, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    Ok(active_sessions(&conn, user.id, &token)?)
+}
+
+/// List another user's active sessions (Admin only)
+#[tauri::command]
+pub fn get_user_sessions(token: String, user_id: i64, db: State<'_, Database>) -> Result<Vec<SessionPublic>, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_admin(&conn, &user)?;
+
+    Ok(active_sessions(&conn, user_id, &token)?)
+}
+
+/// Sign out one session. Users may revoke their own sessions, including the
+/// current one; Admins may revoke anyone's.
+#[tauri::command]
+pub fn revoke_session(token: String, session_id: i64, db: State<'_, Database>) -> Result<(), AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+
+    let owner: i64 = conn
+        .query_row("SELECT user_id FROM sessions WHERE id = ?1", [session_id], |row| row.get(0))
+        .map_err(|_| "Session not found".to_string())?;
+    if owner != user.id {
+        require_admin(&conn, &user)?;
+    }
+
+    conn.execute("UPDATE sessions SET is_valid = 0 WHERE id = ?1", [session_id])
+        .map_err(|e| format!("Failed to revoke session: {}", e))?;
+    if owner != user.id {
+        record_audit(
+            &conn,
+            &user,
+            "revoke_session",
+            "sessions",
+            Some(session_id),
+            None,
+            Some(&serde_json::json!({ "user_id": owner }).to_string()),
+        );
+    }
+    Ok(())
+}
+
+/// Sign out every session of the signed-in user except the current one.
+/// Returns how many were revoked.
+#[tauri::command]
+pub fn revoke_other_sessions(token: String, db: State<'_, Database>) -> Result<usize, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+
+    conn.execute(
+        "UPDATE sessions SET is_valid = 0 WHERE user_id = ?1 AND token != ?2 AND is_valid = 1",
+        params![user.id, token],
+    )
+    .map_err(|e| AppError::Database(format!("Failed to revoke sessions: {}", e)))
+}
+
+/// Delete sessions that expired over 30 days ago (Admin only)
+#[tauri::command]
+pub fn cleanup_sessions(token: String, db: State<'_, Database>) -> Result<SessionCleanup, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_admin(&conn, &user)?;
+
+    let cleanup = purge_expired_sessions(&conn)?;
+    if cleanup.deleted > 0 {
+        record_audit(
+            &conn,
+            &user,
+            "cleanup",
+            "sessions",
+            None,
+            None,
+            Some(&serde_json::json!({ "deleted": cleanup.deleted }).to_string()),
+        );
+    }
+    Ok(cleanup)
+}
+
+/// Start setting up two-factor login. Returns a new secret and its otpauth
+/// link for an authenticator app; it takes effect once `confirm_totp` checks
+/// a code from the app.
+#[tauri::command]
+pub fn enable_totp(token: String, db: State<'_, Database>) -> Result<TotpSetup, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    if user.totp_secret.is_some() {
+        return Err(AppError::Conflict("Two-factor login is already enabled".to_string()));
+    }
+
+    let secret = generate_totp_secret();
+    let otpauth_uri = totp_uri(&secret, &user.username)?;
+    conn.execute(
+        "UPDATE users SET totp_pending_secret = ?1 WHERE id = ?2",
+        params![seal_totp_secret(&db.secret_key_path(), &secret)?, user.id],
+    )
+    .map_err(|e| format!("Failed to start two-factor setup: {}", e))?;
+
+    Ok(TotpSetup { secret, otpauth_uri })
+}
+
+/// Finish setting up two-factor login with a code from the authenticator app
+#[tauri::command]
+pub fn confirm_totp(token: String, code: String, db: State<'_, Database>) -> Result<UserPublic, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+
+    let pending: Option<String> = conn
+        .query_row("SELECT totp_pending_secret FROM users WHERE id = ?1", [user.id], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    let pending = pending.ok_or("Start two-factor setup first")?;
+    if !verify_totp(&open_totp_secret(&db.secret_key_path(), &pending)?, &code) {
+        return Err(AppError::validation("the code does not match. Check the time on your device"));
+    }
+
+    conn.execute(
+        "UPDATE users SET totp_secret = totp_pending_secret, totp_pending_secret = NULL,
+             updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?1",
+        [user.id],
+    )
+    .map_err(|e| format!("Failed to enable two-factor login: {}", e))?;
+    record_audit(&conn, &user, "enable_totp", "users", Some(user.id), None, None);
+
+    validate_session(&conn, &token).map(UserPublic::from)
+}
+
+/// Turn off two-factor login, confirming with the caller's password. An Admin
+/// can pass `user_id` to turn it off for someone who lost their device.
+#[tauri::command]
+pub fn disable_totp(
+    token: String,
+    password: String,
+    user_id: Option<i64>,
+    db: State<'_, Database>,
+) -> Result<(), AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    let target_id = user_id.unwrap_or(user.id);
+    if target_id != user.id {
+        require_admin(&conn, &user)?;
+    }
+    if !verify_password(&password, &user.password_hash) {
+        return Err(AppError::invalid_field("password", "password is incorrect"));
+    }
+
+    let updated = conn
+        .execute(
+            "UPDATE users SET totp_secret = NULL, totp_pending_secret = NULL, updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?1",
+            [target_id],
+        )
+        .map_err(|e| format!("Failed to disable two-factor login: {}", e))?;
+    if updated == 0 {
+        return Err(AppError::NotFound("User not found".to_string()));
+    }
+    record_audit(&conn, &user, "disable_totp", "users", Some(target_id), None, None);
+    Ok(())
+}
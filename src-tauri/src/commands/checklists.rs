@@ -2,7 +2,8 @@ use rusqlite::params;
 use serde::{Deserialize, Serialize};
 use tauri::State;
 use crate::db::Database;
-use crate::utils::{require_edit_permission, require_view_permission, validate_session};
+use crate::error::AppError;
+use crate::utils::{require_permission, require_view_permission, validate_session};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChecklistTemplate {
@@ -43,10 +44,10 @@ pub struct ChecklistCompletionItem {
 }
 
 #[tauri::command]
-pub fn get_checklist_templates(token: String, machine_id: Option<i64>, db: State<'_, Database>) -> Result<Vec<ChecklistTemplate>, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+pub fn get_checklist_templates(token: String, machine_id: Option<i64>, db: State<'_, Database>) -> Result<Vec<ChecklistTemplate>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
     let sql = if machine_id.is_some() {
         "SELECT * FROM checklist_templates WHERE (machine_id = ?1 OR machine_id IS NULL) AND is_active = 1 ORDER BY id ASC"
@@ -76,10 +77,10 @@ pub fn get_checklist_templates(token: String, machine_id: Option<i64>, db: State
 }
 
 #[tauri::command]
-pub fn create_checklist_template(token: String, machine_id: Option<i64>, checklist_item: String, db: State<'_, Database>) -> Result<ChecklistTemplate, String> {
+pub fn create_checklist_template(token: String, machine_id: Option<i64>, checklist_item: String, db: State<'_, Database>) -> Result<ChecklistTemplate, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "checklists.edit")?;
     conn.execute("INSERT INTO checklist_templates (machine_id, checklist_item) VALUES (?1, ?2)", params![machine_id, checklist_item]).map_err(|e| e.to_string())?;
     let id = conn.last_insert_rowid();
     let t: ChecklistTemplate = conn.query_row("SELECT * FROM checklist_templates WHERE id = ?1", params![id], |row| Ok(ChecklistTemplate {
@@ -93,19 +94,19 @@ pub fn create_checklist_template(token: String, machine_id: Option<i64>, checkli
 }
 
 #[tauri::command]
-pub fn delete_checklist_template(token: String, id: i64, db: State<'_, Database>) -> Result<(), String> {
+pub fn delete_checklist_template(token: String, id: i64, db: State<'_, Database>) -> Result<(), AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "checklists.edit")?;
     conn.execute("DELETE FROM checklist_templates WHERE id = ?1", params![id]).map_err(|e| e.to_string())?;
     Ok(())
 }
 
 #[tauri::command]
-pub fn submit_checklist(token: String, input: SubmitChecklistInput, db: State<'_, Database>) -> Result<(), String> {
+pub fn submit_checklist(token: String, input: SubmitChecklistInput, db: State<'_, Database>) -> Result<(), AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "checklists.edit")?;
     for item in &input.completions {
         conn.execute(
             "INSERT INTO checklist_completions (machine_id, template_id, checked_by, check_date, is_completed, notes) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
@@ -117,10 +118,10 @@ pub fn submit_checklist(token: String, input: SubmitChecklistInput, db: State<'_
 }
 
 #[tauri::command]
-pub fn get_checklist_completions(token: String, machine_id: i64, check_date: String, db: State<'_, Database>) -> Result<Vec<ChecklistCompletion>, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+pub fn get_checklist_completions(token: String, machine_id: i64, check_date: String, db: State<'_, Database>) -> Result<Vec<ChecklistCompletion>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
     let mut stmt = conn.prepare(
         "SELECT cc.*, ct.checklist_item, m.name as machine_name, u.full_name as operator_name
          FROM checklist_completions cc
@@ -0,0 +1,163 @@
+use rusqlite::{params, Connection};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{ClientContact, CreateContactInput, UpdateContactInput};
+use crate::utils::{record_audit, require_permission, require_view_permission, validate_session};
+
+fn fetch_contact(conn: &Connection, id: i64) -> Result<ClientContact, String> {
+    conn.query_row("SELECT * FROM client_contacts WHERE id = ?1", [id], ClientContact::from_row)
+        .map_err(|_| "Contact not found".to_string())
+}
+
+/// A client's contacts, primary first
+pub(crate) fn load_client_contacts(conn: &Connection, client_id: i64) -> Result<Vec<ClientContact>, String> {
+    let mut stmt = conn
+        .prepare("SELECT * FROM client_contacts WHERE client_id = ?1 ORDER BY is_primary DESC, name")
+        .map_err(|e| e.to_string())?;
+
+    let contacts = stmt
+        .query_map([client_id], ClientContact::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(contacts)
+}
+
+/// Clear the primary flag on a client's other contacts, so a unique index
+/// allows the new primary
+fn demote_primary(conn: &Connection, client_id: i64) -> Result<(), String> {
+    conn.execute(
+        "UPDATE client_contacts SET is_primary = 0, updated_at = CURRENT_TIMESTAMP
+         WHERE client_id = ?1 AND is_primary = 1",
+        [client_id],
+    )
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+/// Get a client's contacts, primary first
+#[tauri::command]
+pub fn get_client_contacts(
+    token: String,
+    client_id: i64,
+    db: State<'_, Database>,
+) -> Result<Vec<ClientContact>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    Ok(load_client_contacts(&conn, client_id)?)
+}
+
+/// Add a contact to a client (Admin only)
+#[tauri::command]
+pub fn create_client_contact(
+    token: String,
+    client_id: i64,
+    input: CreateContactInput,
+    db: State<'_, Database>,
+) -> Result<ClientContact, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "clients.manage")?;
+
+    if input.name.trim().is_empty() {
+        return Err(AppError::validation("contact name is required"));
+    }
+    conn.query_row("SELECT id FROM clients WHERE id = ?1", [client_id], |row| row.get::<_, i64>(0))
+        .map_err(|_| "Client not found".to_string())?;
+
+    let is_primary = input.is_primary.unwrap_or(false);
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    if is_primary {
+        demote_primary(&tx, client_id)?;
+    }
+    tx.execute(
+        "INSERT INTO client_contacts (client_id, name, role, email, phone, is_primary)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![client_id, input.name.trim(), input.role, input.email, input.phone, is_primary],
+    )
+    .map_err(|e| format!("Failed to create contact: {}", e))?;
+    let id = tx.last_insert_rowid();
+    record_audit(&tx, &user, "create", "client_contacts", Some(id), None, Some(input.name.trim()));
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(fetch_contact(&conn, id)?)
+}
+
+/// Update a client contact (Admin only)
+#[tauri::command]
+pub fn update_client_contact(
+    token: String,
+    id: i64,
+    input: UpdateContactInput,
+    db: State<'_, Database>,
+) -> Result<ClientContact, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "clients.manage")?;
+
+    let existing = fetch_contact(&conn, id)?;
+    let mut updates = Vec::new();
+    let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(name) = &input.name {
+        if name.trim().is_empty() {
+            return Err(AppError::validation("contact name is required"));
+        }
+        updates.push("name = ?");
+        values.push(Box::new(name.trim().to_string()));
+    }
+    if let Some(role) = &input.role {
+        updates.push("role = ?");
+        values.push(Box::new(role.clone()));
+    }
+    if let Some(email) = &input.email {
+        updates.push("email = ?");
+        values.push(Box::new(email.clone()));
+    }
+    if let Some(phone) = &input.phone {
+        updates.push("phone = ?");
+        values.push(Box::new(phone.clone()));
+    }
+    if let Some(is_primary) = input.is_primary {
+        updates.push("is_primary = ?");
+        values.push(Box::new(is_primary));
+    }
+
+    if updates.is_empty() {
+        return Ok(existing);
+    }
+
+    updates.push("updated_at = CURRENT_TIMESTAMP");
+    let query = format!("UPDATE client_contacts SET {} WHERE id = ?", updates.join(", "));
+    values.push(Box::new(id));
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    if input.is_primary == Some(true) && !existing.is_primary {
+        demote_primary(&tx, existing.client_id)?;
+    }
+    let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+    tx.execute(&query, params.as_slice())
+        .map_err(|e| format!("Failed to update contact: {}", e))?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(fetch_contact(&conn, id)?)
+}
+
+/// Delete a client contact (Admin only)
+#[tauri::command]
+pub fn delete_client_contact(token: String, id: i64, db: State<'_, Database>) -> Result<(), AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "clients.manage")?;
+
+    let existing = fetch_contact(&conn, id)?;
+    conn.execute("DELETE FROM client_contacts WHERE id = ?1", [id])
+        .map_err(|e| format!("Failed to delete contact: {}", e))?;
+    record_audit(&conn, &user, "delete", "client_contacts", Some(id), Some(&existing.name), None);
+    Ok(())
+}
@@ -0,0 +1,268 @@
+use std::collections::{HashMap, HashSet};
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::commands::machine_import::ImportLineError;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::Client;
+use crate::utils::{
+    parse_csv, record_audit, require_permission, run_operation, validate_session, CsvRecord, OperationContext,
+    OperationOutcome,
+};
+
+/// Columns accepted in a client CSV; only `name` is required
+const CLIENT_COLUMNS: [&str; 5] = ["name", "contact_email", "contact_phone", "address", "notes"];
+
+/// What the import did, or on a dry run would do, with one CSV line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientImportRow {
+    pub line: usize,
+    pub name: String,
+    /// create, merge or skip
+    pub action: String,
+    /// The client created or matched; None for a new client on a dry run
+    pub client_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientImportResult {
+    pub dry_run: bool,
+    pub total_rows: i64,
+    pub created: i64,
+    pub merged: i64,
+    pub skipped: i64,
+    pub rows: Vec<ClientImportRow>,
+    /// Clients created or merged into; empty on a dry run
+    pub imported: Vec<Client>,
+    pub errors: Vec<ImportLineError>,
+}
+
+struct ClientRow {
+    name: String,
+    contact_email: Option<String>,
+    contact_phone: Option<String>,
+    address: Option<String>,
+    notes: Option<String>,
+}
+
+/// Map header names to column positions, rejecting unknown or missing columns
+fn read_header(header: &CsvRecord) -> Result<HashMap<String, usize>, String> {
+    let mut columns = HashMap::new();
+    for (i, name) in header.fields.iter().enumerate() {
+        let name = name.trim().to_lowercase();
+        if !CLIENT_COLUMNS.contains(&name.as_str()) {
+            return Err(format!("Unknown column '{}'. Accepted columns: {}", name, CLIENT_COLUMNS.join(", ")));
+        }
+        if columns.insert(name.clone(), i).is_some() {
+            return Err(format!("Column '{}' appears more than once", name));
+        }
+    }
+    if !columns.contains_key("name") {
+        return Err("Missing required column 'name'".to_string());
+    }
+    Ok(columns)
+}
+
+/// Read one CSV row. Blank cells count as missing.
+fn read_row(columns: &HashMap<String, usize>, record: &CsvRecord) -> Result<ClientRow, String> {
+    let cell = |column: &str| -> Option<String> {
+        columns
+            .get(column)
+            .and_then(|&i| record.fields.get(i))
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    let name = cell("name").ok_or("name is required")?;
+    if let Some(email) = cell("contact_email") {
+        if !email.contains('@') {
+            return Err(format!("contact_email is not an email address: '{}'", email));
+        }
+    }
+
+    Ok(ClientRow {
+        name,
+        contact_email: cell("contact_email"),
+        contact_phone: cell("contact_phone"),
+        address: cell("address"),
+        notes: cell("notes"),
+    })
+}
+
+fn import_client_rows(
+    conn: &Connection,
+    ctx: &OperationContext,
+    records: Vec<CsvRecord>,
+    merge_duplicates: bool,
+    dry_run: bool,
+) -> Result<ClientImportResult, String> {
+    let mut records = records.into_iter();
+    let header = records.next().ok_or("The file is empty")?;
+    let columns = read_header(&header)?;
+    let rows: Vec<CsvRecord> = records.collect();
+    let total = rows.len() as i64;
+    ctx.progress(0, total, "Importing clients")?;
+
+    // Existing clients by name, ignoring case
+    let mut existing: HashMap<String, i64> = HashMap::new();
+    {
+        let mut stmt = conn.prepare("SELECT id, name FROM clients").map_err(|e| e.to_string())?;
+        let clients = stmt
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))
+            .map_err(|e| e.to_string())?;
+        for (id, name) in clients.filter_map(|r| r.ok()) {
+            existing.entry(name.trim().to_lowercase()).or_insert(id);
+        }
+    }
+
+    // A dry run makes the same changes and rolls them back
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let mut seen_names = HashSet::new();
+    let mut outcomes = Vec::new();
+    let mut errors = Vec::new();
+
+    for (i, record) in rows.iter().enumerate() {
+        let result = read_row(&columns, record).and_then(|row| {
+            if !seen_names.insert(row.name.to_lowercase()) {
+                return Err(format!("Client '{}' appears more than once in the file", row.name));
+            }
+            let outcome = match existing.get(&row.name.to_lowercase()) {
+                Some(&id) if merge_duplicates => {
+                    // Filled-in cells overwrite the existing client; blank ones leave it alone
+                    tx.execute(
+                        "UPDATE clients SET contact_email = COALESCE(?1, contact_email),
+                                contact_phone = COALESCE(?2, contact_phone),
+                                address = COALESCE(?3, address),
+                                notes = COALESCE(?4, notes),
+                                updated_at = CURRENT_TIMESTAMP
+                         WHERE id = ?5",
+                        params![row.contact_email, row.contact_phone, row.address, row.notes, id],
+                    )
+                    .map_err(|e| format!("Failed to merge client: {}", e))?;
+                    ("merge", id)
+                }
+                Some(&id) => ("skip", id),
+                None => {
+                    tx.execute(
+                        "INSERT INTO clients (name, contact_email, contact_phone, address, notes)
+                         VALUES (?1, ?2, ?3, ?4, ?5)",
+                        params![row.name, row.contact_email, row.contact_phone, row.address, row.notes],
+                    )
+                    .map_err(|e| format!("Failed to create client: {}", e))?;
+                    ("create", tx.last_insert_rowid())
+                }
+            };
+            Ok((row.name, outcome))
+        });
+        match result {
+            Ok((name, (action, id))) => outcomes.push(ClientImportRow {
+                line: record.line,
+                name,
+                action: action.to_string(),
+                // A new client's id only lasts if the import is saved
+                client_id: if action == "create" && dry_run { None } else { Some(id) },
+            }),
+            Err(error) => errors.push(ImportLineError {
+                line: record.line,
+                name: columns
+                    .get("name")
+                    .and_then(|&i| record.fields.get(i))
+                    .map(|n| n.trim().to_string())
+                    .filter(|n| !n.is_empty()),
+                error,
+            }),
+        }
+        ctx.progress(i as i64 + 1, total, "Importing clients")?;
+    }
+
+    let count = |action: &str| outcomes.iter().filter(|row| row.action == action).count() as i64;
+    let (created, merged, skipped) = (count("create"), count("merge"), count("skip"));
+    if dry_run {
+        drop(tx);
+        return Ok(ClientImportResult {
+            dry_run,
+            total_rows: total,
+            created,
+            merged,
+            skipped,
+            rows: outcomes,
+            imported: Vec::new(),
+            errors,
+        });
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    let imported = outcomes
+        .iter()
+        .filter(|row| row.action != "skip")
+        .filter_map(|row| row.client_id)
+        .map(|id| {
+            conn.query_row("SELECT * FROM clients WHERE id = ?1", [id], Client::from_row)
+                .map_err(|e| e.to_string())
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(ClientImportResult {
+        dry_run,
+        total_rows: total,
+        created,
+        merged,
+        skipped,
+        rows: outcomes,
+        imported,
+        errors,
+    })
+}
+
+/// Create clients from a CSV file (Admin only).
+///
+/// The header row names the columns: name, contact_email, contact_phone,
+/// address and notes. A name matching an existing client, ignoring case, is
+/// skipped, or with `merge_duplicates` has its filled-in cells written over
+/// that client. Valid rows are saved together in one transaction; the rest
+/// come back as per-line errors. With `dry_run` nothing is saved.
+#[tauri::command]
+pub fn import_clients_csv(
+    token: String,
+    file_path: String,
+    merge_duplicates: Option<bool>,
+    dry_run: Option<bool>,
+    background: Option<bool>,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<OperationOutcome<ClientImportResult>, AppError> {
+    let user = {
+        let conn = db.conn.lock();
+        let user = validate_session(&conn, &token)?;
+        require_permission(&conn, &user, "clients.manage")?;
+        user
+    };
+
+    let text = std::fs::read_to_string(&file_path).map_err(|e| format!("Cannot read '{}': {}", file_path, e))?;
+    let records = parse_csv(&text)?;
+    let merge_duplicates = merge_duplicates.unwrap_or(false);
+    let dry_run = dry_run.unwrap_or(false);
+
+    run_operation(&app, "import_clients", user.id, background.unwrap_or(false), move |ctx| {
+        let db = ctx.app().state::<Database>();
+        let conn = db.conn.lock();
+        let result = import_client_rows(&conn, ctx, records, merge_duplicates, dry_run)?;
+        if !dry_run && (result.created > 0 || result.merged > 0) {
+            record_audit(
+                &conn,
+                &user,
+                "import",
+                "clients",
+                None,
+                None,
+                Some(
+                    &serde_json::json!({ "file": file_path, "created": result.created, "merged": result.merged })
+                        .to_string(),
+                ),
+            );
+        }
+        Ok(result)
+    })
+}
@@ -1,37 +1,225 @@
-use rusqlite::params;
+use rusqlite::{params, Connection};
 use tauri::State;
 
+use crate::commands::client_contacts::load_client_contacts;
+use crate::commands::reports::compute_delivery_performance;
 use crate::db::Database;
-use crate::models::{Client, CreateClientInput, UpdateClientInput};
-use crate::utils::{require_admin, require_view_permission, validate_session};
+use crate::error::AppError;
+use crate::models::{
+    Client, ClientActivityItem, ClientWithContacts, ClientWithStats, CreateClientInput, UpdateClientInput,
+};
+use crate::utils::{
+    audit_json, count_rows, record_audit, record_audit_update, require_permission, require_view_permission,
+    validate_session, PageRequest, Paginated,
+};
 
-/// Get all clients
+/// Fail if the client is archived, as no new projects may be assigned to it
+pub(crate) fn ensure_client_not_archived(conn: &Connection, client_id: i64) -> Result<(), String> {
+    let (name, archived_at): (String, Option<String>) = conn
+        .query_row(
+            "SELECT name, archived_at FROM clients WHERE id = ?1",
+            [client_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| "Client not found".to_string())?;
+    if archived_at.is_some() {
+        return Err(format!("Client '{}' is archived", name));
+    }
+    Ok(())
+}
+
+/// Get all clients; archived clients only with `include_archived`
 #[tauri::command]
-pub fn get_clients(token: String, db: State<'_, Database>) -> Result<Vec<Client>, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+pub fn get_clients(
+    token: String,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    include_archived: Option<bool>,
+    db: State<'_, Database>,
+) -> Result<Paginated<Client>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
+
+    let include_archived = include_archived.unwrap_or(false);
+    let page = PageRequest::resolve(&conn, limit, offset, None)?;
+    let total = count_rows(
+        &conn,
+        "SELECT COUNT(*) FROM clients WHERE (?1 OR archived_at IS NULL)",
+        &[&include_archived],
+    )?;
 
     let mut stmt = conn
-        .prepare("SELECT * FROM clients ORDER BY name ASC")
+        .prepare(&format!(
+            "SELECT * FROM clients WHERE (?1 OR archived_at IS NULL) ORDER BY name ASC{}",
+            page.sql()
+        ))
         .map_err(|e| e.to_string())?;
 
     let clients = stmt
-        .query_map([], Client::from_row)
+        .query_map(params![include_archived, page.limit, page.offset], Client::from_row)
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
         .collect();
 
-    Ok(clients)
+    Ok(Paginated::new(clients, page, total))
 }
 
-/// Get single client by ID
+/// Date range wide enough to take in every project's end date
+const ALL_DATES: (&str, &str) = ("0001-01-01", "9999-12-31");
+
+/// Get all clients with project counts, hours and on-time delivery. Archived
+/// clients are left out unless `include_archived` is set.
 #[tauri::command]
-pub fn get_client(token: String, id: i64, db: State<'_, Database>) -> Result<Client, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+pub fn get_clients_with_stats(
+    token: String,
+    include_archived: Option<bool>,
+    db: State<'_, Database>,
+) -> Result<Vec<ClientWithStats>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    Ok(clients_with_stats(&conn, include_archived.unwrap_or(false))?)
+}
+
+/// Delivery figures come from the delivery performance report over all dates,
+/// so the two always agree
+fn clients_with_stats(conn: &Connection, include_archived: bool) -> Result<Vec<ClientWithStats>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT c.*,
+                    COUNT(p.id) as project_count,
+                    COALESCE(SUM(p.status IN ('planning', 'active')), 0) as active_projects,
+                    COALESCE(SUM(p.planned_hours), 0) as planned_hours,
+                    COALESCE(SUM(p.actual_hours), 0) as actual_hours,
+                    MAX(p.end_date) as last_project_end_date
+             FROM clients c
+             LEFT JOIN projects p ON p.client_id = c.id
+             WHERE (?1 OR c.archived_at IS NULL)
+             GROUP BY c.id
+             ORDER BY c.name ASC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows: Vec<(Client, i64, i64, f64, f64, Option<String>)> = stmt
+        .query_map([include_archived], |row| {
+            Ok((
+                Client::from_row(row)?,
+                row.get("project_count")?,
+                row.get("active_projects")?,
+                row.get("planned_hours")?,
+                row.get("actual_hours")?,
+                row.get("last_project_end_date")?,
+            ))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    rows.into_iter()
+        .map(|(client, project_count, active_projects, planned_hours, actual_hours, last_project_end_date)| {
+            let delivery = compute_delivery_performance(conn, ALL_DATES.0, ALL_DATES.1, Some(client.id))?.overall;
+            Ok(ClientWithStats {
+                client,
+                project_count,
+                active_projects,
+                planned_hours,
+                actual_hours,
+                last_project_end_date,
+                evaluated_projects: delivery.evaluated_projects,
+                late_projects: delivery.late_projects,
+                on_time_percentage: (delivery.evaluated_projects > 0).then_some(delivery.on_time_percentage),
+            })
+        })
+        .collect()
+}
+
+/// Everything that happens on a client's projects, as one feed. Each branch
+/// binds the client id as ?1.
+const CLIENT_ACTIVITY_FEED: &str = "
+    SELECT 'project_created' as item_type, p.created_at as occurred_at, p.id as item_id,
+           p.id as project_id, p.name as project_name, NULL as machine_id, NULL as machine_name,
+           p.status as status, 'Project created' as title, p.description as detail
+    FROM projects p WHERE p.client_id = ?1
+    UNION ALL
+    SELECT 'project_status', a.timestamp, a.id, p.id, p.name, NULL, NULL,
+           json_extract(a.new_values, '$.status'),
+           'Status changed to ' || json_extract(a.new_values, '$.status'),
+           'Was ' || json_extract(a.old_values, '$.status')
+    FROM audit_log a
+    JOIN projects p ON a.table_name = 'projects' AND a.record_id = p.id
+    WHERE a.action = 'status_change' AND p.client_id = ?1
+    UNION ALL
+    SELECT 'schedule', s.date || ' ' || COALESCE(s.start_time, '00:00'), s.id, p.id, p.name, m.id, m.name,
+           s.status, COALESCE(s.load_name, p.name), s.notes
+    FROM schedules s
+    JOIN projects p ON s.project_id = p.id
+    JOIN machines m ON s.machine_id = m.id
+    WHERE p.client_id = ?1
+    UNION ALL
+    SELECT 'alert', al.created_at, al.id, p.id, p.name, m.id, m.name, al.priority, al.title, al.message
+    FROM alerts al
+    JOIN projects p ON al.project_id = p.id
+    LEFT JOIN machines m ON al.machine_id = m.id
+    WHERE p.client_id = ?1";
+
+/// Get a page of a client's activity, newest first: project creation and
+/// status changes, schedule entries on its projects (upcoming ones first) and
+/// alerts tied to its projects
+#[tauri::command]
+pub fn get_client_activity(
+    token: String,
+    client_id: i64,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    db: State<'_, Database>,
+) -> Result<Paginated<ClientActivityItem>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    conn.query_row("SELECT id FROM clients WHERE id = ?1", [client_id], |row| row.get::<_, i64>(0))
+        .map_err(|_| "Client not found".to_string())?;
+
+    let page = PageRequest::resolve(&conn, limit, offset, Some(50))?;
+    let total = count_rows(
+        &conn,
+        &format!("SELECT COUNT(*) FROM ({})", CLIENT_ACTIVITY_FEED),
+        &[&client_id],
+    )?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT * FROM ({}) ORDER BY occurred_at DESC, item_type, item_id DESC LIMIT ?2 OFFSET ?3",
+            CLIENT_ACTIVITY_FEED
+        ))
+        .map_err(|e| e.to_string())?;
+    let items = stmt
+        .query_map(params![client_id, page.limit, page.offset], ClientActivityItem::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(Paginated::new(items, page, total))
+}
+
+/// Get single client by ID, with its contacts
+#[tauri::command]
+pub fn get_client(token: String, id: i64, db: State<'_, Database>) -> Result<ClientWithContacts, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
+
+    let client = conn
+        .query_row("SELECT * FROM clients WHERE id = ?1", [id], Client::from_row)
+        .map_err(|_| "Client not found".to_string())?;
+    let contacts = load_client_contacts(&conn, id)?;
+
+    Ok(ClientWithContacts { client, contacts })
+}
 
+fn fetch_client(conn: &Connection, id: i64) -> Result<Client, String> {
     conn.query_row("SELECT * FROM clients WHERE id = ?1", [id], Client::from_row)
         .map_err(|_| "Client not found".to_string())
 }
@@ -42,10 +230,10 @@ pub fn create_client(
     token: String,
     input: CreateClientInput,
     db: State<'_, Database>,
-) -> Result<Client, String> {
+) -> Result<Client, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_admin(&user)?;
+    require_permission(&conn, &user, "clients.manage")?;
 
     conn.execute(
         "INSERT INTO clients (name, contact_email, contact_phone, address, notes) VALUES (?1, ?2, ?3, ?4, ?5)",
@@ -54,12 +242,9 @@ pub fn create_client(
     .map_err(|e| format!("Failed to create client: {}", e))?;
 
     let new_id = conn.last_insert_rowid();
-    conn.query_row(
-        "SELECT * FROM clients WHERE id = ?1",
-        [new_id],
-        Client::from_row,
-    )
-    .map_err(|e| e.to_string())
+    let client = fetch_client(&conn, new_id)?;
+    record_audit(&conn, &user, "create", "clients", Some(new_id), None, audit_json(&client).as_deref());
+    Ok(client)
 }
 
 /// Update client (Admin only)
@@ -69,11 +254,12 @@ pub fn update_client(
     id: i64,
     input: UpdateClientInput,
     db: State<'_, Database>,
-) -> Result<Client, String> {
+) -> Result<Client, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_admin(&user)?;
+    require_permission(&conn, &user, "clients.manage")?;
 
+    let existing = fetch_client(&conn, id)?;
     let mut updates = Vec::new();
     let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
@@ -99,7 +285,7 @@ pub fn update_client(
     }
 
     if updates.is_empty() {
-        return Err("No fields to update".to_string());
+        return Err(AppError::validation("no fields to update"));
     }
 
     updates.push("updated_at = CURRENT_TIMESTAMP");
@@ -110,19 +296,104 @@ pub fn update_client(
     conn.execute(&query, params.as_slice())
         .map_err(|e| format!("Failed to update client: {}", e))?;
 
-    conn.query_row("SELECT * FROM clients WHERE id = ?1", [id], Client::from_row)
-        .map_err(|e| e.to_string())
+    let client = fetch_client(&conn, id)?;
+    record_audit_update(&conn, &user, "update", "clients", id, &existing, &client);
+    Ok(client)
 }
 
 /// Delete client (Admin only)
 #[tauri::command]
-pub fn delete_client(token: String, id: i64, db: State<'_, Database>) -> Result<(), String> {
+pub fn delete_client(token: String, id: i64, db: State<'_, Database>) -> Result<(), AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_admin(&user)?;
+    require_permission(&conn, &user, "clients.delete")?;
 
+    let existing = fetch_client(&conn, id)?;
     conn.execute("DELETE FROM clients WHERE id = ?1", [id])
         .map_err(|e| format!("Failed to delete client: {}", e))?;
+    record_audit(&conn, &user, "delete", "clients", Some(id), audit_json(&existing).as_deref(), None);
 
     Ok(())
 }
+
+/// Archive a client (Admin only)
+///
+/// The client drops out of client lists and can take no new projects, but its
+/// existing projects stay linked to it. Prefer this to deleting a client with
+/// projects.
+#[tauri::command]
+pub fn archive_client(token: String, id: i64, db: State<'_, Database>) -> Result<Client, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "clients.manage")?;
+
+    ensure_client_not_archived(&conn, id)?;
+    conn.execute(
+        "UPDATE clients SET archived_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+        [id],
+    )
+    .map_err(|e| format!("Failed to archive client: {}", e))?;
+    record_audit(&conn, &user, "archive_client", "clients", Some(id), None, None);
+
+    conn.query_row("SELECT * FROM clients WHERE id = ?1", [id], Client::from_row)
+        .map_err(AppError::from)
+}
+
+/// Return an archived client to the client lists (Admin only)
+#[tauri::command]
+pub fn unarchive_client(token: String, id: i64, db: State<'_, Database>) -> Result<Client, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "clients.manage")?;
+
+    let updated = conn
+        .execute(
+            "UPDATE clients SET archived_at = NULL, updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?1 AND archived_at IS NOT NULL",
+            [id],
+        )
+        .map_err(|e| format!("Failed to unarchive client: {}", e))?;
+    if updated == 0 {
+        return Err(AppError::NotFound("Client not found or not archived".to_string()));
+    }
+    record_audit(&conn, &user, "unarchive_client", "clients", Some(id), None, None);
+
+    conn.query_row("SELECT * FROM clients WHERE id = ?1", [id], Client::from_row)
+        .map_err(AppError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_pending_migrations;
+
+    #[test]
+    fn delivery_figures_match_the_report() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_pending_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO clients (id, name) VALUES (1, 'Acme'), (2, 'Globex'), (3, 'Initech');
+             INSERT INTO projects (name, client_id, status, end_date, actual_completion_date) VALUES
+                 ('on time', 1, 'completed', '2026-01-31', '2026-01-30 17:00:00'),
+                 ('finished late', 1, 'completed', '2026-02-28', '2026-03-04 09:00:00'),
+                 ('overdue', 1, 'active', '2020-06-30', NULL),
+                 ('not due', 1, 'active', '2999-12-31', NULL),
+                 ('no end date', 1, 'planning', NULL, NULL),
+                 ('completed undated', 2, 'completed', '2026-01-15', NULL);",
+        )
+        .unwrap();
+
+        let clients = clients_with_stats(&conn, false).unwrap();
+        for stats in &clients {
+            let report = compute_delivery_performance(&conn, ALL_DATES.0, ALL_DATES.1, Some(stats.client.id)).unwrap();
+            assert_eq!(stats.evaluated_projects, report.overall.evaluated_projects, "{}", stats.client.name);
+            assert_eq!(stats.late_projects, report.overall.late_projects, "{}", stats.client.name);
+        }
+
+        let acme = &clients[0];
+        assert_eq!((acme.project_count, acme.evaluated_projects, acme.late_projects), (5, 3, 2));
+        assert!((acme.on_time_percentage.unwrap() - 100.0 / 3.0).abs() < 1e-9);
+        assert_eq!((clients[1].evaluated_projects, clients[1].on_time_percentage), (0, None));
+        assert_eq!((clients[2].project_count, clients[2].on_time_percentage), (0, None));
+    }
+}
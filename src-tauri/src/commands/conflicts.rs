@@ -0,0 +1,627 @@
+use std::collections::BTreeMap;
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::commands::holidays::holidays_between;
+use crate::commands::materials::{describe_late_materials, late_materials_for};
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{validate_schedule_source, LateMaterial};
+use crate::utils::{
+    get_float, get_string, parse_date, require_view_permission, start_of_week, validate_session,
+    DEFAULT_MACHINE_DAILY_CAPACITY, DEFAULT_OPERATOR_DAILY_LIMIT, DEFAULT_WORKING_HOURS_END,
+    DEFAULT_WORKING_HOURS_START, MACHINE_DAILY_CAPACITY_KEY, OPERATOR_DAILY_LIMIT_KEY,
+    WORKING_HOURS_END_KEY, WORKING_HOURS_START_KEY,
+};
+
+pub const SEVERITY_ERROR: &str = "error";
+pub const SEVERITY_WARNING: &str = "warning";
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// A single rule violation found in the schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleConflict {
+    pub category: String,
+    pub severity: String,
+    pub date: String,
+    pub machine_id: Option<i64>,
+    pub machine_name: Option<String>,
+    pub operator_id: Option<i64>,
+    pub operator_name: Option<String>,
+    pub entry_ids: Vec<i64>,
+    pub message: String,
+}
+
+/// All conflicts for a week, grouped by category
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WeekConflictsResponse {
+    pub week_start: String,
+    pub week_end: String,
+    pub machine_double_bookings: Vec<ScheduleConflict>,
+    pub operator_double_bookings: Vec<ScheduleConflict>,
+    pub over_capacity: Vec<ScheduleConflict>,
+    pub unavailable_machines: Vec<ScheduleConflict>,
+    pub outside_working_hours: Vec<ScheduleConflict>,
+    pub holidays: Vec<ScheduleConflict>,
+    pub operator_over_limit: Vec<ScheduleConflict>,
+    pub material_risks: Vec<ScheduleConflict>,
+    pub stale_references: Vec<ScheduleConflict>,
+    pub error_count: i64,
+    pub warning_count: i64,
+}
+
+impl WeekConflictsResponse {
+    fn categories(&self) -> [&Vec<ScheduleConflict>; 9] {
+        [
+            &self.machine_double_bookings,
+            &self.operator_double_bookings,
+            &self.over_capacity,
+            &self.unavailable_machines,
+            &self.outside_working_hours,
+            &self.holidays,
+            &self.operator_over_limit,
+            &self.material_risks,
+            &self.stale_references,
+        ]
+    }
+
+    fn update_counts(&mut self) {
+        let count = |severity: &str| {
+            self.categories()
+                .into_iter()
+                .flatten()
+                .filter(|c| c.severity == severity)
+                .count() as i64
+        };
+        let (errors, warnings) = (count(SEVERITY_ERROR), count(SEVERITY_WARNING));
+        self.error_count = errors;
+        self.warning_count = warnings;
+    }
+}
+
+/// Schedule entry fields needed by the conflict rules
+#[derive(Debug, Clone)]
+pub struct ConflictEntry {
+    pub id: i64,
+    pub machine_id: i64,
+    pub machine_name: String,
+    pub machine_status: String,
+    pub project_id: Option<i64>,
+    pub project_name: Option<String>,
+    pub project_status: Option<String>,
+    pub operator_id: Option<i64>,
+    pub operator_name: Option<String>,
+    /// False when the assigned operator's account is deactivated
+    pub operator_active: bool,
+    pub date: String,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub planned_hours: f64,
+    pub setup_hours: f64,
+}
+
+impl ConflictEntry {
+    fn label(&self) -> String {
+        match (&self.start_time, &self.end_time) {
+            (Some(s), Some(e)) => format!("#{} ({}-{})", self.id, s, e),
+            _ => format!("#{}", self.id),
+        }
+    }
+
+    fn conflict(&self, category: &str, severity: &str, entry_ids: Vec<i64>, message: String) -> ScheduleConflict {
+        ScheduleConflict {
+            category: category.to_string(),
+            severity: severity.to_string(),
+            date: self.date.clone(),
+            machine_id: Some(self.machine_id),
+            machine_name: Some(self.machine_name.clone()),
+            operator_id: self.operator_id,
+            operator_name: self.operator_name.clone(),
+            entry_ids,
+            message,
+        }
+    }
+}
+
+/// Convert "HH:MM" into minutes since midnight ("24:00" is allowed as end of day)
+pub fn time_to_minutes(time: &str) -> Option<u32> {
+    let (h, m) = time.trim().split_once(':')?;
+    let h: u32 = h.parse().ok()?;
+    let m: u32 = m.get(..2).unwrap_or(m).parse().ok()?;
+    if m >= 60 || h > 24 || (h == 24 && m > 0) {
+        return None;
+    }
+    Some(h * 60 + m)
+}
+
+/// Format minutes since midnight as "HH:MM", wrapping past midnight
+pub fn minutes_to_time(minutes: u32) -> String {
+    let minutes = minutes % MINUTES_PER_DAY;
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// Time window of an entry in minutes; an end before the start runs past midnight
+pub fn entry_window(start: Option<&str>, end: Option<&str>) -> Option<(u32, u32)> {
+    let start = time_to_minutes(start?)?;
+    let mut end = time_to_minutes(end?)?;
+    if end <= start {
+        end += MINUTES_PER_DAY;
+    }
+    Some((start, end))
+}
+
+/// Whether two time windows overlap (touching edges do not count)
+pub fn windows_overlap(a: (u32, u32), b: (u32, u32)) -> bool {
+    a.0 < b.1 && b.0 < a.1
+}
+
+/// Whether two entries overlap. Entries on neighbouring days are compared too,
+/// since a window running past midnight reaches into the next day.
+fn entries_overlap(a: &ConflictEntry, b: &ConflictEntry) -> bool {
+    let (Some(wa), Some(wb)) = (
+        entry_window(a.start_time.as_deref(), a.end_time.as_deref()),
+        entry_window(b.start_time.as_deref(), b.end_time.as_deref()),
+    ) else {
+        return false;
+    };
+    let days = match (
+        chrono::NaiveDate::parse_from_str(&a.date, "%Y-%m-%d"),
+        chrono::NaiveDate::parse_from_str(&b.date, "%Y-%m-%d"),
+    ) {
+        (Ok(da), Ok(db)) => (db - da).num_days(),
+        _ => return false,
+    };
+    match days {
+        0 => windows_overlap(wa, wb),
+        // Put the later entry's window on the earlier day's clock
+        1 => windows_overlap(wa, (wb.0 + MINUTES_PER_DAY, wb.1 + MINUTES_PER_DAY)),
+        -1 => windows_overlap((wa.0 + MINUTES_PER_DAY, wa.1 + MINUTES_PER_DAY), wb),
+        _ => false,
+    }
+}
+
+/// Two entries booked on the same machine at overlapping times
+pub fn check_machine_double_booking(a: &ConflictEntry, b: &ConflictEntry) -> Option<ScheduleConflict> {
+    if a.id == b.id || a.machine_id != b.machine_id || !entries_overlap(a, b) {
+        return None;
+    }
+    Some(a.conflict(
+        "machine_double_booking",
+        SEVERITY_ERROR,
+        vec![a.id, b.id],
+        format!(
+            "{} is double-booked on {}: entries {} and {} overlap",
+            a.machine_name,
+            a.date,
+            a.label(),
+            b.label()
+        ),
+    ))
+}
+
+/// The same operator assigned to overlapping entries
+pub fn check_operator_double_booking(a: &ConflictEntry, b: &ConflictEntry) -> Option<ScheduleConflict> {
+    let operator_id = a.operator_id?;
+    if a.id == b.id || b.operator_id != Some(operator_id) || !entries_overlap(a, b) {
+        return None;
+    }
+    let name = a.operator_name.clone().unwrap_or_else(|| format!("Operator #{}", operator_id));
+    let mut conflict = a.conflict(
+        "operator_double_booking",
+        SEVERITY_ERROR,
+        vec![a.id, b.id],
+        format!(
+            "{} is double-booked on {}: {} on {} overlaps {} on {}",
+            name,
+            a.date,
+            a.label(),
+            a.machine_name,
+            b.label(),
+            b.machine_name
+        ),
+    );
+    if a.machine_id != b.machine_id {
+        conflict.machine_id = None;
+        conflict.machine_name = None;
+    }
+    Some(conflict)
+}
+
+/// An entry on a machine that is currently in maintenance or error
+pub fn check_machine_availability(entry: &ConflictEntry) -> Option<ScheduleConflict> {
+    if !["maintenance", "error"].contains(&entry.machine_status.as_str()) {
+        return None;
+    }
+    Some(entry.conflict(
+        "unavailable_machine",
+        SEVERITY_ERROR,
+        vec![entry.id],
+        format!(
+            "Entry {} is on {} which is in '{}' status",
+            entry.label(),
+            entry.machine_name,
+            entry.machine_status
+        ),
+    ))
+}
+
+/// Planned hours on one machine-day exceeding the daily capacity
+pub fn check_machine_capacity(day_entries: &[&ConflictEntry], capacity: f64) -> Option<ScheduleConflict> {
+    let first = day_entries.first()?;
+    let total: f64 = day_entries.iter().map(|e| e.planned_hours + e.setup_hours).sum();
+    if total <= capacity {
+        return None;
+    }
+    let mut conflict = first.conflict(
+        "over_capacity",
+        SEVERITY_WARNING,
+        day_entries.iter().map(|e| e.id).collect(),
+        format!(
+            "{} has {:.2}h planned on {} (capacity {:.2}h)",
+            first.machine_name, total, first.date, capacity
+        ),
+    );
+    conflict.operator_id = None;
+    conflict.operator_name = None;
+    Some(conflict)
+}
+
+/// Whether a window lies inside the working hours. A window running past
+/// midnight must fit both the evening up to midnight and the morning after.
+pub fn within_working_hours(window: (u32, u32), working_hours: (u32, u32)) -> bool {
+    if window.1 <= MINUTES_PER_DAY {
+        return window.0 >= working_hours.0 && window.1 <= working_hours.1;
+    }
+    window.0 >= working_hours.0
+        && working_hours.1 >= MINUTES_PER_DAY
+        && working_hours.0 == 0
+        && window.1 - MINUTES_PER_DAY <= working_hours.1
+}
+
+/// An entry starting or ending outside the configured working hours
+pub fn check_working_hours(entry: &ConflictEntry, working_hours: (u32, u32)) -> Option<ScheduleConflict> {
+    let window = entry_window(entry.start_time.as_deref(), entry.end_time.as_deref())?;
+    if within_working_hours(window, working_hours) {
+        return None;
+    }
+    Some(entry.conflict(
+        "outside_working_hours",
+        SEVERITY_WARNING,
+        vec![entry.id],
+        format!(
+            "Entry {} on {} falls outside working hours",
+            entry.label(),
+            entry.machine_name
+        ),
+    ))
+}
+
+/// An entry dated on a shop holiday
+pub fn check_holiday(entry: &ConflictEntry, holiday: Option<&str>) -> Option<ScheduleConflict> {
+    let holiday = holiday?;
+    Some(entry.conflict(
+        "holiday",
+        SEVERITY_WARNING,
+        vec![entry.id],
+        format!("Entry {} on {} falls on {} ({})", entry.label(), entry.machine_name, holiday, entry.date),
+    ))
+}
+
+/// Planned hours for one operator-day exceeding the daily limit
+pub fn check_operator_limit(day_entries: &[&ConflictEntry], limit: f64) -> Option<ScheduleConflict> {
+    let first = day_entries.first()?;
+    let operator_id = first.operator_id?;
+    let total: f64 = day_entries.iter().map(|e| e.planned_hours + e.setup_hours).sum();
+    if total <= limit {
+        return None;
+    }
+    let name = first.operator_name.clone().unwrap_or_else(|| format!("Operator #{}", operator_id));
+    let mut conflict = first.conflict(
+        "operator_over_limit",
+        SEVERITY_WARNING,
+        day_entries.iter().map(|e| e.id).collect(),
+        format!(
+            "{} has {:.2}h planned on {} (limit {:.2}h)",
+            name, total, first.date, limit
+        ),
+    );
+    conflict.machine_id = None;
+    conflict.machine_name = None;
+    Some(conflict)
+}
+
+/// Entry still pointing at a deactivated operator or a completed project
+pub fn check_stale_references(entry: &ConflictEntry) -> Option<ScheduleConflict> {
+    let mut reasons = Vec::new();
+    if entry.operator_id.is_some() && !entry.operator_active {
+        reasons.push(format!(
+            "operator {} is inactive",
+            entry.operator_name.as_deref().unwrap_or("(unknown)")
+        ));
+    }
+    if entry.project_status.as_deref() == Some("completed") {
+        reasons.push(format!(
+            "project {} is completed",
+            entry.project_name.as_deref().unwrap_or("(unknown)")
+        ));
+    }
+    if reasons.is_empty() {
+        return None;
+    }
+    Some(entry.conflict(
+        "stale_reference",
+        SEVERITY_WARNING,
+        vec![entry.id],
+        format!("Entry {} on {}: {}", entry.label(), entry.machine_name, reasons.join(", ")),
+    ))
+}
+
+/// Entry scheduled before its project's material is due in
+pub fn check_material_availability(entry: &ConflictEntry, late: &[LateMaterial]) -> Option<ScheduleConflict> {
+    if late.is_empty() {
+        return None;
+    }
+    Some(entry.conflict(
+        "material_risk",
+        SEVERITY_WARNING,
+        vec![entry.id],
+        format!("{} on {}: {}", entry.label(), entry.machine_name, describe_late_materials(&entry.date, late)),
+    ))
+}
+
+/// Load active (non-cancelled) schedule entries within a date range
+pub fn load_conflict_entries(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    source: Option<&str>,
+) -> Result<Vec<ConflictEntry>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.id, s.machine_id, m.name as machine_name, m.status as machine_status, s.project_id,
+                    p.name as project_name, p.status as project_status,
+                    s.operator_id, u.full_name as operator_name, COALESCE(u.is_active, 1) as operator_active,
+                    s.date, s.start_time, s.end_time, s.planned_hours, s.setup_hours
+             FROM schedules s
+             JOIN machines m ON s.machine_id = m.id
+             LEFT JOIN projects p ON s.project_id = p.id
+             LEFT JOIN users u ON s.operator_id = u.id
+             WHERE s.date >= ?1 AND s.date <= ?2 AND s.status != 'cancelled'
+               AND (?3 IS NULL OR s.source = ?3)
+             ORDER BY s.date, m.name, s.start_time",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let entries = stmt
+        .query_map(params![start_date, end_date, source], |row| {
+            Ok(ConflictEntry {
+                id: row.get("id")?,
+                machine_id: row.get("machine_id")?,
+                machine_name: row.get("machine_name")?,
+                machine_status: row.get("machine_status")?,
+                project_id: row.get("project_id")?,
+                project_name: row.get("project_name")?,
+                project_status: row.get("project_status")?,
+                operator_id: row.get("operator_id")?,
+                operator_name: row.get("operator_name")?,
+                operator_active: row.get("operator_active")?,
+                date: row.get("date")?,
+                start_time: row.get("start_time")?,
+                end_time: row.get("end_time")?,
+                planned_hours: row.get::<_, Option<f64>>("planned_hours")?.unwrap_or(0.0),
+                setup_hours: row.get::<_, Option<f64>>("setup_hours").ok().flatten().unwrap_or(0.0),
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(entries)
+}
+
+/// Configured working hours as a minute window
+pub fn working_hours_window(conn: &Connection) -> (u32, u32) {
+    let start = time_to_minutes(&get_string(conn, WORKING_HOURS_START_KEY, DEFAULT_WORKING_HOURS_START))
+        .unwrap_or(0);
+    let end = time_to_minutes(&get_string(conn, WORKING_HOURS_END_KEY, DEFAULT_WORKING_HOURS_END))
+        .unwrap_or(MINUTES_PER_DAY);
+    (start, end.max(start))
+}
+
+/// Run every conflict rule over the week starting at `week_start`.
+///
+/// `week_start` is taken as given; callers align it with `start_of_week` first.
+///
+/// With `source` set only entries from that creation source are checked, so e.g.
+/// auto-generated entries can be reviewed on their own.
+pub fn sweep_week_conflicts(
+    conn: &Connection,
+    week_start: &str,
+    source: Option<&str>,
+) -> Result<WeekConflictsResponse, String> {
+    let start = chrono::NaiveDate::parse_from_str(week_start, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    let end = start + chrono::Duration::days(6);
+    let week_end = end.format("%Y-%m-%d").to_string();
+
+    // The days either side are loaded only so overnight entries can clash across the week's edges
+    let day_before = (start - chrono::Duration::days(1)).format("%Y-%m-%d").to_string();
+    let day_after = (end + chrono::Duration::days(1)).format("%Y-%m-%d").to_string();
+    let nearby = load_conflict_entries(conn, &day_before, &day_after, source)?;
+    let in_week = |e: &ConflictEntry| e.date.as_str() >= week_start && e.date <= week_end;
+    let entries: Vec<&ConflictEntry> = nearby.iter().filter(|e| in_week(e)).collect();
+    let capacity = get_float(conn, MACHINE_DAILY_CAPACITY_KEY, DEFAULT_MACHINE_DAILY_CAPACITY);
+    let operator_limit = get_float(conn, OPERATOR_DAILY_LIMIT_KEY, DEFAULT_OPERATOR_DAILY_LIMIT);
+    let working_hours = working_hours_window(conn);
+    let holidays = holidays_between(conn, start, end)?;
+
+    let mut response = WeekConflictsResponse {
+        week_start: week_start.to_string(),
+        week_end: week_end.clone(),
+        ..Default::default()
+    };
+
+    let mut machine_days: BTreeMap<(String, i64), Vec<&ConflictEntry>> = BTreeMap::new();
+    let mut operator_days: BTreeMap<(String, i64), Vec<&ConflictEntry>> = BTreeMap::new();
+    for &entry in &entries {
+        machine_days
+            .entry((entry.date.clone(), entry.machine_id))
+            .or_default()
+            .push(entry);
+        if let Some(operator_id) = entry.operator_id {
+            operator_days
+                .entry((entry.date.clone(), operator_id))
+                .or_default()
+                .push(entry);
+        }
+
+        if let Some(c) = check_machine_availability(entry) {
+            response.unavailable_machines.push(c);
+        }
+        if let Some(c) = check_working_hours(entry, working_hours) {
+            response.outside_working_hours.push(c);
+        }
+        let holiday = chrono::NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| holidays.get(&date));
+        if let Some(c) = check_holiday(entry, holiday.map(String::as_str)) {
+            response.holidays.push(c);
+        }
+        if let Some(c) = check_stale_references(entry) {
+            response.stale_references.push(c);
+        }
+        if let Some(project_id) = entry.project_id {
+            let late = late_materials_for(conn, project_id, &entry.date)?;
+            if let Some(c) = check_material_availability(entry, &late) {
+                response.material_risks.push(c);
+            }
+        }
+    }
+
+    // Pairs involving at least one entry of the week, in date order
+    for (i, a) in nearby.iter().enumerate() {
+        for b in &nearby[i + 1..] {
+            if !in_week(a) && !in_week(b) {
+                continue;
+            }
+            if let Some(c) = check_machine_double_booking(a, b) {
+                response.machine_double_bookings.push(c);
+            }
+            if let Some(c) = check_operator_double_booking(a, b) {
+                response.operator_double_bookings.push(c);
+            }
+        }
+    }
+
+    for day in machine_days.values() {
+        if let Some(c) = check_machine_capacity(day, capacity) {
+            response.over_capacity.push(c);
+        }
+    }
+
+    for day in operator_days.values() {
+        if let Some(c) = check_operator_limit(day, operator_limit) {
+            response.operator_over_limit.push(c);
+        }
+    }
+
+    response.update_counts();
+    Ok(response)
+}
+
+/// Get every scheduling conflict for a week, grouped by category
+#[tauri::command]
+pub fn get_week_conflicts(
+    token: String,
+    week_start: String,
+    source: Option<String>,
+    db: State<'_, Database>,
+) -> Result<WeekConflictsResponse, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let week_start = start_of_week(&conn, parse_date("week_start", &week_start)?).format("%Y-%m-%d").to_string();
+    if let Some(source) = &source {
+        validate_schedule_source(source)?;
+    }
+
+    Ok(sweep_week_conflicts(&conn, &week_start, source.as_deref())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(start: &str, end: &str) -> ConflictEntry {
+        ConflictEntry {
+            id: 1,
+            machine_id: 1,
+            machine_name: "VMC-1".to_string(),
+            machine_status: "active".to_string(),
+            project_id: None,
+            project_name: None,
+            project_status: None,
+            operator_id: None,
+            operator_name: None,
+            operator_active: true,
+            date: "2026-01-05".to_string(),
+            start_time: Some(start.to_string()),
+            end_time: Some(end.to_string()),
+            planned_hours: 8.0,
+            setup_hours: 0.0,
+        }
+    }
+
+    #[test]
+    fn night_shift_fits_round_the_clock_working_hours() {
+        assert!(check_working_hours(&entry("20:00", "08:00"), (0, MINUTES_PER_DAY)).is_none());
+        assert!(check_working_hours(&entry("22:00", "00:00"), (0, MINUTES_PER_DAY)).is_none());
+    }
+
+    #[test]
+    fn night_shift_outside_day_working_hours() {
+        let day = (6 * 60, 22 * 60);
+        assert!(check_working_hours(&entry("20:00", "08:00"), day).is_some());
+        assert!(check_working_hours(&entry("08:00", "16:00"), day).is_none());
+        assert!(check_working_hours(&entry("05:00", "13:00"), day).is_some());
+        assert!(check_working_hours(&entry("16:00", "23:00"), day).is_some());
+    }
+
+    fn on(date: &str, id: i64, start: &str, end: &str) -> ConflictEntry {
+        ConflictEntry { id, date: date.to_string(), ..entry(start, end) }
+    }
+
+    #[test]
+    fn night_shift_clashes_with_next_morning() {
+        let night = on("2026-01-05", 1, "22:00", "06:00");
+        let early = on("2026-01-06", 2, "02:00", "04:00");
+        assert!(check_machine_double_booking(&night, &early).is_some());
+        assert!(check_machine_double_booking(&early, &night).is_some());
+
+        let after = on("2026-01-06", 3, "06:00", "14:00");
+        assert!(check_machine_double_booking(&night, &after).is_none());
+        let day_before = on("2026-01-04", 4, "02:00", "04:00");
+        assert!(check_machine_double_booking(&night, &day_before).is_none());
+        let two_days_on = on("2026-01-07", 5, "02:00", "04:00");
+        assert!(check_machine_double_booking(&night, &two_days_on).is_none());
+    }
+
+    #[test]
+    fn operator_night_shift_clashes_across_machines() {
+        let night = ConflictEntry { operator_id: Some(9), ..on("2026-01-05", 1, "22:00", "06:00") };
+        let early = ConflictEntry { operator_id: Some(9), machine_id: 2, ..on("2026-01-06", 2, "05:00", "07:00") };
+        let conflict = check_operator_double_booking(&night, &early).unwrap();
+        assert_eq!(conflict.machine_id, None);
+    }
+
+    #[test]
+    fn holiday_is_a_warning() {
+        let conflict = check_holiday(&entry("08:00", "16:00"), Some("New Year")).unwrap();
+        assert_eq!(conflict.category, "holiday");
+        assert_eq!(conflict.severity, SEVERITY_WARNING);
+        assert!(check_holiday(&entry("08:00", "16:00"), None).is_none());
+    }
+}
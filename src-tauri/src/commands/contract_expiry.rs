@@ -0,0 +1,129 @@
+use chrono::NaiveDate;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::commands::alerts::UNREAD_BY_ANYONE;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::utils::{require_view_permission, validate_session};
+
+/// Days before an expiry date that a warning alert is raised
+const EXPIRY_ALERT_DAYS: i64 = 30;
+
+pub const CONTRACT_WARRANTY: &str = "warranty";
+pub const CONTRACT_AMC: &str = "amc";
+
+/// A machine warranty or AMC contract ending soon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpiringContract {
+    pub machine_id: i64,
+    pub machine_name: String,
+    /// "warranty" or "amc"
+    pub contract_type: String,
+    pub expiry_date: String,
+    pub days_remaining: i64,
+}
+
+/// Warranties and AMC contracts of active machines ending between today and
+/// `days_ahead` days from now, soonest first
+pub fn find_expiring_contracts(conn: &Connection, days_ahead: i64) -> Result<Vec<ExpiringContract>, String> {
+    let today = chrono::Local::now().date_naive();
+    let until = today + chrono::Duration::days(days_ahead);
+    let today_str = today.format("%Y-%m-%d").to_string();
+    let until_str = until.format("%Y-%m-%d").to_string();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, ?1 as contract_type, warranty_expiry as expiry_date FROM machines
+             WHERE archived_at IS NULL AND warranty_expiry >= ?3 AND warranty_expiry <= ?4
+             UNION ALL
+             SELECT id, name, ?2, amc_expiry FROM machines
+             WHERE archived_at IS NULL AND amc_expiry >= ?3 AND amc_expiry <= ?4
+             ORDER BY expiry_date, name",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let contracts = stmt
+        .query_map(params![CONTRACT_WARRANTY, CONTRACT_AMC, today_str, until_str], |row| {
+            let expiry_date: String = row.get(3)?;
+            let days_remaining = NaiveDate::parse_from_str(&expiry_date, "%Y-%m-%d")
+                .map(|d| (d - today).num_days())
+                .unwrap_or(0);
+            Ok(ExpiringContract {
+                machine_id: row.get(0)?,
+                machine_name: row.get(1)?,
+                contract_type: row.get(2)?,
+                expiry_date,
+                days_remaining,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(contracts)
+}
+
+fn alert_title(contract_type: &str) -> &'static str {
+    if contract_type == CONTRACT_AMC {
+        "AMC contract expiring"
+    } else {
+        "Warranty expiring"
+    }
+}
+
+/// Raise a warning alert for each contract ending within the alert window,
+/// unless the machine already has an unread one for it
+pub fn raise_contract_expiry_alerts(conn: &Connection) -> Result<Vec<ExpiringContract>, String> {
+    let contracts = find_expiring_contracts(conn, EXPIRY_ALERT_DAYS)?;
+    for contract in &contracts {
+        let title = alert_title(&contract.contract_type);
+        let pending: i64 = conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM alerts WHERE title = ?1 AND machine_id = ?2 AND {}", UNREAD_BY_ANYONE),
+                params![title, contract.machine_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if pending > 0 {
+            continue;
+        }
+
+        let label = if contract.contract_type == CONTRACT_AMC { "AMC contract" } else { "warranty" };
+        conn.execute(
+            "INSERT INTO alerts (alert_type, priority, title, message, machine_id)
+             VALUES ('warning', 'medium', ?1, ?2, ?3)",
+            params![
+                title,
+                format!(
+                    "The {} for {} expires on {} ({} days left)",
+                    label, contract.machine_name, contract.expiry_date, contract.days_remaining
+                ),
+                contract.machine_id
+            ],
+        )
+        .map_err(|e| format!("Failed to create alert: {}", e))?;
+    }
+    Ok(contracts)
+}
+
+/// Warranties and AMC contracts ending within `days_ahead` days (default 30).
+/// Contracts inside the alert window also get a warning alert.
+#[tauri::command]
+pub fn get_expiring_contracts(
+    token: String,
+    days_ahead: Option<i64>,
+    db: State<'_, Database>,
+) -> Result<Vec<ExpiringContract>, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_view_permission(&user)?;
+
+    let days_ahead = days_ahead.unwrap_or(EXPIRY_ALERT_DAYS);
+    if days_ahead < 0 {
+        return Err(AppError::validation("days_ahead must not be negative"));
+    }
+    raise_contract_expiry_alerts(&conn)?;
+    Ok(find_expiring_contracts(&conn, days_ahead)?)
+}
@@ -1,31 +1,48 @@
 use chrono::Datelike;
-use rusqlite::params;
+use rusqlite::{params, Connection};
 use tauri::State;
 
+use crate::commands::alerts::unread_by;
+use crate::commands::demo_data::demo_data_loaded;
+use crate::commands::sites::{fetch_site, site_today};
 use crate::db::Database;
-use crate::models::DashboardStats;
-use crate::utils::{require_view_permission, validate_session};
+use crate::error::AppError;
+use crate::models::{computed_progress, DashboardStats, PROJECT_PRIORITY_RANK};
+use crate::utils::{require_view_permission, start_of_week, validate_session};
 
 /// Get dashboard statistics
+///
+/// With `site_id` the machine and schedule figures cover only that site's
+/// machines, and "today"/"this week" follow the site's clock. Project, client
+/// and alert figures are shop-wide either way.
 #[tauri::command]
 pub fn get_dashboard_stats(
     token: String,
+    site_id: Option<i64>,
     db: State<'_, Database>,
-) -> Result<DashboardStats, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+) -> Result<DashboardStats, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
+
+    if let Some(site_id) = site_id {
+        fetch_site(&conn, site_id)?;
+    }
 
     // Total machines
     let total_machines: i32 = conn
-        .query_row("SELECT COUNT(*) FROM machines", [], |row| row.get(0))
+        .query_row(
+            "SELECT COUNT(*) FROM machines WHERE archived_at IS NULL AND (?1 IS NULL OR site_id = ?1)",
+            [site_id],
+            |row| row.get(0),
+        )
         .unwrap_or(0);
 
     // Active machines (status = 'active')
     let active_machines: i32 = conn
         .query_row(
-            "SELECT COUNT(*) FROM machines WHERE status = 'active'",
-            [],
+            "SELECT COUNT(*) FROM machines WHERE archived_at IS NULL AND status = 'active' AND (?1 IS NULL OR site_id = ?1)",
+            [site_id],
             |row| row.get(0),
         )
         .unwrap_or(0);
@@ -33,8 +50,8 @@ pub fn get_dashboard_stats(
     // Machines under maintenance
     let maintenance_machines: i32 = conn
         .query_row(
-            "SELECT COUNT(*) FROM machines WHERE status = 'maintenance'",
-            [],
+            "SELECT COUNT(*) FROM machines WHERE archived_at IS NULL AND status = 'maintenance' AND (?1 IS NULL OR site_id = ?1)",
+            [site_id],
             |row| row.get(0),
         )
         .unwrap_or(0);
@@ -42,8 +59,8 @@ pub fn get_dashboard_stats(
     // Idle machines
     let idle_machines: i32 = conn
         .query_row(
-            "SELECT COUNT(*) FROM machines WHERE status = 'idle'",
-            [],
+            "SELECT COUNT(*) FROM machines WHERE archived_at IS NULL AND status = 'idle' AND (?1 IS NULL OR site_id = ?1)",
+            [site_id],
             |row| row.get(0),
         )
         .unwrap_or(0);
@@ -51,8 +68,8 @@ pub fn get_dashboard_stats(
     // Error machines
     let error_machines: i32 = conn
         .query_row(
-            "SELECT COUNT(*) FROM machines WHERE status = 'error'",
-            [],
+            "SELECT COUNT(*) FROM machines WHERE archived_at IS NULL AND status = 'error' AND (?1 IS NULL OR site_id = ?1)",
+            [site_id],
             |row| row.get(0),
         )
         .unwrap_or(0);
@@ -82,32 +99,21 @@ pub fn get_dashboard_stats(
 
     // Total clients
     let total_clients: i32 = conn
-        .query_row("SELECT COUNT(*) FROM clients", [], |row| row.get(0))
+        .query_row("SELECT COUNT(*) FROM clients WHERE archived_at IS NULL", [], |row| row.get(0))
         .unwrap_or(0);
 
     // Hours this week
-    let today = chrono::Utc::now().naive_utc().date();
-    let week_start = today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+    let today = match site_id {
+        Some(_) => site_today(&conn, site_id)?,
+        None => chrono::Utc::now().naive_utc().date(),
+    };
+    let week_start = start_of_week(&conn, today);
     let week_end = week_start + chrono::Duration::days(6);
 
     let week_start_str = week_start.format("%Y-%m-%d").to_string();
     let week_end_str = week_end.format("%Y-%m-%d").to_string();
 
-    let planned_hours_week: f64 = conn
-        .query_row(
-            "SELECT COALESCE(SUM(planned_hours), 0) FROM schedules WHERE date >= ?1 AND date <= ?2",
-            params![week_start_str, week_end_str],
-            |row| row.get(0),
-        )
-        .unwrap_or(0.0);
-
-    let actual_hours_week: f64 = conn
-        .query_row(
-            "SELECT COALESCE(SUM(actual_hours), 0) FROM schedules WHERE date >= ?1 AND date <= ?2",
-            params![week_start_str, week_end_str],
-            |row| row.get(0),
-        )
-        .unwrap_or(0.0);
+    let (planned_hours_week, actual_hours_week) = schedule_hours(&conn, &week_start_str, &week_end_str, site_id);
 
     // Hours this month
     let month_start = today.with_day(1).unwrap_or(today);
@@ -128,16 +134,20 @@ pub fn get_dashboard_stats(
 
     let planned_hours_month: f64 = conn
         .query_row(
-            "SELECT COALESCE(SUM(planned_hours), 0) FROM schedules WHERE date >= ?1 AND date <= ?2",
-            params![month_start_str, month_end_str],
+            "SELECT COALESCE(SUM(planned_hours), 0) FROM schedules
+             WHERE date >= ?1 AND date <= ?2
+               AND (?3 IS NULL OR machine_id IN (SELECT id FROM machines WHERE site_id = ?3))",
+            params![month_start_str, month_end_str, site_id],
             |row| row.get(0),
         )
         .unwrap_or(0.0);
 
     let actual_hours_month: f64 = conn
         .query_row(
-            "SELECT COALESCE(SUM(actual_hours), 0) FROM schedules WHERE date >= ?1 AND date <= ?2",
-            params![month_start_str, month_end_str],
+            "SELECT COALESCE(SUM(actual_hours), 0) FROM schedules
+             WHERE date >= ?1 AND date <= ?2
+               AND (?3 IS NULL OR machine_id IN (SELECT id FROM machines WHERE site_id = ?3))",
+            params![month_start_str, month_end_str, site_id],
             |row| row.get(0),
         )
         .unwrap_or(0.0);
@@ -167,24 +177,22 @@ pub fn get_dashboard_stats(
     };
 
     // Efficiency rate (actual hours / planned hours * 100)
-    let efficiency_rate = if planned_hours_week > 0.0 {
-        (actual_hours_week / planned_hours_week * 100.0).min(100.0)
-    } else {
-        0.0
-    };
+    let efficiency_rate = efficiency_percentage(planned_hours_week, actual_hours_week);
 
     // Upcoming maintenance count
     let upcoming_maintenance: i32 = conn
         .query_row(
-            "SELECT COUNT(*) FROM maintenance WHERE date >= ?1 AND status = 'scheduled'",
-            [&today.format("%Y-%m-%d").to_string()],
+            "SELECT COUNT(*) FROM maintenance
+             WHERE date >= ?1 AND status = 'scheduled'
+               AND (?2 IS NULL OR machine_id IN (SELECT id FROM machines WHERE site_id = ?2))",
+            params![today.format("%Y-%m-%d").to_string(), site_id],
             |row| row.get(0),
         )
         .unwrap_or(0);
 
-    // Unread alerts count
+    // Alerts the current user hasn't read
     let unread_alerts: i32 = conn
-        .query_row("SELECT COUNT(*) FROM alerts WHERE is_read = 0", [], |row| {
+        .query_row(&format!("SELECT COUNT(*) FROM alerts a WHERE {}", unread_by(user.id)), [], |row| {
             row.get(0)
         })
         .unwrap_or(0);
@@ -214,13 +222,14 @@ pub fn get_dashboard_stats(
             "SELECT m.name, COALESCE(SUM(s.actual_hours), 0) as hours
              FROM machines m
              LEFT JOIN schedules s ON m.id = s.machine_id AND s.date >= ?1 AND s.date <= ?2
+             WHERE (?3 IS NULL OR m.site_id = ?3) AND (m.archived_at IS NULL OR s.id IS NOT NULL)
              GROUP BY m.id
              ORDER BY hours DESC
              LIMIT 5",
         )
         .ok()
         .and_then(|mut stmt| {
-            stmt.query_map(params![week_start_str, week_end_str], |row| {
+            stmt.query_map(params![week_start_str, week_end_str, site_id], |row| {
                 Ok((row.get(0)?, row.get(1)?))
             })
             .ok()
@@ -239,16 +248,20 @@ pub fn get_dashboard_stats(
 
         let planned: f64 = conn
             .query_row(
-                "SELECT COALESCE(SUM(planned_hours), 0) FROM schedules WHERE date >= ?1 AND date <= ?2",
-                params![ws_str, we_str],
+                "SELECT COALESCE(SUM(planned_hours), 0) FROM schedules
+                 WHERE date >= ?1 AND date <= ?2
+                   AND (?3 IS NULL OR machine_id IN (SELECT id FROM machines WHERE site_id = ?3))",
+                params![ws_str, we_str, site_id],
                 |row| row.get(0),
             )
             .unwrap_or(0.0);
 
         let actual: f64 = conn
             .query_row(
-                "SELECT COALESCE(SUM(actual_hours), 0) FROM schedules WHERE date >= ?1 AND date <= ?2",
-                params![ws_str, we_str],
+                "SELECT COALESCE(SUM(actual_hours), 0) FROM schedules
+                 WHERE date >= ?1 AND date <= ?2
+                   AND (?3 IS NULL OR machine_id IN (SELECT id FROM machines WHERE site_id = ?3))",
+                params![ws_str, we_str, site_id],
                 |row| row.get(0),
             )
             .unwrap_or(0.0);
@@ -280,20 +293,46 @@ pub fn get_dashboard_stats(
         project_status,
         top_machines_week,
         weekly_trend,
+        demo_data_loaded: demo_data_loaded(&conn),
     })
 }
 
-/// Get machine utilization for a date range
+/// Planned and actual schedule hours between two dates, inclusive, optionally
+/// for one site's machines. Shared with the weekly KPI comparison so both
+/// report the same week the same way.
+pub fn schedule_hours(conn: &Connection, start_date: &str, end_date: &str, site_id: Option<i64>) -> (f64, f64) {
+    conn.query_row(
+        "SELECT COALESCE(SUM(planned_hours), 0), COALESCE(SUM(actual_hours), 0)
+         FROM schedules
+         WHERE date >= ?1 AND date <= ?2
+           AND (?3 IS NULL OR machine_id IN (SELECT id FROM machines WHERE site_id = ?3))",
+        params![start_date, end_date, site_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .unwrap_or((0.0, 0.0))
+}
+
+/// Actual hours as a percentage of planned, capped at 100
+pub fn efficiency_percentage(planned_hours: f64, actual_hours: f64) -> f64 {
+    if planned_hours > 0.0 {
+        (actual_hours / planned_hours * 100.0).min(100.0)
+    } else {
+        0.0
+    }
+}
+
+/// Get machine utilization for a date range, optionally for one site's machines
 #[tauri::command]
 pub fn get_machine_utilization(
     token: String,
     start_date: String,
     end_date: String,
+    site_id: Option<i64>,
     db: State<'_, Database>,
-) -> Result<Vec<MachineUtilization>, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+) -> Result<Vec<MachineUtilization>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
     let mut stmt = conn
         .prepare(
@@ -303,13 +342,14 @@ pub fn get_machine_utilization(
                     COUNT(s.id) as schedule_count
              FROM machines m
              LEFT JOIN schedules s ON m.id = s.machine_id AND s.date >= ?1 AND s.date <= ?2
+             WHERE (?3 IS NULL OR m.site_id = ?3) AND (m.archived_at IS NULL OR s.id IS NOT NULL)
              GROUP BY m.id
              ORDER BY actual DESC",
         )
         .map_err(|e| e.to_string())?;
 
     let utilization: Vec<MachineUtilization> = stmt
-        .query_map(params![start_date, end_date], |row| {
+        .query_map(params![start_date, end_date, site_id], |row| {
             let planned: f64 = row.get(2)?;
             let actual: f64 = row.get(3)?;
             let efficiency = if planned > 0.0 {
@@ -349,19 +389,22 @@ pub struct MachineUtilization {
 pub fn get_project_progress(
     token: String,
     db: State<'_, Database>,
-) -> Result<Vec<ProjectProgress>, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+) -> Result<Vec<ProjectProgress>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
     let mut stmt = conn
         .prepare(
-            "SELECT p.id, p.name, p.status, p.planned_hours, p.actual_hours,
-                    p.start_date, p.end_date, c.name as client_name
-             FROM projects p
-             LEFT JOIN clients c ON p.client_id = c.id
-             WHERE p.status IN ('planning', 'active')
-             ORDER BY p.end_date ASC",
+            &format!(
+                "SELECT p.id, p.name, p.status, p.planned_hours, p.actual_hours,
+                        p.start_date, p.end_date, c.name as client_name, p.progress_override, p.priority
+                 FROM projects p
+                 LEFT JOIN clients c ON p.client_id = c.id
+                 WHERE p.status IN ('planning', 'active')
+                 ORDER BY {}, p.end_date IS NULL, p.end_date ASC",
+                PROJECT_PRIORITY_RANK
+            ),
         )
         .map_err(|e| e.to_string())?;
 
@@ -369,19 +412,19 @@ pub fn get_project_progress(
         .query_map([], |row| {
             let planned: f64 = row.get(3)?;
             let actual: f64 = row.get(4)?;
-            let progress = if planned > 0.0 {
-                (actual / planned * 100.0).min(100.0)
-            } else {
-                0.0
-            };
+            let progress = computed_progress(planned, actual);
+            let progress_override: Option<f64> = row.get(8)?;
 
             Ok(ProjectProgress {
                 project_id: row.get(0)?,
                 project_name: row.get(1)?,
                 status: row.get(2)?,
+                priority: row.get(9)?,
                 planned_hours: planned,
                 actual_hours: actual,
                 progress_percentage: progress,
+                progress_override,
+                effective_progress: progress_override.unwrap_or(progress),
                 start_date: row.get(5)?,
                 end_date: row.get(6)?,
                 client_name: row.get(7)?,
@@ -399,9 +442,14 @@ pub struct ProjectProgress {
     pub project_id: i64,
     pub project_name: String,
     pub status: String,
+    pub priority: String,
     pub planned_hours: f64,
     pub actual_hours: f64,
+    /// Hours-based progress
     pub progress_percentage: f64,
+    pub progress_override: Option<f64>,
+    /// Override when set, otherwise `progress_percentage`
+    pub effective_progress: f64,
     pub start_date: Option<String>,
     pub end_date: Option<String>,
     pub client_name: Option<String>,
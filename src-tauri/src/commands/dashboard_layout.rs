@@ -0,0 +1,263 @@
+use std::collections::HashSet;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::User;
+use crate::utils::{check_role, require_view_permission, validate_session};
+
+const PERMISSION_VIEW: &str = "view";
+const PERMISSION_EDIT: &str = "edit";
+const PERMISSION_ADMIN: &str = "admin";
+
+/// Dashboard widget known to the backend
+#[derive(Debug, Clone, Serialize)]
+pub struct WidgetDefinition {
+    pub id: &'static str,
+    pub title: &'static str,
+    /// "view", "edit" or "admin"
+    pub required_permission: &'static str,
+    pub default_width: i64,
+    pub default_height: i64,
+}
+
+const fn widget(
+    id: &'static str,
+    title: &'static str,
+    required_permission: &'static str,
+    default_width: i64,
+    default_height: i64,
+) -> WidgetDefinition {
+    WidgetDefinition { id, title, required_permission, default_width, default_height }
+}
+
+/// Every widget the dashboard can show. Removing an entry here is safe: saved
+/// layouts that still reference it drop it on read.
+const WIDGET_REGISTRY: &[WidgetDefinition] = &[
+    widget("machine_utilization", "Machine Utilization", PERMISSION_VIEW, 1, 1),
+    widget("active_machines", "Active Machines", PERMISSION_VIEW, 1, 1),
+    widget("active_projects", "Active Projects", PERMISSION_VIEW, 1, 1),
+    widget("weekly_hours", "Weekly Hours", PERMISSION_VIEW, 1, 1),
+    widget("total_clients", "Total Clients", PERMISSION_VIEW, 1, 1),
+    widget("upcoming_maintenance", "Upcoming Maintenance", PERMISSION_VIEW, 1, 1),
+    widget("unread_alerts", "Unread Alerts", PERMISSION_VIEW, 1, 1),
+    widget("machine_oee", "Machine OEE", PERMISSION_VIEW, 4, 2),
+    widget("machine_status_distribution", "Machine Status Distribution", PERMISSION_VIEW, 2, 2),
+    widget("project_status_distribution", "Project Status Distribution", PERMISSION_VIEW, 2, 2),
+    widget("machine_status", "Machine Status", PERMISSION_VIEW, 2, 2),
+    widget("project_progress", "Project Progress", PERMISSION_VIEW, 2, 2),
+    widget("weekly_hours_trend", "Weekly Hours Trend", PERMISSION_VIEW, 2, 2),
+    widget("recent_alerts", "Recent Alerts", PERMISSION_VIEW, 2, 2),
+    widget("machine_utilization_week", "Machine Utilization (This Week)", PERMISSION_VIEW, 4, 2),
+    widget("material_risk", "Material Risk", PERMISSION_VIEW, 2, 2),
+    widget("overtime_report", "Overtime", PERMISSION_EDIT, 2, 2),
+    widget("audit_activity", "Audit Activity", PERMISSION_ADMIN, 2, 2),
+];
+
+const ADMIN_DEFAULT_WIDGETS: &[&str] = &[
+    "machine_utilization",
+    "active_machines",
+    "active_projects",
+    "unread_alerts",
+    "machine_oee",
+    "machine_status",
+    "project_progress",
+    "overtime_report",
+    "audit_activity",
+    "recent_alerts",
+];
+const OPERATOR_DEFAULT_WIDGETS: &[&str] = &[
+    "active_machines",
+    "weekly_hours",
+    "upcoming_maintenance",
+    "unread_alerts",
+    "machine_status",
+    "material_risk",
+    "recent_alerts",
+];
+const VIEWER_DEFAULT_WIDGETS: &[&str] = &[
+    "machine_utilization",
+    "active_projects",
+    "weekly_hours",
+    "total_clients",
+    "project_progress",
+    "weekly_hours_trend",
+];
+
+/// Columns in the dashboard grid
+const GRID_COLUMNS: i64 = 4;
+
+/// Placement of one widget on the dashboard grid
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardLayoutItem {
+    pub widget_id: String,
+    pub x: i64,
+    pub y: i64,
+    pub w: i64,
+    pub h: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardLayout {
+    pub items: Vec<DashboardLayoutItem>,
+    /// True when the user has not saved a layout and the role default is shown
+    pub is_default: bool,
+    /// Saved widgets that were dropped because they no longer exist or are no longer permitted
+    pub warnings: Vec<String>,
+}
+
+fn widget_permitted(user: &User, widget: &WidgetDefinition) -> bool {
+    match widget.required_permission {
+        // Hiding a widget is not a refused action, so check the role without auditing
+        PERMISSION_ADMIN => check_role(user, &["Admin"]).is_ok(),
+        PERMISSION_EDIT => check_role(user, &["Admin", "Operator"]).is_ok(),
+        _ => require_view_permission(user).is_ok(),
+    }
+}
+
+fn find_widget(id: &str) -> Option<&'static WidgetDefinition> {
+    WIDGET_REGISTRY.iter().find(|w| w.id == id)
+}
+
+/// Built-in layout for a role, flowing widgets left to right across the grid
+fn default_layout(user: &User) -> Vec<DashboardLayoutItem> {
+    let ids = match user.role.as_str() {
+        "Admin" => ADMIN_DEFAULT_WIDGETS,
+        "Operator" => OPERATOR_DEFAULT_WIDGETS,
+        _ => VIEWER_DEFAULT_WIDGETS,
+    };
+
+    let mut items = Vec::new();
+    let (mut x, mut y, mut row_height) = (0, 0, 0);
+    for widget in ids.iter().filter_map(|id| find_widget(id)) {
+        if !widget_permitted(user, widget) {
+            continue;
+        }
+        if x + widget.default_width > GRID_COLUMNS {
+            x = 0;
+            y += row_height;
+            row_height = 0;
+        }
+        items.push(DashboardLayoutItem {
+            widget_id: widget.id.to_string(),
+            x,
+            y,
+            w: widget.default_width,
+            h: widget.default_height,
+        });
+        x += widget.default_width;
+        row_height = row_height.max(widget.default_height);
+    }
+    items
+}
+
+fn load_layout(conn: &Connection, user: &User) -> Result<DashboardLayout, String> {
+    let saved: Option<String> = conn
+        .query_row(
+            "SELECT layout FROM user_dashboard_layouts WHERE user_id = ?1",
+            [user.id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    let Some(saved) = saved else {
+        return Ok(DashboardLayout { items: default_layout(user), is_default: true, warnings: Vec::new() });
+    };
+
+    let items: Vec<DashboardLayoutItem> = match serde_json::from_str(&saved) {
+        Ok(items) => items,
+        Err(_) => {
+            return Ok(DashboardLayout {
+                items: default_layout(user),
+                is_default: true,
+                warnings: vec!["Saved layout could not be read; showing the default layout".to_string()],
+            })
+        }
+    };
+
+    let mut warnings = Vec::new();
+    let items = items
+        .into_iter()
+        .filter(|item| match find_widget(&item.widget_id) {
+            Some(widget) if widget_permitted(user, widget) => true,
+            Some(_) => {
+                warnings.push(format!("Widget '{}' is not available to your role", item.widget_id));
+                false
+            }
+            None => {
+                warnings.push(format!("Widget '{}' no longer exists", item.widget_id));
+                false
+            }
+        })
+        .collect();
+
+    Ok(DashboardLayout { items, is_default: false, warnings })
+}
+
+/// Get the widgets the current user may place on their dashboard
+#[tauri::command]
+pub fn get_available_widgets(token: String, db: State<'_, Database>) -> Result<Vec<WidgetDefinition>, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_view_permission(&user)?;
+
+    Ok(WIDGET_REGISTRY
+        .iter()
+        .filter(|w| widget_permitted(&user, w))
+        .cloned()
+        .collect())
+}
+
+/// Get the current user's dashboard layout, or their role's default
+#[tauri::command]
+pub fn get_my_dashboard_layout(token: String, db: State<'_, Database>) -> Result<DashboardLayout, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    Ok(load_layout(&conn, &user)?)
+}
+
+/// Save the current user's dashboard layout
+#[tauri::command]
+pub fn save_my_dashboard_layout(
+    token: String,
+    layout_json: String,
+    db: State<'_, Database>,
+) -> Result<DashboardLayout, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_view_permission(&user)?;
+
+    let items: Vec<DashboardLayoutItem> =
+        serde_json::from_str(&layout_json).map_err(|e| format!("Invalid layout: {}", e))?;
+
+    let mut seen = HashSet::new();
+    for item in &items {
+        let widget = find_widget(&item.widget_id).ok_or_else(|| format!("Unknown widget '{}'", item.widget_id))?;
+        if !widget_permitted(&user, widget) {
+            return Err(AppError::validation(format!("widget '{}' is not available to your role", item.widget_id)));
+        }
+        if !seen.insert(item.widget_id.as_str()) {
+            return Err(AppError::validation(format!("widget '{}' appears more than once", item.widget_id)));
+        }
+        if item.x < 0 || item.y < 0 || item.w < 1 || item.h < 1 || item.x + item.w > GRID_COLUMNS {
+            return Err(AppError::validation(format!("widget '{}' has an invalid position or size", item.widget_id)));
+        }
+    }
+
+    let layout = serde_json::to_string(&items).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO user_dashboard_layouts (user_id, layout, updated_at)
+         VALUES (?1, ?2, CURRENT_TIMESTAMP)
+         ON CONFLICT(user_id) DO UPDATE SET layout = excluded.layout, updated_at = CURRENT_TIMESTAMP",
+        params![user.id, layout],
+    )
+    .map_err(|e| format!("Failed to save dashboard layout: {}", e))?;
+
+    Ok(load_layout(&conn, &user)?)
+}
@@ -0,0 +1,649 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::{params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager, State};
+
+use crate::commands::audit::{foreign_keys, sql_value, table_columns, TableColumn};
+use crate::db::meta::APP_VERSION;
+use crate::db::migrations::{user_version, LATEST_MIGRATION};
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{computed_progress, User};
+use crate::utils::{record_audit, require_admin, run_operation, validate_session, OperationContext, OperationOutcome};
+
+/// Identifies a data export, whatever the file has been renamed to
+pub const EXPORT_FORMAT: &str = "vmc-planner-data";
+
+/// Layout of the export document. Tables gaining columns doesn't change it.
+pub const EXPORT_VERSION: i64 = 1;
+
+pub const IMPORT_MODE_REPLACE: &str = "replace";
+pub const IMPORT_MODE_MERGE: &str = "merge";
+
+/// Short-lived or per-install tables that never move between databases
+const LOCAL_TABLES: [&str; 3] = ["sessions", "login_attempts", "app_meta"];
+
+/// Sign-in secrets, exported only when asked for
+const CREDENTIAL_TABLES: [&str; 1] = ["api_tokens"];
+const CREDENTIAL_COLUMNS: [&str; 3] = ["password_hash", "totp_secret", "totp_pending_secret"];
+
+/// Tables naming records by bare id, which a merge renumbers
+const MERGE_SKIPPED_TABLES: [&str; 2] = ["audit_log", "demo_records"];
+
+/// Hours-based progress added to exported projects beside progress_override,
+/// for readers of the file; it isn't a column, so imports ignore it
+const COMPUTED_PROGRESS_FIELD: &str = "computed_progress";
+
+/// Stored for imported users whose password wasn't exported; matches no password
+const NO_PASSWORD_HASH: &str = "!password-reset-required";
+
+/// A whole database as one versioned JSON document, readable as a backup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataExport {
+    pub format: String,
+    pub version: i64,
+    pub app_version: String,
+    /// Migration the source database was at
+    pub schema_version: i64,
+    pub exported_at: String,
+    pub exported_by: String,
+    /// Whether password hashes, two-factor secrets and API tokens are included
+    pub includes_credentials: bool,
+    /// Machine documents whose rows are exported but whose files are not; the
+    /// files stay in the machine_documents folder beside the database
+    #[serde(default)]
+    pub document_files_not_included: i64,
+    /// Parents before the tables that reference them
+    pub tables: Vec<ExportedTable>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedTable {
+    pub name: String,
+    pub rows: Vec<Map<String, Value>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableRowCount {
+    pub table: String,
+    pub rows: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataExportSummary {
+    pub file_path: String,
+    pub includes_credentials: bool,
+    /// Attached document files left out of the export, to copy by hand
+    pub document_files_not_included: i64,
+    pub tables: Vec<TableRowCount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableImportCount {
+    pub table: String,
+    pub inserted: i64,
+    /// Merge only: rows that matched an existing record on a unique key and were kept as is
+    pub matched: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedTable {
+    pub table: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataImportResult {
+    pub mode: String,
+    pub tables: Vec<TableImportCount>,
+    pub skipped_tables: Vec<SkippedTable>,
+    /// Imported users without a password, who need one set before signing in
+    pub users_needing_password: Vec<String>,
+    /// A replace removes every session, including the one that ran it
+    pub signed_out: bool,
+}
+
+/// A unique index or constraint; `filter` is the WHERE clause of a partial index
+struct UniqueKey {
+    columns: Vec<String>,
+    filter: Option<String>,
+}
+
+struct DataTable {
+    name: String,
+    columns: Vec<TableColumn>,
+    /// (column, referenced table)
+    parents: Vec<(String, String)>,
+    unique_keys: Vec<UniqueKey>,
+    /// Rows are identified by an integer `id`, which a merge renumbers
+    has_id: bool,
+}
+
+impl DataTable {
+    fn parent_of(&self, column: &str) -> Option<&str> {
+        self.parents.iter().find(|(from, _)| from == column).map(|(_, table)| table.as_str())
+    }
+}
+
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn unique_keys(conn: &Connection, table: &str) -> Result<Vec<UniqueKey>, String> {
+    let mut stmt = conn
+        .prepare("SELECT name, \"partial\" FROM pragma_index_list(?1) WHERE \"unique\" = 1")
+        .map_err(|e| e.to_string())?;
+    let indexes = stmt
+        .query_map([table], |row| Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut keys = Vec::new();
+    for (index, partial) in indexes {
+        let columns: Vec<Option<String>> = conn
+            .prepare("SELECT name FROM pragma_index_info(?1) ORDER BY seqno")
+            .and_then(|mut stmt| stmt.query_map([&index], |row| row.get(0))?.collect())
+            .map_err(|e| e.to_string())?;
+        // Indexes on expressions can't be matched column by column
+        let Some(columns) = columns.into_iter().collect::<Option<Vec<_>>>() else {
+            continue;
+        };
+        let filter = if partial {
+            let sql: Option<String> = conn
+                .query_row("SELECT sql FROM sqlite_master WHERE type = 'index' AND name = ?1", [&index], |row| {
+                    row.get(0)
+                })
+                .optional()
+                .map_err(|e| e.to_string())?
+                .flatten();
+            let Some(filter) = sql.and_then(|sql| {
+                let at = sql.to_uppercase().rfind(" WHERE ")?;
+                Some(sql[at + " WHERE ".len()..].trim().to_string())
+            }) else {
+                continue;
+            };
+            Some(filter)
+        } else {
+            None
+        };
+        keys.push(UniqueKey { columns, filter });
+    }
+    Ok(keys)
+}
+
+/// Every table that moves with the data, parents before the tables referencing them
+fn data_tables(conn: &Connection) -> Result<Vec<DataTable>, String> {
+    let names: Vec<String> = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name")
+        .and_then(|mut stmt| stmt.query_map([], |row| row.get(0))?.collect())
+        .map_err(|e| e.to_string())?;
+
+    let mut pending = Vec::new();
+    for name in names.into_iter().filter(|n| !LOCAL_TABLES.contains(&n.as_str())) {
+        let columns = table_columns(conn, &name)?;
+        let has_id = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = 'id' AND pk = 1 AND UPPER(type) = 'INTEGER'",
+                [&name],
+                |row| row.get::<_, i64>(0),
+            )
+            .map_err(|e| e.to_string())?
+            > 0;
+        let parents = foreign_keys(conn, &name)?.into_iter().map(|(from, table, _)| (from, table)).collect();
+        let unique_keys = unique_keys(conn, &name)?;
+        pending.push(DataTable { name, columns, parents, unique_keys, has_id });
+    }
+
+    let mut ordered: Vec<DataTable> = Vec::new();
+    while !pending.is_empty() {
+        let ready = pending.iter().position(|table| {
+            table.parents.iter().all(|(_, parent)| {
+                *parent == table.name
+                    || ordered.iter().any(|t| t.name == *parent)
+                    || !pending.iter().any(|t| t.name == *parent)
+            })
+        });
+        // A reference cycle keeps the remaining tables in name order; deferred keys cover them
+        ordered.push(pending.remove(ready.unwrap_or(0)));
+    }
+    Ok(ordered)
+}
+
+fn json_value(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::from(i),
+        ValueRef::Real(f) => serde_json::Number::from_f64(f).map(Value::Number).unwrap_or(Value::Null),
+        ValueRef::Text(text) => Value::String(String::from_utf8_lossy(text).into_owned()),
+        ValueRef::Blob(bytes) => Value::Array(bytes.iter().map(|&b| Value::from(b)).collect()),
+    }
+}
+
+/// Inverse of `json_value`: byte arrays go back to blobs
+fn import_value(value: &Value) -> SqlValue {
+    if let Value::Array(items) = value {
+        let bytes: Option<Vec<u8>> = items.iter().map(|v| v.as_u64().and_then(|b| u8::try_from(b).ok())).collect();
+        if let Some(bytes) = bytes {
+            return SqlValue::Blob(bytes);
+        }
+    }
+    sql_value(value)
+}
+
+fn export_tables(
+    conn: &Connection,
+    ctx: &OperationContext,
+    include_credentials: bool,
+) -> Result<Vec<ExportedTable>, String> {
+    let tables = data_tables(conn)?;
+    let total = tables.len() as i64;
+    let mut exported = Vec::new();
+    for (index, table) in tables.iter().enumerate() {
+        ctx.progress(index as i64, total, &format!("Exporting {}", table.name))?;
+        if !include_credentials && CREDENTIAL_TABLES.contains(&table.name.as_str()) {
+            continue;
+        }
+        let skip_credentials = !include_credentials && table.name == "users";
+
+        let mut stmt = conn
+            .prepare(&format!("SELECT * FROM {}", quote(&table.name)))
+            .map_err(|e| e.to_string())?;
+        let names: Vec<String> = stmt.column_names().iter().map(|name| name.to_string()).collect();
+        let rows = stmt
+            .query_map([], |row| {
+                let mut object = Map::new();
+                for (i, name) in names.iter().enumerate() {
+                    if skip_credentials && CREDENTIAL_COLUMNS.contains(&name.as_str()) {
+                        continue;
+                    }
+                    object.insert(name.clone(), json_value(row.get_ref(i)?));
+                }
+                if table.name == "projects" {
+                    let hours = |key: &str| object.get(key).and_then(Value::as_f64).unwrap_or(0.0);
+                    let progress = computed_progress(hours("planned_hours"), hours("actual_hours"));
+                    object.insert(COMPUTED_PROGRESS_FIELD.to_string(), Value::from(progress));
+                }
+                Ok(object)
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| format!("Failed to export {}: {}", table.name, e))?;
+        exported.push(ExportedTable { name: table.name.clone(), rows });
+    }
+    ctx.progress(total, total, "Export complete")?;
+    Ok(exported)
+}
+
+/// Rowid of an existing record sharing a unique key with the incoming values
+fn find_existing(conn: &Connection, table: &DataTable, values: &[(String, SqlValue)]) -> Result<Option<i64>, String> {
+    for key in &table.unique_keys {
+        let key_values: Option<Vec<&SqlValue>> = key
+            .columns
+            .iter()
+            .map(|column| {
+                values
+                    .iter()
+                    .find(|(name, _)| name == column)
+                    .map(|(_, value)| value)
+                    .filter(|value| **value != SqlValue::Null)
+            })
+            .collect();
+        let Some(key_values) = key_values else {
+            continue;
+        };
+
+        if let Some(filter) = &key.filter {
+            // A partial index only constrains incoming rows its filter selects
+            let columns: Vec<String> =
+                values.iter().enumerate().map(|(i, (name, _))| format!("?{} AS {}", i + 1, quote(name))).collect();
+            let applies = conn
+                .query_row(
+                    &format!("SELECT 1 FROM (SELECT {}) WHERE {}", columns.join(", "), filter),
+                    params_from_iter(values.iter().map(|(_, value)| value)),
+                    |_| Ok(()),
+                )
+                .optional()
+                .unwrap_or(None)
+                .is_some();
+            if !applies {
+                continue;
+            }
+        }
+
+        let mut conditions: Vec<String> =
+            key.columns.iter().enumerate().map(|(i, column)| format!("{} = ?{}", quote(column), i + 1)).collect();
+        if let Some(filter) = &key.filter {
+            conditions.push(format!("({})", filter));
+        }
+        let existing = conn
+            .query_row(
+                &format!("SELECT rowid FROM {} WHERE {} LIMIT 1", quote(&table.name), conditions.join(" AND ")),
+                params_from_iter(key_values),
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?;
+        if existing.is_some() {
+            return Ok(existing);
+        }
+    }
+    Ok(None)
+}
+
+/// Credentials of current users by username, kept across a replace whose file has none
+fn current_credentials(conn: &Connection) -> Result<HashMap<String, Vec<(String, SqlValue)>>, String> {
+    let columns: Vec<String> = table_columns(conn, "users")?
+        .into_iter()
+        .map(|c| c.name)
+        .filter(|name| CREDENTIAL_COLUMNS.contains(&name.as_str()))
+        .collect();
+    let quoted: Vec<String> = columns.iter().map(|c| quote(c)).collect();
+    let mut stmt = conn
+        .prepare(&format!("SELECT username, {} FROM users", quoted.join(", ")))
+        .map_err(|e| e.to_string())?;
+    let credentials = stmt
+        .query_map([], |row| {
+            let values = columns
+                .iter()
+                .enumerate()
+                .map(|(i, name)| Ok((name.clone(), row.get::<_, SqlValue>(i + 1)?)))
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok((row.get::<_, String>(0)?, values))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<rusqlite::Result<HashMap<_, _>>>()
+        .map_err(|e| e.to_string())?;
+    Ok(credentials)
+}
+
+fn import_tables(
+    conn: &Connection,
+    ctx: &OperationContext,
+    export: &DataExport,
+    mode: &str,
+) -> Result<DataImportResult, String> {
+    let merge = mode == IMPORT_MODE_MERGE;
+    let tables = data_tables(conn)?;
+
+    let mut skipped_tables: Vec<SkippedTable> = export
+        .tables
+        .iter()
+        .filter(|source| !tables.iter().any(|t| t.name == source.name))
+        .map(|source| SkippedTable {
+            table: source.name.clone(),
+            reason: "Not a table in this database".to_string(),
+        })
+        .collect();
+    let sources: Vec<(&DataTable, &ExportedTable)> = tables
+        .iter()
+        .filter_map(|table| Some((table, export.tables.iter().find(|source| source.name == table.name)?)))
+        .filter(|(table, _)| {
+            let skip = merge && MERGE_SKIPPED_TABLES.contains(&table.name.as_str());
+            if skip {
+                skipped_tables.push(SkippedTable {
+                    table: table.name.clone(),
+                    reason: "Refers to records by id, so it is only imported when replacing".to_string(),
+                });
+            }
+            !skip
+        })
+        .collect();
+    let total: i64 = sources.iter().map(|(_, source)| source.rows.len() as i64).sum();
+
+    let kept_credentials =
+        if merge || export.includes_credentials { HashMap::new() } else { current_credentials(conn)? };
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    // References are checked once everything is in, so row order within the file doesn't matter
+    tx.execute_batch("PRAGMA defer_foreign_keys = ON").map_err(|e| e.to_string())?;
+
+    if !merge {
+        for table in tables.iter().rev() {
+            tx.execute(&format!("DELETE FROM {}", quote(&table.name)), [])
+                .map_err(|e| format!("Failed to clear {}: {}", table.name, e))?;
+        }
+        tx.execute("DELETE FROM sessions", []).map_err(|e| e.to_string())?;
+    }
+
+    let mut id_maps: HashMap<&str, HashMap<i64, i64>> = HashMap::new();
+    let mut counts = Vec::new();
+    let mut users_needing_password = Vec::new();
+    let mut done = 0;
+    for (table, source) in sources {
+        let mut count = TableImportCount { table: table.name.clone(), inserted: 0, matched: 0 };
+        let mut id_map = HashMap::new();
+
+        for (row_number, row) in source.rows.iter().enumerate() {
+            done += 1;
+            ctx.progress(done, total, &format!("Importing {}", table.name))?;
+            let old_id = row.get("id").and_then(Value::as_i64);
+            let describe = || match old_id {
+                Some(id) => format!("{} {}", table.name, id),
+                None => format!("{} row {}", table.name, row_number + 1),
+            };
+
+            let mut values: Vec<(String, SqlValue)> = Vec::new();
+            for column in &table.columns {
+                if merge && table.has_id && column.name == "id" {
+                    continue;
+                }
+                // Columns the file doesn't have take their defaults
+                let Some(value) = row.get(&column.name) else {
+                    continue;
+                };
+                let mut value = import_value(value);
+                let parent_ids = table.parent_of(&column.name).and_then(|parent| id_maps.get(parent));
+                if let (Some(parent_ids), SqlValue::Integer(old)) = (parent_ids, &value) {
+                    value = match parent_ids.get(old) {
+                        Some(&new) => SqlValue::Integer(new),
+                        None if column.not_null => {
+                            return Err(format!(
+                                "{} refers to {} {} which is not in the file",
+                                describe(),
+                                table.parent_of(&column.name).unwrap_or_default(),
+                                old
+                            ))
+                        }
+                        None => SqlValue::Null,
+                    };
+                }
+                values.push((column.name.clone(), value));
+            }
+
+            if merge {
+                if let Some(existing) = find_existing(&tx, table, &values)? {
+                    if let Some(old_id) = old_id {
+                        id_map.insert(old_id, existing);
+                    }
+                    count.matched += 1;
+                    continue;
+                }
+            }
+
+            if table.name == "users" && !export.includes_credentials {
+                let username = row.get("username").and_then(Value::as_str).unwrap_or_default();
+                match kept_credentials.get(username) {
+                    Some(credentials) => values.extend(credentials.iter().cloned()),
+                    None => {
+                        values.push(("password_hash".to_string(), SqlValue::Text(NO_PASSWORD_HASH.to_string())));
+                        users_needing_password.push(username.to_string());
+                    }
+                }
+            }
+
+            let columns: Vec<String> = values.iter().map(|(name, _)| quote(name)).collect();
+            let placeholders: Vec<String> = (1..=values.len()).map(|i| format!("?{}", i)).collect();
+            tx.execute(
+                &format!(
+                    "INSERT INTO {} ({}) VALUES ({})",
+                    quote(&table.name),
+                    columns.join(", "),
+                    placeholders.join(", ")
+                ),
+                params_from_iter(values.iter().map(|(_, value)| value)),
+            )
+            .map_err(|e| format!("Failed to import {}: {}", describe(), e))?;
+            if let Some(old_id) = old_id.filter(|_| table.has_id) {
+                id_map.insert(old_id, tx.last_insert_rowid());
+            }
+            count.inserted += 1;
+        }
+
+        if merge && table.has_id {
+            id_maps.insert(&table.name, id_map);
+        }
+        counts.push(count);
+    }
+
+    tx.commit()
+        .map_err(|e| format!("The file has rows referring to records it doesn't contain: {}", e))?;
+    Ok(DataImportResult {
+        mode: mode.to_string(),
+        tables: counts,
+        skipped_tables,
+        users_needing_password,
+        signed_out: !merge,
+    })
+}
+
+/// Write every table to one JSON file (Admin only)
+///
+/// Sessions, sign-in attempts and version metadata stay behind. Password
+/// hashes, two-factor secrets and API tokens are left out unless
+/// `include_credentials` is set; sealed two-factor secrets only open next to
+/// the source database's key file. Machine document files are not included,
+/// only their records; the summary says how many were left out. The
+/// pretty-printed file doubles as a human-readable backup.
+#[tauri::command]
+pub fn export_all_data(
+    token: String,
+    file_path: String,
+    include_credentials: Option<bool>,
+    background: Option<bool>,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<OperationOutcome<DataExportSummary>, AppError> {
+    let user = {
+        let conn = db.conn.lock();
+        let user = validate_session(&conn, &token)?;
+        require_admin(&conn, &user)?;
+        user
+    };
+    let include_credentials = include_credentials.unwrap_or(false);
+
+    run_operation(&app, "export_all_data", user.id, background.unwrap_or(false), move |ctx| {
+        let db = ctx.app().state::<Database>();
+        let export = {
+            let conn = db.read();
+            // One read transaction so every table comes from the same moment
+            let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+            DataExport {
+                format: EXPORT_FORMAT.to_string(),
+                version: EXPORT_VERSION,
+                app_version: APP_VERSION.to_string(),
+                schema_version: user_version(&tx).map_err(|e| e.to_string())?,
+                exported_at: Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                exported_by: user.username.clone(),
+                includes_credentials: include_credentials,
+                document_files_not_included: tx
+                    .query_row("SELECT COUNT(*) FROM machine_documents", [], |row| row.get(0))
+                    .map_err(|e| e.to_string())?,
+                tables: export_tables(&tx, ctx, include_credentials)?,
+            }
+        };
+
+        let json = serde_json::to_string_pretty(&export).map_err(|e| e.to_string())?;
+        std::fs::write(&file_path, json).map_err(|e| format!("Cannot write '{}': {}", file_path, e))?;
+
+        let tables: Vec<TableRowCount> = export
+            .tables
+            .iter()
+            .map(|t| TableRowCount { table: t.name.clone(), rows: t.rows.len() as i64 })
+            .collect();
+        let details = serde_json::json!({ "file": file_path, "includes_credentials": include_credentials });
+        record_audit(&db.conn.lock(), &user, "export", "data", None, None, Some(&details.to_string()));
+        Ok(DataExportSummary {
+            file_path,
+            includes_credentials: include_credentials,
+            document_files_not_included: export.document_files_not_included,
+            tables,
+        })
+    })
+}
+
+/// Load a file written by export_all_data (Admin only)
+///
+/// `replace` empties every table first and keeps the file's ids, signing
+/// everyone out. `merge` adds the file's records under new ids, pointing their
+/// references at the renumbered rows; a record sharing a unique key with an
+/// existing one, such as a username or machine name, is matched to it and the
+/// existing one kept. Either way the import is one transaction, so a failure
+/// leaves the database untouched. Users imported without a password can't
+/// sign in until an admin sets one; on replace, users who already exist keep
+/// their current credentials.
+#[tauri::command]
+pub fn import_all_data(
+    token: String,
+    file_path: String,
+    mode: String,
+    background: Option<bool>,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<OperationOutcome<DataImportResult>, AppError> {
+    let user = {
+        let conn = db.conn.lock();
+        let user = validate_session(&conn, &token)?;
+        require_admin(&conn, &user)?;
+        user
+    };
+
+    if mode != IMPORT_MODE_REPLACE && mode != IMPORT_MODE_MERGE {
+        return Err(AppError::invalid_field("mode", "Import mode must be 'replace' or 'merge'"));
+    }
+    let text = std::fs::read_to_string(&file_path).map_err(|e| format!("Cannot read '{}': {}", file_path, e))?;
+    let export: DataExport = serde_json::from_str(&text)
+        .map_err(|e| AppError::invalid_field("file_path", format!("Not a data export file: {}", e)))?;
+    if export.format != EXPORT_FORMAT {
+        return Err(AppError::invalid_field("file_path", "Not a data export file"));
+    }
+    if export.version > EXPORT_VERSION {
+        return Err(AppError::invalid_field(
+            "file_path",
+            format!(
+                "The file uses export format {} but this app reads up to {}. Please update the app.",
+                export.version, EXPORT_VERSION
+            ),
+        ));
+    }
+    if export.schema_version > LATEST_MIGRATION {
+        return Err(AppError::invalid_field(
+            "file_path",
+            format!(
+                "The file was exported by app version {} with a newer database schema. Please update the app.",
+                export.app_version
+            ),
+        ));
+    }
+
+    run_operation(&app, "import_all_data", user.id, background.unwrap_or(false), move |ctx| {
+        let db = ctx.app().state::<Database>();
+        let conn = db.conn.lock();
+        let result = import_tables(&conn, ctx, &export, &mode)?;
+
+        // After a replace the importing admin is whoever has their username in the file
+        let actor = conn.query_row("SELECT * FROM users WHERE username = ?1", [&user.username], User::from_row);
+        match actor {
+            Ok(actor) => {
+                let details = serde_json::json!({ "file": file_path, "mode": mode, "tables": result.tables });
+                record_audit(&conn, &actor, "import", "data", None, None, Some(&details.to_string()));
+            }
+            Err(_) => log::warn!("Imported data from {} ({}); importing user is not in it", file_path, mode),
+        }
+        Ok(result)
+    })
+}
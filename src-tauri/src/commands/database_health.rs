@@ -0,0 +1,226 @@
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tauri::{AppHandle, Manager, State};
+
+use crate::db::migrations::user_version;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::utils::{record_audit, require_admin, run_operation, validate_session, OperationOutcome};
+
+/// Share of the file on the freelist above which VACUUM is worth running
+const VACUUM_FREE_RATIO: f64 = 0.2;
+
+/// integrity_check stops after this many problems
+const MAX_INTEGRITY_MESSAGES: i64 = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableStats {
+    pub name: String,
+    pub rows: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexStats {
+    pub name: String,
+    pub table: String,
+    pub unique: bool,
+}
+
+/// Size, layout and consistency of the database file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseHealth {
+    pub path: String,
+    pub size_bytes: Option<u64>,
+    /// Write-ahead log not yet checkpointed into the main file
+    pub wal_size_bytes: Option<u64>,
+    pub page_size: i64,
+    pub page_count: i64,
+    /// Unused pages VACUUM would give back
+    pub freelist_count: i64,
+    pub free_bytes: i64,
+    pub vacuum_recommended: bool,
+    pub schema_version: i64,
+    pub journal_mode: String,
+    pub tables: Vec<TableStats>,
+    pub indexes: Vec<IndexStats>,
+    /// Latest full data export, which doubles as the backup
+    pub last_backup_at: Option<String>,
+    pub last_optimized_at: Option<String>,
+    pub integrity_ok: bool,
+    /// "ok", or the problems integrity_check found
+    pub integrity_messages: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizeResult {
+    pub size_before: Option<u64>,
+    pub size_after: Option<u64>,
+    pub duration_ms: i64,
+}
+
+fn file_size(path: &Path) -> Option<u64> {
+    std::fs::metadata(path).ok().map(|m| m.len())
+}
+
+/// SQLite keeps the write-ahead log beside the database as `<file>-wal`
+fn wal_path(path: &Path) -> PathBuf {
+    let mut wal = path.as_os_str().to_owned();
+    wal.push("-wal");
+    PathBuf::from(wal)
+}
+
+fn pragma_i64(conn: &Connection, pragma: &str) -> Result<i64, String> {
+    conn.query_row(&format!("PRAGMA {}", pragma), [], |row| row.get(0)).map_err(|e| e.to_string())
+}
+
+/// When the audit log last recorded `action` on `table_name`
+fn last_audited(conn: &Connection, action: &str, table_name: &str) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT MAX(timestamp) FROM audit_log WHERE action = ?1 AND table_name = ?2",
+        [action, table_name],
+        |row| row.get(0),
+    )
+    .optional()
+    .map(Option::flatten)
+    .map_err(|e| e.to_string())
+}
+
+fn collect_health(conn: &Connection, path: &Path) -> Result<DatabaseHealth, String> {
+    let page_size = pragma_i64(conn, "page_size")?;
+    let page_count = pragma_i64(conn, "page_count")?;
+    let freelist_count = pragma_i64(conn, "freelist_count")?;
+    let journal_mode: String =
+        conn.query_row("PRAGMA journal_mode", [], |row| row.get(0)).map_err(|e| e.to_string())?;
+
+    let table_names: Vec<String> = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name")
+        .and_then(|mut stmt| stmt.query_map([], |row| row.get(0))?.collect())
+        .map_err(|e| e.to_string())?;
+    let tables = table_names
+        .into_iter()
+        .map(|name| {
+            let rows = conn
+                .query_row(&format!("SELECT COUNT(*) FROM \"{}\"", name), [], |row| row.get(0))
+                .map_err(|e| e.to_string())?;
+            Ok(TableStats { name, rows })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let indexes = conn
+        .prepare(
+            "SELECT m.name, m.tbl_name, il.\"unique\"
+             FROM sqlite_master m JOIN pragma_index_list(m.tbl_name) il ON il.name = m.name
+             WHERE m.type = 'index'
+             ORDER BY m.tbl_name, m.name",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| Ok(IndexStats { name: row.get(0)?, table: row.get(1)?, unique: row.get(2)? }))?
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })
+        .map_err(|e| e.to_string())?;
+
+    let integrity_messages: Vec<String> = conn
+        .prepare(&format!("PRAGMA integrity_check({})", MAX_INTEGRITY_MESSAGES))
+        .and_then(|mut stmt| stmt.query_map([], |row| row.get(0))?.collect())
+        .map_err(|e| e.to_string())?;
+
+    Ok(DatabaseHealth {
+        path: path.display().to_string(),
+        size_bytes: file_size(path),
+        wal_size_bytes: file_size(&wal_path(path)),
+        page_size,
+        page_count,
+        freelist_count,
+        free_bytes: freelist_count * page_size,
+        vacuum_recommended: page_count > 0 && freelist_count as f64 / page_count as f64 > VACUUM_FREE_RATIO,
+        schema_version: user_version(conn).map_err(|e| e.to_string())?,
+        journal_mode,
+        tables,
+        indexes,
+        last_backup_at: last_audited(conn, "export", "data")?,
+        last_optimized_at: last_audited(conn, "optimize", "database")?,
+        integrity_ok: integrity_messages.len() == 1 && integrity_messages[0] == "ok",
+        integrity_messages,
+    })
+}
+
+/// Report file size, free pages, row counts, indexes, last backup and an
+/// integrity check (Admin only)
+#[tauri::command]
+pub fn get_database_health(token: String, db: State<'_, Database>) -> Result<DatabaseHealth, AppError> {
+    {
+        let conn = db.conn.lock();
+        let user = validate_session(&conn, &token)?;
+        require_admin(&conn, &user)?;
+    }
+    let conn = db.read();
+
+    Ok(collect_health(&conn, &db.path())?)
+}
+
+/// Rebuild the file with VACUUM, then refresh query planner statistics with
+/// ANALYZE (Admin only)
+///
+/// Holds the writer for the whole run, so other changes wait. VACUUM works on
+/// a copy and needs about the database's size in free disk space; if it
+/// fails, the database is left as it was.
+#[tauri::command]
+pub fn optimize_database(
+    token: String,
+    background: Option<bool>,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<OperationOutcome<OptimizeResult>, AppError> {
+    let user = {
+        let conn = db.conn.lock();
+        let user = validate_session(&conn, &token)?;
+        require_admin(&conn, &user)?;
+        user
+    };
+
+    run_operation(&app, "optimize_database", user.id, background.unwrap_or(false), move |ctx| {
+        let db = ctx.app().state::<Database>();
+        let path = db.path();
+        let conn = db.conn.lock();
+        let started = Instant::now();
+        let size_before = file_size(&path);
+
+        let steps = [
+            ("VACUUM", "Rebuilding the database file"),
+            ("ANALYZE", "Refreshing query statistics"),
+            ("PRAGMA wal_checkpoint(TRUNCATE)", "Folding the write-ahead log into the file"),
+        ];
+        for (index, (sql, message)) in steps.iter().enumerate() {
+            ctx.progress(index as i64, steps.len() as i64, message)?;
+            conn.execute_batch(sql).map_err(|e| {
+                format!(
+                    "Optimization stopped at step {} of {} ({}): {}. The database was left as it was; \
+                     make sure there is free disk space about the size of the database and try again.",
+                    index + 1,
+                    steps.len(),
+                    message.to_lowercase(),
+                    e
+                )
+            })?;
+        }
+        ctx.progress(steps.len() as i64, steps.len() as i64, "Optimization complete")?;
+
+        let result = OptimizeResult {
+            size_before,
+            size_after: file_size(&path),
+            duration_ms: started.elapsed().as_millis() as i64,
+        };
+        record_audit(
+            &conn,
+            &user,
+            "optimize",
+            "database",
+            None,
+            None,
+            Some(&serde_json::to_string(&result).map_err(|e| e.to_string())?),
+        );
+        Ok(result)
+    })
+}
@@ -0,0 +1,174 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::db::seed::seed_demo_data;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::utils::{
+    get_bool, record_audit, require_admin, run_operation, set_setting, validate_session, OperationOutcome,
+    DEMO_DATA_LOADED_KEY,
+};
+
+/// Rows added or removed by loading or clearing demo data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DemoDataSummary {
+    pub machines: i64,
+    pub locations: i64,
+    /// Demo machines left in place because schedule entries or maintenance were recorded on them
+    pub kept_machines: Vec<String>,
+}
+
+fn demo_record_ids(conn: &Connection, table: &str) -> Result<Vec<i64>, String> {
+    conn.prepare("SELECT record_id FROM demo_records WHERE table_name = ?1 ORDER BY record_id")
+        .and_then(|mut stmt| stmt.query_map([table], |row| row.get(0))?.collect())
+        .map_err(|e| e.to_string())
+}
+
+fn forget_demo_record(conn: &Connection, table: &str, id: i64) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM demo_records WHERE table_name = ?1 AND record_id = ?2",
+        params![table, id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Whether demo data is currently loaded, for the dashboard banner
+pub fn demo_data_loaded(conn: &Connection) -> bool {
+    get_bool(conn, DEMO_DATA_LOADED_KEY, false)
+}
+
+/// Add the sample machines and their locations (Admin only)
+///
+/// Fresh installs start with only the admin account; this loads the sample
+/// set for trying the app out. Sample machines whose names are already in use
+/// are skipped. Runs as an operation, so it reports progress and can be
+/// cancelled or sent to the background like import.
+#[tauri::command]
+pub fn load_demo_data(
+    token: String,
+    background: Option<bool>,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<OperationOutcome<DemoDataSummary>, AppError> {
+    let user = {
+        let conn = db.conn.lock();
+        let user = validate_session(&conn, &token)?;
+        require_admin(&conn, &user)?;
+        if demo_data_loaded(&conn) {
+            return Err(AppError::Conflict("Demo data is already loaded".to_string()));
+        }
+        user
+    };
+
+    run_operation(&app, "load_demo_data", user.id, background.unwrap_or(false), move |ctx| {
+        let db = ctx.app().state::<Database>();
+        let conn = db.conn.lock();
+        // Checked again under this lock, in case another load finished in between
+        if demo_data_loaded(&conn) {
+            return Err("Demo data is already loaded".to_string());
+        }
+
+        ctx.progress(0, 2, "Adding sample machines")?;
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        let created = seed_demo_data(&tx).map_err(|e| format!("Failed to load demo data: {}", e))?;
+        set_setting(&tx, DEMO_DATA_LOADED_KEY, "true", "boolean", Some(user.id))?;
+
+        let count = |table: &str| created.iter().filter(|(t, _)| *t == table).count() as i64;
+        let summary = DemoDataSummary {
+            machines: count("machines"),
+            locations: count("locations"),
+            kept_machines: Vec::new(),
+        };
+        record_audit(
+            &tx,
+            &user,
+            "load_demo_data",
+            "demo_records",
+            None,
+            None,
+            Some(&serde_json::json!({ "machines": summary.machines, "locations": summary.locations }).to_string()),
+        );
+        // Last chance to cancel; dropping tx rolls everything back
+        ctx.progress(1, 2, "Recording demo data")?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        Ok(summary)
+    })
+}
+
+/// Remove the rows load_demo_data created, and nothing else (Admin only)
+///
+/// Like delete_machine, a demo machine with schedule entries or maintenance
+/// records is kept so that work isn't lost; it stays marked as demo data and
+/// the banner stays up until it is cleared. Demo locations go once no machine
+/// uses them.
+#[tauri::command]
+pub fn clear_demo_data(token: String, db: State<'_, Database>) -> Result<DemoDataSummary, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_admin(&conn, &user)?;
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let mut summary = DemoDataSummary { machines: 0, locations: 0, kept_machines: Vec::new() };
+
+    for id in demo_record_ids(&tx, "machines")? {
+        let machine: Option<(String, i64)> = tx
+            .query_row(
+                "SELECT name,
+                        (SELECT COUNT(*) FROM schedules WHERE machine_id = ?1)
+                        + (SELECT COUNT(*) FROM maintenance WHERE machine_id = ?1)
+                 FROM machines WHERE id = ?1",
+                [id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .ok();
+        match machine {
+            Some((name, history)) if history > 0 => {
+                summary.kept_machines.push(name);
+                continue;
+            }
+            Some(_) => {
+                tx.execute("DELETE FROM alerts WHERE machine_id = ?1", [id])
+                    .map_err(|e| format!("Failed to delete machine alerts: {}", e))?;
+                tx.execute("DELETE FROM machines WHERE id = ?1", [id])
+                    .map_err(|e| format!("Failed to delete machine: {}", e))?;
+                summary.machines += 1;
+            }
+            // Already deleted by hand
+            None => {}
+        }
+        forget_demo_record(&tx, "machines", id)?;
+    }
+
+    for id in demo_record_ids(&tx, "locations")? {
+        let in_use: bool = tx
+            .query_row("SELECT EXISTS (SELECT 1 FROM machines WHERE location_id = ?1)", [id], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        if in_use {
+            continue;
+        }
+        summary.locations += tx
+            .execute("DELETE FROM locations WHERE id = ?1", [id])
+            .map_err(|e| format!("Failed to delete location: {}", e))? as i64;
+        forget_demo_record(&tx, "locations", id)?;
+    }
+
+    let remaining: i64 = tx
+        .query_row("SELECT COUNT(*) FROM demo_records", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    set_setting(&tx, DEMO_DATA_LOADED_KEY, if remaining > 0 { "true" } else { "false" }, "boolean", Some(user.id))?;
+    record_audit(
+        &tx,
+        &user,
+        "clear_demo_data",
+        "demo_records",
+        None,
+        None,
+        Some(&serde_json::to_string(&summary).map_err(|e| e.to_string())?),
+    );
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(summary)
+}
@@ -0,0 +1,220 @@
+use chrono::Utc;
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_notification::{NotificationExt, PermissionState};
+
+use crate::commands::notification_settings::{resolve_alert_cue, resolve_notification_settings};
+use crate::db::Database;
+use crate::error::AppError;
+use crate::utils::{parse_timestamp, require_view_permission, validate_session};
+
+/// How often the watcher looks for new alerts
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Shortest gap between two desktop notifications. Alerts arriving inside it
+/// are held and shown together once it has passed.
+const DEBOUNCE: Duration = Duration::from_secs(30);
+
+/// Alert titles listed in a combined notification
+const MAX_LISTED_TITLES: usize = 3;
+
+/// Only these priorities ever reach the desktop
+const DESKTOP_PRIORITIES: [&str; 2] = ["high", "critical"];
+
+/// The session signed in on this instance. Its user's notification settings
+/// decide which alerts are shown on the desktop.
+#[derive(Default)]
+pub struct DesktopNotifier {
+    session: Mutex<Option<String>>,
+}
+
+impl DesktopNotifier {
+    pub fn sign_in(&self, token: &str) {
+        *self.session.lock() = Some(token.to_string());
+    }
+
+    pub fn sign_out(&self, token: &str) {
+        let mut session = self.session.lock();
+        if session.as_deref() == Some(token) {
+            *session = None;
+        }
+    }
+
+    fn session(&self) -> Option<String> {
+        self.session.lock().clone()
+    }
+}
+
+struct NewAlert {
+    id: i64,
+    priority: String,
+    title: String,
+    message: String,
+}
+
+/// Follows the alerts table and turns new high and critical alerts into
+/// desktop notifications, whichever code path inserted them
+#[derive(Default)]
+struct AlertWatcher {
+    db_path: Option<PathBuf>,
+    last_seen: i64,
+    pending: Vec<NewAlert>,
+    last_shown: Option<Instant>,
+}
+
+/// User and device of a session that is still valid. Unlike validate_session
+/// this only reads, so polling doesn't keep the session alive.
+fn session_recipient(conn: &Connection, token: &str) -> Option<(i64, Option<String>)> {
+    let (user_id, device_name, expires_at): (i64, Option<String>, String) = conn
+        .query_row(
+            "SELECT s.user_id, s.device_name, s.expires_at
+             FROM sessions s JOIN users u ON u.id = s.user_id
+             WHERE s.token = ?1 AND s.is_valid = 1 AND u.is_active = 1",
+            [token],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()
+        .ok()
+        .flatten()?;
+    parse_timestamp(&expires_at).filter(|expires_at| *expires_at > Utc::now())?;
+    Some((user_id, device_name))
+}
+
+fn newest_alert_id(conn: &Connection) -> Result<i64, String> {
+    conn.query_row("SELECT COALESCE(MAX(id), 0) FROM alerts", [], |row| row.get(0))
+        .map_err(|e| e.to_string())
+}
+
+fn alerts_after(conn: &Connection, last_seen: i64) -> Result<Vec<NewAlert>, String> {
+    conn.prepare(
+        "SELECT id, priority, title, message FROM alerts
+         WHERE id > ?1 AND priority IN (?2, ?3) AND is_read = 0
+         ORDER BY id",
+    )
+    .and_then(|mut stmt| {
+        stmt.query_map(params![last_seen, DESKTOP_PRIORITIES[0], DESKTOP_PRIORITIES[1]], |row| {
+            Ok(NewAlert { id: row.get(0)?, priority: row.get(1)?, title: row.get(2)?, message: row.get(3)? })
+        })?
+        .collect()
+    })
+    .map_err(|e| e.to_string())
+}
+
+impl AlertWatcher {
+    fn tick(&mut self, app: &AppHandle) -> Result<(), String> {
+        let db = app.state::<Database>();
+        let path = db.path();
+        let conn = db.read();
+
+        // A freshly opened or switched database: start from its newest alert
+        // rather than announcing its history
+        if self.db_path.as_ref() != Some(&path) {
+            self.last_seen = newest_alert_id(&conn)?;
+            self.db_path = Some(path);
+            self.pending.clear();
+            return Ok(());
+        }
+
+        let Some((user_id, device_name)) =
+            app.state::<DesktopNotifier>().session().and_then(|token| session_recipient(&conn, &token))
+        else {
+            // Alerts raised while nobody is signed in are left for the in-app list
+            self.last_seen = newest_alert_id(&conn)?;
+            self.pending.clear();
+            return Ok(());
+        };
+
+        let alerts = alerts_after(&conn, self.last_seen)?;
+        if let Some(last) = alerts.last() {
+            self.last_seen = last.id;
+        }
+        if resolve_notification_settings(&conn, user_id, device_name.as_deref()).desktop_enabled {
+            self.pending.extend(
+                alerts
+                    .into_iter()
+                    .filter(|alert| resolve_alert_cue(&conn, user_id, device_name.as_deref(), &alert.priority).notify),
+            );
+        }
+        drop(conn);
+
+        if self.pending.is_empty() || self.last_shown.is_some_and(|shown| shown.elapsed() < DEBOUNCE) {
+            return Ok(());
+        }
+        let (title, body) = summarize(&self.pending);
+        self.pending.clear();
+        self.last_shown = Some(Instant::now());
+        app.notification()
+            .builder()
+            .title(title)
+            .body(body)
+            .show()
+            .map_err(|e| format!("Failed to show desktop notification: {}", e))
+    }
+}
+
+/// One alert is shown as it is; a burst becomes a single count with the
+/// first few titles
+fn summarize(alerts: &[NewAlert]) -> (String, String) {
+    if let [alert] = alerts {
+        return (alert.title.clone(), alert.message.clone());
+    }
+    let mut body = alerts
+        .iter()
+        .take(MAX_LISTED_TITLES)
+        .map(|alert| alert.title.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if alerts.len() > MAX_LISTED_TITLES {
+        body.push_str(&format!("\nand {} more", alerts.len() - MAX_LISTED_TITLES));
+    }
+    (format!("{} new alerts", alerts.len()), body)
+}
+
+/// Watch for new high and critical alerts and show them as OS notifications.
+/// Called once the database is open.
+///
+/// Polling the table rather than hooking each insert covers every command
+/// that raises alerts, and other instances sharing the database file.
+pub fn start_alert_watcher(app: &AppHandle) {
+    let app = app.clone();
+    thread::spawn(move || {
+        let mut watcher = AlertWatcher::default();
+        loop {
+            if let Err(e) = watcher.tick(&app) {
+                log::warn!("Desktop notification check failed: {}", e);
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
+
+/// Show a sample desktop notification so users can check the operating system
+/// lets the app notify them
+#[tauri::command]
+pub fn test_notification(token: String, app: AppHandle, db: State<'_, Database>) -> Result<(), AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+
+    let notification = app.notification();
+    let mut permission = notification.permission_state().map_err(|e| e.to_string())?;
+    if matches!(permission, PermissionState::Prompt | PermissionState::PromptWithRationale) {
+        permission = notification.request_permission().map_err(|e| e.to_string())?;
+    }
+    if permission != PermissionState::Granted {
+        return Err(AppError::Forbidden(
+            "Notifications are turned off for VMC Planner; allow them in the operating system's settings".to_string(),
+        ));
+    }
+
+    notification
+        .builder()
+        .title("VMC Planner")
+        .body(format!("Desktop notifications are working for {}", user.username))
+        .show()
+        .map_err(|e| format!("Failed to show notification: {}", e))?;
+    Ok(())
+}
@@ -2,7 +2,8 @@ use rusqlite::params;
 use serde::{Deserialize, Serialize};
 use tauri::State;
 use crate::db::Database;
-use crate::utils::{require_edit_permission, require_view_permission, validate_session};
+use crate::error::AppError;
+use crate::utils::{require_permission, require_view_permission, validate_session};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DowntimeLog {
@@ -28,10 +29,10 @@ pub struct CreateDowntimeInput {
 }
 
 #[tauri::command]
-pub fn get_downtime_log(token: String, machine_id: Option<i64>, db: State<'_, Database>) -> Result<Vec<DowntimeLog>, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+pub fn get_downtime_log(token: String, machine_id: Option<i64>, db: State<'_, Database>) -> Result<Vec<DowntimeLog>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
     let sql = if machine_id.is_some() {
         "SELECT d.*, m.name as machine_name FROM downtime_log d
@@ -96,10 +97,10 @@ pub fn get_downtime_log(token: String, machine_id: Option<i64>, db: State<'_, Da
 }
 
 #[tauri::command]
-pub fn create_downtime(token: String, input: CreateDowntimeInput, db: State<'_, Database>) -> Result<DowntimeLog, String> {
+pub fn create_downtime(token: String, input: CreateDowntimeInput, db: State<'_, Database>) -> Result<DowntimeLog, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "downtime.edit")?;
 
     conn.execute(
         "INSERT INTO downtime_log (machine_id, start_time, end_time, reason_category, description, created_by) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
@@ -137,19 +138,19 @@ pub fn create_downtime(token: String, input: CreateDowntimeInput, db: State<'_,
 }
 
 #[tauri::command]
-pub fn close_downtime(token: String, id: i64, end_time: String, db: State<'_, Database>) -> Result<(), String> {
+pub fn close_downtime(token: String, id: i64, end_time: String, db: State<'_, Database>) -> Result<(), AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "downtime.edit")?;
     conn.execute("UPDATE downtime_log SET end_time = ?1 WHERE id = ?2", params![end_time, id]).map_err(|e| e.to_string())?;
     Ok(())
 }
 
 #[tauri::command]
-pub fn delete_downtime(token: String, id: i64, db: State<'_, Database>) -> Result<(), String> {
+pub fn delete_downtime(token: String, id: i64, db: State<'_, Database>) -> Result<(), AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "downtime.delete")?;
     conn.execute("DELETE FROM downtime_log WHERE id = ?1", params![id]).map_err(|e| e.to_string())?;
     Ok(())
 }
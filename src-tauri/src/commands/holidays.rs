@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, NaiveDate};
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::utils::{require_permission, require_view_permission, validate_session};
+
+/// Leave entries that land on a holiday where they are
+pub const HOLIDAY_MODE_IGNORE: &str = "ignore";
+/// Drop entries that land on a holiday
+pub const HOLIDAY_MODE_SKIP: &str = "skip";
+/// Move entries that land on a holiday to the next working day
+pub const HOLIDAY_MODE_SHIFT: &str = "shift";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Holiday {
+    pub id: i64,
+    pub date: String,
+    pub name: String,
+    pub is_recurring: bool,
+    pub created_by: Option<i64>,
+    pub created_at: String,
+}
+
+impl Holiday {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            date: row.get("date")?,
+            name: row.get("name")?,
+            is_recurring: row.get::<_, i64>("is_recurring")? != 0,
+            created_by: row.get("created_by")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateHolidayInput {
+    pub date: String,
+    pub name: String,
+    pub is_recurring: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateHolidayInput {
+    pub date: Option<String>,
+    pub name: Option<String>,
+    pub is_recurring: Option<bool>,
+}
+
+/// Holidays falling between two dates, keyed by date. Recurring holidays match on
+/// month and day in every year of the range.
+pub fn holidays_between(conn: &Connection, start: NaiveDate, end: NaiveDate) -> Result<HashMap<NaiveDate, String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT date, name, is_recurring FROM holidays")
+        .map_err(|e| e.to_string())?;
+    let rows: Vec<(String, String, i64)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut holidays = HashMap::new();
+    for (date, name, is_recurring) in rows {
+        let Ok(date) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") else {
+            continue;
+        };
+        if is_recurring != 0 {
+            for year in start.year()..=end.year() {
+                if let Some(d) = date.with_year(year) {
+                    if d >= start && d <= end {
+                        holidays.entry(d).or_insert_with(|| name.clone());
+                    }
+                }
+            }
+        } else if date >= start && date <= end {
+            holidays.insert(date, name);
+        }
+    }
+    Ok(holidays)
+}
+
+/// Validate a holiday mode parameter, defaulting to ignoring holidays
+pub fn parse_holiday_mode(mode: Option<&str>) -> Result<&'static str, String> {
+    match mode.unwrap_or(HOLIDAY_MODE_IGNORE) {
+        HOLIDAY_MODE_IGNORE => Ok(HOLIDAY_MODE_IGNORE),
+        HOLIDAY_MODE_SKIP => Ok(HOLIDAY_MODE_SKIP),
+        HOLIDAY_MODE_SHIFT => Ok(HOLIDAY_MODE_SHIFT),
+        other => Err(format!(
+            "Invalid holiday mode '{}'. Use 'ignore', 'skip' or 'shift'",
+            other
+        )),
+    }
+}
+
+/// Where an entry for `date` should go under the given holiday mode; `None` means drop it
+pub fn apply_holiday_mode(conn: &Connection, date: NaiveDate, mode: &str) -> Result<Option<NaiveDate>, String> {
+    if mode == HOLIDAY_MODE_IGNORE {
+        return Ok(Some(date));
+    }
+    // A year ahead is plenty to find the next working day
+    let holidays = holidays_between(conn, date, date + chrono::Duration::days(366))?;
+    if !holidays.contains_key(&date) {
+        return Ok(Some(date));
+    }
+    if mode == HOLIDAY_MODE_SKIP {
+        return Ok(None);
+    }
+    let mut next = date;
+    while holidays.contains_key(&next) {
+        next += chrono::Duration::days(1);
+    }
+    Ok(Some(next))
+}
+
+fn validate_date(date: &str) -> Result<(), String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|_| ())
+        .map_err(|_| format!("Invalid date '{}'. Use YYYY-MM-DD", date))
+}
+
+fn fetch_holiday(conn: &Connection, id: i64) -> Result<Holiday, String> {
+    conn.query_row("SELECT * FROM holidays WHERE id = ?1", params![id], Holiday::from_row)
+        .map_err(|_| "Holiday not found".to_string())
+}
+
+/// Get holidays, optionally limited to one year (recurring holidays are always included)
+#[tauri::command]
+pub fn get_holidays(token: String, year: Option<i32>, db: State<'_, Database>) -> Result<Vec<Holiday>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM holidays
+             WHERE ?1 IS NULL OR is_recurring = 1 OR substr(date, 1, 4) = printf('%04d', ?1)
+             ORDER BY substr(date, 6, 5), date",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let holidays = stmt
+        .query_map(params![year], Holiday::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(holidays)
+}
+
+/// Create a holiday (admin only)
+#[tauri::command]
+pub fn create_holiday(token: String, input: CreateHolidayInput, db: State<'_, Database>) -> Result<Holiday, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "holidays.manage")?;
+
+    validate_date(&input.date)?;
+    if input.name.trim().is_empty() {
+        return Err(AppError::invalid_field("name", "holiday name is required"));
+    }
+
+    conn.execute(
+        "INSERT INTO holidays (date, name, is_recurring, created_by) VALUES (?1, ?2, ?3, ?4)",
+        params![input.date, input.name.trim(), input.is_recurring.unwrap_or(false), user.id],
+    )
+    .map_err(|e| format!("Failed to create holiday: {}", e))?;
+
+    Ok(fetch_holiday(&conn, conn.last_insert_rowid())?)
+}
+
+/// Update a holiday (admin only)
+#[tauri::command]
+pub fn update_holiday(
+    token: String,
+    id: i64,
+    input: UpdateHolidayInput,
+    db: State<'_, Database>,
+) -> Result<Holiday, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "holidays.manage")?;
+
+    let mut updates = Vec::new();
+    let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(date) = &input.date {
+        validate_date(date)?;
+        updates.push("date = ?");
+        values.push(Box::new(date.clone()));
+    }
+    if let Some(name) = &input.name {
+        if name.trim().is_empty() {
+            return Err(AppError::invalid_field("name", "holiday name is required"));
+        }
+        updates.push("name = ?");
+        values.push(Box::new(name.trim().to_string()));
+    }
+    if let Some(is_recurring) = input.is_recurring {
+        updates.push("is_recurring = ?");
+        values.push(Box::new(is_recurring));
+    }
+
+    if !updates.is_empty() {
+        let query = format!("UPDATE holidays SET {} WHERE id = ?", updates.join(", "));
+        values.push(Box::new(id));
+        let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+        conn.execute(&query, params.as_slice())
+            .map_err(|e| format!("Failed to update holiday: {}", e))?;
+    }
+
+    Ok(fetch_holiday(&conn, id)?)
+}
+
+/// Delete a holiday (admin only)
+#[tauri::command]
+pub fn delete_holiday(token: String, id: i64, db: State<'_, Database>) -> Result<(), AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "holidays.manage")?;
+
+    let affected = conn
+        .execute("DELETE FROM holidays WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    if affected == 0 {
+        return Err(AppError::NotFound("Holiday not found".to_string()));
+    }
+    Ok(())
+}
@@ -1,11 +1,15 @@
+use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use tauri::{AppHandle, State};
 
+use crate::commands::machine_status::record_status_change;
+use crate::commands::projects::recalculate_hours;
 use crate::db::Database;
-use crate::utils::validate_session;
+use crate::error::AppError;
+use crate::utils::{audit_json, emit_machine_status, record_audit, require_admin, validate_session};
 
 /// Represents a cascade effect when deleting a record
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CascadeEffect {
     pub table: String,
     pub label: String,
@@ -13,23 +17,65 @@ pub struct CascadeEffect {
 }
 
 /// Represents the full impact of deleting a record
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeleteImpact {
     pub item_type: String,
     pub item_name: String,
     pub cascade_effects: Vec<CascadeEffect>,
 }
 
-/// Check the impact of deleting a machine
-#[tauri::command]
-pub fn check_machine_delete_impact(
-    token: String,
-    machine_id: i64,
-    db: State<'_, Database>,
-) -> Result<DeleteImpact, String> {
-    let conn = db.conn.lock();
-    let _user = validate_session(&conn, &token)?;
+/// Check that a delete takes with it exactly what the user was shown.
+///
+/// Delete commands take back the `cascade_effects` from the matching
+/// `check_*_delete_impact` call; if the data has changed since, or the
+/// effects were never reviewed, the delete is refused so that a stale
+/// screen cannot remove more than the user agreed to.
+pub(crate) fn confirm_delete_impact(impact: &DeleteImpact, expected: Option<&[CascadeEffect]>) -> Result<(), AppError> {
+    let expected = expected.unwrap_or_default();
+    let count_of = |effects: &[CascadeEffect], table: &str| {
+        effects.iter().filter(|e| e.table == table).map(|e| e.count).sum::<i64>()
+    };
+
+    let mut changes = Vec::new();
+    for effect in &impact.cascade_effects {
+        let seen = count_of(expected, &effect.table);
+        if seen != effect.count {
+            changes.push(format!("{}: {} now, {} when reviewed", effect.label, effect.count, seen));
+        }
+    }
+    for effect in expected {
+        if effect.count > 0 && count_of(&impact.cascade_effects, &effect.table) == 0 {
+            changes.push(format!("{}: 0 now, {} when reviewed", effect.label, effect.count));
+        }
+    }
 
+    if changes.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::Conflict(format!(
+            "What this delete would remove has changed since it was reviewed ({}). Review the delete again",
+            changes.join("; ")
+        )))
+    }
+}
+
+/// Add an effect for the rows `sql` counts for `id`, when there are any
+fn push_effect(
+    conn: &Connection,
+    cascade_effects: &mut Vec<CascadeEffect>,
+    table: &str,
+    label: &str,
+    sql: &str,
+    id: i64,
+) {
+    let count: i64 = conn.query_row(sql, [id], |row| row.get(0)).unwrap_or(0);
+    if count > 0 {
+        cascade_effects.push(CascadeEffect { table: table.to_string(), label: label.to_string(), count });
+    }
+}
+
+/// What deleting a machine takes with it
+pub(crate) fn machine_delete_impact(conn: &Connection, machine_id: i64) -> Result<DeleteImpact, String> {
     // Get machine name
     let machine_name: String = conn
         .query_row(
@@ -105,6 +151,19 @@ pub fn check_machine_delete_impact(
         });
     }
 
+    // Records kept with the machine, removed by ON DELETE CASCADE
+    for (table, label) in [
+        ("machine_documents", "Documents (the stored files are removed too)"),
+        ("downtime_log", "Downtime log entries"),
+        ("checklist_templates", "Checklist items"),
+        ("checklist_completions", "Checklist completions"),
+        ("shift_logs", "Shift handover logs"),
+        ("machine_status_history", "Status history entries"),
+    ] {
+        let sql = format!("SELECT COUNT(*) FROM {} WHERE machine_id = ?1", table);
+        push_effect(conn, &mut cascade_effects, table, label, &sql, machine_id);
+    }
+
     Ok(DeleteImpact {
         item_type: "Machine".to_string(),
         item_name: machine_name,
@@ -112,16 +171,20 @@ pub fn check_machine_delete_impact(
     })
 }
 
-/// Check the impact of deleting a project
+/// Check the impact of deleting a machine
 #[tauri::command]
-pub fn check_project_delete_impact(
+pub fn check_machine_delete_impact(
     token: String,
-    project_id: i64,
+    machine_id: i64,
     db: State<'_, Database>,
-) -> Result<DeleteImpact, String> {
+) -> Result<DeleteImpact, AppError> {
     let conn = db.conn.lock();
     let _user = validate_session(&conn, &token)?;
+    Ok(machine_delete_impact(&conn, machine_id)?)
+}
 
+/// What deleting a project takes with it
+pub(crate) fn project_delete_impact(conn: &Connection, project_id: i64) -> Result<DeleteImpact, String> {
     // Get project name
     let project_name: String = conn
         .query_row(
@@ -181,6 +244,95 @@ pub fn check_project_delete_impact(
         });
     }
 
+    // Count milestones
+    let milestone_count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM project_milestones WHERE project_id = ?1",
+            [project_id],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+    if milestone_count > 0 {
+        cascade_effects.push(CascadeEffect {
+            table: "project_milestones".to_string(),
+            label: "Milestones".to_string(),
+            count: milestone_count,
+        });
+    }
+
+    // Count tasks
+    let task_count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM project_tasks WHERE project_id = ?1",
+            [project_id],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+    if task_count > 0 {
+        cascade_effects.push(CascadeEffect {
+            table: "project_tasks".to_string(),
+            label: "Tasks".to_string(),
+            count: task_count,
+        });
+    }
+
+    // Count recorded costs
+    let cost_count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM project_costs WHERE project_id = ?1",
+            [project_id],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+    if cost_count > 0 {
+        cascade_effects.push(CascadeEffect {
+            table: "project_costs".to_string(),
+            label: "Recorded costs".to_string(),
+            count: cost_count,
+        });
+    }
+
+    // Count hour log entries
+    let hour_log_count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM project_hour_logs WHERE project_id = ?1",
+            [project_id],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+    if hour_log_count > 0 {
+        cascade_effects.push(CascadeEffect {
+            table: "project_hour_logs".to_string(),
+            label: "Hour log entries".to_string(),
+            count: hour_log_count,
+        });
+    }
+
+    // Count notes
+    let note_count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM project_notes WHERE project_id = ?1",
+            [project_id],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+    if note_count > 0 {
+        cascade_effects.push(CascadeEffect {
+            table: "project_notes".to_string(),
+            label: "Notes".to_string(),
+            count: note_count,
+        });
+    }
+
+    push_effect(
+        conn,
+        &mut cascade_effects,
+        "project_materials",
+        "Materials",
+        "SELECT COUNT(*) FROM project_materials WHERE project_id = ?1",
+        project_id,
+    );
+
     // Count alerts
     let alert_count: i64 = conn
         .query_row(
@@ -204,13 +356,25 @@ pub fn check_project_delete_impact(
     })
 }
 
+/// Check the impact of deleting a project
+#[tauri::command]
+pub fn check_project_delete_impact(
+    token: String,
+    project_id: i64,
+    db: State<'_, Database>,
+) -> Result<DeleteImpact, AppError> {
+    let conn = db.conn.lock();
+    let _user = validate_session(&conn, &token)?;
+    Ok(project_delete_impact(&conn, project_id)?)
+}
+
 /// Check the impact of deleting a client
 #[tauri::command]
 pub fn check_client_delete_impact(
     token: String,
     client_id: i64,
     db: State<'_, Database>,
-) -> Result<DeleteImpact, String> {
+) -> Result<DeleteImpact, AppError> {
     let conn = db.conn.lock();
     let _user = validate_session(&conn, &token)?;
 
@@ -236,11 +400,27 @@ pub fn check_client_delete_impact(
     if project_count > 0 {
         cascade_effects.push(CascadeEffect {
             table: "projects".to_string(),
-            label: "Projects (will be unlinked)".to_string(),
+            label: "Projects (will be unlinked; archive the client instead to keep them linked)".to_string(),
             count: project_count,
         });
     }
 
+    // Count contacts
+    let contact_count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM client_contacts WHERE client_id = ?1",
+            [client_id],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+    if contact_count > 0 {
+        cascade_effects.push(CascadeEffect {
+            table: "client_contacts".to_string(),
+            label: "Contacts".to_string(),
+            count: contact_count,
+        });
+    }
+
     Ok(DeleteImpact {
         item_type: "Client".to_string(),
         item_name: client_name,
@@ -248,16 +428,8 @@ pub fn check_client_delete_impact(
     })
 }
 
-/// Check the impact of deleting a user
-#[tauri::command]
-pub fn check_user_delete_impact(
-    token: String,
-    user_id: i64,
-    db: State<'_, Database>,
-) -> Result<DeleteImpact, String> {
-    let conn = db.conn.lock();
-    let _user = validate_session(&conn, &token)?;
-
+/// What deleting a user takes with it
+pub(crate) fn user_delete_impact(conn: &Connection, user_id: i64) -> Result<DeleteImpact, String> {
     // Get username
     let username: String = conn
         .query_row(
@@ -333,9 +505,408 @@ pub fn check_user_delete_impact(
         });
     }
 
+    // Count API tokens
+    let api_token_count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM api_tokens WHERE user_id = ?1 AND revoked = 0",
+            [user_id],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+    if api_token_count > 0 {
+        cascade_effects.push(CascadeEffect {
+            table: "api_tokens".to_string(),
+            label: "API tokens".to_string(),
+            count: api_token_count,
+        });
+    }
+
+    // The user's own records, removed by ON DELETE CASCADE
+    for (table, column, label) in [
+        ("checklist_completions", "checked_by", "Checklist completions"),
+        ("role_permissions", "user_id", "Permission overrides"),
+        ("user_dashboard_layouts", "user_id", "Dashboard layout"),
+        ("user_notification_settings", "user_id", "Notification settings"),
+    ] {
+        let sql = format!("SELECT COUNT(*) FROM {} WHERE {} = ?1", table, column);
+        push_effect(conn, &mut cascade_effects, table, label, &sql, user_id);
+    }
+
     Ok(DeleteImpact {
         item_type: "User".to_string(),
         item_name: username,
         cascade_effects,
     })
 }
+
+/// Check the impact of deleting a user
+#[tauri::command]
+pub fn check_user_delete_impact(
+    token: String,
+    user_id: i64,
+    db: State<'_, Database>,
+) -> Result<DeleteImpact, AppError> {
+    let conn = db.conn.lock();
+    let _user = validate_session(&conn, &token)?;
+    Ok(user_delete_impact(&conn, user_id)?)
+}
+
+pub const INTEGRITY_ORPHANED_ROWS: &str = "orphaned_rows";
+pub const INTEGRITY_HOUR_MISMATCHES: &str = "hour_mismatches";
+pub const INTEGRITY_MAINTENANCE_STATUS: &str = "maintenance_status";
+pub const INTEGRITY_ORPHANED_SESSIONS: &str = "orphaned_sessions";
+
+/// Every category, in report order, with its label
+const INTEGRITY_CATEGORIES: [(&str, &str); 4] = [
+    (INTEGRITY_ORPHANED_ROWS, "Rows pointing at deleted records"),
+    (INTEGRITY_HOUR_MISMATCHES, "Project hours that differ from their schedule entries and hour log"),
+    (INTEGRITY_MAINTENANCE_STATUS, "Machines in maintenance with no open maintenance record"),
+    (INTEGRITY_ORPHANED_SESSIONS, "Sessions of deleted users"),
+];
+
+/// Project hours within this of the recomputed total count as matching
+const HOUR_TOLERANCE: f64 = 0.01;
+
+/// One problem found by the integrity check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityIssue {
+    pub table: String,
+    pub record_id: i64,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityCategory {
+    pub category: String,
+    pub label: String,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub total_issues: i64,
+    pub categories: Vec<IntegrityCategory>,
+}
+
+/// What fix_integrity_issues changed in one category
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityFix {
+    pub category: String,
+    pub fixed: i64,
+    pub changes: Vec<String>,
+}
+
+/// A row whose foreign key points at a missing record, with the repair its
+/// ON DELETE rule would have made
+struct OrphanedRow {
+    table: String,
+    row_id: i64,
+    column: String,
+    parent_table: String,
+    parent_id: Option<i64>,
+    on_delete: String,
+}
+
+impl OrphanedRow {
+    fn description(&self) -> String {
+        let repair = match self.on_delete.as_str() {
+            "CASCADE" => "delete the row",
+            "SET NULL" => "clear the link",
+            _ => "needs fixing by hand",
+        };
+        format!(
+            "{} #{} refers to {} #{} through {}, which no longer exists ({})",
+            self.table,
+            self.row_id,
+            self.parent_table,
+            self.parent_id.map(|id| id.to_string()).unwrap_or_default(),
+            self.column,
+            repair
+        )
+    }
+}
+
+/// Rows breaking a foreign key, found with `PRAGMA foreign_key_check`. These
+/// are left over from before foreign keys were enforced.
+fn find_orphaned_rows(conn: &Connection) -> Result<Vec<OrphanedRow>, String> {
+    let mut stmt = conn.prepare("PRAGMA foreign_key_check").map_err(|e| e.to_string())?;
+    let violations: Vec<(String, Option<i64>, String, i64)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut orphans = Vec::new();
+    for (table, row_id, parent_table, fk_id) in violations {
+        let Some(row_id) = row_id else {
+            continue;
+        };
+        let (column, on_delete): (String, String) = conn
+            .query_row(
+                &format!("SELECT \"from\", on_delete FROM pragma_foreign_key_list('{}') WHERE id = ?1", table),
+                [fk_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| e.to_string())?;
+        let parent_id: Option<i64> = conn
+            .query_row(&format!("SELECT {} FROM {} WHERE rowid = ?1", column, table), [row_id], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        orphans.push(OrphanedRow { table, row_id, column, parent_table, parent_id, on_delete });
+    }
+    Ok(orphans)
+}
+
+/// Projects whose actual hours differ from their schedule entries plus hour
+/// log, as (id, name, stored, expected)
+fn find_hour_mismatches(conn: &Connection) -> Result<Vec<(i64, String, f64, f64)>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, COALESCE(actual_hours, 0), expected FROM (
+                SELECT p.id, p.name, p.actual_hours,
+                       (SELECT COALESCE(SUM(actual_hours), 0) FROM schedules WHERE project_id = p.id)
+                       + (SELECT COALESCE(SUM(hours), 0) FROM project_hour_logs WHERE project_id = p.id) AS expected
+                FROM projects p
+             )
+             WHERE ABS(COALESCE(actual_hours, 0) - expected) > ?1
+             ORDER BY name",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mismatches = stmt
+        .query_map([HOUR_TOLERANCE], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(mismatches)
+}
+
+/// Machines marked under maintenance with nothing scheduled or in progress
+fn find_stuck_maintenance(conn: &Connection) -> Result<Vec<(i64, String)>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT m.id, m.name FROM machines m
+             WHERE m.status = 'maintenance'
+               AND NOT EXISTS (
+                   SELECT 1 FROM maintenance mt
+                   WHERE mt.machine_id = m.id AND mt.status IN ('scheduled', 'in-progress')
+               )
+             ORDER BY m.name",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let machines = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(machines)
+}
+
+fn integrity_issues(conn: &Connection, category: &str) -> Result<Vec<IntegrityIssue>, String> {
+    let issues = match category {
+        INTEGRITY_ORPHANED_ROWS | INTEGRITY_ORPHANED_SESSIONS => find_orphaned_rows(conn)?
+            .into_iter()
+            .filter(|orphan| (orphan.table == "sessions") == (category == INTEGRITY_ORPHANED_SESSIONS))
+            .map(|orphan| IntegrityIssue {
+                description: orphan.description(),
+                table: orphan.table,
+                record_id: orphan.row_id,
+            })
+            .collect(),
+        INTEGRITY_HOUR_MISMATCHES => find_hour_mismatches(conn)?
+            .into_iter()
+            .map(|(id, name, stored, expected)| IntegrityIssue {
+                table: "projects".to_string(),
+                record_id: id,
+                description: format!(
+                    "{} has {:.2} actual hours recorded, but its work adds up to {:.2}",
+                    name, stored, expected
+                ),
+            })
+            .collect(),
+        INTEGRITY_MAINTENANCE_STATUS => find_stuck_maintenance(conn)?
+            .into_iter()
+            .map(|(id, name)| IntegrityIssue {
+                table: "machines".to_string(),
+                record_id: id,
+                description: format!("{} is in maintenance but has no scheduled or in-progress maintenance", name),
+            })
+            .collect(),
+        other => return Err(format!("Validation error: unknown integrity category '{}'", other)),
+    };
+    Ok(issues)
+}
+
+/// Repair one category, returning a line per change
+/// Machines moved out of maintenance are added to `idled` so their new status
+/// can be announced once the repair commits
+fn repair_category(
+    conn: &Connection,
+    category: &str,
+    user_id: i64,
+    idled: &mut Vec<i64>,
+) -> Result<Vec<String>, String> {
+    let mut changes = Vec::new();
+    match category {
+        INTEGRITY_ORPHANED_ROWS | INTEGRITY_ORPHANED_SESSIONS => {
+            for orphan in find_orphaned_rows(conn)?
+                .into_iter()
+                .filter(|orphan| (orphan.table == "sessions") == (category == INTEGRITY_ORPHANED_SESSIONS))
+            {
+                let sql = match orphan.on_delete.as_str() {
+                    "CASCADE" => format!("DELETE FROM {} WHERE rowid = ?1", orphan.table),
+                    "SET NULL" => format!("UPDATE {} SET {} = NULL WHERE rowid = ?1", orphan.table, orphan.column),
+                    _ => continue,
+                };
+                // An earlier repair may already have removed this row through a cascade
+                let affected = conn
+                    .execute(&sql, [orphan.row_id])
+                    .map_err(|e| format!("Failed to repair {} #{}: {}", orphan.table, orphan.row_id, e))?;
+                if affected > 0 {
+                    changes.push(orphan.description());
+                }
+            }
+        }
+        INTEGRITY_HOUR_MISMATCHES => {
+            for (id, name, stored, expected) in find_hour_mismatches(conn)? {
+                recalculate_hours(conn, id)?;
+                changes.push(format!("{}: actual hours {:.2} -> {:.2}", name, stored, expected));
+            }
+        }
+        INTEGRITY_MAINTENANCE_STATUS => {
+            for (id, name) in find_stuck_maintenance(conn)? {
+                conn.execute(
+                    "UPDATE machines SET status = 'idle', updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+                    [id],
+                )
+                .map_err(|e| format!("Failed to update machine status: {}", e))?;
+                record_status_change(
+                    conn,
+                    id,
+                    "maintenance",
+                    "idle",
+                    Some("Integrity check: no open maintenance record"),
+                    Some(user_id),
+                )?;
+                idled.push(id);
+                changes.push(format!("{}: maintenance -> idle", name));
+            }
+        }
+        other => return Err(format!("Validation error: unknown integrity category '{}'", other)),
+    }
+    Ok(changes)
+}
+
+/// Look for data that has drifted out of line (Admin only): rows pointing
+/// at deleted records, project hours that no longer add up, machines left
+/// in maintenance, and sessions of deleted users. Nothing is changed.
+#[tauri::command]
+pub fn run_integrity_check(token: String, db: State<'_, Database>) -> Result<IntegrityReport, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_admin(&conn, &user)?;
+
+    let mut categories = Vec::new();
+    for (category, label) in INTEGRITY_CATEGORIES {
+        categories.push(IntegrityCategory {
+            category: category.to_string(),
+            label: label.to_string(),
+            issues: integrity_issues(&conn, category)?,
+        });
+    }
+    let total_issues = categories.iter().map(|c| c.issues.len() as i64).sum();
+
+    Ok(IntegrityReport { total_issues, categories })
+}
+
+/// Repair the selected categories of `run_integrity_check` together in one
+/// transaction (Admin only). Orphaned rows get what their foreign key's ON
+/// DELETE rule would have done: deleted, or the link cleared.
+#[tauri::command]
+pub fn fix_integrity_issues(
+    token: String,
+    categories: Vec<String>,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<Vec<IntegrityFix>, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_admin(&conn, &user)?;
+
+    if categories.is_empty() {
+        return Err(AppError::invalid_field("categories", "choose at least one category to fix"));
+    }
+    for category in &categories {
+        if !INTEGRITY_CATEGORIES.iter().any(|(known, _)| known == category) {
+            return Err(AppError::invalid_field("categories", format!("unknown integrity category '{}'", category)));
+        }
+    }
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let mut fixes = Vec::new();
+    let mut idled = Vec::new();
+    // Report order, so orphans are cleared before hours are recomputed
+    for (category, _) in INTEGRITY_CATEGORIES.iter().filter(|(c, _)| categories.iter().any(|s| s == c)) {
+        let changes = repair_category(&tx, category, user.id, &mut idled)?;
+        fixes.push(IntegrityFix { category: category.to_string(), fixed: changes.len() as i64, changes });
+    }
+    record_audit(&tx, &user, "integrity_fix", "integrity", None, None, audit_json(&fixes).as_deref());
+    tx.commit().map_err(|e| e.to_string())?;
+    for id in idled {
+        emit_machine_status(&app, id, "idle");
+    }
+
+    Ok(fixes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_pending_migrations;
+
+    fn tables(impact: &DeleteImpact) -> Vec<&str> {
+        impact.cascade_effects.iter().map(|e| e.table.as_str()).collect()
+    }
+
+    #[test]
+    fn impact_lists_every_cascade() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_pending_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO users (id, username, password_hash, role) VALUES (1, 'op', 'x', 'Operator');
+             INSERT INTO machines (id, name, model, status) VALUES (1, 'VMC-1', 'V1', 'active');
+             INSERT INTO projects (id, name, status) VALUES (1, 'Housings', 'active');
+             INSERT INTO machine_documents (machine_id, file_name, stored_path, mime_type)
+                 VALUES (1, 'a.pdf', 'a.pdf', 'application/pdf');
+             INSERT INTO downtime_log (machine_id, start_time, reason_category) VALUES (1, '2026-01-05 08:00', 'idle');
+             INSERT INTO checklist_templates (id, machine_id, checklist_item) VALUES (1, 1, 'Coolant');
+             INSERT INTO checklist_completions (machine_id, template_id, checked_by, check_date)
+                 VALUES (1, 1, 1, '2026-01-05');
+             INSERT INTO shift_logs (machine_id, shift_date, notes) VALUES (1, '2026-01-05', 'ok');
+             INSERT INTO machine_status_history (machine_id, new_status) VALUES (1, 'active');
+             INSERT INTO project_materials (project_id, name) VALUES (1, 'Billet');
+             INSERT INTO role_permissions (user_id, permission, allowed) VALUES (1, 'machines.edit', 1);
+             INSERT INTO user_dashboard_layouts (user_id, layout) VALUES (1, '[]');
+             INSERT INTO user_notification_settings (user_id) VALUES (1);",
+        )
+        .unwrap();
+
+        let machine = machine_delete_impact(&conn, 1).unwrap();
+        assert_eq!(
+            tables(&machine),
+            [
+                "machine_documents",
+                "downtime_log",
+                "checklist_templates",
+                "checklist_completions",
+                "shift_logs",
+                "machine_status_history"
+            ]
+        );
+        assert_eq!(tables(&project_delete_impact(&conn, 1).unwrap()), ["project_materials"]);
+        assert_eq!(
+            tables(&user_delete_impact(&conn, 1).unwrap()),
+            ["checklist_completions", "role_permissions", "user_dashboard_layouts", "user_notification_settings"]
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::utils::{record_audit, require_permission, require_view_permission, validate_session};
+
+/// A named place on the shop floor, such as a bay.
+///
+/// Names are unique ignoring case. Machines keep their free-text `location`
+/// in step with the name of their managed location.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Location {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub machine_count: i64,
+    pub created_at: String,
+}
+
+impl Location {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            name: row.get("name")?,
+            description: row.get("description")?,
+            machine_count: row.get("machine_count")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateLocationInput {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateLocationInput {
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+const LOCATION_SELECT: &str = "SELECT l.*, (SELECT COUNT(*) FROM machines m WHERE m.location_id = l.id) as machine_count
+     FROM locations l";
+
+/// Look up a location by id
+pub fn fetch_location(conn: &Connection, id: i64) -> Result<Location, String> {
+    conn.query_row(&format!("{} WHERE l.id = ?1", LOCATION_SELECT), params![id], Location::from_row)
+        .map_err(|_| "Location not found".to_string())
+}
+
+/// Id of the location with this name, ignoring case and surrounding spaces
+pub(crate) fn find_location_id(conn: &Connection, name: &str) -> Result<Option<i64>, String> {
+    conn.query_row("SELECT id FROM locations WHERE name = ?1", params![name.trim()], |row| row.get(0))
+        .optional()
+        .map_err(|e| e.to_string())
+}
+
+fn map_name_error(e: rusqlite::Error, action: &str) -> String {
+    if e.to_string().contains("UNIQUE constraint failed") {
+        "Location name already exists".to_string()
+    } else {
+        format!("Failed to {} location: {}", action, e)
+    }
+}
+
+/// Get all locations with their machine counts
+#[tauri::command]
+pub fn get_locations(token: String, db: State<'_, Database>) -> Result<Vec<Location>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let mut stmt = conn
+        .prepare(&format!("{} ORDER BY l.name", LOCATION_SELECT))
+        .map_err(|e| e.to_string())?;
+
+    let locations = stmt
+        .query_map([], Location::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(locations)
+}
+
+/// Create a location (Admin only)
+#[tauri::command]
+pub fn create_location(
+    token: String,
+    input: CreateLocationInput,
+    db: State<'_, Database>,
+) -> Result<Location, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "locations.manage")?;
+
+    if input.name.trim().is_empty() {
+        return Err(AppError::invalid_field("name", "location name is required"));
+    }
+
+    conn.execute(
+        "INSERT INTO locations (name, description) VALUES (?1, ?2)",
+        params![input.name.trim(), input.description],
+    )
+    .map_err(|e| map_name_error(e, "create"))?;
+
+    Ok(fetch_location(&conn, conn.last_insert_rowid())?)
+}
+
+/// Rename or describe a location (Admin only). Machines at the location pick
+/// up the new name.
+#[tauri::command]
+pub fn update_location(
+    token: String,
+    id: i64,
+    input: UpdateLocationInput,
+    db: State<'_, Database>,
+) -> Result<Location, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "locations.manage")?;
+
+    fetch_location(&conn, id)?;
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    if let Some(name) = &input.name {
+        if name.trim().is_empty() {
+            return Err(AppError::invalid_field("name", "location name is required"));
+        }
+        tx.execute("UPDATE locations SET name = ?1 WHERE id = ?2", params![name.trim(), id])
+            .map_err(|e| map_name_error(e, "update"))?;
+        tx.execute(
+            "UPDATE machines SET location = ?1, updated_at = CURRENT_TIMESTAMP WHERE location_id = ?2",
+            params![name.trim(), id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    if let Some(description) = &input.description {
+        tx.execute("UPDATE locations SET description = ?1 WHERE id = ?2", params![description, id])
+            .map_err(|e| map_name_error(e, "update"))?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(fetch_location(&conn, id)?)
+}
+
+/// Delete a location (Admin only).
+///
+/// A location with machines can only be deleted by naming another location
+/// to move them to, which is also how duplicate locations are merged.
+#[tauri::command]
+pub fn delete_location(
+    token: String,
+    id: i64,
+    reassign_to: Option<i64>,
+    db: State<'_, Database>,
+) -> Result<(), AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "locations.manage")?;
+
+    let location = fetch_location(&conn, id)?;
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    match reassign_to {
+        Some(target_id) if target_id == id => {
+            return Err(AppError::validation("cannot reassign machines to the location being deleted"));
+        }
+        Some(target_id) => {
+            let target = fetch_location(&conn, target_id)?;
+            tx.execute(
+                "UPDATE machines SET location_id = ?1, location = ?2, updated_at = CURRENT_TIMESTAMP
+                 WHERE location_id = ?3",
+                params![target.id, target.name, id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        None if location.machine_count > 0 => {
+            return Err(AppError::Conflict(format!(
+                "Location '{}' has {} machine(s). Reassign them to another location first",
+                location.name, location.machine_count
+            )));
+        }
+        None => {}
+    }
+    tx.execute("DELETE FROM locations WHERE id = ?1", params![id])
+        .map_err(|e| format!("Failed to delete location: {}", e))?;
+    record_audit(
+        &tx,
+        &user,
+        "delete",
+        "locations",
+        Some(id),
+        Some(&location.name),
+        reassign_to.map(|target| serde_json::json!({ "reassigned_to": target }).to_string()).as_deref(),
+    );
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
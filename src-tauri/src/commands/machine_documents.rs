@@ -0,0 +1,236 @@
+use std::path::{Path, PathBuf};
+
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::commands::machines::ensure_machine_not_archived;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::utils::{record_audit, require_permission, require_view_permission, validate_session};
+
+/// Folder beside the database file holding machine documents
+const DOCUMENTS_DIR: &str = "machine_documents";
+
+/// Largest file accepted, in bytes
+const MAX_DOCUMENT_BYTES: u64 = 50 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MachineDocument {
+    pub id: i64,
+    pub machine_id: i64,
+    pub file_name: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub uploaded_by: Option<i64>,
+    pub uploaded_by_name: Option<String>,
+    pub uploaded_at: String,
+}
+
+impl MachineDocument {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            machine_id: row.get("machine_id")?,
+            file_name: row.get("file_name")?,
+            mime_type: row.get("mime_type")?,
+            size_bytes: row.get("size_bytes")?,
+            uploaded_by: row.get("uploaded_by")?,
+            uploaded_by_name: row.get("uploaded_by_name")?,
+            uploaded_at: row.get("uploaded_at")?,
+        })
+    }
+}
+
+/// A document's bytes, or where they were copied to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MachineDocumentContent {
+    pub document: MachineDocument,
+    /// File contents when no target path was given
+    pub bytes: Option<Vec<u8>>,
+    pub saved_to: Option<String>,
+}
+
+const DOCUMENT_SELECT: &str = "SELECT d.*, u.full_name as uploaded_by_name
+     FROM machine_documents d
+     LEFT JOIN users u ON d.uploaded_by = u.id";
+
+fn fetch_document(conn: &Connection, id: i64) -> Result<(MachineDocument, String), String> {
+    conn.query_row(&format!("{} WHERE d.id = ?1", DOCUMENT_SELECT), [id], |row| {
+        Ok((MachineDocument::from_row(row)?, row.get("stored_path")?))
+    })
+    .map_err(|_| "Document not found".to_string())
+}
+
+/// Folder stored paths are relative to: the database's own folder, so the
+/// files travel with a workspace that lives on a share or is copied elsewhere
+fn documents_base(db: &Database) -> PathBuf {
+    db.path().parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+/// Where a stored document lives. Files uploaded before documents moved next
+/// to the database are still found under the app data dir.
+pub(crate) fn document_file(app: &AppHandle, db: &Database, stored_path: &str) -> PathBuf {
+    let path = documents_base(db).join(stored_path);
+    if !path.exists() {
+        if let Ok(legacy) = app.path().app_data_dir().map(|dir| dir.join(stored_path)) {
+            if legacy.exists() {
+                return legacy;
+            }
+        }
+    }
+    path
+}
+
+/// MIME type from the file extension, falling back to a generic binary type
+fn mime_type_for(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "pdf" => "application/pdf",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xls" => "application/vnd.ms-excel",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "zip" => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Copy a file into the managed documents folder and attach it to a machine
+#[tauri::command]
+pub fn add_machine_document(
+    token: String,
+    machine_id: i64,
+    file_path: String,
+    db: State<'_, Database>,
+) -> Result<MachineDocument, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "machines.edit")?;
+    ensure_machine_not_archived(&conn, machine_id)?;
+
+    let source = PathBuf::from(&file_path);
+    let metadata = std::fs::metadata(&source).map_err(|e| format!("Cannot read '{}': {}", file_path, e))?;
+    if !metadata.is_file() {
+        return Err(AppError::validation(format!("'{}' is not a file", file_path)));
+    }
+    if metadata.len() > MAX_DOCUMENT_BYTES {
+        return Err(AppError::validation(format!("documents are limited to {} MB", MAX_DOCUMENT_BYTES / (1024 * 1024))));
+    }
+    let file_name = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| "Invalid file name".to_string())?
+        .to_string();
+
+    // Stored under a unique name so two uploads of "manual.pdf" never collide
+    let relative = PathBuf::from(DOCUMENTS_DIR)
+        .join(machine_id.to_string())
+        .join(format!("{}_{}", uuid::Uuid::new_v4(), file_name));
+    let target = documents_base(&db).join(&relative);
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create documents folder: {}", e))?;
+    }
+    std::fs::copy(&source, &target).map_err(|e| format!("Failed to copy document: {}", e))?;
+
+    let stored_path = relative.to_string_lossy().to_string();
+    if let Err(e) = conn.execute(
+        "INSERT INTO machine_documents (machine_id, file_name, stored_path, mime_type, size_bytes, uploaded_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![machine_id, file_name, stored_path, mime_type_for(&source), metadata.len() as i64, user.id],
+    ) {
+        std::fs::remove_file(&target).ok();
+        return Err(AppError::Database(format!("Failed to save document: {}", e)));
+    }
+
+    let id = conn.last_insert_rowid();
+    record_audit(&conn, &user, "add_document", "machine_documents", Some(id), None, Some(&file_name));
+    Ok(fetch_document(&conn, id).map(|(document, _)| document)?)
+}
+
+/// List a machine's documents, newest first
+#[tauri::command]
+pub fn list_machine_documents(
+    token: String,
+    machine_id: i64,
+    db: State<'_, Database>,
+) -> Result<Vec<MachineDocument>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let mut stmt = conn
+        .prepare(&format!("{} WHERE d.machine_id = ?1 ORDER BY d.uploaded_at DESC, d.id DESC", DOCUMENT_SELECT))
+        .map_err(|e| e.to_string())?;
+
+    let documents = stmt
+        .query_map([machine_id], MachineDocument::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(documents)
+}
+
+/// Read a document. With `target_path` the file is copied there instead of
+/// being returned as bytes.
+#[tauri::command]
+pub fn get_machine_document(
+    token: String,
+    id: i64,
+    target_path: Option<String>,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<MachineDocumentContent, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let (document, stored_path) = fetch_document(&conn, id)?;
+    let source = document_file(&app, &db, &stored_path);
+
+    match target_path {
+        Some(target) => {
+            std::fs::copy(&source, &target).map_err(|e| format!("Failed to save document: {}", e))?;
+            Ok(MachineDocumentContent { document, bytes: None, saved_to: Some(target) })
+        }
+        None => {
+            let bytes = std::fs::read(&source).map_err(|e| format!("Failed to read document: {}", e))?;
+            Ok(MachineDocumentContent { document, bytes: Some(bytes), saved_to: None })
+        }
+    }
+}
+
+/// Remove a document and its stored file
+#[tauri::command]
+pub fn delete_machine_document(
+    token: String,
+    id: i64,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<(), AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "machines.edit")?;
+
+    let (document, stored_path) = fetch_document(&conn, id)?;
+    conn.execute("DELETE FROM machine_documents WHERE id = ?1", [id])
+        .map_err(|e| format!("Failed to delete document: {}", e))?;
+    record_audit(&conn, &user, "delete_document", "machine_documents", Some(id), Some(&document.file_name), None);
+
+    // A file already missing from disk is not an error; the record is gone either way
+    let path = document_file(&app, &db, &stored_path);
+    if let Err(e) = std::fs::remove_file(&path) {
+        log::warn!("Could not remove document file {:?}: {}", path, e);
+    }
+    Ok(())
+}
@@ -0,0 +1,232 @@
+use std::collections::{HashMap, HashSet};
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::commands::machines::insert_machine;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{CreateMachineInput, Machine};
+use crate::utils::{
+    parse_csv, record_audit, require_permission, run_operation, validate_session, CsvRecord, OperationContext,
+    OperationOutcome,
+};
+
+/// Columns accepted in a machine CSV; `name` and `model` are required
+const MACHINE_COLUMNS: [&str; 21] = [
+    "name",
+    "model",
+    "serial_number",
+    "purchase_date",
+    "status",
+    "location",
+    "capacity",
+    "power_consumption",
+    "dimensions",
+    "weight",
+    "max_rpm",
+    "axis_travel",
+    "hourly_rate",
+    "power_kw",
+    "weight_kg",
+    "max_rpm_value",
+    "maintenance_interval_hours",
+    "site_id",
+    "warranty_expiry",
+    "amc_expiry",
+    "location_id",
+];
+
+/// A CSV line that could not be imported
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportLineError {
+    pub line: usize,
+    pub name: Option<String>,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MachineImportResult {
+    pub dry_run: bool,
+    pub total_rows: i64,
+    /// Rows that passed validation; on a real run these were inserted
+    pub valid_rows: i64,
+    /// Machines created; empty on a dry run
+    pub imported: Vec<Machine>,
+    pub errors: Vec<ImportLineError>,
+}
+
+/// Map header names to column positions, rejecting unknown or missing columns
+fn read_header(header: &CsvRecord) -> Result<HashMap<String, usize>, String> {
+    let mut columns = HashMap::new();
+    for (i, name) in header.fields.iter().enumerate() {
+        let name = name.trim().to_lowercase();
+        if !MACHINE_COLUMNS.contains(&name.as_str()) {
+            return Err(format!("Unknown column '{}'. Accepted columns: {}", name, MACHINE_COLUMNS.join(", ")));
+        }
+        if columns.insert(name.clone(), i).is_some() {
+            return Err(format!("Column '{}' appears more than once", name));
+        }
+    }
+    for required in ["name", "model"] {
+        if !columns.contains_key(required) {
+            return Err(format!("Missing required column '{}'", required));
+        }
+    }
+    Ok(columns)
+}
+
+fn parse_number<T: std::str::FromStr>(column: &str, value: Option<String>) -> Result<Option<T>, String> {
+    value
+        .map(|v| v.parse().map_err(|_| format!("{} must be a number, got '{}'", column, v)))
+        .transpose()
+}
+
+/// Build a machine from one CSV row. Blank cells count as missing and a blank
+/// status means idle.
+fn row_to_input(columns: &HashMap<String, usize>, record: &CsvRecord) -> Result<CreateMachineInput, String> {
+    let cell = |column: &str| -> Option<String> {
+        columns
+            .get(column)
+            .and_then(|&i| record.fields.get(i))
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    let name = cell("name").ok_or("name is required")?;
+    let model = cell("model").ok_or("model is required")?;
+    for column in ["purchase_date", "warranty_expiry", "amc_expiry"] {
+        if let Some(date) = cell(column) {
+            chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .map_err(|_| format!("{} must be a date in YYYY-MM-DD format, got '{}'", column, date))?;
+        }
+    }
+
+    Ok(CreateMachineInput {
+        name,
+        model,
+        serial_number: cell("serial_number"),
+        purchase_date: cell("purchase_date"),
+        status: cell("status").unwrap_or_else(|| "idle".to_string()).to_lowercase(),
+        location: cell("location"),
+        capacity: cell("capacity"),
+        power_consumption: cell("power_consumption"),
+        dimensions: cell("dimensions"),
+        weight: cell("weight"),
+        max_rpm: cell("max_rpm"),
+        axis_travel: cell("axis_travel"),
+        hourly_rate: parse_number("hourly_rate", cell("hourly_rate"))?,
+        power_kw: parse_number("power_kw", cell("power_kw"))?,
+        weight_kg: parse_number("weight_kg", cell("weight_kg"))?,
+        max_rpm_value: parse_number("max_rpm_value", cell("max_rpm_value"))?,
+        maintenance_interval_hours: parse_number("maintenance_interval_hours", cell("maintenance_interval_hours"))?,
+        site_id: parse_number("site_id", cell("site_id"))?,
+        warranty_expiry: cell("warranty_expiry"),
+        amc_expiry: cell("amc_expiry"),
+        location_id: parse_number("location_id", cell("location_id"))?,
+    })
+}
+
+fn import_machine_rows(
+    conn: &Connection,
+    ctx: &OperationContext,
+    records: Vec<CsvRecord>,
+    dry_run: bool,
+) -> Result<MachineImportResult, String> {
+    let mut records = records.into_iter();
+    let header = records.next().ok_or("The file is empty")?;
+    let columns = read_header(&header)?;
+    let rows: Vec<CsvRecord> = records.collect();
+    let total = rows.len() as i64;
+    ctx.progress(0, total, "Importing machines")?;
+
+    // A dry run does the same inserts and rolls them back, so uniqueness is
+    // checked against the database exactly as a real run would
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let mut seen_names = HashSet::new();
+    let mut created_ids = Vec::new();
+    let mut errors = Vec::new();
+
+    for (i, record) in rows.iter().enumerate() {
+        let result = row_to_input(&columns, record).and_then(|input| {
+            if !seen_names.insert(input.name.to_lowercase()) {
+                return Err(format!("Machine '{}' appears more than once in the file", input.name));
+            }
+            insert_machine(&tx, &input).map_err(|e| e.trim_start_matches("Validation error: ").to_string())
+        });
+        match result {
+            Ok(id) => created_ids.push(id),
+            Err(error) => errors.push(ImportLineError {
+                line: record.line,
+                name: columns
+                    .get("name")
+                    .and_then(|&i| record.fields.get(i))
+                    .map(|n| n.trim().to_string())
+                    .filter(|n| !n.is_empty()),
+                error,
+            }),
+        }
+        ctx.progress(i as i64 + 1, total, "Importing machines")?;
+    }
+
+    let valid_rows = created_ids.len() as i64;
+    if dry_run {
+        drop(tx);
+        return Ok(MachineImportResult { dry_run, total_rows: total, valid_rows, imported: Vec::new(), errors });
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    let imported = created_ids
+        .iter()
+        .map(|&id| {
+            conn.query_row("SELECT * FROM machines WHERE id = ?1", [id], Machine::from_row)
+                .map_err(|e| e.to_string())
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(MachineImportResult { dry_run, total_rows: total, valid_rows, imported, errors })
+}
+
+/// Create machines from a CSV file (Admin only).
+///
+/// The header row names the columns, which are the fields of
+/// CreateMachineInput. Valid rows are inserted together in one transaction;
+/// the rest come back as per-line errors. With `dry_run` nothing is saved.
+#[tauri::command]
+pub fn import_machines_csv(
+    token: String,
+    file_path: String,
+    dry_run: Option<bool>,
+    background: Option<bool>,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<OperationOutcome<MachineImportResult>, AppError> {
+    let user = {
+        let conn = db.conn.lock();
+        let user = validate_session(&conn, &token)?;
+        require_permission(&conn, &user, "machines.create")?;
+        user
+    };
+
+    let text = std::fs::read_to_string(&file_path).map_err(|e| format!("Cannot read '{}': {}", file_path, e))?;
+    let records = parse_csv(&text)?;
+    let dry_run = dry_run.unwrap_or(false);
+
+    run_operation(&app, "import_machines", user.id, background.unwrap_or(false), move |ctx| {
+        let db = ctx.app().state::<Database>();
+        let conn = db.conn.lock();
+        let result = import_machine_rows(&conn, ctx, records, dry_run)?;
+        if !dry_run && !result.imported.is_empty() {
+            record_audit(
+                &conn,
+                &user,
+                "import",
+                "machines",
+                None,
+                None,
+                Some(&serde_json::json!({ "file": file_path, "created": result.valid_rows }).to_string()),
+            );
+        }
+        Ok(result)
+    })
+}
@@ -0,0 +1,223 @@
+use std::collections::BTreeMap;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::utils::{parse_date, require_view_permission, validate_session};
+
+/// Machine statuses that count against availability
+const DOWN_STATUSES: [&str; 2] = ["maintenance", "error"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusHours {
+    pub status: String,
+    pub hours: f64,
+}
+
+/// Time a machine spent in each status over a date range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MachineDowntime {
+    pub machine_id: i64,
+    pub machine_name: String,
+    pub start_date: String,
+    pub end_date: String,
+    /// Hours covered, up to now for ranges that end in the future
+    pub total_hours: f64,
+    pub hours_by_status: Vec<StatusHours>,
+    /// Hours in "maintenance" or "error"
+    pub downtime_hours: f64,
+    pub availability_pct: f64,
+}
+
+/// Availability combined with schedule efficiency over a date range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MachineOee {
+    pub machine_id: i64,
+    pub machine_name: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub availability_pct: f64,
+    pub planned_hours: f64,
+    pub actual_hours: f64,
+    /// Actual over planned hours; None when nothing was planned
+    pub efficiency_pct: Option<f64>,
+    pub oee_pct: Option<f64>,
+}
+
+fn parse_timestamp(value: &str) -> Result<NaiveDateTime, String> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").map_err(|e| e.to_string())
+}
+
+/// Hours per status between the start of `start_date` and the end of `end_date`.
+///
+/// Status history timestamps are UTC. The status at the start of the range is
+/// carried forward from the last change before it; a machine with no history
+/// at all is assumed to have had its current status throughout.
+fn status_hours(
+    conn: &Connection,
+    machine_id: i64,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<(String, BTreeMap<String, f64>, f64), String> {
+    if end_date < start_date {
+        return Err("End date must not be before start date".to_string());
+    }
+    let (machine_name, current_status): (String, String) = conn
+        .query_row(
+            "SELECT name, status FROM machines WHERE id = ?1",
+            [machine_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| "Machine not found".to_string())?;
+
+    let range_start = start_date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    let range_end = (end_date + chrono::Duration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .min(chrono::Utc::now().naive_utc());
+    let start_str = range_start.format("%Y-%m-%d %H:%M:%S").to_string();
+    let end_str = range_end.format("%Y-%m-%d %H:%M:%S").to_string();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT changed_at, old_status, new_status FROM machine_status_history
+             WHERE machine_id = ?1 AND changed_at >= ?2 AND changed_at < ?3
+             ORDER BY changed_at, id",
+        )
+        .map_err(|e| e.to_string())?;
+    let changes: Vec<(String, Option<String>, String)> = stmt
+        .query_map(params![machine_id, start_str, end_str], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let before: Option<String> = conn
+        .query_row(
+            "SELECT new_status FROM machine_status_history
+             WHERE machine_id = ?1 AND changed_at < ?2
+             ORDER BY changed_at DESC, id DESC LIMIT 1",
+            params![machine_id, start_str],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    let mut status = match (before, changes.first()) {
+        (Some(status), _) => status,
+        (None, Some((_, Some(old), _))) => old.clone(),
+        _ => current_status,
+    };
+
+    let mut hours = BTreeMap::new();
+    let mut since = range_start;
+    for (changed_at, _, new_status) in changes {
+        let at = parse_timestamp(&changed_at)?;
+        *hours.entry(status).or_insert(0.0) += (at - since).num_seconds() as f64 / 3600.0;
+        status = new_status;
+        since = at;
+    }
+    if range_end > since {
+        *hours.entry(status).or_insert(0.0) += (range_end - since).num_seconds() as f64 / 3600.0;
+    }
+
+    Ok((machine_name, hours, (range_end - range_start).num_seconds().max(0) as f64 / 3600.0))
+}
+
+fn machine_downtime(
+    conn: &Connection,
+    machine_id: i64,
+    start_date: &str,
+    end_date: &str,
+) -> Result<MachineDowntime, String> {
+    let start = NaiveDate::parse_from_str(start_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    let end = NaiveDate::parse_from_str(end_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    let (machine_name, hours, total_hours) = status_hours(conn, machine_id, start, end)?;
+
+    let downtime_hours: f64 = hours
+        .iter()
+        .filter(|(status, _)| DOWN_STATUSES.contains(&status.as_str()))
+        .map(|(_, h)| h)
+        .sum();
+    let availability_pct = if total_hours > 0.0 {
+        (total_hours - downtime_hours) / total_hours * 100.0
+    } else {
+        100.0
+    };
+
+    Ok(MachineDowntime {
+        machine_id,
+        machine_name,
+        start_date: start_date.to_string(),
+        end_date: end_date.to_string(),
+        total_hours,
+        hours_by_status: hours.into_iter().map(|(status, hours)| StatusHours { status, hours }).collect(),
+        downtime_hours,
+        availability_pct,
+    })
+}
+
+/// Hours a machine spent in each status between two dates, with availability
+/// (time not in maintenance or error)
+#[tauri::command]
+pub fn get_machine_downtime(
+    token: String,
+    machine_id: i64,
+    start_date: String,
+    end_date: String,
+    db: State<'_, Database>,
+) -> Result<MachineDowntime, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    parse_date("start_date", &start_date)?;
+    parse_date("end_date", &end_date)?;
+    Ok(machine_downtime(&conn, machine_id, &start_date, &end_date)?)
+}
+
+/// OEE-style figure for a machine: availability times schedule efficiency
+/// (actual over planned hours of non-cancelled entries)
+#[tauri::command]
+pub fn get_machine_oee(
+    token: String,
+    machine_id: i64,
+    start_date: String,
+    end_date: String,
+    db: State<'_, Database>,
+) -> Result<MachineOee, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    parse_date("start_date", &start_date)?;
+    parse_date("end_date", &end_date)?;
+    let downtime = machine_downtime(&conn, machine_id, &start_date, &end_date)?;
+    let (planned_hours, actual_hours): (f64, f64) = conn
+        .query_row(
+            "SELECT COALESCE(SUM(planned_hours), 0), COALESCE(SUM(actual_hours), 0) FROM schedules
+             WHERE machine_id = ?1 AND date >= ?2 AND date <= ?3 AND status != 'cancelled'",
+            params![machine_id, start_date, end_date],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let efficiency_pct = (planned_hours > 0.0).then(|| actual_hours / planned_hours * 100.0);
+    let oee_pct = efficiency_pct.map(|e| downtime.availability_pct * e / 100.0);
+
+    Ok(MachineOee {
+        machine_id,
+        machine_name: downtime.machine_name,
+        start_date,
+        end_date,
+        availability_pct: downtime.availability_pct,
+        planned_hours,
+        actual_hours,
+        efficiency_pct,
+        oee_pct,
+    })
+}
@@ -0,0 +1,125 @@
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::Machine;
+use crate::utils::{require_view_permission, validate_session};
+
+const QR_PREFIX: &str = "vmc://machine/";
+
+/// Deep link to encode in a machine's QR label
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MachineQrPayload {
+    pub machine_id: i64,
+    pub payload: String,
+    /// False when the machine has no serial number and the link falls back to
+    /// its id, which does not survive re-seeding the database
+    pub uses_serial_number: bool,
+}
+
+/// FNV-1a over the link body. Catches typos and truncated scans; it is not a
+/// signature.
+fn checksum(body: &str) -> String {
+    let hash = body
+        .bytes()
+        .fold(0x811c_9dc5_u32, |hash, byte| (hash ^ byte as u32).wrapping_mul(0x0100_0193));
+    format!("{:08x}", hash)
+}
+
+/// Percent-encode everything but unreserved URL characters
+fn encode_segment(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn decode_segment(value: &str) -> Result<String, String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = value.get(i + 1..i + 3).ok_or("Invalid machine QR code")?;
+            decoded.push(u8::from_str_radix(hex, 16).map_err(|_| "Invalid machine QR code")?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| "Invalid machine QR code".to_string())
+}
+
+/// Link body for a machine: its serial number when it has one, so labels keep
+/// working after the database is re-seeded, otherwise its id
+fn link_body(id: i64, serial_number: Option<&str>) -> String {
+    match serial_number.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(serial) => format!("sn/{}", encode_segment(serial)),
+        None => format!("id/{}", id),
+    }
+}
+
+/// Deterministic deep link for a machine's QR label, e.g.
+/// `vmc://machine/sn/VMC-001?c=1a2b3c4d`
+#[tauri::command]
+pub fn get_machine_qr_payload(
+    token: String,
+    machine_id: i64,
+    db: State<'_, Database>,
+) -> Result<MachineQrPayload, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let serial_number: Option<String> = conn
+        .query_row("SELECT serial_number FROM machines WHERE id = ?1", [machine_id], |row| row.get(0))
+        .map_err(|_| "Machine not found".to_string())?;
+
+    let body = link_body(machine_id, serial_number.as_deref());
+    Ok(MachineQrPayload {
+        machine_id,
+        uses_serial_number: body.starts_with("sn/"),
+        payload: format!("{}{}?c={}", QR_PREFIX, body, checksum(&body)),
+    })
+}
+
+/// Check a scanned QR payload and return the machine it points at
+#[tauri::command]
+pub fn resolve_machine_qr(
+    token: String,
+    payload: String,
+    db: State<'_, Database>,
+) -> Result<Machine, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let (body, check) = payload
+        .trim()
+        .strip_prefix(QR_PREFIX)
+        .and_then(|rest| rest.split_once("?c="))
+        .ok_or("Not a machine QR code")?;
+    if !check.eq_ignore_ascii_case(&checksum(body)) {
+        return Err(AppError::validation("machine QR code checksum does not match; rescan the label"));
+    }
+
+    let machine = match body.split_once('/') {
+        Some(("sn", serial)) => conn.query_row(
+            "SELECT * FROM machines WHERE serial_number = ?1",
+            params![decode_segment(serial)?],
+            Machine::from_row,
+        ),
+        Some(("id", id)) => {
+            let id: i64 = id.parse().map_err(|_| "Invalid machine QR code")?;
+            conn.query_row("SELECT * FROM machines WHERE id = ?1", [id], Machine::from_row)
+        }
+        _ => return Err(AppError::validation("invalid machine QR code")),
+    };
+    machine.map_err(|_| AppError::NotFound("No machine matches this QR code".to_string()))
+}
@@ -0,0 +1,362 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::commands::alerts::UNREAD_BY_ANYONE;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::User;
+use crate::utils::{emit_machine_status, record_audit, require_permission, require_view_permission, validate_session};
+
+const MISMATCH_ALERT_TITLE: &str = "Machine status mismatch";
+
+/// A machine whose stored status disagrees with its maintenance, downtime and schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MachineStatusMismatch {
+    pub machine_id: i64,
+    pub machine_name: String,
+    pub current_status: String,
+    pub expected_status: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconcileResult {
+    pub checked: i64,
+    pub mismatches: Vec<MachineStatusMismatch>,
+    /// Whether the mismatches were corrected
+    pub applied: bool,
+}
+
+/// Status a machine should have, with the rule that decided it.
+///
+/// Rules in order of precedence: in-progress maintenance, open downtime, an
+/// in-progress schedule entry today, otherwise idle.
+pub fn derive_expected_status(
+    has_active_maintenance: bool,
+    has_open_downtime: bool,
+    has_active_schedule: bool,
+) -> (&'static str, &'static str) {
+    if has_active_maintenance {
+        ("maintenance", "Maintenance is in progress")
+    } else if has_open_downtime {
+        ("error", "Downtime is still open")
+    } else if has_active_schedule {
+        ("active", "A schedule entry is in progress today")
+    } else {
+        ("idle", "No maintenance, downtime or running work")
+    }
+}
+
+/// Compare every machine's stored status with the derived one
+pub fn find_status_mismatches(conn: &Connection) -> Result<(i64, Vec<MachineStatusMismatch>), String> {
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let mut stmt = conn
+        .prepare(
+            "SELECT m.id, m.name, m.status,
+                    EXISTS (SELECT 1 FROM maintenance mt WHERE mt.machine_id = m.id AND mt.status = 'in-progress'),
+                    EXISTS (SELECT 1 FROM downtime_log d WHERE d.machine_id = m.id AND d.end_time IS NULL),
+                    EXISTS (SELECT 1 FROM schedules s
+                            WHERE s.machine_id = m.id AND s.date = ?1 AND s.status = 'in-progress')
+             FROM machines m
+             ORDER BY m.name",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows: Vec<(i64, String, String, bool, bool, bool)> = stmt
+        .query_map([&today], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let checked = rows.len() as i64;
+    let mismatches = rows
+        .into_iter()
+        .filter_map(|(machine_id, machine_name, current_status, maintenance, downtime, schedule)| {
+            let (expected, reason) = derive_expected_status(maintenance, downtime, schedule);
+            (current_status != expected).then(|| MachineStatusMismatch {
+                machine_id,
+                machine_name,
+                current_status,
+                expected_status: expected.to_string(),
+                reason: reason.to_string(),
+            })
+        })
+        .collect();
+
+    Ok((checked, mismatches))
+}
+
+/// Append a status change to the machine's history
+pub fn record_status_change(
+    conn: &Connection,
+    machine_id: i64,
+    old_status: &str,
+    new_status: &str,
+    reason: Option<&str>,
+    changed_by: Option<i64>,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO machine_status_history (machine_id, old_status, new_status, reason, changed_by)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![machine_id, old_status, new_status, reason, changed_by],
+    )
+    .map_err(|e| format!("Failed to record status history: {}", e))?;
+    Ok(())
+}
+
+/// A status change made by sync_machine_statuses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MachineStatusTransition {
+    pub machine_id: i64,
+    pub machine_name: String,
+    pub old_status: String,
+    pub new_status: String,
+    pub reason: String,
+}
+
+/// Move machines to the status today's activity implies: "maintenance" with
+/// maintenance in progress, "active" with a schedule entry in progress today,
+/// otherwise "idle".
+///
+/// Machines in "error" are never touched, and machines with open downtime are
+/// left for reconcile_machine_statuses since only a person should mark one as
+/// broken. `user` is None for the startup run.
+pub fn sync_statuses(conn: &Connection, user: Option<&User>) -> Result<Vec<MachineStatusTransition>, String> {
+    let (_, mismatches) = find_status_mismatches(conn)?;
+    let transitions: Vec<MachineStatusTransition> = mismatches
+        .into_iter()
+        .filter(|m| m.current_status != "error" && m.expected_status != "error")
+        .map(|m| MachineStatusTransition {
+            machine_id: m.machine_id,
+            machine_name: m.machine_name,
+            old_status: m.current_status,
+            new_status: m.expected_status,
+            reason: m.reason,
+        })
+        .collect();
+    if transitions.is_empty() {
+        return Ok(transitions);
+    }
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    for transition in &transitions {
+        tx.execute(
+            "UPDATE machines SET status = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
+            params![transition.new_status, transition.machine_id],
+        )
+        .map_err(|e| format!("Failed to update status: {}", e))?;
+        record_status_change(
+            &tx,
+            transition.machine_id,
+            &transition.old_status,
+            &transition.new_status,
+            Some(&format!("Synced: {}", transition.reason)),
+            user.map(|u| u.id),
+        )?;
+        if let Some(user) = user {
+            record_audit(
+                &tx,
+                user,
+                "sync_status",
+                "machines",
+                Some(transition.machine_id),
+                Some(&serde_json::json!({ "status": transition.old_status }).to_string()),
+                Some(&serde_json::json!({ "status": transition.new_status, "reason": transition.reason }).to_string()),
+            );
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(transitions)
+}
+
+/// Bring machine statuses in line with today's schedule and maintenance,
+/// returning the changes made
+#[tauri::command]
+pub fn sync_machine_statuses(
+    token: String,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<Vec<MachineStatusTransition>, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "machines.edit")?;
+
+    let transitions = sync_statuses(&conn, Some(&user))?;
+    for transition in &transitions {
+        emit_machine_status(&app, transition.machine_id, &transition.new_status);
+    }
+    Ok(transitions)
+}
+
+/// Report-only check run at startup; raises a low alert when machines are out of step.
+/// An unread alert from an earlier run is not duplicated.
+pub fn check_statuses_on_startup(conn: &Connection) -> Result<(), String> {
+    let (_, mismatches) = find_status_mismatches(conn)?;
+    if mismatches.is_empty() {
+        return Ok(());
+    }
+
+    let pending: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM alerts WHERE title = ?1 AND {}", UNREAD_BY_ANYONE),
+            [MISMATCH_ALERT_TITLE],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if pending > 0 {
+        return Ok(());
+    }
+
+    let names: Vec<&str> = mismatches.iter().map(|m| m.machine_name.as_str()).collect();
+    conn.execute(
+        "INSERT INTO alerts (alert_type, priority, title, message) VALUES ('warning', 'low', ?1, ?2)",
+        params![
+            MISMATCH_ALERT_TITLE,
+            format!(
+                "{} machine(s) have a status that does not match maintenance, downtime or schedules: {}",
+                mismatches.len(),
+                names.join(", ")
+            )
+        ],
+    )
+    .map_err(|e| format!("Failed to create alert: {}", e))?;
+    Ok(())
+}
+
+/// Find machines whose status disagrees with maintenance, downtime and today's
+/// schedule. With `apply` set (admin only) the statuses are corrected.
+#[tauri::command]
+pub fn reconcile_machine_statuses(
+    token: String,
+    apply: bool,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<ReconcileResult, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    if apply {
+        require_permission(&conn, &user, "machines.manage")?;
+    } else {
+        require_view_permission(&user)?;
+    }
+
+    let (checked, mismatches) = find_status_mismatches(&conn)?;
+
+    if apply && !mismatches.is_empty() {
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        for mismatch in &mismatches {
+            tx.execute(
+                "UPDATE machines SET status = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
+                params![mismatch.expected_status, mismatch.machine_id],
+            )
+            .map_err(|e| format!("Failed to update status: {}", e))?;
+            record_status_change(
+                &tx,
+                mismatch.machine_id,
+                &mismatch.current_status,
+                &mismatch.expected_status,
+                Some(&format!("Reconciled: {}", mismatch.reason)),
+                Some(user.id),
+            )?;
+            record_audit(
+                &tx,
+                &user,
+                "reconcile_status",
+                "machines",
+                Some(mismatch.machine_id),
+                Some(&serde_json::json!({ "status": mismatch.current_status }).to_string()),
+                Some(&serde_json::json!({ "status": mismatch.expected_status, "reason": mismatch.reason }).to_string()),
+            );
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+        for mismatch in &mismatches {
+            emit_machine_status(&app, mismatch.machine_id, &mismatch.expected_status);
+        }
+    }
+
+    Ok(ReconcileResult { checked, mismatches, applied: apply })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_pending_migrations;
+
+    #[test]
+    fn each_rule_on_its_own() {
+        assert_eq!(derive_expected_status(true, false, false).0, "maintenance");
+        assert_eq!(derive_expected_status(false, true, false).0, "error");
+        assert_eq!(derive_expected_status(false, false, true).0, "active");
+        assert_eq!(derive_expected_status(false, false, false).0, "idle");
+    }
+
+    #[test]
+    fn maintenance_beats_downtime_beats_schedule() {
+        assert_eq!(derive_expected_status(true, true, true).0, "maintenance");
+        assert_eq!(derive_expected_status(true, true, false).0, "maintenance");
+        assert_eq!(derive_expected_status(true, false, true).0, "maintenance");
+        assert_eq!(derive_expected_status(false, true, true).0, "error");
+    }
+
+    /// One machine per rule, all stored as "active"
+    fn sample_database() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_pending_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO machines (id, name, model, status) VALUES
+                 (1, 'A-maintenance', 'V', 'active'),
+                 (2, 'B-downtime', 'V', 'active'),
+                 (3, 'C-running', 'V', 'active'),
+                 (4, 'D-quiet', 'V', 'active'),
+                 (5, 'E-both', 'V', 'active');
+             INSERT INTO maintenance (machine_id, date, maintenance_type, status) VALUES
+                 (1, date('now', 'localtime'), 'preventive', 'in-progress'),
+                 (4, date('now', 'localtime'), 'preventive', 'completed'),
+                 (5, date('now', 'localtime'), 'corrective', 'in-progress');
+             INSERT INTO downtime_log (machine_id, start_time, end_time, reason_category) VALUES
+                 (2, '2026-01-01 08:00', NULL, 'breakdown'),
+                 (4, '2026-01-01 08:00', '2026-01-01 09:00', 'breakdown'),
+                 (5, '2026-01-01 08:00', NULL, 'breakdown');
+             INSERT INTO schedules (machine_id, date, planned_hours, status) VALUES
+                 (3, date('now', 'localtime'), 8, 'in-progress'),
+                 (4, date('now', 'localtime', '-1 day'), 8, 'in-progress'),
+                 (5, date('now', 'localtime'), 8, 'in-progress');",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn finds_machines_out_of_step() {
+        let conn = sample_database();
+        let (checked, mismatches) = find_status_mismatches(&conn).unwrap();
+
+        assert_eq!(checked, 5);
+        let expected: Vec<(&str, &str)> = mismatches
+            .iter()
+            .map(|m| (m.machine_name.as_str(), m.expected_status.as_str()))
+            .collect();
+        // C-running is correctly active; closed downtime and yesterday's work don't count for D-quiet
+        assert_eq!(
+            expected,
+            [("A-maintenance", "maintenance"), ("B-downtime", "error"), ("D-quiet", "idle"), ("E-both", "maintenance")]
+        );
+    }
+
+    #[test]
+    fn sync_leaves_downtime_for_a_person() {
+        let conn = sample_database();
+        let transitions = sync_statuses(&conn, None).unwrap();
+
+        let changed: Vec<&str> = transitions.iter().map(|t| t.machine_name.as_str()).collect();
+        assert_eq!(changed, ["A-maintenance", "D-quiet", "E-both"]);
+        let status: String = conn.query_row("SELECT status FROM machines WHERE id = 2", [], |row| row.get(0)).unwrap();
+        assert_eq!(status, "active");
+        let history: i64 =
+            conn.query_row("SELECT COUNT(*) FROM machine_status_history", [], |row| row.get(0)).unwrap();
+        assert_eq!(history, 3);
+    }
+}
@@ -1,19 +1,256 @@
-use rusqlite::params;
-use tauri::State;
+use rusqlite::{params, Connection};
+use tauri::{AppHandle, State};
 
+use crate::commands::integrity::{confirm_delete_impact, machine_delete_impact, CascadeEffect};
+use crate::commands::locations::{fetch_location, find_location_id};
+use crate::commands::machine_documents::document_file;
+use crate::commands::machine_status::record_status_change;
+use crate::commands::search::escape_like;
+use crate::commands::sites::fetch_site;
 use crate::db::Database;
-use crate::models::{CreateMachineInput, Machine, Maintenance, Schedule, UpdateMachineInput};
-use crate::utils::{require_admin, require_edit_permission, require_view_permission, validate_session};
+use crate::error::AppError;
+use crate::models::{
+    parse_power_kw, parse_rpm, parse_weight_kg, validate_numeric_specs, CreateMachineInput, FleetMachineSummary, Machine,
+    MachineFilter, MachineListItem, MachineWithStats, Maintenance, Schedule, UpdateMachineInput,
+};
+use crate::utils::{
+    audit_json, count_rows, csv_number, csv_row, emit_change, emit_machine_status, record_audit, record_audit_update,
+    require_permission, require_view_permission, start_of_week, validate_session, PageRequest, Paginated,
+    ACTION_CREATED, ACTION_DELETED, ACTION_UPDATED, ALERT_CHANGED_EVENT, MACHINE_CHANGED_EVENT,
+};
 
-/// Get all machines
+/// Get all machines, leaving out archived ones unless `include_archived` is set
 #[tauri::command]
-pub fn get_machines(token: String, db: State<'_, Database>) -> Result<Vec<Machine>, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+pub fn get_machines(
+    token: String,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    include_archived: Option<bool>,
+    db: State<'_, Database>,
+) -> Result<Paginated<Machine>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let include_archived = include_archived.unwrap_or(false);
+    let page = PageRequest::resolve(&conn, limit, offset, None)?;
+    let total = count_rows(
+        &conn,
+        "SELECT COUNT(*) FROM machines WHERE (? OR archived_at IS NULL)",
+        &[&include_archived],
+    )?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT * FROM machines WHERE (? OR archived_at IS NULL) ORDER BY name ASC{}",
+            page.sql()
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let machines = stmt
+        .query_map(params![include_archived, page.limit, page.offset], Machine::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(Paginated::new(machines, page, total))
+}
+
+/// Get all machines with this week's hours, today's project and next maintenance
+///
+/// `current_project` is the active project with an in-progress entry on the
+/// machine today; `maintenance_due` is the earliest scheduled maintenance date.
+#[tauri::command]
+pub fn get_machines_with_stats(token: String, db: State<'_, Database>) -> Result<Vec<MachineWithStats>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let today = chrono::Local::now().date_naive();
+    let week_start = start_of_week(&conn, today);
+    let week_end = week_start + chrono::Duration::days(6);
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT m.*, cp.project_name,
+                    COALESCE(wk.scheduled_hours, 0) as scheduled_hours,
+                    COALESCE(wk.actual_hours, 0) as actual_hours,
+                    mt.maintenance_due
+             FROM machines m
+             LEFT JOIN (
+                 SELECT machine_id, SUM(planned_hours) as scheduled_hours,
+                        SUM(COALESCE(actual_hours, 0)) as actual_hours
+                 FROM schedules WHERE date >= ?1 AND date <= ?2
+                 GROUP BY machine_id
+             ) wk ON wk.machine_id = m.id
+             LEFT JOIN (
+                 SELECT s.machine_id, MIN(p.name) as project_name
+                 FROM schedules s JOIN projects p ON s.project_id = p.id
+                 WHERE s.date = ?3 AND s.status = 'in-progress' AND p.status = 'active'
+                 GROUP BY s.machine_id
+             ) cp ON cp.machine_id = m.id
+             LEFT JOIN (
+                 SELECT machine_id, MIN(date) as maintenance_due
+                 FROM maintenance WHERE status = 'scheduled'
+                 GROUP BY machine_id
+             ) mt ON mt.machine_id = m.id
+             WHERE m.archived_at IS NULL
+             ORDER BY m.name ASC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let machines = stmt
+        .query_map(
+            params![
+                week_start.format("%Y-%m-%d").to_string(),
+                week_end.format("%Y-%m-%d").to_string(),
+                today.format("%Y-%m-%d").to_string()
+            ],
+            |row| {
+                Ok(MachineWithStats {
+                    machine: Machine::from_row(row)?,
+                    current_project: row.get("project_name")?,
+                    scheduled_hours_this_week: row.get("scheduled_hours")?,
+                    actual_hours_this_week: row.get("actual_hours")?,
+                    maintenance_due: row.get("maintenance_due")?,
+                })
+            },
+        )
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(machines)
+}
+
+/// ORDER BY clause for a machine sort field, with missing values last
+fn machine_order_by(sort_by: &str, order: Option<&str>) -> Result<String, String> {
+    let column = match sort_by {
+        "name" => "name",
+        "power_kw" => "power_kw",
+        "weight_kg" => "weight_kg",
+        "max_rpm" | "max_rpm_value" => "max_rpm_value",
+        "hourly_rate" => "hourly_rate",
+        "purchase_date" => "purchase_date",
+        _ => return Err(format!("Validation error: cannot sort machines by '{}'", sort_by)),
+    };
+    let direction = match order.unwrap_or("asc") {
+        "asc" => "ASC",
+        "desc" => "DESC",
+        other => return Err(format!("Validation error: order must be asc or desc, not '{}'", other)),
+    };
+    Ok(format!("{column} IS NULL, {column} {direction}, name ASC"))
+}
+
+/// Search and filter machines server-side, one page at a time
+///
+/// Substring filters match case-insensitively. With `include_week_counts` each
+/// machine also carries its number of schedule entries this week.
+#[tauri::command]
+pub fn query_machines(
+    token: String,
+    filter: Option<MachineFilter>,
+    db: State<'_, Database>,
+) -> Result<Paginated<MachineListItem>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let filter = filter.unwrap_or_default();
+    let mut query = String::from(" FROM machines m WHERE 1=1");
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if !filter.include_archived.unwrap_or(false) {
+        query.push_str(" AND m.archived_at IS NULL");
+    }
+    if let Some(ref status) = filter.status {
+        query.push_str(" AND m.status = ?");
+        params_vec.push(Box::new(status.clone()));
+    }
+    if let Some(ref capacity) = filter.capacity {
+        query.push_str(" AND m.capacity = ?");
+        params_vec.push(Box::new(capacity.clone()));
+    }
+    if let Some(location_id) = filter.location_id {
+        query.push_str(" AND m.location_id = ?");
+        params_vec.push(Box::new(location_id));
+    }
+    if let Some(location) = filter.location.as_deref().map(str::trim).filter(|l| !l.is_empty()) {
+        query.push_str(" AND m.location LIKE ? ESCAPE '\\'");
+        params_vec.push(Box::new(format!("%{}%", escape_like(location))));
+    }
+    if let Some(search) = filter.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        query.push_str(" AND (m.name LIKE ? ESCAPE '\\' OR m.model LIKE ? ESCAPE '\\')");
+        let pattern = format!("%{}%", escape_like(search));
+        params_vec.push(Box::new(pattern.clone()));
+        params_vec.push(Box::new(pattern));
+    }
+    let order_by = machine_order_by(filter.sort_by.as_deref().unwrap_or("name"), filter.order.as_deref())?;
+
+    let page = PageRequest::resolve(&conn, filter.limit, filter.offset, None)?;
+    let count_params: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+    let total = count_rows(&conn, &format!("SELECT COUNT(*){}", query), count_params.as_slice())?;
+
+    // The week count subquery comes first in the SQL, so its dates bind first
+    let include_week_counts = filter.include_week_counts.unwrap_or(false);
+    let mut data_params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+    let week_column = if include_week_counts {
+        let today = chrono::Local::now().date_naive();
+        let week_start = start_of_week(&conn, today);
+        data_params.push(Box::new(week_start.format("%Y-%m-%d").to_string()));
+        data_params.push(Box::new((week_start + chrono::Duration::days(6)).format("%Y-%m-%d").to_string()));
+        "(SELECT COUNT(*) FROM schedules s WHERE s.machine_id = m.id AND s.date >= ? AND s.date <= ?)"
+    } else {
+        "NULL"
+    };
+    data_params.extend(params_vec);
+    page.bind(&mut data_params);
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT m.*, {} as schedules_this_week{} ORDER BY {}{}",
+            week_column,
+            query,
+            order_by,
+            page.sql()
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let params_slice: Vec<&dyn rusqlite::ToSql> = data_params.iter().map(|p| p.as_ref()).collect();
+    let machines = stmt
+        .query_map(params_slice.as_slice(), |row| {
+            Ok(MachineListItem {
+                machine: Machine::from_row(row)?,
+                schedules_this_week: row.get("schedules_this_week")?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(Paginated::new(machines, page, total))
+}
+
+/// Get active machines ordered by a numeric spec or name
+///
+/// `sort_by` is one of name, power_kw, weight_kg, max_rpm, hourly_rate or
+/// purchase_date; machines without the value sort last either way.
+#[tauri::command]
+pub fn get_machines_sorted(
+    token: String,
+    sort_by: String,
+    order: Option<String>,
+    db: State<'_, Database>,
+) -> Result<Vec<Machine>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
     let mut stmt = conn
-        .prepare("SELECT * FROM machines ORDER BY name ASC")
+        .prepare(&format!(
+            "SELECT * FROM machines WHERE archived_at IS NULL ORDER BY {}",
+            machine_order_by(&sort_by, order.as_deref())?
+        ))
         .map_err(|e| e.to_string())?;
 
     let machines = stmt
@@ -27,17 +264,17 @@ pub fn get_machines(token: String, db: State<'_, Database>) -> Result<Vec<Machin
 
 /// Get single machine by ID
 #[tauri::command]
-pub fn get_machine(token: String, id: i64, db: State<'_, Database>) -> Result<Machine, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+pub fn get_machine(token: String, id: i64, db: State<'_, Database>) -> Result<Machine, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
     conn.query_row(
         "SELECT * FROM machines WHERE id = ?1",
         [id],
         Machine::from_row,
     )
-    .map_err(|_| "Machine not found".to_string())
+    .map_err(|_| AppError::NotFound("Machine not found".to_string()))
 }
 
 /// Create new machine (Admin only)
@@ -45,34 +282,68 @@ pub fn get_machine(token: String, id: i64, db: State<'_, Database>) -> Result<Ma
 pub fn create_machine(
     token: String,
     input: CreateMachineInput,
+    app: AppHandle,
     db: State<'_, Database>,
-) -> Result<Machine, String> {
+) -> Result<Machine, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_admin(&user)?;
+    require_permission(&conn, &user, "machines.create")?;
+
+    let new_id = insert_machine(&conn, &input)?;
+    let machine = fetch_machine(&conn, new_id)?;
+    record_audit(&conn, &user, "create", "machines", Some(new_id), None, audit_json(&machine).as_deref());
+    emit_change(&app, MACHINE_CHANGED_EVENT, ACTION_CREATED, vec![new_id]);
+    Ok(machine)
+}
 
+/// Validate a new machine and insert it, returning its id
+pub(crate) fn insert_machine(conn: &Connection, input: &CreateMachineInput) -> Result<i64, String> {
     // Validate status
     if !["active", "idle", "maintenance", "error"].contains(&input.status.as_str()) {
         return Err("Invalid status".to_string());
     }
+    if let Some(capacity) = &input.capacity {
+        if !["Small", "Medium", "Large", "Extra Large"].contains(&capacity.as_str()) {
+            return Err("Invalid capacity. Must be Small, Medium, Large or Extra Large".to_string());
+        }
+    }
+    if let Some(site_id) = input.site_id {
+        fetch_site(conn, site_id)?;
+    }
+    let power_kw = input.power_kw.or_else(|| input.power_consumption.as_deref().and_then(parse_power_kw));
+    let weight_kg = input.weight_kg.or_else(|| input.weight.as_deref().and_then(parse_weight_kg));
+    let max_rpm_value = input.max_rpm_value.or_else(|| input.max_rpm.as_deref().and_then(parse_rpm));
+    validate_numeric_specs(power_kw, weight_kg, max_rpm_value)?;
+    validate_maintenance_interval(input.maintenance_interval_hours)?;
+    validate_expiry_date("warranty_expiry", input.warranty_expiry.as_deref())?;
+    validate_expiry_date("amc_expiry", input.amc_expiry.as_deref())?;
+    let (location_id, location) = resolve_location(conn, input.location_id, input.location.as_deref())?;
 
     conn.execute(
-        "INSERT INTO machines (name, model, serial_number, purchase_date, status, location, capacity, power_consumption, dimensions, weight, max_rpm, axis_travel, hourly_rate)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        "INSERT INTO machines (name, model, serial_number, purchase_date, status, location, capacity, power_consumption, dimensions, weight, max_rpm, axis_travel, hourly_rate, site_id, power_kw, weight_kg, max_rpm_value, maintenance_interval_hours, warranty_expiry, amc_expiry, location_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)",
         params![
             input.name,
             input.model,
             input.serial_number,
             input.purchase_date,
             input.status,
-            input.location,
+            location,
             input.capacity,
             input.power_consumption,
             input.dimensions,
             input.weight,
             input.max_rpm,
             input.axis_travel,
-            input.hourly_rate.unwrap_or(0.0)
+            input.hourly_rate.unwrap_or(0.0),
+            input.site_id,
+            power_kw,
+            weight_kg,
+            max_rpm_value,
+            input.maintenance_interval_hours,
+            input.warranty_expiry,
+            input.amc_expiry,
+            location_id
         ],
     )
     .map_err(|e| {
@@ -83,13 +354,125 @@ pub fn create_machine(
         }
     })?;
 
+    Ok(conn.last_insert_rowid())
+}
+
+/// Create a machine with the same specs as an existing one (Admin only)
+///
+/// Copies model, capacity, power, dimensions, weight, RPM and axis travel; the
+/// clone starts idle with no schedules, maintenance or project assignments.
+#[tauri::command]
+pub fn clone_machine(
+    token: String,
+    source_id: i64,
+    new_name: String,
+    new_serial_number: Option<String>,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<Machine, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "machines.create")?;
+
+    let new_name = new_name.trim().to_string();
+    if new_name.is_empty() {
+        return Err(AppError::invalid_field("name", "machine name is required"));
+    }
+    let new_serial_number = new_serial_number
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    if let Some(serial) = &new_serial_number {
+        let taken: i64 = conn
+            .query_row("SELECT COUNT(*) FROM machines WHERE serial_number = ?1", [serial], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        if taken > 0 {
+            return Err(AppError::Conflict("Serial number already exists".to_string()));
+        }
+    }
+
+    let inserted = conn
+        .execute(
+            "INSERT INTO machines (name, model, serial_number, status, capacity, power_consumption, dimensions, weight, max_rpm, axis_travel, power_kw, weight_kg, max_rpm_value)
+             SELECT ?1, model, ?2, 'idle', capacity, power_consumption, dimensions, weight, max_rpm, axis_travel, power_kw, weight_kg, max_rpm_value
+             FROM machines WHERE id = ?3",
+            params![new_name, new_serial_number, source_id],
+        )
+        .map_err(|e| {
+            if e.to_string().contains("UNIQUE constraint failed") {
+                "Machine name already exists".to_string()
+            } else {
+                format!("Failed to clone machine: {}", e)
+            }
+        })?;
+    if inserted == 0 {
+        return Err(AppError::NotFound("Machine not found".to_string()));
+    }
+
     let new_id = conn.last_insert_rowid();
-    conn.query_row(
-        "SELECT * FROM machines WHERE id = ?1",
-        [new_id],
-        Machine::from_row,
-    )
-    .map_err(|e| e.to_string())
+    record_audit(
+        &conn,
+        &user,
+        "clone_machine",
+        "machines",
+        Some(new_id),
+        None,
+        Some(&serde_json::json!({ "source_id": source_id, "name": new_name }).to_string()),
+    );
+
+    let machine = fetch_machine(&conn, new_id)?;
+    emit_change(&app, MACHINE_CHANGED_EVENT, ACTION_CREATED, vec![new_id]);
+    Ok(machine)
+}
+
+/// Refuse new schedule entries or maintenance on a retired machine
+pub(crate) fn ensure_machine_not_archived(conn: &Connection, machine_id: i64) -> Result<(), String> {
+    let (name, archived_at): (String, Option<String>) = conn
+        .query_row(
+            "SELECT name, archived_at FROM machines WHERE id = ?1",
+            [machine_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| "Machine not found".to_string())?;
+    if archived_at.is_some() {
+        return Err(format!("Machine '{}' is archived", name));
+    }
+    Ok(())
+}
+
+/// Managed location and free text to store for a machine. A location id wins
+/// and its name becomes the text; free text links to the location of the same
+/// name when there is one.
+fn resolve_location(
+    conn: &Connection,
+    location_id: Option<i64>,
+    text: Option<&str>,
+) -> Result<(Option<i64>, Option<String>), String> {
+    match (location_id, text) {
+        (Some(id), _) => Ok((Some(id), Some(fetch_location(conn, id)?.name))),
+        (None, Some(text)) => Ok((find_location_id(conn, text)?, Some(text.to_string()))),
+        (None, None) => Ok((None, None)),
+    }
+}
+
+fn validate_expiry_date(field: &str, date: Option<&str>) -> Result<(), String> {
+    match date {
+        Some(date) if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() => {
+            Err(format!("Validation error: {} must be a date in YYYY-MM-DD format", field))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn validate_maintenance_interval(interval: Option<f64>) -> Result<(), String> {
+    if interval.is_some_and(|h| !h.is_finite() || h <= 0.0) {
+        return Err("Validation error: maintenance_interval_hours must be greater than zero".to_string());
+    }
+    Ok(())
+}
+
+fn fetch_machine(conn: &Connection, id: i64) -> Result<Machine, String> {
+    conn.query_row("SELECT * FROM machines WHERE id = ?1", [id], Machine::from_row)
+        .map_err(|_| "Machine not found".to_string())
 }
 
 /// Update machine (Admin or Operator)
@@ -98,11 +481,12 @@ pub fn update_machine(
     token: String,
     id: i64,
     input: UpdateMachineInput,
+    app: AppHandle,
     db: State<'_, Database>,
-) -> Result<Machine, String> {
+) -> Result<Machine, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "machines.edit")?;
 
     let mut updates = Vec::new();
     let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
@@ -125,23 +509,42 @@ pub fn update_machine(
     }
     if let Some(status) = &input.status {
         if !["active", "idle", "maintenance", "error"].contains(&status.as_str()) {
-            return Err("Invalid status".to_string());
+            return Err(AppError::invalid_field("status", "invalid status"));
         }
         updates.push("status = ?");
         values.push(Box::new(status.clone()));
     }
-    if let Some(location) = &input.location {
+    if input.clear_location.unwrap_or(false) {
+        updates.push("location_id = NULL");
+        if let Some(location) = &input.location {
+            updates.push("location = ?");
+            values.push(Box::new(location.clone()));
+        }
+    } else if input.location_id.is_some() || input.location.is_some() {
+        let (location_id, location) = resolve_location(&conn, input.location_id, input.location.as_deref())?;
+        updates.push("location_id = ?");
+        values.push(Box::new(location_id));
         updates.push("location = ?");
-        values.push(Box::new(location.clone()));
+        values.push(Box::new(location));
     }
     if let Some(capacity) = &input.capacity {
         updates.push("capacity = ?");
         values.push(Box::new(capacity.clone()));
     }
+    // Numeric specs follow their text field unless given explicitly
+    let power_kw = input.power_kw.or_else(|| input.power_consumption.as_deref().and_then(parse_power_kw));
+    let weight_kg = input.weight_kg.or_else(|| input.weight.as_deref().and_then(parse_weight_kg));
+    let max_rpm_value = input.max_rpm_value.or_else(|| input.max_rpm.as_deref().and_then(parse_rpm));
+    validate_numeric_specs(power_kw, weight_kg, max_rpm_value)?;
+
     if let Some(power) = &input.power_consumption {
         updates.push("power_consumption = ?");
         values.push(Box::new(power.clone()));
     }
+    if input.power_kw.is_some() || input.power_consumption.is_some() {
+        updates.push("power_kw = ?");
+        values.push(Box::new(power_kw));
+    }
     if let Some(dims) = &input.dimensions {
         updates.push("dimensions = ?");
         values.push(Box::new(dims.clone()));
@@ -150,10 +553,18 @@ pub fn update_machine(
         updates.push("weight = ?");
         values.push(Box::new(weight.clone()));
     }
+    if input.weight_kg.is_some() || input.weight.is_some() {
+        updates.push("weight_kg = ?");
+        values.push(Box::new(weight_kg));
+    }
     if let Some(rpm) = &input.max_rpm {
         updates.push("max_rpm = ?");
         values.push(Box::new(rpm.clone()));
     }
+    if input.max_rpm_value.is_some() || input.max_rpm.is_some() {
+        updates.push("max_rpm_value = ?");
+        values.push(Box::new(max_rpm_value));
+    }
     if let Some(axis) = &input.axis_travel {
         updates.push("axis_travel = ?");
         values.push(Box::new(axis.clone()));
@@ -162,11 +573,41 @@ pub fn update_machine(
         updates.push("hourly_rate = ?");
         values.push(Box::new(rate));
     }
+    if input.clear_maintenance_interval.unwrap_or(false) {
+        updates.push("maintenance_interval_hours = NULL");
+    } else if let Some(interval) = input.maintenance_interval_hours {
+        validate_maintenance_interval(Some(interval))?;
+        updates.push("maintenance_interval_hours = ?");
+        values.push(Box::new(interval));
+    }
+    if input.clear_site.unwrap_or(false) {
+        updates.push("site_id = NULL");
+    } else if let Some(site_id) = input.site_id {
+        fetch_site(&conn, site_id)?;
+        updates.push("site_id = ?");
+        values.push(Box::new(site_id));
+    }
+    if input.clear_warranty_expiry.unwrap_or(false) {
+        updates.push("warranty_expiry = NULL");
+    } else if let Some(expiry) = &input.warranty_expiry {
+        validate_expiry_date("warranty_expiry", Some(expiry))?;
+        updates.push("warranty_expiry = ?");
+        values.push(Box::new(expiry.clone()));
+    }
+    if input.clear_amc_expiry.unwrap_or(false) {
+        updates.push("amc_expiry = NULL");
+    } else if let Some(expiry) = &input.amc_expiry {
+        validate_expiry_date("amc_expiry", Some(expiry))?;
+        updates.push("amc_expiry = ?");
+        values.push(Box::new(expiry.clone()));
+    }
 
     if updates.is_empty() {
-        return Err("No fields to update".to_string());
+        return Err(AppError::validation("no fields to update"));
     }
 
+    let existing = fetch_machine(&conn, id)?;
+
     updates.push("updated_at = CURRENT_TIMESTAMP");
     let query = format!("UPDATE machines SET {} WHERE id = ?", updates.join(", "));
     values.push(Box::new(id));
@@ -175,12 +616,18 @@ pub fn update_machine(
     conn.execute(&query, params.as_slice())
         .map_err(|e| format!("Failed to update machine: {}", e))?;
 
-    conn.query_row(
-        "SELECT * FROM machines WHERE id = ?1",
-        [id],
-        Machine::from_row,
-    )
-    .map_err(|e| e.to_string())
+    let status_changed = input.status.as_ref().filter(|s| **s != existing.status);
+    if let Some(status) = status_changed {
+        record_status_change(&conn, id, &existing.status, status, None, Some(user.id))?;
+    }
+
+    let machine = fetch_machine(&conn, id)?;
+    record_audit_update(&conn, &user, "update", "machines", id, &existing, &machine);
+    emit_change(&app, MACHINE_CHANGED_EVENT, ACTION_UPDATED, vec![id]);
+    if let Some(status) = status_changed {
+        emit_machine_status(&app, id, status);
+    }
+    Ok(machine)
 }
 
 /// Update machine status only (Admin or Operator)
@@ -189,40 +636,224 @@ pub fn update_machine_status(
     token: String,
     id: i64,
     status: String,
+    app: AppHandle,
     db: State<'_, Database>,
-) -> Result<Machine, String> {
+) -> Result<Machine, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "machines.edit")?;
 
     if !["active", "idle", "maintenance", "error"].contains(&status.as_str()) {
-        return Err("Invalid status".to_string());
+        return Err(AppError::invalid_field("status", "invalid status"));
     }
 
+    let existing = fetch_machine(&conn, id)?;
     conn.execute(
         "UPDATE machines SET status = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
         params![status, id],
     )
     .map_err(|e| format!("Failed to update status: {}", e))?;
+    if status != existing.status {
+        record_status_change(&conn, id, &existing.status, &status, None, Some(user.id))?;
+    }
 
-    conn.query_row(
-        "SELECT * FROM machines WHERE id = ?1",
+    let machine = fetch_machine(&conn, id)?;
+    record_audit_update(&conn, &user, "update_status", "machines", id, &existing, &machine);
+    if status != existing.status {
+        emit_machine_status(&app, id, &status);
+    }
+    Ok(machine)
+}
+
+/// Correct a machine's runtime hour meter, e.g. after a spindle swap (Admin only)
+#[tauri::command]
+pub fn set_machine_runtime_hours(
+    token: String,
+    machine_id: i64,
+    hours: f64,
+    reason: Option<String>,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<Machine, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "machines.manage")?;
+
+    if !hours.is_finite() || hours < 0.0 {
+        return Err(AppError::validation("runtime hours must be a non-negative number"));
+    }
+    let old_hours: f64 = conn
+        .query_row(
+            "SELECT COALESCE(runtime_hours, 0) FROM machines WHERE id = ?1",
+            [machine_id],
+            |row| row.get(0),
+        )
+        .map_err(|_| "Machine not found".to_string())?;
+
+    conn.execute(
+        "UPDATE machines SET runtime_hours = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
+        params![hours, machine_id],
+    )
+    .map_err(|e| format!("Failed to set runtime hours: {}", e))?;
+    record_audit(
+        &conn,
+        &user,
+        "set_runtime_hours",
+        "machines",
+        Some(machine_id),
+        Some(&serde_json::json!({ "runtime_hours": old_hours }).to_string()),
+        Some(&serde_json::json!({ "runtime_hours": hours, "reason": reason }).to_string()),
+    );
+
+    let machine = fetch_machine(&conn, machine_id)?;
+    emit_change(&app, MACHINE_CHANGED_EVENT, ACTION_UPDATED, vec![machine_id]);
+    Ok(machine)
+}
+
+/// Archive a retired machine (Admin only)
+///
+/// The machine drops out of lists and can take no new schedules or maintenance,
+/// but its history is kept. Upcoming entries must be moved or cancelled first.
+#[tauri::command]
+pub fn archive_machine(
+    token: String,
+    id: i64,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<Machine, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "machines.manage")?;
+
+    ensure_machine_not_archived(&conn, id)?;
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let upcoming: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM schedules
+             WHERE machine_id = ?1 AND date >= ?2 AND status IN ('scheduled', 'in-progress')",
+            params![id, today],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if upcoming > 0 {
+        return Err(AppError::Conflict(format!(
+            "Machine has {} upcoming schedule entries. Move or cancel them before archiving",
+            upcoming
+        )));
+    }
+
+    conn.execute(
+        "UPDATE machines SET archived_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
         [id],
-        Machine::from_row,
     )
-    .map_err(|e| e.to_string())
+    .map_err(|e| format!("Failed to archive machine: {}", e))?;
+    record_audit(&conn, &user, "archive_machine", "machines", Some(id), None, None);
+
+    let machine = fetch_machine(&conn, id)?;
+    emit_change(&app, MACHINE_CHANGED_EVENT, ACTION_UPDATED, vec![id]);
+    Ok(machine)
+}
+
+/// Return an archived machine to service (Admin only)
+#[tauri::command]
+pub fn unarchive_machine(
+    token: String,
+    id: i64,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<Machine, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "machines.manage")?;
+
+    let updated = conn
+        .execute(
+            "UPDATE machines SET archived_at = NULL, updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?1 AND archived_at IS NOT NULL",
+            [id],
+        )
+        .map_err(|e| format!("Failed to unarchive machine: {}", e))?;
+    if updated == 0 {
+        return Err(AppError::NotFound("Machine not found or not archived".to_string()));
+    }
+    record_audit(&conn, &user, "unarchive_machine", "machines", Some(id), None, None);
+
+    let machine = fetch_machine(&conn, id)?;
+    emit_change(&app, MACHINE_CHANGED_EVENT, ACTION_UPDATED, vec![id]);
+    Ok(machine)
 }
 
 /// Delete machine (Admin only)
+///
+/// Only for records created by mistake: a machine with schedule or maintenance
+/// history must be archived instead. `expected_effects` are the cascade
+/// effects from `check_machine_delete_impact` that the user confirmed.
 #[tauri::command]
-pub fn delete_machine(token: String, id: i64, db: State<'_, Database>) -> Result<(), String> {
+pub fn delete_machine(
+    token: String,
+    id: i64,
+    expected_effects: Option<Vec<CascadeEffect>>,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<(), AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_admin(&user)?;
+    require_permission(&conn, &user, "machines.delete")?;
+
+    let existing = fetch_machine(&conn, id)?;
+    let (schedules, maintenance): (i64, i64) = conn
+        .query_row(
+            "SELECT (SELECT COUNT(*) FROM schedules WHERE machine_id = ?1),
+                    (SELECT COUNT(*) FROM maintenance WHERE machine_id = ?1)",
+            [id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+    if schedules > 0 || maintenance > 0 {
+        return Err(AppError::Conflict(format!(
+            "Machine has {} schedule entries and {} maintenance records. Archive it instead to keep its history",
+            schedules, maintenance
+        )));
+    }
+
+    let impact = machine_delete_impact(&conn, id)?;
+    confirm_delete_impact(&impact, expected_effects.as_deref())?;
+
+    let alert_ids: Vec<i64> = conn
+        .prepare("SELECT id FROM alerts WHERE machine_id = ?1")
+        .and_then(|mut stmt| stmt.query_map([id], |row| row.get(0))?.collect())
+        .map_err(|e| e.to_string())?;
+    let document_paths: Vec<String> = conn
+        .prepare("SELECT stored_path FROM machine_documents WHERE machine_id = ?1")
+        .and_then(|mut stmt| stmt.query_map([id], |row| row.get(0))?.collect())
+        .map_err(|e| e.to_string())?;
 
-    conn.execute("DELETE FROM machines WHERE id = ?1", [id])
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM alerts WHERE machine_id = ?1", [id])
+        .map_err(|e| format!("Failed to delete machine alerts: {}", e))?;
+    tx.execute("DELETE FROM machines WHERE id = ?1", [id])
         .map_err(|e| format!("Failed to delete machine: {}", e))?;
+    record_audit(
+        &tx,
+        &user,
+        "delete",
+        "machines",
+        Some(id),
+        audit_json(&existing).as_deref(),
+        Some(&serde_json::json!({ "impact": impact.cascade_effects }).to_string()),
+    );
+    tx.commit().map_err(|e| e.to_string())?;
 
+    // The document rows went with the machine; their files only once that is committed
+    for stored_path in document_paths {
+        let path = document_file(&app, &db, &stored_path);
+        if let Err(e) = std::fs::remove_file(&path) {
+            log::warn!("Could not remove document file {:?}: {}", path, e);
+        }
+    }
+
+    emit_change(&app, MACHINE_CHANGED_EVENT, ACTION_DELETED, vec![id]);
+    emit_change(&app, ALERT_CHANGED_EVENT, ACTION_DELETED, alert_ids);
     Ok(())
 }
 
@@ -232,10 +863,10 @@ pub fn get_machine_history(
     token: String,
     machine_id: i64,
     db: State<'_, Database>,
-) -> Result<MachineHistoryResponse, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+) -> Result<MachineHistoryResponse, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
     // Get machine
     let machine = conn
@@ -297,6 +928,126 @@ pub fn get_machine_history(
     })
 }
 
+fn load_fleet_overview(conn: &Connection) -> Result<Vec<FleetMachineSummary>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT m.id, m.name, m.model, m.status, m.purchase_date,
+                    COALESCE(mt.completed_count, 0), mt.total_cost,
+                    COALESCE(mt.corrective_count, 0), COALESCE(mt.preventive_count, 0),
+                    dt.downtime_hours, rt.runtime_hours
+             FROM machines m
+             LEFT JOIN (
+                 SELECT machine_id, COUNT(*) as completed_count, SUM(cost) as total_cost,
+                        SUM(maintenance_type = 'corrective') as corrective_count,
+                        SUM(maintenance_type = 'preventive') as preventive_count
+                 FROM maintenance WHERE status = 'completed'
+                 GROUP BY machine_id
+             ) mt ON mt.machine_id = m.id
+             LEFT JOIN (
+                 SELECT machine_id, SUM((julianday(end_time) - julianday(start_time)) * 24) as downtime_hours
+                 FROM downtime_log WHERE end_time IS NOT NULL
+                 GROUP BY machine_id
+             ) dt ON dt.machine_id = m.id
+             LEFT JOIN (
+                 SELECT machine_id, SUM(actual_hours) as runtime_hours
+                 FROM schedules WHERE actual_hours IS NOT NULL
+                 GROUP BY machine_id
+             ) rt ON rt.machine_id = m.id
+             ORDER BY m.name ASC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let today = chrono::Local::now().date_naive();
+    let fleet = stmt
+        .query_map([], |row| {
+            let purchase_date: Option<String> = row.get(4)?;
+            let maintenance_cost: Option<f64> = row.get(6)?;
+            let corrective_count: i64 = row.get(7)?;
+            let preventive_count: i64 = row.get(8)?;
+            let runtime_hours: Option<f64> = row.get(10)?;
+
+            let age_years = purchase_date
+                .as_deref()
+                .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+                .map(|d| (today - d).num_days() as f64 / 365.25);
+            let corrective_ratio = match corrective_count + preventive_count {
+                0 => None,
+                total => Some(corrective_count as f64 / total as f64),
+            };
+            let cost_per_runtime_hour = match (maintenance_cost, runtime_hours) {
+                (Some(cost), Some(hours)) if hours > 0.0 => Some(cost / hours),
+                _ => None,
+            };
+
+            Ok(FleetMachineSummary {
+                machine_id: row.get(0)?,
+                machine_name: row.get(1)?,
+                model: row.get(2)?,
+                status: row.get(3)?,
+                purchase_date,
+                age_years,
+                completed_maintenance_count: row.get(5)?,
+                maintenance_cost,
+                corrective_count,
+                preventive_count,
+                corrective_ratio,
+                downtime_hours: row.get(9)?,
+                runtime_hours,
+                cost_per_runtime_hour,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(fleet)
+}
+
+/// Lifetime maintenance spend, incidents, downtime and age for every machine
+///
+/// Figures without data (no purchase date, no recorded costs, no runtime) are
+/// null so the UI can tell "no data" from zero.
+#[tauri::command]
+pub fn get_fleet_overview(token: String, db: State<'_, Database>) -> Result<Vec<FleetMachineSummary>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    Ok(load_fleet_overview(&conn)?)
+}
+
+/// The fleet overview as CSV text for the fleet review
+#[tauri::command]
+pub fn export_fleet_overview_csv(token: String, db: State<'_, Database>) -> Result<String, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let mut csv = csv_row(&[
+        "machine", "model", "status", "purchase_date", "age_years", "completed_maintenance",
+        "maintenance_cost", "corrective", "preventive", "corrective_ratio", "downtime_hours",
+        "runtime_hours", "cost_per_runtime_hour",
+    ]);
+    for m in load_fleet_overview(&conn)? {
+        csv.push_str(&csv_row(&[
+            m.machine_name,
+            m.model,
+            m.status,
+            m.purchase_date.unwrap_or_default(),
+            csv_number(m.age_years),
+            m.completed_maintenance_count.to_string(),
+            csv_number(m.maintenance_cost),
+            m.corrective_count.to_string(),
+            m.preventive_count.to_string(),
+            csv_number(m.corrective_ratio),
+            csv_number(m.downtime_hours),
+            csv_number(m.runtime_hours),
+            csv_number(m.cost_per_runtime_hour),
+        ]));
+    }
+    Ok(csv)
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ProjectSummary {
     pub id: i64,
@@ -310,3 +1061,24 @@ pub struct MachineHistoryResponse {
     pub maintenance: Vec<Maintenance>,
     pub assigned_projects: Vec<ProjectSummary>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_by_whitelisted_columns_only() {
+        assert_eq!(
+            machine_order_by("power_kw", Some("desc")).unwrap(),
+            "power_kw IS NULL, power_kw DESC, name ASC"
+        );
+        assert_eq!(machine_order_by("max_rpm", None).unwrap(), "max_rpm_value IS NULL, max_rpm_value ASC, name ASC");
+
+        for sort_by in ["name; DROP TABLE machines", "name DESC", "(SELECT password_hash FROM users)", "", "NAME"] {
+            assert!(machine_order_by(sort_by, None).unwrap_err().starts_with("Validation error"));
+        }
+        for order in ["asc; DELETE FROM machines", "ASC", "asc --", "1"] {
+            assert!(machine_order_by("name", Some(order)).unwrap_err().starts_with("Validation error"));
+        }
+    }
+}
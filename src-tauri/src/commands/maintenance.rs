@@ -1,31 +1,77 @@
-use rusqlite::params;
-use tauri::State;
+use rusqlite::{params, Connection};
+use tauri::{AppHandle, State};
 
+use crate::commands::machine_status::record_status_change;
+use crate::commands::machines::ensure_machine_not_archived;
+use crate::commands::status_transitions::{check_transition, ENTITY_MAINTENANCE};
 use crate::db::Database;
+use crate::error::AppError;
 use crate::models::{CreateMaintenanceInput, Maintenance, UpdateMaintenanceInput, UpcomingMaintenance};
-use crate::utils::{require_edit_permission, require_view_permission, validate_session};
+use crate::utils::{
+    audit_json, count_rows, emit_change, emit_machine_status, record_audit, record_audit_update, require_permission,
+    require_view_permission, validate_session, PageRequest, Paginated, ACTION_CREATED, ACTION_DELETED, ACTION_UPDATED,
+    MAINTENANCE_CHANGED_EVENT,
+};
+
+/// Best-effort machine status change driven by maintenance, kept in the status history
+fn set_machine_status(app: &AppHandle, conn: &Connection, machine_id: i64, status: &str, reason: &str, user_id: i64) {
+    let Ok(old_status) = conn.query_row("SELECT status FROM machines WHERE id = ?1", [machine_id], |row| {
+        row.get::<_, String>(0)
+    }) else {
+        return;
+    };
+    if old_status == status {
+        return;
+    }
+    let updated = conn.execute(
+        "UPDATE machines SET status = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
+        params![status, machine_id],
+    );
+    if updated.is_ok() {
+        record_status_change(conn, machine_id, &old_status, status, Some(reason), Some(user_id)).ok();
+        emit_machine_status(app, machine_id, status);
+    }
+}
+
+/// Record the machine's runtime meter on a completed maintenance record, the
+/// starting point for runtime-based maintenance intervals
+fn stamp_runtime_hours(conn: &Connection, maintenance_id: i64) -> Result<(), String> {
+    conn.execute(
+        "UPDATE maintenance SET runtime_hours_at = (
+             SELECT COALESCE(runtime_hours, 0) FROM machines WHERE machines.id = maintenance.machine_id
+         ) WHERE id = ?1",
+        [maintenance_id],
+    )
+    .map_err(|e| format!("Failed to record runtime hours: {}", e))?;
+    Ok(())
+}
 
 /// Get all maintenance records
 #[tauri::command]
 pub fn get_all_maintenance(
     token: String,
+    limit: Option<i64>,
+    offset: Option<i64>,
     db: State<'_, Database>,
-) -> Result<Vec<Maintenance>, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+) -> Result<Paginated<Maintenance>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
+
+    let page = PageRequest::resolve(&conn, limit, offset, None)?;
+    let total = count_rows(&conn, "SELECT COUNT(*) FROM maintenance", &[])?;
 
     let mut stmt = conn
-        .prepare("SELECT * FROM maintenance ORDER BY date DESC")
+        .prepare(&format!("SELECT * FROM maintenance ORDER BY date DESC{}", page.sql()))
         .map_err(|e| e.to_string())?;
 
     let records = stmt
-        .query_map([], Maintenance::from_row)
+        .query_map(params![page.limit, page.offset], Maintenance::from_row)
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
         .collect();
 
-    Ok(records)
+    Ok(Paginated::new(records, page, total))
 }
 
 /// Get maintenance records for a specific machine
@@ -34,10 +80,10 @@ pub fn get_machine_maintenance(
     token: String,
     machine_id: i64,
     db: State<'_, Database>,
-) -> Result<Vec<Maintenance>, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+) -> Result<Vec<Maintenance>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
     let mut stmt = conn
         .prepare("SELECT * FROM maintenance WHERE machine_id = ?1 ORDER BY date DESC")
@@ -52,23 +98,28 @@ pub fn get_machine_maintenance(
     Ok(records)
 }
 
+fn fetch_maintenance(conn: &Connection, id: i64) -> Result<Maintenance, String> {
+    conn.query_row("SELECT * FROM maintenance WHERE id = ?1", [id], Maintenance::from_row)
+        .map_err(|_| "Maintenance record not found".to_string())
+}
+
 /// Get single maintenance record
 #[tauri::command]
 pub fn get_maintenance(
     token: String,
     id: i64,
     db: State<'_, Database>,
-) -> Result<Maintenance, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+) -> Result<Maintenance, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
     conn.query_row(
         "SELECT * FROM maintenance WHERE id = ?1",
         [id],
         Maintenance::from_row,
     )
-    .map_err(|_| "Maintenance record not found".to_string())
+    .map_err(|_| AppError::NotFound("Maintenance record not found".to_string()))
 }
 
 /// Create maintenance record
@@ -76,23 +127,25 @@ pub fn get_maintenance(
 pub fn create_maintenance(
     token: String,
     input: CreateMaintenanceInput,
+    app: AppHandle,
     db: State<'_, Database>,
-) -> Result<Maintenance, String> {
+) -> Result<Maintenance, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "maintenance.edit")?;
+    ensure_machine_not_archived(&conn, input.machine_id)?;
 
     // Validate maintenance type
     if !["preventive", "corrective", "inspection", "calibration"]
         .contains(&input.maintenance_type.as_str())
     {
-        return Err("Invalid maintenance type".to_string());
+        return Err(AppError::invalid_field("maintenance_type", "invalid maintenance type"));
     }
 
     // Validate status
     let status = input.status.unwrap_or_else(|| "scheduled".to_string());
     if !["scheduled", "in-progress", "completed", "cancelled"].contains(&status.as_str()) {
-        return Err("Invalid status".to_string());
+        return Err(AppError::invalid_field("status", "invalid status"));
     }
 
     conn.execute(
@@ -115,37 +168,36 @@ pub fn create_maintenance(
 
     // If maintenance is in-progress, update machine status
     if status == "in-progress" {
-        conn.execute(
-            "UPDATE machines SET status = 'maintenance', updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
-            [input.machine_id],
-        )
-        .ok();
+        set_machine_status(&app, &conn, input.machine_id, "maintenance", "Maintenance started", user.id);
+    } else if status == "completed" {
+        stamp_runtime_hours(&conn, new_id)?;
     }
 
-    conn.query_row(
-        "SELECT * FROM maintenance WHERE id = ?1",
-        [new_id],
-        Maintenance::from_row,
-    )
-    .map_err(|e| e.to_string())
+    let maintenance = fetch_maintenance(&conn, new_id)?;
+    record_audit(&conn, &user, "create", "maintenance", Some(new_id), None, audit_json(&maintenance).as_deref());
+    emit_change(&app, MAINTENANCE_CHANGED_EVENT, ACTION_CREATED, vec![new_id]);
+    Ok(maintenance)
 }
 
 /// Update maintenance record
+///
+/// Status changes must follow the maintenance transition map unless an Admin
+/// sets `force_transition`.
 #[tauri::command]
 pub fn update_maintenance(
     token: String,
     id: i64,
     input: UpdateMaintenanceInput,
+    force_transition: Option<bool>,
+    app: AppHandle,
     db: State<'_, Database>,
-) -> Result<Maintenance, String> {
+) -> Result<Maintenance, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "maintenance.edit")?;
 
     // Get original record for machine status update
-    let original: Maintenance = conn
-        .query_row("SELECT * FROM maintenance WHERE id = ?1", [id], Maintenance::from_row)
-        .map_err(|_| "Maintenance record not found".to_string())?;
+    let original = fetch_maintenance(&conn, id)?;
 
     let mut updates = Vec::new();
     let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
@@ -156,7 +208,7 @@ pub fn update_maintenance(
     }
     if let Some(mtype) = &input.maintenance_type {
         if !["preventive", "corrective", "inspection", "calibration"].contains(&mtype.as_str()) {
-            return Err("Invalid maintenance type".to_string());
+            return Err(AppError::invalid_field("maintenance_type", "invalid maintenance type"));
         }
         updates.push("maintenance_type = ?");
         values.push(Box::new(mtype.clone()));
@@ -175,8 +227,9 @@ pub fn update_maintenance(
     }
     if let Some(status) = &input.status {
         if !["scheduled", "in-progress", "completed", "cancelled"].contains(&status.as_str()) {
-            return Err("Invalid status".to_string());
+            return Err(AppError::invalid_field("status", "invalid status"));
         }
+        check_transition(&conn, &user, ENTITY_MAINTENANCE, id, &original.status, status, force_transition.unwrap_or(false))?;
         updates.push("status = ?");
         values.push(Box::new(status.clone()));
     }
@@ -186,7 +239,7 @@ pub fn update_maintenance(
     }
 
     if updates.is_empty() {
-        return Err("No fields to update".to_string());
+        return Err(AppError::validation("no fields to update"));
     }
 
     updates.push("updated_at = CURRENT_TIMESTAMP");
@@ -199,41 +252,37 @@ pub fn update_maintenance(
 
     // Handle machine status updates based on maintenance status change
     if let Some(new_status) = &input.status {
+        if new_status == "completed" && original.status != "completed" {
+            stamp_runtime_hours(&conn, id)?;
+        }
         if new_status == "in-progress" && original.status != "in-progress" {
             // Set machine to maintenance
-            conn.execute(
-                "UPDATE machines SET status = 'maintenance', updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
-                [original.machine_id],
-            )
-            .ok();
+            set_machine_status(&app, &conn, original.machine_id, "maintenance", "Maintenance started", user.id);
         } else if new_status == "completed" && original.status == "in-progress" {
             // Set machine back to idle
-            conn.execute(
-                "UPDATE machines SET status = 'idle', updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
-                [original.machine_id],
-            )
-            .ok();
+            set_machine_status(&app, &conn, original.machine_id, "idle", "Maintenance completed", user.id);
         }
     }
 
-    conn.query_row(
-        "SELECT * FROM maintenance WHERE id = ?1",
-        [id],
-        Maintenance::from_row,
-    )
-    .map_err(|e| e.to_string())
+    let maintenance = fetch_maintenance(&conn, id)?;
+    record_audit_update(&conn, &user, "update", "maintenance", id, &original, &maintenance);
+    emit_change(&app, MAINTENANCE_CHANGED_EVENT, ACTION_UPDATED, vec![id]);
+    Ok(maintenance)
 }
 
 /// Delete maintenance record
 #[tauri::command]
-pub fn delete_maintenance(token: String, id: i64, db: State<'_, Database>) -> Result<(), String> {
+pub fn delete_maintenance(token: String, id: i64, app: AppHandle, db: State<'_, Database>) -> Result<(), AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "maintenance.delete")?;
 
+    let existing = fetch_maintenance(&conn, id)?;
     conn.execute("DELETE FROM maintenance WHERE id = ?1", [id])
         .map_err(|e| format!("Failed to delete maintenance: {}", e))?;
+    record_audit(&conn, &user, "delete", "maintenance", Some(id), audit_json(&existing).as_deref(), None);
 
+    emit_change(&app, MAINTENANCE_CHANGED_EVENT, ACTION_DELETED, vec![id]);
     Ok(())
 }
 
@@ -243,10 +292,10 @@ pub fn get_upcoming_maintenance(
     token: String,
     days_ahead: Option<i32>,
     db: State<'_, Database>,
-) -> Result<Vec<UpcomingMaintenance>, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+) -> Result<Vec<UpcomingMaintenance>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
     let days = days_ahead.unwrap_or(30);
     let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
@@ -286,10 +335,10 @@ pub fn get_upcoming_maintenance(
 pub fn get_overdue_maintenance(
     token: String,
     db: State<'_, Database>,
-) -> Result<Vec<UpcomingMaintenance>, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+) -> Result<Vec<UpcomingMaintenance>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
     let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
 
@@ -0,0 +1,331 @@
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::LateMaterial;
+use crate::utils::{parse_date, require_permission, require_view_permission, start_of_week, validate_session};
+
+/// Material or bought-in part a project needs before machining can start
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectMaterial {
+    pub id: i64,
+    pub project_id: i64,
+    pub name: String,
+    pub quantity_required: f64,
+    pub quantity_received: f64,
+    pub unit: Option<String>,
+    pub expected_date: Option<String>,
+    pub received_date: Option<String>,
+    pub notes: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl ProjectMaterial {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            project_id: row.get("project_id")?,
+            name: row.get("name")?,
+            quantity_required: row.get("quantity_required")?,
+            quantity_received: row.get("quantity_received")?,
+            unit: row.get("unit")?,
+            expected_date: row.get("expected_date")?,
+            received_date: row.get("received_date")?,
+            notes: row.get("notes")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateProjectMaterialInput {
+    pub project_id: i64,
+    pub name: String,
+    pub quantity_required: f64,
+    pub quantity_received: Option<f64>,
+    pub unit: Option<String>,
+    pub expected_date: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateProjectMaterialInput {
+    pub name: Option<String>,
+    pub quantity_required: Option<f64>,
+    pub quantity_received: Option<f64>,
+    pub unit: Option<String>,
+    pub expected_date: Option<String>,
+    pub received_date: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// A schedule entry whose project is still waiting on material
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaterialRiskEntry {
+    pub schedule_id: i64,
+    pub date: String,
+    pub machine_id: i64,
+    pub machine_name: String,
+    pub project_id: i64,
+    pub project_name: String,
+    pub late_materials: Vec<LateMaterial>,
+}
+
+/// Materials for a project that are still short and expected after `date`.
+///
+/// Computed live, so receiving the material clears the risk without any cleanup.
+pub fn late_materials_for(conn: &Connection, project_id: i64, date: &str) -> Result<Vec<LateMaterial>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, expected_date, quantity_required, quantity_received, unit
+             FROM project_materials
+             WHERE project_id = ?1 AND expected_date > ?2 AND quantity_received < quantity_required
+             ORDER BY expected_date, name",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let materials = stmt
+        .query_map(params![project_id, date], |row| {
+            let required: f64 = row.get(3)?;
+            let received: f64 = row.get(4)?;
+            Ok(LateMaterial {
+                material_id: row.get(0)?,
+                name: row.get(1)?,
+                expected_date: row.get(2)?,
+                quantity_required: required,
+                quantity_received: received,
+                shortfall: required - received,
+                unit: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(materials)
+}
+
+/// Human-readable summary of late materials for warnings
+pub fn describe_late_materials(date: &str, materials: &[LateMaterial]) -> String {
+    let names: Vec<String> = materials
+        .iter()
+        .map(|m| format!("{} (due {}, short {})", m.name, m.expected_date, m.shortfall))
+        .collect();
+    format!("Material not in by {}: {}", date, names.join(", "))
+}
+
+/// Schedule entries in a date range whose projects are waiting on material
+pub fn load_material_risk(conn: &Connection, start_date: &str, end_date: &str) -> Result<Vec<MaterialRiskEntry>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.id, s.date, s.machine_id, m.name as machine_name, s.project_id, p.name as project_name
+             FROM schedules s
+             JOIN machines m ON s.machine_id = m.id
+             JOIN projects p ON s.project_id = p.id
+             WHERE s.date >= ?1 AND s.date <= ?2
+               AND s.status IN ('scheduled', 'in-progress')
+               AND EXISTS (
+                   SELECT 1 FROM project_materials pm
+                   WHERE pm.project_id = s.project_id AND pm.expected_date > s.date
+                     AND pm.quantity_received < pm.quantity_required
+               )
+             ORDER BY s.date, m.name",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows: Vec<(i64, String, i64, String, i64, String)> = stmt
+        .query_map(params![start_date, end_date], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut entries = Vec::new();
+    for (schedule_id, date, machine_id, machine_name, project_id, project_name) in rows {
+        let late_materials = late_materials_for(conn, project_id, &date)?;
+        entries.push(MaterialRiskEntry {
+            schedule_id,
+            date,
+            machine_id,
+            machine_name,
+            project_id,
+            project_name,
+            late_materials,
+        });
+    }
+    Ok(entries)
+}
+
+fn validate_quantity(quantity: f64) -> Result<(), String> {
+    if !quantity.is_finite() || quantity < 0.0 {
+        return Err("Quantities must be zero or more".to_string());
+    }
+    Ok(())
+}
+
+fn fetch_material(conn: &Connection, id: i64) -> Result<ProjectMaterial, String> {
+    conn.query_row("SELECT * FROM project_materials WHERE id = ?1", params![id], ProjectMaterial::from_row)
+        .map_err(|_| "Material not found".to_string())
+}
+
+/// Get the materials tracked for a project
+#[tauri::command]
+pub fn get_project_materials(
+    token: String,
+    project_id: i64,
+    db: State<'_, Database>,
+) -> Result<Vec<ProjectMaterial>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let mut stmt = conn
+        .prepare("SELECT * FROM project_materials WHERE project_id = ?1 ORDER BY expected_date, name")
+        .map_err(|e| e.to_string())?;
+
+    let materials = stmt
+        .query_map(params![project_id], ProjectMaterial::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(materials)
+}
+
+/// Add a material requirement to a project
+#[tauri::command]
+pub fn create_project_material(
+    token: String,
+    input: CreateProjectMaterialInput,
+    db: State<'_, Database>,
+) -> Result<ProjectMaterial, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.details")?;
+
+    if input.name.trim().is_empty() {
+        return Err(AppError::invalid_field("name", "material name is required"));
+    }
+    validate_quantity(input.quantity_required)?;
+    let received = input.quantity_received.unwrap_or(0.0);
+    validate_quantity(received)?;
+
+    conn.execute(
+        "INSERT INTO project_materials (project_id, name, quantity_required, quantity_received, unit, expected_date, notes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            input.project_id,
+            input.name.trim(),
+            input.quantity_required,
+            received,
+            input.unit,
+            input.expected_date,
+            input.notes
+        ],
+    )
+    .map_err(|e| format!("Failed to create material: {}", e))?;
+
+    Ok(fetch_material(&conn, conn.last_insert_rowid())?)
+}
+
+/// Update a material, including recording what has been received
+#[tauri::command]
+pub fn update_project_material(
+    token: String,
+    id: i64,
+    input: UpdateProjectMaterialInput,
+    db: State<'_, Database>,
+) -> Result<ProjectMaterial, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.details")?;
+
+    let mut updates = Vec::new();
+    let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(name) = &input.name {
+        if name.trim().is_empty() {
+            return Err(AppError::invalid_field("name", "material name is required"));
+        }
+        updates.push("name = ?");
+        values.push(Box::new(name.trim().to_string()));
+    }
+    if let Some(quantity) = input.quantity_required {
+        validate_quantity(quantity)?;
+        updates.push("quantity_required = ?");
+        values.push(Box::new(quantity));
+    }
+    if let Some(quantity) = input.quantity_received {
+        validate_quantity(quantity)?;
+        updates.push("quantity_received = ?");
+        values.push(Box::new(quantity));
+    }
+    if let Some(unit) = &input.unit {
+        updates.push("unit = ?");
+        values.push(Box::new(unit.clone()));
+    }
+    if let Some(expected) = &input.expected_date {
+        updates.push("expected_date = ?");
+        values.push(Box::new(expected.clone()));
+    }
+    if let Some(received) = &input.received_date {
+        updates.push("received_date = ?");
+        values.push(Box::new(received.clone()));
+    }
+    if let Some(notes) = &input.notes {
+        updates.push("notes = ?");
+        values.push(Box::new(notes.clone()));
+    }
+
+    if updates.is_empty() {
+        return Ok(fetch_material(&conn, id)?);
+    }
+
+    updates.push("updated_at = CURRENT_TIMESTAMP");
+    let query = format!("UPDATE project_materials SET {} WHERE id = ?", updates.join(", "));
+    values.push(Box::new(id));
+
+    let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+    conn.execute(&query, params.as_slice())
+        .map_err(|e| format!("Failed to update material: {}", e))?;
+
+    Ok(fetch_material(&conn, id)?)
+}
+
+/// Remove a material from a project
+#[tauri::command]
+pub fn delete_project_material(token: String, id: i64, db: State<'_, Database>) -> Result<(), AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.details")?;
+
+    let affected = conn
+        .execute("DELETE FROM project_materials WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    if affected == 0 {
+        return Err(AppError::NotFound("Material not found".to_string()));
+    }
+    Ok(())
+}
+
+/// Get schedule entries for a week whose projects are waiting on material, for purchasing
+#[tauri::command]
+pub fn get_material_risk(
+    token: String,
+    week_start: String,
+    db: State<'_, Database>,
+) -> Result<Vec<MaterialRiskEntry>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let start = start_of_week(&conn, parse_date("week_start", &week_start)?);
+    let week_end = (start + chrono::Duration::days(6)).format("%Y-%m-%d").to_string();
+
+    Ok(load_material_risk(&conn, &start.format("%Y-%m-%d").to_string(), &week_end)?)
+}
@@ -0,0 +1,243 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::commands::alerts::unread_by;
+use crate::commands::schedules::{
+    check_edit_cutoff, ensure_entry_unlocked, record_actual_hours, validate_schedule_status,
+};
+use crate::commands::status_transitions::{check_transition, ENTITY_SCHEDULE};
+use crate::db::Database;
+use crate::error::AppError;
+use crate::utils::{require_permission, require_view_permission, validate_session, HoursValue};
+
+const NEXT_ASSIGNMENT_COUNT: i64 = 3;
+
+/// Slimmed-down schedule entry for small screens (no notes or spec fields)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MobileAssignment {
+    pub id: i64,
+    pub date: String,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub machine_name: String,
+    pub project_name: Option<String>,
+    pub planned_hours: f64,
+    pub actual_hours: Option<f64>,
+    pub status: String,
+}
+
+impl MobileAssignment {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            date: row.get("date")?,
+            start_time: row.get("start_time")?,
+            end_time: row.get("end_time")?,
+            machine_name: row.get("machine_name")?,
+            project_name: row.get("project_name")?,
+            planned_hours: row.get("planned_hours")?,
+            actual_hours: row.get("actual_hours")?,
+            status: row.get("status")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MobileMachine {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MobileProgress {
+    pub date: String,
+    pub completed: i64,
+    pub total: i64,
+}
+
+/// Everything the mobile home screen shows, in one small payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MobileSummary {
+    pub next_assignments: Vec<MobileAssignment>,
+    pub unread_alerts: i64,
+    pub machines_in_error: Vec<MobileMachine>,
+    pub today: MobileProgress,
+}
+
+const ASSIGNMENT_SELECT: &str = "SELECT s.id, s.date, s.start_time, s.end_time, m.name as machine_name,
+            p.name as project_name, s.planned_hours, s.actual_hours, s.status
+     FROM schedules s
+     JOIN machines m ON s.machine_id = m.id
+     LEFT JOIN projects p ON s.project_id = p.id";
+
+fn fetch_assignment(conn: &Connection, id: i64) -> Result<MobileAssignment, String> {
+    conn.query_row(
+        &format!("{} WHERE s.id = ?1", ASSIGNMENT_SELECT),
+        [id],
+        MobileAssignment::from_row,
+    )
+    .map_err(|_| "Schedule not found".to_string())
+}
+
+/// Get the compact home-screen summary for the mobile app
+#[tauri::command]
+pub fn get_mobile_summary(token: String, db: State<'_, Database>) -> Result<MobileSummary, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    Ok(mobile_summary(&conn, user.id, &today)?)
+}
+
+/// Home-screen summary for `user_id` as of `today`
+fn mobile_summary(conn: &Connection, user_id: i64, today: &str) -> Result<MobileSummary, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "{} WHERE s.operator_id = ?1 AND s.date >= ?2 AND s.status IN ('scheduled', 'in-progress')
+             ORDER BY s.date, s.start_time LIMIT ?3",
+            ASSIGNMENT_SELECT
+        ))
+        .map_err(|e| e.to_string())?;
+    let next_assignments = stmt
+        .query_map(params![user_id, today, NEXT_ASSIGNMENT_COUNT], MobileAssignment::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let unread_alerts: i64 = conn
+        .query_row(&format!("SELECT COUNT(*) FROM alerts a WHERE {}", unread_by(user_id)), [], |row| row.get(0))
+        .unwrap_or(0);
+
+    let mut stmt = conn
+        .prepare("SELECT id, name FROM machines WHERE status = 'error' ORDER BY name")
+        .map_err(|e| e.to_string())?;
+    let machines_in_error = stmt
+        .query_map([], |row| Ok(MobileMachine { id: row.get(0)?, name: row.get(1)? }))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let (completed, total): (i64, i64) = conn
+        .query_row(
+            "SELECT COALESCE(SUM(CASE WHEN status = 'completed' THEN 1 ELSE 0 END), 0), COUNT(*)
+             FROM schedules WHERE date = ?1 AND status != 'cancelled'",
+            [today],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    Ok(MobileSummary {
+        next_assignments,
+        unread_alerts,
+        machines_in_error,
+        today: MobileProgress { date: today.to_string(), completed, total },
+    })
+}
+
+/// Log actual hours on an entry, returning the slim entry
+#[tauri::command]
+pub fn quick_log_hours(
+    token: String,
+    schedule_id: i64,
+    hours: HoursValue,
+    db: State<'_, Database>,
+) -> Result<MobileAssignment, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "schedules.log_hours")?;
+
+    record_actual_hours(&conn, &user, schedule_id, &hours, None)?;
+    Ok(fetch_assignment(&conn, schedule_id)?)
+}
+
+/// Change the status of an entry, returning the slim entry
+#[tauri::command]
+pub fn quick_update_status(
+    token: String,
+    schedule_id: i64,
+    status: String,
+    db: State<'_, Database>,
+) -> Result<MobileAssignment, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "schedules.edit")?;
+
+    validate_schedule_status(&status)?;
+    let current: String = conn
+        .query_row("SELECT status FROM schedules WHERE id = ?1", [schedule_id], |row| row.get(0))
+        .optional()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Schedule not found".to_string())?;
+    check_transition(&conn, &user, ENTITY_SCHEDULE, schedule_id, &current, &status, false)?;
+    // No override from the mobile app; past the cutoff this always fails
+    check_edit_cutoff(&conn, &user, schedule_id, None, "change the status of", None)?;
+    ensure_entry_unlocked(&conn, schedule_id)?;
+
+    if current != status {
+        conn.execute(
+            "UPDATE schedules SET status = ?1, updated_by = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?3",
+            params![status, user.id, schedule_id],
+        )
+        .map_err(|e| format!("Failed to update schedule status: {}", e))?;
+    }
+
+    Ok(fetch_assignment(&conn, schedule_id)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_pending_migrations;
+    use crate::db::seed::{seed_demo_data, seed_initial_data};
+
+    const PAYLOAD_LIMIT: usize = 4 * 1024;
+
+    /// Demo machines plus a busy operator: long notes on every entry and a pile of alerts
+    fn seeded_database() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_pending_migrations(&conn).unwrap();
+        seed_initial_data(&conn).unwrap();
+        seed_demo_data(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO users (id, username, password_hash, full_name, role)
+                 VALUES (50, 'op', 'x', 'Operator', 'Operator');
+             INSERT INTO projects (id, name, status) VALUES (1, 'Gearbox housings', 'active');
+             UPDATE machines SET status = 'error' WHERE id IN (SELECT id FROM machines LIMIT 2);",
+        )
+        .unwrap();
+        let notes = "Fixture change and first-article inspection. ".repeat(40);
+        for day in 1..=28 {
+            conn.execute(
+                "INSERT INTO schedules (machine_id, project_id, operator_id, date, start_time, end_time,
+                                        planned_hours, status, notes)
+                 VALUES ((SELECT MIN(id) FROM machines), 1, 50, ?1, '08:00', '16:00', 8, 'scheduled', ?2)",
+                params![format!("2026-03-{:02}", day), notes],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO alerts (alert_type, priority, title, message) VALUES ('info', 'low', 'Note', ?1)",
+                [&notes],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    #[test]
+    fn seeded_summary_stays_small() {
+        let conn = seeded_database();
+        let summary = mobile_summary(&conn, 50, "2026-03-10").unwrap();
+
+        assert_eq!(summary.next_assignments.len(), NEXT_ASSIGNMENT_COUNT as usize);
+        assert_eq!(summary.next_assignments[0].date, "2026-03-10");
+        assert_eq!(summary.unread_alerts, 28);
+        assert_eq!(summary.machines_in_error.len(), 2);
+        assert_eq!((summary.today.completed, summary.today.total), (0, 1));
+
+        let json = serde_json::to_string(&summary).unwrap();
+        assert!(!json.contains("first-article"), "notes leaked into the payload");
+        assert!(json.len() < PAYLOAD_LIMIT, "payload is {} bytes", json.len());
+    }
+}
@@ -12,6 +12,48 @@ pub mod audit;
 pub mod downtime;
 pub mod checklists;
 pub mod shift_logs;
+pub mod reports;
+pub mod weekly_kpi;
+pub mod conflicts;
+pub mod timeline;
+pub mod operations;
+pub mod holidays;
+pub mod system;
+pub mod notification_settings;
+pub mod shifts;
+pub mod materials;
+pub mod mobile;
+pub mod dashboard_layout;
+pub mod tags;
+pub mod machine_status;
+pub mod search;
+pub mod week_locks;
+pub mod sites;
+pub mod week_diff;
+pub mod stale_references;
+pub mod status_transitions;
+pub mod machine_metrics;
+pub mod runtime_maintenance;
+pub mod machine_documents;
+pub mod contract_expiry;
+pub mod machine_qr;
+pub mod locations;
+pub mod machine_import;
+pub mod project_milestones;
+pub mod project_tasks;
+pub mod project_costs;
+pub mod project_deadlines;
+pub mod project_notes;
+pub mod client_contacts;
+pub mod client_import;
+pub mod permissions;
+pub mod api_tokens;
+pub mod workspace;
+pub mod data_transfer;
+pub mod demo_data;
+pub mod database_health;
+pub mod app_settings;
+pub mod desktop_notifications;
 
 pub use auth::*;
 pub use users::*;
@@ -27,3 +69,45 @@ pub use audit::*;
 pub use downtime::*;
 pub use checklists::*;
 pub use shift_logs::*;
+pub use reports::*;
+pub use weekly_kpi::*;
+pub use conflicts::*;
+pub use timeline::*;
+pub use operations::*;
+pub use holidays::*;
+pub use system::*;
+pub use notification_settings::*;
+pub use shifts::*;
+pub use materials::*;
+pub use mobile::*;
+pub use dashboard_layout::*;
+pub use tags::*;
+pub use machine_status::*;
+pub use search::*;
+pub use week_locks::*;
+pub use sites::*;
+pub use week_diff::*;
+pub use stale_references::*;
+pub use status_transitions::*;
+pub use machine_metrics::*;
+pub use runtime_maintenance::*;
+pub use machine_documents::*;
+pub use contract_expiry::*;
+pub use machine_qr::*;
+pub use locations::*;
+pub use machine_import::*;
+pub use project_milestones::*;
+pub use project_tasks::*;
+pub use project_costs::*;
+pub use project_deadlines::*;
+pub use project_notes::*;
+pub use client_contacts::*;
+pub use client_import::*;
+pub use permissions::*;
+pub use api_tokens::*;
+pub use workspace::*;
+pub use data_transfer::*;
+pub use demo_data::*;
+pub use database_health::*;
+pub use app_settings::*;
+pub use desktop_notifications::*;
@@ -0,0 +1,195 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::commands::conflicts::time_to_minutes;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::AlertCue;
+use crate::utils::{require_view_permission, validate_session};
+
+const ALERT_PRIORITIES: [&str; 4] = ["low", "medium", "high", "critical"];
+const DEFAULT_NOTIFY_PRIORITIES: [&str; 2] = ["high", "critical"];
+
+/// Notification preferences for a user, optionally specific to one device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationSettings {
+    pub device_name: Option<String>,
+    pub notify_priorities: Vec<String>,
+    pub sound_enabled: bool,
+    /// Whether high and critical alerts also raise an OS notification
+    pub desktop_enabled: bool,
+    pub quiet_hours_start: Option<String>,
+    pub quiet_hours_end: Option<String>,
+    /// Whether these values come from a saved row or are the built-in defaults
+    pub is_default: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            device_name: None,
+            notify_priorities: DEFAULT_NOTIFY_PRIORITIES.iter().map(|p| p.to_string()).collect(),
+            sound_enabled: false,
+            desktop_enabled: true,
+            quiet_hours_start: None,
+            quiet_hours_end: None,
+            is_default: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateNotificationSettingsInput {
+    pub notify_priorities: Vec<String>,
+    pub sound_enabled: bool,
+    /// Left out, the current value is kept
+    pub desktop_enabled: Option<bool>,
+    pub quiet_hours_start: Option<String>,
+    pub quiet_hours_end: Option<String>,
+}
+
+fn device_key(device_name: Option<&str>) -> String {
+    device_name.map(|d| d.trim().to_string()).unwrap_or_default()
+}
+
+fn load_settings_row(conn: &Connection, user_id: i64, device: &str) -> Option<NotificationSettings> {
+    conn.query_row(
+        "SELECT device_name, notify_priorities, sound_enabled, quiet_hours_start, quiet_hours_end, desktop_enabled
+         FROM user_notification_settings WHERE user_id = ?1 AND device_name = ?2",
+        params![user_id, device],
+        |row| {
+            let device_name: String = row.get(0)?;
+            let priorities: String = row.get(1)?;
+            Ok(NotificationSettings {
+                device_name: if device_name.is_empty() { None } else { Some(device_name) },
+                notify_priorities: priorities
+                    .split(',')
+                    .map(|p| p.trim().to_string())
+                    .filter(|p| !p.is_empty())
+                    .collect(),
+                sound_enabled: row.get::<_, i64>(2)? != 0,
+                desktop_enabled: row.get::<_, i64>(5)? != 0,
+                quiet_hours_start: row.get(3)?,
+                quiet_hours_end: row.get(4)?,
+                is_default: false,
+            })
+        },
+    )
+    .optional()
+    .ok()
+    .flatten()
+}
+
+/// Settings for a user on a device: the device row, then the user's general row,
+/// then the built-in defaults
+pub fn resolve_notification_settings(conn: &Connection, user_id: i64, device_name: Option<&str>) -> NotificationSettings {
+    let device = device_key(device_name);
+    if !device.is_empty() {
+        if let Some(settings) = load_settings_row(conn, user_id, &device) {
+            return settings;
+        }
+    }
+    load_settings_row(conn, user_id, "").unwrap_or_default()
+}
+
+/// Whether `now` ("HH:MM") falls inside the quiet hours; windows may run past midnight
+fn in_quiet_hours(settings: &NotificationSettings, now: &str) -> bool {
+    let (Some(start), Some(end), Some(now)) = (
+        settings.quiet_hours_start.as_deref().and_then(time_to_minutes),
+        settings.quiet_hours_end.as_deref().and_then(time_to_minutes),
+        time_to_minutes(now),
+    ) else {
+        return false;
+    };
+    if start <= end {
+        now >= start && now < end
+    } else {
+        now >= start || now < end
+    }
+}
+
+/// Resolve how an alert of `priority` should be presented to a user right now
+pub fn resolve_alert_cue(conn: &Connection, user_id: i64, device_name: Option<&str>, priority: &str) -> AlertCue {
+    let settings = resolve_notification_settings(conn, user_id, device_name);
+    let now = chrono::Local::now().format("%H:%M").to_string();
+    let quiet = in_quiet_hours(&settings, &now);
+    let notify = !quiet && settings.notify_priorities.iter().any(|p| p == priority);
+    AlertCue {
+        notify,
+        sound: notify && settings.sound_enabled,
+        in_quiet_hours: quiet,
+    }
+}
+
+/// Get the current user's notification settings for a device (or all devices)
+#[tauri::command]
+pub fn get_notification_settings(
+    token: String,
+    device_name: Option<String>,
+    db: State<'_, Database>,
+) -> Result<NotificationSettings, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    Ok(resolve_notification_settings(&conn, user.id, device_name.as_deref()))
+}
+
+/// Save the current user's notification settings for a device (or all devices)
+#[tauri::command]
+pub fn update_notification_settings(
+    token: String,
+    device_name: Option<String>,
+    input: UpdateNotificationSettingsInput,
+    db: State<'_, Database>,
+) -> Result<NotificationSettings, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_view_permission(&user)?;
+
+    for priority in &input.notify_priorities {
+        if !ALERT_PRIORITIES.contains(&priority.as_str()) {
+            return Err(AppError::invalid_field("priority", format!("invalid priority '{}'", priority)));
+        }
+    }
+    match (&input.quiet_hours_start, &input.quiet_hours_end) {
+        (None, None) => {}
+        (Some(start), Some(end)) => {
+            if time_to_minutes(start).is_none() || time_to_minutes(end).is_none() {
+                return Err(AppError::validation("quiet hours must be in HH:MM format"));
+            }
+        }
+        _ => return Err(AppError::validation("quiet hours need both a start and an end time")),
+    }
+
+    let device = device_key(device_name.as_deref());
+    let desktop_enabled = input
+        .desktop_enabled
+        .unwrap_or_else(|| resolve_notification_settings(&conn, user.id, device_name.as_deref()).desktop_enabled);
+    conn.execute(
+        "INSERT INTO user_notification_settings
+             (user_id, device_name, notify_priorities, sound_enabled, desktop_enabled,
+              quiet_hours_start, quiet_hours_end, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, CURRENT_TIMESTAMP)
+         ON CONFLICT(user_id, device_name) DO UPDATE SET
+             notify_priorities = excluded.notify_priorities,
+             sound_enabled = excluded.sound_enabled,
+             desktop_enabled = excluded.desktop_enabled,
+             quiet_hours_start = excluded.quiet_hours_start,
+             quiet_hours_end = excluded.quiet_hours_end,
+             updated_at = CURRENT_TIMESTAMP",
+        params![
+            user.id,
+            device,
+            input.notify_priorities.join(","),
+            input.sound_enabled,
+            desktop_enabled,
+            input.quiet_hours_start,
+            input.quiet_hours_end
+        ],
+    )
+    .map_err(|e| format!("Failed to save notification settings: {}", e))?;
+
+    Ok(resolve_notification_settings(&conn, user.id, device_name.as_deref()))
+}
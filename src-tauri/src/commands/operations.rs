@@ -0,0 +1,56 @@
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::utils::{require_view_permission, validate_session, OperationRegistry, OperationStatus};
+
+/// Get progress, and the final result once finished, of a long-running operation
+/// (its starter or an admin only)
+#[tauri::command]
+pub fn get_operation_status(
+    token: String,
+    operation_id: String,
+    db: State<'_, Database>,
+    operations: State<'_, OperationRegistry>,
+) -> Result<OperationStatus, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_view_permission(&user)?;
+
+    let status = operations
+        .get(&operation_id)
+        .ok_or_else(|| AppError::NotFound("Operation not found".to_string()))?;
+    if status.started_by != user.id && !user.is_admin() {
+        return Err(AppError::Forbidden(
+            "Permission denied. Only the user who started an operation or an admin can view it".to_string(),
+        ));
+    }
+    Ok(status)
+}
+
+/// Request cancellation of a running operation (its starter or an admin only)
+#[tauri::command]
+pub fn cancel_operation(
+    token: String,
+    operation_id: String,
+    db: State<'_, Database>,
+    operations: State<'_, OperationRegistry>,
+) -> Result<(), AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_view_permission(&user)?;
+
+    let status = operations
+        .get(&operation_id)
+        .ok_or_else(|| "Operation not found".to_string())?;
+    if status.started_by != user.id && !user.is_admin() {
+        return Err(AppError::Forbidden(
+            "Permission denied. Only the user who started an operation or an admin can cancel it".to_string(),
+        ));
+    }
+
+    if !operations.request_cancel(&operation_id) {
+        return Err(AppError::Conflict("Operation is no longer running".to_string()));
+    }
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::User;
+use crate::utils::{
+    find_permission, has_permission, record_audit, require_admin, validate_session, PermissionDef, PERMISSIONS,
+};
+
+const ROLES: [&str; 3] = ["Admin", "Operator", "Viewer"];
+
+/// How a permission is granted to each role, and to one user when asked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionState {
+    pub key: String,
+    pub description: String,
+    pub default_roles: Vec<String>,
+    pub admin: bool,
+    pub operator: bool,
+    pub viewer: bool,
+    /// The user's own entry, overriding their role's; None when there is none
+    pub user_allowed: Option<bool>,
+    /// Whether the user ends up holding the permission
+    pub effective: Option<bool>,
+}
+
+/// The stored entry for a role (`column` "role") or user ("user_id"), if any
+fn stored_grant(
+    conn: &Connection,
+    column: &str,
+    value: &dyn rusqlite::ToSql,
+    key: &str,
+) -> Result<Option<bool>, String> {
+    conn.query_row(
+        &format!("SELECT allowed FROM role_permissions WHERE {} = ?1 AND permission = ?2", column),
+        params![value, key],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+fn find_user(conn: &Connection, id: i64) -> Result<User, String> {
+    conn.query_row("SELECT * FROM users WHERE id = ?1", [id], User::from_row)
+        .map_err(|_| "User not found".to_string())
+}
+
+fn permission_state(
+    conn: &Connection,
+    permission: &PermissionDef,
+    target: Option<&User>,
+) -> Result<PermissionState, String> {
+    let role_grant = |role: &str| -> Result<bool, String> {
+        let stored = stored_grant(conn, "role", &role, permission.key)?;
+        Ok(stored.unwrap_or_else(|| permission.default_roles.contains(&role)))
+    };
+    let (user_allowed, effective) = match target {
+        Some(target) => (
+            stored_grant(conn, "user_id", &target.id, permission.key)?,
+            Some(has_permission(conn, target, permission.key)?),
+        ),
+        None => (None, None),
+    };
+
+    Ok(PermissionState {
+        key: permission.key.to_string(),
+        description: permission.description.to_string(),
+        default_roles: permission.default_roles.iter().map(|r| r.to_string()).collect(),
+        admin: role_grant("Admin")?,
+        operator: role_grant("Operator")?,
+        viewer: role_grant("Viewer")?,
+        user_allowed,
+        effective,
+    })
+}
+
+/// Keys of the permissions the signed-in user holds, for showing or hiding actions
+#[tauri::command]
+pub fn get_my_permissions(token: String, db: State<'_, Database>) -> Result<Vec<String>, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+
+    let mut keys = Vec::new();
+    for permission in PERMISSIONS {
+        if has_permission(&conn, &user, permission.key)? {
+            keys.push(permission.key.to_string());
+        }
+    }
+    Ok(keys)
+}
+
+/// List every permission with its role grants (Admin only). With `user_id`,
+/// also show that user's override and effective access.
+#[tauri::command]
+pub fn get_permissions(
+    token: String,
+    user_id: Option<i64>,
+    db: State<'_, Database>,
+) -> Result<Vec<PermissionState>, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_admin(&conn, &user)?;
+
+    let target = user_id.map(|id| find_user(&conn, id)).transpose()?;
+    let states = PERMISSIONS
+        .iter()
+        .map(|permission| permission_state(&conn, permission, target.as_ref()))
+        .collect::<Result<_, String>>()?;
+    Ok(states)
+}
+
+/// Grant or revoke a permission for a role or a single user (Admin only).
+///
+/// Give exactly one of `role` and `user_id`. `allowed: None` removes the
+/// entry: a user falls back to their role, and a role to the built-in default.
+#[tauri::command]
+pub fn set_permission(
+    token: String,
+    permission: String,
+    role: Option<String>,
+    user_id: Option<i64>,
+    allowed: Option<bool>,
+    db: State<'_, Database>,
+) -> Result<PermissionState, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_admin(&conn, &user)?;
+
+    let definition = find_permission(&permission)
+        .ok_or_else(|| format!("Validation error: unknown permission '{}'", permission))?;
+    let target = user_id.map(|id| find_user(&conn, id)).transpose()?;
+    let (column, value): (&str, Box<dyn rusqlite::ToSql>) = match (&role, &target) {
+        (Some(role), None) if ROLES.contains(&role.as_str()) => ("role", Box::new(role.clone())),
+        (Some(role), None) => return Err(AppError::validation(format!("invalid role '{}'", role))),
+        (None, Some(target)) => ("user_id", Box::new(target.id)),
+        _ => return Err(AppError::validation("give either a role or a user")),
+    };
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        &format!("DELETE FROM role_permissions WHERE {} = ?1 AND permission = ?2", column),
+        params![value, definition.key],
+    )
+    .map_err(|e| format!("Failed to update permission: {}", e))?;
+    if let Some(allowed) = allowed {
+        tx.execute(
+            &format!("INSERT INTO role_permissions ({}, permission, allowed) VALUES (?1, ?2, ?3)", column),
+            params![value, definition.key, allowed],
+        )
+        .map_err(|e| format!("Failed to update permission: {}", e))?;
+    }
+    record_audit(
+        &tx,
+        &user,
+        "set_permission",
+        "role_permissions",
+        user_id,
+        None,
+        Some(&serde_json::json!({ "permission": definition.key, "role": role, "allowed": allowed }).to_string()),
+    );
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(permission_state(&conn, definition, target.as_ref())?)
+}
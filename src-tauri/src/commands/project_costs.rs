@@ -0,0 +1,431 @@
+use std::collections::BTreeMap;
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{CreateCostInput, ProjectCost, UpdateCostInput, COST_CATEGORIES};
+use crate::utils::{record_audit, require_permission, validate_session};
+
+/// Machine time a project used on one machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MachineCostLine {
+    pub machine_id: i64,
+    pub machine_name: String,
+    pub hours: f64,
+    /// None when the machine has no hourly rate; its hours are not costed
+    pub hourly_rate: Option<f64>,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostCategoryTotal {
+    pub category: String,
+    pub amount: f64,
+}
+
+/// What a project has cost so far against its budget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectFinancials {
+    pub project_id: i64,
+    pub project_name: String,
+    pub budget_amount: Option<f64>,
+    pub currency: Option<String>,
+    pub machines: Vec<MachineCostLine>,
+    pub machine_cost: f64,
+    /// Actual hours on machines without an hourly rate
+    pub unrated_hours: f64,
+    /// Share of maintenance on the project's machines during its window;
+    /// None unless requested
+    pub maintenance_cost: Option<f64>,
+    pub recorded_costs: Vec<CostCategoryTotal>,
+    pub recorded_cost: f64,
+    pub total_cost: f64,
+    pub budget_remaining: Option<f64>,
+    pub budget_used_percentage: Option<f64>,
+}
+
+fn validate_category(category: &str) -> Result<(), String> {
+    if COST_CATEGORIES.contains(&category) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Validation error: invalid cost category '{}'. Must be one of: {}",
+            category,
+            COST_CATEGORIES.join(", ")
+        ))
+    }
+}
+
+fn validate_amount(amount: f64) -> Result<(), String> {
+    if amount.is_finite() && amount >= 0.0 {
+        Ok(())
+    } else {
+        Err("Validation error: amount must not be negative".to_string())
+    }
+}
+
+fn validate_cost_date(date: &str) -> Result<(), String> {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|_| ())
+        .map_err(|_| format!("Validation error: invalid cost date '{}'. Use YYYY-MM-DD", date))
+}
+
+fn fetch_cost(conn: &Connection, id: i64) -> Result<ProjectCost, String> {
+    conn.query_row("SELECT * FROM project_costs WHERE id = ?1", [id], ProjectCost::from_row)
+        .map_err(|_| "Cost not found".to_string())
+}
+
+/// Maintenance cost on each machine between two dates, shared out by the
+/// project's part of all hours logged on that machine in the same dates
+fn project_maintenance_cost(
+    conn: &Connection,
+    project_id: i64,
+    machine_ids: &[i64],
+    start_date: &str,
+    end_date: &str,
+) -> Result<f64, String> {
+    let mut total = 0.0;
+    for machine_id in machine_ids {
+        let (cost, project_hours, all_hours): (f64, f64, f64) = conn
+            .query_row(
+                "SELECT
+                    (SELECT COALESCE(SUM(cost), 0) FROM maintenance
+                     WHERE machine_id = ?1 AND date >= ?3 AND date <= ?4 AND status != 'cancelled'),
+                    COALESCE(SUM(CASE WHEN project_id = ?2 THEN actual_hours END), 0),
+                    COALESCE(SUM(actual_hours), 0)
+                 FROM schedules
+                 WHERE machine_id = ?1 AND date >= ?3 AND date <= ?4 AND status != 'cancelled' AND actual_hours > 0",
+                params![machine_id, project_id, start_date, end_date],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .map_err(|e| e.to_string())?;
+        if all_hours > 0.0 {
+            total += cost * project_hours / all_hours;
+        }
+    }
+    Ok(total)
+}
+
+/// Get a project's recorded costs, newest first (Admin or Operator)
+#[tauri::command]
+pub fn get_project_costs(
+    token: String,
+    project_id: i64,
+    db: State<'_, Database>,
+) -> Result<Vec<ProjectCost>, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.costs")?;
+
+    let mut stmt = conn
+        .prepare("SELECT * FROM project_costs WHERE project_id = ?1 ORDER BY cost_date DESC, id DESC")
+        .map_err(|e| e.to_string())?;
+
+    let costs = stmt
+        .query_map([project_id], ProjectCost::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(costs)
+}
+
+/// Record an expense on a project (Admin or Operator)
+#[tauri::command]
+pub fn create_project_cost(
+    token: String,
+    project_id: i64,
+    input: CreateCostInput,
+    db: State<'_, Database>,
+) -> Result<ProjectCost, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.costs")?;
+
+    validate_category(&input.category)?;
+    validate_amount(input.amount)?;
+    if input.description.trim().is_empty() {
+        return Err(AppError::validation("cost description is required"));
+    }
+    let cost_date = input
+        .cost_date
+        .unwrap_or_else(|| chrono::Local::now().format("%Y-%m-%d").to_string());
+    validate_cost_date(&cost_date)?;
+    conn.query_row("SELECT id FROM projects WHERE id = ?1", [project_id], |row| row.get::<_, i64>(0))
+        .map_err(|_| "Project not found".to_string())?;
+
+    conn.execute(
+        "INSERT INTO project_costs (project_id, category, description, amount, cost_date, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![project_id, input.category, input.description.trim(), input.amount, cost_date, user.id],
+    )
+    .map_err(|e| format!("Failed to record cost: {}", e))?;
+
+    let id = conn.last_insert_rowid();
+    record_audit(
+        &conn,
+        &user,
+        "create",
+        "project_costs",
+        Some(id),
+        None,
+        Some(&serde_json::json!({ "category": input.category, "amount": input.amount }).to_string()),
+    );
+    Ok(fetch_cost(&conn, id)?)
+}
+
+/// Edit a recorded expense (Admin or Operator)
+#[tauri::command]
+pub fn update_project_cost(
+    token: String,
+    id: i64,
+    input: UpdateCostInput,
+    db: State<'_, Database>,
+) -> Result<ProjectCost, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.costs")?;
+
+    let existing = fetch_cost(&conn, id)?;
+    let mut updates = Vec::new();
+    let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(category) = &input.category {
+        validate_category(category)?;
+        updates.push("category = ?");
+        values.push(Box::new(category.clone()));
+    }
+    if let Some(description) = &input.description {
+        if description.trim().is_empty() {
+            return Err(AppError::validation("cost description is required"));
+        }
+        updates.push("description = ?");
+        values.push(Box::new(description.trim().to_string()));
+    }
+    if let Some(amount) = input.amount {
+        validate_amount(amount)?;
+        updates.push("amount = ?");
+        values.push(Box::new(amount));
+    }
+    if let Some(cost_date) = &input.cost_date {
+        validate_cost_date(cost_date)?;
+        updates.push("cost_date = ?");
+        values.push(Box::new(cost_date.clone()));
+    }
+
+    if updates.is_empty() {
+        return Ok(existing);
+    }
+
+    updates.push("updated_at = CURRENT_TIMESTAMP");
+    let query = format!("UPDATE project_costs SET {} WHERE id = ?", updates.join(", "));
+    values.push(Box::new(id));
+
+    let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+    conn.execute(&query, params.as_slice())
+        .map_err(|e| format!("Failed to update cost: {}", e))?;
+
+    if let Some(amount) = input.amount.filter(|a| *a != existing.amount) {
+        record_audit(
+            &conn,
+            &user,
+            "update",
+            "project_costs",
+            Some(id),
+            Some(&existing.amount.to_string()),
+            Some(&amount.to_string()),
+        );
+    }
+    Ok(fetch_cost(&conn, id)?)
+}
+
+/// Delete a recorded expense (Admin or Operator)
+#[tauri::command]
+pub fn delete_project_cost(token: String, id: i64, db: State<'_, Database>) -> Result<(), AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.costs")?;
+
+    let existing = fetch_cost(&conn, id)?;
+    conn.execute("DELETE FROM project_costs WHERE id = ?1", [id])
+        .map_err(|e| format!("Failed to delete cost: {}", e))?;
+    record_audit(
+        &conn,
+        &user,
+        "delete",
+        "project_costs",
+        Some(id),
+        Some(&serde_json::json!({ "description": existing.description, "amount": existing.amount }).to_string()),
+        None,
+    );
+    Ok(())
+}
+
+/// Set or clear (`budget_amount` = None) a project's budget (Admin only).
+/// Currency is a three-letter code such as INR or USD.
+#[tauri::command]
+pub fn set_project_budget(
+    token: String,
+    id: i64,
+    budget_amount: Option<f64>,
+    currency: Option<String>,
+    db: State<'_, Database>,
+) -> Result<ProjectFinancials, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.budget")?;
+
+    if let Some(amount) = budget_amount {
+        validate_amount(amount)?;
+    }
+    let currency = currency.map(|c| c.trim().to_uppercase()).filter(|c| !c.is_empty());
+    if let Some(code) = &currency {
+        if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(AppError::validation(format!("invalid currency '{}'. Use a three-letter code", code)));
+        }
+    }
+
+    let old: (Option<f64>, Option<String>) = conn
+        .query_row("SELECT budget_amount, currency FROM projects WHERE id = ?1", [id], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .map_err(|_| "Project not found".to_string())?;
+    conn.execute(
+        "UPDATE projects SET budget_amount = ?1, currency = COALESCE(?2, currency), updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?3",
+        params![budget_amount, currency, id],
+    )
+    .map_err(|e| format!("Failed to set budget: {}", e))?;
+    record_audit(
+        &conn,
+        &user,
+        "set_budget",
+        "projects",
+        Some(id),
+        Some(&serde_json::json!({ "budget_amount": old.0, "currency": old.1 }).to_string()),
+        Some(&serde_json::json!({ "budget_amount": budget_amount, "currency": currency.as_ref().or(old.1.as_ref()) }).to_string()),
+    );
+
+    Ok(project_financials(&conn, id, false)?)
+}
+
+fn project_financials(conn: &Connection, project_id: i64, include_maintenance: bool) -> Result<ProjectFinancials, String> {
+    let (project_name, budget_amount, currency, start_date, end_date): (
+        String,
+        Option<f64>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+    ) = conn
+        .query_row(
+            "SELECT name, budget_amount, currency, start_date, COALESCE(actual_completion_date, end_date)
+             FROM projects WHERE id = ?1",
+            [project_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )
+        .map_err(|_| "Project not found".to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT m.id, m.name, m.hourly_rate, SUM(s.actual_hours)
+             FROM schedules s
+             JOIN machines m ON s.machine_id = m.id
+             WHERE s.project_id = ?1 AND s.status != 'cancelled' AND s.actual_hours > 0
+             GROUP BY m.id
+             ORDER BY m.name",
+        )
+        .map_err(|e| e.to_string())?;
+    let machines: Vec<MachineCostLine> = stmt
+        .query_map([project_id], |row| {
+            let hourly_rate = row.get::<_, Option<f64>>(2)?.filter(|rate| *rate > 0.0);
+            let hours: f64 = row.get(3)?;
+            Ok(MachineCostLine {
+                machine_id: row.get(0)?,
+                machine_name: row.get(1)?,
+                hours,
+                hourly_rate,
+                cost: hourly_rate.map(|rate| rate * hours).unwrap_or(0.0),
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    // The window runs from the project's start (or first logged day) to its
+    // completion or end date (or last logged day)
+    let maintenance_cost = if include_maintenance {
+        let (first_day, last_day): (Option<String>, Option<String>) = conn
+            .query_row(
+                "SELECT MIN(date), MAX(date) FROM schedules
+                 WHERE project_id = ?1 AND status != 'cancelled' AND actual_hours > 0",
+                [project_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| e.to_string())?;
+        let machine_ids: Vec<i64> = machines.iter().map(|m| m.machine_id).collect();
+        match (start_date.or(first_day), end_date.or(last_day)) {
+            (Some(start), Some(end)) => Some(project_maintenance_cost(conn, project_id, &machine_ids, &start, &end)?),
+            _ => Some(0.0),
+        }
+    } else {
+        None
+    };
+
+    let mut stmt = conn
+        .prepare("SELECT category, SUM(amount) FROM project_costs WHERE project_id = ?1 GROUP BY category")
+        .map_err(|e| e.to_string())?;
+    let by_category: BTreeMap<String, f64> = stmt
+        .query_map([project_id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    let recorded_costs: Vec<CostCategoryTotal> = by_category
+        .into_iter()
+        .map(|(category, amount)| CostCategoryTotal { category, amount })
+        .collect();
+
+    let machine_cost: f64 = machines.iter().map(|m| m.cost).sum();
+    let unrated_hours: f64 = machines.iter().filter(|m| m.hourly_rate.is_none()).map(|m| m.hours).sum();
+    let recorded_cost: f64 = recorded_costs.iter().map(|c| c.amount).sum();
+    let total_cost = machine_cost + maintenance_cost.unwrap_or(0.0) + recorded_cost;
+
+    Ok(ProjectFinancials {
+        project_id,
+        project_name,
+        budget_remaining: budget_amount.map(|budget| budget - total_cost),
+        budget_used_percentage: budget_amount
+            .filter(|budget| *budget > 0.0)
+            .map(|budget| total_cost / budget * 100.0),
+        budget_amount,
+        currency,
+        machines,
+        machine_cost,
+        unrated_hours,
+        maintenance_cost,
+        recorded_costs,
+        recorded_cost,
+        total_cost,
+    })
+}
+
+/// Cost of a project so far against its budget (Admin or Operator).
+///
+/// Machine cost is actual hours times each machine's hourly rate. With
+/// `include_maintenance`, maintenance on those machines during the project is
+/// shared out by the project's part of their hours, as in the job cost report.
+#[tauri::command]
+pub fn get_project_financials(
+    token: String,
+    project_id: i64,
+    include_maintenance: Option<bool>,
+    db: State<'_, Database>,
+) -> Result<ProjectFinancials, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.costs")?;
+
+    Ok(project_financials(&conn, project_id, include_maintenance.unwrap_or(false))?)
+}
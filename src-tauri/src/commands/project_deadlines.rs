@@ -0,0 +1,147 @@
+use chrono::NaiveDate;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::Project;
+use crate::utils::{require_view_permission, validate_session};
+
+const DEADLINE_ALERT_TITLE: &str = "Project deadline at risk";
+/// Days before the end date that a project behind schedule is flagged
+const DEADLINE_WARNING_DAYS: i64 = 7;
+/// Progress below which a project nearing its end date is flagged
+const DEADLINE_PROGRESS_THRESHOLD: f64 = 80.0;
+/// An unchanged risk is raised again after this many days
+const DEADLINE_REALERT_DAYS: i64 = 7;
+
+/// An active project at risk of missing its end date
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadlineRisk {
+    pub project_id: i64,
+    pub project_name: String,
+    pub end_date: String,
+    /// Negative once the end date has passed
+    pub days_remaining: i64,
+    pub progress_percentage: f64,
+    /// Share of the start-to-end calendar span already gone
+    pub elapsed_percentage: Option<f64>,
+    /// "high" when due soon and behind, "critical" when overdue
+    pub priority: String,
+    /// Whether this check raised a new alert
+    pub alerted: bool,
+}
+
+fn parse_date(date: Option<&str>) -> Option<NaiveDate> {
+    date.and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+}
+
+/// Active projects overdue or due within a week with under 80% progress,
+/// soonest first
+pub fn find_deadline_risks(conn: &Connection) -> Result<Vec<DeadlineRisk>, String> {
+    let today = chrono::Local::now().date_naive();
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM projects
+             WHERE status = 'active' AND archived_at IS NULL AND end_date IS NOT NULL
+             ORDER BY end_date, name",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let projects: Vec<Project> = stmt
+        .query_map([], Project::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let risks = projects
+        .into_iter()
+        .filter_map(|project| {
+            let end = parse_date(project.end_date.as_deref())?;
+            let days_remaining = (end - today).num_days();
+            let progress = project.effective_progress();
+            let priority = if days_remaining < 0 {
+                "critical"
+            } else if days_remaining <= DEADLINE_WARNING_DAYS && progress < DEADLINE_PROGRESS_THRESHOLD {
+                "high"
+            } else {
+                return None;
+            };
+            let elapsed_percentage = parse_date(project.start_date.as_deref())
+                .filter(|start| *start < end)
+                .map(|start| ((today - start).num_days() as f64 / (end - start).num_days() as f64 * 100.0).clamp(0.0, 100.0));
+
+            Some(DeadlineRisk {
+                project_id: project.id,
+                project_name: project.name,
+                end_date: end.format("%Y-%m-%d").to_string(),
+                days_remaining,
+                progress_percentage: progress,
+                elapsed_percentage,
+                priority: priority.to_string(),
+                alerted: false,
+            })
+        })
+        .collect();
+
+    Ok(risks)
+}
+
+/// Raise a schedule alert for each project at risk. A project is alerted
+/// again only when its risk escalates or a week after its last alert.
+pub fn raise_deadline_alerts(conn: &Connection) -> Result<Vec<DeadlineRisk>, String> {
+    let today = chrono::Local::now().date_naive();
+    let mut risks = find_deadline_risks(conn)?;
+    for risk in &mut risks {
+        let (last_date, last_priority): (Option<String>, Option<String>) = conn
+            .query_row(
+                "SELECT deadline_alert_date, deadline_alert_priority FROM projects WHERE id = ?1",
+                [risk.project_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| e.to_string())?;
+        let recent = parse_date(last_date.as_deref())
+            .map(|d| (today - d).num_days() < DEADLINE_REALERT_DAYS)
+            .unwrap_or(false);
+        if recent && last_priority.as_deref() == Some(risk.priority.as_str()) {
+            continue;
+        }
+
+        let message = if risk.days_remaining < 0 {
+            format!(
+                "{} passed its end date of {} {} day(s) ago at {:.0}% progress",
+                risk.project_name, risk.end_date, -risk.days_remaining, risk.progress_percentage
+            )
+        } else {
+            format!(
+                "{} is due on {} ({} day(s) left) but is only {:.0}% complete",
+                risk.project_name, risk.end_date, risk.days_remaining, risk.progress_percentage
+            )
+        };
+        conn.execute(
+            "INSERT INTO alerts (alert_type, priority, title, message, project_id)
+             VALUES ('schedule', ?1, ?2, ?3, ?4)",
+            params![risk.priority, DEADLINE_ALERT_TITLE, message, risk.project_id],
+        )
+        .map_err(|e| format!("Failed to create alert: {}", e))?;
+        conn.execute(
+            "UPDATE projects SET deadline_alert_date = ?1, deadline_alert_priority = ?2 WHERE id = ?3",
+            params![today.format("%Y-%m-%d").to_string(), risk.priority, risk.project_id],
+        )
+        .map_err(|e| e.to_string())?;
+        risk.alerted = true;
+    }
+    Ok(risks)
+}
+
+/// List active projects at risk of missing their end date, raising alerts
+/// for any not flagged recently
+#[tauri::command]
+pub fn check_project_deadlines(token: String, db: State<'_, Database>) -> Result<Vec<DeadlineRisk>, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_view_permission(&user)?;
+
+    Ok(raise_deadline_alerts(&conn)?)
+}
@@ -0,0 +1,289 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::commands::alerts::UNREAD_BY_ANYONE;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{CreateMilestoneInput, MilestoneSummary, ProjectMilestone, UpdateMilestoneInput};
+use crate::utils::{record_audit, require_permission, require_view_permission, validate_session};
+
+const OVERDUE_ALERT_TITLE: &str = "Milestone overdue";
+
+/// An open milestone whose due date has passed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverdueMilestone {
+    #[serde(flatten)]
+    pub milestone: ProjectMilestone,
+    pub project_name: String,
+    pub days_overdue: i64,
+}
+
+fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+fn validate_due_date(date: &str) -> Result<(), String> {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|_| ())
+        .map_err(|_| format!("Validation error: invalid due date '{}'. Use YYYY-MM-DD", date))
+}
+
+fn fetch_milestone(conn: &Connection, id: i64) -> Result<ProjectMilestone, String> {
+    conn.query_row("SELECT * FROM project_milestones WHERE id = ?1", [id], ProjectMilestone::from_row)
+        .map_err(|_| "Milestone not found".to_string())
+}
+
+/// Totals and the next open milestone for a project
+pub(crate) fn milestone_summary(conn: &Connection, project_id: i64) -> Result<MilestoneSummary, String> {
+    let (total, completed, overdue): (i64, i64, i64) = conn
+        .query_row(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(completed_at IS NOT NULL), 0),
+                    COALESCE(SUM(completed_at IS NULL AND due_date < ?2), 0)
+             FROM project_milestones WHERE project_id = ?1",
+            params![project_id, today()],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|e| e.to_string())?;
+    let next_due = conn
+        .query_row(
+            "SELECT * FROM project_milestones
+             WHERE project_id = ?1 AND completed_at IS NULL AND due_date IS NOT NULL
+             ORDER BY due_date, sort_order LIMIT 1",
+            [project_id],
+            ProjectMilestone::from_row,
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    Ok(MilestoneSummary { total, completed, overdue, next_due })
+}
+
+/// Open milestones past their due date, oldest first
+pub fn find_overdue_milestones(conn: &Connection) -> Result<Vec<OverdueMilestone>, String> {
+    let today = chrono::Local::now().date_naive();
+    let mut stmt = conn
+        .prepare(
+            "SELECT pm.*, p.name as project_name FROM project_milestones pm
+             JOIN projects p ON pm.project_id = p.id
+             WHERE pm.completed_at IS NULL AND pm.due_date < ?1
+             ORDER BY pm.due_date, p.name",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let overdue = stmt
+        .query_map([today.format("%Y-%m-%d").to_string()], |row| {
+            let milestone = ProjectMilestone::from_row(row)?;
+            let days_overdue = milestone
+                .due_date
+                .as_deref()
+                .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+                .map(|d| (today - d).num_days())
+                .unwrap_or(0);
+            Ok(OverdueMilestone { milestone, project_name: row.get("project_name")?, days_overdue })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(overdue)
+}
+
+/// Raise a schedule alert for each overdue milestone, unless its project
+/// already has an unread one naming it. Returns the overdue milestones.
+pub fn raise_overdue_milestone_alerts(conn: &Connection) -> Result<Vec<OverdueMilestone>, String> {
+    let overdue = find_overdue_milestones(conn)?;
+    for item in &overdue {
+        let message = format!(
+            "Milestone '{}' of {} was due on {}",
+            item.milestone.title,
+            item.project_name,
+            item.milestone.due_date.as_deref().unwrap_or_default()
+        );
+        let pending: i64 = conn
+            .query_row(
+                &format!(
+                    "SELECT COUNT(*) FROM alerts WHERE title = ?1 AND project_id = ?2 AND message = ?3 AND {}",
+                    UNREAD_BY_ANYONE
+                ),
+                params![OVERDUE_ALERT_TITLE, item.milestone.project_id, message],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if pending > 0 {
+            continue;
+        }
+
+        conn.execute(
+            "INSERT INTO alerts (alert_type, priority, title, message, project_id)
+             VALUES ('schedule', 'medium', ?1, ?2, ?3)",
+            params![OVERDUE_ALERT_TITLE, message, item.milestone.project_id],
+        )
+        .map_err(|e| format!("Failed to create alert: {}", e))?;
+    }
+    Ok(overdue)
+}
+
+/// Get a project's milestones in display order
+#[tauri::command]
+pub fn get_project_milestones(
+    token: String,
+    project_id: i64,
+    db: State<'_, Database>,
+) -> Result<Vec<ProjectMilestone>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let mut stmt = conn
+        .prepare("SELECT * FROM project_milestones WHERE project_id = ?1 ORDER BY sort_order, due_date, id")
+        .map_err(|e| e.to_string())?;
+
+    let milestones = stmt
+        .query_map([project_id], ProjectMilestone::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(milestones)
+}
+
+/// Add a milestone to a project (Admin or Operator)
+#[tauri::command]
+pub fn create_project_milestone(
+    token: String,
+    project_id: i64,
+    input: CreateMilestoneInput,
+    db: State<'_, Database>,
+) -> Result<ProjectMilestone, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.details")?;
+
+    if input.title.trim().is_empty() {
+        return Err(AppError::validation("milestone title is required"));
+    }
+    if let Some(due_date) = &input.due_date {
+        validate_due_date(due_date)?;
+    }
+    conn.query_row("SELECT id FROM projects WHERE id = ?1", [project_id], |row| row.get::<_, i64>(0))
+        .map_err(|_| "Project not found".to_string())?;
+
+    let sort_order = match input.sort_order {
+        Some(order) => order,
+        None => conn
+            .query_row(
+                "SELECT COALESCE(MAX(sort_order) + 1, 0) FROM project_milestones WHERE project_id = ?1",
+                [project_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?,
+    };
+
+    conn.execute(
+        "INSERT INTO project_milestones (project_id, title, due_date, sort_order, notes)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![project_id, input.title.trim(), input.due_date, sort_order, input.notes],
+    )
+    .map_err(|e| format!("Failed to create milestone: {}", e))?;
+
+    let id = conn.last_insert_rowid();
+    record_audit(&conn, &user, "create", "project_milestones", Some(id), None, Some(input.title.trim()));
+    Ok(fetch_milestone(&conn, id)?)
+}
+
+/// Edit, complete or reopen a milestone (Admin or Operator)
+#[tauri::command]
+pub fn update_project_milestone(
+    token: String,
+    id: i64,
+    input: UpdateMilestoneInput,
+    db: State<'_, Database>,
+) -> Result<ProjectMilestone, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.details")?;
+
+    let existing = fetch_milestone(&conn, id)?;
+    let mut updates = Vec::new();
+    let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(title) = &input.title {
+        if title.trim().is_empty() {
+            return Err(AppError::validation("milestone title is required"));
+        }
+        updates.push("title = ?");
+        values.push(Box::new(title.trim().to_string()));
+    }
+    if input.clear_due_date.unwrap_or(false) {
+        updates.push("due_date = NULL");
+    } else if let Some(due_date) = &input.due_date {
+        validate_due_date(due_date)?;
+        updates.push("due_date = ?");
+        values.push(Box::new(due_date.clone()));
+    }
+    match input.completed {
+        // Completing twice keeps the original completion time
+        Some(true) if existing.completed_at.is_none() => updates.push("completed_at = CURRENT_TIMESTAMP"),
+        Some(false) => updates.push("completed_at = NULL"),
+        _ => {}
+    }
+    if let Some(sort_order) = input.sort_order {
+        updates.push("sort_order = ?");
+        values.push(Box::new(sort_order));
+    }
+    if let Some(notes) = &input.notes {
+        updates.push("notes = ?");
+        values.push(Box::new(notes.clone()));
+    }
+
+    if updates.is_empty() {
+        return Ok(existing);
+    }
+
+    updates.push("updated_at = CURRENT_TIMESTAMP");
+    let query = format!("UPDATE project_milestones SET {} WHERE id = ?", updates.join(", "));
+    values.push(Box::new(id));
+
+    let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+    conn.execute(&query, params.as_slice())
+        .map_err(|e| format!("Failed to update milestone: {}", e))?;
+
+    if let Some(completed) = input.completed {
+        record_audit(
+            &conn,
+            &user,
+            if completed { "complete" } else { "reopen" },
+            "project_milestones",
+            Some(id),
+            None,
+            Some(&existing.title),
+        );
+    }
+    Ok(fetch_milestone(&conn, id)?)
+}
+
+/// Delete a milestone (Admin or Operator)
+#[tauri::command]
+pub fn delete_project_milestone(token: String, id: i64, db: State<'_, Database>) -> Result<(), AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.details")?;
+
+    let existing = fetch_milestone(&conn, id)?;
+    conn.execute("DELETE FROM project_milestones WHERE id = ?1", [id])
+        .map_err(|e| format!("Failed to delete milestone: {}", e))?;
+    record_audit(&conn, &user, "delete", "project_milestones", Some(id), Some(&existing.title), None);
+    Ok(())
+}
+
+/// List overdue milestones, raising an alert for any not yet flagged
+#[tauri::command]
+pub fn check_overdue_milestones(token: String, db: State<'_, Database>) -> Result<Vec<OverdueMilestone>, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_view_permission(&user)?;
+
+    Ok(raise_overdue_milestone_alerts(&conn)?)
+}
@@ -0,0 +1,119 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::ProjectNote;
+use crate::utils::{
+    count_rows, record_audit, require_permission, require_view_permission, validate_session, PageRequest, Paginated,
+};
+
+const NOTE_SELECT: &str = "SELECT n.*, COALESCE(u.full_name, u.username) as user_name
+     FROM project_notes n
+     LEFT JOIN users u ON n.user_id = u.id";
+
+fn fetch_note(conn: &Connection, id: i64) -> Result<ProjectNote, String> {
+    conn.query_row(&format!("{} WHERE n.id = ?1", NOTE_SELECT), [id], ProjectNote::from_row)
+        .map_err(|_| "Note not found".to_string())
+}
+
+/// Append a note to a project's timeline, returning its id
+pub(crate) fn insert_project_note(conn: &Connection, project_id: i64, user_id: i64, note: &str) -> Result<i64, String> {
+    conn.execute(
+        "INSERT INTO project_notes (project_id, user_id, note) VALUES (?1, ?2, ?3)",
+        params![project_id, user_id, note],
+    )
+    .map_err(|e| format!("Failed to add note: {}", e))?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Number of notes on a project and the most recent one
+pub(crate) fn note_summary(conn: &Connection, project_id: i64) -> Result<(i64, Option<ProjectNote>), String> {
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM project_notes WHERE project_id = ?1", [project_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    let latest = conn
+        .query_row(
+            &format!("{} WHERE n.project_id = ?1 ORDER BY n.created_at DESC, n.id DESC LIMIT 1", NOTE_SELECT),
+            [project_id],
+            ProjectNote::from_row,
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    Ok((count, latest))
+}
+
+/// Get a page of a project's notes, newest first
+#[tauri::command]
+pub fn get_project_notes(
+    token: String,
+    project_id: i64,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    db: State<'_, Database>,
+) -> Result<Paginated<ProjectNote>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let page = PageRequest::resolve(&conn, limit, offset, Some(50))?;
+    let total = count_rows(&conn, "SELECT COUNT(*) FROM project_notes WHERE project_id = ?1", &[&project_id])?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "{} WHERE n.project_id = ? ORDER BY n.created_at DESC, n.id DESC{}",
+            NOTE_SELECT,
+            page.sql()
+        ))
+        .map_err(|e| e.to_string())?;
+    let notes = stmt
+        .query_map(params![project_id, page.limit, page.offset], ProjectNote::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(Paginated::new(notes, page, total))
+}
+
+/// Add a note to a project (Admin or Operator)
+#[tauri::command]
+pub fn add_project_note(
+    token: String,
+    project_id: i64,
+    note: String,
+    db: State<'_, Database>,
+) -> Result<ProjectNote, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.details")?;
+
+    let note = note.trim();
+    if note.is_empty() {
+        return Err(AppError::validation("note text is required"));
+    }
+    conn.query_row("SELECT id FROM projects WHERE id = ?1", [project_id], |row| row.get::<_, i64>(0))
+        .map_err(|_| "Project not found".to_string())?;
+
+    let id = insert_project_note(&conn, project_id, user.id, note)?;
+    Ok(fetch_note(&conn, id)?)
+}
+
+/// Delete a note. Only its author or an Admin may do this.
+#[tauri::command]
+pub fn delete_project_note(token: String, id: i64, db: State<'_, Database>) -> Result<(), AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_view_permission(&user)?;
+
+    let existing = fetch_note(&conn, id)?;
+    if !user.is_admin() && existing.user_id != Some(user.id) {
+        return Err(AppError::Forbidden(
+            "Permission denied. Only the note's author or an Admin can delete it".to_string(),
+        ));
+    }
+
+    conn.execute("DELETE FROM project_notes WHERE id = ?1", [id])
+        .map_err(|e| format!("Failed to delete note: {}", e))?;
+    record_audit(&conn, &user, "delete", "project_notes", Some(id), Some(&existing.note), None);
+    Ok(())
+}
@@ -0,0 +1,194 @@
+use rusqlite::{params, Connection};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{CreateTaskInput, ProjectTask, TaskHoursSummary, UpdateTaskInput, TASK_STATUSES};
+use crate::utils::{record_audit, require_permission, require_view_permission, validate_session};
+
+fn validate_task_status(status: &str) -> Result<(), String> {
+    if TASK_STATUSES.contains(&status) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Validation error: invalid task status '{}'. Must be one of: {}",
+            status,
+            TASK_STATUSES.join(", ")
+        ))
+    }
+}
+
+fn validate_planned_hours(hours: f64) -> Result<(), String> {
+    if hours.is_finite() && hours >= 0.0 {
+        Ok(())
+    } else {
+        Err("Validation error: planned hours must not be negative".to_string())
+    }
+}
+
+fn fetch_task(conn: &Connection, id: i64) -> Result<ProjectTask, String> {
+    conn.query_row("SELECT * FROM project_tasks WHERE id = ?1", [id], ProjectTask::from_row)
+        .map_err(|_| "Task not found".to_string())
+}
+
+/// Planned and logged hours across a project's tasks
+pub(crate) fn task_hours_summary(conn: &Connection, project_id: i64) -> Result<TaskHoursSummary, String> {
+    conn.query_row(
+        "SELECT COUNT(*),
+                COALESCE(SUM(status = 'completed'), 0),
+                COALESCE(SUM(planned_hours), 0),
+                COALESCE(SUM(actual_hours), 0)
+         FROM project_tasks WHERE project_id = ?1",
+        [project_id],
+        |row| {
+            Ok(TaskHoursSummary {
+                task_count: row.get(0)?,
+                completed_tasks: row.get(1)?,
+                planned_hours: row.get(2)?,
+                actual_hours: row.get(3)?,
+            })
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Get a project's tasks in display order
+#[tauri::command]
+pub fn get_project_tasks(
+    token: String,
+    project_id: i64,
+    db: State<'_, Database>,
+) -> Result<Vec<ProjectTask>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let mut stmt = conn
+        .prepare("SELECT * FROM project_tasks WHERE project_id = ?1 ORDER BY sort_order, id")
+        .map_err(|e| e.to_string())?;
+
+    let tasks = stmt
+        .query_map([project_id], ProjectTask::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(tasks)
+}
+
+/// Add a task to a project (Admin or Operator)
+#[tauri::command]
+pub fn create_project_task(
+    token: String,
+    project_id: i64,
+    input: CreateTaskInput,
+    db: State<'_, Database>,
+) -> Result<ProjectTask, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.details")?;
+
+    if input.name.trim().is_empty() {
+        return Err(AppError::validation("task name is required"));
+    }
+    let status = input.status.unwrap_or_else(|| "planning".to_string());
+    validate_task_status(&status)?;
+    let planned_hours = input.planned_hours.unwrap_or(0.0);
+    validate_planned_hours(planned_hours)?;
+    conn.query_row("SELECT id FROM projects WHERE id = ?1", [project_id], |row| row.get::<_, i64>(0))
+        .map_err(|_| "Project not found".to_string())?;
+
+    let sort_order = match input.sort_order {
+        Some(order) => order,
+        None => conn
+            .query_row(
+                "SELECT COALESCE(MAX(sort_order) + 1, 0) FROM project_tasks WHERE project_id = ?1",
+                [project_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?,
+    };
+
+    conn.execute(
+        "INSERT INTO project_tasks (project_id, name, status, planned_hours, sort_order)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![project_id, input.name.trim(), status, planned_hours, sort_order],
+    )
+    .map_err(|e| format!("Failed to create task: {}", e))?;
+
+    let id = conn.last_insert_rowid();
+    record_audit(&conn, &user, "create", "project_tasks", Some(id), None, Some(input.name.trim()));
+    Ok(fetch_task(&conn, id)?)
+}
+
+/// Rename, re-plan or change the status of a task (Admin or Operator).
+/// Actual hours come from the schedule and cannot be set here.
+#[tauri::command]
+pub fn update_project_task(
+    token: String,
+    id: i64,
+    input: UpdateTaskInput,
+    db: State<'_, Database>,
+) -> Result<ProjectTask, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.details")?;
+
+    let existing = fetch_task(&conn, id)?;
+    let mut updates = Vec::new();
+    let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(name) = &input.name {
+        if name.trim().is_empty() {
+            return Err(AppError::validation("task name is required"));
+        }
+        updates.push("name = ?");
+        values.push(Box::new(name.trim().to_string()));
+    }
+    if let Some(status) = &input.status {
+        validate_task_status(status)?;
+        updates.push("status = ?");
+        values.push(Box::new(status.clone()));
+    }
+    if let Some(planned_hours) = input.planned_hours {
+        validate_planned_hours(planned_hours)?;
+        updates.push("planned_hours = ?");
+        values.push(Box::new(planned_hours));
+    }
+    if let Some(sort_order) = input.sort_order {
+        updates.push("sort_order = ?");
+        values.push(Box::new(sort_order));
+    }
+
+    if updates.is_empty() {
+        return Ok(existing);
+    }
+
+    updates.push("updated_at = CURRENT_TIMESTAMP");
+    let query = format!("UPDATE project_tasks SET {} WHERE id = ?", updates.join(", "));
+    values.push(Box::new(id));
+
+    let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+    conn.execute(&query, params.as_slice())
+        .map_err(|e| format!("Failed to update task: {}", e))?;
+
+    if let Some(status) = input.status.filter(|s| *s != existing.status) {
+        record_audit(&conn, &user, "update_status", "project_tasks", Some(id), Some(&existing.status), Some(&status));
+    }
+    Ok(fetch_task(&conn, id)?)
+}
+
+/// Delete a task (Admin or Operator). Its schedule entries stay on the
+/// project and are unlinked from the task.
+#[tauri::command]
+pub fn delete_project_task(token: String, id: i64, db: State<'_, Database>) -> Result<(), AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.details")?;
+
+    let existing = fetch_task(&conn, id)?;
+    conn.execute("DELETE FROM project_tasks WHERE id = ?1", [id])
+        .map_err(|e| format!("Failed to delete task: {}", e))?;
+    record_audit(&conn, &user, "delete", "project_tasks", Some(id), Some(&existing.name), None);
+    Ok(())
+}
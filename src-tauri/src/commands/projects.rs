@@ -1,84 +1,258 @@
-use rusqlite::params;
+use std::collections::HashMap;
+
+use rusqlite::{params, Connection};
 use tauri::State;
 
+use crate::commands::clients::ensure_client_not_archived;
+use crate::commands::integrity::{confirm_delete_impact, project_delete_impact, CascadeEffect};
+use crate::commands::machines::ensure_machine_not_archived;
+use crate::commands::project_milestones::milestone_summary;
+use crate::commands::project_notes::{insert_project_note, note_summary};
+use crate::commands::project_tasks::task_hours_summary;
+use crate::commands::schedules::fetch_schedule_with_details;
+use crate::commands::search::escape_like;
+use crate::commands::status_transitions::{check_transition, ENTITY_PROJECT};
+use crate::commands::users::ensure_user_active;
 use crate::db::Database;
-use crate::models::{CreateProjectInput, Project, ProjectWithDetails, UpdateProjectInput};
-use crate::utils::{require_admin, require_edit_permission, require_view_permission, validate_session};
+use crate::error::AppError;
+use crate::models::{
+    computed_progress, validate_project_priority, CreateProjectInput, Project, ProjectFilter, ProjectHourLog,
+    ProjectMachineHours, ProjectTeamMember, ProjectWithDetails, ScheduleWithDetails, TeamMemberInput,
+    UpdateProjectInput, User, UserPublic, PROJECT_PRIORITY_RANK, SOURCE_AUTO, TEAM_ROLES,
+};
+use crate::utils::{
+    audit_json, count_rows, record_audit, record_audit_update, require_permission, require_view_permission,
+    validate_session, MutationResult, PageRequest, Paginated,
+};
 
-#[allow(unused_imports)]
 use chrono::Local;
 
-/// Get all projects
-#[tauri::command]
-pub fn get_projects(token: String, db: State<'_, Database>) -> Result<Vec<ProjectWithDetails>, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
-    require_view_permission(&user)?;
+/// Refuse new work on an archived project
+pub(crate) fn ensure_project_not_archived(conn: &Connection, project_id: i64) -> Result<(), String> {
+    let (name, archived_at): (String, Option<String>) = conn
+        .query_row(
+            "SELECT name, archived_at FROM projects WHERE id = ?1",
+            [project_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| "Project not found".to_string())?;
+    if archived_at.is_some() {
+        return Err(format!("Project '{}' is archived", name));
+    }
+    Ok(())
+}
+
+fn project_order_by(sort_by: &str, order: Option<&str>) -> Result<String, String> {
+    if sort_by == "priority" {
+        let direction = match order.unwrap_or("asc") {
+            "asc" => "ASC",
+            "desc" => "DESC",
+            other => return Err(format!("Validation error: order must be asc or desc, not '{}'", other)),
+        };
+        return Ok(format!("{PROJECT_PRIORITY_RANK} {direction}, p.end_date IS NULL, p.end_date ASC, p.id DESC"));
+    }
+    let column = match sort_by {
+        "name" => "p.name",
+        "status" => "p.status",
+        "start_date" => "p.start_date",
+        "end_date" => "p.end_date",
+        "created_at" => "p.created_at",
+        _ => return Err(format!("Validation error: cannot sort projects by '{}'", sort_by)),
+    };
+    let default_order = if sort_by == "created_at" { "desc" } else { "asc" };
+    let direction = match order.unwrap_or(default_order) {
+        "asc" => "ASC",
+        "desc" => "DESC",
+        other => return Err(format!("Validation error: order must be asc or desc, not '{}'", other)),
+    };
+    Ok(format!("{column} IS NULL, {column} {direction}, p.id DESC"))
+}
 
+/// Map each project id to the ids in `column` of its rows in a link table,
+/// in one query for the whole page
+fn linked_ids(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    project_ids: &[i64],
+) -> Result<HashMap<i64, Vec<i64>>, String> {
+    let mut linked: HashMap<i64, Vec<i64>> = HashMap::new();
+    if project_ids.is_empty() {
+        return Ok(linked);
+    }
+    let placeholders = vec!["?"; project_ids.len()].join(", ");
     let mut stmt = conn
-        .prepare(
-            "SELECT p.*, c.name as client_name FROM projects p
-             LEFT JOIN clients c ON p.client_id = c.id
-             ORDER BY p.created_at DESC",
-        )
+        .prepare(&format!(
+            "SELECT project_id, {} FROM {} WHERE project_id IN ({})",
+            column, table, placeholders
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(project_ids), |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))
+        })
+        .map_err(|e| e.to_string())?;
+    for (project_id, id) in rows.filter_map(|r| r.ok()) {
+        linked.entry(project_id).or_default().push(id);
+    }
+    Ok(linked)
+}
+
+/// Map each project id to its team members and their roles
+fn team_roles(conn: &Connection, project_ids: &[i64]) -> Result<HashMap<i64, Vec<ProjectTeamMember>>, String> {
+    let mut teams: HashMap<i64, Vec<ProjectTeamMember>> = HashMap::new();
+    if project_ids.is_empty() {
+        return Ok(teams);
+    }
+    let placeholders = vec!["?"; project_ids.len()].join(", ");
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT project_id, user_id, COALESCE(role, 'member') FROM project_team
+             WHERE project_id IN ({}) ORDER BY role = 'lead' DESC, assigned_at, id",
+            placeholders
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(project_ids), |row| {
+            Ok((row.get::<_, i64>(0)?, ProjectTeamMember { user_id: row.get(1)?, role: row.get(2)? }))
+        })
+        .map_err(|e| e.to_string())?;
+    for (project_id, member) in rows.filter_map(|r| r.ok()) {
+        teams.entry(project_id).or_default().push(member);
+    }
+    Ok(teams)
+}
+
+/// One filtered page of projects with their details
+fn load_projects(conn: &Connection, filter: &ProjectFilter) -> Result<Paginated<ProjectWithDetails>, String> {
+    let mut query = String::from(" FROM projects p LEFT JOIN clients c ON p.client_id = c.id WHERE 1=1");
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if !filter.include_archived.unwrap_or(false) {
+        query.push_str(" AND p.archived_at IS NULL");
+    }
+    if let Some(ref status) = filter.status {
+        query.push_str(" AND p.status = ?");
+        params_vec.push(Box::new(status.clone()));
+    }
+    if let Some(ref priority) = filter.priority {
+        validate_project_priority(priority)?;
+        query.push_str(" AND p.priority = ?");
+        params_vec.push(Box::new(priority.clone()));
+    }
+    if let Some(client_id) = filter.client_id {
+        query.push_str(" AND p.client_id = ?");
+        params_vec.push(Box::new(client_id));
+    }
+    if let Some(search) = filter.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        query.push_str(" AND p.name LIKE ? ESCAPE '\\'");
+        params_vec.push(Box::new(format!("%{}%", escape_like(search))));
+    }
+    if let Some(ref date_from) = filter.date_from {
+        query.push_str(" AND (p.end_date IS NULL OR p.end_date >= ?)");
+        params_vec.push(Box::new(date_from.clone()));
+    }
+    if let Some(ref date_to) = filter.date_to {
+        query.push_str(" AND (p.start_date IS NULL OR p.start_date <= ?)");
+        params_vec.push(Box::new(date_to.clone()));
+    }
+    let order_by = project_order_by(filter.sort_by.as_deref().unwrap_or("created_at"), filter.order.as_deref())?;
+
+    let page = PageRequest::resolve(conn, filter.limit, filter.offset, None)?;
+    let count_params: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+    let total = count_rows(conn, &format!("SELECT COUNT(*){}", query), count_params.as_slice())?;
+
+    page.bind(&mut params_vec);
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT p.*, c.name as client_name{} ORDER BY {}{}",
+            query,
+            order_by,
+            page.sql()
+        ))
         .map_err(|e| e.to_string())?;
 
-    let projects: Vec<ProjectWithDetails> = stmt
-        .query_map([], |row| {
+    let params_slice: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+    let rows: Vec<(Project, Option<String>)> = stmt
+        .query_map(params_slice.as_slice(), |row| {
             let project = Project::from_row(row)?;
             let client_name: Option<String> = row.get("client_name")?;
             Ok((project, client_name))
         })
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
+        .collect();
+
+    let project_ids: Vec<i64> = rows.iter().map(|(project, _)| project.id).collect();
+    let mut machines = linked_ids(conn, "project_machines", "machine_id", &project_ids)?;
+    let mut teams = team_roles(conn, &project_ids)?;
+
+    let projects = rows
+        .into_iter()
         .map(|(project, client_name)| {
-            // Get assigned machines
-            let machines: Vec<i64> = conn
-                .prepare("SELECT machine_id FROM project_machines WHERE project_id = ?1")
-                .ok()
-                .and_then(|mut stmt| {
-                    stmt.query_map([project.id], |row| row.get(0))
-                        .ok()
-                        .map(|iter| iter.filter_map(|r| r.ok()).collect())
-                })
-                .unwrap_or_default();
-
-            // Get team members
-            let team: Vec<i64> = conn
-                .prepare("SELECT user_id FROM project_team WHERE project_id = ?1")
-                .ok()
-                .and_then(|mut stmt| {
-                    stmt.query_map([project.id], |row| row.get(0))
-                        .ok()
-                        .map(|iter| iter.filter_map(|r| r.ok()).collect())
-                })
-                .unwrap_or_default();
-
-            let progress = if project.planned_hours > 0.0 {
-                (project.actual_hours / project.planned_hours * 100.0).min(100.0)
-            } else {
-                0.0
-            };
+            let progress = computed_progress(project.planned_hours, project.actual_hours);
+            let effective_progress = project.effective_progress();
+            let milestones = milestone_summary(conn, project.id).unwrap_or_default();
+            let task_hours = task_hours_summary(conn, project.id).unwrap_or_default();
+            let (note_count, latest_note) = note_summary(conn, project.id).unwrap_or_default();
+            let team = teams.remove(&project.id).unwrap_or_default();
 
             ProjectWithDetails {
+                assigned_machines: machines.remove(&project.id).unwrap_or_default(),
+                team_members: team.iter().map(|member| member.user_id).collect(),
+                team,
                 project,
                 client_name,
-                assigned_machines: machines,
-                team_members: team,
                 progress_percentage: progress,
+                effective_progress,
+                milestones,
+                task_hours,
+                latest_note,
+                note_count,
             }
         })
         .collect();
 
-    Ok(projects)
+    Ok(Paginated::new(projects, page, total))
+}
+
+/// Get all projects, leaving out archived ones unless `include_archived` is set
+#[tauri::command]
+pub fn get_projects(
+    token: String,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    include_archived: Option<bool>,
+    db: State<'_, Database>,
+) -> Result<Paginated<ProjectWithDetails>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    Ok(load_projects(&conn, &ProjectFilter { include_archived, limit, offset, ..Default::default() })?)
+}
+
+/// Search and filter projects server-side, one page at a time. The name
+/// search matches case-insensitively.
+#[tauri::command]
+pub fn query_projects(
+    token: String,
+    filter: Option<ProjectFilter>,
+    db: State<'_, Database>,
+) -> Result<Paginated<ProjectWithDetails>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    Ok(load_projects(&conn, &filter.unwrap_or_default())?)
 }
 
 /// Get single project by ID
 #[tauri::command]
-pub fn get_project(token: String, id: i64, db: State<'_, Database>) -> Result<ProjectWithDetails, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+pub fn get_project(token: String, id: i64, db: State<'_, Database>) -> Result<ProjectWithDetails, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
     let (project, client_name): (Project, Option<String>) = conn
         .query_row(
@@ -105,49 +279,116 @@ pub fn get_project(token: String, id: i64, db: State<'_, Database>) -> Result<Pr
         .collect();
 
     // Get team members
-    let mut stmt = conn
-        .prepare("SELECT user_id FROM project_team WHERE project_id = ?1")
-        .map_err(|e| e.to_string())?;
-    let team: Vec<i64> = stmt
-        .query_map([id], |row| row.get(0))
-        .map_err(|e| e.to_string())?
-        .filter_map(|r| r.ok())
-        .collect();
+    let team = team_roles(&conn, &[id])?.remove(&id).unwrap_or_default();
 
-    let progress = if project.planned_hours > 0.0 {
-        (project.actual_hours / project.planned_hours * 100.0).min(100.0)
-    } else {
-        0.0
-    };
+    let progress = computed_progress(project.planned_hours, project.actual_hours);
+    let effective_progress = project.effective_progress();
+    let milestones = milestone_summary(&conn, id)?;
+    let task_hours = task_hours_summary(&conn, id)?;
+    let (note_count, latest_note) = note_summary(&conn, id)?;
 
     Ok(ProjectWithDetails {
         project,
         client_name,
         assigned_machines: machines,
-        team_members: team,
+        team_members: team.iter().map(|member| member.user_id).collect(),
+        team,
         progress_percentage: progress,
+        effective_progress,
+        milestones,
+        task_hours,
+        latest_note,
+        note_count,
     })
 }
 
+/// Planned and actual schedule hours per machine for a project. Assigned
+/// machines with no schedule entries are listed with zero hours; cancelled
+/// entries are left out.
+#[tauri::command]
+pub fn get_project_machine_breakdown(
+    token: String,
+    project_id: i64,
+    db: State<'_, Database>,
+) -> Result<Vec<ProjectMachineHours>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    conn.query_row("SELECT id FROM projects WHERE id = ?1", [project_id], |row| row.get::<_, i64>(0))
+        .map_err(|_| "Project not found".to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT m.id, m.name,
+                    EXISTS (SELECT 1 FROM project_machines pm WHERE pm.project_id = ?1 AND pm.machine_id = m.id)
+                        as assigned,
+                    COUNT(s.id) as schedule_count,
+                    COALESCE(SUM(s.planned_hours), 0) as planned,
+                    COALESCE(SUM(s.actual_hours), 0) as actual
+             FROM machines m
+             LEFT JOIN schedules s ON s.machine_id = m.id AND s.project_id = ?1 AND s.status != 'cancelled'
+             WHERE s.id IS NOT NULL
+                OR m.id IN (SELECT machine_id FROM project_machines WHERE project_id = ?1)
+             GROUP BY m.id
+             ORDER BY actual DESC, m.name",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let breakdown = stmt
+        .query_map([project_id], |row| {
+            let planned: f64 = row.get(4)?;
+            let actual: f64 = row.get(5)?;
+            let efficiency = if planned > 0.0 {
+                (actual / planned * 100.0).min(100.0)
+            } else {
+                0.0
+            };
+
+            Ok(ProjectMachineHours {
+                machine_id: row.get(0)?,
+                machine_name: row.get(1)?,
+                assigned: row.get(2)?,
+                schedule_count: row.get(3)?,
+                planned_hours: planned,
+                actual_hours: actual,
+                efficiency_percentage: efficiency,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(breakdown)
+}
+
 /// Create new project (Admin only)
 #[tauri::command]
 pub fn create_project(
     token: String,
     input: CreateProjectInput,
     db: State<'_, Database>,
-) -> Result<ProjectWithDetails, String> {
+) -> Result<ProjectWithDetails, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_admin(&user)?;
+    require_permission(&conn, &user, "projects.create")?;
 
     // Validate status
     if !["planning", "active", "completed", "on-hold"].contains(&input.status.as_str()) {
-        return Err("Invalid status".to_string());
+        return Err(AppError::invalid_field("status", "invalid status"));
+    }
+    let priority = input.priority.as_deref().unwrap_or("normal");
+    validate_project_priority(priority)?;
+    if let Some(client_id) = input.client_id {
+        ensure_client_not_archived(&conn, client_id)?;
+    }
+    for machine_id in input.assigned_machines.iter().flatten() {
+        ensure_machine_not_archived(&conn, *machine_id)?;
     }
 
     conn.execute(
-        "INSERT INTO projects (name, client_id, description, start_date, end_date, status, planned_hours, part_name, created_by)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        "INSERT INTO projects (name, client_id, description, start_date, end_date, status, priority, planned_hours, part_name, external_ref, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
         params![
             input.name,
             input.client_id,
@@ -155,8 +396,10 @@ pub fn create_project(
             input.start_date,
             input.end_date,
             input.status,
+            priority,
             input.planned_hours,
             input.part_name,
+            input.external_ref.as_deref().map(str::trim).filter(|r| !r.is_empty()),
             user.id
         ],
     )
@@ -179,9 +422,9 @@ pub fn create_project(
             let load_name = input.part_name.clone().unwrap_or_else(|| input.name.clone());
             for machine_id in machines {
                 conn.execute(
-                    "INSERT INTO schedules (machine_id, project_id, date, load_name, planned_hours, status, created_by)
-                     VALUES (?1, ?2, ?3, ?4, ?5, 'scheduled', ?6)",
-                    params![machine_id, new_id, start_date, load_name, input.planned_hours, user.id],
+                    "INSERT INTO schedules (machine_id, project_id, date, load_name, planned_hours, status, source, created_by)
+                     VALUES (?1, ?2, ?3, ?4, ?5, 'scheduled', ?6, ?7)",
+                    params![machine_id, new_id, start_date, load_name, input.planned_hours, SOURCE_AUTO, user.id],
                 )
                 .ok();
             }
@@ -199,31 +442,146 @@ pub fn create_project(
         }
     }
 
+    let project = fetch_project(&conn, new_id)?;
+    record_audit(&conn, &user, "create", "projects", Some(new_id), None, audit_json(&project).as_deref());
+
     // Return the created project
     drop(conn);
     get_project(token, new_id, db)
 }
 
+/// Start a new project from an existing one, e.g. a repeat order (Admin only)
+///
+/// Copies the client, description, part name and planned hours, and optionally
+/// the machine and team assignments. The copy starts in planning with no hours
+/// logged; archived machines are left out.
+#[tauri::command]
+pub fn clone_project(
+    token: String,
+    source_id: i64,
+    new_name: String,
+    new_start_date: Option<String>,
+    new_end_date: Option<String>,
+    copy_team: bool,
+    copy_machines: bool,
+    db: State<'_, Database>,
+) -> Result<ProjectWithDetails, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.create")?;
+
+    let new_name = new_name.trim().to_string();
+    if new_name.is_empty() {
+        return Err(AppError::validation("project name is required"));
+    }
+    for date in [&new_start_date, &new_end_date].into_iter().flatten() {
+        chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| format!("Validation error: invalid date '{}'. Use YYYY-MM-DD", date))?;
+    }
+    if let (Some(start), Some(end)) = (&new_start_date, &new_end_date) {
+        if end < start {
+            return Err(AppError::validation("end date is before start date"));
+        }
+    }
+    let source = conn
+        .query_row("SELECT * FROM projects WHERE id = ?1", [source_id], Project::from_row)
+        .map_err(|_| "Project not found".to_string())?;
+    if let Some(client_id) = source.client_id {
+        ensure_client_not_archived(&conn, client_id)?;
+    }
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT INTO projects (name, client_id, description, start_date, end_date, status, priority, planned_hours, actual_hours, part_name, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, 'planning', ?6, ?7, 0, ?8, ?9)",
+        params![
+            new_name,
+            source.client_id,
+            source.description,
+            new_start_date,
+            new_end_date,
+            source.priority,
+            source.planned_hours,
+            source.part_name,
+            user.id
+        ],
+    )
+    .map_err(|e| format!("Failed to clone project: {}", e))?;
+    let new_id = tx.last_insert_rowid();
+
+    if copy_machines {
+        tx.execute(
+            "INSERT INTO project_machines (project_id, machine_id)
+             SELECT ?1, pm.machine_id FROM project_machines pm
+             JOIN machines m ON pm.machine_id = m.id
+             WHERE pm.project_id = ?2 AND m.archived_at IS NULL",
+            params![new_id, source_id],
+        )
+        .map_err(|e| format!("Failed to copy machine assignments: {}", e))?;
+    }
+    if copy_team {
+        tx.execute(
+            "INSERT INTO project_team (project_id, user_id, role)
+             SELECT ?1, user_id, role FROM project_team WHERE project_id = ?2",
+            params![new_id, source_id],
+        )
+        .map_err(|e| format!("Failed to copy team: {}", e))?;
+    }
+    record_audit(
+        &tx,
+        &user,
+        "clone",
+        "projects",
+        Some(new_id),
+        None,
+        Some(&serde_json::json!({ "source_id": source_id, "name": new_name }).to_string()),
+    );
+    tx.commit().map_err(|e| e.to_string())?;
+
+    drop(conn);
+    get_project(token, new_id, db)
+}
+
+fn fetch_project(conn: &Connection, id: i64) -> Result<Project, String> {
+    conn.query_row("SELECT * FROM projects WHERE id = ?1", [id], Project::from_row)
+        .map_err(|_| "Project not found".to_string())
+}
+
 /// Update project (Admin or Operator)
+///
+/// Status changes must follow the project transition map unless an Admin sets
+/// `force_transition`. Completing a project cancels its remaining scheduled
+/// entries.
 #[tauri::command]
 pub fn update_project(
     token: String,
     id: i64,
     input: UpdateProjectInput,
+    force_transition: Option<bool>,
     db: State<'_, Database>,
-) -> Result<ProjectWithDetails, String> {
+) -> Result<ProjectWithDetails, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "projects.edit")?;
 
+    let existing = fetch_project(&conn, id)?;
     let mut updates = Vec::new();
     let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+    let mut completing = false;
+    let mut status_change = None;
 
     if let Some(name) = &input.name {
         updates.push("name = ?");
         values.push(Box::new(name.clone()));
     }
     if let Some(client_id) = input.client_id {
+        let current: Option<i64> = conn
+            .query_row("SELECT client_id FROM projects WHERE id = ?1", [id], |row| row.get(0))
+            .map_err(|_| "Project not found".to_string())?;
+        // A project may stay with a client archived after it was assigned
+        if current != Some(client_id) {
+            ensure_client_not_archived(&conn, client_id)?;
+        }
         updates.push("client_id = ?");
         values.push(Box::new(client_id));
     }
@@ -239,9 +597,29 @@ pub fn update_project(
         updates.push("end_date = ?");
         values.push(Box::new(end.clone()));
     }
+    if let Some(priority) = &input.priority {
+        validate_project_priority(priority)?;
+        let current: String = conn
+            .query_row("SELECT priority FROM projects WHERE id = ?1", [id], |row| row.get(0))
+            .map_err(|_| "Project not found".to_string())?;
+        // Operators may resend the current priority with the rest of the form
+        if *priority != current {
+            require_permission(&conn, &user, "projects.prioritize")?;
+            updates.push("priority = ?");
+            values.push(Box::new(priority.clone()));
+        }
+    }
     if let Some(status) = &input.status {
         if !["planning", "active", "completed", "on-hold"].contains(&status.as_str()) {
-            return Err("Invalid status".to_string());
+            return Err(AppError::invalid_field("status", "invalid status"));
+        }
+        let current: String = conn
+            .query_row("SELECT status FROM projects WHERE id = ?1", [id], |row| row.get(0))
+            .map_err(|_| "Project not found".to_string())?;
+        check_transition(&conn, &user, ENTITY_PROJECT, id, &current, status, force_transition.unwrap_or(false))?;
+        completing = status == "completed" && current != "completed";
+        if current != *status {
+            status_change = Some(current);
         }
         updates.push("status = ?");
         values.push(Box::new(status.clone()));
@@ -268,9 +646,13 @@ pub fn update_project(
         updates.push("part_name = ?");
         values.push(Box::new(pn.clone()));
     }
+    if let Some(ref external_ref) = input.external_ref {
+        updates.push("external_ref = ?");
+        values.push(Box::new(Some(external_ref.trim()).filter(|r| !r.is_empty()).map(str::to_string)));
+    }
 
     if updates.is_empty() {
-        return Err("No fields to update".to_string());
+        return Err(AppError::validation("no fields to update"));
     }
 
     updates.push("updated_at = CURRENT_TIMESTAMP");
@@ -278,12 +660,47 @@ pub fn update_project(
     values.push(Box::new(id));
 
     let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
-    conn.execute(&query, params.as_slice())
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(&query, params.as_slice())
         .map_err(|e| format!("Failed to update project: {}", e))?;
+    record_audit_update(&tx, &user, "update", "projects", id, &existing, &fetch_project(&tx, id)?);
+    if let (Some(previous), Some(status)) = (&status_change, &input.status) {
+        record_audit(
+            &tx,
+            &user,
+            "status_change",
+            "projects",
+            Some(id),
+            Some(&serde_json::json!({ "status": previous }).to_string()),
+            Some(&serde_json::json!({ "status": status }).to_string()),
+        );
+    }
+
+    // Work still waiting to run will not happen once the project is done
+    if completing {
+        let cancelled = tx
+            .execute(
+                "UPDATE schedules SET status = 'cancelled', updated_at = CURRENT_TIMESTAMP
+                 WHERE project_id = ?1 AND status = 'scheduled'",
+                [id],
+            )
+            .map_err(|e| format!("Failed to cancel scheduled entries: {}", e))?;
+        if cancelled > 0 {
+            record_audit(
+                &tx,
+                &user,
+                "cancel_schedules",
+                "projects",
+                Some(id),
+                None,
+                Some(&serde_json::json!({ "reason": "project completed", "cancelled": cancelled }).to_string()),
+            );
+        }
+    }
 
     // Propagate planned_hours change to linked schedules
     if let Some(planned) = input.planned_hours {
-        let _ = conn.execute(
+        let _ = tx.execute(
             "UPDATE schedules SET planned_hours = ?1 WHERE project_id = ?2",
             params![planned, id],
         );
@@ -291,40 +708,163 @@ pub fn update_project(
 
     // Propagate part_name change to linked schedules load_name
     if let Some(ref pn) = input.part_name {
-        let _ = conn.execute(
+        let _ = tx.execute(
             "UPDATE schedules SET load_name = ?1 WHERE project_id = ?2",
             params![pn, id],
         );
     }
+    tx.commit().map_err(|e| e.to_string())?;
 
     drop(conn);
     get_project(token, id, db)
 }
 
 /// Delete project (Admin only)
+///
+/// A project with work scheduled or in progress from today on is only deleted
+/// with `force`; those entries stay on the schedule without a project.
+/// `expected_effects` are the cascade effects from
+/// `check_project_delete_impact` that the user confirmed.
 #[tauri::command]
-pub fn delete_project(token: String, id: i64, db: State<'_, Database>) -> Result<(), String> {
+pub fn delete_project(
+    token: String,
+    id: i64,
+    force: Option<bool>,
+    expected_effects: Option<Vec<CascadeEffect>>,
+    db: State<'_, Database>,
+) -> Result<(), AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_admin(&user)?;
+    require_permission(&conn, &user, "projects.delete")?;
+
+    let existing = fetch_project(&conn, id)?;
+    let completed: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM schedules WHERE project_id = ?1 AND status = 'completed'",
+            [id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if completed > 0 {
+        return Err(AppError::Conflict(format!(
+            "Project has {} completed schedule entries. Archive it instead to keep its history for reporting",
+            completed
+        )));
+    }
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let active: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM schedules
+             WHERE project_id = ?1 AND date >= ?2 AND status IN ('scheduled', 'in-progress')",
+            params![id, today],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if active > 0 && !force.unwrap_or(false) {
+        return Err(AppError::Conflict(format!(
+            "Project has {} scheduled or in-progress entries from today on. Move or cancel them, or delete with force",
+            active
+        )));
+    }
+
+    let impact = project_delete_impact(&conn, id)?;
+    confirm_delete_impact(&impact, expected_effects.as_deref())?;
 
-    conn.execute("DELETE FROM projects WHERE id = ?1", [id])
+    let summary = serde_json::json!({ "impact": impact.cascade_effects, "forced": active > 0, "active_entries": active });
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM alerts WHERE project_id = ?1", [id])
+        .map_err(|e| format!("Failed to delete project alerts: {}", e))?;
+    tx.execute("DELETE FROM projects WHERE id = ?1", [id])
         .map_err(|e| format!("Failed to delete project: {}", e))?;
+    record_audit(
+        &tx,
+        &user,
+        "delete",
+        "projects",
+        Some(id),
+        audit_json(&existing).as_deref(),
+        Some(&summary.to_string()),
+    );
+    tx.commit().map_err(|e| e.to_string())?;
 
     Ok(())
 }
 
+/// Archive a finished project (Admin only)
+///
+/// The project drops out of lists and can take no new schedules, but its
+/// history stays for reporting. Upcoming entries must be moved or cancelled first.
+#[tauri::command]
+pub fn archive_project(token: String, id: i64, db: State<'_, Database>) -> Result<Project, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.archive")?;
+
+    ensure_project_not_archived(&conn, id)?;
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let upcoming: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM schedules
+             WHERE project_id = ?1 AND date >= ?2 AND status IN ('scheduled', 'in-progress')",
+            params![id, today],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if upcoming > 0 {
+        return Err(AppError::Conflict(format!(
+            "Project has {} upcoming schedule entries. Move or cancel them before archiving",
+            upcoming
+        )));
+    }
+
+    conn.execute(
+        "UPDATE projects SET archived_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+        [id],
+    )
+    .map_err(|e| format!("Failed to archive project: {}", e))?;
+    record_audit(&conn, &user, "archive_project", "projects", Some(id), None, None);
+
+    conn.query_row("SELECT * FROM projects WHERE id = ?1", [id], Project::from_row)
+        .map_err(AppError::from)
+}
+
+/// Return an archived project to the active lists (Admin only)
+#[tauri::command]
+pub fn unarchive_project(token: String, id: i64, db: State<'_, Database>) -> Result<Project, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.archive")?;
+
+    let updated = conn
+        .execute(
+            "UPDATE projects SET archived_at = NULL, updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?1 AND archived_at IS NOT NULL",
+            [id],
+        )
+        .map_err(|e| format!("Failed to unarchive project: {}", e))?;
+    if updated == 0 {
+        return Err(AppError::NotFound("Project not found or not archived".to_string()));
+    }
+    record_audit(&conn, &user, "unarchive_project", "projects", Some(id), None, None);
+
+    conn.query_row("SELECT * FROM projects WHERE id = ?1", [id], Project::from_row)
+        .map_err(AppError::from)
+}
+
 /// Assign machines to project (Admin only)
+///
+/// Returns the ids of schedule entries created or removed while syncing, with
+/// the created entries as rows.
 #[tauri::command]
 pub fn assign_machines_to_project(
     token: String,
     project_id: i64,
     machine_ids: Vec<i64>,
     db: State<'_, Database>,
-) -> Result<(), String> {
+) -> Result<MutationResult<ScheduleWithDetails>, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_admin(&user)?;
+    require_permission(&conn, &user, "projects.assign")?;
 
     // Fetch project details needed for schedule creation
     let project_info: Option<(Option<String>, f64, Option<String>, String)> = conn
@@ -344,6 +884,9 @@ pub fn assign_machines_to_project(
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
         .collect();
+    for machine_id in machine_ids.iter().filter(|id| !prev_machines.contains(id)) {
+        ensure_machine_not_archived(&conn, *machine_id)?;
+    }
 
     // Remove existing assignments
     conn.execute(
@@ -361,12 +904,25 @@ pub fn assign_machines_to_project(
         .map_err(|e| format!("Failed to assign machine: {}", e))?;
     }
 
+    let mut affected_ids = Vec::new();
+    let mut created_ids = Vec::new();
+    let mut warnings = Vec::new();
+
     // Sync schedules: create entries for newly added machines, remove for removed machines
     if let Some((Some(start_date), planned_hours, part_name, project_name)) = project_info {
         let load_name = part_name.unwrap_or(project_name);
 
         // Remove schedules for machines no longer assigned to this project
         for removed_id in prev_machines.iter().filter(|id| !machine_ids.contains(id)) {
+            let mut removed_stmt = conn
+                .prepare("SELECT id FROM schedules WHERE project_id = ?1 AND machine_id = ?2")
+                .map_err(|e| e.to_string())?;
+            affected_ids.extend(
+                removed_stmt
+                    .query_map(params![project_id, removed_id], |row| row.get::<_, i64>(0))
+                    .map_err(|e| e.to_string())?
+                    .filter_map(|r| r.ok()),
+            );
             let _ = conn.execute(
                 "DELETE FROM schedules WHERE project_id = ?1 AND machine_id = ?2",
                 params![project_id, removed_id],
@@ -385,71 +941,389 @@ pub fn assign_machines_to_project(
                 .unwrap_or(false);
 
             if !exists {
-                let _ = conn.execute(
-                    "INSERT INTO schedules (machine_id, project_id, date, load_name, planned_hours, status, created_by)
-                     VALUES (?1, ?2, ?3, ?4, ?5, 'scheduled', ?6)",
-                    params![machine_id, project_id, start_date, load_name, planned_hours, user.id],
-                );
+                match conn.execute(
+                    "INSERT INTO schedules (machine_id, project_id, date, load_name, planned_hours, status, source, created_by)
+                     VALUES (?1, ?2, ?3, ?4, ?5, 'scheduled', ?6, ?7)",
+                    params![machine_id, project_id, start_date, load_name, planned_hours, SOURCE_AUTO, user.id],
+                ) {
+                    Ok(_) => created_ids.push(conn.last_insert_rowid()),
+                    Err(e) => warnings.push(format!("No schedule entry created for machine {}: {}", machine_id, e)),
+                }
             }
         }
+    } else if project_info.is_some() {
+        warnings.push("Project has no start date, so schedule entries were not synced".to_string());
     }
 
-    Ok(())
+    let rows = created_ids
+        .iter()
+        .map(|&id| fetch_schedule_with_details(&conn, id))
+        .collect::<Result<Vec<_>, String>>()?;
+    affected_ids.extend(&created_ids);
+    Ok(MutationResult::new(affected_ids, rows, warnings))
 }
 
-/// Assign team members to project (Admin only)
+/// Set a project's team (Admin only).
+///
+/// Members left out are removed. Members who stay keep their assignment date,
+/// and keep their role unless a new one is given.
 #[tauri::command]
 pub fn assign_team_to_project(
     token: String,
     project_id: i64,
-    user_ids: Vec<i64>,
+    members: Vec<TeamMemberInput>,
     db: State<'_, Database>,
-) -> Result<(), String> {
+) -> Result<MutationResult<UserPublic>, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_admin(&user)?;
+    require_permission(&conn, &user, "projects.assign")?;
 
-    // Remove existing assignments
-    conn.execute(
-        "DELETE FROM project_team WHERE project_id = ?1",
-        [project_id],
-    )
-    .map_err(|e| e.to_string())?;
+    conn.query_row("SELECT id FROM projects WHERE id = ?1", [project_id], |row| row.get::<_, i64>(0))
+        .map_err(|_| "Project not found".to_string())?;
+    let mut user_ids = Vec::new();
+    for member in &members {
+        if let Some(role) = &member.role {
+            if !TEAM_ROLES.contains(&role.as_str()) {
+                return Err(AppError::validation(format!("invalid team role '{}'. Must be one of: {}",
+                    role,
+                    TEAM_ROLES.join(", ")
+                )));
+            }
+        }
+        if user_ids.contains(&member.user_id) {
+            return Err(AppError::validation(format!("user #{} is listed more than once", member.user_id)));
+        }
+        user_ids.push(member.user_id);
+    }
 
-    // Add new assignments
-    for user_id in user_ids {
-        conn.execute(
-            "INSERT INTO project_team (project_id, user_id) VALUES (?1, ?2)",
-            params![project_id, user_id],
+    // Inactive users may stay on a team but cannot join one
+    let current_team = team_roles(&conn, &[project_id])?.remove(&project_id).unwrap_or_default();
+    for &user_id in &user_ids {
+        if !current_team.iter().any(|member| member.user_id == user_id) {
+            ensure_user_active(&conn, user_id)?;
+        }
+    }
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+
+    // Remove members who are no longer on the team
+    let mut remove_query = String::from("DELETE FROM project_team WHERE project_id = ?");
+    if !user_ids.is_empty() {
+        remove_query.push_str(&format!(" AND user_id NOT IN ({})", vec!["?"; user_ids.len()].join(", ")));
+    }
+    let mut remove_params: Vec<&dyn rusqlite::ToSql> = vec![&project_id];
+    remove_params.extend(user_ids.iter().map(|id| id as &dyn rusqlite::ToSql));
+    tx.execute(&remove_query, remove_params.as_slice())
+        .map_err(|e| e.to_string())?;
+
+    // Add new members and apply any role changes
+    let mut rows = Vec::new();
+    for member in &members {
+        let account = tx
+            .query_row("SELECT * FROM users WHERE id = ?1", [member.user_id], User::from_row)
+            .map_err(|_| format!("User #{} not found", member.user_id))?;
+        tx.execute(
+            "INSERT INTO project_team (project_id, user_id, role) VALUES (?1, ?2, COALESCE(?3, 'member'))
+             ON CONFLICT(project_id, user_id) DO UPDATE SET role = COALESCE(?3, role)",
+            params![project_id, member.user_id, member.role],
         )
         .map_err(|e| format!("Failed to assign team member: {}", e))?;
+        rows.push(UserPublic::from(account));
     }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(MutationResult::new(user_ids, rows, Vec::new()))
+}
 
+const HOUR_LOG_SELECT: &str = "SELECT l.*, COALESCE(u.full_name, u.username) as user_name
+     FROM project_hour_logs l
+     LEFT JOIN users u ON l.user_id = u.id";
+
+fn fetch_hour_log(conn: &Connection, id: i64) -> Result<ProjectHourLog, String> {
+    conn.query_row(&format!("{} WHERE l.id = ?1", HOUR_LOG_SELECT), [id], ProjectHourLog::from_row)
+        .map_err(|_| "Hour log entry not found".to_string())
+}
+
+fn validate_logged_hours(hours: f64) -> Result<(), String> {
+    if hours.is_finite() && hours > 0.0 {
+        Ok(())
+    } else {
+        Err("Validation error: hours must be greater than zero".to_string())
+    }
+}
+
+/// Add `delta` to a project's cached actual hours
+fn adjust_project_hours(conn: &Connection, project_id: i64, delta: f64) -> Result<(), String> {
+    conn.execute(
+        "UPDATE projects SET actual_hours = MAX(COALESCE(actual_hours, 0) + ?1, 0), updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?2",
+        params![delta, project_id],
+    )
+    .map_err(|e| format!("Failed to update project hours: {}", e))?;
     Ok(())
 }
 
-/// Log hours to a project
+/// Log hours to a project.
+///
+/// Each call is kept as a log entry with who logged it, the day worked
+/// (default today) and an optional note; actual_hours is their running total
+/// plus the hours logged on schedule entries.
 #[tauri::command]
 pub fn log_project_hours(
     token: String,
     project_id: i64,
     hours: f64,
+    date: Option<String>,
+    note: Option<String>,
     db: State<'_, Database>,
-) -> Result<Project, String> {
+) -> Result<Project, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "projects.log_hours")?;
 
-    conn.execute(
-        "UPDATE projects SET actual_hours = actual_hours + ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
-        params![hours, project_id],
+    validate_logged_hours(hours)?;
+    let date = date.unwrap_or_else(|| Local::now().format("%Y-%m-%d").to_string());
+    chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|_| format!("Validation error: invalid date '{}'. Use YYYY-MM-DD", date))?;
+    conn.query_row("SELECT id FROM projects WHERE id = ?1", [project_id], |row| row.get::<_, i64>(0))
+        .map_err(|_| "Project not found".to_string())?;
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT INTO project_hour_logs (project_id, user_id, hours, date, note) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![project_id, user.id, hours, date, note.as_deref().map(str::trim).filter(|n| !n.is_empty())],
     )
     .map_err(|e| format!("Failed to log hours: {}", e))?;
+    adjust_project_hours(&tx, project_id, hours)?;
+    tx.commit().map_err(|e| e.to_string())?;
 
     conn.query_row(
         "SELECT * FROM projects WHERE id = ?1",
         [project_id],
         Project::from_row,
     )
-    .map_err(|e| e.to_string())
+    .map_err(AppError::from)
+}
+
+/// Get the hours logged directly against a project, newest first
+#[tauri::command]
+pub fn get_project_hour_logs(
+    token: String,
+    project_id: i64,
+    db: State<'_, Database>,
+) -> Result<Vec<ProjectHourLog>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let mut stmt = conn
+        .prepare(&format!("{} WHERE l.project_id = ?1 ORDER BY l.date DESC, l.id DESC", HOUR_LOG_SELECT))
+        .map_err(|e| e.to_string())?;
+
+    let logs = stmt
+        .query_map([project_id], ProjectHourLog::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(logs)
+}
+
+/// Correct the hours or note of a log entry, moving the project's total by
+/// the difference
+#[tauri::command]
+pub fn correct_project_hour_log(
+    token: String,
+    id: i64,
+    hours: f64,
+    note: Option<String>,
+    db: State<'_, Database>,
+) -> Result<ProjectHourLog, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.log_hours")?;
+
+    validate_logged_hours(hours)?;
+    let existing = fetch_hour_log(&conn, id)?;
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "UPDATE project_hour_logs SET hours = ?1, note = COALESCE(?2, note) WHERE id = ?3",
+        params![hours, note.as_deref().map(str::trim).filter(|n| !n.is_empty()), id],
+    )
+    .map_err(|e| format!("Failed to correct hours: {}", e))?;
+    adjust_project_hours(&tx, existing.project_id, hours - existing.hours)?;
+    record_audit(
+        &tx,
+        &user,
+        "correct_hours",
+        "project_hour_logs",
+        Some(id),
+        Some(&existing.hours.to_string()),
+        Some(&hours.to_string()),
+    );
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(fetch_hour_log(&conn, id)?)
+}
+
+/// Delete a log entry, taking its hours off the project's total
+#[tauri::command]
+pub fn delete_project_hour_log(token: String, id: i64, db: State<'_, Database>) -> Result<Project, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.log_hours")?;
+
+    let existing = fetch_hour_log(&conn, id)?;
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM project_hour_logs WHERE id = ?1", [id])
+        .map_err(|e| format!("Failed to delete hour log entry: {}", e))?;
+    adjust_project_hours(&tx, existing.project_id, -existing.hours)?;
+    record_audit(
+        &tx,
+        &user,
+        "delete",
+        "project_hour_logs",
+        Some(id),
+        Some(&serde_json::json!({ "hours": existing.hours, "date": existing.date, "note": existing.note }).to_string()),
+        None,
+    );
+    tx.commit().map_err(|e| e.to_string())?;
+
+    conn.query_row("SELECT * FROM projects WHERE id = ?1", [existing.project_id], Project::from_row)
+        .map_err(AppError::from)
+}
+
+/// Recompute a project's actual hours, and its tasks', from its schedule
+/// entries and hour log. Returns false when there is no such project.
+pub(crate) fn recalculate_hours(conn: &Connection, project_id: i64) -> Result<bool, String> {
+    let affected = conn
+        .execute(
+            "UPDATE projects SET actual_hours = (
+                SELECT COALESCE(SUM(actual_hours), 0)
+                FROM schedules
+                WHERE project_id = ?1 AND actual_hours IS NOT NULL
+            ) + (
+                SELECT COALESCE(SUM(hours), 0) FROM project_hour_logs WHERE project_id = ?1
+            ), updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?1",
+            [project_id],
+        )
+        .map_err(|e| format!("Failed to recalculate hours: {}", e))?;
+    if affected == 0 {
+        return Ok(false);
+    }
+    conn.execute(
+        "UPDATE project_tasks SET actual_hours = (
+            SELECT COALESCE(SUM(actual_hours), 0)
+            FROM schedules
+            WHERE task_id = project_tasks.id AND actual_hours IS NOT NULL
+        ), updated_at = CURRENT_TIMESTAMP
+        WHERE project_id = ?1",
+        [project_id],
+    )
+    .map_err(|e| format!("Failed to recalculate task hours: {}", e))?;
+    Ok(true)
+}
+
+/// Recompute a project's actual hours from its schedule entries and hour log
+/// to fix drift.
+#[tauri::command]
+pub fn recalculate_project_hours(
+    token: String,
+    project_id: i64,
+    db: State<'_, Database>,
+) -> Result<Project, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.log_hours")?;
+
+    if !recalculate_hours(&conn, project_id)? {
+        return Err(AppError::NotFound("Project not found".to_string()));
+    }
+
+    conn.query_row(
+        "SELECT * FROM projects WHERE id = ?1",
+        [project_id],
+        Project::from_row,
+    )
+    .map_err(AppError::from)
+}
+
+/// Set or clear (`pct` = None) a project's manual percent complete.
+///
+/// The note is kept with the override and in the audit log.
+#[tauri::command]
+pub fn set_project_progress(
+    token: String,
+    id: i64,
+    pct: Option<f64>,
+    note: Option<String>,
+    db: State<'_, Database>,
+) -> Result<Project, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "projects.log_hours")?;
+
+    if let Some(pct) = pct {
+        if !pct.is_finite() || !(0.0..=100.0).contains(&pct) {
+            return Err(AppError::invalid_field("progress", "progress must be between 0 and 100"));
+        }
+    }
+    let note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+
+    let before = conn
+        .query_row("SELECT * FROM projects WHERE id = ?1", [id], Project::from_row)
+        .map_err(|_| "Project not found".to_string())?;
+
+    conn.execute(
+        "UPDATE projects SET progress_override = ?1, progress_note = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?3",
+        params![pct, if pct.is_some() { note.as_deref() } else { None }, id],
+    )
+    .map_err(|e| format!("Failed to set progress: {}", e))?;
+
+    // Keep the reason on the project's timeline, where it survives the next override
+    let timeline_note = match (pct, &note) {
+        (Some(pct), Some(note)) => format!("Progress set to {:.0}%: {}", pct, note),
+        (Some(pct), None) => format!("Progress set to {:.0}%", pct),
+        (None, _) => "Progress override cleared".to_string(),
+    };
+    insert_project_note(&conn, id, user.id, &timeline_note)?;
+
+    record_audit(
+        &conn,
+        &user,
+        "set_progress",
+        "projects",
+        Some(id),
+        Some(&serde_json::json!({ "progress_override": before.progress_override }).to_string()),
+        Some(&serde_json::json!({ "progress_override": pct, "note": note }).to_string()),
+    );
+
+    conn.query_row("SELECT * FROM projects WHERE id = ?1", [id], Project::from_row)
+        .map_err(AppError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_by_whitelisted_columns_only() {
+        assert_eq!(project_order_by("name", None).unwrap(), "p.name IS NULL, p.name ASC, p.id DESC");
+        assert_eq!(
+            project_order_by("created_at", None).unwrap(),
+            "p.created_at IS NULL, p.created_at DESC, p.id DESC"
+        );
+
+        for sort_by in ["name; DROP TABLE projects", "p.name", "1 OR 1=1", "", "priority DESC"] {
+            assert!(project_order_by(sort_by, None).unwrap_err().starts_with("Validation error"));
+        }
+        for sort_by in ["name", "priority"] {
+            for order in ["desc; DROP TABLE projects", "DESC", "asc, p.id"] {
+                assert!(project_order_by(sort_by, Some(order)).unwrap_err().starts_with("Validation error"));
+            }
+        }
+    }
 }
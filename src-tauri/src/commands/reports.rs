@@ -0,0 +1,681 @@
+use std::collections::BTreeMap;
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::utils::{
+    get_float, has_permission, parse_date, require_permission, require_view_permission, validate_session,
+    DEFAULT_OVERTIME_THRESHOLD, OVERTIME_THRESHOLD_KEY,
+};
+
+/// On-time delivery figures for a set of projects
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeliveryStats {
+    pub evaluated_projects: i64,
+    pub on_time_projects: i64,
+    pub late_projects: i64,
+    pub on_time_percentage: f64,
+    pub average_days_late: f64,
+    pub projects_without_end_date: i64,
+}
+
+/// A project that missed (or is missing) its promised end date
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LateProject {
+    pub project_id: i64,
+    pub project_name: String,
+    pub status: String,
+    pub end_date: String,
+    pub completion_date: Option<String>,
+    pub days_late: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientDeliveryPerformance {
+    pub client_id: Option<i64>,
+    pub client_name: Option<String>,
+    #[serde(flatten)]
+    pub stats: DeliveryStats,
+    pub late_project_list: Vec<LateProject>,
+}
+
+/// Milestones due in the range, judged the same way as projects
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MilestoneDeliveryStats {
+    pub evaluated_milestones: i64,
+    pub on_time_milestones: i64,
+    pub late_milestones: i64,
+    pub on_time_percentage: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryPerformanceReport {
+    pub start_date: String,
+    pub end_date: String,
+    pub overall: DeliveryStats,
+    pub milestones: MilestoneDeliveryStats,
+    pub clients: Vec<ClientDeliveryPerformance>,
+}
+
+/// Get on-time delivery performance for projects due within a date range
+#[tauri::command]
+pub fn get_delivery_performance(
+    token: String,
+    start_date: String,
+    end_date: String,
+    client_id: Option<i64>,
+    db: State<'_, Database>,
+) -> Result<DeliveryPerformanceReport, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    parse_date("start_date", &start_date)?;
+    parse_date("end_date", &end_date)?;
+    Ok(compute_delivery_performance(&conn, &start_date, &end_date, client_id)?)
+}
+
+struct DeliveryRow {
+    project_id: i64,
+    project_name: String,
+    status: String,
+    end_date: Option<String>,
+    completion_date: Option<String>,
+    client_id: Option<i64>,
+    client_name: Option<String>,
+}
+
+#[derive(Default)]
+struct DeliveryAccumulator {
+    client_name: Option<String>,
+    on_time: i64,
+    total_days_late: i64,
+    without_end_date: i64,
+    late: Vec<LateProject>,
+}
+
+impl DeliveryAccumulator {
+    fn stats(&self) -> DeliveryStats {
+        let late = self.late.len() as i64;
+        let evaluated = self.on_time + late;
+        DeliveryStats {
+            evaluated_projects: evaluated,
+            on_time_projects: self.on_time,
+            late_projects: late,
+            on_time_percentage: if evaluated > 0 {
+                self.on_time as f64 / evaluated as f64 * 100.0
+            } else {
+                0.0
+            },
+            average_days_late: if late > 0 {
+                self.total_days_late as f64 / late as f64
+            } else {
+                0.0
+            },
+            projects_without_end_date: self.without_end_date,
+        }
+    }
+}
+
+/// Compute delivery performance for projects due between `start_date` and `end_date`.
+///
+/// Completed projects are compared against their completion stamp; open projects
+/// whose end date has already passed count as late as of today. Open projects that
+/// are not yet due are left out, and projects without an end date are only counted.
+pub fn compute_delivery_performance(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    client_id: Option<i64>,
+) -> Result<DeliveryPerformanceReport, String> {
+    let start = chrono::NaiveDate::parse_from_str(start_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    let end = chrono::NaiveDate::parse_from_str(end_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    if end < start {
+        return Err("End date must not be before start date".to_string());
+    }
+    let today = chrono::Local::now().date_naive();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT p.id, p.name, p.status, p.end_date, p.actual_completion_date,
+                    p.client_id, c.name as client_name
+             FROM projects p
+             LEFT JOIN clients c ON p.client_id = c.id
+             WHERE (?1 IS NULL OR p.client_id = ?1)
+               AND ((p.end_date >= ?2 AND p.end_date <= ?3)
+                    OR (p.end_date IS NULL AND substr(p.created_at, 1, 10) <= ?3))
+             ORDER BY c.name, p.end_date",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows: Vec<DeliveryRow> = stmt
+        .query_map(params![client_id, start_date, end_date], |row| {
+            Ok(DeliveryRow {
+                project_id: row.get(0)?,
+                project_name: row.get(1)?,
+                status: row.get(2)?,
+                end_date: row.get(3)?,
+                completion_date: row.get(4)?,
+                client_id: row.get(5)?,
+                client_name: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut overall = DeliveryAccumulator::default();
+    let mut per_client: BTreeMap<Option<i64>, DeliveryAccumulator> = BTreeMap::new();
+
+    for row in rows {
+        let entry = per_client.entry(row.client_id).or_default();
+        entry.client_name = row.client_name;
+
+        let due_date = match row
+            .end_date
+            .as_deref()
+            .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        {
+            Some(d) => d,
+            None => {
+                entry.without_end_date += 1;
+                overall.without_end_date += 1;
+                continue;
+            }
+        };
+
+        let completion = row
+            .completion_date
+            .as_deref()
+            .and_then(|d| chrono::NaiveDate::parse_from_str(d.get(..10).unwrap_or(d), "%Y-%m-%d").ok());
+
+        let days_late = match (row.status.as_str(), completion) {
+            (_, Some(done)) => (done - due_date).num_days(),
+            ("completed", None) => continue,
+            (_, None) if due_date < today => (today - due_date).num_days(),
+            _ => continue,
+        };
+
+        if days_late > 0 {
+            let late = LateProject {
+                project_id: row.project_id,
+                project_name: row.project_name,
+                status: row.status,
+                end_date: due_date.format("%Y-%m-%d").to_string(),
+                completion_date: completion.map(|d| d.format("%Y-%m-%d").to_string()),
+                days_late,
+            };
+            entry.total_days_late += days_late;
+            entry.late.push(late.clone());
+            overall.total_days_late += days_late;
+            overall.late.push(late);
+        } else {
+            entry.on_time += 1;
+            overall.on_time += 1;
+        }
+    }
+
+    let clients = per_client
+        .into_iter()
+        .map(|(client_id, acc)| ClientDeliveryPerformance {
+            client_id,
+            client_name: acc.client_name.clone(),
+            stats: acc.stats(),
+            late_project_list: acc.late,
+        })
+        .collect();
+
+    Ok(DeliveryPerformanceReport {
+        start_date: start_date.to_string(),
+        end_date: end_date.to_string(),
+        overall: overall.stats(),
+        milestones: milestone_delivery(conn, start_date, end_date, client_id, today)?,
+        clients,
+    })
+}
+
+/// On-time figures for milestones due in the range. A completed milestone is
+/// on time when finished by its due date; an open one counts as late once the
+/// date has passed and is left out until then.
+fn milestone_delivery(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    client_id: Option<i64>,
+    today: chrono::NaiveDate,
+) -> Result<MilestoneDeliveryStats, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT pm.due_date, substr(pm.completed_at, 1, 10) FROM project_milestones pm
+             JOIN projects p ON pm.project_id = p.id
+             WHERE (?1 IS NULL OR p.client_id = ?1) AND pm.due_date >= ?2 AND pm.due_date <= ?3",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows: Vec<(String, Option<String>)> = stmt
+        .query_map(params![client_id, start_date, end_date], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut stats = MilestoneDeliveryStats::default();
+    for (due_date, completed) in rows {
+        let on_time = match completed {
+            Some(done) => done <= due_date,
+            None if due_date < today.format("%Y-%m-%d").to_string() => false,
+            None => continue,
+        };
+        stats.evaluated_milestones += 1;
+        if on_time {
+            stats.on_time_milestones += 1;
+        } else {
+            stats.late_milestones += 1;
+        }
+    }
+    if stats.evaluated_milestones > 0 {
+        stats.on_time_percentage = stats.on_time_milestones as f64 / stats.evaluated_milestones as f64 * 100.0;
+    }
+    Ok(stats)
+}
+
+/// Planned and actual hours for one schedule creation source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceHours {
+    pub source: String,
+    pub entry_count: i64,
+    pub planned_hours: f64,
+    pub actual_hours: f64,
+    pub planned_percentage: f64,
+}
+
+/// Get scheduled hours grouped by creation source, to show how much of the plan is automated
+#[tauri::command]
+pub fn get_hours_by_source(
+    token: String,
+    start_date: String,
+    end_date: String,
+    db: State<'_, Database>,
+) -> Result<Vec<SourceHours>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT COALESCE(source, 'manual') as source, COUNT(*),
+                    COALESCE(SUM(planned_hours), 0), COALESCE(SUM(actual_hours), 0)
+             FROM schedules
+             WHERE date >= ?1 AND date <= ?2 AND status != 'cancelled'
+             GROUP BY COALESCE(source, 'manual')
+             ORDER BY 3 DESC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut rows: Vec<SourceHours> = stmt
+        .query_map(params![start_date, end_date], |row| {
+            Ok(SourceHours {
+                source: row.get(0)?,
+                entry_count: row.get(1)?,
+                planned_hours: row.get(2)?,
+                actual_hours: row.get(3)?,
+                planned_percentage: 0.0,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let total_planned: f64 = rows.iter().map(|r| r.planned_hours).sum();
+    if total_planned > 0.0 {
+        for row in &mut rows {
+            row.planned_percentage = row.planned_hours / total_planned * 100.0;
+        }
+    }
+
+    Ok(rows)
+}
+
+/// A schedule entry where actual hours exceeded planned by more than the threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OvertimeEntry {
+    pub schedule_id: i64,
+    pub date: String,
+    pub machine_name: Option<String>,
+    pub project_name: Option<String>,
+    pub planned_hours: f64,
+    pub actual_hours: f64,
+    pub overtime_hours: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperatorOvertime {
+    pub operator_id: i64,
+    pub operator_name: String,
+    pub total_planned_hours: f64,
+    pub total_actual_hours: f64,
+    pub delta_hours: f64,
+    pub overtime_entries: Vec<OvertimeEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OvertimeReport {
+    pub start_date: String,
+    pub end_date: String,
+    pub threshold_hours: f64,
+    pub operators: Vec<OperatorOvertime>,
+}
+
+struct OvertimeRow {
+    schedule_id: i64,
+    date: String,
+    operator_id: i64,
+    operator_name: String,
+    machine_name: Option<String>,
+    project_name: Option<String>,
+    planned_hours: f64,
+    actual_hours: Option<f64>,
+}
+
+/// Get planned vs actual hours per operator, listing entries that ran over.
+///
+/// Admins can report on every operator or a single one; operators only on themselves.
+#[tauri::command]
+pub fn get_overtime_report(
+    token: String,
+    start_date: String,
+    end_date: String,
+    operator_id: Option<i64>,
+    db: State<'_, Database>,
+) -> Result<OvertimeReport, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "reports.overtime")?;
+
+    let operator_id = if has_permission(&conn, &user, "reports.overtime_all")? {
+        operator_id
+    } else {
+        match operator_id {
+            Some(id) if id != user.id => {
+                return Err(AppError::Forbidden(
+                    "Permission denied. Operators can only view their own overtime".to_string(),
+                ))
+            }
+            _ => Some(user.id),
+        }
+    };
+
+    let threshold = get_float(&conn, OVERTIME_THRESHOLD_KEY, DEFAULT_OVERTIME_THRESHOLD);
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.id, s.date, s.operator_id, u.full_name as operator_name,
+                    m.name as machine_name, p.name as project_name, s.planned_hours, s.actual_hours
+             FROM schedules s
+             JOIN users u ON s.operator_id = u.id
+             LEFT JOIN machines m ON s.machine_id = m.id
+             LEFT JOIN projects p ON s.project_id = p.id
+             WHERE s.date >= ?1 AND s.date <= ?2 AND s.status != 'cancelled'
+               AND (?3 IS NULL OR s.operator_id = ?3)
+             ORDER BY u.full_name, s.date",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows: Vec<OvertimeRow> = stmt
+        .query_map(params![start_date, end_date, operator_id], |row| {
+            Ok(OvertimeRow {
+                schedule_id: row.get(0)?,
+                date: row.get(1)?,
+                operator_id: row.get(2)?,
+                operator_name: row.get(3)?,
+                machine_name: row.get(4)?,
+                project_name: row.get(5)?,
+                planned_hours: row.get::<_, Option<f64>>(6)?.unwrap_or(0.0),
+                actual_hours: row.get(7)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut operators: Vec<OperatorOvertime> = Vec::new();
+    for row in rows {
+        if operators.last().map(|o| o.operator_id) != Some(row.operator_id) {
+            operators.push(OperatorOvertime {
+                operator_id: row.operator_id,
+                operator_name: row.operator_name,
+                total_planned_hours: 0.0,
+                total_actual_hours: 0.0,
+                delta_hours: 0.0,
+                overtime_entries: Vec::new(),
+            });
+        }
+        let Some(operator) = operators.last_mut() else { continue };
+
+        operator.total_planned_hours += row.planned_hours;
+        if let Some(actual) = row.actual_hours {
+            operator.total_actual_hours += actual;
+            let overtime = actual - row.planned_hours;
+            if overtime > threshold {
+                operator.overtime_entries.push(OvertimeEntry {
+                    schedule_id: row.schedule_id,
+                    date: row.date,
+                    machine_name: row.machine_name,
+                    project_name: row.project_name,
+                    planned_hours: row.planned_hours,
+                    actual_hours: actual,
+                    overtime_hours: overtime,
+                });
+            }
+        }
+    }
+    for operator in &mut operators {
+        operator.delta_hours = operator.total_actual_hours - operator.total_planned_hours;
+    }
+
+    Ok(OvertimeReport {
+        start_date,
+        end_date,
+        threshold_hours: threshold,
+        operators,
+    })
+}
+
+/// Cost figures for one project, machine or client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobCostGroup {
+    /// None collects entries without a project or client
+    pub group_id: Option<i64>,
+    pub group_name: Option<String>,
+    /// Actual hours on machines with an hourly rate
+    pub rated_hours: f64,
+    pub machine_cost: f64,
+    /// Actual hours on machines without a rate; not costed
+    pub unrated_hours: f64,
+    pub maintenance_cost: f64,
+    pub total_cost: f64,
+}
+
+/// Hours logged on a machine that has no hourly rate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnratedMachineHours {
+    pub machine_id: i64,
+    pub machine_name: String,
+    pub hours: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobCostReport {
+    pub start_date: String,
+    pub end_date: String,
+    pub group_by: String,
+    pub groups: Vec<JobCostGroup>,
+    pub total_machine_cost: f64,
+    pub total_maintenance_cost: f64,
+    /// Maintenance on machines with no logged hours in the period, which
+    /// cannot be attributed to a project or client
+    pub unallocated_maintenance_cost: f64,
+    pub unrated_machines: Vec<UnratedMachineHours>,
+}
+
+struct CostRow {
+    machine_id: i64,
+    machine_name: String,
+    hourly_rate: Option<f64>,
+    group_id: Option<i64>,
+    group_name: Option<String>,
+    hours: f64,
+}
+
+/// Machine and maintenance cost of the work done in a date range.
+///
+/// Machine cost is actual hours times the machine's hourly rate. Maintenance
+/// cost of non-cancelled maintenance in the range goes to its machine, or for
+/// project and client grouping is shared out in proportion to each group's
+/// hours on that machine.
+#[tauri::command]
+pub fn get_job_cost_report(
+    token: String,
+    start_date: String,
+    end_date: String,
+    group_by: String,
+    db: State<'_, Database>,
+) -> Result<JobCostReport, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let (group_id, group_name) = match group_by.as_str() {
+        "project" => ("p.id", "p.name"),
+        "machine" => ("m.id", "m.name"),
+        "client" => ("c.id", "c.name"),
+        _ => return Err(AppError::validation("group_by must be project, machine or client")),
+    };
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT m.id, m.name, m.hourly_rate, {group_id}, {group_name}, SUM(s.actual_hours)
+             FROM schedules s
+             JOIN machines m ON s.machine_id = m.id
+             LEFT JOIN projects p ON s.project_id = p.id
+             LEFT JOIN clients c ON p.client_id = c.id
+             WHERE s.date >= ?1 AND s.date <= ?2 AND s.status != 'cancelled' AND s.actual_hours > 0
+             GROUP BY m.id, {group_id}"
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let rows: Vec<CostRow> = stmt
+        .query_map(params![start_date, end_date], |row| {
+            Ok(CostRow {
+                machine_id: row.get(0)?,
+                machine_name: row.get(1)?,
+                hourly_rate: row.get::<_, Option<f64>>(2)?.filter(|rate| *rate > 0.0),
+                group_id: row.get(3)?,
+                group_name: row.get(4)?,
+                hours: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut maintenance_stmt = conn
+        .prepare(
+            "SELECT machine_id, SUM(cost) FROM maintenance
+             WHERE date >= ?1 AND date <= ?2 AND status != 'cancelled' AND cost IS NOT NULL
+             GROUP BY machine_id",
+        )
+        .map_err(|e| e.to_string())?;
+    let maintenance: BTreeMap<i64, f64> = maintenance_stmt
+        .query_map(params![start_date, end_date], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut machine_hours: BTreeMap<i64, f64> = BTreeMap::new();
+    for row in &rows {
+        *machine_hours.entry(row.machine_id).or_insert(0.0) += row.hours;
+    }
+
+    let mut groups: BTreeMap<Option<i64>, JobCostGroup> = BTreeMap::new();
+    let mut unrated: BTreeMap<i64, UnratedMachineHours> = BTreeMap::new();
+    for row in rows {
+        let group = groups.entry(row.group_id).or_insert_with(|| JobCostGroup {
+            group_id: row.group_id,
+            group_name: row.group_name.clone(),
+            rated_hours: 0.0,
+            machine_cost: 0.0,
+            unrated_hours: 0.0,
+            maintenance_cost: 0.0,
+            total_cost: 0.0,
+        });
+        match row.hourly_rate {
+            Some(rate) => {
+                group.rated_hours += row.hours;
+                group.machine_cost += row.hours * rate;
+            }
+            None => {
+                group.unrated_hours += row.hours;
+                unrated
+                    .entry(row.machine_id)
+                    .or_insert_with(|| UnratedMachineHours {
+                        machine_id: row.machine_id,
+                        machine_name: row.machine_name.clone(),
+                        hours: 0.0,
+                    })
+                    .hours += row.hours;
+            }
+        }
+        if let (Some(cost), Some(total_hours)) = (maintenance.get(&row.machine_id), machine_hours.get(&row.machine_id)) {
+            group.maintenance_cost += cost * row.hours / total_hours;
+        }
+    }
+
+    let total_maintenance_cost: f64 = maintenance.values().sum();
+    let mut unallocated_maintenance_cost: f64 = maintenance
+        .iter()
+        .filter(|(machine_id, _)| !machine_hours.contains_key(machine_id))
+        .map(|(_, cost)| cost)
+        .sum();
+
+    // Grouped by machine, idle machines still carry their own maintenance
+    if group_by == "machine" && unallocated_maintenance_cost > 0.0 {
+        let mut idle_stmt = conn
+            .prepare("SELECT name FROM machines WHERE id = ?1")
+            .map_err(|e| e.to_string())?;
+        for (machine_id, cost) in maintenance.iter().filter(|(id, _)| !machine_hours.contains_key(id)) {
+            let name: Option<String> = idle_stmt.query_row([machine_id], |row| row.get(0)).ok();
+            groups.insert(
+                Some(*machine_id),
+                JobCostGroup {
+                    group_id: Some(*machine_id),
+                    group_name: name,
+                    rated_hours: 0.0,
+                    machine_cost: 0.0,
+                    unrated_hours: 0.0,
+                    maintenance_cost: *cost,
+                    total_cost: 0.0,
+                },
+            );
+        }
+        unallocated_maintenance_cost = 0.0;
+    }
+
+    let mut groups: Vec<JobCostGroup> = groups.into_values().collect();
+    for group in &mut groups {
+        group.total_cost = group.machine_cost + group.maintenance_cost;
+    }
+    groups.sort_by(|a, b| b.total_cost.total_cmp(&a.total_cost));
+
+    Ok(JobCostReport {
+        start_date,
+        end_date,
+        group_by,
+        total_machine_cost: groups.iter().map(|g| g.machine_cost).sum(),
+        total_maintenance_cost,
+        unallocated_maintenance_cost,
+        unrated_machines: unrated.into_values().collect(),
+        groups,
+    })
+}
@@ -0,0 +1,108 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::commands::alerts::UNREAD_BY_ANYONE;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::utils::{require_view_permission, validate_session};
+
+const RUNTIME_ALERT_TITLE: &str = "Runtime maintenance due";
+
+/// A machine that has run longer than its maintenance interval
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeMaintenanceDue {
+    pub machine_id: i64,
+    pub machine_name: String,
+    pub runtime_hours: f64,
+    pub maintenance_interval_hours: f64,
+    /// Runtime at the last completed maintenance, None if there has been none
+    pub last_maintenance_runtime: Option<f64>,
+    pub hours_since_maintenance: f64,
+    pub hours_overdue: f64,
+}
+
+/// Machines with an hour-based interval whose runtime since the last completed
+/// maintenance has reached it
+pub fn find_runtime_maintenance_due(conn: &Connection) -> Result<Vec<RuntimeMaintenanceDue>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT m.id, m.name, COALESCE(m.runtime_hours, 0), m.maintenance_interval_hours,
+                    (SELECT mt.runtime_hours_at FROM maintenance mt
+                     WHERE mt.machine_id = m.id AND mt.status = 'completed' AND mt.runtime_hours_at IS NOT NULL
+                     ORDER BY mt.runtime_hours_at DESC LIMIT 1)
+             FROM machines m
+             WHERE m.maintenance_interval_hours IS NOT NULL AND m.archived_at IS NULL
+             ORDER BY m.name",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let due = stmt
+        .query_map([], |row| {
+            let runtime_hours: f64 = row.get(2)?;
+            let interval: f64 = row.get(3)?;
+            let last: Option<f64> = row.get(4)?;
+            let since = runtime_hours - last.unwrap_or(0.0);
+            Ok(RuntimeMaintenanceDue {
+                machine_id: row.get(0)?,
+                machine_name: row.get(1)?,
+                runtime_hours,
+                maintenance_interval_hours: interval,
+                last_maintenance_runtime: last,
+                hours_since_maintenance: since,
+                hours_overdue: since - interval,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .filter(|d| d.hours_overdue >= 0.0)
+        .collect();
+
+    Ok(due)
+}
+
+/// Raise a maintenance alert for each machine past its runtime interval, unless
+/// it already has an unread one. Returns the machines that are due.
+pub fn raise_runtime_maintenance_alerts(conn: &Connection) -> Result<Vec<RuntimeMaintenanceDue>, String> {
+    let due = find_runtime_maintenance_due(conn)?;
+    for machine in &due {
+        let pending: i64 = conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM alerts WHERE title = ?1 AND machine_id = ?2 AND {}", UNREAD_BY_ANYONE),
+                params![RUNTIME_ALERT_TITLE, machine.machine_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if pending > 0 {
+            continue;
+        }
+
+        conn.execute(
+            "INSERT INTO alerts (alert_type, priority, title, message, machine_id)
+             VALUES ('maintenance', 'medium', ?1, ?2, ?3)",
+            params![
+                RUNTIME_ALERT_TITLE,
+                format!(
+                    "{} has run {:.1} hours since its last maintenance (interval {:.0} hours)",
+                    machine.machine_name, machine.hours_since_maintenance, machine.maintenance_interval_hours
+                ),
+                machine.machine_id
+            ],
+        )
+        .map_err(|e| format!("Failed to create alert: {}", e))?;
+    }
+    Ok(due)
+}
+
+/// Check runtime-based maintenance intervals, raising alerts for machines that are due
+#[tauri::command]
+pub fn check_runtime_maintenance_due(
+    token: String,
+    db: State<'_, Database>,
+) -> Result<Vec<RuntimeMaintenanceDue>, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_view_permission(&user)?;
+
+    Ok(raise_runtime_maintenance_alerts(&conn)?)
+}
@@ -1,45 +1,89 @@
-use rusqlite::params;
-use tauri::State;
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::{AppHandle, Manager, State};
 
+use crate::commands::conflicts::{
+    check_machine_availability, check_machine_double_booking, check_operator_double_booking, entry_window,
+    load_conflict_entries, minutes_to_time, time_to_minutes,
+};
+use crate::commands::holidays::{apply_holiday_mode, holidays_between, parse_holiday_mode};
+use crate::commands::machines::ensure_machine_not_archived;
+use crate::commands::projects::ensure_project_not_archived;
+use crate::commands::materials::late_materials_for;
+use crate::commands::shifts::fetch_shift;
+use crate::commands::status_transitions::{check_transition, ENTITY_SCHEDULE};
+use crate::commands::tags::fetch_tag;
+use crate::commands::users::ensure_user_active;
+use crate::commands::week_locks::{ensure_week_unlocked, find_week_lock};
 use crate::db::Database;
+use crate::error::AppError;
 use crate::models::{
-    CreateScheduleInput, DaySchedule, MachineWeekSchedule, Schedule, ScheduleEntry,
-    ScheduleWithDetails, UpdateScheduleInput, WeeklyScheduleResponse,
+    validate_schedule_source, BatchStatusResult, CreateScheduleInput, DaySchedule, LateMaterial, MachineWeekSchedule, OperatorWeekSchedule, ReassignOperatorResult, Schedule, ScheduleEntry,
+    ScheduleWithDetails, UpdateScheduleInput, User, WeeklyScheduleResponse, SOURCE_COPY, SOURCE_MANUAL,
+};
+use crate::utils::{
+    audit_json, count_rows, emit_change, get_int, has_permission, normalize_hours, normalize_optional_hours, parse_date,
+    record_audit, record_audit_update, require_permission, require_view_permission, run_operation, start_of_week,
+    validate_session,
+    HoursValue, MutationResult, OperationContext, OperationOutcome, PageRequest, Paginated, ACTION_CREATED,
+    ACTION_DELETED, ACTION_UPDATED, ALERT_CHANGED_EVENT, DEFAULT_SCHEDULE_EDIT_CUTOFF, SCHEDULE_CHANGED_EVENT,
+    SCHEDULE_EDIT_CUTOFF_KEY,
 };
-use crate::utils::{require_edit_permission, require_view_permission, validate_session};
 
-/// Get weekly schedule for all machines
+/// Get weekly schedule for all machines, or only those in `machine_ids`
+///
+/// Unknown ids are ignored and an empty list means all machines. `site_id`
+/// further limits the grid to one site's machines.
 #[tauri::command]
 pub fn get_weekly_schedule(
     token: String,
-    week_start: String, // YYYY-MM-DD (Monday)
+    week_start: String, // YYYY-MM-DD, any day of the week
+    machine_ids: Option<Vec<i64>>,
+    site_id: Option<i64>,
     db: State<'_, Database>,
-) -> Result<WeeklyScheduleResponse, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+) -> Result<WeeklyScheduleResponse, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
-    // Calculate week end (Sunday)
-    let start_date =
-        chrono::NaiveDate::parse_from_str(&week_start, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    // The week runs from the configured first day
+    let start_date = start_of_week(&conn, parse_date("week_start", &week_start)?);
+    let week_start = start_date.format("%Y-%m-%d").to_string();
     let end_date = start_date + chrono::Duration::days(6);
     let week_end = end_date.format("%Y-%m-%d").to_string();
+    let holidays = holidays_between(&conn, start_date, end_date)?;
 
     // Get all machines
     let mut stmt = conn
-        .prepare("SELECT id, name FROM machines ORDER BY name ASC")
+        .prepare(
+            "SELECT m.id, m.name, m.site_id, st.name, st.timezone
+             FROM machines m
+             LEFT JOIN sites st ON m.site_id = st.id
+             WHERE (?1 IS NULL OR m.site_id = ?1)
+               AND (m.archived_at IS NULL OR EXISTS (
+                   SELECT 1 FROM schedules s WHERE s.machine_id = m.id AND s.date >= ?2 AND s.date <= ?3
+               ))
+             ORDER BY m.name ASC",
+        )
         .map_err(|e| e.to_string())?;
 
-    let machines: Vec<(i64, String)> = stmt
-        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+    // Archived machines only appear in weeks where they still have entries
+    type MachineRow = (i64, String, Option<i64>, Option<String>, Option<String>);
+    let mut machines: Vec<MachineRow> = stmt
+        .query_map(params![site_id, week_start, week_end], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+        })
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
         .collect();
 
+    if let Some(ids) = machine_ids.filter(|ids| !ids.is_empty()) {
+        machines.retain(|(id, ..)| ids.contains(id));
+    }
+
     // Build schedule for each machine
     let mut machine_schedules = Vec::new();
 
-    for (machine_id, machine_name) in machines {
+    for (machine_id, machine_name, site_id, site_name, site_timezone) in machines {
         let mut days: Vec<DaySchedule> = Vec::new();
 
         // For each day of the week (Monday to Sunday)
@@ -51,41 +95,22 @@ pub fn get_weekly_schedule(
             // Get schedules for this machine on this day
             let mut stmt = conn
                 .prepare(
-                    "SELECT s.*, p.name as project_name, u.full_name as operator_name
+                    "SELECT s.*, p.name as project_name, u.full_name as operator_name,
+                            sh.name as shift_name, cb.full_name as created_by_name,
+                            tg.name as tag_name, tg.color as tag_color
                      FROM schedules s
                      LEFT JOIN projects p ON s.project_id = p.id
                      LEFT JOIN users u ON s.operator_id = u.id
+                     LEFT JOIN shifts sh ON s.shift_id = sh.id
+                     LEFT JOIN users cb ON s.created_by = cb.id
+                     LEFT JOIN schedule_tags tg ON s.tag_id = tg.id
                      WHERE s.machine_id = ?1 AND s.date = ?2
                      ORDER BY s.start_time ASC",
                 )
                 .map_err(|e| e.to_string())?;
 
             let entries: Vec<ScheduleEntry> = stmt
-                .query_map(params![machine_id, date_str], |row| {
-                    Ok(ScheduleEntry {
-                        id: row.get("id")?,
-                        project_id: row.get("project_id")?,
-                        project_name: row.get("project_name")?,
-                        operator_id: row.get("operator_id")?,
-                        operator_name: row.get("operator_name")?,
-                        load_name: row.get("load_name")?,
-                        start_time: row.get("start_time")?,
-                        end_time: row.get("end_time")?,
-                        planned_hours: row.get("planned_hours")?,
-                        actual_hours: row.get("actual_hours")?,
-                        notes: row.get("notes")?,
-                        status: row.get("status")?,
-                        setup_hours: row.get("setup_hours").unwrap_or(0.0),
-                        sequence_order: row.get("sequence_order").unwrap_or(0),
-                        drawing_number: row.get("drawing_number").ok().flatten(),
-                        revision: row.get("revision").ok().flatten(),
-                        material: row.get("material").ok().flatten(),
-                        cam_planned_hours: row.get("cam_planned_hours").ok().flatten(),
-                        cam_actual_hours: row.get("cam_actual_hours").ok().flatten(),
-                        cam_buffer_percentage: row.get("cam_buffer_percentage").ok().flatten(),
-                        job_type: row.get("job_type").ok().flatten(),
-                    })
-                })
+                .query_map(params![machine_id, date_str], ScheduleEntry::from_row)
                 .map_err(|e| e.to_string())?
                 .filter_map(|r| r.ok())
                 .collect();
@@ -94,9 +119,12 @@ pub fn get_weekly_schedule(
             let total_planned: f64 = entries.iter().map(|e| e.planned_hours).sum();
             let total_actual: f64 = entries.iter().map(|e| e.actual_hours.unwrap_or(0.0)).sum();
 
+            let holiday_name = holidays.get(&current_date).cloned();
             days.push(DaySchedule {
                 date: date_str,
                 day_name,
+                is_holiday: holiday_name.is_some(),
+                holiday_name,
                 entries,
                 total_planned_hours: total_planned,
                 total_actual_hours: total_actual,
@@ -110,6 +138,9 @@ pub fn get_weekly_schedule(
         machine_schedules.push(MachineWeekSchedule {
             machine_id,
             machine_name,
+            site_id,
+            site_name,
+            site_timezone,
             days,
             weekly_planned_hours: weekly_planned,
             weekly_actual_hours: weekly_actual,
@@ -117,79 +148,527 @@ pub fn get_weekly_schedule(
     }
 
     Ok(WeeklyScheduleResponse {
+        lock: find_week_lock(&conn, &week_start)?,
         week_start: week_start.clone(),
         week_end,
         machines: machine_schedules,
     })
 }
 
+/// Get one operator's assignments for a week across all machines
+///
+/// Viewers only ever see their own week, whatever `operator_id` they pass.
+#[tauri::command]
+pub fn get_operator_week(
+    token: String,
+    operator_id: i64,
+    week_start: String, // YYYY-MM-DD, any day of the week
+    db: State<'_, Database>,
+) -> Result<OperatorWeekSchedule, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let operator_id = if has_permission(&conn, &user, "schedules.view_all")? { operator_id } else { user.id };
+    let operator_name: Option<String> = conn
+        .query_row(
+            "SELECT COALESCE(full_name, username) FROM users WHERE id = ?1",
+            [operator_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Operator not found".to_string())?;
+
+    let start_date = start_of_week(&conn, parse_date("week_start", &week_start)?);
+    let week_start = start_date.format("%Y-%m-%d").to_string();
+    let end_date = start_date + chrono::Duration::days(6);
+    let week_end = end_date.format("%Y-%m-%d").to_string();
+    let holidays = holidays_between(&conn, start_date, end_date)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.*, m.name as machine_name, p.name as project_name, u.full_name as operator_name,
+                    sh.name as shift_name, cb.full_name as created_by_name,
+                    tg.name as tag_name, tg.color as tag_color, st.timezone as site_timezone
+             FROM schedules s
+             JOIN machines m ON s.machine_id = m.id
+             LEFT JOIN sites st ON m.site_id = st.id
+             LEFT JOIN projects p ON s.project_id = p.id
+             LEFT JOIN users u ON s.operator_id = u.id
+             LEFT JOIN shifts sh ON s.shift_id = sh.id
+             LEFT JOIN users cb ON s.created_by = cb.id
+             LEFT JOIN schedule_tags tg ON s.tag_id = tg.id
+             WHERE s.operator_id = ?1 AND s.date >= ?2 AND s.date <= ?3
+             ORDER BY s.date, s.start_time, m.name",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let entries: Vec<(String, ScheduleEntry)> = stmt
+        .query_map(params![operator_id, week_start, week_end], |row| {
+            Ok((row.get("date")?, ScheduleEntry::from_row(row)?))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut days = Vec::new();
+    for day_offset in 0..7 {
+        let current_date = start_date + chrono::Duration::days(day_offset);
+        let date_str = current_date.format("%Y-%m-%d").to_string();
+        let day_entries: Vec<ScheduleEntry> = entries
+            .iter()
+            .filter(|(date, _)| *date == date_str)
+            .map(|(_, entry)| entry.clone())
+            .collect();
+
+        let holiday_name = holidays.get(&current_date).cloned();
+        days.push(DaySchedule {
+            day_name: current_date.format("%A").to_string(),
+            date: date_str,
+            is_holiday: holiday_name.is_some(),
+            holiday_name,
+            total_planned_hours: day_entries.iter().map(|e| e.planned_hours).sum(),
+            total_actual_hours: day_entries.iter().map(|e| e.actual_hours.unwrap_or(0.0)).sum(),
+            entries: day_entries,
+        });
+    }
+
+    Ok(OperatorWeekSchedule {
+        operator_id,
+        operator_name,
+        weekly_planned_hours: days.iter().map(|d| d.total_planned_hours).sum(),
+        weekly_actual_hours: days.iter().map(|d| d.total_actual_hours).sum(),
+        lock: find_week_lock(&conn, &week_start)?,
+        week_start,
+        week_end,
+        days,
+    })
+}
+
 /// Get single schedule entry
 #[tauri::command]
 pub fn get_schedule(
     token: String,
     id: i64,
     db: State<'_, Database>,
-) -> Result<ScheduleWithDetails, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+) -> Result<ScheduleWithDetails, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
+    Ok(fetch_schedule_with_details(&conn, id)?)
+}
+
+/// Load a single schedule entry joined with machine, project and operator names
+pub(crate) fn fetch_schedule_with_details(conn: &Connection, id: i64) -> Result<ScheduleWithDetails, String> {
     conn.query_row(
-        "SELECT s.*, m.name as machine_name, p.name as project_name, u.full_name as operator_name
+        "SELECT s.*, m.name as machine_name, p.name as project_name, u.full_name as operator_name,
+                ub.username as updated_by_username, cb.full_name as created_by_name,
+                tg.name as tag_name, tg.color as tag_color
          FROM schedules s
          LEFT JOIN machines m ON s.machine_id = m.id
          LEFT JOIN projects p ON s.project_id = p.id
          LEFT JOIN users u ON s.operator_id = u.id
+         LEFT JOIN users ub ON s.updated_by = ub.id
+         LEFT JOIN users cb ON s.created_by = cb.id
+         LEFT JOIN schedule_tags tg ON s.tag_id = tg.id
          WHERE s.id = ?1",
         [id],
-        |row| {
-            let schedule = Schedule::from_row(row)?;
-            Ok(ScheduleWithDetails {
-                schedule,
-                machine_name: row.get("machine_name")?,
-                project_name: row.get("project_name")?,
-                operator_name: row.get("operator_name")?,
-            })
-        },
+        ScheduleWithDetails::from_row,
+    )
+    .map_err(|_| "Schedule not found".to_string())
+}
+
+/// Reject anything outside the schedule status whitelist
+pub(crate) fn validate_schedule_status(status: &str) -> Result<(), String> {
+    if !["scheduled", "in-progress", "completed", "cancelled"].contains(&status) {
+        return Err("Invalid status".to_string());
+    }
+    Ok(())
+}
+
+/// An allowed edit-cutoff override that still has to be recorded
+#[must_use]
+pub(crate) struct CutoffOverride {
+    schedule_id: i64,
+    action: String,
+    date: String,
+    cutoff: String,
+    reason: String,
+}
+
+impl CutoffOverride {
+    /// Write the override to the audit log and raise it as an info alert so other
+    /// admins see it. Call inside the transaction that makes the change.
+    pub(crate) fn record(&self, conn: &Connection, user: &User) -> Result<(), String> {
+        let details = serde_json::json!({
+            "action": self.action,
+            "date": self.date,
+            "cutoff": self.cutoff,
+            "reason": self.reason,
+        });
+        record_audit(
+            conn,
+            user,
+            "cutoff_override",
+            "schedules",
+            Some(self.schedule_id),
+            None,
+            Some(&details.to_string()),
+        );
+        conn.execute(
+            "INSERT INTO alerts (alert_type, priority, title, message) VALUES ('info', 'medium', ?1, ?2)",
+            params![
+                "Locked schedule entry changed",
+                format!(
+                    "{} overrode the edit cutoff to {} entry #{} dated {}: {}",
+                    user.full_name.as_deref().unwrap_or(&user.username),
+                    self.action,
+                    self.schedule_id,
+                    self.date,
+                    self.reason
+                )
+            ],
+        )
+        .map_err(|e| format!("Failed to create alert: {}", e))?;
+        Ok(())
+    }
+}
+
+/// Refuse changes to entries dated before the edit cutoff.
+///
+/// Holders of `schedules.override_lock` (Admins by default) may go ahead by giving
+/// `override_reason`, in which case the override is returned for the caller to
+/// record once the change is known to go through. `target_date` covers edits that
+/// move an entry back into the locked period.
+pub(crate) fn check_edit_cutoff(
+    conn: &Connection,
+    user: &User,
+    schedule_id: i64,
+    target_date: Option<&str>,
+    action: &str,
+    override_reason: Option<&str>,
+) -> Result<Option<CutoffOverride>, String> {
+    let days = get_int(conn, SCHEDULE_EDIT_CUTOFF_KEY, DEFAULT_SCHEDULE_EDIT_CUTOFF);
+    if days <= 0 {
+        return Ok(None);
+    }
+    let date: String = conn
+        .query_row("SELECT date FROM schedules WHERE id = ?1", [schedule_id], |row| row.get(0))
+        .map_err(|_| "Schedule not found".to_string())?;
+    let earliest = match target_date {
+        Some(target) if target < date.as_str() => target.to_string(),
+        _ => date,
+    };
+
+    let cutoff = (chrono::Local::now().date_naive() - chrono::Duration::days(days))
+        .format("%Y-%m-%d")
+        .to_string();
+    if earliest >= cutoff {
+        return Ok(None);
+    }
+
+    let reason = override_reason.map(str::trim).filter(|r| !r.is_empty());
+    let can_override = has_permission(conn, user, "schedules.override_lock")?;
+    let Some(reason) = reason.filter(|_| can_override) else {
+        return Err(format!(
+            "Entries dated before {} are locked (older than {} days). Use a correction, or ask an Admin to override with a reason",
+            cutoff, days
+        ));
+    };
+
+    Ok(Some(CutoffOverride {
+        schedule_id,
+        action: action.to_string(),
+        date: earliest,
+        cutoff,
+        reason: reason.to_string(),
+    }))
+}
+
+/// Refuse changes to an entry whose week is locked
+pub(crate) fn ensure_entry_unlocked(conn: &Connection, schedule_id: i64) -> Result<(), String> {
+    let date: String = conn
+        .query_row("SELECT date FROM schedules WHERE id = ?1", [schedule_id], |row| row.get(0))
+        .map_err(|_| "Schedule not found".to_string())?;
+    ensure_week_unlocked(conn, &date)
+}
+
+/// Normalize and store actual hours on an entry
+pub(crate) fn record_actual_hours(
+    conn: &Connection,
+    user: &User,
+    schedule_id: i64,
+    hours: &HoursValue,
+    override_reason: Option<&str>,
+) -> Result<(), String> {
+    let hours = normalize_hours(conn, hours)?;
+    let cutoff_override = check_edit_cutoff(conn, user, schedule_id, None, "log hours on", override_reason)?;
+    ensure_entry_unlocked(conn, schedule_id)?;
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let before = entry_hours(&tx, schedule_id)?;
+    let task_before = entry_task_hours(&tx, schedule_id)?;
+    tx.execute(
+        "UPDATE schedules SET actual_hours = ?1, updated_by = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?3",
+        params![hours, user.id, schedule_id],
+    )
+    .map_err(|e| format!("Failed to log hours: {}", e))?;
+    rollup_project_hours(&tx, before, (before.0, hours))?;
+    rollup_task_hours(&tx, task_before, (task_before.0, hours))?;
+    adjust_entry_machine_runtime(&tx, schedule_id, hours - before.1)?;
+    if hours != before.1 {
+        record_audit(
+            &tx,
+            user,
+            "log_hours",
+            "schedules",
+            Some(schedule_id),
+            Some(&serde_json::json!({ "actual_hours": before.1 }).to_string()),
+            Some(&serde_json::json!({ "actual_hours": hours }).to_string()),
+        );
+    }
+    if let Some(cutoff_override) = &cutoff_override {
+        cutoff_override.record(&tx, user)?;
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// Add `delta` hours to the runtime meter of the entry's machine
+pub(crate) fn adjust_entry_machine_runtime(conn: &Connection, schedule_id: i64, delta: f64) -> Result<(), String> {
+    if delta == 0.0 {
+        return Ok(());
+    }
+    conn.execute(
+        "UPDATE machines SET runtime_hours = MAX(COALESCE(runtime_hours, 0) + ?1, 0)
+         WHERE id = (SELECT machine_id FROM schedules WHERE id = ?2)",
+        params![delta, schedule_id],
+    )
+    .map_err(|e| format!("Failed to update machine runtime: {}", e))?;
+    Ok(())
+}
+
+/// Linked project and actual hours (0 when not logged) of an entry
+pub(crate) fn entry_hours(conn: &Connection, schedule_id: i64) -> Result<(Option<i64>, f64), String> {
+    conn.query_row(
+        "SELECT project_id, COALESCE(actual_hours, 0) FROM schedules WHERE id = ?1",
+        [schedule_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
     )
     .map_err(|_| "Schedule not found".to_string())
 }
 
+/// Linked task and actual hours (0 when not logged) of an entry
+pub(crate) fn entry_task_hours(conn: &Connection, schedule_id: i64) -> Result<(Option<i64>, f64), String> {
+    conn.query_row(
+        "SELECT task_id, COALESCE(actual_hours, 0) FROM schedules WHERE id = ?1",
+        [schedule_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .map_err(|_| "Schedule not found".to_string())
+}
+
+/// Apply the change in an entry's actual hours to its project(s).
+///
+/// Works on the difference between the stored values, so editing the same entry
+/// repeatedly never counts its hours twice.
+pub(crate) fn rollup_project_hours(conn: &Connection, before: (Option<i64>, f64), after: (Option<i64>, f64)) -> Result<(), String> {
+    rollup_hours(conn, "projects", before, after)
+}
+
+/// Apply the change in an entry's actual hours to its task(s), as
+/// `rollup_project_hours` does for projects
+pub(crate) fn rollup_task_hours(conn: &Connection, before: (Option<i64>, f64), after: (Option<i64>, f64)) -> Result<(), String> {
+    rollup_hours(conn, "project_tasks", before, after)
+}
+
+fn rollup_hours(
+    conn: &Connection,
+    table: &str,
+    before: (Option<i64>, f64),
+    after: (Option<i64>, f64),
+) -> Result<(), String> {
+    let adjust = |id: Option<i64>, delta: f64| -> Result<(), String> {
+        let Some(id) = id.filter(|_| delta != 0.0) else {
+            return Ok(());
+        };
+        conn.execute(
+            &format!(
+                "UPDATE {} SET actual_hours = MAX(COALESCE(actual_hours, 0) + ?1, 0), updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?2",
+                table
+            ),
+            params![delta, id],
+        )
+        .map(|_| ())
+        .map_err(|e| format!("Failed to update {} hours: {}", if table == "projects" { "project" } else { "task" }, e))
+    };
+
+    if before.0 == after.0 {
+        adjust(after.0, after.1 - before.1)
+    } else {
+        adjust(before.0, -before.1)?;
+        adjust(after.0, after.1)
+    }
+}
+
+/// Refuse a task that does not belong to the entry's project
+fn validate_entry_task(conn: &Connection, task_id: i64, project_id: Option<i64>) -> Result<(), String> {
+    let task_project: i64 = conn
+        .query_row("SELECT project_id FROM project_tasks WHERE id = ?1", [task_id], |row| row.get(0))
+        .map_err(|_| "Task not found".to_string())?;
+    if project_id != Some(task_project) {
+        return Err("Validation error: the task belongs to a different project than the entry".to_string());
+    }
+    Ok(())
+}
+
+/// Materials that will still be outstanding on an active entry's date
+fn entry_material_warnings(conn: &Connection, schedule_id: i64) -> Result<Vec<LateMaterial>, String> {
+    let (project_id, date, status): (Option<i64>, String, String) = conn
+        .query_row(
+            "SELECT project_id, date, status FROM schedules WHERE id = ?1",
+            [schedule_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|_| "Schedule not found".to_string())?;
+    match project_id {
+        Some(project_id) if ["scheduled", "in-progress"].contains(&status.as_str()) => {
+            late_materials_for(conn, project_id, &date)
+        }
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Refuse an entry that, as now stored, double-books its machine or operator,
+/// or (with `check_machine`) sits on a machine in maintenance or error.
+///
+/// Uses the same rules as the weekly conflict sweep. Call it inside the write's
+/// transaction so that returning an error rolls the change back.
+fn ensure_no_slot_conflicts(conn: &Connection, id: i64, action: &str, check_machine: bool) -> Result<(), AppError> {
+    let date: String = conn
+        .query_row("SELECT date FROM schedules WHERE id = ?1", [id], |row| row.get(0))
+        .map_err(|_| "Schedule not found".to_string())?;
+    // Neighbouring days too, for overnight entries running into or out of this one's day
+    let day = chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    let day_entries = load_conflict_entries(
+        conn,
+        &(day - chrono::Duration::days(1)).format("%Y-%m-%d").to_string(),
+        &(day + chrono::Duration::days(1)).format("%Y-%m-%d").to_string(),
+        None,
+    )?;
+    // Cancelled entries are not loaded and never conflict
+    let Some(entry) = day_entries.iter().find(|e| e.id == id) else {
+        return Ok(());
+    };
+
+    let conflicts: Vec<String> = check_machine
+        .then(|| check_machine_availability(entry))
+        .flatten()
+        .into_iter()
+        .chain(day_entries.iter().filter_map(|other| {
+            check_machine_double_booking(entry, other).or_else(|| check_operator_double_booking(entry, other))
+        }))
+        .map(|c| c.message)
+        .collect();
+    if !conflicts.is_empty() {
+        return Err(AppError::Conflict(format!("Cannot {} entry: {}", action, conflicts.join("; "))));
+    }
+    Ok(())
+}
+
+/// Bring an entry recreated from the audit log back into the totals: its
+/// actual hours count towards its project, task and machine again. Refuses a
+/// locked week and a slot that has since been taken, as create_schedule does.
+///
+/// Call it inside the restore's transaction so that an error rolls it back.
+pub(crate) fn reinstate_entry(conn: &Connection, id: i64) -> Result<(), AppError> {
+    let date: String = conn
+        .query_row("SELECT date FROM schedules WHERE id = ?1", [id], |row| row.get(0))
+        .map_err(|_| "Schedule not found".to_string())?;
+    ensure_week_unlocked(conn, &date)?;
+
+    let hours = entry_hours(conn, id)?;
+    let task_hours = entry_task_hours(conn, id)?;
+    rollup_project_hours(conn, (hours.0, 0.0), hours)?;
+    rollup_task_hours(conn, (task_hours.0, 0.0), task_hours)?;
+    adjust_entry_machine_runtime(conn, id, hours.1)?;
+
+    ensure_no_slot_conflicts(conn, id, "restore", true)
+}
+
 /// Create schedule entry
 #[tauri::command]
 pub fn create_schedule(
     token: String,
     input: CreateScheduleInput,
+    app: AppHandle,
     db: State<'_, Database>,
-) -> Result<ScheduleWithDetails, String> {
+) -> Result<ScheduleWithDetails, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "schedules.edit")?;
 
     // Validate status
     if let Some(status) = &input.status {
-        if !["scheduled", "in-progress", "completed", "cancelled"].contains(&status.as_str()) {
-            return Err("Invalid status".to_string());
-        }
+        validate_schedule_status(status)?;
     }
 
+    let source = input.source.unwrap_or_else(|| SOURCE_MANUAL.to_string());
+    validate_schedule_source(&source)?;
+
     let status = input.status.unwrap_or_else(|| "scheduled".to_string());
+    let setup_hours = normalize_optional_hours(&conn, &input.setup_hours)?;
 
-    conn.execute(
-        "INSERT INTO schedules (machine_id, project_id, date, start_time, end_time, operator_id, load_name, planned_hours, notes, status, setup_hours, sequence_order, drawing_number, revision, material, cam_planned_hours, cam_actual_hours, cam_buffer_percentage, job_type, created_by)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)",
+    // Explicit times and hours win; anything omitted comes from the shift
+    ensure_week_unlocked(&conn, &input.date)?;
+    ensure_machine_not_archived(&conn, input.machine_id)?;
+    if let Some(project_id) = input.project_id {
+        ensure_project_not_archived(&conn, project_id)?;
+    }
+    if let Some(tag_id) = input.tag_id {
+        fetch_tag(&conn, tag_id)?;
+    }
+    if let Some(task_id) = input.task_id {
+        validate_entry_task(&conn, task_id, input.project_id)?;
+    }
+    if let Some(operator_id) = input.operator_id {
+        ensure_user_active(&conn, operator_id)?;
+    }
+
+    let shift = match input.shift_id {
+        Some(shift_id) => Some(fetch_shift(&conn, shift_id)?),
+        None => None,
+    };
+    let start_time = input
+        .start_time
+        .or_else(|| shift.as_ref().map(|s| s.start_time.clone()));
+    let end_time = input
+        .end_time
+        .or_else(|| shift.as_ref().map(|s| s.end_time.clone()));
+    let planned_hours = match (&input.planned_hours, &shift) {
+        (Some(hours), _) => normalize_hours(&conn, hours)?,
+        (None, Some(shift)) => shift.default_hours,
+        (None, None) => return Err(AppError::invalid_field("planned_hours", "planned hours are required")),
+    };
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT INTO schedules (machine_id, project_id, date, start_time, end_time, operator_id, load_name, planned_hours, notes, status, setup_hours, sequence_order, drawing_number, revision, material, cam_planned_hours, cam_actual_hours, cam_buffer_percentage, job_type, shift_id, tag_id, task_id, external_ref, source, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25)",
         params![
             input.machine_id,
             input.project_id,
             input.date,
-            input.start_time,
-            input.end_time,
+            start_time,
+            end_time,
             input.operator_id,
             input.load_name,
-            input.planned_hours,
+            planned_hours,
             input.notes,
             status,
-            input.setup_hours.unwrap_or(0.0),
+            setup_hours.unwrap_or(0.0),
             input.sequence_order.unwrap_or(0),
             input.drawing_number,
             input.revision,
@@ -198,32 +677,67 @@ pub fn create_schedule(
             input.cam_actual_hours,
             input.cam_buffer_percentage,
             input.job_type,
+            input.shift_id,
+            input.tag_id,
+            input.task_id,
+            input.external_ref.as_deref().map(str::trim).filter(|r| !r.is_empty()),
+            source,
             user.id
         ],
     )
     .map_err(|e| format!("Failed to create schedule: {}", e))?;
 
-    let new_id = conn.last_insert_rowid();
+    let new_id = tx.last_insert_rowid();
+    ensure_no_slot_conflicts(&tx, new_id, "create", true)?;
+    let created = fetch_schedule(&tx, new_id)?;
+    record_audit(&tx, &user, "create", "schedules", Some(new_id), None, audit_json(&created).as_deref());
+    tx.commit().map_err(|e| e.to_string())?;
+    let material_warnings = entry_material_warnings(&conn, new_id)?;
     drop(conn);
-    get_schedule(token, new_id, db)
+    let mut schedule = get_schedule(token, new_id, db)?;
+    schedule.material_warnings = material_warnings;
+    emit_change(&app, SCHEDULE_CHANGED_EVENT, ACTION_CREATED, vec![new_id]);
+    Ok(schedule)
+}
+
+fn fetch_schedule(conn: &Connection, id: i64) -> Result<Schedule, String> {
+    conn.query_row("SELECT * FROM schedules WHERE id = ?1", [id], Schedule::from_row)
+        .map_err(|_| "Schedule not found".to_string())
 }
 
 /// Update schedule entry
+///
+/// Status changes must follow the schedule transition map; an Admin may set
+/// `force_transition` to make any other change, which is audited.
 #[tauri::command]
 pub fn update_schedule(
     token: String,
     id: i64,
     input: UpdateScheduleInput,
+    override_reason: Option<String>,
+    force_transition: Option<bool>,
+    app: AppHandle,
     db: State<'_, Database>,
-) -> Result<ScheduleWithDetails, String> {
+) -> Result<ScheduleWithDetails, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "schedules.edit")?;
+
+    let cutoff_override =
+        check_edit_cutoff(&conn, &user, id, input.date.as_deref(), "edit", override_reason.as_deref())?;
+    ensure_entry_unlocked(&conn, id)?;
+    if let Some(date) = &input.date {
+        ensure_week_unlocked(&conn, date)?;
+    }
 
     let mut updates = Vec::new();
     let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
+    let (current_project, _) = entry_hours(&conn, id)?;
     if let Some(project_id) = input.project_id {
+        if current_project != Some(project_id) {
+            ensure_project_not_archived(&conn, project_id)?;
+        }
         updates.push("project_id = ?");
         values.push(Box::new(project_id));
     }
@@ -240,6 +754,13 @@ pub fn update_schedule(
         values.push(Box::new(end.clone()));
     }
     if let Some(op_id) = input.operator_id {
+        // Entries already held by a since-deactivated operator can still be edited
+        let current_operator: Option<i64> = conn
+            .query_row("SELECT operator_id FROM schedules WHERE id = ?1", [id], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        if current_operator != Some(op_id) {
+            ensure_user_active(&conn, op_id)?;
+        }
         updates.push("operator_id = ?");
         values.push(Box::new(op_id));
     }
@@ -247,11 +768,11 @@ pub fn update_schedule(
         updates.push("load_name = ?");
         values.push(Box::new(load.clone()));
     }
-    if let Some(planned) = input.planned_hours {
+    if let Some(planned) = normalize_optional_hours(&conn, &input.planned_hours)? {
         updates.push("planned_hours = ?");
         values.push(Box::new(planned));
     }
-    if let Some(actual) = input.actual_hours {
+    if let Some(actual) = normalize_optional_hours(&conn, &input.actual_hours)? {
         updates.push("actual_hours = ?");
         values.push(Box::new(actual));
     }
@@ -260,13 +781,15 @@ pub fn update_schedule(
         values.push(Box::new(notes.clone()));
     }
     if let Some(status) = &input.status {
-        if !["scheduled", "in-progress", "completed", "cancelled"].contains(&status.as_str()) {
-            return Err("Invalid status".to_string());
-        }
+        validate_schedule_status(status)?;
+        let current: String = conn
+            .query_row("SELECT status FROM schedules WHERE id = ?1", [id], |row| row.get(0))
+            .map_err(|_| "Schedule not found".to_string())?;
+        check_transition(&conn, &user, ENTITY_SCHEDULE, id, &current, status, force_transition.unwrap_or(false))?;
         updates.push("status = ?");
         values.push(Box::new(status.clone()));
     }
-    if let Some(setup) = input.setup_hours {
+    if let Some(setup) = normalize_optional_hours(&conn, &input.setup_hours)? {
         updates.push("setup_hours = ?");
         values.push(Box::new(setup));
     }
@@ -302,40 +825,79 @@ pub fn update_schedule(
         updates.push("job_type = ?");
         values.push(Box::new(job.clone()));
     }
+    if let Some(shift_id) = input.shift_id {
+        fetch_shift(&conn, shift_id)?;
+        updates.push("shift_id = ?");
+        values.push(Box::new(shift_id));
+    }
+    if let Some(external_ref) = &input.external_ref {
+        updates.push("external_ref = ?");
+        values.push(Box::new(Some(external_ref.trim()).filter(|r| !r.is_empty()).map(str::to_string)));
+    }
+    if input.clear_tag.unwrap_or(false) {
+        updates.push("tag_id = NULL");
+    } else if let Some(tag_id) = input.tag_id {
+        fetch_tag(&conn, tag_id)?;
+        updates.push("tag_id = ?");
+        values.push(Box::new(tag_id));
+    }
+    if input.clear_task.unwrap_or(false) {
+        updates.push("task_id = NULL");
+    } else if let Some(task_id) = input.task_id {
+        validate_entry_task(&conn, task_id, input.project_id.or(current_project))?;
+        updates.push("task_id = ?");
+        values.push(Box::new(task_id));
+    } else if input.project_id.is_some() && input.project_id != current_project {
+        // The old task belongs to the old project
+        updates.push("task_id = NULL");
+    }
 
     if updates.is_empty() {
-        return Err("No fields to update".to_string());
+        return Err(AppError::validation("no fields to update"));
     }
 
+    updates.push("updated_by = ?");
+    values.push(Box::new(user.id));
     updates.push("updated_at = CURRENT_TIMESTAMP");
     let query = format!("UPDATE schedules SET {} WHERE id = ?", updates.join(", "));
     values.push(Box::new(id));
 
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let existing = fetch_schedule(&tx, id)?;
+    let before = entry_hours(&tx, id)?;
+    let task_before = entry_task_hours(&tx, id)?;
+
     let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
-    conn.execute(&query, params.as_slice())
+    tx.execute(&query, params.as_slice())
         .map_err(|e| format!("Failed to update schedule: {}", e))?;
 
-    // If actual_hours was updated, recalculate the linked project's actual_hours
-    if input.actual_hours.is_some() {
-        let project_id: Option<i64> = conn
-            .query_row("SELECT project_id FROM schedules WHERE id = ?1", [id], |row| row.get(0))
-            .ok()
-            .flatten();
-        if let Some(pid) = project_id {
-            let _ = conn.execute(
-                "UPDATE projects SET actual_hours = (
-                    SELECT COALESCE(SUM(actual_hours), 0)
-                    FROM schedules
-                    WHERE project_id = ?1 AND actual_hours IS NOT NULL
-                ), updated_at = CURRENT_TIMESTAMP
-                WHERE id = ?1",
-                [pid],
-            );
-        }
+    // Keep the linked project's and task's actual_hours and the machine's
+    // runtime in step, including when the entry changes project or task
+    let after = entry_hours(&tx, id)?;
+    rollup_project_hours(&tx, before, after)?;
+    rollup_task_hours(&tx, task_before, entry_task_hours(&tx, id)?)?;
+    adjust_entry_machine_runtime(&tx, id, after.1 - before.1)?;
+    // Only a new slot or a revived entry can start a clash; the machine itself never changes here
+    let rescheduled = input.date.is_some()
+        || input.start_time.is_some()
+        || input.end_time.is_some()
+        || input.operator_id.is_some()
+        || input.status.is_some();
+    if rescheduled {
+        ensure_no_slot_conflicts(&tx, id, "update", false)?;
+    }
+    record_audit_update(&tx, &user, "update", "schedules", id, &existing, &fetch_schedule(&tx, id)?);
+    if let Some(cutoff_override) = &cutoff_override {
+        cutoff_override.record(&tx, &user)?;
     }
+    tx.commit().map_err(|e| e.to_string())?;
 
+    let material_warnings = entry_material_warnings(&conn, id)?;
     drop(conn);
-    get_schedule(token, id, db)
+    let mut schedule = get_schedule(token, id, db)?;
+    schedule.material_warnings = material_warnings;
+    emit_change(&app, SCHEDULE_CHANGED_EVENT, ACTION_UPDATED, vec![id]);
+    Ok(schedule)
 }
 
 /// Log actual hours for a schedule entry
@@ -343,117 +905,471 @@ pub fn update_schedule(
 pub fn log_actual_hours(
     token: String,
     schedule_id: i64,
-    hours: f64,
+    hours: HoursValue,
+    override_reason: Option<String>,
+    app: AppHandle,
     db: State<'_, Database>,
-) -> Result<ScheduleWithDetails, String> {
+) -> Result<ScheduleWithDetails, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "schedules.log_hours")?;
 
-    conn.execute(
-        "UPDATE schedules SET actual_hours = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
-        params![hours, schedule_id],
+    record_actual_hours(&conn, &user, schedule_id, &hours, override_reason.as_deref())?;
+
+    drop(conn);
+    let schedule = get_schedule(token, schedule_id, db)?;
+    emit_change(&app, SCHEDULE_CHANGED_EVENT, ACTION_UPDATED, vec![schedule_id]);
+    Ok(schedule)
+}
+
+/// Set the status of the given entries, returning the ids updated and the ids
+/// not found. Nothing changes if any entry is in a locked week, past the edit
+/// cutoff without an override, or refused by the transition map.
+fn set_entries_status(
+    conn: &Connection,
+    user: &User,
+    ids: Vec<i64>,
+    status: &str,
+    force_transition: bool,
+    override_reason: Option<&str>,
+) -> Result<(Vec<i64>, Vec<i64>), String> {
+    let mut not_found = Vec::new();
+    let mut entries = Vec::new();
+    let mut cutoff_overrides = Vec::new();
+    for id in ids {
+        let current: Option<String> = conn
+            .query_row("SELECT status FROM schedules WHERE id = ?1", [id], |row| row.get(0))
+            .optional()
+            .map_err(|e| e.to_string())?;
+        let Some(current) = current else {
+            not_found.push(id);
+            continue;
+        };
+        cutoff_overrides.extend(check_edit_cutoff(conn, user, id, None, "change the status of", override_reason)?);
+        ensure_entry_unlocked(conn, id)?;
+        entries.push((id, current));
+    }
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let mut updated_ids = Vec::new();
+
+    for (id, current) in entries {
+        check_transition(&tx, user, ENTITY_SCHEDULE, id, &current, status, force_transition)?;
+        if current == status {
+            continue;
+        }
+
+        if tx
+            .execute(
+                "UPDATE schedules SET status = ?1, updated_by = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?3",
+                params![status, user.id, id],
+            )
+            .map_err(|e| format!("Failed to update schedule status: {}", e))?
+            > 0
+        {
+            updated_ids.push(id);
+        }
+        record_audit(
+            &tx,
+            user,
+            "update",
+            "schedules",
+            Some(id),
+            Some(&serde_json::json!({ "status": current }).to_string()),
+            Some(&serde_json::json!({ "status": status }).to_string()),
+        );
+    }
+    for cutoff_override in &cutoff_overrides {
+        cutoff_override.record(&tx, user)?;
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok((updated_ids, not_found))
+}
+
+/// Set the status of several schedule entries at once
+///
+/// If any entry in the batch would make a transition the schedule map does not
+/// allow, nothing is updated unless an Admin sets `force_transition`. The same
+/// goes for entries in a locked week, or past the edit cutoff without an
+/// override reason.
+#[tauri::command]
+pub fn update_schedules_status(
+    token: String,
+    ids: Vec<i64>,
+    status: String,
+    force_transition: Option<bool>,
+    override_reason: Option<String>,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<BatchStatusResult, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "schedules.edit")?;
+
+    validate_schedule_status(&status)?;
+
+    let (updated_ids, not_found) = set_entries_status(
+        &conn,
+        &user,
+        ids,
+        &status,
+        force_transition.unwrap_or(false),
+        override_reason.as_deref(),
+    )?;
+
+    let updated = updated_ids.len() as i64;
+    emit_change(&app, SCHEDULE_CHANGED_EVENT, ACTION_UPDATED, updated_ids);
+    Ok(BatchStatusResult { updated, not_found })
+}
+
+/// Hand all of an operator's scheduled and in-progress entries within a date range
+/// to another operator, e.g. when someone goes on leave
+///
+/// The new operator must be an active Admin or Operator. Entries in locked weeks,
+/// or past the edit cutoff without an override reason, block the whole reassignment.
+#[tauri::command]
+pub fn reassign_operator(
+    token: String,
+    from_user_id: i64,
+    to_user_id: i64,
+    start_date: String,
+    end_date: String,
+    override_reason: Option<String>,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<ReassignOperatorResult, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "schedules.edit")?;
+
+    if from_user_id == to_user_id {
+        return Err(AppError::invalid_field("to_user_id", "choose a different operator to reassign to"));
+    }
+    let start = parse_date("start_date", &start_date)?;
+    let end = parse_date("end_date", &end_date)?;
+    if end < start {
+        return Err(AppError::invalid_field("end_date", "end date must not be before start date"));
+    }
+
+    let lookup_user = |id: i64| -> Result<(String, String, bool), String> {
+        conn.query_row(
+            "SELECT COALESCE(full_name, username), role, is_active FROM users WHERE id = ?1",
+            [id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|_| format!("User {} not found", id))
+    };
+    let (from_name, _, _) = lookup_user(from_user_id)?;
+    let (to_name, to_role, to_active) = lookup_user(to_user_id)?;
+    if !to_active {
+        return Err(AppError::validation(format!("{} is inactive and cannot be assigned work", to_name)));
+    }
+    if to_role == "Viewer" {
+        return Err(AppError::validation(format!("{} is a Viewer and cannot be assigned work", to_name)));
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, date FROM schedules
+             WHERE operator_id = ?1 AND date >= ?2 AND date <= ?3 AND status IN ('scheduled', 'in-progress')
+             ORDER BY date, start_time",
+        )
+        .map_err(|e| e.to_string())?;
+    let entries: Vec<(i64, String)> = stmt
+        .query_map(params![from_user_id, start_date, end_date], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut cutoff_overrides = Vec::new();
+    for (id, date) in &entries {
+        cutoff_overrides.extend(check_edit_cutoff(&conn, &user, *id, None, "reassign", override_reason.as_deref())?);
+        ensure_week_unlocked(&conn, date)?;
+    }
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    for (id, _) in &entries {
+        tx.execute(
+            "UPDATE schedules SET operator_id = ?1, updated_by = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?3",
+            params![to_user_id, user.id, id],
+        )
+        .map_err(|e| format!("Failed to reassign schedule: {}", e))?;
+        record_audit(
+            &tx,
+            &user,
+            "reassign_operator",
+            "schedules",
+            Some(*id),
+            Some(&serde_json::json!({ "operator_id": from_user_id }).to_string()),
+            Some(&serde_json::json!({ "operator_id": to_user_id }).to_string()),
+        );
+    }
+
+    let mut alert_id = None;
+    if !entries.is_empty() {
+        tx.execute(
+            "INSERT INTO alerts (alert_type, priority, title, message) VALUES ('schedule', 'medium', ?1, ?2)",
+            params![
+                "Operator reassigned",
+                format!(
+                    "{} schedule entries from {} to {} were reassigned from {} to {}",
+                    entries.len(),
+                    start_date,
+                    end_date,
+                    from_name,
+                    to_name
+                )
+            ],
+        )
+        .map_err(|e| format!("Failed to create alert: {}", e))?;
+        alert_id = Some(tx.last_insert_rowid());
+    }
+    for cutoff_override in &cutoff_overrides {
+        cutoff_override.record(&tx, &user)?;
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+
+    let schedule_ids: Vec<i64> = entries.into_iter().map(|(id, _)| id).collect();
+    emit_change(&app, SCHEDULE_CHANGED_EVENT, ACTION_UPDATED, schedule_ids.clone());
+    emit_change(&app, ALERT_CHANGED_EVENT, ACTION_CREATED, alert_id.into_iter().collect());
+    Ok(ReassignOperatorResult { reassigned: schedule_ids.len() as i64, schedule_ids })
+}
+
+/// Move an entry to another machine and/or day (planner drag-and-drop)
+///
+/// The target machine must be available and the moved entry must not double-book
+/// the machine or its operator; otherwise nothing changes. A new start time keeps
+/// the entry's original duration. Moving into or out of the period before the edit
+/// cutoff needs an override reason.
+#[tauri::command]
+pub fn move_schedule(
+    token: String,
+    id: i64,
+    new_machine_id: i64,
+    new_date: String,
+    new_start_time: Option<String>,
+    override_reason: Option<String>,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<ScheduleWithDetails, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "schedules.edit")?;
+
+    parse_date("new_date", &new_date)?;
+    let cutoff_override = check_edit_cutoff(&conn, &user, id, Some(&new_date), "move", override_reason.as_deref())?;
+    ensure_entry_unlocked(&conn, id)?;
+    ensure_week_unlocked(&conn, &new_date)?;
+
+    let (status, start_time, end_time): (String, Option<String>, Option<String>) = conn
+        .query_row(
+            "SELECT status, start_time, end_time FROM schedules WHERE id = ?1",
+            [id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|_| "Schedule not found".to_string())?;
+    if status == "completed" {
+        return Err(AppError::validation("completed entries cannot be moved"));
+    }
+    ensure_machine_not_archived(&conn, new_machine_id)?;
+
+    let (start_time, end_time) = match new_start_time {
+        Some(new_start) => {
+            let start = time_to_minutes(&new_start).ok_or("Start time must be in HH:MM format")?;
+            let end = entry_window(start_time.as_deref(), end_time.as_deref())
+                .map(|(s, e)| minutes_to_time(start + (e - s)));
+            (Some(new_start), end.or(end_time))
+        }
+        None => (start_time, end_time),
+    };
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let existing = fetch_schedule(&tx, id)?;
+    // Hours already logged move to the new machine's runtime meter
+    let (_, logged_hours) = entry_hours(&tx, id)?;
+    adjust_entry_machine_runtime(&tx, id, -logged_hours)?;
+    tx.execute(
+        "UPDATE schedules SET machine_id = ?1, date = ?2, start_time = ?3, end_time = ?4,
+                updated_by = ?5, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?6",
+        params![new_machine_id, new_date, start_time, end_time, user.id, id],
     )
-    .map_err(|e| format!("Failed to log hours: {}", e))?;
+    .map_err(|e| format!("Failed to move schedule: {}", e))?;
+    adjust_entry_machine_runtime(&tx, id, logged_hours)?;
+
+    // Check the target slot with the entry in place; dropping the transaction rolls back
+    ensure_no_slot_conflicts(&tx, id, "move", true)?;
+
+    record_audit_update(&tx, &user, "move", "schedules", id, &existing, &fetch_schedule(&tx, id)?);
+    if let Some(cutoff_override) = &cutoff_override {
+        cutoff_override.record(&tx, &user)?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
 
     drop(conn);
-    get_schedule(token, schedule_id, db)
+    let schedule = get_schedule(token, id, db)?;
+    emit_change(&app, SCHEDULE_CHANGED_EVENT, ACTION_UPDATED, vec![id]);
+    Ok(schedule)
+}
+
+/// Delete an entry, taking its logged hours back off the project, task and machine
+fn delete_entry(conn: &Connection, user: &User, id: i64, override_reason: Option<&str>) -> Result<(), String> {
+    let cutoff_override = check_edit_cutoff(conn, user, id, None, "delete", override_reason)?;
+    ensure_entry_unlocked(conn, id)?;
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let existing = fetch_schedule(&tx, id)?;
+    let before = entry_hours(&tx, id)?;
+    let task_before = entry_task_hours(&tx, id)?;
+    rollup_project_hours(&tx, before, (before.0, 0.0))?;
+    rollup_task_hours(&tx, task_before, (task_before.0, 0.0))?;
+    adjust_entry_machine_runtime(&tx, id, -before.1)?;
+    tx.execute("DELETE FROM schedules WHERE id = ?1", [id])
+        .map_err(|e| format!("Failed to delete schedule: {}", e))?;
+    record_audit(&tx, user, "delete", "schedules", Some(id), audit_json(&existing).as_deref(), None);
+    if let Some(cutoff_override) = &cutoff_override {
+        cutoff_override.record(&tx, user)?;
+    }
+    tx.commit().map_err(|e| e.to_string())
 }
 
 /// Delete schedule entry
 #[tauri::command]
-pub fn delete_schedule(token: String, id: i64, db: State<'_, Database>) -> Result<(), String> {
+pub fn delete_schedule(
+    token: String,
+    id: i64,
+    override_reason: Option<String>,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<(), AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "schedules.delete")?;
 
-    conn.execute("DELETE FROM schedules WHERE id = ?1", [id])
-        .map_err(|e| format!("Failed to delete schedule: {}", e))?;
+    delete_entry(&conn, &user, id, override_reason.as_deref())?;
 
+    emit_change(&app, SCHEDULE_CHANGED_EVENT, ACTION_DELETED, vec![id]);
     Ok(())
 }
 
-/// Get schedules for a specific date range
+/// Get schedules for a specific date range, optionally for one machine, creation source or tag
 #[tauri::command]
 pub fn get_schedules_by_date_range(
     token: String,
     start_date: String,
     end_date: String,
     machine_id: Option<i64>,
+    source: Option<String>,
+    tag_id: Option<i64>,
+    limit: Option<i64>,
+    offset: Option<i64>,
     db: State<'_, Database>,
-) -> Result<Vec<ScheduleWithDetails>, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+) -> Result<Paginated<ScheduleWithDetails>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
-    let query = if machine_id.is_some() {
-        "SELECT s.*, m.name as machine_name, p.name as project_name, u.full_name as operator_name
-         FROM schedules s
-         LEFT JOIN machines m ON s.machine_id = m.id
-         LEFT JOIN projects p ON s.project_id = p.id
-         LEFT JOIN users u ON s.operator_id = u.id
-         WHERE s.date >= ?1 AND s.date <= ?2 AND s.machine_id = ?3
-         ORDER BY s.date, m.name, s.start_time"
-    } else {
-        "SELECT s.*, m.name as machine_name, p.name as project_name, u.full_name as operator_name
-         FROM schedules s
-         LEFT JOIN machines m ON s.machine_id = m.id
-         LEFT JOIN projects p ON s.project_id = p.id
-         LEFT JOIN users u ON s.operator_id = u.id
+    if let Some(source) = &source {
+        validate_schedule_source(source)?;
+    }
+
+    let page = PageRequest::resolve(&conn, limit, offset, None)?;
+    let total = count_rows(
+        &conn,
+        "SELECT COUNT(*) FROM schedules s
          WHERE s.date >= ?1 AND s.date <= ?2
-         ORDER BY s.date, m.name, s.start_time"
-    };
+           AND (?3 IS NULL OR s.machine_id = ?3)
+           AND (?4 IS NULL OR s.source = ?4)
+           AND (?5 IS NULL OR s.tag_id = ?5)",
+        params![start_date, end_date, machine_id, source, tag_id],
+    )?;
 
-    let mut stmt = conn.prepare(query).map_err(|e| e.to_string())?;
-
-    let schedules: Vec<ScheduleWithDetails> = if let Some(mid) = machine_id {
-        stmt.query_map(params![start_date, end_date, mid], |row| {
-            let schedule = Schedule::from_row(row)?;
-            Ok(ScheduleWithDetails {
-                schedule,
-                machine_name: row.get("machine_name")?,
-                project_name: row.get("project_name")?,
-                operator_name: row.get("operator_name")?,
-            })
-        })
-        .map_err(|e| e.to_string())?
-        .filter_map(|r| r.ok())
-        .collect()
-    } else {
-        stmt.query_map(params![start_date, end_date], |row| {
-            let schedule = Schedule::from_row(row)?;
-            Ok(ScheduleWithDetails {
-                schedule,
-                machine_name: row.get("machine_name")?,
-                project_name: row.get("project_name")?,
-                operator_name: row.get("operator_name")?,
-            })
-        })
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.*, m.name as machine_name, p.name as project_name, u.full_name as operator_name,
+                    ub.username as updated_by_username, cb.full_name as created_by_name,
+                    tg.name as tag_name, tg.color as tag_color
+             FROM schedules s
+             LEFT JOIN machines m ON s.machine_id = m.id
+             LEFT JOIN projects p ON s.project_id = p.id
+             LEFT JOIN users u ON s.operator_id = u.id
+             LEFT JOIN users ub ON s.updated_by = ub.id
+             LEFT JOIN users cb ON s.created_by = cb.id
+             LEFT JOIN schedule_tags tg ON s.tag_id = tg.id
+             WHERE s.date >= ?1 AND s.date <= ?2
+               AND (?3 IS NULL OR s.machine_id = ?3)
+               AND (?4 IS NULL OR s.source = ?4)
+               AND (?5 IS NULL OR s.tag_id = ?5)
+             ORDER BY s.date, m.name, s.start_time
+             LIMIT ?6 OFFSET ?7",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let schedules: Vec<ScheduleWithDetails> = stmt
+        .query_map(
+            params![start_date, end_date, machine_id, source, tag_id, page.limit, page.offset],
+            ScheduleWithDetails::from_row,
+        )
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
-        .collect()
-    };
+        .collect();
 
-    Ok(schedules)
+    Ok(Paginated::new(schedules, page, total))
 }
 
 /// Copy schedule from one week to another
+///
+/// `holiday_mode` decides what happens to entries landing on a holiday: "ignore"
+/// (default), "skip" or "shift" to the next working day. With `background` set the
+/// copy runs on the async runtime and an operation id is returned instead of the
+/// copied entries; progress arrives via `operation:progress` events.
+/// Entries skipped for a holiday or that fail to insert come back as warnings.
 #[tauri::command]
 pub fn copy_week_schedule(
     token: String,
     source_week_start: String,
     target_week_start: String,
+    holiday_mode: Option<String>,
+    background: Option<bool>,
+    app: AppHandle,
     db: State<'_, Database>,
-) -> Result<i32, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+) -> Result<OperationOutcome<MutationResult<ScheduleWithDetails>>, AppError> {
+    let source_start = parse_date("source_week_start", &source_week_start)?;
+    let target_start = parse_date("target_week_start", &target_week_start)?;
 
-    let source_start = chrono::NaiveDate::parse_from_str(&source_week_start, "%Y-%m-%d")
-        .map_err(|e| e.to_string())?;
-    let target_start = chrono::NaiveDate::parse_from_str(&target_week_start, "%Y-%m-%d")
-        .map_err(|e| e.to_string())?;
+    let (user, source_start, target_start) = {
+        let conn = db.conn.lock();
+        let user = validate_session(&conn, &token)?;
+        require_permission(&conn, &user, "schedules.edit")?;
+        // Whole weeks, from the configured first day
+        let source_start = start_of_week(&conn, source_start);
+        let target_start = start_of_week(&conn, target_start);
+        for day in [target_start, target_start + chrono::Duration::days(6)] {
+            ensure_week_unlocked(&conn, &day.format("%Y-%m-%d").to_string())?;
+        }
+        (user, source_start, target_start)
+    };
+    let holiday_mode = parse_holiday_mode(holiday_mode.as_deref())?;
 
+    run_operation(&app, "copy_week", user.id, background.unwrap_or(false), move |ctx| {
+        let db = ctx.app().state::<Database>();
+        let conn = db.conn.lock();
+        let result = copy_week_entries(&conn, ctx, source_start, target_start, holiday_mode, user.id)?;
+        emit_change(ctx.app(), SCHEDULE_CHANGED_EVENT, ACTION_CREATED, result.affected_ids.clone());
+        Ok(result)
+    })
+}
+
+fn copy_week_entries(
+    conn: &Connection,
+    ctx: &OperationContext,
+    source_start: chrono::NaiveDate,
+    target_start: chrono::NaiveDate,
+    holiday_mode: &str,
+    user_id: i64,
+) -> Result<MutationResult<ScheduleWithDetails>, String> {
     let source_end = source_start + chrono::Duration::days(6);
     let day_diff = (target_start - source_start).num_days();
 
@@ -476,17 +1392,35 @@ pub fn copy_week_schedule(
         .filter_map(|r| r.ok())
         .collect();
 
-    let mut copied = 0;
+    let total = source_schedules.len() as i64;
+    ctx.progress(0, total, "Copying week")?;
 
-    for schedule in source_schedules {
+    // A cancelled copy drops the transaction, rolling back what was inserted so far
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let mut created_ids = Vec::new();
+    let mut warnings = Vec::new();
+
+    for (i, schedule) in source_schedules.into_iter().enumerate() {
         let old_date =
             chrono::NaiveDate::parse_from_str(&schedule.date, "%Y-%m-%d").map_err(|e| e.to_string())?;
-        let new_date = old_date + chrono::Duration::days(day_diff);
+        let new_date = match apply_holiday_mode(&tx, old_date + chrono::Duration::days(day_diff), holiday_mode)? {
+            Some(date) => date,
+            None => {
+                warnings.push(format!("Entry #{} skipped: {} is a holiday", schedule.id, old_date + chrono::Duration::days(day_diff)));
+                ctx.progress(i as i64 + 1, total, "Copying week")?;
+                continue;
+            }
+        };
         let new_date_str = new_date.format("%Y-%m-%d").to_string();
+        if let Err(e) = ensure_machine_not_archived(&tx, schedule.machine_id) {
+            warnings.push(format!("Entry #{} skipped: {}", schedule.id, e));
+            ctx.progress(i as i64 + 1, total, "Copying week")?;
+            continue;
+        }
 
-        conn.execute(
-            "INSERT INTO schedules (machine_id, project_id, date, start_time, end_time, operator_id, load_name, planned_hours, notes, status, created_by)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, 'scheduled', ?10)",
+        let inserted = tx.execute(
+            "INSERT INTO schedules (machine_id, project_id, date, start_time, end_time, operator_id, load_name, planned_hours, notes, status, source, created_by)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, 'scheduled', ?10, ?11)",
             params![
                 schedule.machine_id,
                 schedule.project_id,
@@ -497,14 +1431,134 @@ pub fn copy_week_schedule(
                 schedule.load_name,
                 schedule.planned_hours,
                 schedule.notes,
+                SOURCE_COPY,
+                user_id
+            ],
+        );
+        match inserted {
+            Ok(_) => created_ids.push(tx.last_insert_rowid()),
+            Err(e) => warnings.push(format!("Entry #{} could not be copied: {}", schedule.id, e)),
+        }
+        ctx.progress(i as i64 + 1, total, "Copying week")?;
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+
+    let rows = created_ids
+        .iter()
+        .map(|&id| fetch_schedule_with_details(conn, id))
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(MutationResult::new(created_ids, rows, warnings))
+}
+
+/// Copy schedule entries from one day to another, optionally for a single machine
+#[tauri::command]
+pub fn copy_day_schedule(
+    token: String,
+    source_date: String,
+    target_date: String,
+    machine_id: Option<i64>,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<Vec<ScheduleWithDetails>, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "schedules.edit")?;
+
+    parse_date("source_date", &source_date)?;
+    parse_date("target_date", &target_date)?;
+
+    if source_date == target_date {
+        return Err(AppError::invalid_field("target_date", "source and target dates must differ"));
+    }
+    ensure_week_unlocked(&conn, &target_date)?;
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+
+    let source_schedules: Vec<Schedule> = {
+        let mut stmt = tx
+            .prepare(
+                "SELECT * FROM schedules
+                 WHERE date = ?1 AND (?2 IS NULL OR machine_id = ?2)
+                 ORDER BY machine_id, sequence_order, start_time",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![source_date, machine_id], Schedule::from_row)
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        rows
+    };
+
+    let mut created_ids = Vec::new();
+
+    for schedule in source_schedules {
+        // Skip entries that already exist verbatim on the target day, and archived machines
+        let duplicate: i64 = tx
+            .query_row(
+                "SELECT COUNT(*) FROM schedules
+                 WHERE date = ?1 AND machine_id = ?2 AND project_id IS ?3
+                   AND start_time IS ?4 AND end_time IS ?5 AND operator_id IS ?6
+                   AND load_name IS ?7 AND planned_hours = ?8",
+                params![
+                    target_date,
+                    schedule.machine_id,
+                    schedule.project_id,
+                    schedule.start_time,
+                    schedule.end_time,
+                    schedule.operator_id,
+                    schedule.load_name,
+                    schedule.planned_hours
+                ],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if duplicate > 0 || ensure_machine_not_archived(&tx, schedule.machine_id).is_err() {
+            continue;
+        }
+
+        tx.execute(
+            "INSERT INTO schedules (machine_id, project_id, date, start_time, end_time, operator_id, load_name, planned_hours, notes, status, setup_hours, sequence_order, drawing_number, revision, material, cam_planned_hours, cam_buffer_percentage, job_type, shift_id, tag_id, task_id, external_ref, source, created_by)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, 'scheduled', ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)",
+            params![
+                schedule.machine_id,
+                schedule.project_id,
+                target_date,
+                schedule.start_time,
+                schedule.end_time,
+                schedule.operator_id,
+                schedule.load_name,
+                schedule.planned_hours,
+                schedule.notes,
+                schedule.setup_hours,
+                schedule.sequence_order,
+                schedule.drawing_number,
+                schedule.revision,
+                schedule.material,
+                schedule.cam_planned_hours,
+                schedule.cam_buffer_percentage,
+                schedule.job_type,
+                schedule.shift_id,
+                schedule.tag_id,
+                schedule.task_id,
+                schedule.external_ref,
+                SOURCE_COPY,
                 user.id
             ],
         )
-        .ok();
-        copied += 1;
+        .map_err(|e| format!("Failed to copy schedule: {}", e))?;
+        created_ids.push(tx.last_insert_rowid());
     }
 
-    Ok(copied)
+    tx.commit().map_err(|e| e.to_string())?;
+
+    let created = created_ids
+        .iter()
+        .map(|&id| fetch_schedule_with_details(&conn, id))
+        .collect::<Result<_, String>>()?;
+    emit_change(&app, SCHEDULE_CHANGED_EVENT, ACTION_CREATED, created_ids);
+    Ok(created)
 }
 
 /// Get today's schedule for the currently logged-in operator
@@ -513,53 +1567,177 @@ pub fn get_operator_schedule(
     token: String,
     date: String,
     db: State<'_, Database>,
-) -> Result<Vec<ScheduleWithDetails>, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+) -> Result<Vec<ScheduleWithDetails>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
 
     let mut stmt = conn.prepare(
-        "SELECT s.*, m.name as machine_name, p.name as project_name, u.full_name as operator_name
+        "SELECT s.*, m.name as machine_name, p.name as project_name, u.full_name as operator_name,
+                ub.username as updated_by_username, cb.full_name as created_by_name,
+                tg.name as tag_name, tg.color as tag_color
          FROM schedules s
          LEFT JOIN machines m ON s.machine_id = m.id
          LEFT JOIN projects p ON s.project_id = p.id
          LEFT JOIN users u ON s.operator_id = u.id
+         LEFT JOIN users ub ON s.updated_by = ub.id
+         LEFT JOIN users cb ON s.created_by = cb.id
+         LEFT JOIN schedule_tags tg ON s.tag_id = tg.id
          WHERE s.operator_id = ?1 AND s.date = ?2
          ORDER BY s.sequence_order ASC, s.start_time ASC"
     ).map_err(|e| e.to_string())?;
 
-    let schedules: Vec<ScheduleWithDetails> = stmt.query_map(params![user.id, date], |row| {
-        Ok(ScheduleWithDetails {
-            schedule: Schedule {
-                id: row.get("id")?,
-                machine_id: row.get("machine_id")?,
-                project_id: row.get("project_id")?,
-                date: row.get("date")?,
-                start_time: row.get("start_time")?,
-                end_time: row.get("end_time")?,
-                operator_id: row.get("operator_id")?,
-                load_name: row.get("load_name")?,
-                planned_hours: row.get("planned_hours")?,
-                actual_hours: row.get("actual_hours")?,
-                notes: row.get("notes")?,
-                status: row.get("status")?,
-                setup_hours: row.get("setup_hours").unwrap_or(0.0),
-                sequence_order: row.get("sequence_order").unwrap_or(0),
-                drawing_number: row.get("drawing_number").ok().flatten(),
-                revision: row.get("revision").ok().flatten(),
-                material: row.get("material").ok().flatten(),
-                cam_planned_hours: row.get("cam_planned_hours").ok().flatten(),
-                cam_actual_hours: row.get("cam_actual_hours").ok().flatten(),
-                cam_buffer_percentage: row.get("cam_buffer_percentage").ok().flatten(),
-                job_type: row.get("job_type").ok().flatten(),
-                created_at: row.get("created_at")?,
-                updated_at: row.get("updated_at")?,
-            },
-            machine_name: row.get("machine_name")?,
-            project_name: row.get("project_name")?,
-            operator_name: row.get("operator_name")?,
-        })
-    }).map_err(|e| e.to_string())?.filter_map(|r| r.ok()).collect();
+    let schedules: Vec<ScheduleWithDetails> = stmt.query_map(params![user.id, date], ScheduleWithDetails::from_row)
+        .map_err(|e| e.to_string())?.filter_map(|r| r.ok()).collect();
 
     Ok(schedules)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_pending_migrations;
+    use chrono::Datelike;
+
+    /// An admin and one entry long past the edit cutoff
+    fn sample_database() -> (Connection, User) {
+        let conn = Connection::open_in_memory().unwrap();
+        run_pending_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO users (id, username, password_hash, role) VALUES (1, 'admin', 'x', 'Admin');
+             INSERT INTO machines (id, name, model, status) VALUES (1, 'VMC-1', 'V1', 'active');
+             INSERT INTO schedules (id, machine_id, date, planned_hours, status)
+                 VALUES (1, 1, '2020-01-07', 8, 'scheduled');",
+        )
+        .unwrap();
+        let user = conn.query_row("SELECT * FROM users WHERE id = 1", [], User::from_row).unwrap();
+        (conn, user)
+    }
+
+    fn overrides_recorded(conn: &Connection) -> (i64, i64) {
+        let count = |sql: &str| conn.query_row(sql, [], |row| row.get(0)).unwrap();
+        (
+            count("SELECT COUNT(*) FROM audit_log WHERE action = 'cutoff_override'"),
+            count("SELECT COUNT(*) FROM alerts WHERE title = 'Locked schedule entry changed'"),
+        )
+    }
+
+    #[test]
+    fn cutoff_needs_a_reason() {
+        let (conn, user) = sample_database();
+        let err = record_actual_hours(&conn, &user, 1, &HoursValue::Number(6.0), None).unwrap_err();
+        assert!(err.contains("locked"), "{}", err);
+        assert_eq!(overrides_recorded(&conn), (0, 0));
+    }
+
+    #[test]
+    fn override_is_recorded_with_the_change() {
+        let (conn, user) = sample_database();
+        record_actual_hours(&conn, &user, 1, &HoursValue::Number(6.0), Some("late paperwork")).unwrap();
+        assert_eq!(overrides_recorded(&conn), (1, 1));
+    }
+
+    #[test]
+    fn override_is_not_recorded_for_a_locked_week() {
+        let (conn, user) = sample_database();
+        conn.execute("INSERT INTO schedule_locks (week_start, locked_by) VALUES ('2020-01-06', 1)", [])
+            .unwrap();
+        let err = record_actual_hours(&conn, &user, 1, &HoursValue::Number(6.0), Some("late paperwork")).unwrap_err();
+        assert!(err.contains("is locked by"), "{}", err);
+        assert_eq!(overrides_recorded(&conn), (0, 0));
+        let hours: Option<f64> =
+            conn.query_row("SELECT actual_hours FROM schedules WHERE id = 1", [], |row| row.get(0)).unwrap();
+        assert_eq!(hours, None);
+    }
+
+    #[test]
+    fn week_lock_follows_the_configured_first_day() {
+        let (conn, _) = sample_database();
+        conn.execute_batch(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('week_start_day', 'sunday');
+             INSERT INTO schedule_locks (week_start, locked_by) VALUES ('2020-01-05', 1);",
+        )
+        .unwrap();
+        let locked = |date: &str| find_week_lock(&conn, date).unwrap().map(|lock| lock.week_start);
+        assert_eq!(locked("2020-01-04"), None);
+        assert_eq!(locked("2020-01-05").as_deref(), Some("2020-01-05"));
+        assert_eq!(locked("2020-01-11").as_deref(), Some("2020-01-05"));
+        assert_eq!(locked("2020-01-12"), None);
+        assert_eq!(start_of_week(&conn, parse_date("week_start", "2020-01-07").unwrap()).to_string(), "2020-01-05");
+    }
+
+    #[test]
+    fn batch_status_refuses_a_locked_week() {
+        let (conn, user) = sample_database();
+        let this_week = chrono::Local::now().date_naive();
+        let locked_week = this_week + chrono::Duration::days(14);
+        conn.execute(
+            "INSERT INTO schedules (id, machine_id, date, planned_hours, status) VALUES (2, 1, ?1, 8, 'scheduled'),
+                 (3, 1, ?2, 8, 'scheduled')",
+            params![this_week.to_string(), locked_week.to_string()],
+        )
+        .unwrap();
+        let monday = locked_week - chrono::Duration::days(locked_week.weekday().num_days_from_monday() as i64);
+        conn.execute("INSERT INTO schedule_locks (week_start, locked_by) VALUES (?1, 1)", [monday.to_string()])
+            .unwrap();
+
+        let err = set_entries_status(&conn, &user, vec![2, 3], "in-progress", false, None).unwrap_err();
+        assert!(err.contains("is locked by"), "{}", err);
+        let moved: i64 = conn
+            .query_row("SELECT COUNT(*) FROM schedules WHERE status = 'in-progress'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(moved, 0);
+
+        let (updated, not_found) = set_entries_status(&conn, &user, vec![2, 99], "in-progress", false, None).unwrap();
+        assert_eq!((updated, not_found), (vec![2], vec![99]));
+    }
+
+    #[test]
+    fn deleting_an_entry_takes_its_hours_back() {
+        let (conn, user) = sample_database();
+        conn.execute_batch(
+            "INSERT INTO projects (id, name, status) VALUES (1, 'Housings', 'active');
+             INSERT INTO project_tasks (id, project_id, name) VALUES (1, 1, 'Roughing');
+             UPDATE machines SET runtime_hours = 100 WHERE id = 1;
+             INSERT INTO schedules (id, machine_id, project_id, task_id, date, planned_hours, status)
+                 VALUES (2, 1, 1, 1, date('now'), 8, 'in-progress');",
+        )
+        .unwrap();
+        record_actual_hours(&conn, &user, 2, &HoursValue::Number(6.5), None).unwrap();
+
+        delete_entry(&conn, &user, 2, None).unwrap();
+
+        let hours = |sql: &str| -> f64 { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+        assert_eq!(hours("SELECT actual_hours FROM projects WHERE id = 1"), 0.0);
+        assert_eq!(hours("SELECT actual_hours FROM project_tasks WHERE id = 1"), 0.0);
+        assert_eq!(hours("SELECT runtime_hours FROM machines WHERE id = 1"), 100.0);
+    }
+
+    #[test]
+    fn reinstated_entry_counts_again_unless_its_slot_is_taken() {
+        let (conn, _) = sample_database();
+        conn.execute_batch(
+            "INSERT INTO projects (id, name, status) VALUES (1, 'Housings', 'active');
+             UPDATE machines SET runtime_hours = 100 WHERE id = 1;
+             INSERT INTO schedules (id, machine_id, project_id, date, start_time, end_time, planned_hours,
+                                    actual_hours, status)
+                 VALUES (2, 1, 1, date('now'), '08:00', '16:00', 8, 6, 'in-progress');",
+        )
+        .unwrap();
+        reinstate_entry(&conn, 2).unwrap();
+        let hours = |sql: &str| -> f64 { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+        assert_eq!(hours("SELECT actual_hours FROM projects WHERE id = 1"), 6.0);
+        assert_eq!(hours("SELECT runtime_hours FROM machines WHERE id = 1"), 106.0);
+
+        conn.execute_batch(
+            "INSERT INTO schedules (id, machine_id, date, start_time, end_time, planned_hours, status)
+                 VALUES (3, 1, date('now'), '12:00', '14:00', 2, 'scheduled');",
+        )
+        .unwrap();
+        let tx = conn.unchecked_transaction().unwrap();
+        let err = reinstate_entry(&tx, 3).unwrap_err();
+        assert!(matches!(err, AppError::Conflict(_)), "{:?}", err);
+        drop(tx);
+        assert_eq!(hours("SELECT runtime_hours FROM machines WHERE id = 1"), 106.0);
+    }
+}
@@ -0,0 +1,173 @@
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{Project, ScheduleWithDetails};
+use crate::utils::{count_rows, require_view_permission, validate_session, PageRequest, Paginated};
+
+/// Everything carrying an external reference, grouped by entity type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalRefMatches {
+    pub external_ref: String,
+    pub projects: Vec<Project>,
+    pub schedules: Vec<ScheduleWithDetails>,
+}
+
+/// Find projects and schedule entries with exactly this external (ERP) reference
+#[tauri::command]
+pub fn find_by_external_ref(
+    token: String,
+    external_ref: String,
+    db: State<'_, Database>,
+) -> Result<ExternalRefMatches, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let external_ref = external_ref.trim().to_string();
+    if external_ref.is_empty() {
+        return Err(AppError::invalid_field("reference", "reference is required"));
+    }
+
+    let mut stmt = conn
+        .prepare("SELECT * FROM projects WHERE external_ref = ?1 ORDER BY name")
+        .map_err(|e| e.to_string())?;
+    let projects = stmt
+        .query_map([&external_ref], Project::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.*, m.name as machine_name, p.name as project_name, u.full_name as operator_name,
+                    ub.username as updated_by_username, cb.full_name as created_by_name,
+                    tg.name as tag_name, tg.color as tag_color
+             FROM schedules s
+             LEFT JOIN machines m ON s.machine_id = m.id
+             LEFT JOIN projects p ON s.project_id = p.id
+             LEFT JOIN users u ON s.operator_id = u.id
+             LEFT JOIN users ub ON s.updated_by = ub.id
+             LEFT JOIN users cb ON s.created_by = cb.id
+             LEFT JOIN schedule_tags tg ON s.tag_id = tg.id
+             WHERE s.external_ref = ?1
+             ORDER BY s.date, m.name, s.start_time",
+        )
+        .map_err(|e| e.to_string())?;
+    let schedules = stmt
+        .query_map([&external_ref], ScheduleWithDetails::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(ExternalRefMatches { external_ref, projects, schedules })
+}
+
+/// Escape LIKE wildcards so user input is matched literally
+pub(crate) fn escape_like(query: &str) -> String {
+    let mut escaped = String::with_capacity(query.len());
+    for c in query.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Find schedule entries whose load name or notes contain `query` (case-insensitive),
+/// newest first
+#[tauri::command]
+pub fn search_schedules(
+    token: String,
+    query: String,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    db: State<'_, Database>,
+) -> Result<Paginated<ScheduleWithDetails>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(AppError::invalid_field("query", "search text is required"));
+    }
+    let pattern = format!("%{}%", escape_like(query));
+
+    let page = PageRequest::resolve(&conn, limit, offset, None)?;
+    let total = count_rows(
+        &conn,
+        "SELECT COUNT(*) FROM schedules s
+         WHERE s.load_name LIKE ?1 ESCAPE '\\' OR s.notes LIKE ?1 ESCAPE '\\'",
+        params![pattern],
+    )?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.*, m.name as machine_name, p.name as project_name, u.full_name as operator_name,
+                    ub.username as updated_by_username, cb.full_name as created_by_name,
+                    tg.name as tag_name, tg.color as tag_color
+             FROM schedules s
+             LEFT JOIN machines m ON s.machine_id = m.id
+             LEFT JOIN projects p ON s.project_id = p.id
+             LEFT JOIN users u ON s.operator_id = u.id
+             LEFT JOIN users ub ON s.updated_by = ub.id
+             LEFT JOIN users cb ON s.created_by = cb.id
+             LEFT JOIN schedule_tags tg ON s.tag_id = tg.id
+             WHERE s.load_name LIKE ?1 ESCAPE '\\' OR s.notes LIKE ?1 ESCAPE '\\'
+             ORDER BY s.date DESC, m.name, s.start_time
+             LIMIT ?2 OFFSET ?3",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let schedules: Vec<ScheduleWithDetails> = stmt
+        .query_map(params![pattern, page.limit, page.offset], ScheduleWithDetails::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(Paginated::new(schedules, page, total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    #[test]
+    fn escapes_like_wildcards_in_filters() {
+        assert_eq!(escape_like("VMC"), "VMC");
+        assert_eq!(escape_like("100%"), "100\\%");
+        assert_eq!(escape_like("a_b"), "a\\_b");
+        assert_eq!(escape_like("C:\\"), "C:\\\\");
+        assert_eq!(escape_like("' OR 1=1 --"), "' OR 1=1 --");
+    }
+
+    #[test]
+    fn filter_text_is_matched_literally() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE machines (name TEXT NOT NULL);
+             INSERT INTO machines (name) VALUES ('VMC-1'), ('VMC 100%'), ('Lathe');",
+        )
+        .unwrap();
+        let matching = |filter: &str| -> Vec<String> {
+            let mut stmt = conn
+                .prepare("SELECT name FROM machines WHERE name LIKE ? ESCAPE '\\' ORDER BY name")
+                .unwrap();
+            stmt.query_map([format!("%{}%", escape_like(filter))], |row| row.get(0))
+                .unwrap()
+                .map(Result::unwrap)
+                .collect()
+        };
+
+        assert_eq!(matching("%"), vec!["VMC 100%"]);
+        assert_eq!(matching("_"), Vec::<String>::new());
+        assert_eq!(matching("' OR '1'='1"), Vec::<String>::new());
+        assert_eq!(matching("'; DROP TABLE machines; --"), Vec::<String>::new());
+        assert_eq!(matching("vmc").len(), 2);
+    }
+}
@@ -2,7 +2,8 @@ use rusqlite::params;
 use serde::{Deserialize, Serialize};
 use tauri::State;
 use crate::db::Database;
-use crate::utils::{require_edit_permission, require_view_permission, validate_session};
+use crate::error::AppError;
+use crate::utils::{require_permission, require_view_permission, validate_session};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShiftLog {
@@ -24,10 +25,14 @@ pub struct CreateShiftLogInput {
 }
 
 #[tauri::command]
-pub fn get_shift_logs(token: String, machine_id: Option<i64>, db: State<'_, Database>) -> Result<Vec<ShiftLog>, String> {
-    let conn = db.conn.lock();
-    let user = validate_session(&conn, &token)?;
+pub fn get_shift_logs(
+    token: String,
+    machine_id: Option<i64>,
+    db: State<'_, Database>,
+) -> Result<Vec<ShiftLog>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
     require_view_permission(&user)?;
+    let conn = db.read();
     let sql = if machine_id.is_some() {
         "SELECT sl.*, m.name as machine_name, u.full_name as operator_name
          FROM shift_logs sl LEFT JOIN machines m ON sl.machine_id = m.id LEFT JOIN users u ON sl.outgoing_operator_id = u.id
@@ -66,10 +71,14 @@ pub fn get_shift_logs(token: String, machine_id: Option<i64>, db: State<'_, Data
 }
 
 #[tauri::command]
-pub fn create_shift_log(token: String, input: CreateShiftLogInput, db: State<'_, Database>) -> Result<ShiftLog, String> {
+pub fn create_shift_log(
+    token: String,
+    input: CreateShiftLogInput,
+    db: State<'_, Database>,
+) -> Result<ShiftLog, AppError> {
     let conn = db.conn.lock();
     let user = validate_session(&conn, &token)?;
-    require_edit_permission(&user)?;
+    require_permission(&conn, &user, "schedules.log_hours")?;
     conn.execute(
         "INSERT INTO shift_logs (machine_id, shift_date, outgoing_operator_id, notes) VALUES (?1, ?2, ?3, ?4)",
         params![input.machine_id, input.shift_date, user.id, input.notes],
@@ -0,0 +1,192 @@
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::commands::conflicts::{entry_window, time_to_minutes};
+use crate::db::Database;
+use crate::error::AppError;
+use crate::utils::{require_permission, require_view_permission, validate_session, MAX_HOURS_PER_ENTRY};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Shift {
+    pub id: i64,
+    pub name: String,
+    pub start_time: String,
+    pub end_time: String,
+    pub default_hours: f64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Shift {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            name: row.get("name")?,
+            start_time: row.get("start_time")?,
+            end_time: row.get("end_time")?,
+            default_hours: row.get("default_hours")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateShiftInput {
+    pub name: String,
+    pub start_time: String,
+    pub end_time: String,
+    pub default_hours: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateShiftInput {
+    pub name: Option<String>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub default_hours: Option<f64>,
+}
+
+/// Look up a shift by id
+pub fn fetch_shift(conn: &Connection, id: i64) -> Result<Shift, String> {
+    conn.query_row("SELECT * FROM shifts WHERE id = ?1", params![id], Shift::from_row)
+        .map_err(|_| "Shift not found".to_string())
+}
+
+fn validate_time(time: &str) -> Result<(), String> {
+    time_to_minutes(time)
+        .map(|_| ())
+        .ok_or_else(|| format!("Invalid time '{}'. Use HH:MM", time))
+}
+
+fn validate_default_hours(hours: f64) -> Result<(), String> {
+    if !(0.0..=MAX_HOURS_PER_ENTRY).contains(&hours) {
+        return Err(format!("Default hours must be between 0 and {}", MAX_HOURS_PER_ENTRY));
+    }
+    Ok(())
+}
+
+/// Length of a shift in hours; end times before the start run past midnight
+fn shift_length(start_time: &str, end_time: &str) -> f64 {
+    entry_window(Some(start_time), Some(end_time))
+        .map(|(start, end)| (end - start) as f64 / 60.0)
+        .unwrap_or(0.0)
+}
+
+/// Get all shifts
+#[tauri::command]
+pub fn get_shifts(token: String, db: State<'_, Database>) -> Result<Vec<Shift>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let mut stmt = conn
+        .prepare("SELECT * FROM shifts ORDER BY start_time")
+        .map_err(|e| e.to_string())?;
+
+    let shifts = stmt
+        .query_map([], Shift::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(shifts)
+}
+
+/// Create a shift (admin only). Default hours fall back to the shift length.
+#[tauri::command]
+pub fn create_shift(token: String, input: CreateShiftInput, db: State<'_, Database>) -> Result<Shift, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "shifts.manage")?;
+
+    if input.name.trim().is_empty() {
+        return Err(AppError::invalid_field("name", "shift name is required"));
+    }
+    validate_time(&input.start_time)?;
+    validate_time(&input.end_time)?;
+    let default_hours = input
+        .default_hours
+        .unwrap_or_else(|| shift_length(&input.start_time, &input.end_time));
+    validate_default_hours(default_hours)?;
+
+    conn.execute(
+        "INSERT INTO shifts (name, start_time, end_time, default_hours) VALUES (?1, ?2, ?3, ?4)",
+        params![input.name.trim(), input.start_time, input.end_time, default_hours],
+    )
+    .map_err(|e| format!("Failed to create shift: {}", e))?;
+
+    Ok(fetch_shift(&conn, conn.last_insert_rowid())?)
+}
+
+/// Update a shift (admin only)
+#[tauri::command]
+pub fn update_shift(
+    token: String,
+    id: i64,
+    input: UpdateShiftInput,
+    db: State<'_, Database>,
+) -> Result<Shift, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "shifts.manage")?;
+
+    let mut updates = Vec::new();
+    let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(name) = &input.name {
+        if name.trim().is_empty() {
+            return Err(AppError::invalid_field("name", "shift name is required"));
+        }
+        updates.push("name = ?");
+        values.push(Box::new(name.trim().to_string()));
+    }
+    if let Some(start) = &input.start_time {
+        validate_time(start)?;
+        updates.push("start_time = ?");
+        values.push(Box::new(start.clone()));
+    }
+    if let Some(end) = &input.end_time {
+        validate_time(end)?;
+        updates.push("end_time = ?");
+        values.push(Box::new(end.clone()));
+    }
+    if let Some(hours) = input.default_hours {
+        validate_default_hours(hours)?;
+        updates.push("default_hours = ?");
+        values.push(Box::new(hours));
+    }
+
+    if updates.is_empty() {
+        return Ok(fetch_shift(&conn, id)?);
+    }
+
+    updates.push("updated_at = CURRENT_TIMESTAMP");
+    let query = format!("UPDATE shifts SET {} WHERE id = ?", updates.join(", "));
+    values.push(Box::new(id));
+
+    let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+    conn.execute(&query, params.as_slice())
+        .map_err(|e| format!("Failed to update shift: {}", e))?;
+
+    Ok(fetch_shift(&conn, id)?)
+}
+
+/// Delete a shift (admin only). Schedule entries keep their times but lose the shift link.
+#[tauri::command]
+pub fn delete_shift(token: String, id: i64, db: State<'_, Database>) -> Result<(), AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "shifts.manage")?;
+
+    conn.execute("UPDATE schedules SET shift_id = NULL WHERE shift_id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    let affected = conn
+        .execute("DELETE FROM shifts WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    if affected == 0 {
+        return Err(AppError::NotFound("Shift not found".to_string()));
+    }
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use chrono::{FixedOffset, NaiveDate};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::utils::{require_permission, require_view_permission, validate_session};
+
+/// A plant whose machines share a wall clock.
+///
+/// Machines without a site belong to the implicit local site, so single-site
+/// installs never need to create one. Schedule times are always local to the
+/// machine's site; `timezone` is a UTC offset ("+02:00") the UI can use to
+/// annotate or convert them. Sites observing daylight saving update the
+/// offset when the clocks change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Site {
+    pub id: i64,
+    pub name: String,
+    pub timezone: String,
+    pub created_at: String,
+}
+
+impl Site {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            name: row.get("name")?,
+            timezone: row.get("timezone")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSiteInput {
+    pub name: String,
+    pub timezone: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateSiteInput {
+    pub name: Option<String>,
+    pub timezone: Option<String>,
+}
+
+/// Look up a site by id
+pub fn fetch_site(conn: &Connection, id: i64) -> Result<Site, String> {
+    conn.query_row("SELECT * FROM sites WHERE id = ?1", params![id], Site::from_row)
+        .map_err(|_| "Site not found".to_string())
+}
+
+/// Parse a "+HH:MM" / "-HH:MM" offset
+pub fn parse_timezone(timezone: &str) -> Result<FixedOffset, String> {
+    let invalid = || format!("Invalid timezone '{}'. Use a UTC offset like +02:00", timezone.trim());
+    let tz = timezone.trim();
+    let (sign, rest) = match tz.chars().next() {
+        Some('+') => (1, &tz[1..]),
+        Some('-') => (-1, &tz[1..]),
+        _ => return Err(invalid()),
+    };
+    let (hours, minutes) = rest.split_once(':').ok_or_else(invalid)?;
+    if hours.len() != 2 || minutes.len() != 2 {
+        return Err(invalid());
+    }
+    let hours: i32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+    if hours > 14 || minutes > 59 {
+        return Err(invalid());
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(invalid)
+}
+
+/// Today's date at a site; the implicit site (`None`) uses the server's local clock
+pub fn site_today(conn: &Connection, site_id: Option<i64>) -> Result<NaiveDate, String> {
+    let Some(site_id) = site_id else {
+        return Ok(chrono::Local::now().date_naive());
+    };
+    let offset = parse_timezone(&fetch_site(conn, site_id)?.timezone)?;
+    Ok(chrono::Utc::now().with_timezone(&offset).date_naive())
+}
+
+/// Get all sites
+#[tauri::command]
+pub fn get_sites(token: String, db: State<'_, Database>) -> Result<Vec<Site>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let mut stmt = conn
+        .prepare("SELECT * FROM sites ORDER BY name")
+        .map_err(|e| e.to_string())?;
+
+    let sites = stmt
+        .query_map([], Site::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(sites)
+}
+
+/// Create a site (Admin only)
+#[tauri::command]
+pub fn create_site(token: String, input: CreateSiteInput, db: State<'_, Database>) -> Result<Site, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "sites.manage")?;
+
+    if input.name.trim().is_empty() {
+        return Err(AppError::invalid_field("name", "site name is required"));
+    }
+    parse_timezone(&input.timezone)?;
+
+    conn.execute(
+        "INSERT INTO sites (name, timezone) VALUES (?1, ?2)",
+        params![input.name.trim(), input.timezone.trim()],
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint failed") {
+            "Site name already exists".to_string()
+        } else {
+            format!("Failed to create site: {}", e)
+        }
+    })?;
+
+    Ok(fetch_site(&conn, conn.last_insert_rowid())?)
+}
+
+/// Rename a site or change its timezone (Admin only)
+#[tauri::command]
+pub fn update_site(
+    token: String,
+    id: i64,
+    input: UpdateSiteInput,
+    db: State<'_, Database>,
+) -> Result<Site, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "sites.manage")?;
+
+    let mut updates = Vec::new();
+    let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(name) = &input.name {
+        if name.trim().is_empty() {
+            return Err(AppError::invalid_field("name", "site name is required"));
+        }
+        updates.push("name = ?");
+        values.push(Box::new(name.trim().to_string()));
+    }
+    if let Some(timezone) = &input.timezone {
+        parse_timezone(timezone)?;
+        updates.push("timezone = ?");
+        values.push(Box::new(timezone.trim().to_string()));
+    }
+
+    if updates.is_empty() {
+        return Ok(fetch_site(&conn, id)?);
+    }
+
+    let query = format!("UPDATE sites SET {} WHERE id = ?", updates.join(", "));
+    values.push(Box::new(id));
+
+    let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+    conn.execute(&query, params.as_slice())
+        .map_err(|e| format!("Failed to update site: {}", e))?;
+
+    Ok(fetch_site(&conn, id)?)
+}
+
+/// Delete a site (Admin only). Its machines move back to the implicit local site.
+#[tauri::command]
+pub fn delete_site(token: String, id: i64, db: State<'_, Database>) -> Result<(), AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "sites.manage")?;
+
+    let exists: Option<i64> = conn
+        .query_row("SELECT id FROM sites WHERE id = ?1", params![id], |row| row.get(0))
+        .optional()
+        .map_err(|e| e.to_string())?;
+    if exists.is_none() {
+        return Err(AppError::NotFound("Site not found".to_string()));
+    }
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute("UPDATE machines SET site_id = NULL WHERE site_id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM sites WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,202 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::commands::schedules::{entry_hours, entry_task_hours, rollup_project_hours, rollup_task_hours};
+use crate::commands::week_locks::ensure_week_unlocked;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::utils::{record_audit, require_permission, require_view_permission, validate_session};
+
+const STALE_ALERT_TITLE: &str = "Schedules reference inactive records";
+
+/// Days between stale-reference summary alerts
+const STALE_ALERT_INTERVAL_DAYS: i64 = 7;
+
+pub const STALE_FIELD_OPERATOR: &str = "operator";
+pub const STALE_FIELD_PROJECT: &str = "project";
+
+/// An upcoming schedule entry pointing at a deactivated operator or completed project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaleReference {
+    pub schedule_id: i64,
+    pub date: String,
+    pub machine_id: i64,
+    pub machine_name: String,
+    pub load_name: Option<String>,
+    /// "operator" or "project"
+    pub field: String,
+    pub reference_id: i64,
+    pub reference_name: Option<String>,
+    pub reason: String,
+}
+
+/// Active entries dated `from_date` or later that reference stale records
+pub fn load_stale_references(conn: &Connection, from_date: &str) -> Result<Vec<StaleReference>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.id, s.date, s.machine_id, m.name, s.load_name, 'operator', u.id,
+                    COALESCE(u.full_name, u.username), 'Operator account is inactive'
+             FROM schedules s
+             JOIN machines m ON s.machine_id = m.id
+             JOIN users u ON s.operator_id = u.id
+             WHERE s.date >= ?1 AND s.status IN ('scheduled', 'in-progress') AND u.is_active = 0
+             UNION ALL
+             SELECT s.id, s.date, s.machine_id, m.name, s.load_name, 'project', p.id,
+                    p.name, 'Project is completed'
+             FROM schedules s
+             JOIN machines m ON s.machine_id = m.id
+             JOIN projects p ON s.project_id = p.id
+             WHERE s.date >= ?1 AND s.status IN ('scheduled', 'in-progress') AND p.status = 'completed'
+             ORDER BY 2, 4",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let references = stmt
+        .query_map([from_date], |row| {
+            Ok(StaleReference {
+                schedule_id: row.get(0)?,
+                date: row.get(1)?,
+                machine_id: row.get(2)?,
+                machine_name: row.get(3)?,
+                load_name: row.get(4)?,
+                field: row.get(5)?,
+                reference_id: row.get(6)?,
+                reference_name: row.get(7)?,
+                reason: row.get(8)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(references)
+}
+
+/// Raise a medium alert summarizing stale references, at most once a week.
+/// Run at startup.
+pub fn check_stale_references_weekly(conn: &Connection) -> Result<(), String> {
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let references = load_stale_references(conn, &today)?;
+    if references.is_empty() {
+        return Ok(());
+    }
+
+    let recent: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM alerts WHERE title = ?1 AND created_at >= datetime('now', ?2)",
+            params![STALE_ALERT_TITLE, format!("-{} days", STALE_ALERT_INTERVAL_DAYS)],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if recent > 0 {
+        return Ok(());
+    }
+
+    let operators = references.iter().filter(|r| r.field == STALE_FIELD_OPERATOR).count();
+    let projects = references.len() - operators;
+    conn.execute(
+        "INSERT INTO alerts (alert_type, priority, title, message) VALUES ('schedule', 'medium', ?1, ?2)",
+        params![
+            STALE_ALERT_TITLE,
+            format!(
+                "{} upcoming schedule entries need attention: {} with an inactive operator, {} on a completed project",
+                references.len(),
+                operators,
+                projects
+            )
+        ],
+    )
+    .map_err(|e| format!("Failed to create alert: {}", e))?;
+    Ok(())
+}
+
+/// List upcoming schedule entries that point at inactive operators or completed projects
+#[tauri::command]
+pub fn find_stale_references(token: String, db: State<'_, Database>) -> Result<Vec<StaleReference>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    Ok(load_stale_references(&conn, &today)?)
+}
+
+/// Clear a stale operator or project from a schedule entry.
+///
+/// With `notify` set a schedule alert asks the planner to reassign the entry.
+#[tauri::command]
+pub fn clear_stale_reference(
+    token: String,
+    schedule_id: i64,
+    field: String,
+    notify: Option<bool>,
+    db: State<'_, Database>,
+) -> Result<(), AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "schedules.edit")?;
+
+    if field != STALE_FIELD_OPERATOR && field != STALE_FIELD_PROJECT {
+        return Err(AppError::invalid_field("field", format!("invalid field '{}'. Must be operator or project", field)));
+    }
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let stale = load_stale_references(&conn, &today)?
+        .into_iter()
+        .find(|r| r.schedule_id == schedule_id && r.field == field)
+        .ok_or_else(|| format!("Schedule {} has no stale {} to clear", schedule_id, field))?;
+    ensure_week_unlocked(&conn, &stale.date)?;
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    if field == STALE_FIELD_PROJECT {
+        let (project_id, hours) = entry_hours(&tx, schedule_id)?;
+        let (task_id, _) = entry_task_hours(&tx, schedule_id)?;
+        tx.execute(
+            "UPDATE schedules SET project_id = NULL, task_id = NULL, updated_by = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
+            params![user.id, schedule_id],
+        )
+        .map_err(|e| format!("Failed to update schedule: {}", e))?;
+        rollup_project_hours(&tx, (project_id, hours), (None, hours))?;
+        rollup_task_hours(&tx, (task_id, hours), (None, hours))?;
+    } else {
+        tx.execute(
+            "UPDATE schedules SET operator_id = NULL, updated_by = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
+            params![user.id, schedule_id],
+        )
+        .map_err(|e| format!("Failed to update schedule: {}", e))?;
+    }
+
+    record_audit(
+        &tx,
+        &user,
+        "clear_stale_reference",
+        "schedules",
+        Some(schedule_id),
+        Some(&serde_json::json!({ field.as_str(): stale.reference_id }).to_string()),
+        Some(&serde_json::json!({ field.as_str(): null, "reason": stale.reason }).to_string()),
+    );
+
+    if notify.unwrap_or(false) {
+        tx.execute(
+            "INSERT INTO alerts (alert_type, priority, title, message, machine_id)
+             VALUES ('schedule', 'medium', ?1, ?2, ?3)",
+            params![
+                "Schedule entry needs reassignment",
+                format!(
+                    "Entry #{} on {} ({}) lost its {}: {}. Please reassign it.",
+                    schedule_id,
+                    stale.machine_name,
+                    stale.date,
+                    field,
+                    stale.reason
+                ),
+                stale.machine_id
+            ],
+        )
+        .map_err(|e| format!("Failed to create alert: {}", e))?;
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,240 @@
+use rusqlite::Connection;
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::User;
+use crate::utils::{record_audit, require_view_permission, validate_session};
+
+pub const ENTITY_SCHEDULE: &str = "schedule";
+pub const ENTITY_MAINTENANCE: &str = "maintenance";
+pub const ENTITY_PROJECT: &str = "project";
+
+/// Status -> statuses it may move to
+type TransitionMap = &'static [(&'static str, &'static [&'static str])];
+
+const SCHEDULE_TRANSITIONS: TransitionMap = &[
+    ("scheduled", &["in-progress", "completed", "cancelled"]),
+    ("in-progress", &["scheduled", "completed", "cancelled"]),
+    ("completed", &[]),
+    ("cancelled", &["scheduled"]),
+];
+
+const MAINTENANCE_TRANSITIONS: TransitionMap = &[
+    ("scheduled", &["in-progress", "completed", "cancelled"]),
+    ("in-progress", &["scheduled", "completed", "cancelled"]),
+    ("completed", &[]),
+    ("cancelled", &["scheduled"]),
+];
+
+const PROJECT_TRANSITIONS: TransitionMap = &[
+    ("planning", &["active", "on-hold"]),
+    ("active", &["on-hold", "completed"]),
+    ("on-hold", &["active"]),
+    ("completed", &[]),
+];
+
+fn transition_map(entity: &str) -> Result<TransitionMap, String> {
+    match entity {
+        ENTITY_SCHEDULE => Ok(SCHEDULE_TRANSITIONS),
+        ENTITY_MAINTENANCE => Ok(MAINTENANCE_TRANSITIONS),
+        ENTITY_PROJECT => Ok(PROJECT_TRANSITIONS),
+        _ => Err(format!(
+            "Validation error: unknown entity '{}'. Must be schedule, maintenance or project",
+            entity
+        )),
+    }
+}
+
+/// Statuses an entity may move to from `current`, not counting `current` itself
+pub fn allowed_transitions(entity: &str, current: &str) -> Result<&'static [&'static str], String> {
+    transition_map(entity)?
+        .iter()
+        .find(|(from, _)| *from == current)
+        .map(|(_, to)| *to)
+        .ok_or_else(|| format!("Validation error: '{}' is not a {} status", current, entity))
+}
+
+/// Reject a status change the transition map does not allow.
+///
+/// With `force` an Admin may make the change anyway; the forced transition is
+/// written to the audit log. Leaving the status unchanged is always allowed.
+pub fn check_transition(
+    conn: &Connection,
+    user: &User,
+    entity: &str,
+    id: i64,
+    current: &str,
+    next: &str,
+    force: bool,
+) -> Result<(), String> {
+    if current == next {
+        return Ok(());
+    }
+    if !transition_map(entity)?.iter().any(|(status, _)| *status == next) {
+        return Err(format!("Validation error: '{}' is not a {} status", next, entity));
+    }
+    if allowed_transitions(entity, current)?.contains(&next) {
+        return Ok(());
+    }
+
+    if !force {
+        return Err(format!(
+            "Validation error: {} #{} cannot move from '{}' to '{}'",
+            entity, id, current, next
+        ));
+    }
+    if !user.is_admin() {
+        return Err(format!(
+            "Permission denied. Only an Admin can force {} #{} from '{}' to '{}'",
+            entity, id, current, next
+        ));
+    }
+
+    let table = match entity {
+        ENTITY_SCHEDULE => "schedules",
+        ENTITY_MAINTENANCE => "maintenance",
+        _ => "projects",
+    };
+    record_audit(
+        conn,
+        user,
+        "force_status_transition",
+        table,
+        Some(id),
+        Some(&serde_json::json!({ "status": current }).to_string()),
+        Some(&serde_json::json!({ "status": next }).to_string()),
+    );
+    Ok(())
+}
+
+/// Statuses the given entity may move to from `current_status`, so the UI can
+/// disable the rest
+#[tauri::command]
+pub fn get_allowed_transitions(
+    token: String,
+    entity: String,
+    current_status: String,
+    db: State<'_, Database>,
+) -> Result<Vec<String>, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_view_permission(&user)?;
+
+    Ok(allowed_transitions(&entity, &current_status)?
+        .iter()
+        .map(|s| s.to_string())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_pending_migrations;
+
+    /// Entity, its statuses and every move allowed between them
+    type Expected = (&'static str, &'static [&'static str], &'static [(&'static str, &'static str)]);
+
+    /// Written out independently of the maps
+    const EXPECTED: &[Expected] = &[
+        (
+            ENTITY_SCHEDULE,
+            &["scheduled", "in-progress", "completed", "cancelled"],
+            &[
+                ("scheduled", "in-progress"),
+                ("scheduled", "completed"),
+                ("scheduled", "cancelled"),
+                ("in-progress", "scheduled"),
+                ("in-progress", "completed"),
+                ("in-progress", "cancelled"),
+                ("cancelled", "scheduled"),
+            ],
+        ),
+        (
+            ENTITY_MAINTENANCE,
+            &["scheduled", "in-progress", "completed", "cancelled"],
+            &[
+                ("scheduled", "in-progress"),
+                ("scheduled", "completed"),
+                ("scheduled", "cancelled"),
+                ("in-progress", "scheduled"),
+                ("in-progress", "completed"),
+                ("in-progress", "cancelled"),
+                ("cancelled", "scheduled"),
+            ],
+        ),
+        (
+            ENTITY_PROJECT,
+            &["planning", "active", "on-hold", "completed"],
+            &[
+                ("planning", "active"),
+                ("planning", "on-hold"),
+                ("active", "on-hold"),
+                ("active", "completed"),
+                ("on-hold", "active"),
+            ],
+        ),
+    ];
+
+    fn database_with_user(role: &str) -> (Connection, User) {
+        let conn = Connection::open_in_memory().unwrap();
+        run_pending_migrations(&conn).unwrap();
+        conn.execute("INSERT INTO users (id, username, password_hash, role) VALUES (1, 'u', 'x', ?1)", [role])
+            .unwrap();
+        let user = conn.query_row("SELECT * FROM users WHERE id = 1", [], User::from_row).unwrap();
+        (conn, user)
+    }
+
+    fn forced_transitions(conn: &Connection) -> i64 {
+        conn.query_row(
+            "SELECT COUNT(*) FROM audit_log WHERE action = 'force_status_transition'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn every_pair_matches_the_map() {
+        let (conn, user) = database_with_user("Operator");
+        for (entity, statuses, allowed) in EXPECTED {
+            for from in *statuses {
+                for to in *statuses {
+                    let expected = from == to || allowed.contains(&(*from, *to));
+                    let result = check_transition(&conn, &user, entity, 1, from, to, false);
+                    assert_eq!(result.is_ok(), expected, "{} {} -> {}: {:?}", entity, from, to, result);
+                    if let Err(e) = result {
+                        assert!(e.contains(&format!("cannot move from '{}' to '{}'", from, to)), "{}", e);
+                    }
+                }
+                let listed: Vec<&str> = statuses
+                    .iter()
+                    .copied()
+                    .filter(|to| allowed.contains(&(*from, *to)))
+                    .collect();
+                assert_eq!(allowed_transitions(entity, from).unwrap(), listed.as_slice(), "{} {}", entity, from);
+            }
+        }
+        assert_eq!(forced_transitions(&conn), 0);
+    }
+
+    #[test]
+    fn unknown_statuses_and_entities_are_rejected() {
+        let (conn, user) = database_with_user("Admin");
+        assert!(check_transition(&conn, &user, ENTITY_SCHEDULE, 1, "scheduled", "done", true).is_err());
+        assert!(check_transition(&conn, &user, "invoice", 1, "draft", "sent", true).is_err());
+        assert!(allowed_transitions(ENTITY_PROJECT, "scheduled").is_err());
+    }
+
+    #[test]
+    fn only_an_admin_can_force_and_it_is_audited() {
+        let (conn, operator) = database_with_user("Operator");
+        let err = check_transition(&conn, &operator, ENTITY_SCHEDULE, 7, "completed", "scheduled", true).unwrap_err();
+        assert!(err.starts_with("Permission denied"), "{}", err);
+        assert_eq!(forced_transitions(&conn), 0);
+
+        let (conn, admin) = database_with_user("Admin");
+        check_transition(&conn, &admin, ENTITY_SCHEDULE, 7, "completed", "scheduled", true).unwrap();
+        assert_eq!(forced_transitions(&conn), 1);
+    }
+}
@@ -0,0 +1,41 @@
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::error::AppError;
+use crate::db::StartupStatus;
+use crate::startup::StartupGate;
+
+/// A window that currently exists, for support diagnostics
+#[derive(Debug, Clone, Serialize)]
+pub struct AppWindowInfo {
+    pub label: String,
+    pub title: Option<String>,
+    pub visible: Option<bool>,
+}
+
+/// Get the result of opening the database at startup (available before login)
+///
+/// The database opens in the background, so this waits until it has been
+/// opened or has failed to; other commands can be called once it returns.
+#[tauri::command]
+pub async fn get_startup_status(gate: State<'_, StartupGate>) -> Result<StartupStatus, AppError> {
+    gate.status()
+        .await
+        .ok_or_else(|| AppError::Database("Startup ended before the database was opened".to_string()))
+}
+
+/// List the app's open windows and whether they are visible (available before login)
+#[tauri::command]
+pub fn get_app_windows_info(app: AppHandle) -> Result<Vec<AppWindowInfo>, AppError> {
+    let mut windows: Vec<AppWindowInfo> = app
+        .webview_windows()
+        .into_iter()
+        .map(|(label, window)| AppWindowInfo {
+            label,
+            title: window.title().ok(),
+            visible: window.is_visible().ok(),
+        })
+        .collect();
+    windows.sort_by(|a, b| a.label.cmp(&b.label));
+    Ok(windows)
+}
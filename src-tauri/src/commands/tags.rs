@@ -0,0 +1,159 @@
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::utils::{require_permission, require_view_permission, validate_session};
+
+/// Label planners put on schedule entries ("rush", "rework", ...) to color the grid
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleTag {
+    pub id: i64,
+    pub name: String,
+    pub color: String,
+    pub created_at: String,
+}
+
+impl ScheduleTag {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            name: row.get("name")?,
+            color: row.get("color")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateScheduleTagInput {
+    pub name: String,
+    pub color: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateScheduleTagInput {
+    pub name: Option<String>,
+    pub color: Option<String>,
+}
+
+/// Look up a tag by id
+pub fn fetch_tag(conn: &Connection, id: i64) -> Result<ScheduleTag, String> {
+    conn.query_row("SELECT * FROM schedule_tags WHERE id = ?1", params![id], ScheduleTag::from_row)
+        .map_err(|_| "Tag not found".to_string())
+}
+
+/// Accept "#RGB" or "#RRGGBB" and store it lowercase
+fn normalize_color(color: &str) -> Result<String, String> {
+    let color = color.trim();
+    let hex = color.strip_prefix('#').unwrap_or("");
+    if ![3, 6].contains(&hex.len()) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid color '{}'. Use a hex value like #ff8800", color));
+    }
+    Ok(format!("#{}", hex.to_ascii_lowercase()))
+}
+
+/// Get all schedule tags
+#[tauri::command]
+pub fn get_schedule_tags(token: String, db: State<'_, Database>) -> Result<Vec<ScheduleTag>, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let mut stmt = conn
+        .prepare("SELECT * FROM schedule_tags ORDER BY name")
+        .map_err(|e| e.to_string())?;
+
+    let tags = stmt
+        .query_map([], ScheduleTag::from_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(tags)
+}
+
+/// Create a schedule tag
+#[tauri::command]
+pub fn create_schedule_tag(
+    token: String,
+    input: CreateScheduleTagInput,
+    db: State<'_, Database>,
+) -> Result<ScheduleTag, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "schedules.edit")?;
+
+    if input.name.trim().is_empty() {
+        return Err(AppError::invalid_field("name", "tag name is required"));
+    }
+    let color = normalize_color(&input.color)?;
+
+    conn.execute(
+        "INSERT INTO schedule_tags (name, color) VALUES (?1, ?2)",
+        params![input.name.trim(), color],
+    )
+    .map_err(|e| format!("Failed to create tag: {}", e))?;
+
+    Ok(fetch_tag(&conn, conn.last_insert_rowid())?)
+}
+
+/// Rename or recolor a schedule tag
+#[tauri::command]
+pub fn update_schedule_tag(
+    token: String,
+    id: i64,
+    input: UpdateScheduleTagInput,
+    db: State<'_, Database>,
+) -> Result<ScheduleTag, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "schedules.edit")?;
+
+    let mut updates = Vec::new();
+    let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(name) = &input.name {
+        if name.trim().is_empty() {
+            return Err(AppError::invalid_field("name", "tag name is required"));
+        }
+        updates.push("name = ?");
+        values.push(Box::new(name.trim().to_string()));
+    }
+    if let Some(color) = &input.color {
+        updates.push("color = ?");
+        values.push(Box::new(normalize_color(color)?));
+    }
+
+    if updates.is_empty() {
+        return Ok(fetch_tag(&conn, id)?);
+    }
+
+    let query = format!("UPDATE schedule_tags SET {} WHERE id = ?", updates.join(", "));
+    values.push(Box::new(id));
+
+    let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+    conn.execute(&query, params.as_slice())
+        .map_err(|e| format!("Failed to update tag: {}", e))?;
+
+    Ok(fetch_tag(&conn, id)?)
+}
+
+/// Delete a schedule tag. Tagged entries are kept and simply lose the tag.
+#[tauri::command]
+pub fn delete_schedule_tag(token: String, id: i64, db: State<'_, Database>) -> Result<(), AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "schedules.edit")?;
+
+    conn.execute("UPDATE schedules SET tag_id = NULL WHERE tag_id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    let affected = conn
+        .execute("DELETE FROM schedule_tags WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    if affected == 0 {
+        return Err(AppError::NotFound("Tag not found".to_string()));
+    }
+    Ok(())
+}
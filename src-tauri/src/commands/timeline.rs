@@ -0,0 +1,305 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::NaiveDate;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::utils::{parse_date, require_view_permission, validate_session};
+
+pub const BLOCK_KIND_WORK: &str = "work";
+pub const BLOCK_KIND_GAP: &str = "gap";
+
+/// Longest range a single timeline request may cover
+const MAX_TIMELINE_DAYS: i64 = 366;
+
+/// A contiguous stretch of days on the timeline, either scheduled work or idle time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineBlock {
+    pub kind: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub days: i64,
+    /// Project (when grouped by machine) or machine (when grouped by project)
+    pub item_id: Option<i64>,
+    pub item_name: Option<String>,
+    pub total_planned_hours: f64,
+    pub total_actual_hours: f64,
+    pub entry_count: i64,
+    pub status_counts: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineGroup {
+    pub group_id: i64,
+    pub group_name: String,
+    pub blocks: Vec<TimelineBlock>,
+    pub total_planned_hours: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineResponse {
+    pub start_date: String,
+    pub end_date: String,
+    pub group_by: String,
+    pub groups: Vec<TimelineGroup>,
+}
+
+struct TimelineRow {
+    date: NaiveDate,
+    machine_id: i64,
+    machine_name: String,
+    project_id: Option<i64>,
+    project_name: Option<String>,
+    planned_hours: f64,
+    actual_hours: Option<f64>,
+    status: String,
+}
+
+type DayRows<'a> = BTreeMap<NaiveDate, Vec<&'a TimelineRow>>;
+
+#[derive(Default)]
+struct GroupRows {
+    name: String,
+    rows: Vec<TimelineRow>,
+}
+
+/// Get Gantt-style timeline blocks grouped by machine or by project
+#[tauri::command]
+pub fn get_timeline(
+    token: String,
+    start_date: String,
+    end_date: String,
+    group_by: String,
+    db: State<'_, Database>,
+) -> Result<TimelineResponse, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    parse_date("start_date", &start_date)?;
+    parse_date("end_date", &end_date)?;
+    Ok(build_timeline(&conn, &start_date, &end_date, &group_by)?)
+}
+
+/// Build the timeline for a date range.
+///
+/// Consecutive days with the same project on the same machine are merged into one
+/// block. Machines show gaps over the whole range; projects only between their first
+/// and last scheduled day, since a project is not expected to run before it starts.
+pub fn build_timeline(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    group_by: &str,
+) -> Result<TimelineResponse, String> {
+    let start = NaiveDate::parse_from_str(start_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    let end = NaiveDate::parse_from_str(end_date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    if end < start {
+        return Err("End date must not be before start date".to_string());
+    }
+    if (end - start).num_days() >= MAX_TIMELINE_DAYS {
+        return Err(format!("Timeline range cannot exceed {} days", MAX_TIMELINE_DAYS));
+    }
+    let by_machine = match group_by {
+        "machine" => true,
+        "project" => false,
+        _ => return Err("group_by must be 'machine' or 'project'".to_string()),
+    };
+
+    let rows = load_timeline_rows(conn, start_date, end_date)?;
+
+    let mut groups: BTreeMap<i64, GroupRows> = BTreeMap::new();
+    if by_machine {
+        // Every machine gets a row so completely idle machines are visible too
+        let mut stmt = conn
+            .prepare("SELECT id, name FROM machines ORDER BY name")
+            .map_err(|e| e.to_string())?;
+        let machines = stmt
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok());
+        for (id, name) in machines {
+            groups.insert(id, GroupRows { name, rows: Vec::new() });
+        }
+    }
+
+    for row in rows {
+        let (group_id, group_name) = if by_machine {
+            (row.machine_id, row.machine_name.clone())
+        } else {
+            match row.project_id {
+                Some(id) => (id, row.project_name.clone().unwrap_or_default()),
+                None => continue,
+            }
+        };
+        let group = groups.entry(group_id).or_default();
+        group.name = group_name;
+        group.rows.push(row);
+    }
+
+    let mut result: Vec<TimelineGroup> = groups
+        .into_iter()
+        .map(|(group_id, group)| {
+            let (gap_start, gap_end) = if by_machine {
+                (start, end)
+            } else {
+                let first = group.rows.iter().map(|r| r.date).min().unwrap_or(start);
+                let last = group.rows.iter().map(|r| r.date).max().unwrap_or(end);
+                (first, last)
+            };
+            let total_planned_hours = group.rows.iter().map(|r| r.planned_hours).sum();
+            TimelineGroup {
+                group_id,
+                group_name: group.name,
+                blocks: build_blocks(&group.rows, by_machine, gap_start, gap_end),
+                total_planned_hours,
+            }
+        })
+        .collect();
+    result.sort_by(|a, b| a.group_name.cmp(&b.group_name));
+
+    Ok(TimelineResponse {
+        start_date: start_date.to_string(),
+        end_date: end_date.to_string(),
+        group_by: group_by.to_string(),
+        groups: result,
+    })
+}
+
+fn load_timeline_rows(conn: &Connection, start_date: &str, end_date: &str) -> Result<Vec<TimelineRow>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.date, s.machine_id, m.name as machine_name, s.project_id, p.name as project_name,
+                    s.planned_hours, s.actual_hours, s.status
+             FROM schedules s
+             JOIN machines m ON s.machine_id = m.id
+             LEFT JOIN projects p ON s.project_id = p.id
+             WHERE s.date >= ?1 AND s.date <= ?2 AND s.status != 'cancelled'
+             ORDER BY s.date",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(params![start_date, end_date], |row| {
+            Ok((
+                row.get::<_, String>("date")?,
+                row.get::<_, i64>("machine_id")?,
+                row.get::<_, String>("machine_name")?,
+                row.get::<_, Option<i64>>("project_id")?,
+                row.get::<_, Option<String>>("project_name")?,
+                row.get::<_, Option<f64>>("planned_hours")?,
+                row.get::<_, Option<f64>>("actual_hours")?,
+                row.get::<_, String>("status")?,
+            ))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .filter_map(|(date, machine_id, machine_name, project_id, project_name, planned, actual_hours, status)| {
+            Some(TimelineRow {
+                date: NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok()?,
+                machine_id,
+                machine_name,
+                project_id,
+                project_name,
+                planned_hours: planned.unwrap_or(0.0),
+                actual_hours,
+                status,
+            })
+        })
+        .collect();
+
+    Ok(rows)
+}
+
+/// Merge one group's rows into work blocks and fill idle days between `from` and `to` with gaps
+fn build_blocks(rows: &[TimelineRow], by_machine: bool, from: NaiveDate, to: NaiveDate) -> Vec<TimelineBlock> {
+    // Rows per counterpart (project for machines, machine for projects), keyed by day
+    let mut items: BTreeMap<Option<i64>, (Option<String>, DayRows)> = BTreeMap::new();
+    let mut busy_days: BTreeSet<NaiveDate> = BTreeSet::new();
+    for row in rows {
+        let (item_id, item_name) = if by_machine {
+            (row.project_id, row.project_name.clone())
+        } else {
+            (Some(row.machine_id), Some(row.machine_name.clone()))
+        };
+        let item = items.entry(item_id).or_insert_with(|| (item_name, BTreeMap::new()));
+        item.1.entry(row.date).or_default().push(row);
+        busy_days.insert(row.date);
+    }
+
+    let mut blocks = Vec::new();
+    for (item_id, (item_name, days)) in items {
+        let mut current: Option<TimelineBlock> = None;
+        let mut last_day: Option<NaiveDate> = None;
+        for (day, day_rows) in days {
+            let contiguous = last_day.is_some_and(|d| d.succ_opt() == Some(day));
+            if !contiguous {
+                blocks.extend(current.take());
+            }
+            let block = current.get_or_insert_with(|| TimelineBlock {
+                kind: BLOCK_KIND_WORK.to_string(),
+                start_date: day.format("%Y-%m-%d").to_string(),
+                end_date: String::new(),
+                days: 0,
+                item_id,
+                item_name: item_name.clone(),
+                total_planned_hours: 0.0,
+                total_actual_hours: 0.0,
+                entry_count: 0,
+                status_counts: BTreeMap::new(),
+            });
+            block.end_date = day.format("%Y-%m-%d").to_string();
+            block.days += 1;
+            for row in day_rows {
+                block.total_planned_hours += row.planned_hours;
+                block.total_actual_hours += row.actual_hours.unwrap_or(0.0);
+                block.entry_count += 1;
+                *block.status_counts.entry(row.status.clone()).or_insert(0) += 1;
+            }
+            last_day = Some(day);
+        }
+        blocks.extend(current);
+    }
+
+    let mut day = from;
+    while day <= to {
+        if busy_days.contains(&day) {
+            day = day.succ_opt().unwrap_or(NaiveDate::MAX);
+            continue;
+        }
+        let gap_start = day;
+        let mut gap_end = day;
+        while let Some(next) = gap_end.succ_opt() {
+            if next > to || busy_days.contains(&next) {
+                break;
+            }
+            gap_end = next;
+        }
+        blocks.push(TimelineBlock {
+            kind: BLOCK_KIND_GAP.to_string(),
+            start_date: gap_start.format("%Y-%m-%d").to_string(),
+            end_date: gap_end.format("%Y-%m-%d").to_string(),
+            days: (gap_end - gap_start).num_days() + 1,
+            item_id: None,
+            item_name: None,
+            total_planned_hours: 0.0,
+            total_actual_hours: 0.0,
+            entry_count: 0,
+            status_counts: BTreeMap::new(),
+        });
+        match gap_end.succ_opt() {
+            Some(next) => day = next,
+            None => break,
+        }
+    }
+
+    blocks.sort_by(|a, b| {
+        a.start_date
+            .cmp(&b.start_date)
+            .then_with(|| a.item_name.cmp(&b.item_name))
+    });
+    blocks
+}
@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, NaiveDate};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::ScheduleEntry;
+use crate::utils::{parse_date, require_view_permission, start_of_week, validate_session};
+
+/// An entry present in only one of the compared weeks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeekDiffEntry {
+    pub machine_id: i64,
+    pub machine_name: String,
+    /// 0 = Monday
+    pub weekday: u32,
+    pub day_name: String,
+    pub entry: ScheduleEntry,
+}
+
+/// A pair of entries on the same machine and weekday that differ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModifiedWeekEntry {
+    pub machine_id: i64,
+    pub machine_name: String,
+    pub weekday: u32,
+    pub day_name: String,
+    pub before: ScheduleEntry,
+    pub after: ScheduleEntry,
+    /// "load_name", "operator" and/or "planned_hours"
+    pub changed_fields: Vec<String>,
+}
+
+/// Week B compared to week A
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeekDiff {
+    pub week_a_start: String,
+    pub week_b_start: String,
+    pub added: Vec<WeekDiffEntry>,
+    pub removed: Vec<WeekDiffEntry>,
+    pub modified: Vec<ModifiedWeekEntry>,
+    pub unchanged: i64,
+}
+
+/// Entries of one machine on one weekday, in planner order
+type WeekGroups = BTreeMap<(String, i64, u32), Vec<(String, ScheduleEntry)>>;
+
+fn load_week(conn: &Connection, start: NaiveDate) -> Result<WeekGroups, String> {
+    let end = start + chrono::Duration::days(6);
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.*, m.name as machine_name, p.name as project_name, u.full_name as operator_name,
+                    sh.name as shift_name, cb.full_name as created_by_name,
+                    tg.name as tag_name, tg.color as tag_color
+             FROM schedules s
+             JOIN machines m ON s.machine_id = m.id
+             LEFT JOIN projects p ON s.project_id = p.id
+             LEFT JOIN users u ON s.operator_id = u.id
+             LEFT JOIN shifts sh ON s.shift_id = sh.id
+             LEFT JOIN users cb ON s.created_by = cb.id
+             LEFT JOIN schedule_tags tg ON s.tag_id = tg.id
+             WHERE s.date >= ?1 AND s.date <= ?2
+             ORDER BY m.name, s.date, s.start_time, s.sequence_order",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows: Vec<(String, ScheduleEntry)> = stmt
+        .query_map(
+            params![start.format("%Y-%m-%d").to_string(), end.format("%Y-%m-%d").to_string()],
+            |row| Ok((row.get("date")?, ScheduleEntry::from_row(row)?)),
+        )
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut groups = WeekGroups::new();
+    for (date, entry) in rows {
+        let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d").map_err(|e| e.to_string())?;
+        let weekday = date.weekday().num_days_from_monday();
+        let day_name = date.format("%A").to_string();
+        let machine_name = entry.machine_name.clone().unwrap_or_default();
+        groups
+            .entry((machine_name, entry.machine_id, weekday))
+            .or_default()
+            .push((day_name, entry));
+    }
+    Ok(groups)
+}
+
+fn changed_fields(before: &ScheduleEntry, after: &ScheduleEntry) -> Vec<String> {
+    let mut fields = Vec::new();
+    if before.load_name != after.load_name {
+        fields.push("load_name".to_string());
+    }
+    if before.operator_id != after.operator_id {
+        fields.push("operator".to_string());
+    }
+    if (before.planned_hours - after.planned_hours).abs() > f64::EPSILON {
+        fields.push("planned_hours".to_string());
+    }
+    fields
+}
+
+/// Compare two weeks, aligning entries by machine and weekday rather than date.
+///
+/// Within a machine and weekday, entries with the same load name are paired
+/// first and the rest in planner order; anything left over is added or removed.
+#[tauri::command]
+pub fn diff_weeks(
+    token: String,
+    week_a_start: String,
+    week_b_start: String,
+    db: State<'_, Database>,
+) -> Result<WeekDiff, AppError> {
+    let user = validate_session(&db.conn.lock(), &token)?;
+    require_view_permission(&user)?;
+    let conn = db.read();
+
+    let start_a = start_of_week(&conn, parse_date("week_a_start", &week_a_start)?);
+    let start_b = start_of_week(&conn, parse_date("week_b_start", &week_b_start)?);
+
+    let mut week_a = load_week(&conn, start_a)?;
+    let mut week_b = load_week(&conn, start_b)?;
+
+    let mut keys: Vec<(String, i64, u32)> = week_a.keys().chain(week_b.keys()).cloned().collect();
+    keys.sort();
+    keys.dedup();
+
+    let mut diff = WeekDiff {
+        week_a_start: start_a.format("%Y-%m-%d").to_string(),
+        week_b_start: start_b.format("%Y-%m-%d").to_string(),
+        added: Vec::new(),
+        removed: Vec::new(),
+        modified: Vec::new(),
+        unchanged: 0,
+    };
+
+    for key in keys {
+        let (machine_name, machine_id, weekday) = key.clone();
+        let mut before = week_a.remove(&key).unwrap_or_default();
+        let mut after = week_b.remove(&key).unwrap_or_default();
+
+        // Pair entries carrying the same load name, then whatever remains by position
+        let mut pairs = Vec::new();
+        let mut i = 0;
+        while i < before.len() {
+            let load_name = &before[i].1.load_name;
+            match after.iter().position(|(_, e)| load_name.is_some() && e.load_name == *load_name) {
+                Some(j) => pairs.push((before.remove(i), after.remove(j))),
+                None => i += 1,
+            }
+        }
+        let leftover = before.len().min(after.len());
+        pairs.extend(before.drain(..leftover).zip(after.drain(..leftover)));
+
+        for ((day_name, old), (_, new)) in pairs {
+            let changed = changed_fields(&old, &new);
+            if changed.is_empty() {
+                diff.unchanged += 1;
+            } else {
+                diff.modified.push(ModifiedWeekEntry {
+                    machine_id,
+                    machine_name: machine_name.clone(),
+                    weekday,
+                    day_name,
+                    before: old,
+                    after: new,
+                    changed_fields: changed,
+                });
+            }
+        }
+
+        let as_diff_entry = |(day_name, entry): (String, ScheduleEntry)| WeekDiffEntry {
+            machine_id,
+            machine_name: machine_name.clone(),
+            weekday,
+            day_name,
+            entry,
+        };
+        diff.removed.extend(before.into_iter().map(as_diff_entry));
+        diff.added.extend(after.into_iter().map(as_diff_entry));
+    }
+
+    Ok(diff)
+}
@@ -0,0 +1,103 @@
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::State;
+
+use crate::commands::conflicts::sweep_week_conflicts;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::WeekLock;
+use crate::utils::{parse_date, require_permission, start_of_week, validate_session};
+
+/// First day of the week containing `date`, as YYYY-MM-DD, by the
+/// week_start_day setting
+fn week_start_of(conn: &Connection, date: NaiveDate) -> String {
+    start_of_week(conn, date).format("%Y-%m-%d").to_string()
+}
+
+/// Lock on the week containing `date`, if any.
+///
+/// Matches any lock whose seven days cover `date` rather than the current
+/// week start, so weeks locked before week_start_day was changed stay locked.
+pub fn find_week_lock(conn: &Connection, date: &str) -> Result<Option<WeekLock>, String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| format!("Validation error: invalid date '{}'. Use YYYY-MM-DD", date))?;
+    conn.query_row(
+        "SELECT l.week_start, l.locked_by, COALESCE(u.full_name, u.username) as locked_by_name, l.locked_at
+         FROM schedule_locks l
+         LEFT JOIN users u ON l.locked_by = u.id
+         WHERE l.week_start <= ?1 AND l.week_start > date(?1, '-7 days')
+         ORDER BY l.week_start DESC
+         LIMIT 1",
+        [date],
+        WeekLock::from_row,
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// Refuse schedule changes on any date inside a locked week
+pub fn ensure_week_unlocked(conn: &Connection, date: &str) -> Result<(), String> {
+    match find_week_lock(conn, date)? {
+        Some(lock) => Err(format!(
+            "The week of {} is locked by {} (since {}). Ask an Admin to unlock it first",
+            lock.week_start,
+            lock.locked_by_name.as_deref().unwrap_or("an unknown user"),
+            lock.locked_at
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Lock a reviewed week against edits (Admin only).
+///
+/// A week with error-level conflicts is only locked when `force` is set.
+#[tauri::command]
+pub fn lock_week(
+    token: String,
+    week_start: String,
+    force: Option<bool>,
+    db: State<'_, Database>,
+) -> Result<WeekLock, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "weeks.lock")?;
+
+    let week_start = week_start_of(&conn, parse_date("week_start", &week_start)?);
+    if let Some(lock) = find_week_lock(&conn, &week_start)? {
+        return Ok(lock);
+    }
+
+    if !force.unwrap_or(false) {
+        let conflicts = sweep_week_conflicts(&conn, &week_start, None)?;
+        if conflicts.error_count > 0 {
+            return Err(AppError::Conflict(format!(
+                "The week of {} still has {} scheduling conflict(s). Resolve them or lock with force",
+                week_start, conflicts.error_count
+            )));
+        }
+    }
+
+    conn.execute(
+        "INSERT INTO schedule_locks (week_start, locked_by) VALUES (?1, ?2)",
+        params![week_start, user.id],
+    )
+    .map_err(|e| format!("Failed to lock week: {}", e))?;
+
+    find_week_lock(&conn, &week_start)?.ok_or_else(|| AppError::Database("Failed to lock week".to_string()))
+}
+
+/// Unlock a week so it can be edited again (Admin only)
+#[tauri::command]
+pub fn unlock_week(token: String, week_start: String, db: State<'_, Database>) -> Result<(), AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_permission(&conn, &user, "weeks.lock")?;
+
+    let week_start = week_start_of(&conn, parse_date("week_start", &week_start)?);
+    let Some(lock) = find_week_lock(&conn, &week_start)? else {
+        return Err(AppError::Conflict(format!("The week of {} is not locked", week_start)));
+    };
+    conn.execute("DELETE FROM schedule_locks WHERE week_start = ?1", [&lock.week_start])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
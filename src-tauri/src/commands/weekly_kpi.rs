@@ -0,0 +1,288 @@
+use chrono::{Duration, NaiveDate};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::commands::dashboard::{efficiency_percentage, schedule_hours};
+use crate::db::Database;
+use crate::error::AppError;
+use crate::utils::{require_admin, start_of_week, validate_session};
+
+/// Weeks averaged for the trailing comparison
+const TRAILING_WEEKS: i64 = 4;
+
+/// Changes smaller than this count as flat
+const FLAT_EPSILON: f64 = 0.005;
+
+/// Headline figures for one seven-day week
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WeekKpis {
+    pub week_start: String,
+    pub week_end: String,
+    pub planned_hours: f64,
+    pub actual_hours: f64,
+    /// Same formula as the dashboard's efficiency rate
+    pub efficiency: f64,
+    pub top_machine: Option<String>,
+    pub top_machine_hours: f64,
+    /// Schedule entries marked completed
+    pub completed_loads: i64,
+    /// Cost of maintenance dated in the week, leaving out cancelled work
+    pub maintenance_spend: f64,
+}
+
+/// One figure against the previous week and the trailing average
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KpiDelta {
+    pub metric: String,
+    pub current: f64,
+    pub previous: f64,
+    pub trailing_average: f64,
+    pub change_vs_previous: f64,
+    pub change_vs_average: f64,
+    /// "up", "down" or "flat"
+    pub direction_vs_previous: String,
+    pub direction_vs_average: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyKpiComparison {
+    pub current: WeekKpis,
+    pub previous: WeekKpis,
+    /// Mean of the four weeks before the current one
+    pub trailing_average: WeekKpis,
+    pub deltas: Vec<KpiDelta>,
+}
+
+fn direction(change: f64) -> String {
+    if change > FLAT_EPSILON {
+        "up".to_string()
+    } else if change < -FLAT_EPSILON {
+        "down".to_string()
+    } else {
+        "flat".to_string()
+    }
+}
+
+fn delta(metric: &str, current: f64, previous: f64, trailing_average: f64) -> KpiDelta {
+    KpiDelta {
+        metric: metric.to_string(),
+        current,
+        previous,
+        trailing_average,
+        change_vs_previous: current - previous,
+        change_vs_average: current - trailing_average,
+        direction_vs_previous: direction(current - previous),
+        direction_vs_average: direction(current - trailing_average),
+    }
+}
+
+/// Figures for the week starting on `week_start`
+fn week_kpis(conn: &Connection, week_start: NaiveDate) -> Result<WeekKpis, String> {
+    let week_end = week_start + Duration::days(6);
+    let start = week_start.format("%Y-%m-%d").to_string();
+    let end = week_end.format("%Y-%m-%d").to_string();
+
+    let (planned_hours, actual_hours) = schedule_hours(conn, &start, &end, None);
+
+    let top_machine: Option<(String, f64)> = conn
+        .query_row(
+            "SELECT m.name, SUM(s.actual_hours) as hours
+             FROM schedules s JOIN machines m ON s.machine_id = m.id
+             WHERE s.date >= ?1 AND s.date <= ?2 AND s.actual_hours > 0
+             GROUP BY m.id
+             ORDER BY hours DESC, m.name
+             LIMIT 1",
+            params![start, end],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .ok();
+
+    let completed_loads: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM schedules WHERE date >= ?1 AND date <= ?2 AND status = 'completed'",
+            params![start, end],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let maintenance_spend: f64 = conn
+        .query_row(
+            "SELECT COALESCE(SUM(cost), 0) FROM maintenance
+             WHERE date >= ?1 AND date <= ?2 AND status != 'cancelled'",
+            params![start, end],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let (top_machine, top_machine_hours) = match top_machine {
+        Some((name, hours)) => (Some(name), hours),
+        None => (None, 0.0),
+    };
+
+    Ok(WeekKpis {
+        week_start: start,
+        week_end: end,
+        planned_hours,
+        actual_hours,
+        efficiency: efficiency_percentage(planned_hours, actual_hours),
+        top_machine,
+        top_machine_hours,
+        completed_loads,
+        maintenance_spend,
+    })
+}
+
+/// Mean of each figure over `weeks`; the top machine is left out
+fn average(weeks: &[WeekKpis]) -> WeekKpis {
+    let count = weeks.len().max(1) as f64;
+    let mean = |f: fn(&WeekKpis) -> f64| weeks.iter().map(f).sum::<f64>() / count;
+    WeekKpis {
+        week_start: weeks.last().map(|w| w.week_start.clone()).unwrap_or_default(),
+        week_end: weeks.first().map(|w| w.week_end.clone()).unwrap_or_default(),
+        planned_hours: mean(|w| w.planned_hours),
+        actual_hours: mean(|w| w.actual_hours),
+        efficiency: mean(|w| w.efficiency),
+        top_machine: None,
+        top_machine_hours: 0.0,
+        completed_loads: mean(|w| w.completed_loads as f64).round() as i64,
+        maintenance_spend: mean(|w| w.maintenance_spend),
+    }
+}
+
+/// Figures for the week starting on `first_day`, the week before it and the
+/// average of the four weeks before it
+pub fn compare_week(conn: &Connection, first_day: NaiveDate) -> Result<WeeklyKpiComparison, String> {
+    let current = week_kpis(conn, first_day)?;
+    let earlier = (1..=TRAILING_WEEKS)
+        .map(|weeks_ago| week_kpis(conn, first_day - Duration::weeks(weeks_ago)))
+        .collect::<Result<Vec<_>, String>>()?;
+    let previous = earlier[0].clone();
+    let trailing_average = average(&earlier);
+
+    let deltas = vec![
+        delta("planned_hours", current.planned_hours, previous.planned_hours, trailing_average.planned_hours),
+        delta("actual_hours", current.actual_hours, previous.actual_hours, trailing_average.actual_hours),
+        delta("efficiency", current.efficiency, previous.efficiency, trailing_average.efficiency),
+        delta(
+            "completed_loads",
+            current.completed_loads as f64,
+            previous.completed_loads as f64,
+            earlier.iter().map(|w| w.completed_loads as f64).sum::<f64>() / TRAILING_WEEKS as f64,
+        ),
+        delta(
+            "maintenance_spend",
+            current.maintenance_spend,
+            previous.maintenance_spend,
+            trailing_average.maintenance_spend,
+        ),
+    ];
+
+    Ok(WeeklyKpiComparison { current, previous, trailing_average, deltas })
+}
+
+/// Compare a week's hours, efficiency, completed loads and maintenance spend
+/// with the week before and the average of the four weeks before (Admin only)
+///
+/// `week_start` may be any day; the comparison covers the week containing it,
+/// starting on the configured week_start_day. Hours and efficiency use the
+/// dashboard's own queries, so they match the dashboard for the same week.
+#[tauri::command]
+pub fn get_weekly_kpi_comparison(
+    token: String,
+    week_start: String,
+    db: State<'_, Database>,
+) -> Result<WeeklyKpiComparison, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_admin(&conn, &user)?;
+
+    let date = NaiveDate::parse_from_str(&week_start, "%Y-%m-%d")
+        .map_err(|_| AppError::invalid_field("week_start", "week start must be a date in YYYY-MM-DD format"))?;
+
+    Ok(compare_week(&conn, start_of_week(&conn, date))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_pending_migrations;
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    /// Two machines with work in the week of 2026-03-09 and the four weeks before it
+    fn sample_database() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_pending_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO machines (id, name, model, status) VALUES
+                 (1, 'VMC-1', 'V1', 'active'),
+                 (2, 'VMC-2', 'V2', 'idle');
+             INSERT INTO schedules (machine_id, date, planned_hours, actual_hours, status) VALUES
+                 (1, '2026-03-09', 8, 8, 'completed'),
+                 (1, '2026-03-10', 8, 6, 'completed'),
+                 (2, '2026-03-15', 10, 9, 'in-progress'),
+                 (2, '2026-03-16', 8, 8, 'completed'),
+                 (1, '2026-03-02', 8, 4, 'completed'),
+                 (2, '2026-02-23', 8, 8, 'completed'),
+                 (2, '2026-02-16', 8, 8, 'scheduled'),
+                 (1, '2026-02-09', 8, 8, 'completed');
+             INSERT INTO maintenance (machine_id, date, maintenance_type, cost, status) VALUES
+                 (1, '2026-03-11', 'preventive', 300, 'completed'),
+                 (2, '2026-03-12', 'corrective', 999, 'cancelled'),
+                 (1, '2026-03-03', 'inspection', 100, 'scheduled');",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn current_week_reconciles_with_dashboard() {
+        let conn = sample_database();
+        let comparison = compare_week(&conn, date("2026-03-09")).unwrap();
+        let current = &comparison.current;
+
+        // The dashboard's "this week" figures for the same dates
+        let (planned, actual) = schedule_hours(&conn, "2026-03-09", "2026-03-15", None);
+        assert_eq!((current.planned_hours, current.actual_hours), (planned, actual));
+        assert_eq!(current.efficiency, efficiency_percentage(planned, actual));
+
+        assert_eq!((current.week_start.as_str(), current.week_end.as_str()), ("2026-03-09", "2026-03-15"));
+        assert_eq!((planned, actual), (26.0, 23.0));
+        assert_eq!(current.completed_loads, 2);
+        assert_eq!(current.maintenance_spend, 300.0);
+        assert_eq!(current.top_machine.as_deref(), Some("VMC-1"));
+        assert_eq!(current.top_machine_hours, 14.0);
+    }
+
+    #[test]
+    fn compares_with_previous_week_and_trailing_average() {
+        let conn = sample_database();
+        let comparison = compare_week(&conn, date("2026-03-09")).unwrap();
+
+        assert_eq!(comparison.previous.week_start, "2026-03-02");
+        assert_eq!(comparison.previous.actual_hours, 4.0);
+        assert_eq!(comparison.previous.maintenance_spend, 100.0);
+        assert_eq!(comparison.trailing_average.actual_hours, 7.0);
+        assert_eq!(comparison.trailing_average.planned_hours, 8.0);
+
+        let actual = comparison.deltas.iter().find(|d| d.metric == "actual_hours").unwrap();
+        assert_eq!(actual.change_vs_previous, 19.0);
+        assert_eq!(actual.change_vs_average, 16.0);
+        assert_eq!(actual.direction_vs_previous, "up");
+
+        let loads = comparison.deltas.iter().find(|d| d.metric == "completed_loads").unwrap();
+        assert_eq!((loads.previous, loads.trailing_average), (1.0, 0.75));
+    }
+
+    #[test]
+    fn empty_weeks_are_flat() {
+        let conn = sample_database();
+        let comparison = compare_week(&conn, date("2025-01-06")).unwrap();
+        assert_eq!(comparison.current.efficiency, 0.0);
+        assert_eq!(comparison.current.top_machine, None);
+        assert!(comparison.deltas.iter().all(|d| d.direction_vs_previous == "flat"));
+    }
+}
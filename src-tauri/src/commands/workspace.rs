@@ -0,0 +1,147 @@
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
+
+use crate::db::meta::InitError;
+use crate::db::workspace::{self, WorkspaceConfig, DEFAULT_DB_FILE};
+use crate::db::{open_database, Database};
+use crate::error::AppError;
+use crate::utils::{record_audit, require_admin, validate_session};
+
+/// The database file in use and the workspace config around it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseInfo {
+    pub path: String,
+    pub default_path: String,
+    pub is_default: bool,
+    /// Path from the workspace config when it isn't the open file, i.e. startup fell back
+    pub unavailable_path: Option<String>,
+    pub size_bytes: Option<u64>,
+    /// Last migration applied (PRAGMA user_version)
+    pub schema_version: i64,
+    pub journal_mode: String,
+    /// Recently opened database files, most recent first
+    pub recent_paths: Vec<String>,
+}
+
+/// Result of switching to another database file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceSwitch {
+    pub database: DatabaseInfo,
+    /// Sessions signed out, in the old and new database together
+    pub sessions_invalidated: usize,
+}
+
+fn database_info(conn: &Connection, path: PathBuf, app: &AppHandle) -> Result<DatabaseInfo, String> {
+    let default_path = workspace::default_db_path(app);
+    let config = WorkspaceConfig::load(app);
+    let configured = config.database_path.clone().unwrap_or_else(|| default_path.clone());
+
+    let schema_version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).map_err(|e| e.to_string())?;
+    let journal_mode: String = conn.query_row("PRAGMA journal_mode", [], |row| row.get(0)).map_err(|e| e.to_string())?;
+
+    Ok(DatabaseInfo {
+        size_bytes: std::fs::metadata(&path).ok().map(|m| m.len()),
+        is_default: path == default_path,
+        unavailable_path: (configured != path).then(|| configured.display().to_string()),
+        path: path.display().to_string(),
+        default_path: default_path.display().to_string(),
+        schema_version,
+        journal_mode,
+        recent_paths: config.recent.iter().map(|p| p.display().to_string()).collect(),
+    })
+}
+
+/// Sign out every session in the database; tokens don't carry over between workspaces
+fn invalidate_all_sessions(conn: &Connection) -> Result<usize, String> {
+    conn.execute("UPDATE sessions SET is_valid = 0 WHERE is_valid = 1", [])
+        .map_err(|e| format!("Failed to invalidate sessions: {}", e))
+}
+
+/// Show which database file is open, where the default one is, and recent workspaces (Admin only)
+#[tauri::command]
+pub fn get_database_info(token: String, app: AppHandle, db: State<'_, Database>) -> Result<DatabaseInfo, AppError> {
+    let conn = db.conn.lock();
+    let user = validate_session(&conn, &token)?;
+    require_admin(&conn, &user)?;
+
+    Ok(database_info(&conn, db.path(), &app)?)
+}
+
+/// Open another database file as the workspace (Admin only)
+///
+/// `path` may name a file or a folder, which uses the default file name inside
+/// it. The file is created and seeded if new, and migrated if older. Every
+/// session in both databases is signed out, including the caller's, and the
+/// choice is remembered for the next start.
+#[tauri::command]
+pub fn switch_workspace(
+    token: String,
+    path: String,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<WorkspaceSwitch, AppError> {
+    let user = {
+        let conn = db.conn.lock();
+        let user = validate_session(&conn, &token)?;
+        require_admin(&conn, &user)?;
+        user
+    };
+
+    let path = path.trim();
+    if path.is_empty() {
+        return Err(AppError::invalid_field("path", "Database location is required"));
+    }
+    let mut new_path = PathBuf::from(path);
+    if !new_path.is_absolute() {
+        return Err(AppError::invalid_field("path", "Database location must be a full path"));
+    }
+    if new_path.is_dir() {
+        new_path.push(DEFAULT_DB_FILE);
+    }
+    let old_path = db.path();
+    if new_path == old_path {
+        return Err(AppError::invalid_field("path", "This database is already open"));
+    }
+    if !workspace::is_reachable(&new_path) {
+        return Err(AppError::invalid_field(
+            "path",
+            format!("The folder for {} can't be reached", new_path.display()),
+        ));
+    }
+
+    let wal = workspace::is_local(&app, &new_path);
+    let (new_db, compatibility) = open_database(new_path.clone(), wal).map_err(|e| match e {
+        InitError::Incompatible(message) => AppError::Conflict(message),
+        InitError::Failed(message) => AppError::Database(message),
+    })?;
+    for warning in &compatibility.warnings {
+        log::warn!("{}", warning);
+    }
+    crate::run_startup_checks(&new_db);
+
+    let mut config = WorkspaceConfig::load(&app);
+    config.select(&new_path, &workspace::default_db_path(&app));
+    config.save(&app)?;
+
+    let mut sessions_invalidated = invalidate_all_sessions(&new_db.conn.lock())?;
+    {
+        let conn = db.conn.lock();
+        let values = serde_json::json!({
+            "from": old_path.display().to_string(),
+            "to": new_path.display().to_string(),
+        });
+        record_audit(&conn, &user, "workspace_switch", "workspace", None, None, Some(&values.to_string()));
+        sessions_invalidated += invalidate_all_sessions(&conn)?;
+    }
+
+    log::info!("Switched workspace from {:?} to {:?}", old_path, new_path);
+    db.replace(new_db);
+
+    let conn = db.conn.lock();
+    Ok(WorkspaceSwitch {
+        database: database_info(&conn, new_path, &app)?,
+        sessions_invalidated,
+    })
+}
@@ -0,0 +1,466 @@
+/// Tables created by migration 1, as released.
+///
+/// Frozen: fresh databases are built from this and then brought up to date by
+/// the later migrations, so editing it would change a released migration and
+/// leave new and upgraded databases with different schemas. Schema changes go
+/// in a new migration in migrations.rs.
+pub(super) const BASE_SCHEMA: &str = r#"
+        -- Users table for authentication
+        CREATE TABLE IF NOT EXISTS users (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            username TEXT NOT NULL UNIQUE,
+            password_hash TEXT NOT NULL,
+            email TEXT,
+            full_name TEXT,
+            role TEXT NOT NULL CHECK (role IN ('Admin', 'Operator', 'Viewer')),
+            is_active INTEGER DEFAULT 1,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Sessions table for tracking active logins
+        CREATE TABLE IF NOT EXISTS sessions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            token TEXT NOT NULL UNIQUE,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            expires_at TEXT NOT NULL,
+            is_valid INTEGER DEFAULT 1
+        );
+
+        -- Clients table
+        CREATE TABLE IF NOT EXISTS clients (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            contact_email TEXT,
+            contact_phone TEXT,
+            address TEXT,
+            notes TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Machines table with the 7 real VMC machines
+        CREATE TABLE IF NOT EXISTS machines (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            model TEXT NOT NULL,
+            serial_number TEXT,
+            purchase_date TEXT,
+            status TEXT NOT NULL CHECK (status IN ('active', 'idle', 'maintenance', 'error')),
+            location TEXT,
+            capacity TEXT CHECK (capacity IN ('Small', 'Medium', 'Large', 'Extra Large')),
+            power_consumption TEXT,
+            dimensions TEXT,
+            weight TEXT,
+            max_rpm TEXT,
+            axis_travel TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Projects table
+        CREATE TABLE IF NOT EXISTS projects (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            client_id INTEGER REFERENCES clients(id) ON DELETE SET NULL,
+            description TEXT,
+            start_date TEXT,
+            end_date TEXT,
+            status TEXT NOT NULL CHECK (status IN ('planning', 'active', 'completed', 'on-hold')),
+            planned_hours REAL DEFAULT 0,
+            actual_hours REAL DEFAULT 0,
+            created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Project-Machine many-to-many relationship
+        CREATE TABLE IF NOT EXISTS project_machines (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+            machine_id INTEGER NOT NULL REFERENCES machines(id) ON DELETE CASCADE,
+            assigned_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(project_id, machine_id)
+        );
+
+        -- Project team members
+        CREATE TABLE IF NOT EXISTS project_team (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+            user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            role TEXT DEFAULT 'member',
+            assigned_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(project_id, user_id)
+        );
+
+        -- Weekly planner schedules
+        CREATE TABLE IF NOT EXISTS schedules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            machine_id INTEGER NOT NULL REFERENCES machines(id) ON DELETE CASCADE,
+            project_id INTEGER REFERENCES projects(id) ON DELETE SET NULL,
+            date TEXT NOT NULL,
+            start_time TEXT,
+            end_time TEXT,
+            operator_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+            load_name TEXT,
+            planned_hours REAL DEFAULT 0,
+            actual_hours REAL,
+            notes TEXT,
+            status TEXT DEFAULT 'scheduled' CHECK (status IN ('scheduled', 'in-progress', 'completed', 'cancelled')),
+            created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Maintenance records
+        CREATE TABLE IF NOT EXISTS maintenance (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            machine_id INTEGER NOT NULL REFERENCES machines(id) ON DELETE CASCADE,
+            date TEXT NOT NULL,
+            maintenance_type TEXT NOT NULL CHECK (maintenance_type IN ('preventive', 'corrective', 'inspection', 'calibration')),
+            description TEXT,
+            performed_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+            cost REAL,
+            status TEXT DEFAULT 'scheduled' CHECK (status IN ('scheduled', 'in-progress', 'completed', 'cancelled')),
+            notes TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Alerts/Notifications
+        CREATE TABLE IF NOT EXISTS alerts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            alert_type TEXT NOT NULL CHECK (alert_type IN ('info', 'warning', 'error', 'maintenance', 'schedule')),
+            priority TEXT NOT NULL CHECK (priority IN ('low', 'medium', 'high', 'critical')),
+            title TEXT NOT NULL,
+            message TEXT NOT NULL,
+            machine_id INTEGER REFERENCES machines(id) ON DELETE CASCADE,
+            project_id INTEGER REFERENCES projects(id) ON DELETE CASCADE,
+            is_read INTEGER DEFAULT 0,
+            read_at TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Audit log for tracking changes
+        CREATE TABLE IF NOT EXISTS audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+            username TEXT,
+            action TEXT NOT NULL,
+            table_name TEXT NOT NULL,
+            record_id INTEGER,
+            old_values TEXT,
+            new_values TEXT,
+            timestamp TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Indexes for performance
+        CREATE INDEX IF NOT EXISTS idx_schedules_date ON schedules(date);
+        CREATE INDEX IF NOT EXISTS idx_schedules_machine ON schedules(machine_id);
+        CREATE INDEX IF NOT EXISTS idx_schedules_load_name ON schedules(load_name COLLATE NOCASE);
+        CREATE INDEX IF NOT EXISTS idx_maintenance_machine ON maintenance(machine_id);
+        CREATE INDEX IF NOT EXISTS idx_alerts_machine ON alerts(machine_id);
+        CREATE INDEX IF NOT EXISTS idx_alerts_is_read ON alerts(is_read);
+        CREATE INDEX IF NOT EXISTS idx_audit_log_table ON audit_log(table_name);
+        CREATE INDEX IF NOT EXISTS idx_audit_log_timestamp ON audit_log(timestamp);
+        CREATE INDEX IF NOT EXISTS idx_projects_status ON projects(status);
+        CREATE INDEX IF NOT EXISTS idx_machines_status ON machines(status);
+        CREATE INDEX IF NOT EXISTS idx_sessions_token ON sessions(token);
+        CREATE INDEX IF NOT EXISTS idx_sessions_user ON sessions(user_id);
+
+        -- Downtime log for machine availability tracking
+        CREATE TABLE IF NOT EXISTS downtime_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            machine_id INTEGER NOT NULL REFERENCES machines(id) ON DELETE CASCADE,
+            start_time TEXT NOT NULL,
+            end_time TEXT,
+            reason_category TEXT NOT NULL CHECK (reason_category IN ('maintenance', 'breakdown', 'setup', 'idle', 'other')),
+            description TEXT,
+            created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Pre-shift checklist templates per machine
+        CREATE TABLE IF NOT EXISTS checklist_templates (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            machine_id INTEGER REFERENCES machines(id) ON DELETE CASCADE,
+            checklist_item TEXT NOT NULL,
+            is_active INTEGER DEFAULT 1,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Pre-shift checklist completions
+        CREATE TABLE IF NOT EXISTS checklist_completions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            machine_id INTEGER NOT NULL REFERENCES machines(id) ON DELETE CASCADE,
+            template_id INTEGER NOT NULL REFERENCES checklist_templates(id) ON DELETE CASCADE,
+            checked_by INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            check_date TEXT NOT NULL,
+            is_completed INTEGER DEFAULT 0,
+            notes TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Shift handover logs
+        CREATE TABLE IF NOT EXISTS shift_logs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            machine_id INTEGER REFERENCES machines(id) ON DELETE CASCADE,
+            shift_date TEXT NOT NULL,
+            outgoing_operator_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+            notes TEXT NOT NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Application settings (key/value)
+        CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            value_type TEXT NOT NULL DEFAULT 'string',
+            updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Per-user notification preferences; device_name '' applies to every device
+        CREATE TABLE IF NOT EXISTS user_notification_settings (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            device_name TEXT NOT NULL DEFAULT '',
+            notify_priorities TEXT NOT NULL DEFAULT 'high,critical',
+            sound_enabled INTEGER NOT NULL DEFAULT 0,
+            quiet_hours_start TEXT,
+            quiet_hours_end TEXT,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE (user_id, device_name)
+        );
+
+        -- Database metadata (app version of the last writer; the schema version is PRAGMA user_version)
+        CREATE TABLE IF NOT EXISTS app_meta (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Fixed shift definitions used to fill schedule times
+        CREATE TABLE IF NOT EXISTS shifts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            start_time TEXT NOT NULL,
+            end_time TEXT NOT NULL,
+            default_hours REAL NOT NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Machine status changes, newest last
+        CREATE TABLE IF NOT EXISTS machine_status_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            machine_id INTEGER NOT NULL REFERENCES machines(id) ON DELETE CASCADE,
+            old_status TEXT,
+            new_status TEXT NOT NULL,
+            reason TEXT,
+            changed_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+            changed_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Weeks locked against schedule edits, keyed by Monday
+        CREATE TABLE IF NOT EXISTS schedule_locks (
+            week_start TEXT PRIMARY KEY,
+            locked_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+            locked_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Plants with their own wall clock; machines without one use the local site
+        CREATE TABLE IF NOT EXISTS sites (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            timezone TEXT NOT NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Named places on the shop floor; machines.location keeps the old free text
+        CREATE TABLE IF NOT EXISTS locations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            description TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Color labels for schedule entries
+        CREATE TABLE IF NOT EXISTS schedule_tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            color TEXT NOT NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Shop holidays and other non-working days
+        CREATE TABLE IF NOT EXISTS holidays (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            date TEXT NOT NULL UNIQUE,
+            name TEXT NOT NULL,
+            is_recurring INTEGER NOT NULL DEFAULT 0,
+            created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Material and bought-in parts a project waits on
+        CREATE TABLE IF NOT EXISTS project_materials (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+            name TEXT NOT NULL,
+            quantity_required REAL NOT NULL DEFAULT 0,
+            quantity_received REAL NOT NULL DEFAULT 0,
+            unit TEXT,
+            expected_date TEXT,
+            received_date TEXT,
+            notes TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Saved dashboard widget arrangement per user (JSON array of placements)
+        CREATE TABLE IF NOT EXISTS user_dashboard_layouts (
+            user_id INTEGER PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+            layout TEXT NOT NULL,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Manuals, certificates and other files kept with a machine.
+        -- stored_path is relative to the database file's folder; the files are not
+        -- inside the database, so copy that folder along with it.
+        CREATE TABLE IF NOT EXISTS machine_documents (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            machine_id INTEGER NOT NULL REFERENCES machines(id) ON DELETE CASCADE,
+            file_name TEXT NOT NULL,
+            stored_path TEXT NOT NULL,
+            mime_type TEXT NOT NULL,
+            size_bytes INTEGER NOT NULL DEFAULT 0,
+            uploaded_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+            uploaded_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Client-facing checkpoints within a project ("first article approved")
+        CREATE TABLE IF NOT EXISTS project_milestones (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+            title TEXT NOT NULL,
+            due_date TEXT,
+            completed_at TEXT,
+            sort_order INTEGER NOT NULL DEFAULT 0,
+            notes TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Units of work within a project, each with its own hours
+        CREATE TABLE IF NOT EXISTS project_tasks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+            name TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'planning' CHECK (status IN ('planning', 'active', 'completed', 'on-hold')),
+            planned_hours REAL DEFAULT 0,
+            actual_hours REAL DEFAULT 0,
+            sort_order INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Expenses recorded against a project besides machine time
+        CREATE TABLE IF NOT EXISTS project_costs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+            category TEXT NOT NULL CHECK (category IN ('tooling', 'material', 'outsourcing', 'other')),
+            description TEXT NOT NULL,
+            amount REAL NOT NULL,
+            cost_date TEXT NOT NULL,
+            created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Hours logged directly against a project, outside schedule entries
+        CREATE TABLE IF NOT EXISTS project_hour_logs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+            user_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+            hours REAL NOT NULL,
+            date TEXT NOT NULL,
+            note TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS project_notes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+            user_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+            note TEXT NOT NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS client_contacts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            client_id INTEGER NOT NULL REFERENCES clients(id) ON DELETE CASCADE,
+            name TEXT NOT NULL,
+            role TEXT,
+            email TEXT,
+            phone TEXT,
+            is_primary INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS login_attempts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            username TEXT NOT NULL,
+            user_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+            attempted_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            success INTEGER NOT NULL,
+            host TEXT
+        );
+
+        -- Long-lived tokens for scripts; only a hash of each token is kept
+        CREATE TABLE IF NOT EXISTS api_tokens (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            name TEXT NOT NULL,
+            token_hash TEXT NOT NULL UNIQUE,
+            token_hint TEXT NOT NULL,
+            created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            last_used_at TEXT,
+            revoked INTEGER NOT NULL DEFAULT 0
+        );
+
+        -- Permission grants for a role or for one user (exactly one of the two);
+        -- a user's own entry overrides their role's
+        CREATE TABLE IF NOT EXISTS role_permissions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            role TEXT CHECK (role IN ('Admin', 'Operator', 'Viewer')),
+            user_id INTEGER REFERENCES users(id) ON DELETE CASCADE,
+            permission TEXT NOT NULL,
+            allowed INTEGER NOT NULL,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            CHECK ((role IS NULL) != (user_id IS NULL))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_downtime_machine ON downtime_log(machine_id);
+        CREATE INDEX IF NOT EXISTS idx_project_tasks_project ON project_tasks(project_id, sort_order);
+        CREATE INDEX IF NOT EXISTS idx_project_costs_project ON project_costs(project_id, cost_date);
+        CREATE INDEX IF NOT EXISTS idx_project_hour_logs_project ON project_hour_logs(project_id, date);
+        CREATE INDEX IF NOT EXISTS idx_project_notes_project ON project_notes(project_id, created_at);
+        CREATE INDEX IF NOT EXISTS idx_client_contacts_client ON client_contacts(client_id);
+        CREATE INDEX IF NOT EXISTS idx_login_attempts_username ON login_attempts(username, attempted_at);
+        CREATE INDEX IF NOT EXISTS idx_login_attempts_user ON login_attempts(user_id, attempted_at);
+        CREATE INDEX IF NOT EXISTS idx_api_tokens_user ON api_tokens(user_id);
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_role_permissions_role ON role_permissions(role, permission) WHERE role IS NOT NULL;
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_role_permissions_user ON role_permissions(user_id, permission) WHERE user_id IS NOT NULL;
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_client_contacts_primary ON client_contacts(client_id) WHERE is_primary = 1;
+        CREATE INDEX IF NOT EXISTS idx_project_milestones_project ON project_milestones(project_id, sort_order);
+        CREATE INDEX IF NOT EXISTS idx_machine_documents_machine ON machine_documents(machine_id);
+        CREATE INDEX IF NOT EXISTS idx_checklist_machine ON checklist_templates(machine_id);
+        CREATE INDEX IF NOT EXISTS idx_checklist_completions_date ON checklist_completions(check_date);
+        CREATE INDEX IF NOT EXISTS idx_shift_logs_date ON shift_logs(shift_date);
+        CREATE INDEX IF NOT EXISTS idx_project_materials_project ON project_materials(project_id);
+        CREATE INDEX IF NOT EXISTS idx_machine_status_history_machine ON machine_status_history(machine_id, changed_at);
+"#;
@@ -1,61 +1,189 @@
-use parking_lot::Mutex;
-use rusqlite::Connection;
+use parking_lot::{Mutex, MutexGuard, RwLock};
+use rusqlite::{Connection, OpenFlags};
 use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tauri::AppHandle;
+
+use super::meta::{Compatibility, InitError};
+use super::workspace::{self, WorkspaceConfig};
+
+/// Read-only connections kept open next to the writer
+const READ_POOL_SIZE: usize = 3;
+
+/// How long a statement waits for another connection's lock before failing
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Thread-safe database wrapper
+///
+/// `conn` is the single writer: commands that change anything, or that may
+/// audit a refusal, hold it for their whole run. Commands that only query
+/// take a connection from `read()` instead, which in WAL mode doesn't wait
+/// for the writer. Files outside the app data dir use a rollback journal, so
+/// there readers and the writer wait on each other up to the busy timeout.
 pub struct Database {
     pub conn: Mutex<Connection>,
+    readers: Vec<Mutex<Connection>>,
+    next_reader: AtomicUsize,
+    /// Location of the database file; changes when the workspace is switched
+    path: RwLock<PathBuf>,
 }
 
 impl Database {
-    /// Create a new database connection
-    pub fn new(db_path: PathBuf) -> Result<Self, rusqlite::Error> {
+    /// Create a new database connection, in WAL mode when `wal` is set and
+    /// with a rollback journal otherwise
+    pub fn new(db_path: PathBuf, wal: bool) -> Result<Self, rusqlite::Error> {
         // Ensure parent directory exists
         if let Some(parent) = db_path.parent() {
             std::fs::create_dir_all(parent).ok();
         }
 
         let conn = Connection::open(&db_path)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
 
         // Enable foreign keys
         conn.execute_batch("PRAGMA foreign_keys = ON;")?;
 
+        // WAL lets readers run alongside a write; some filesystems refuse it.
+        // Switching back to DELETE also converts a file last opened in WAL mode.
+        let requested = if wal { "WAL" } else { "DELETE" };
+        let journal_mode: String =
+            conn.pragma_update_and_check(None, "journal_mode", requested, |row| row.get(0))?;
+        if journal_mode.eq_ignore_ascii_case("wal") {
+            conn.pragma_update(None, "synchronous", "NORMAL")?;
+        } else if wal {
+            log::warn!("WAL journal mode unavailable, using {}", journal_mode);
+        }
+
+        let readers = (0..READ_POOL_SIZE)
+            .map(|_| {
+                let reader = Connection::open_with_flags(
+                    &db_path,
+                    OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+                )?;
+                reader.busy_timeout(BUSY_TIMEOUT)?;
+                Ok(Mutex::new(reader))
+            })
+            .collect::<rusqlite::Result<_>>()?;
+
         Ok(Self {
             conn: Mutex::new(conn),
+            readers,
+            next_reader: AtomicUsize::new(0),
+            path: RwLock::new(db_path),
         })
     }
 
-    /// Get the database path from app handle
-    pub fn get_db_path(app_handle: &AppHandle) -> PathBuf {
-        let app_data_dir = app_handle
-            .path()
-            .app_data_dir()
-            .expect("Failed to get app data directory");
+    /// A read-only connection for commands that only query
+    ///
+    /// Takes the first idle reader, or waits on the next one in turn when all
+    /// are busy. Writes through it fail, so sessions must still be validated
+    /// on `conn`, which may extend them.
+    pub fn read(&self) -> MutexGuard<'_, Connection> {
+        if let Some(reader) = self.readers.iter().find_map(|reader| reader.try_lock()) {
+            return reader;
+        }
+        let index = self.next_reader.fetch_add(1, Ordering::Relaxed) % self.readers.len();
+        self.readers[index].lock()
+    }
+
+    /// Location of the open database file
+    pub fn path(&self) -> PathBuf {
+        self.path.read().clone()
+    }
 
-        app_data_dir.join("vmc_planner.db")
+    /// File holding the key that seals stored TOTP secrets
+    pub fn secret_key_path(&self) -> PathBuf {
+        self.path().with_extension("key")
+    }
+
+    /// Take over `other`'s connections, closing the current ones
+    ///
+    /// Tauri state can't be managed twice, so switching workspaces swaps the
+    /// connections inside the managed Database. Waits for commands still
+    /// holding a connection; the caller must not hold one itself.
+    pub fn replace(&self, other: Database) {
+        let mut conn = self.conn.lock();
+        let mut readers: Vec<_> = self.readers.iter().map(|reader| reader.lock()).collect();
+
+        *conn = other.conn.into_inner();
+        for (reader, replacement) in readers.iter_mut().zip(other.readers) {
+            **reader = replacement.into_inner();
+        }
+        *self.path.write() = other.path.into_inner();
     }
 }
 
-/// Initialize the database with tables and seed data if needed
-pub fn initialize_database(app_handle: &AppHandle) -> Result<Database, String> {
-    let db_path = Database::get_db_path(app_handle);
+/// Open the workspace's database, falling back to the default one when the
+/// configured location can't be opened
+///
+/// The fallback is reported as a startup warning and an alert; the config keeps
+/// pointing at the configured file so the next start tries it again.
+pub fn initialize_database(app_handle: &AppHandle) -> Result<(Database, Compatibility), InitError> {
+    let default_path = workspace::default_db_path(app_handle);
+    let configured = WorkspaceConfig::load(app_handle)
+        .database_path
+        .filter(|path| *path != default_path);
+    let Some(configured) = configured else {
+        return open_database(default_path, true);
+    };
+
+    let reason = if workspace::is_reachable(&configured) {
+        match open_database(configured.clone(), workspace::is_local(app_handle, &configured)) {
+            Err(InitError::Failed(reason)) => reason,
+            opened => return opened,
+        }
+    } else {
+        "its folder can't be reached".to_string()
+    };
+    log::warn!("Workspace database {:?} unavailable ({}); opening the default", configured, reason);
+
+    let (db, mut compatibility) = open_database(default_path, true)?;
+    let message = format!(
+        "The workspace database at {} could not be opened ({}), so the default database was opened \
+         instead. Switch back in Settings once it is available.",
+        configured.display(),
+        reason
+    );
+    if let Err(e) = workspace::raise_fallback_alert(&db.conn.lock(), &message) {
+        log::warn!("{}", e);
+    }
+    compatibility.warnings.push(message);
+    Ok((db, compatibility))
+}
 
+/// Open the database file at `db_path` with tables and seed data if needed
+///
+/// Refuses databases whose user_version is past this build's last migration,
+/// before touching any tables, otherwise runs pending migrations before seeding.
+/// A failed migration stops with an error naming it. Non-fatal version warnings
+/// are returned alongside the database. `wal` is only set for local files
+/// (see `workspace::is_local`).
+pub fn open_database(db_path: PathBuf, wal: bool) -> Result<(Database, Compatibility), InitError> {
     log::info!("Initializing database at: {:?}", db_path);
 
-    let db = Database::new(db_path).map_err(|e| format!("Failed to open database: {}", e))?;
+    let db = Database::new(db_path, wal).map_err(|e| format!("Failed to open database: {}", e))?;
 
-    // Create tables
-    {
+    // Read the versions before changing anything
+    let compatibility = {
         let conn = db.conn.lock();
-        super::schema::create_tables(&conn)
-            .map_err(|e| format!("Failed to create tables: {}", e))?;
+        super::meta::check_compatibility(&conn)?
+    };
+    for warning in &compatibility.warnings {
+        log::warn!("{}", warning);
     }
 
-    // Run column migrations for existing databases
+    // Bring the schema up to date, each pending migration in its own transaction
     {
         let conn = db.conn.lock();
-        run_migrations(&conn);
+        let applied = super::migrations::run_pending_migrations(&conn)?;
+        if !applied.is_empty() {
+            log::info!("Database migrated to version {}", super::migrations::LATEST_MIGRATION);
+        }
+        if let Err(e) = super::seed::seed_role_permissions(&conn) {
+            log::warn!("Permission defaults could not be stored: {}", e);
+        }
+        super::meta::record_app_version(&conn)?;
     }
 
     // Seed initial data if database is empty
@@ -73,26 +201,25 @@ pub fn initialize_database(app_handle: &AppHandle) -> Result<Database, String> {
         }
     }
 
-    Ok(db)
+    Ok((db, compatibility))
 }
 
-fn run_migrations(conn: &Connection) {
-    // Add new columns to existing tables - errors ignored (column already exists)
-    let migrations = [
-        "ALTER TABLE machines ADD COLUMN hourly_rate REAL DEFAULT 0.0",
-        "ALTER TABLE schedules ADD COLUMN setup_hours REAL DEFAULT 0.0",
-        "ALTER TABLE schedules ADD COLUMN sequence_order INTEGER DEFAULT 0",
-        "ALTER TABLE schedules ADD COLUMN drawing_number TEXT",
-        "ALTER TABLE schedules ADD COLUMN revision TEXT",
-        "ALTER TABLE schedules ADD COLUMN material TEXT",
-        "ALTER TABLE projects ADD COLUMN actual_completion_date TEXT",
-        "ALTER TABLE schedules ADD COLUMN cam_planned_hours REAL",
-        "ALTER TABLE schedules ADD COLUMN cam_actual_hours REAL",
-        "ALTER TABLE schedules ADD COLUMN cam_buffer_percentage REAL",
-        "ALTER TABLE schedules ADD COLUMN job_type TEXT",
-        "ALTER TABLE projects ADD COLUMN part_name TEXT",
-    ];
-    for sql in &migrations {
-        let _ = conn.execute_batch(sql);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn journal_mode(db: &Database) -> String {
+        db.conn.lock().query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn wal_only_when_asked() {
+        let path = std::env::temp_dir().join(format!("vmc-journal-{}.db", uuid::Uuid::new_v4()));
+        assert_eq!(journal_mode(&Database::new(path.clone(), true).unwrap()), "wal");
+        // Reopening a WAL file from a share converts it back
+        assert_eq!(journal_mode(&Database::new(path.clone(), false).unwrap()), "delete");
+        for suffix in ["", "-wal", "-shm"] {
+            std::fs::remove_file(format!("{}{}", path.display(), suffix)).ok();
+        }
     }
 }
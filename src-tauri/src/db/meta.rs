@@ -0,0 +1,247 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use super::migrations::{user_version, MigrationError, LATEST_MIGRATION};
+
+/// Version of the running application
+pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const APP_VERSION_KEY: &str = "app_version";
+
+pub const STARTUP_READY: &str = "ready";
+pub const STARTUP_INCOMPATIBLE: &str = "incompatible";
+pub const STARTUP_FAILED: &str = "failed";
+
+/// Why the database could not be opened
+#[derive(Debug, Clone)]
+pub enum InitError {
+    /// The file was written by a newer schema than this build understands
+    Incompatible(String),
+    Failed(String),
+}
+
+impl From<String> for InitError {
+    fn from(message: String) -> Self {
+        InitError::Failed(message)
+    }
+}
+
+impl From<MigrationError> for InitError {
+    fn from(error: MigrationError) -> Self {
+        match error {
+            MigrationError::Newer { .. } => InitError::Incompatible(error.to_string()),
+            MigrationError::Failed { .. } => InitError::Failed(error.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for InitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InitError::Incompatible(message) | InitError::Failed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Outcome of opening the database at startup, shown by the frontend before login
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupStatus {
+    pub state: String,
+    pub message: Option<String>,
+    pub warnings: Vec<String>,
+    pub app_version: String,
+    pub supported_schema_version: i64,
+    pub database_schema_version: Option<i64>,
+    pub database_app_version: Option<String>,
+}
+
+impl StartupStatus {
+    pub fn ready(compat: &Compatibility) -> Self {
+        Self {
+            state: STARTUP_READY.to_string(),
+            message: None,
+            warnings: compat.warnings.clone(),
+            app_version: APP_VERSION.to_string(),
+            supported_schema_version: LATEST_MIGRATION,
+            database_schema_version: compat.schema_version,
+            database_app_version: compat.app_version.clone(),
+        }
+    }
+
+    pub fn from_error(error: &InitError) -> Self {
+        let state = match error {
+            InitError::Incompatible(_) => STARTUP_INCOMPATIBLE,
+            InitError::Failed(_) => STARTUP_FAILED,
+        };
+        Self {
+            state: state.to_string(),
+            message: Some(error.to_string()),
+            warnings: Vec::new(),
+            app_version: APP_VERSION.to_string(),
+            supported_schema_version: LATEST_MIGRATION,
+            database_schema_version: None,
+            database_app_version: None,
+        }
+    }
+}
+
+/// Versions found in an existing database, plus any non-fatal warnings
+#[derive(Debug, Clone, Default)]
+pub struct Compatibility {
+    /// The database's user_version; None for a new, empty file
+    pub schema_version: Option<i64>,
+    pub app_version: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+fn read_meta(conn: &Connection, key: &str) -> Option<String> {
+    let has_table: bool = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'app_meta'",
+            [],
+            |row| row.get::<_, i64>(0),
+        )
+        .map(|count| count > 0)
+        .unwrap_or(false);
+    if !has_table {
+        return None;
+    }
+    conn.query_row("SELECT value FROM app_meta WHERE key = ?1", params![key], |row| row.get(0))
+        .optional()
+        .ok()
+        .flatten()
+}
+
+/// Parse "major.minor.patch" leniently, treating missing parts as zero
+fn parse_version(version: &str) -> (u64, u64, u64) {
+    let mut parts = version
+        .trim()
+        .trim_start_matches('v')
+        .split('.')
+        .map(|p| p.split(|c: char| !c.is_ascii_digit()).next().unwrap_or("").parse().unwrap_or(0));
+    (
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+    )
+}
+
+/// Read the database's schema version and the app version that last wrote it.
+///
+/// The schema version is `PRAGMA user_version`; run_pending_migrations refuses a
+/// database past this build's last migration. A database last written by a newer
+/// major or minor app version only produces a warning, since its schema still
+/// matches.
+pub fn check_compatibility(conn: &Connection) -> Result<Compatibility, InitError> {
+    let schema_version = user_version(conn).map_err(|e| format!("Failed to read schema version: {}", e))?;
+    Ok(check_app_version(
+        (schema_version > 0).then_some(schema_version),
+        read_meta(conn, APP_VERSION_KEY),
+        APP_VERSION,
+    ))
+}
+
+fn check_app_version(schema_version: Option<i64>, app_version: Option<String>, current_app: &str) -> Compatibility {
+    let mut warnings = Vec::new();
+    if let Some(written_by) = &app_version {
+        let (db_major, db_minor, _) = parse_version(written_by);
+        let (major, minor, _) = parse_version(current_app);
+        if (db_major, db_minor) > (major, minor) {
+            warnings.push(format!(
+                "This database was last opened by a newer app version ({}). Some data may not \
+                 display correctly in version {}.",
+                written_by, current_app
+            ));
+        }
+    }
+
+    Compatibility {
+        schema_version,
+        app_version,
+        warnings,
+    }
+}
+
+/// Record this build's app version as the database's last writer
+pub fn record_app_version(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO app_meta (key, value, updated_at) VALUES (?1, ?2, CURRENT_TIMESTAMP)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = CURRENT_TIMESTAMP",
+        params![APP_VERSION_KEY, APP_VERSION],
+    )
+    .map_err(|e| format!("Failed to record app version: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_pending_migrations;
+
+    fn table_count(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn refuses_database_with_newer_schema() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", LATEST_MIGRATION + 1).unwrap();
+
+        let compat = check_compatibility(&conn).unwrap();
+        assert_eq!(compat.schema_version, Some(LATEST_MIGRATION + 1));
+        match run_pending_migrations(&conn).map_err(InitError::from) {
+            Err(InitError::Incompatible(message)) => {
+                assert!(message.contains(&(LATEST_MIGRATION + 1).to_string()), "{}", message);
+            }
+            other => panic!("expected an incompatible database, got {:?}", other),
+        }
+        assert_eq!(table_count(&conn), 0);
+        assert_eq!(user_version(&conn).unwrap(), LATEST_MIGRATION + 1);
+    }
+
+    #[test]
+    fn upgrades_database_with_older_schema() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_pending_migrations(&conn).unwrap();
+        conn.pragma_update(None, "user_version", 1).unwrap();
+
+        assert_eq!(check_compatibility(&conn).unwrap().schema_version, Some(1));
+        let applied = run_pending_migrations(&conn).unwrap();
+        assert_eq!(applied, (2..=LATEST_MIGRATION).collect::<Vec<_>>());
+        assert_eq!(check_compatibility(&conn).unwrap().schema_version, Some(LATEST_MIGRATION));
+    }
+
+    #[test]
+    fn opens_new_database() {
+        let conn = Connection::open_in_memory().unwrap();
+        let compat = check_compatibility(&conn).unwrap();
+        assert_eq!(compat.schema_version, None);
+        assert_eq!(compat.app_version, None);
+        assert!(compat.warnings.is_empty());
+
+        run_pending_migrations(&conn).unwrap();
+        record_app_version(&conn).unwrap();
+        assert_eq!(check_compatibility(&conn).unwrap().app_version.as_deref(), Some(APP_VERSION));
+    }
+
+    #[test]
+    fn warns_when_written_by_newer_minor_version() {
+        let compat = check_app_version(Some(1), Some("2.3.0".to_string()), "2.2.9");
+        assert_eq!(compat.warnings.len(), 1);
+
+        let compat = check_app_version(Some(1), Some("2.2.9".to_string()), "2.2.0");
+        assert!(compat.warnings.is_empty());
+
+        let compat = check_app_version(Some(1), Some("1.9.0".to_string()), "2.0.0");
+        assert!(compat.warnings.is_empty());
+    }
+
+    #[test]
+    fn parses_versions_leniently() {
+        assert_eq!(parse_version("v2.0.2"), (2, 0, 2));
+        assert_eq!(parse_version("2.1"), (2, 1, 0));
+        assert_eq!(parse_version("3.0.0-beta.1"), (3, 0, 0));
+        assert_eq!(parse_version("garbage"), (0, 0, 0));
+    }
+}
@@ -0,0 +1,385 @@
+use rusqlite::{params, Connection};
+
+use super::meta::APP_VERSION;
+use crate::models::{parse_power_kw, parse_rpm, parse_weight_kg};
+
+/// A numbered change to the schema.
+///
+/// `PRAGMA user_version` holds the number of the last migration applied.
+/// Pending migrations run in order at startup, each in its own transaction, so
+/// a failure leaves the database at the last version that completed. Never
+/// edit a released migration, including the base schema it starts from: add a
+/// new one.
+struct Migration {
+    version: i64,
+    description: &'static str,
+    apply: fn(&Connection) -> rusqlite::Result<()>,
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, description: "base schema", apply: base_schema },
+    Migration { version: 2, description: "record who last edited a schedule entry", apply: add_schedule_updated_by },
+    Migration { version: 3, description: "track rows created as demo data", apply: add_demo_records },
+    Migration { version: 4, description: "let users turn off desktop notifications", apply: add_desktop_notifications },
+    Migration { version: 5, description: "keep alert read state per user", apply: add_alert_reads },
+    Migration { version: 6, description: "record alert acknowledgements", apply: add_alert_acknowledgements },
+];
+
+/// Version a database is at once every migration has run
+pub const LATEST_MIGRATION: i64 = MIGRATIONS[MIGRATIONS.len() - 1].version;
+
+/// Why the schema could not be brought up to date
+#[derive(Debug)]
+pub enum MigrationError {
+    /// The database is past the last migration this build knows, so a newer
+    /// version of the app wrote it
+    Newer { version: i64 },
+    /// A migration failed and was rolled back
+    Failed {
+        version: i64,
+        description: &'static str,
+        /// Version the database was left at
+        current_version: i64,
+        error: rusqlite::Error,
+    },
+}
+
+impl std::fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrationError::Newer { version } => write!(
+                f,
+                "This database uses schema version {}, but this app (version {}) only supports up to \
+                 schema version {}. Please update the app.",
+                version, APP_VERSION, LATEST_MIGRATION
+            ),
+            MigrationError::Failed { version, description, current_version, error } => write!(
+                f,
+                "Database upgrade failed at migration {} ({}): {}. The database was left unchanged at version {}",
+                version, description, error, current_version
+            ),
+        }
+    }
+}
+
+pub fn user_version(conn: &Connection) -> rusqlite::Result<i64> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+/// Apply every migration newer than the database's user_version, returning
+/// the versions applied. A database past LATEST_MIGRATION is refused untouched.
+pub fn run_pending_migrations(conn: &Connection) -> Result<Vec<i64>, MigrationError> {
+    let mut current_version = user_version(conn).map_err(|error| MigrationError::Failed {
+        version: 0,
+        description: "read schema version",
+        current_version: 0,
+        error,
+    })?;
+    if current_version > LATEST_MIGRATION {
+        return Err(MigrationError::Newer { version: current_version });
+    }
+
+    let mut applied = Vec::new();
+    let starting_version = current_version;
+    for migration in MIGRATIONS.iter().filter(|m| m.version > starting_version) {
+        let apply = || -> rusqlite::Result<()> {
+            let tx = conn.unchecked_transaction()?;
+            (migration.apply)(&tx)?;
+            tx.pragma_update(None, "user_version", migration.version)?;
+            tx.commit()
+        };
+        apply().map_err(|error| MigrationError::Failed {
+            version: migration.version,
+            description: migration.description,
+            current_version,
+            error,
+        })?;
+
+        log::info!("Applied database migration {}: {}", migration.version, migration.description);
+        current_version = migration.version;
+        applied.push(migration.version);
+    }
+    Ok(applied)
+}
+
+/// Migration 1: every table in base_schema.rs, plus the columns and backfills
+/// that databases created before numbered migrations may be missing
+fn base_schema(conn: &Connection) -> rusqlite::Result<()> {
+    // Directly logged project hours move into the log table the first time it appears
+    let backfill_hour_logs = !table_exists(conn, "project_hour_logs")?;
+
+    conn.execute_batch(super::base_schema::BASE_SCHEMA)?;
+    add_legacy_columns(conn)?;
+    if backfill_hour_logs {
+        backfill_project_hour_logs(conn)?;
+    }
+    Ok(())
+}
+
+/// Migration 2: schedules.updated_by. Databases that got the column before
+/// migrations were numbered already have it.
+fn add_schedule_updated_by(conn: &Connection) -> rusqlite::Result<()> {
+    add_column_if_missing(conn, "schedules", "updated_by", "INTEGER REFERENCES users(id) ON DELETE SET NULL")
+}
+
+/// Migration 3: demo_records, so clearing demo data removes only the rows it created
+fn add_demo_records(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS demo_records (
+             table_name TEXT NOT NULL,
+             record_id INTEGER NOT NULL,
+             created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+             PRIMARY KEY (table_name, record_id)
+         );",
+    )
+}
+
+/// Migration 4: user_notification_settings.desktop_enabled, on by default so
+/// existing preferences keep notifying
+fn add_desktop_notifications(conn: &Connection) -> rusqlite::Result<()> {
+    add_column_if_missing(conn, "user_notification_settings", "desktop_enabled", "INTEGER NOT NULL DEFAULT 1")
+}
+
+/// Migration 5: alert_reads, so marking an alert read only clears it for that
+/// user. alerts.is_read is kept and still counts as read for everyone, which
+/// covers alerts read before this migration.
+fn add_alert_reads(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS alert_reads (
+             alert_id INTEGER NOT NULL REFERENCES alerts(id) ON DELETE CASCADE,
+             user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+             read_at TEXT DEFAULT CURRENT_TIMESTAMP,
+             PRIMARY KEY (alert_id, user_id)
+         );
+         CREATE INDEX IF NOT EXISTS idx_alert_reads_user ON alert_reads(user_id);",
+    )
+}
+
+/// Migration 6: alert_acknowledgements, one per alert, kept when the
+/// acknowledging user is deleted
+fn add_alert_acknowledgements(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS alert_acknowledgements (
+             id INTEGER PRIMARY KEY AUTOINCREMENT,
+             alert_id INTEGER NOT NULL UNIQUE REFERENCES alerts(id) ON DELETE CASCADE,
+             user_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+             comment TEXT NOT NULL,
+             acknowledged_at TEXT DEFAULT CURRENT_TIMESTAMP
+         );",
+    )
+}
+
+/// Columns, indexes and one-off backfills added to existing databases before
+/// migrations were numbered. Each step checks whether it is still needed, so
+/// this is safe on any database that predates user_version.
+fn add_legacy_columns(conn: &Connection) -> rusqlite::Result<()> {
+    // Numeric machine specs are backfilled once, when their columns first appear
+    let backfill_specs = !column_exists(conn, "machines", "power_kw").unwrap_or(true);
+    // The runtime meter starts from the hours already logged on schedules
+    let backfill_runtime = !column_exists(conn, "machines", "runtime_hours").unwrap_or(true);
+    // Free-text locations become managed locations once
+    let backfill_locations = !column_exists(conn, "machines", "location_id").unwrap_or(true);
+
+    // Add new columns to existing tables, skipping any that already exist
+    let columns = [
+        ("machines", "hourly_rate", "REAL DEFAULT 0.0"),
+        ("schedules", "setup_hours", "REAL DEFAULT 0.0"),
+        ("schedules", "sequence_order", "INTEGER DEFAULT 0"),
+        ("schedules", "drawing_number", "TEXT"),
+        ("schedules", "revision", "TEXT"),
+        ("schedules", "material", "TEXT"),
+        ("projects", "actual_completion_date", "TEXT"),
+        ("schedules", "cam_planned_hours", "REAL"),
+        ("schedules", "cam_actual_hours", "REAL"),
+        ("schedules", "cam_buffer_percentage", "REAL"),
+        ("schedules", "job_type", "TEXT"),
+        ("projects", "part_name", "TEXT"),
+        ("schedules", "shift_id", "INTEGER REFERENCES shifts(id) ON DELETE SET NULL"),
+        ("schedules", "source", "TEXT NOT NULL DEFAULT 'manual'"),
+        ("schedules", "tag_id", "INTEGER REFERENCES schedule_tags(id) ON DELETE SET NULL"),
+        ("projects", "external_ref", "TEXT"),
+        ("schedules", "external_ref", "TEXT"),
+        ("machines", "site_id", "INTEGER REFERENCES sites(id) ON DELETE SET NULL"),
+        ("projects", "progress_override", "REAL"),
+        ("projects", "progress_note", "TEXT"),
+        ("machines", "archived_at", "TEXT"),
+        ("machines", "power_kw", "REAL"),
+        ("machines", "weight_kg", "REAL"),
+        ("machines", "max_rpm_value", "INTEGER"),
+        ("machines", "runtime_hours", "REAL DEFAULT 0"),
+        ("machines", "maintenance_interval_hours", "REAL"),
+        ("maintenance", "runtime_hours_at", "REAL"),
+        ("machines", "warranty_expiry", "TEXT"),
+        ("machines", "amc_expiry", "TEXT"),
+        ("machines", "location_id", "INTEGER REFERENCES locations(id) ON DELETE SET NULL"),
+        ("schedules", "task_id", "INTEGER REFERENCES project_tasks(id) ON DELETE SET NULL"),
+        ("projects", "budget_amount", "REAL"),
+        ("projects", "currency", "TEXT"),
+        ("projects", "archived_at", "TEXT"),
+        ("clients", "archived_at", "TEXT"),
+        ("projects", "deadline_alert_date", "TEXT"),
+        ("projects", "deadline_alert_priority", "TEXT"),
+        (
+            "projects",
+            "priority",
+            "TEXT NOT NULL DEFAULT 'normal' CHECK (priority IN ('low', 'normal', 'high', 'critical'))",
+        ),
+        ("users", "locked_until", "TEXT"),
+        ("sessions", "lifetime_minutes", "INTEGER NOT NULL DEFAULT 1440"),
+        ("sessions", "remember_me", "INTEGER NOT NULL DEFAULT 0"),
+        ("sessions", "device_name", "TEXT"),
+        ("users", "last_login_at", "TEXT"),
+        ("users", "totp_secret", "TEXT"),
+        ("users", "totp_pending_secret", "TEXT"),
+    ];
+    for (table, column, definition) in &columns {
+        add_column_if_missing(conn, table, column, definition)?;
+    }
+
+    // Indexes on migrated columns can only be created once the columns exist
+    let indexes = [
+        "CREATE INDEX IF NOT EXISTS idx_projects_external_ref ON projects(external_ref)",
+        "CREATE INDEX IF NOT EXISTS idx_schedules_external_ref ON schedules(external_ref)",
+        "CREATE INDEX IF NOT EXISTS idx_machines_site ON machines(site_id)",
+        "CREATE INDEX IF NOT EXISTS idx_machines_location ON machines(location_id)",
+        "CREATE INDEX IF NOT EXISTS idx_schedules_task ON schedules(task_id)",
+    ];
+    for index in &indexes {
+        conn.execute(index, [])?;
+    }
+
+    if backfill_specs {
+        backfill_machine_specs(conn)?;
+    }
+    if backfill_runtime {
+        conn.execute(
+            "UPDATE machines SET runtime_hours = (
+                 SELECT COALESCE(SUM(actual_hours), 0) FROM schedules WHERE schedules.machine_id = machines.id
+             )",
+            [],
+        )?;
+    }
+    if backfill_locations {
+        link_machine_locations(conn)?;
+    }
+    Ok(())
+}
+
+/// Create a location for each distinct free-text machine location and link
+/// machines without a location_id to it. Names match case-insensitively after
+/// trimming, so "Bay 1" and "bay 1 " share a location.
+pub(crate) fn link_machine_locations(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO locations (name)
+         SELECT DISTINCT TRIM(location) FROM machines
+         WHERE location IS NOT NULL AND TRIM(location) != ''",
+        [],
+    )?;
+    conn.execute(
+        "UPDATE machines SET location_id = (
+             SELECT l.id FROM locations l WHERE l.name = TRIM(machines.location)
+         )
+         WHERE location_id IS NULL AND location IS NOT NULL AND TRIM(location) != ''",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Parse the free-text power, weight and RPM of existing machines into the
+/// numeric columns. Values that cannot be parsed stay NULL.
+fn backfill_machine_specs(conn: &Connection) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare("SELECT id, power_consumption, weight, max_rpm FROM machines")?;
+    let rows: Vec<(i64, Option<String>, Option<String>, Option<String>)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    for (id, power, weight, rpm) in rows {
+        conn.execute(
+            "UPDATE machines SET power_kw = ?1, weight_kg = ?2, max_rpm_value = ?3 WHERE id = ?4",
+            params![
+                power.as_deref().and_then(parse_power_kw),
+                weight.as_deref().and_then(parse_weight_kg),
+                rpm.as_deref().and_then(parse_rpm),
+                id
+            ],
+        )?;
+    }
+    log::info!("Backfilled numeric specs for existing machines");
+    Ok(())
+}
+
+/// Record the part of each project's actual hours not explained by its
+/// schedule entries as one "Opening balance" log row, so the cached total
+/// stays equal to schedule hours plus logged hours
+fn backfill_project_hour_logs(conn: &Connection) -> rusqlite::Result<()> {
+    let inserted = conn.execute(
+        "INSERT INTO project_hour_logs (project_id, user_id, hours, date, note)
+         SELECT id, NULL, balance, COALESCE(start_date, date(created_at)), 'Opening balance'
+         FROM (
+             SELECT p.id, p.start_date, p.created_at,
+                    COALESCE(p.actual_hours, 0) - (
+                        SELECT COALESCE(SUM(s.actual_hours), 0) FROM schedules s WHERE s.project_id = p.id
+                    ) as balance
+             FROM projects p
+         )
+         WHERE balance > 0.005",
+        [],
+    )?;
+    if inserted > 0 {
+        log::info!("Added opening hour balances for {} project(s)", inserted);
+    }
+    Ok(())
+}
+
+fn table_exists(conn: &Connection, table: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [table],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|count| count > 0)
+}
+
+fn column_exists(conn: &Connection, table: &str, column: &str) -> rusqlite::Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let names = stmt.query_map([], |row| row.get::<_, String>("name"))?;
+    for name in names {
+        if name? == column {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Add a column via ALTER TABLE unless table_info shows it is already there
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> rusqlite::Result<()> {
+    if !column_exists(conn, table, column)? {
+        conn.execute_batch(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {}",
+            table, column, definition
+        ))?;
+        log::info!("Added column {}.{}", table, column);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn later_columns_come_from_their_own_migration() {
+        let conn = Connection::open_in_memory().unwrap();
+        base_schema(&conn).unwrap();
+        assert!(!column_exists(&conn, "schedules", "updated_by").unwrap());
+
+        conn.pragma_update(None, "user_version", 1).unwrap();
+        assert_eq!(run_pending_migrations(&conn).unwrap(), (2..=LATEST_MIGRATION).collect::<Vec<_>>());
+        assert!(column_exists(&conn, "schedules", "updated_by").unwrap());
+    }
+}
@@ -1,5 +1,9 @@
+mod base_schema;
 pub mod connection;
-pub mod schema;
+pub mod meta;
+pub mod migrations;
 pub mod seed;
+pub mod workspace;
 
-pub use connection::{Database, initialize_database};
+pub use connection::{Database, initialize_database, open_database};
+pub use meta::StartupStatus;
@@ -1,10 +1,65 @@
 use rusqlite::{Connection, Result, params};
 use bcrypt::{hash, DEFAULT_COST};
 
-/// Seed initial data into the database
+use crate::models::{parse_power_kw, parse_rpm, parse_weight_kg};
+use crate::utils::{setting_definitions, PERMISSIONS};
+
+/// Seed a fresh database with the admin account, default settings, shifts and
+/// tags. Sample machines are only added on request, through seed_demo_data.
 pub fn seed_initial_data(conn: &Connection) -> Result<()> {
     seed_users(conn)?;
-    seed_machines(conn)?;
+    seed_settings(conn)?;
+    seed_shifts(conn)?;
+    seed_schedule_tags(conn)?;
+    Ok(())
+}
+
+/// Store the default grant of every permission for each role. Runs at every
+/// startup; existing entries, including ones an Admin changed, are left alone,
+/// so only permissions new to this build are added.
+pub fn seed_role_permissions(conn: &Connection) -> Result<()> {
+    for role in ["Admin", "Operator", "Viewer"] {
+        for permission in PERMISSIONS {
+            conn.execute(
+                "INSERT OR IGNORE INTO role_permissions (role, permission, allowed) VALUES (?1, ?2, ?3)",
+                params![role, permission.key, permission.default_roles.contains(&role)],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Store the default of every editable setting so get_settings shows where
+/// each value came from
+fn seed_settings(conn: &Connection) -> Result<()> {
+    for definition in setting_definitions() {
+        conn.execute(
+            "INSERT OR IGNORE INTO settings (key, value, value_type) VALUES (?1, ?2, ?3)",
+            params![definition.key, definition.default, definition.value_type()],
+        )?;
+    }
+    Ok(())
+}
+
+fn seed_shifts(conn: &Connection) -> Result<()> {
+    let shifts = [("Day", "08:00", "20:00", 12.0), ("Night", "20:00", "08:00", 12.0)];
+    for (name, start_time, end_time, default_hours) in shifts {
+        conn.execute(
+            "INSERT OR IGNORE INTO shifts (name, start_time, end_time, default_hours) VALUES (?1, ?2, ?3, ?4)",
+            params![name, start_time, end_time, default_hours],
+        )?;
+    }
+    Ok(())
+}
+
+fn seed_schedule_tags(conn: &Connection) -> Result<()> {
+    let tags = [("rush", "#ef4444"), ("rework", "#f59e0b"), ("setup", "#3b82f6")];
+    for (name, color) in tags {
+        conn.execute(
+            "INSERT OR IGNORE INTO schedule_tags (name, color) VALUES (?1, ?2)",
+            params![name, color],
+        )?;
+    }
     Ok(())
 }
 
@@ -17,7 +72,10 @@ fn seed_users(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
-fn seed_machines(conn: &Connection) -> Result<()> {
+/// Add the sample machines and their locations, recording every row created
+/// in demo_records so they can be removed again. Machines whose names are
+/// already taken, and locations that already exist, are left as they are.
+pub fn seed_demo_data(conn: &Connection) -> Result<Vec<(&'static str, i64)>> {
     let machines = vec![
         (
             "TAKUMI H12E",
@@ -119,12 +177,38 @@ fn seed_machines(conn: &Connection) -> Result<()> {
         ),
     ];
 
+    let mut created = Vec::new();
     for (name, model, serial, purchase_date, status, location, capacity, power, dims, weight, rpm, axis) in machines {
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO machines (name, model, serial_number, purchase_date, status, location, capacity, power_consumption, dimensions, weight, max_rpm, axis_travel, power_kw, weight_kg, max_rpm_value)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+            params![
+                name, model, serial, purchase_date, status, location, capacity, power, dims, weight, rpm, axis,
+                parse_power_kw(power),
+                parse_weight_kg(weight),
+                parse_rpm(rpm)
+            ],
+        )?;
+        if inserted == 0 {
+            continue;
+        }
+        let machine_id = conn.last_insert_rowid();
+        created.push(("machines", machine_id));
+
+        if conn.execute("INSERT OR IGNORE INTO locations (name) VALUES (?1)", [location])? > 0 {
+            created.push(("locations", conn.last_insert_rowid()));
+        }
         conn.execute(
-            "INSERT INTO machines (name, model, serial_number, purchase_date, status, location, capacity, power_consumption, dimensions, weight, max_rpm, axis_travel)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
-            params![name, model, serial, purchase_date, status, location, capacity, power, dims, weight, rpm, axis],
+            "UPDATE machines SET location_id = (SELECT id FROM locations WHERE name = ?1) WHERE id = ?2",
+            params![location, machine_id],
         )?;
     }
-    Ok(())
+
+    for (table, id) in &created {
+        conn.execute(
+            "INSERT OR IGNORE INTO demo_records (table_name, record_id) VALUES (?1, ?2)",
+            params![table, id],
+        )?;
+    }
+    Ok(created)
 }
@@ -0,0 +1,95 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Database file used when no workspace has been chosen
+pub const DEFAULT_DB_FILE: &str = "vmc_planner.db";
+
+/// Kept in the app data directory rather than the database it points to
+const CONFIG_FILE: &str = "workspace.json";
+
+/// Previously opened databases remembered for quick switching
+const MAX_RECENT: usize = 5;
+
+/// Which database file the app opens, e.g. a production file on a shared
+/// drive or a local sandbox copy
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceConfig {
+    /// None means the default database in the app data directory
+    #[serde(default)]
+    pub database_path: Option<PathBuf>,
+    /// Most recent first, including the current one
+    #[serde(default)]
+    pub recent: Vec<PathBuf>,
+}
+
+impl WorkspaceConfig {
+    /// Read the config, treating a missing or unreadable file as the default workspace
+    pub fn load(app_handle: &AppHandle) -> Self {
+        let path = config_path(app_handle);
+        let Ok(contents) = std::fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&contents).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable workspace config {:?}: {}", path, e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, app_handle: &AppHandle) -> Result<(), String> {
+        let path = config_path(app_handle);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let contents = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(&path, contents).map_err(|e| format!("Failed to save workspace config: {}", e))
+    }
+
+    /// Make `path` the database opened from now on
+    pub fn select(&mut self, path: &Path, default_path: &Path) {
+        self.database_path = (path != default_path).then(|| path.to_path_buf());
+        self.recent.retain(|p| p != path);
+        self.recent.insert(0, path.to_path_buf());
+        self.recent.truncate(MAX_RECENT);
+    }
+}
+
+fn app_data_dir(app_handle: &AppHandle) -> PathBuf {
+    app_handle
+        .path()
+        .app_data_dir()
+        .expect("Failed to get app data directory")
+}
+
+fn config_path(app_handle: &AppHandle) -> PathBuf {
+    app_data_dir(app_handle).join(CONFIG_FILE)
+}
+
+pub fn default_db_path(app_handle: &AppHandle) -> PathBuf {
+    app_data_dir(app_handle).join(DEFAULT_DB_FILE)
+}
+
+/// Whether `path` is inside the app data directory. Only those databases use
+/// WAL: its shared-memory index isn't safe on network filesystems, and a
+/// workspace chosen elsewhere may well be on a shared drive.
+pub fn is_local(app_handle: &AppHandle, path: &Path) -> bool {
+    path.starts_with(app_data_dir(app_handle))
+}
+
+/// Whether the folder holding `path` exists. A chosen location is never
+/// created, so a disconnected network drive isn't replaced by an empty local folder.
+pub fn is_reachable(path: &Path) -> bool {
+    path.parent().is_some_and(Path::is_dir)
+}
+
+/// Tell admins the configured database could not be opened at startup
+pub fn raise_fallback_alert(conn: &Connection, message: &str) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO alerts (alert_type, priority, title, message)
+         VALUES ('warning', 'high', 'Workspace database unavailable', ?1)",
+        params![message],
+    )
+    .map_err(|e| format!("Failed to create alert: {}", e))?;
+    Ok(())
+}
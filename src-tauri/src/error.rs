@@ -0,0 +1,135 @@
+use std::fmt;
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+/// Error returned by every command. It reaches the frontend as
+/// `{ code, message, field? }`: `code` is stable and safe to branch on, and
+/// `message` is the text shown to the user.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    /// No session, or the session or API token is invalid or expired
+    Unauthorized(String),
+    /// Signed in, but not allowed to do this
+    Forbidden(String),
+    /// Password accepted; sign-in needs the user's two-factor code, or the one
+    /// given was wrong. The login screen asks for a code on this.
+    TotpRequired(String),
+    NotFound(String),
+    /// Bad input; `field` names the offending input when known
+    Validation { field: Option<String>, message: String },
+    /// The change clashes with existing data, e.g. a duplicate name
+    Conflict(String),
+    /// Anything else, including failed queries
+    Database(String),
+}
+
+impl AppError {
+    pub fn validation(message: impl Into<String>) -> Self {
+        AppError::Validation { field: None, message: message.into() }
+    }
+
+    pub fn invalid_field(field: &str, message: impl Into<String>) -> Self {
+        AppError::Validation { field: Some(field.to_string()), message: message.into() }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::TotpRequired(_) => "totp_required",
+            AppError::NotFound(_) => "not_found",
+            AppError::Validation { .. } => "validation",
+            AppError::Conflict(_) => "conflict",
+            AppError::Database(_) => "database",
+        }
+    }
+}
+
+/// Matches the plain strings commands returned before this type existed
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Validation { message, .. } => write!(f, "Validation error: {}", message),
+            AppError::Unauthorized(message)
+            | AppError::Forbidden(message)
+            | AppError::TotpRequired(message)
+            | AppError::NotFound(message)
+            | AppError::Conflict(message)
+            | AppError::Database(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        let field = match self {
+            AppError::Validation { field, .. } => field.as_deref(),
+            _ => None,
+        };
+        state.serialize_field("field", &field)?;
+        state.end()
+    }
+}
+
+/// Shown instead of SQLite's own wording when a lock outlasts the busy timeout
+const DATABASE_BUSY: &str = "The database is busy with another change. Please try again.";
+
+/// Helpers shared by commands still return `String`; classify them by the
+/// wording the codebase uses for each kind of failure
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        if let Some(rest) = message.strip_prefix("Validation error: ") {
+            return AppError::validation(rest);
+        }
+        let lower = message.to_lowercase();
+        if message.starts_with("Permission denied") {
+            AppError::Forbidden(message)
+        } else if message.starts_with("Invalid or expired session")
+            || message.starts_with("Session expired")
+            || message.starts_with("Invalid or revoked API token")
+        {
+            AppError::Unauthorized(message)
+        } else if lower.ends_with("not found") {
+            AppError::NotFound(message)
+        } else if lower.contains("already exists") || lower.contains("unique constraint") {
+            AppError::Conflict(message)
+        } else if lower.contains("database is locked") || lower.contains("database is busy") {
+            AppError::Database(DATABASE_BUSY.to_string())
+        } else {
+            AppError::Database(message)
+        }
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::from(message.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(error: rusqlite::Error) -> Self {
+        match error {
+            rusqlite::Error::QueryReturnedNoRows => AppError::NotFound("Record not found".to_string()),
+            rusqlite::Error::SqliteFailure(ref e, _)
+                if matches!(e.code, rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked) =>
+            {
+                AppError::Database(DATABASE_BUSY.to_string())
+            }
+            other => AppError::from(other.to_string()),
+        }
+    }
+}
+
+/// Lets `?` pass an AppError up through helpers that still return `String`
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.to_string()
+    }
+}
@@ -1,41 +1,68 @@
-use std::thread;
-use std::time::Duration;
 use tauri::Manager;
 
 mod commands;
 mod db;
+mod error;
 mod models;
+mod startup;
 mod utils;
 
-use db::initialize_database;
+/// Status syncs, alert checks and cleanup run whenever a database is opened
+pub(crate) fn run_startup_checks(database: &db::Database) {
+    // Follow today's schedule and maintenance before looking for drift
+    match commands::sync_statuses(&database.conn.lock(), None) {
+        Ok(transitions) if !transitions.is_empty() => {
+            log::info!("Synced {} machine status(es) at startup", transitions.len())
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("Machine status sync failed: {}", e),
+    }
+    // Flag machines whose status drifted from maintenance/downtime/schedules
+    if let Err(e) = commands::check_statuses_on_startup(&database.conn.lock()) {
+        log::warn!("Machine status check failed: {}", e);
+    }
+    // Weekly reminder about schedules pointing at inactive operators/completed projects
+    if let Err(e) = commands::check_stale_references_weekly(&database.conn.lock()) {
+        log::warn!("Stale reference check failed: {}", e);
+    }
+    // Machines that have run past their hour-based maintenance interval
+    if let Err(e) = commands::raise_runtime_maintenance_alerts(&database.conn.lock()) {
+        log::warn!("Runtime maintenance check failed: {}", e);
+    }
+    // Project milestones whose due date has passed uncompleted
+    if let Err(e) = commands::raise_overdue_milestone_alerts(&database.conn.lock()) {
+        log::warn!("Milestone check failed: {}", e);
+    }
+    // Active projects overdue or close to their end date and behind
+    if let Err(e) = commands::raise_deadline_alerts(&database.conn.lock()) {
+        log::warn!("Project deadline check failed: {}", e);
+    }
+    // Warranties and AMC contracts running out within the alert window
+    if let Err(e) = commands::raise_contract_expiry_alerts(&database.conn.lock()) {
+        log::warn!("Contract expiry check failed: {}", e);
+    }
+    // Sessions that expired long ago
+    match utils::purge_expired_sessions(&database.conn.lock()) {
+        Ok(cleanup) if cleanup.deleted > 0 => {
+            log::info!("Deleted {} expired session(s) at startup", cleanup.deleted)
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("Session cleanup failed: {}", e),
+    }
+}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        .plugin(tauri_plugin_notification::init())
         .setup(|app| {
-            // Initialize database with tables and seed data
-            let database = initialize_database(&app.handle())
-                .expect("Failed to initialize database");
-
-            // Manage database state
-            app.manage(database);
-
-            // Fetch both windows safely
-            let splashscreen_window = app.get_webview_window("splashscreen").unwrap();
-            let main_window = app.get_webview_window("main").unwrap();
-
-            // Hide main window completely until splash closes
-            main_window.hide().unwrap();
-
-            // Wait 2.8s (enough for the splash animation)
-            thread::spawn(move || {
-                thread::sleep(Duration::from_millis(2800));
+            // Track long-running operations for progress and cancellation
+            app.manage(utils::OperationRegistry::new());
+            // Session whose settings decide which alerts become desktop notifications
+            app.manage(commands::DesktopNotifier::default());
 
-                // Close splash and open main
-                splashscreen_window.close().unwrap();
-                main_window.show().unwrap();
-                main_window.set_focus().unwrap();
-            });
+            // Open the database in the background behind the splash screen
+            startup::start(app.handle());
 
             Ok(())
         })
@@ -46,45 +73,102 @@ pub fn run() {
             commands::get_current_user,
             commands::cmd_change_password,
             commands::validate_token,
+            commands::get_my_sessions,
+            commands::get_user_sessions,
+            commands::revoke_session,
+            commands::revoke_other_sessions,
+            commands::cleanup_sessions,
+            commands::create_api_token,
+            commands::get_api_tokens,
+            commands::revoke_api_token,
+            commands::enable_totp,
+            commands::confirm_totp,
+            commands::disable_totp,
             // User commands
             commands::get_users,
+            commands::get_assignable_users,
             commands::get_user,
             commands::create_user,
             commands::update_user,
             commands::delete_user,
             commands::reset_user_password,
+            commands::unlock_user,
+            commands::get_login_history,
+            commands::get_permissions,
+            commands::set_permission,
+            commands::get_my_permissions,
+            commands::import_users,
+            commands::bulk_reset_passwords,
             // Client commands
             commands::get_clients,
+            commands::get_clients_with_stats,
             commands::get_client,
+            commands::get_client_activity,
             commands::create_client,
             commands::update_client,
             commands::delete_client,
+            commands::archive_client,
+            commands::unarchive_client,
+            commands::get_client_contacts,
+            commands::create_client_contact,
+            commands::update_client_contact,
+            commands::delete_client_contact,
             // Machine commands
             commands::get_machines,
+            commands::get_machines_with_stats,
+            commands::get_machines_sorted,
+            commands::query_machines,
+            commands::set_machine_runtime_hours,
             commands::get_machine,
             commands::create_machine,
+            commands::clone_machine,
             commands::update_machine,
             commands::update_machine_status,
+            commands::reconcile_machine_statuses,
+            commands::sync_machine_statuses,
             commands::delete_machine,
+            commands::archive_machine,
+            commands::unarchive_machine,
             commands::get_machine_history,
+            commands::get_fleet_overview,
+            commands::export_fleet_overview_csv,
             // Project commands
             commands::get_projects,
+            commands::query_projects,
             commands::get_project,
+            commands::get_project_machine_breakdown,
             commands::create_project,
             commands::update_project,
             commands::delete_project,
+            commands::clone_project,
+            commands::archive_project,
+            commands::unarchive_project,
             commands::assign_machines_to_project,
             commands::assign_team_to_project,
             commands::log_project_hours,
+            commands::get_project_hour_logs,
+            commands::correct_project_hour_log,
+            commands::delete_project_hour_log,
+            commands::get_project_notes,
+            commands::add_project_note,
+            commands::delete_project_note,
+            commands::recalculate_project_hours,
+            commands::set_project_progress,
             // Schedule commands
             commands::get_weekly_schedule,
+            commands::get_operator_week,
             commands::get_schedule,
             commands::create_schedule,
             commands::update_schedule,
             commands::log_actual_hours,
+            commands::update_schedules_status,
+            commands::reassign_operator,
+            commands::move_schedule,
             commands::delete_schedule,
             commands::get_schedules_by_date_range,
             commands::copy_week_schedule,
+            commands::diff_weeks,
+            commands::copy_day_schedule,
             // Maintenance commands
             commands::get_all_maintenance,
             commands::get_machine_maintenance,
@@ -100,12 +184,17 @@ pub fn run() {
             commands::create_alert,
             commands::mark_alert_read,
             commands::mark_all_alerts_read,
+            commands::acknowledge_alert,
+            commands::get_unacknowledged_critical_alerts,
             commands::dismiss_alert,
             commands::clear_read_alerts,
             commands::get_alert_stats,
             commands::get_unread_alert_count,
             // Dashboard commands
             commands::get_dashboard_stats,
+            commands::get_available_widgets,
+            commands::get_my_dashboard_layout,
+            commands::save_my_dashboard_layout,
             commands::get_machine_utilization,
             commands::get_project_progress,
             // Integrity commands (delete impact checking)
@@ -113,10 +202,13 @@ pub fn run() {
             commands::check_project_delete_impact,
             commands::check_client_delete_impact,
             commands::check_user_delete_impact,
+            commands::run_integrity_check,
+            commands::fix_integrity_issues,
             // Audit commands
             commands::get_audit_logs,
             commands::get_audit_stats,
             commands::get_audit_filter_options,
+            commands::restore_from_audit,
             // Downtime commands
             commands::get_downtime_log,
             commands::create_downtime,
@@ -133,6 +225,116 @@ pub fn run() {
             commands::create_shift_log,
             // Operator schedule command
             commands::get_operator_schedule,
+            // Report commands
+            commands::get_delivery_performance,
+            commands::get_hours_by_source,
+            commands::get_overtime_report,
+            commands::get_weekly_kpi_comparison,
+            commands::get_job_cost_report,
+            // Conflict commands
+            commands::get_week_conflicts,
+            commands::find_stale_references,
+            commands::clear_stale_reference,
+            commands::lock_week,
+            commands::unlock_week,
+            // Timeline commands
+            commands::get_timeline,
+            // Operation commands
+            commands::get_operation_status,
+            commands::cancel_operation,
+            // Holiday commands
+            commands::get_holidays,
+            commands::create_holiday,
+            commands::update_holiday,
+            commands::delete_holiday,
+            // System commands
+            commands::get_startup_status,
+            commands::get_app_windows_info,
+            commands::get_database_info,
+            commands::get_database_health,
+            commands::optimize_database,
+            commands::get_settings,
+            commands::update_setting,
+            commands::switch_workspace,
+            commands::export_all_data,
+            commands::import_all_data,
+            commands::load_demo_data,
+            commands::clear_demo_data,
+            // Notification settings commands
+            commands::get_notification_settings,
+            commands::update_notification_settings,
+            commands::test_notification,
+            // Shift commands
+            commands::get_shifts,
+            commands::create_shift,
+            commands::update_shift,
+            commands::delete_shift,
+            commands::get_schedule_tags,
+            commands::create_schedule_tag,
+            commands::update_schedule_tag,
+            commands::delete_schedule_tag,
+            // Material commands
+            commands::get_project_materials,
+            commands::create_project_material,
+            commands::update_project_material,
+            commands::delete_project_material,
+            commands::get_material_risk,
+            // Mobile commands
+            commands::get_mobile_summary,
+            commands::quick_log_hours,
+            commands::quick_update_status,
+            // Search commands
+            commands::find_by_external_ref,
+            commands::search_schedules,
+            // Site commands
+            commands::get_sites,
+            commands::create_site,
+            commands::update_site,
+            commands::delete_site,
+            // Status transition commands
+            commands::get_allowed_transitions,
+            // Machine metrics commands
+            commands::get_machine_downtime,
+            commands::get_machine_oee,
+            // Runtime maintenance commands
+            commands::check_runtime_maintenance_due,
+            // Machine document commands
+            commands::add_machine_document,
+            commands::list_machine_documents,
+            commands::get_machine_document,
+            commands::delete_machine_document,
+            // Contract expiry commands
+            commands::get_expiring_contracts,
+            // Machine QR commands
+            commands::get_machine_qr_payload,
+            commands::resolve_machine_qr,
+            // Location commands
+            commands::get_locations,
+            commands::create_location,
+            commands::update_location,
+            commands::delete_location,
+            // Machine import commands
+            commands::import_machines_csv,
+            commands::import_clients_csv,
+            // Project milestone commands
+            commands::get_project_milestones,
+            commands::create_project_milestone,
+            commands::update_project_milestone,
+            commands::delete_project_milestone,
+            commands::check_overdue_milestones,
+            commands::check_project_deadlines,
+            // Project task commands
+            commands::get_project_tasks,
+            commands::create_project_task,
+            commands::update_project_task,
+            commands::delete_project_task,
+            // Project cost commands
+            commands::get_project_costs,
+            commands::create_project_cost,
+            commands::update_project_cost,
+            commands::delete_project_cost,
+            commands::set_project_budget,
+            commands::get_project_financials,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -38,6 +38,27 @@ pub struct AlertWithDetails {
     pub alert: Alert,
     pub machine_name: Option<String>,
     pub project_name: Option<String>,
+    pub cue: Option<AlertCue>,
+    pub acknowledgement: Option<AlertAcknowledgement>,
+}
+
+/// Who acknowledged an alert, when, and what they did about it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertAcknowledgement {
+    pub id: i64,
+    pub alert_id: i64,
+    pub user_id: Option<i64>,
+    pub user_name: Option<String>,
+    pub comment: String,
+    pub acknowledged_at: String,
+}
+
+/// How the UI should present an alert, resolved from the user's notification settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertCue {
+    pub notify: bool,
+    pub sound: bool,
+    pub in_quiet_hours: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,4 +67,6 @@ pub struct DashboardStats {
     pub project_status: Vec<(String, i32)>,
     pub top_machines_week: Vec<(String, f64)>,
     pub weekly_trend: Vec<(String, f64, f64)>,
+    /// Sample data from load_demo_data is present; the dashboard shows a banner
+    pub demo_data_loaded: bool,
 }
@@ -9,6 +9,8 @@ pub struct Client {
     pub contact_phone: Option<String>,
     pub address: Option<String>,
     pub notes: Option<String>,
+    /// Set while the client is archived; its projects keep pointing at it
+    pub archived_at: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -22,12 +24,76 @@ impl Client {
             contact_phone: row.get("contact_phone")?,
             address: row.get("address")?,
             notes: row.get("notes")?,
+            archived_at: row.get("archived_at").ok().flatten(),
             created_at: row.get("created_at")?,
             updated_at: row.get("updated_at")?,
         })
     }
 }
 
+/// A client with its contact people, primary contact first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientWithContacts {
+    #[serde(flatten)]
+    pub client: Client,
+    pub contacts: Vec<ClientContact>,
+}
+
+/// A client with totals over its projects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientWithStats {
+    #[serde(flatten)]
+    pub client: Client,
+    pub project_count: i64,
+    /// Projects in planning or active
+    pub active_projects: i64,
+    pub planned_hours: f64,
+    pub actual_hours: f64,
+    pub last_project_end_date: Option<String>,
+    /// Projects judged for delivery: completed ones with both dates, and open
+    /// ones already past their end date
+    pub evaluated_projects: i64,
+    pub late_projects: i64,
+    /// None when no project has been evaluated yet
+    pub on_time_percentage: Option<f64>,
+}
+
+/// One entry in a client's activity feed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientActivityItem {
+    /// project_created, project_status, schedule or alert
+    pub item_type: String,
+    /// Timestamp, or for schedule entries the scheduled date and start time
+    pub occurred_at: String,
+    /// Id of the project, audit entry, schedule entry or alert
+    pub item_id: i64,
+    pub project_id: i64,
+    pub project_name: String,
+    pub machine_id: Option<i64>,
+    pub machine_name: Option<String>,
+    /// Project or schedule status, or alert priority
+    pub status: Option<String>,
+    pub title: String,
+    pub detail: Option<String>,
+}
+
+impl ClientActivityItem {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            item_type: row.get("item_type")?,
+            occurred_at: row.get("occurred_at")?,
+            item_id: row.get("item_id")?,
+            project_id: row.get("project_id")?,
+            project_name: row.get("project_name")?,
+            machine_id: row.get("machine_id")?,
+            machine_name: row.get("machine_name")?,
+            status: row.get("status")?,
+            title: row.get("title")?,
+            detail: row.get("detail")?,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateClientInput {
     pub name: String,
@@ -45,3 +111,53 @@ pub struct UpdateClientInput {
     pub address: Option<String>,
     pub notes: Option<String>,
 }
+
+/// A person at a client, such as a buyer or quality contact
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientContact {
+    pub id: i64,
+    pub client_id: i64,
+    pub name: String,
+    pub role: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    /// At most one contact per client is primary
+    pub is_primary: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl ClientContact {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            client_id: row.get("client_id")?,
+            name: row.get("name")?,
+            role: row.get("role")?,
+            email: row.get("email")?,
+            phone: row.get("phone")?,
+            is_primary: row.get("is_primary")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateContactInput {
+    pub name: String,
+    pub role: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    /// Making a contact primary demotes the client's current primary contact
+    pub is_primary: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateContactInput {
+    pub name: Option<String>,
+    pub role: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub is_primary: Option<bool>,
+}
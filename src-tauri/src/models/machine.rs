@@ -10,6 +10,8 @@ pub struct Machine {
     pub purchase_date: Option<String>,
     pub status: String,
     pub location: Option<String>,
+    /// Managed location; `location` mirrors its name
+    pub location_id: Option<i64>,
     pub capacity: Option<String>,
     pub power_consumption: Option<String>,
     pub dimensions: Option<String>,
@@ -17,6 +19,21 @@ pub struct Machine {
     pub max_rpm: Option<String>,
     pub axis_travel: Option<String>,
     pub hourly_rate: f64,
+    /// Numeric specs parsed from or entered alongside the text fields
+    pub power_kw: Option<f64>,
+    pub weight_kg: Option<f64>,
+    pub max_rpm_value: Option<i64>,
+    /// Spindle hour meter, advanced whenever actual hours are logged
+    pub runtime_hours: f64,
+    /// Runtime between preventive maintenance; None when maintenance is date based
+    pub maintenance_interval_hours: Option<f64>,
+    /// None for machines at the implicit local site
+    pub site_id: Option<i64>,
+    /// Warranty and annual maintenance contract end dates (YYYY-MM-DD)
+    pub warranty_expiry: Option<String>,
+    pub amc_expiry: Option<String>,
+    /// Set when the machine is retired; its history is kept
+    pub archived_at: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -31,6 +48,7 @@ impl Machine {
             purchase_date: row.get("purchase_date")?,
             status: row.get("status")?,
             location: row.get("location")?,
+            location_id: row.get("location_id").ok().flatten(),
             capacity: row.get("capacity")?,
             power_consumption: row.get("power_consumption")?,
             dimensions: row.get("dimensions")?,
@@ -38,12 +56,78 @@ impl Machine {
             max_rpm: row.get("max_rpm")?,
             axis_travel: row.get("axis_travel")?,
             hourly_rate: row.get("hourly_rate").unwrap_or(0.0),
+            power_kw: row.get("power_kw").ok().flatten(),
+            weight_kg: row.get("weight_kg").ok().flatten(),
+            max_rpm_value: row.get("max_rpm_value").ok().flatten(),
+            runtime_hours: row.get::<_, Option<f64>>("runtime_hours").ok().flatten().unwrap_or(0.0),
+            maintenance_interval_hours: row.get("maintenance_interval_hours").ok().flatten(),
+            site_id: row.get("site_id").ok().flatten(),
+            warranty_expiry: row.get("warranty_expiry").ok().flatten(),
+            amc_expiry: row.get("amc_expiry").ok().flatten(),
+            archived_at: row.get("archived_at").ok().flatten(),
             created_at: row.get("created_at")?,
             updated_at: row.get("updated_at")?,
         })
     }
 }
 
+/// Split a spec like "12,000 kg" into its number and lowercase unit
+fn split_spec(text: &str) -> Option<(f64, String)> {
+    let text = text.trim().replace(',', "");
+    let end = text
+        .char_indices()
+        .find(|(_, c)| !(c.is_ascii_digit() || *c == '.'))
+        .map(|(i, _)| i)
+        .unwrap_or(text.len());
+    let value: f64 = text[..end].parse().ok()?;
+    Some((value, text[end..].trim().to_lowercase()))
+}
+
+/// Best-effort power in kW from text such as "30 kW", "30000 W" or "40 HP"
+pub fn parse_power_kw(text: &str) -> Option<f64> {
+    let (value, unit) = split_spec(text)?;
+    match unit.as_str() {
+        "" | "kw" => Some(value),
+        "w" => Some(value / 1000.0),
+        "hp" => Some(value * 0.7457),
+        _ => None,
+    }
+}
+
+/// Best-effort weight in kg from text such as "12,000 kg", "12 t" or "2500 lbs"
+pub fn parse_weight_kg(text: &str) -> Option<f64> {
+    let (value, unit) = split_spec(text)?;
+    match unit.as_str() {
+        "" | "kg" | "kgs" => Some(value),
+        "t" | "ton" | "tons" | "tonne" | "tonnes" => Some(value * 1000.0),
+        "lb" | "lbs" => Some(value * 0.4536),
+        _ => None,
+    }
+}
+
+/// Best-effort spindle speed from text such as "12,000 rpm" or "12k"
+pub fn parse_rpm(text: &str) -> Option<i64> {
+    let (value, unit) = split_spec(text)?;
+    match unit.as_str() {
+        "" | "rpm" => Some(value.round() as i64),
+        "k" | "k rpm" | "krpm" => Some((value * 1000.0).round() as i64),
+        _ => None,
+    }
+}
+
+/// Reject negative or non-finite numeric specs
+pub fn validate_numeric_specs(power_kw: Option<f64>, weight_kg: Option<f64>, max_rpm: Option<i64>) -> Result<(), String> {
+    for (field, value) in [("power_kw", power_kw), ("weight_kg", weight_kg)] {
+        if value.is_some_and(|v| !v.is_finite() || v < 0.0) {
+            return Err(format!("Validation error: {} must be a non-negative number", field));
+        }
+    }
+    if max_rpm.is_some_and(|v| v < 0) {
+        return Err("Validation error: max_rpm_value must not be negative".to_string());
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MachineSpecs {
     pub power_consumption: Option<String>,
@@ -68,6 +152,16 @@ pub struct CreateMachineInput {
     pub max_rpm: Option<String>,
     pub axis_travel: Option<String>,
     pub hourly_rate: Option<f64>,
+    /// Parsed from the matching text field when omitted
+    pub power_kw: Option<f64>,
+    pub weight_kg: Option<f64>,
+    pub max_rpm_value: Option<i64>,
+    pub maintenance_interval_hours: Option<f64>,
+    pub site_id: Option<i64>,
+    pub warranty_expiry: Option<String>,
+    pub amc_expiry: Option<String>,
+    /// Takes precedence over the free-text location
+    pub location_id: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -85,6 +179,52 @@ pub struct UpdateMachineInput {
     pub max_rpm: Option<String>,
     pub axis_travel: Option<String>,
     pub hourly_rate: Option<f64>,
+    pub power_kw: Option<f64>,
+    pub weight_kg: Option<f64>,
+    pub max_rpm_value: Option<i64>,
+    pub maintenance_interval_hours: Option<f64>,
+    /// Go back to date-based maintenance
+    pub clear_maintenance_interval: Option<bool>,
+    pub site_id: Option<i64>,
+    /// Move the machine back to the implicit local site
+    pub clear_site: Option<bool>,
+    pub warranty_expiry: Option<String>,
+    pub clear_warranty_expiry: Option<bool>,
+    pub amc_expiry: Option<String>,
+    pub clear_amc_expiry: Option<bool>,
+    pub location_id: Option<i64>,
+    /// Unlink the managed location, keeping the free text
+    pub clear_location: Option<bool>,
+}
+
+/// Filters for query_machines; everything is optional
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MachineFilter {
+    pub status: Option<String>,
+    pub capacity: Option<String>,
+    /// Substring of the location
+    pub location: Option<String>,
+    pub location_id: Option<i64>,
+    /// Substring of the name or model
+    pub search: Option<String>,
+    pub include_archived: Option<bool>,
+    /// Same fields as get_machines_sorted; defaults to name
+    pub sort_by: Option<String>,
+    /// "asc" (default) or "desc"
+    pub order: Option<String>,
+    /// Also count each machine's schedule entries this week
+    pub include_week_counts: Option<bool>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MachineListItem {
+    #[serde(flatten)]
+    pub machine: Machine,
+    /// Only filled when include_week_counts is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedules_this_week: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -96,3 +236,27 @@ pub struct MachineWithStats {
     pub actual_hours_this_week: f64,
     pub maintenance_due: Option<String>,
 }
+
+/// Lifetime maintenance, downtime and runtime figures for one machine.
+///
+/// Figures with no underlying data are None rather than zero.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FleetMachineSummary {
+    pub machine_id: i64,
+    pub machine_name: String,
+    pub model: String,
+    pub status: String,
+    pub purchase_date: Option<String>,
+    pub age_years: Option<f64>,
+    pub completed_maintenance_count: i64,
+    pub maintenance_cost: Option<f64>,
+    pub corrective_count: i64,
+    pub preventive_count: i64,
+    /// Corrective share of corrective + preventive maintenance
+    pub corrective_ratio: Option<f64>,
+    /// Hours of closed downtime
+    pub downtime_hours: Option<f64>,
+    /// Actual hours logged on schedule entries
+    pub runtime_hours: Option<f64>,
+    pub cost_per_runtime_hour: Option<f64>,
+}
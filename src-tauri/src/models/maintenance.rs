@@ -12,6 +12,8 @@ pub struct Maintenance {
     pub cost: Option<f64>,
     pub status: String,
     pub notes: Option<String>,
+    /// Machine runtime hours when the work was completed
+    pub runtime_hours_at: Option<f64>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -28,6 +30,7 @@ impl Maintenance {
             cost: row.get("cost")?,
             status: row.get("status")?,
             notes: row.get("notes")?,
+            runtime_hours_at: row.get("runtime_hours_at").ok().flatten(),
             created_at: row.get("created_at")?,
             updated_at: row.get("updated_at")?,
         })
@@ -10,16 +10,57 @@ pub struct Project {
     pub start_date: Option<String>,
     pub end_date: Option<String>,
     pub status: String,
+    /// low, normal, high or critical; decides which project wins a contended machine
+    pub priority: String,
     pub planned_hours: f64,
     pub actual_hours: f64,
     pub actual_completion_date: Option<String>,
     pub part_name: Option<String>,
+    pub external_ref: Option<String>,
+    /// Manual percent complete (0-100) that takes precedence over hours-based progress
+    pub progress_override: Option<f64>,
+    pub progress_note: Option<String>,
+    /// Set when the project is archived: kept for reporting, hidden from lists
+    pub archived_at: Option<String>,
     pub created_by: Option<i64>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+pub const PROJECT_PRIORITIES: [&str; 4] = ["low", "normal", "high", "critical"];
+
+/// Orders projects (aliased `p`) most urgent first
+pub const PROJECT_PRIORITY_RANK: &str =
+    "CASE p.priority WHEN 'critical' THEN 0 WHEN 'high' THEN 1 WHEN 'normal' THEN 2 ELSE 3 END";
+
+pub fn validate_project_priority(priority: &str) -> Result<(), String> {
+    if PROJECT_PRIORITIES.contains(&priority) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Validation error: invalid priority '{}'. Must be one of: {}",
+            priority,
+            PROJECT_PRIORITIES.join(", ")
+        ))
+    }
+}
+
+/// Hours-based progress, capped at 100%
+pub fn computed_progress(planned_hours: f64, actual_hours: f64) -> f64 {
+    if planned_hours > 0.0 {
+        (actual_hours / planned_hours * 100.0).min(100.0)
+    } else {
+        0.0
+    }
+}
+
 impl Project {
+    /// Progress shown to users: the manual override when set, otherwise hours-based
+    pub fn effective_progress(&self) -> f64 {
+        self.progress_override
+            .unwrap_or_else(|| computed_progress(self.planned_hours, self.actual_hours))
+    }
+
     pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
         Ok(Self {
             id: row.get("id")?,
@@ -29,10 +70,19 @@ impl Project {
             start_date: row.get("start_date")?,
             end_date: row.get("end_date")?,
             status: row.get("status")?,
+            priority: row
+                .get::<_, Option<String>>("priority")
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| "normal".to_string()),
             planned_hours: row.get("planned_hours")?,
             actual_hours: row.get("actual_hours")?,
             actual_completion_date: row.get("actual_completion_date").ok().flatten(),
             part_name: row.get("part_name").ok().flatten(),
+            external_ref: row.get("external_ref").ok().flatten(),
+            progress_override: row.get("progress_override").ok().flatten(),
+            progress_note: row.get("progress_note").ok().flatten(),
+            archived_at: row.get("archived_at").ok().flatten(),
             created_by: row.get("created_by")?,
             created_at: row.get("created_at")?,
             updated_at: row.get("updated_at")?,
@@ -46,8 +96,292 @@ pub struct ProjectWithDetails {
     pub project: Project,
     pub client_name: Option<String>,
     pub assigned_machines: Vec<i64>,
+    /// Ids only; `team` carries the same members with their roles
     pub team_members: Vec<i64>,
+    pub team: Vec<ProjectTeamMember>,
+    /// Hours-based progress
     pub progress_percentage: f64,
+    /// Override when set, otherwise `progress_percentage`
+    pub effective_progress: f64,
+    pub milestones: MilestoneSummary,
+    pub task_hours: TaskHoursSummary,
+    pub latest_note: Option<ProjectNote>,
+    pub note_count: i64,
+}
+
+pub const TEAM_ROLES: [&str; 2] = ["lead", "member"];
+
+/// A user on a project's team and their role there
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectTeamMember {
+    pub user_id: i64,
+    /// lead or member
+    pub role: String,
+}
+
+/// One member to put on a project's team
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamMemberInput {
+    pub user_id: i64,
+    /// lead or member. When omitted an existing member keeps their role and a
+    /// new one joins as member.
+    pub role: Option<String>,
+}
+
+/// A project's schedule hours on one machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectMachineHours {
+    pub machine_id: i64,
+    pub machine_name: String,
+    /// Whether the machine is in the project's assigned machines
+    pub assigned: bool,
+    pub schedule_count: i64,
+    pub planned_hours: f64,
+    pub actual_hours: f64,
+    /// Actual over planned hours, capped at 100%; 0 with nothing planned
+    pub efficiency_percentage: f64,
+}
+
+pub const TASK_STATUSES: [&str; 4] = ["planning", "active", "completed", "on-hold"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectTask {
+    pub id: i64,
+    pub project_id: i64,
+    pub name: String,
+    pub status: String,
+    pub planned_hours: f64,
+    /// Rolled up from the actual hours of schedule entries linked to the task
+    pub actual_hours: f64,
+    pub sort_order: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl ProjectTask {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            project_id: row.get("project_id")?,
+            name: row.get("name")?,
+            status: row.get("status")?,
+            planned_hours: row.get::<_, Option<f64>>("planned_hours")?.unwrap_or(0.0),
+            actual_hours: row.get::<_, Option<f64>>("actual_hours")?.unwrap_or(0.0),
+            sort_order: row.get("sort_order")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+/// Hours across a project's tasks
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskHoursSummary {
+    pub task_count: i64,
+    pub completed_tasks: i64,
+    pub planned_hours: f64,
+    pub actual_hours: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTaskInput {
+    pub name: String,
+    /// Defaults to planning
+    pub status: Option<String>,
+    pub planned_hours: Option<f64>,
+    pub sort_order: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateTaskInput {
+    pub name: Option<String>,
+    pub status: Option<String>,
+    pub planned_hours: Option<f64>,
+    pub sort_order: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectMilestone {
+    pub id: i64,
+    pub project_id: i64,
+    pub title: String,
+    pub due_date: Option<String>,
+    pub completed_at: Option<String>,
+    pub sort_order: i64,
+    pub notes: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl ProjectMilestone {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            project_id: row.get("project_id")?,
+            title: row.get("title")?,
+            due_date: row.get("due_date")?,
+            completed_at: row.get("completed_at")?,
+            sort_order: row.get("sort_order")?,
+            notes: row.get("notes")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+/// Milestone counts shown with a project
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MilestoneSummary {
+    pub total: i64,
+    pub completed: i64,
+    /// Open milestones whose due date has passed
+    pub overdue: i64,
+    /// Earliest open milestone with a due date
+    pub next_due: Option<ProjectMilestone>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateMilestoneInput {
+    pub title: String,
+    pub due_date: Option<String>,
+    /// Defaults to after the project's last milestone
+    pub sort_order: Option<i64>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateMilestoneInput {
+    pub title: Option<String>,
+    pub due_date: Option<String>,
+    pub clear_due_date: Option<bool>,
+    /// true stamps completion now, false reopens the milestone
+    pub completed: Option<bool>,
+    pub sort_order: Option<i64>,
+    pub notes: Option<String>,
+}
+
+/// Hours logged directly against a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectHourLog {
+    pub id: i64,
+    pub project_id: i64,
+    /// None for the opening balance carried over from before hours were logged individually
+    pub user_id: Option<i64>,
+    pub user_name: Option<String>,
+    pub hours: f64,
+    pub date: String,
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+impl ProjectHourLog {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            project_id: row.get("project_id")?,
+            user_id: row.get("user_id")?,
+            user_name: row.get("user_name").ok().flatten(),
+            hours: row.get("hours")?,
+            date: row.get("date")?,
+            note: row.get("note")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+}
+
+/// A dated comment in a project's running commentary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectNote {
+    pub id: i64,
+    pub project_id: i64,
+    /// None once the author's account has been deleted
+    pub user_id: Option<i64>,
+    pub user_name: Option<String>,
+    pub note: String,
+    pub created_at: String,
+}
+
+impl ProjectNote {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            project_id: row.get("project_id")?,
+            user_id: row.get("user_id")?,
+            user_name: row.get("user_name").ok().flatten(),
+            note: row.get("note")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+}
+
+pub const COST_CATEGORIES: [&str; 4] = ["tooling", "material", "outsourcing", "other"];
+
+/// An ad-hoc expense on a project, such as tooling or outsourced work
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectCost {
+    pub id: i64,
+    pub project_id: i64,
+    pub category: String,
+    pub description: String,
+    pub amount: f64,
+    pub cost_date: String,
+    pub created_by: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl ProjectCost {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            project_id: row.get("project_id")?,
+            category: row.get("category")?,
+            description: row.get("description")?,
+            amount: row.get("amount")?,
+            cost_date: row.get("cost_date")?,
+            created_by: row.get("created_by")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCostInput {
+    pub category: String,
+    pub description: String,
+    pub amount: f64,
+    /// Defaults to today
+    pub cost_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateCostInput {
+    pub category: Option<String>,
+    pub description: Option<String>,
+    pub amount: Option<f64>,
+    pub cost_date: Option<String>,
+}
+
+/// Filters for query_projects; everything is optional
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectFilter {
+    pub status: Option<String>,
+    pub priority: Option<String>,
+    pub client_id: Option<i64>,
+    /// Substring of the name
+    pub search: Option<String>,
+    /// Projects whose start-to-end span overlaps this range; open ends match
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+    pub include_archived: Option<bool>,
+    /// name, status, priority, start_date, end_date or created_at (default).
+    /// Priority sorts most urgent first, then by end date.
+    pub sort_by: Option<String>,
+    /// "asc" or "desc"; defaults to desc for created_at, asc otherwise
+    pub order: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,8 +392,12 @@ pub struct CreateProjectInput {
     pub start_date: Option<String>,
     pub end_date: Option<String>,
     pub status: String,
+    /// Defaults to normal
+    pub priority: Option<String>,
     pub planned_hours: f64,
     pub part_name: Option<String>,
+    /// ERP order number or similar; not unique
+    pub external_ref: Option<String>,
     pub assigned_machines: Option<Vec<i64>>,
     pub team_members: Option<Vec<i64>>,
 }
@@ -72,10 +410,13 @@ pub struct UpdateProjectInput {
     pub start_date: Option<String>,
     pub end_date: Option<String>,
     pub status: Option<String>,
+    /// Admin only
+    pub priority: Option<String>,
     pub planned_hours: Option<f64>,
     pub actual_hours: Option<f64>,
     pub actual_completion_date: Option<String>,
     pub part_name: Option<String>,
+    pub external_ref: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
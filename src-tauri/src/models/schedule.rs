@@ -1,6 +1,34 @@
 use serde::{Deserialize, Serialize};
 use rusqlite::Row;
 
+use crate::utils::HoursValue;
+
+/// Where a schedule entry came from
+pub const SOURCE_MANUAL: &str = "manual";
+pub const SOURCE_COPY: &str = "copy";
+pub const SOURCE_IMPORT: &str = "import";
+pub const SOURCE_AUTO: &str = "auto";
+pub const SOURCE_MAINTENANCE: &str = "maintenance";
+pub const SOURCE_API: &str = "api";
+
+pub const SCHEDULE_SOURCES: [&str; 6] = [
+    SOURCE_MANUAL,
+    SOURCE_COPY,
+    SOURCE_IMPORT,
+    SOURCE_AUTO,
+    SOURCE_MAINTENANCE,
+    SOURCE_API,
+];
+
+/// Check a schedule source against the whitelist
+pub fn validate_schedule_source(source: &str) -> Result<(), String> {
+    if SCHEDULE_SOURCES.contains(&source) {
+        Ok(())
+    } else {
+        Err(format!("Invalid schedule source '{}'", source))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Schedule {
     pub id: i64,
@@ -24,6 +52,13 @@ pub struct Schedule {
     pub cam_actual_hours: Option<f64>,
     pub cam_buffer_percentage: Option<f64>,
     pub job_type: Option<String>,
+    pub updated_by: Option<i64>,
+    pub shift_id: Option<i64>,
+    pub tag_id: Option<i64>,
+    /// Task of the entry's project that its hours count towards
+    pub task_id: Option<i64>,
+    pub external_ref: Option<String>,
+    pub source: String,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -52,6 +87,16 @@ impl Schedule {
             cam_actual_hours: row.get("cam_actual_hours").ok().flatten(),
             cam_buffer_percentage: row.get("cam_buffer_percentage").ok().flatten(),
             job_type: row.get("job_type").ok().flatten(),
+            updated_by: row.get("updated_by").ok().flatten(),
+            shift_id: row.get("shift_id").ok().flatten(),
+            tag_id: row.get("tag_id").ok().flatten(),
+            task_id: row.get("task_id").ok().flatten(),
+            external_ref: row.get("external_ref").ok().flatten(),
+            source: row
+                .get::<_, Option<String>>("source")
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| SOURCE_MANUAL.to_string()),
             created_at: row.get("created_at")?,
             updated_at: row.get("updated_at")?,
         })
@@ -65,6 +110,29 @@ pub struct ScheduleWithDetails {
     pub machine_name: String,
     pub project_name: Option<String>,
     pub operator_name: Option<String>,
+    pub updated_by_username: Option<String>,
+    pub created_by_name: Option<String>,
+    pub tag_name: Option<String>,
+    pub tag_color: Option<String>,
+    /// Project materials still due in after this entry's date; a warning, not an error
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub material_warnings: Vec<LateMaterial>,
+}
+
+impl ScheduleWithDetails {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            schedule: Schedule::from_row(row)?,
+            machine_name: row.get("machine_name")?,
+            project_name: row.get("project_name")?,
+            operator_name: row.get("operator_name")?,
+            updated_by_username: row.get("updated_by_username").ok().flatten(),
+            created_by_name: row.get("created_by_name").ok().flatten(),
+            tag_name: row.get("tag_name").ok().flatten(),
+            tag_color: row.get("tag_color").ok().flatten(),
+            material_warnings: Vec::new(),
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -76,10 +144,11 @@ pub struct CreateScheduleInput {
     pub end_time: Option<String>,
     pub operator_id: Option<i64>,
     pub load_name: Option<String>,
-    pub planned_hours: f64,
+    /// Optional when `shift_id` is set; the shift's default hours are used instead
+    pub planned_hours: Option<HoursValue>,
     pub notes: Option<String>,
     pub status: Option<String>,
-    pub setup_hours: Option<f64>,
+    pub setup_hours: Option<HoursValue>,
     pub sequence_order: Option<i64>,
     pub drawing_number: Option<String>,
     pub revision: Option<String>,
@@ -88,6 +157,14 @@ pub struct CreateScheduleInput {
     pub cam_actual_hours: Option<f64>,
     pub cam_buffer_percentage: Option<f64>,
     pub job_type: Option<String>,
+    pub shift_id: Option<i64>,
+    pub tag_id: Option<i64>,
+    /// Must be a task of `project_id`
+    pub task_id: Option<i64>,
+    /// ERP order number or similar; not unique
+    pub external_ref: Option<String>,
+    /// Defaults to "manual"; integrations pass "api"
+    pub source: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -98,11 +175,11 @@ pub struct UpdateScheduleInput {
     pub end_time: Option<String>,
     pub operator_id: Option<i64>,
     pub load_name: Option<String>,
-    pub planned_hours: Option<f64>,
-    pub actual_hours: Option<f64>,
+    pub planned_hours: Option<HoursValue>,
+    pub actual_hours: Option<HoursValue>,
     pub notes: Option<String>,
     pub status: Option<String>,
-    pub setup_hours: Option<f64>,
+    pub setup_hours: Option<HoursValue>,
     pub sequence_order: Option<i64>,
     pub drawing_number: Option<String>,
     pub revision: Option<String>,
@@ -111,6 +188,29 @@ pub struct UpdateScheduleInput {
     pub cam_actual_hours: Option<f64>,
     pub cam_buffer_percentage: Option<f64>,
     pub job_type: Option<String>,
+    pub shift_id: Option<i64>,
+    pub tag_id: Option<i64>,
+    /// Remove the entry's tag (takes precedence over `tag_id`)
+    pub clear_tag: Option<bool>,
+    /// Must be a task of the entry's (new) project. Changing project without
+    /// naming a task unlinks the old one.
+    pub task_id: Option<i64>,
+    pub clear_task: Option<bool>,
+    pub external_ref: Option<String>,
+}
+
+/// Result of a batch status update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchStatusResult {
+    pub updated: i64,
+    pub not_found: Vec<i64>,
+}
+
+/// Result of handing one operator's entries to another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReassignOperatorResult {
+    pub reassigned: i64,
+    pub schedule_ids: Vec<i64>,
 }
 
 /// Weekly schedule for a single machine (7 days)
@@ -118,6 +218,11 @@ pub struct UpdateScheduleInput {
 pub struct MachineWeekSchedule {
     pub machine_id: i64,
     pub machine_name: String,
+    /// Site the machine belongs to; None for the implicit local site
+    pub site_id: Option<i64>,
+    pub site_name: Option<String>,
+    /// UTC offset the entry times are local to; None means the server's local time
+    pub site_timezone: Option<String>,
     pub days: Vec<DaySchedule>,
     pub weekly_planned_hours: f64,
     pub weekly_actual_hours: f64,
@@ -128,6 +233,8 @@ pub struct MachineWeekSchedule {
 pub struct DaySchedule {
     pub date: String,
     pub day_name: String,
+    pub is_holiday: bool,
+    pub holiday_name: Option<String>,
     pub entries: Vec<ScheduleEntry>,
     pub total_planned_hours: f64,
     pub total_actual_hours: f64,
@@ -137,6 +244,13 @@ pub struct DaySchedule {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScheduleEntry {
     pub id: i64,
+    pub machine_id: i64,
+    /// Only set by views that span machines, such as the operator week
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub machine_name: Option<String>,
+    /// UTC offset of the machine's site, for views that span machines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site_timezone: Option<String>,
     pub project_id: Option<i64>,
     pub project_name: Option<String>,
     pub operator_id: Option<i64>,
@@ -157,6 +271,59 @@ pub struct ScheduleEntry {
     pub cam_actual_hours: Option<f64>,
     pub cam_buffer_percentage: Option<f64>,
     pub job_type: Option<String>,
+    pub shift_id: Option<i64>,
+    pub shift_name: Option<String>,
+    pub tag_id: Option<i64>,
+    pub tag_name: Option<String>,
+    pub tag_color: Option<String>,
+    pub task_id: Option<i64>,
+    pub external_ref: Option<String>,
+    pub source: String,
+    pub created_by_name: Option<String>,
+}
+
+impl ScheduleEntry {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            machine_id: row.get("machine_id")?,
+            machine_name: row.get("machine_name").ok().flatten(),
+            site_timezone: row.get("site_timezone").ok().flatten(),
+            project_id: row.get("project_id")?,
+            project_name: row.get("project_name")?,
+            operator_id: row.get("operator_id")?,
+            operator_name: row.get("operator_name")?,
+            load_name: row.get("load_name")?,
+            start_time: row.get("start_time")?,
+            end_time: row.get("end_time")?,
+            planned_hours: row.get("planned_hours")?,
+            actual_hours: row.get("actual_hours")?,
+            notes: row.get("notes")?,
+            status: row.get("status")?,
+            setup_hours: row.get("setup_hours").unwrap_or(0.0),
+            sequence_order: row.get("sequence_order").unwrap_or(0),
+            drawing_number: row.get("drawing_number").ok().flatten(),
+            revision: row.get("revision").ok().flatten(),
+            material: row.get("material").ok().flatten(),
+            cam_planned_hours: row.get("cam_planned_hours").ok().flatten(),
+            cam_actual_hours: row.get("cam_actual_hours").ok().flatten(),
+            cam_buffer_percentage: row.get("cam_buffer_percentage").ok().flatten(),
+            job_type: row.get("job_type").ok().flatten(),
+            shift_id: row.get("shift_id").ok().flatten(),
+            shift_name: row.get("shift_name").ok().flatten(),
+            tag_id: row.get("tag_id").ok().flatten(),
+            tag_name: row.get("tag_name").ok().flatten(),
+            tag_color: row.get("tag_color").ok().flatten(),
+            task_id: row.get("task_id").ok().flatten(),
+            external_ref: row.get("external_ref").ok().flatten(),
+            source: row
+                .get::<_, Option<String>>("source")
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| SOURCE_MANUAL.to_string()),
+            created_by_name: row.get("created_by_name").ok().flatten(),
+        })
+    }
 }
 
 /// Complete weekly schedule response
@@ -165,4 +332,52 @@ pub struct WeeklyScheduleResponse {
     pub week_start: String,
     pub week_end: String,
     pub machines: Vec<MachineWeekSchedule>,
+    /// Set when the week is locked against edits
+    pub lock: Option<WeekLock>,
+}
+
+/// One operator's assignments across all machines for a week
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperatorWeekSchedule {
+    pub operator_id: i64,
+    pub operator_name: Option<String>,
+    pub week_start: String,
+    pub week_end: String,
+    pub days: Vec<DaySchedule>,
+    pub weekly_planned_hours: f64,
+    pub weekly_actual_hours: f64,
+    /// Set when the week is locked against edits
+    pub lock: Option<WeekLock>,
+}
+
+/// A week locked against schedule edits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeekLock {
+    pub week_start: String,
+    pub locked_by: Option<i64>,
+    pub locked_by_name: Option<String>,
+    pub locked_at: String,
+}
+
+impl WeekLock {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            week_start: row.get("week_start")?,
+            locked_by: row.get("locked_by")?,
+            locked_by_name: row.get("locked_by_name")?,
+            locked_at: row.get("locked_at")?,
+        })
+    }
+}
+
+/// Material that will not have arrived by a schedule entry's date
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LateMaterial {
+    pub material_id: i64,
+    pub name: String,
+    pub expected_date: String,
+    pub quantity_required: f64,
+    pub quantity_received: f64,
+    pub shortfall: f64,
+    pub unit: Option<String>,
 }
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use rusqlite::Row;
 
+use crate::utils::is_future;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
     pub id: i64,
@@ -11,6 +13,14 @@ pub struct User {
     pub full_name: Option<String>,
     pub role: String,
     pub is_active: bool,
+    /// Logins are refused until this time. Failed attempts before it no longer
+    /// count towards a lockout, so unlocking sets it to the present.
+    pub locked_until: Option<String>,
+    /// Time of the last successful login; None for accounts never used
+    pub last_login_at: Option<String>,
+    /// Sealed TOTP secret; set once two-factor login is confirmed
+    #[serde(skip_serializing)]
+    pub totp_secret: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -25,6 +35,9 @@ impl User {
             full_name: row.get("full_name")?,
             role: row.get("role")?,
             is_active: row.get::<_, i64>("is_active")? == 1,
+            locked_until: row.get("locked_until").ok().flatten(),
+            last_login_at: row.get("last_login_at").ok().flatten(),
+            totp_secret: row.get("totp_secret").ok().flatten(),
             created_at: row.get("created_at")?,
             updated_at: row.get("updated_at")?,
         })
@@ -55,6 +68,10 @@ pub struct UserPublic {
     pub full_name: Option<String>,
     pub role: String,
     pub is_active: bool,
+    /// Set only while the account is locked out after failed logins
+    pub locked_until: Option<String>,
+    pub last_login_at: Option<String>,
+    pub totp_enabled: bool,
     pub created_at: String,
 }
 
@@ -67,6 +84,9 @@ impl From<User> for UserPublic {
             full_name: user.full_name,
             role: user.role,
             is_active: user.is_active,
+            locked_until: user.locked_until.filter(|until| is_future(until)),
+            last_login_at: user.last_login_at,
+            totp_enabled: user.totp_secret.is_some(),
             created_at: user.created_at,
         }
     }
@@ -89,6 +109,12 @@ pub struct UpdateUserInput {
     pub is_active: Option<bool>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordResetInput {
+    pub user_id: i64,
+    pub new_password: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Session {
     pub id: i64,
@@ -97,6 +123,10 @@ pub struct Session {
     pub created_at: String,
     pub expires_at: String,
     pub is_valid: bool,
+    /// Length of the session, used to slide `expires_at` forward on use
+    pub lifetime_minutes: i64,
+    pub remember_me: bool,
+    pub device_name: Option<String>,
 }
 
 impl Session {
@@ -108,10 +138,109 @@ impl Session {
             created_at: row.get("created_at")?,
             expires_at: row.get("expires_at")?,
             is_valid: row.get::<_, i64>("is_valid")? == 1,
+            lifetime_minutes: row.get("lifetime_minutes")?,
+            remember_me: row.get::<_, i64>("remember_me")? == 1,
+            device_name: row.get("device_name")?,
         })
     }
 }
 
+/// A session as shown to users; the token never leaves the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionPublic {
+    pub id: i64,
+    pub user_id: i64,
+    pub created_at: String,
+    pub expires_at: String,
+    pub remember_me: bool,
+    pub device_name: Option<String>,
+    /// True for the session making the request
+    pub is_current: bool,
+}
+
+impl SessionPublic {
+    pub fn from_session(session: Session, current_token: &str) -> Self {
+        Self {
+            is_current: session.token == current_token,
+            id: session.id,
+            user_id: session.user_id,
+            created_at: session.created_at,
+            expires_at: session.expires_at,
+            remember_me: session.remember_me,
+            device_name: session.device_name,
+        }
+    }
+}
+
+/// One recorded login attempt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginAttempt {
+    pub id: i64,
+    pub username: String,
+    pub user_id: Option<i64>,
+    pub attempted_at: String,
+    pub success: bool,
+    pub host: Option<String>,
+}
+
+impl LoginAttempt {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            username: row.get("username")?,
+            user_id: row.get("user_id")?,
+            attempted_at: row.get("attempted_at")?,
+            success: row.get::<_, i64>("success")? == 1,
+            host: row.get("host")?,
+        })
+    }
+}
+
+/// An API token as listed; the token itself is only shown when created
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: i64,
+    pub user_id: i64,
+    pub username: String,
+    pub name: String,
+    /// Last few characters of the token, to tell tokens apart
+    pub token_hint: String,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+    pub revoked: bool,
+}
+
+impl ApiToken {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            user_id: row.get("user_id")?,
+            username: row.get("username")?,
+            name: row.get("name")?,
+            token_hint: row.get("token_hint")?,
+            created_at: row.get("created_at")?,
+            last_used_at: row.get("last_used_at")?,
+            revoked: row.get::<_, i64>("revoked")? == 1,
+        })
+    }
+}
+
+/// A newly created API token with its plaintext, which is not stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedApiToken {
+    #[serde(flatten)]
+    pub api_token: ApiToken,
+    pub token: String,
+}
+
+/// Result of deleting long-expired sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionCleanup {
+    pub deleted: usize,
+    /// Session rows left, valid or not
+    pub remaining: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthResponse {
     pub user: UserPublic,
@@ -123,4 +252,15 @@ pub struct AuthResponse {
 pub struct LoginInput {
     pub username: String,
     pub password: String,
+    pub remember_me: Option<bool>,
+    pub device_name: Option<String>,
+    /// Current code from the user's authenticator app, when two-factor login is on
+    pub totp_code: Option<String>,
+}
+
+/// A new TOTP secret awaiting confirmation with a code from the app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TotpSetup {
+    pub secret: String,
+    pub otpauth_uri: String,
 }
@@ -0,0 +1,278 @@
+use parking_lot::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Manager};
+use tokio::sync::watch;
+
+use crate::db::{initialize_database, StartupStatus};
+
+pub const MAIN_WINDOW: &str = "main";
+pub const SPLASH_WINDOW: &str = "splashscreen";
+
+/// Emitted by the frontend once its first render has committed
+pub const FRONTEND_READY_EVENT: &str = "frontend-ready";
+
+/// Longest the splash screen stays up when a readiness signal never arrives,
+/// e.g. a frontend that failed to load
+const SPLASH_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Default)]
+struct Signals {
+    database_ready: bool,
+    frontend_ready: bool,
+    main_shown: bool,
+}
+
+impl Signals {
+    /// Whether the main window should be shown now; true at most once
+    fn take_show(&mut self, timed_out: bool) -> bool {
+        if self.main_shown || !(timed_out || (self.database_ready && self.frontend_ready)) {
+            return false;
+        }
+        if timed_out {
+            log::warn!(
+                "Startup still waiting after {:?} (database ready: {}, frontend ready: {}); showing main window",
+                SPLASH_TIMEOUT,
+                self.database_ready,
+                self.frontend_ready
+            );
+        }
+        self.main_shown = true;
+        true
+    }
+}
+
+/// Startup waits for two signals: the database being opened and the
+/// frontend's first render. The splash screen closes once both have arrived,
+/// or after SPLASH_TIMEOUT, whichever comes first.
+pub struct StartupGate {
+    signals: Mutex<Signals>,
+    status: watch::Sender<Option<StartupStatus>>,
+}
+
+impl StartupGate {
+    fn new() -> Self {
+        Self {
+            signals: Mutex::new(Signals::default()),
+            status: watch::Sender::new(None),
+        }
+    }
+
+    fn database_ready(&self, app: &AppHandle, status: StartupStatus) {
+        self.status.send_replace(Some(status));
+        self.signals.lock().database_ready = true;
+        self.show_main_when_ready(app, false);
+    }
+
+    fn frontend_ready(&self, app: &AppHandle) {
+        self.signals.lock().frontend_ready = true;
+        self.show_main_when_ready(app, false);
+    }
+
+    fn show_main_when_ready(&self, app: &AppHandle, timed_out: bool) {
+        let show = self.signals.lock().take_show(timed_out);
+        if show {
+            show_main_window(app);
+        }
+    }
+
+    /// The result of opening the database, waiting for it if startup is still
+    /// running
+    pub async fn status(&self) -> Option<StartupStatus> {
+        let mut receiver = self.status.subscribe();
+        let status = receiver.wait_for(Option::is_some).await.ok()?;
+        status.clone()
+    }
+}
+
+/// The window operations startup needs. Each returns None when the window does
+/// not exist, so the splash handling can be tested without a webview.
+trait StartupWindows {
+    fn exists(&self, label: &str) -> bool;
+    fn close(&self, label: &str) -> Option<Result<(), String>>;
+    fn hide(&self, label: &str) -> Option<Result<(), String>>;
+    fn show_and_focus(&self, label: &str) -> Option<Result<(), String>>;
+}
+
+impl StartupWindows for AppHandle {
+    fn exists(&self, label: &str) -> bool {
+        self.get_webview_window(label).is_some()
+    }
+
+    fn close(&self, label: &str) -> Option<Result<(), String>> {
+        self.get_webview_window(label).map(|window| window.close().map_err(|e| e.to_string()))
+    }
+
+    fn hide(&self, label: &str) -> Option<Result<(), String>> {
+        self.get_webview_window(label).map(|window| window.hide().map_err(|e| e.to_string()))
+    }
+
+    fn show_and_focus(&self, label: &str) -> Option<Result<(), String>> {
+        self.get_webview_window(label)
+            .map(|window| window.show().and_then(|_| window.set_focus()).map_err(|e| e.to_string()))
+    }
+}
+
+/// Keep the main window hidden while a splash screen covers startup. Without a
+/// splash window the main window stays as configured.
+fn hide_main_behind_splash(windows: &impl StartupWindows) {
+    if !windows.exists(SPLASH_WINDOW) {
+        return;
+    }
+    if let Some(Err(e)) = windows.hide(MAIN_WINDOW) {
+        log::warn!("Failed to hide main window: {}", e);
+    }
+}
+
+/// Close the splash screen and show the main window. Either window may be
+/// missing from a customized tauri.conf.json or already closed; that is logged
+/// rather than panicking.
+fn show_main_window(windows: &impl StartupWindows) {
+    match windows.close(SPLASH_WINDOW) {
+        Some(Err(e)) => log::warn!("Failed to close splash screen: {}", e),
+        Some(Ok(())) => {}
+        None => log::warn!("No '{}' window open; showing main window", SPLASH_WINDOW),
+    }
+    match windows.show_and_focus(MAIN_WINDOW) {
+        Some(Err(e)) => log::warn!("Failed to show main window: {}", e),
+        Some(Ok(())) => {}
+        None => log::warn!("No '{}' window open; nothing to show", MAIN_WINDOW),
+    }
+}
+
+/// Open the database off the main thread while the splash screen is up, and
+/// show the main window once both it and the frontend are ready.
+///
+/// Commands that need the database fail until it is managed, so the frontend
+/// waits on get_startup_status before its first database call.
+pub fn start(app: &AppHandle) {
+    app.manage(StartupGate::new());
+    hide_main_behind_splash(app);
+
+    let handle = app.clone();
+    app.once(FRONTEND_READY_EVENT, move |_| {
+        handle.state::<StartupGate>().frontend_ready(&handle);
+    });
+
+    let handle = app.clone();
+    thread::spawn(move || {
+        // On failure the app still starts so the frontend can show the startup
+        // status instead of crashing
+        let status = match initialize_database(&handle) {
+            Ok((database, compatibility)) => {
+                crate::run_startup_checks(&database);
+                handle.manage(database);
+                crate::commands::start_alert_watcher(&handle);
+                StartupStatus::ready(&compatibility)
+            }
+            Err(e) => {
+                log::error!("Failed to initialize database: {}", e);
+                StartupStatus::from_error(&e)
+            }
+        };
+        handle.state::<StartupGate>().database_ready(&handle, status);
+    });
+
+    let handle = app.clone();
+    thread::spawn(move || {
+        thread::sleep(SPLASH_TIMEOUT);
+        handle.state::<StartupGate>().show_main_when_ready(&handle, true);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    /// Windows from a tauri.conf.json, recording what startup does to them
+    struct FakeWindows {
+        labels: Vec<&'static str>,
+        calls: RefCell<Vec<String>>,
+    }
+
+    impl FakeWindows {
+        fn new(labels: &[&'static str]) -> Self {
+            Self { labels: labels.to_vec(), calls: RefCell::new(Vec::new()) }
+        }
+
+        fn call(&self, action: &str, label: &str) -> Option<Result<(), String>> {
+            self.labels.contains(&label).then(|| {
+                self.calls.borrow_mut().push(format!("{} {}", action, label));
+                Ok(())
+            })
+        }
+
+        fn calls(&self) -> Vec<String> {
+            self.calls.borrow().clone()
+        }
+    }
+
+    impl StartupWindows for FakeWindows {
+        fn exists(&self, label: &str) -> bool {
+            self.labels.contains(&label)
+        }
+
+        fn close(&self, label: &str) -> Option<Result<(), String>> {
+            self.call("close", label)
+        }
+
+        fn hide(&self, label: &str) -> Option<Result<(), String>> {
+            self.call("hide", label)
+        }
+
+        fn show_and_focus(&self, label: &str) -> Option<Result<(), String>> {
+            self.call("show", label)
+        }
+    }
+
+    #[test]
+    fn splash_covers_main_until_ready() {
+        let windows = FakeWindows::new(&[MAIN_WINDOW, SPLASH_WINDOW]);
+        hide_main_behind_splash(&windows);
+        show_main_window(&windows);
+        assert_eq!(windows.calls(), ["hide main", "close splashscreen", "show main"]);
+    }
+
+    #[test]
+    fn without_splash_main_is_left_alone_and_shown() {
+        let windows = FakeWindows::new(&[MAIN_WINDOW]);
+        hide_main_behind_splash(&windows);
+        assert!(windows.calls().is_empty());
+        show_main_window(&windows);
+        assert_eq!(windows.calls(), ["show main"]);
+    }
+
+    #[test]
+    fn missing_main_window_does_not_panic() {
+        let windows = FakeWindows::new(&[SPLASH_WINDOW]);
+        hide_main_behind_splash(&windows);
+        show_main_window(&windows);
+        assert_eq!(windows.calls(), ["close splashscreen"]);
+
+        let windows = FakeWindows::new(&[]);
+        hide_main_behind_splash(&windows);
+        show_main_window(&windows);
+        assert!(windows.calls().is_empty());
+    }
+
+    #[test]
+    fn main_shows_once_both_signals_arrive() {
+        let mut signals = Signals { database_ready: true, ..Signals::default() };
+        assert!(!signals.take_show(false));
+        signals.frontend_ready = true;
+        assert!(signals.take_show(false));
+        assert!(!signals.take_show(false));
+        assert!(!signals.take_show(true));
+    }
+
+    #[test]
+    fn timeout_shows_main_without_signals() {
+        let mut signals = Signals::default();
+        assert!(signals.take_show(true));
+        signals.database_ready = true;
+        signals.frontend_ready = true;
+        assert!(!signals.take_show(false));
+    }
+}
@@ -0,0 +1,111 @@
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::models::User;
+
+/// Table name for sign-in, sign-out and access-denied events, kept apart
+/// from the tables whose data changes are audited
+pub const AUDIT_TABLE_AUTH: &str = "auth";
+
+/// Actions recorded against `AUDIT_TABLE_AUTH`
+pub const AUTH_AUDIT_ACTIONS: [&str; 5] = ["login", "login_failed", "logout", "password_change", "permission_denied"];
+
+#[allow(clippy::too_many_arguments)]
+fn insert_audit(
+    conn: &Connection,
+    user_id: Option<i64>,
+    username: &str,
+    action: &str,
+    table_name: &str,
+    record_id: Option<i64>,
+    old_values: Option<&str>,
+    new_values: Option<&str>,
+) {
+    if let Err(e) = conn.execute(
+        "INSERT INTO audit_log (user_id, username, action, table_name, record_id, old_values, new_values)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![user_id, username, action, table_name, record_id, old_values, new_values],
+    ) {
+        log::warn!("Failed to write audit log for {} on {}: {}", action, table_name, e);
+    }
+}
+
+/// Write an entry to the audit log.
+///
+/// Auditing never fails the change being audited: if the entry cannot be
+/// written the error is logged and the caller carries on.
+pub fn record_audit(
+    conn: &Connection,
+    user: &User,
+    action: &str,
+    table_name: &str,
+    record_id: Option<i64>,
+    old_values: Option<&str>,
+    new_values: Option<&str>,
+) {
+    insert_audit(conn, Some(user.id), &user.username, action, table_name, record_id, old_values, new_values);
+}
+
+/// Write an authentication event, such as a sign-in, to the audit log.
+/// `user_id` is None when the username did not match an active user; the
+/// record id is the user's, so events can be traced per account.
+pub fn record_auth_event(
+    conn: &Connection,
+    user_id: Option<i64>,
+    username: &str,
+    action: &str,
+    details: Option<&Value>,
+) {
+    let details = details.map(Value::to_string);
+    insert_audit(conn, user_id, username, action, AUDIT_TABLE_AUTH, user_id, None, details.as_deref());
+}
+
+/// A record as JSON for the audit log's old or new values
+pub fn audit_json<T: Serialize>(value: &T) -> Option<String> {
+    serde_json::to_string(value).ok()
+}
+
+/// The fields that differ between two versions of a record, as JSON objects
+/// of their old and new values. None when nothing changed.
+pub fn audit_changes<T: Serialize>(old: &T, new: &T) -> Option<(String, String)> {
+    let (Ok(Value::Object(old)), Ok(Value::Object(new))) = (serde_json::to_value(old), serde_json::to_value(new))
+    else {
+        return None;
+    };
+
+    let mut before = Map::new();
+    let mut after = Map::new();
+    for (key, new_value) in new {
+        // Bookkeeping columns change on every write and say nothing useful
+        if key == "updated_at" {
+            continue;
+        }
+        let old_value = old.get(&key).cloned().unwrap_or(Value::Null);
+        if old_value != new_value {
+            before.insert(key.clone(), old_value);
+            after.insert(key, new_value);
+        }
+    }
+
+    if after.is_empty() {
+        return None;
+    }
+    Some((Value::Object(before).to_string(), Value::Object(after).to_string()))
+}
+
+/// Record an update with only the changed fields; nothing is written when
+/// the record did not change
+pub fn record_audit_update<T: Serialize>(
+    conn: &Connection,
+    user: &User,
+    action: &str,
+    table_name: &str,
+    record_id: i64,
+    old: &T,
+    new: &T,
+) {
+    if let Some((before, after)) = audit_changes(old, new) {
+        record_audit(conn, user, action, table_name, Some(record_id), Some(&before), Some(&after));
+    }
+}
@@ -1,13 +1,31 @@
-use bcrypt::{hash, verify, DEFAULT_COST};
-use chrono::{Duration, Utc};
+use std::path::Path;
+
+use bcrypt::{hash, verify};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
 use rusqlite::Connection;
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
-use crate::models::{AuthResponse, Session, User, UserPublic};
+use crate::error::AppError;
+use crate::models::{AuthResponse, LoginInput, Session, SessionCleanup, User, UserPublic};
+use crate::utils::audit::record_auth_event;
+use crate::utils::settings::{
+    get_int, ADMIN_SESSION_LIFETIME_HOURS_KEY, BCRYPT_COST_KEY, DEFAULT_ADMIN_SESSION_LIFETIME_HOURS,
+    DEFAULT_BCRYPT_COST, DEFAULT_MAX_SESSIONS_PER_USER, DEFAULT_REMEMBER_ME_LIFETIME_DAYS,
+    DEFAULT_SESSION_LIFETIME_HOURS, MAX_BCRYPT_COST, MAX_SESSIONS_PER_USER_KEY, MIN_BCRYPT_COST,
+    REMEMBER_ME_LIFETIME_DAYS_KEY, SESSION_LIFETIME_HOURS_KEY,
+};
+use crate::utils::totp::{open_totp_secret, verify_totp};
+
+/// Hash a password using bcrypt at the given cost (see `bcrypt_cost`)
+pub fn hash_password(password: &str, cost: u32) -> Result<String, String> {
+    hash(password, cost).map_err(|e| format!("Failed to hash password: {}", e))
+}
 
-/// Hash a password using bcrypt
-pub fn hash_password(password: &str) -> Result<String, String> {
-    hash(password, DEFAULT_COST).map_err(|e| format!("Failed to hash password: {}", e))
+/// Configured bcrypt cost. Values below the floor are raised to it, so a bad
+/// setting can slow hashing down but never weaken it.
+pub fn bcrypt_cost(conn: &Connection) -> u32 {
+    get_int(conn, BCRYPT_COST_KEY, DEFAULT_BCRYPT_COST).clamp(MIN_BCRYPT_COST, MAX_BCRYPT_COST) as u32
 }
 
 /// Verify a password against a hash
@@ -20,22 +38,140 @@ pub fn generate_token() -> String {
     Uuid::new_v4().to_string()
 }
 
-/// Create a new session for a user
-pub fn create_session(conn: &Connection, user_id: i64) -> Result<(String, String), String> {
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Parse a stored timestamp. Accepts the `YYYY-MM-DD HH:MM:SS` form written
+/// here and by SQLite's CURRENT_TIMESTAMP, with or without a `T` separator or
+/// fractional seconds, as well as RFC 3339.
+pub(crate) fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|naive| naive.and_utc())
+        .or_else(|| DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Utc)))
+}
+
+/// Whether a stored timestamp lies ahead of now; unreadable ones do not
+pub(crate) fn is_future(value: &str) -> bool {
+    parse_timestamp(value).is_some_and(|time| time > Utc::now())
+}
+
+/// How long a new session for this user lasts. Remember-me applies to
+/// Operators and Viewers only, so Admin sessions always use the shorter
+/// Admin lifetime. Returns the lifetime and whether remember-me took effect.
+fn session_lifetime(conn: &Connection, user: &User, remember_me: bool) -> (Duration, bool) {
+    if user.is_admin() {
+        let hours = get_int(conn, ADMIN_SESSION_LIFETIME_HOURS_KEY, DEFAULT_ADMIN_SESSION_LIFETIME_HOURS);
+        (Duration::hours(hours.max(1)), false)
+    } else if remember_me {
+        let days = get_int(conn, REMEMBER_ME_LIFETIME_DAYS_KEY, DEFAULT_REMEMBER_ME_LIFETIME_DAYS);
+        (Duration::days(days.max(1)), true)
+    } else {
+        let hours = get_int(conn, SESSION_LIFETIME_HOURS_KEY, DEFAULT_SESSION_LIFETIME_HOURS);
+        (Duration::hours(hours.max(1)), false)
+    }
+}
+
+/// Create a new session for a user, named after the device it was opened on
+pub fn create_session(
+    conn: &Connection,
+    user: &User,
+    remember_me: bool,
+    device_name: Option<&str>,
+) -> Result<(String, String), String> {
     let token = generate_token();
-    let expires_at = (Utc::now() + Duration::hours(24)).format("%Y-%m-%d %H:%M:%S").to_string();
+    let (lifetime, remember_me) = session_lifetime(conn, user, remember_me);
+    let expires_at = timestamp(Utc::now() + lifetime);
+    let device_name = device_name.map(str::trim).filter(|d| !d.is_empty());
 
     conn.execute(
-        "INSERT INTO sessions (user_id, token, expires_at) VALUES (?1, ?2, ?3)",
-        rusqlite::params![user_id, token, expires_at],
+        "INSERT INTO sessions (user_id, token, expires_at, lifetime_minutes, remember_me, device_name)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![user.id, token, expires_at, lifetime.num_minutes(), remember_me, device_name],
     )
     .map_err(|e| format!("Failed to create session: {}", e))?;
 
+    // Sign out the oldest sessions beyond the per-user cap
+    let cap = get_int(conn, MAX_SESSIONS_PER_USER_KEY, DEFAULT_MAX_SESSIONS_PER_USER);
+    if cap > 0 {
+        conn.execute(
+            "UPDATE sessions SET is_valid = 0 WHERE id IN (
+                 SELECT id FROM sessions WHERE user_id = ?1 AND is_valid = 1 AND expires_at >= ?2
+                 ORDER BY created_at DESC, id DESC LIMIT -1 OFFSET ?3)",
+            rusqlite::params![user.id, timestamp(Utc::now()), cap],
+        )
+        .map_err(|e| format!("Failed to enforce session limit: {}", e))?;
+    }
+
     Ok((token, expires_at))
 }
 
-/// Validate a session token and return the user if valid
-pub fn validate_session(conn: &Connection, token: &str) -> Result<User, String> {
+/// Days an expired session is kept (e.g. for the sessions list) before cleanup deletes it
+pub const EXPIRED_SESSION_RETENTION_DAYS: i64 = 30;
+
+/// Delete sessions that expired more than `EXPIRED_SESSION_RETENTION_DAYS`
+/// ago, whether or not they were signed out first
+pub fn purge_expired_sessions(conn: &Connection) -> Result<SessionCleanup, String> {
+    let cutoff = timestamp(Utc::now() - Duration::days(EXPIRED_SESSION_RETENTION_DAYS));
+    let deleted = conn
+        .execute("DELETE FROM sessions WHERE expires_at < ?1", [cutoff])
+        .map_err(|e| format!("Failed to delete expired sessions: {}", e))?;
+    let remaining = conn
+        .query_row("SELECT COUNT(*) FROM sessions", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    Ok(SessionCleanup { deleted, remaining })
+}
+
+/// Prefix that marks a token as an API token rather than a session token
+pub const API_TOKEN_PREFIX: &str = "vmc_";
+
+/// Generate a new API token. Only its hash is stored.
+pub fn generate_api_token() -> String {
+    format!("{}{}{}", API_TOKEN_PREFIX, Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// SHA-256 of an API token, hex encoded. Tokens are long and random, so a
+/// fast hash is enough and lets them be looked up directly.
+pub fn hash_api_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Resolve an API token to its owner, recording when it was used
+fn validate_api_token(conn: &Connection, token: &str) -> Result<User, AppError> {
+    let (token_id, user_id): (i64, i64) = conn
+        .query_row(
+            "SELECT id, user_id FROM api_tokens WHERE token_hash = ?1 AND revoked = 0",
+            [hash_api_token(token)],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| AppError::Unauthorized("Invalid or revoked API token".to_string()))?;
+
+    let user = conn
+        .query_row("SELECT * FROM users WHERE id = ?1 AND is_active = 1", [user_id], User::from_row)
+        .map_err(|_| AppError::Unauthorized("User not found or inactive".to_string()))?;
+    conn.execute(
+        "UPDATE api_tokens SET last_used_at = ?1 WHERE id = ?2",
+        rusqlite::params![timestamp(Utc::now()), token_id],
+    )
+    .map_err(|e| format!("Failed to record API token use: {}", e))?;
+
+    Ok(user)
+}
+
+/// Validate a session token and return the user if valid. API tokens are
+/// accepted too and act as their owner, with the owner's permissions.
+///
+/// Expiration slides: once a session is more than halfway through its
+/// lifetime, using it pushes `expires_at` a full lifetime past now.
+pub fn validate_session(conn: &Connection, token: &str) -> Result<User, AppError> {
+    if token.starts_with(API_TOKEN_PREFIX) {
+        return validate_api_token(conn, token);
+    }
+
     // First check if session exists and is valid
     let session: Session = conn
         .query_row(
@@ -43,19 +179,22 @@ pub fn validate_session(conn: &Connection, token: &str) -> Result<User, String>
             [token],
             Session::from_row,
         )
-        .map_err(|_| "Invalid or expired session".to_string())?;
+        .map_err(|_| AppError::Unauthorized("Invalid or expired session".to_string()))?;
 
-    // Check if session has expired
-    let now = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
-    if session.expires_at < now {
-        // Invalidate expired session
-        conn.execute(
-            "UPDATE sessions SET is_valid = 0 WHERE id = ?1",
-            [session.id],
-        )
-        .ok();
-        return Err("Session expired".to_string());
-    }
+    // Check if session has expired. An unreadable expiry counts as expired.
+    let now = Utc::now();
+    let expires_at = match parse_timestamp(&session.expires_at) {
+        Some(expires_at) if expires_at > now => expires_at,
+        _ => {
+            // Invalidate expired session
+            conn.execute(
+                "UPDATE sessions SET is_valid = 0 WHERE id = ?1",
+                [session.id],
+            )
+            .ok();
+            return Err(AppError::Unauthorized("Session expired".to_string()));
+        }
+    };
 
     // Get the user
     let user = conn
@@ -64,7 +203,16 @@ pub fn validate_session(conn: &Connection, token: &str) -> Result<User, String>
             [session.user_id],
             User::from_row,
         )
-        .map_err(|_| "User not found or inactive".to_string())?;
+        .map_err(|_| AppError::Unauthorized("User not found or inactive".to_string()))?;
+
+    let lifetime = Duration::minutes(session.lifetime_minutes.max(1));
+    if expires_at - now < lifetime / 2 {
+        conn.execute(
+            "UPDATE sessions SET expires_at = ?1 WHERE id = ?2",
+            rusqlite::params![timestamp(now + lifetime), session.id],
+        )
+        .map_err(|e| format!("Failed to extend session: {}", e))?;
+    }
 
     Ok(user)
 }
@@ -91,24 +239,141 @@ pub fn invalidate_all_user_sessions(conn: &Connection, user_id: i64) -> Result<(
     Ok(())
 }
 
-/// Login a user with username and password
-pub fn login_user(conn: &Connection, username: &str, password: &str) -> Result<AuthResponse, String> {
-    // Find user by username
-    let user: User = conn
+/// Consecutive failed logins within the window that lock an account
+pub const MAX_FAILED_LOGINS: i64 = 5;
+/// Minutes the failures must fall within, and how long the lockout lasts
+pub const LOGIN_LOCKOUT_MINUTES: i64 = 15;
+
+/// Name of the computer the attempt came from, where the OS reports one
+fn login_host() -> Option<String> {
+    std::env::var("COMPUTERNAME").or_else(|_| std::env::var("HOSTNAME")).ok()
+}
+
+fn record_login_attempt(conn: &Connection, username: &str, user_id: Option<i64>, success: bool) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO login_attempts (username, user_id, success, host) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![username, user_id, success, login_host()],
+    )
+    .map(|_| ())
+    .map_err(|e| format!("Failed to record login attempt: {}", e))
+}
+
+/// Lock the account if this failure completes a run of failed logins within
+/// the window. Runs are broken by a successful login and by the end of an
+/// earlier lockout (or an Admin unlock).
+fn lock_after_failures(conn: &Connection, user: &User) -> Result<Option<String>, String> {
+    let now = Utc::now();
+    let window_start = timestamp(now - Duration::minutes(LOGIN_LOCKOUT_MINUTES));
+    let failures: i64 = conn
         .query_row(
-            "SELECT * FROM users WHERE username = ?1 AND is_active = 1",
-            [username],
-            User::from_row,
+            "SELECT COUNT(*) FROM login_attempts
+             WHERE user_id = ?1 AND success = 0 AND attempted_at >= ?2 AND attempted_at >= ?3
+               AND attempted_at > COALESCE(
+                   (SELECT MAX(attempted_at) FROM login_attempts WHERE user_id = ?1 AND success = 1), '')",
+            rusqlite::params![user.id, window_start, user.locked_until.as_deref().unwrap_or("")],
+            |row| row.get(0),
         )
-        .map_err(|_| "Invalid username or password".to_string())?;
+        .map_err(|e| e.to_string())?;
+    if failures < MAX_FAILED_LOGINS {
+        return Ok(None);
+    }
+
+    let until = timestamp(now + Duration::minutes(LOGIN_LOCKOUT_MINUTES));
+    conn.execute(
+        "UPDATE users SET locked_until = ?1 WHERE id = ?2",
+        rusqlite::params![until, user.id],
+    )
+    .map_err(|e| format!("Failed to lock account: {}", e))?;
+    Ok(Some(until))
+}
+
+fn record_login_failure(conn: &Connection, user_id: Option<i64>, username: &str, reason: &str) {
+    let details = serde_json::json!({ "reason": reason, "host": login_host() });
+    record_auth_event(conn, user_id, username, "login_failed", Some(&details));
+}
+
+/// Same wording for an unknown user and a wrong password, so names can't be probed
+const BAD_CREDENTIALS: &str = "Invalid username or password";
 
-    // Verify password
-    if !verify_password(password, &user.password_hash) {
-        return Err("Invalid username or password".to_string());
+fn lockout_message(locked_until: &str) -> String {
+    let minutes = parse_timestamp(locked_until)
+        .map(|until| (until - Utc::now()).num_seconds())
+        .map(|seconds| ((seconds + 59) / 60).max(1))
+        .unwrap_or(LOGIN_LOCKOUT_MINUTES);
+    format!("Too many failed login attempts. Try again in {} minute(s)", minutes)
+}
+
+/// Login a user with username and password.
+///
+/// Every attempt is recorded. After `MAX_FAILED_LOGINS` failures in a row
+/// within `LOGIN_LOCKOUT_MINUTES`, the account is locked for that long.
+/// `remember_me` asks for a long-lived session (see `session_lifetime`).
+/// Bad credentials and locked accounts are `Unauthorized`. Users with
+/// two-factor login also need a current TOTP code; without a valid one the
+/// error is `TotpRequired`, and a wrong code counts as a failure.
+pub fn login_user(conn: &Connection, input: &LoginInput, secret_key_path: &Path) -> Result<AuthResponse, AppError> {
+    let username = input.username.as_str();
+    // Find user by username
+    let user: User = match conn.query_row(
+        "SELECT * FROM users WHERE username = ?1 AND is_active = 1",
+        [username],
+        User::from_row,
+    ) {
+        Ok(user) => user,
+        Err(_) => {
+            record_login_attempt(conn, username, None, false)?;
+            record_login_failure(conn, None, username, "unknown user");
+            return Err(AppError::Unauthorized(BAD_CREDENTIALS.to_string()));
+        }
+    };
+
+    if let Some(until) = user.locked_until.as_deref().filter(|until| is_future(until)) {
+        record_login_attempt(conn, username, Some(user.id), false)?;
+        record_login_failure(conn, Some(user.id), username, "account locked");
+        return Err(AppError::Unauthorized(lockout_message(until)));
+    }
+
+    // Verify password, then the TOTP code for users who have one set up
+    let failure = if !verify_password(&input.password, &user.password_hash) {
+        Some((AppError::Unauthorized(BAD_CREDENTIALS.to_string()), "wrong password"))
+    } else if let Some(sealed) = user.totp_secret.as_deref() {
+        match input.totp_code.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+            // Asking for the code is the normal second step, not a failure
+            None => return Err(AppError::TotpRequired("Enter the code from your authenticator app".to_string())),
+            Some(code) if !verify_totp(&open_totp_secret(secret_key_path, sealed)?, code) => {
+                Some((AppError::TotpRequired("Invalid authentication code".to_string()), "wrong two-factor code"))
+            }
+            Some(_) => None,
+        }
+    } else {
+        None
+    };
+    if let Some((error, reason)) = failure {
+        record_login_attempt(conn, username, Some(user.id), false)?;
+        record_login_failure(conn, Some(user.id), username, reason);
+        if let Some(until) = lock_after_failures(conn, &user)? {
+            record_login_failure(conn, Some(user.id), username, "account locked after repeated failures");
+            return Err(AppError::Unauthorized(lockout_message(&until)));
+        }
+        return Err(error);
     }
+    record_login_attempt(conn, username, Some(user.id), true)?;
+    conn.execute(
+        "UPDATE users SET last_login_at = ?1 WHERE id = ?2",
+        rusqlite::params![timestamp(Utc::now()), user.id],
+    )
+    .map_err(|e| format!("Failed to record login: {}", e))?;
 
-    // Create session
-    let (token, expires_at) = create_session(conn, user.id)?;
+    // Create session, named after this computer unless the caller names it
+    let device_name = input.device_name.clone().or_else(login_host);
+    let (token, expires_at) = create_session(conn, &user, input.remember_me.unwrap_or(false), device_name.as_deref())?;
+    record_auth_event(
+        conn,
+        Some(user.id),
+        &user.username,
+        "login",
+        Some(&serde_json::json!({ "host": login_host(), "device_name": device_name })),
+    );
 
     Ok(AuthResponse {
         user: UserPublic::from(user),
@@ -139,7 +404,7 @@ pub fn change_password(
     }
 
     // Hash new password
-    let new_hash = hash_password(new_password)?;
+    let new_hash = hash_password(new_password, bcrypt_cost(conn))?;
 
     // Update password
     conn.execute(
@@ -150,6 +415,127 @@ pub fn change_password(
 
     // Invalidate all other sessions
     invalidate_all_user_sessions(conn, user_id)?;
+    record_auth_event(conn, Some(user_id), &user.username, "password_change", None);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_pending_migrations;
+    use crate::utils::totp::{generate_totp_secret, seal_totp_secret};
+
+    fn at(value: &str) -> DateTime<Utc> {
+        parse_timestamp(value).unwrap()
+    }
+
+    #[test]
+    fn parses_every_stored_form() {
+        let expected = at("2026-03-09 10:00:00");
+        assert_eq!(at("2026-03-09T10:00:00"), expected);
+        assert_eq!(at(" 2026-03-09 10:00:00.000 "), expected);
+        assert_eq!(at("2026-03-09T10:00:00Z"), expected);
+        assert_eq!(at("2026-03-09T12:00:00+02:00"), expected);
+        assert_eq!(parse_timestamp(&timestamp(expected)), Some(expected));
+        assert_eq!(parse_timestamp("next tuesday"), None);
+        assert_eq!(parse_timestamp(""), None);
+    }
+
+    #[test]
+    fn compares_instants_not_strings() {
+        // Each pair sorts the wrong way as text
+        assert!(at("2026-03-09T10:00:00") < at("2026-03-09 11:00:00"));
+        assert!(at("2026-03-09T10:00:00Z") < at("2026-03-09 10:00:01"));
+        assert!(at("2026-03-09T12:00:00+02:00") < at("2026-03-09 11:00:00"));
+    }
+
+    #[test]
+    fn only_readable_future_times_are_future() {
+        assert!(is_future(&timestamp(Utc::now() + Duration::minutes(5))));
+        assert!(!is_future(&timestamp(Utc::now() - Duration::minutes(5))));
+        assert!(!is_future("not a time"));
+    }
+
+    /// One user with a 60-minute session expiring at `expires_at`
+    fn database_with_session(expires_at: &str) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_pending_migrations(&conn).unwrap();
+        conn.execute_batch("INSERT INTO users (id, username, password_hash, role) VALUES (1, 'op', 'x', 'Operator')")
+            .unwrap();
+        conn.execute(
+            "INSERT INTO sessions (user_id, token, expires_at, lifetime_minutes) VALUES (1, 'tok', ?1, 60)",
+            [expires_at],
+        )
+        .unwrap();
+        conn
+    }
+
+    fn session_state(conn: &Connection) -> (String, bool) {
+        conn.query_row("SELECT expires_at, is_valid FROM sessions WHERE token = 'tok'", [], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn session_slides_once_past_halfway() {
+        let conn = database_with_session(&timestamp(Utc::now() + Duration::minutes(20)));
+        validate_session(&conn, "tok").unwrap();
+        let remaining = at(&session_state(&conn).0) - Utc::now();
+        assert!(remaining > Duration::minutes(58) && remaining <= Duration::minutes(60), "{:?}", remaining);
+    }
+
+    #[test]
+    fn session_does_not_slide_before_halfway() {
+        let expires_at = timestamp(Utc::now() + Duration::minutes(50));
+        let conn = database_with_session(&expires_at);
+        validate_session(&conn, "tok").unwrap();
+        assert_eq!(session_state(&conn), (expires_at, true));
+    }
+
+    #[test]
+    fn expired_or_unreadable_sessions_are_refused() {
+        for expires_at in [timestamp(Utc::now() - Duration::seconds(1)), "garbage".to_string()] {
+            let conn = database_with_session(&expires_at);
+            assert!(matches!(validate_session(&conn, "tok"), Err(AppError::Unauthorized(_))));
+            assert!(!session_state(&conn).1, "{} left valid", expires_at);
+        }
+    }
+
+    #[test]
+    fn expiry_in_another_format_still_counts() {
+        let later = Utc::now() + Duration::minutes(50);
+        let conn = database_with_session(&later.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string());
+        validate_session(&conn, "tok").unwrap();
+        assert!(session_state(&conn).1);
+    }
+
+    #[test]
+    fn login_failures_carry_their_own_codes() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_pending_migrations(&conn).unwrap();
+        let key_path = std::env::temp_dir().join(format!("vmc-login-test-{}.key", Uuid::new_v4()));
+        let sealed = seal_totp_secret(&key_path, &generate_totp_secret()).unwrap();
+        conn.execute(
+            "INSERT INTO users (id, username, password_hash, role, totp_secret) VALUES (1, 'op', ?1, 'Operator', ?2)",
+            rusqlite::params![hash_password("secret", 4).unwrap(), sealed],
+        )
+        .unwrap();
+        let attempt = |password: &str, totp_code: Option<&str>| {
+            let input = LoginInput {
+                username: "op".to_string(),
+                password: password.to_string(),
+                remember_me: None,
+                device_name: None,
+                totp_code: totp_code.map(str::to_string),
+            };
+            login_user(&conn, &input, &key_path).map(|_| ()).unwrap_err().code()
+        };
+
+        assert_eq!(attempt("wrong", None), "unauthorized");
+        assert_eq!(attempt("secret", None), "totp_required");
+        assert_eq!(attempt("secret", Some("000000x")), "totp_required");
+        std::fs::remove_file(&key_path).ok();
+    }
+}
@@ -0,0 +1,10 @@
+use chrono::NaiveDate;
+
+use crate::error::AppError;
+
+/// Parse a YYYY-MM-DD date passed to a command, naming the parameter when it
+/// isn't one
+pub fn parse_date(field: &str, value: &str) -> Result<NaiveDate, AppError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| AppError::invalid_field(field, format!("{} must be a date in YYYY-MM-DD format", field)))
+}
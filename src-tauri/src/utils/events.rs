@@ -0,0 +1,57 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+/// Schedule entries were created, updated or deleted. Payload: DataChanged
+pub const SCHEDULE_CHANGED_EVENT: &str = "schedule:changed";
+
+/// Machines were created, updated, archived or deleted. Payload: DataChanged
+pub const MACHINE_CHANGED_EVENT: &str = "machine:changed";
+
+/// A machine's status changed. Payload: MachineStatusChanged
+pub const MACHINE_STATUS_EVENT: &str = "machine:status";
+
+/// Maintenance records were created, updated or deleted. Payload: DataChanged
+pub const MAINTENANCE_CHANGED_EVENT: &str = "maintenance:changed";
+
+/// Alerts were created, read, dismissed or cleared. Payload: DataChanged.
+/// Unlike alert:created, this goes out for every change, whatever the
+/// user's notification settings.
+pub const ALERT_CHANGED_EVENT: &str = "alert:changed";
+
+pub const ACTION_CREATED: &str = "created";
+pub const ACTION_UPDATED: &str = "updated";
+pub const ACTION_DELETED: &str = "deleted";
+
+/// Payload of the *:changed events, e.g. `{ "action": "created", "ids": [12, 13] }`.
+/// A command that changes several rows sends one event listing all of them.
+#[derive(Debug, Clone, Serialize)]
+pub struct DataChanged {
+    /// "created", "updated" or "deleted"
+    pub action: &'static str,
+    pub ids: Vec<i64>,
+}
+
+/// Payload of machine:status, e.g. `{ "machine_id": 3, "status": "maintenance" }`
+#[derive(Debug, Clone, Serialize)]
+pub struct MachineStatusChanged {
+    pub machine_id: i64,
+    pub status: String,
+}
+
+/// Tell open views that rows changed so they can refresh. Call only after the
+/// change is committed; nothing is sent when `ids` is empty.
+pub fn emit_change(app: &AppHandle, event: &str, action: &'static str, ids: Vec<i64>) {
+    if ids.is_empty() {
+        return;
+    }
+    if let Err(e) = app.emit(event, DataChanged { action, ids }) {
+        log::warn!("Failed to emit {}: {}", event, e);
+    }
+}
+
+pub fn emit_machine_status(app: &AppHandle, machine_id: i64, status: &str) {
+    let payload = MachineStatusChanged { machine_id, status: status.to_string() };
+    if let Err(e) = app.emit(MACHINE_STATUS_EVENT, payload) {
+        log::warn!("Failed to emit {}: {}", MACHINE_STATUS_EVENT, e);
+    }
+}
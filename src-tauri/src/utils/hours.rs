@@ -0,0 +1,194 @@
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use super::settings::{get_float, DEFAULT_HOURS_INCREMENT, HOURS_INCREMENT_KEY};
+
+/// Maximum hours accepted for a single entry
+pub const MAX_HOURS_PER_ENTRY: f64 = 24.0;
+
+/// Hour value as sent by the frontend or an import: either a number or free text
+/// such as "11.5", "11,5" or "11:30"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum HoursValue {
+    Number(f64),
+    Text(String),
+}
+
+impl From<f64> for HoursValue {
+    fn from(value: f64) -> Self {
+        HoursValue::Number(value)
+    }
+}
+
+/// Parse an hour value written as decimal (dot or comma) or H:MM notation
+pub fn parse_hours(input: &str) -> Result<f64, String> {
+    let text = input.trim();
+    if text.is_empty() {
+        return Err("Hours value is empty".to_string());
+    }
+    if text.starts_with('-') {
+        return Err(format!("Hours cannot be negative (got '{}')", text));
+    }
+
+    let invalid = || {
+        format!(
+            "Invalid hours value '{}': use a number like 11.5, 11,5 or 11:30",
+            text
+        )
+    };
+
+    if let Some((h, m)) = text.split_once(':') {
+        let hours: u32 = h.trim().parse().map_err(|_| invalid())?;
+        let m = m.trim();
+        if m.is_empty() || m.len() > 2 {
+            return Err(invalid());
+        }
+        let minutes: u32 = m.parse().map_err(|_| invalid())?;
+        if minutes >= 60 {
+            return Err(format!("Invalid minutes in '{}': must be between 00 and 59", text));
+        }
+        return Ok(hours as f64 + minutes as f64 / 60.0);
+    }
+
+    if text.matches([',', '.']).count() > 1 {
+        return Err(invalid());
+    }
+    let value: f64 = text.replace(',', ".").parse().map_err(|_| invalid())?;
+    if !value.is_finite() {
+        return Err(invalid());
+    }
+    Ok(value)
+}
+
+/// Validate an hour value and round it to the given increment
+pub fn round_hours(value: f64, increment: f64) -> Result<f64, String> {
+    if !value.is_finite() {
+        return Err("Hours must be a number".to_string());
+    }
+    if value < 0.0 {
+        return Err(format!("Hours cannot be negative (got {})", value));
+    }
+    if value > MAX_HOURS_PER_ENTRY {
+        return Err(format!(
+            "Hours cannot exceed {} per entry (got {})",
+            MAX_HOURS_PER_ENTRY, value
+        ));
+    }
+
+    let rounded = if increment > 0.0 {
+        (value / increment).round() * increment
+    } else {
+        value
+    };
+    // Strip floating point noise such as 11.499999999
+    Ok((rounded * 100.0).round() / 100.0)
+}
+
+/// Parse, validate and round an hour value using the configured increment
+pub fn normalize_hours(conn: &Connection, value: &HoursValue) -> Result<f64, String> {
+    let raw = match value {
+        HoursValue::Number(n) => *n,
+        HoursValue::Text(text) => parse_hours(text)?,
+    };
+    let increment = get_float(conn, HOURS_INCREMENT_KEY, DEFAULT_HOURS_INCREMENT);
+    round_hours(raw, increment)
+}
+
+/// Normalize an optional hour value, passing `None` through
+pub fn normalize_optional_hours(
+    conn: &Connection,
+    value: &Option<HoursValue>,
+) -> Result<Option<f64>, String> {
+    value.as_ref().map(|v| normalize_hours(conn, v)).transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn_with_increment(increment: Option<&str>) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT NOT NULL)").unwrap();
+        if let Some(increment) = increment {
+            conn.execute(
+                "INSERT INTO settings (key, value) VALUES (?1, ?2)",
+                [HOURS_INCREMENT_KEY, increment],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    #[test]
+    fn parses_dot_and_comma_decimals() {
+        assert_eq!(parse_hours("11.5"), Ok(11.5));
+        assert_eq!(parse_hours("11,5"), Ok(11.5));
+        assert_eq!(parse_hours(" 8 "), Ok(8.0));
+        assert_eq!(parse_hours(",5"), Ok(0.5));
+    }
+
+    #[test]
+    fn parses_hours_and_minutes() {
+        assert_eq!(parse_hours("11:30"), Ok(11.5));
+        assert_eq!(parse_hours("0:45"), Ok(0.75));
+        assert_eq!(parse_hours("24:00"), Ok(24.0));
+        assert!(parse_hours("11:60").is_err());
+        assert!(parse_hours("11:").is_err());
+        assert!(parse_hours("11:300").is_err());
+        assert!(parse_hours(":30").is_err());
+    }
+
+    #[test]
+    fn rejects_negative_and_garbage_text() {
+        assert!(parse_hours("-1").is_err());
+        assert!(parse_hours("-0:30").is_err());
+        assert!(parse_hours("").is_err());
+        assert!(parse_hours("   ").is_err());
+        assert!(parse_hours("abc").is_err());
+        assert!(parse_hours("1.2.3").is_err());
+        assert!(parse_hours("1,5.0").is_err());
+        assert!(parse_hours("NaN").is_err());
+        assert!(parse_hours("inf").is_err());
+    }
+
+    #[test]
+    fn rounds_to_increment_within_bounds() {
+        assert_eq!(round_hours(0.0, 0.25), Ok(0.0));
+        assert_eq!(round_hours(24.0, 0.25), Ok(24.0));
+        assert_eq!(round_hours(7.1, 0.25), Ok(7.0));
+        assert_eq!(round_hours(7.2, 0.25), Ok(7.25));
+        assert_eq!(round_hours(7.3, 0.5), Ok(7.5));
+        assert_eq!(round_hours(7.123, 0.0), Ok(7.12));
+    }
+
+    #[test]
+    fn rejects_values_out_of_range() {
+        assert!(round_hours(-0.25, 0.25).is_err());
+        assert!(round_hours(24.01, 0.25).is_err());
+        assert!(round_hours(f64::NAN, 0.25).is_err());
+        assert!(round_hours(f64::INFINITY, 0.25).is_err());
+    }
+
+    #[test]
+    fn normalizes_with_configured_increment() {
+        let conn = conn_with_increment(None);
+        assert_eq!(normalize_hours(&conn, &HoursValue::Text("7,1".to_string())), Ok(7.0));
+        assert_eq!(normalize_hours(&conn, &HoursValue::Text("7:20".to_string())), Ok(7.25));
+        assert_eq!(normalize_hours(&conn, &HoursValue::Number(24.0)), Ok(24.0));
+        assert_eq!(normalize_hours(&conn, &HoursValue::Number(0.0)), Ok(0.0));
+
+        let conn = conn_with_increment(Some("0.5"));
+        assert_eq!(normalize_hours(&conn, &HoursValue::Text("7.3".to_string())), Ok(7.5));
+        assert_eq!(normalize_optional_hours(&conn, &None), Ok(None));
+    }
+
+    #[test]
+    fn normalize_rejects_bad_input() {
+        let conn = conn_with_increment(None);
+        assert!(normalize_hours(&conn, &HoursValue::Number(-1.0)).is_err());
+        assert!(normalize_hours(&conn, &HoursValue::Number(25.0)).is_err());
+        assert!(normalize_hours(&conn, &HoursValue::Text("24:30".to_string())).is_err());
+        assert!(normalize_hours(&conn, &HoursValue::Text("eight".to_string())).is_err());
+    }
+}
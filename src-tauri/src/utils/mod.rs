@@ -1,5 +1,25 @@
+pub mod audit;
 pub mod auth;
+pub mod csv;
+pub mod dates;
+pub mod events;
+pub mod hours;
+pub mod mutation;
+pub mod operations;
+pub mod pagination;
 pub mod permissions;
+pub mod settings;
+pub mod totp;
 
+pub use audit::*;
 pub use auth::*;
+pub use csv::*;
+pub use dates::*;
+pub use events::*;
+pub use hours::*;
+pub use mutation::*;
+pub use operations::*;
+pub use pagination::*;
 pub use permissions::*;
+pub use settings::*;
+pub use totp::*;
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+/// Outcome of a write command that touches several rows, so the frontend can
+/// update its cache without refetching
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MutationResult<T> {
+    /// Number of rows created or changed, for callers that only need a count
+    pub count: i64,
+    /// Ids of every row created, changed or removed
+    pub affected_ids: Vec<i64>,
+    /// Rows created or changed, in their new state
+    pub rows: Vec<T>,
+    /// Items that were skipped or only partly applied
+    pub warnings: Vec<String>,
+}
+
+impl<T> MutationResult<T> {
+    pub fn new(affected_ids: Vec<i64>, rows: Vec<T>, warnings: Vec<String>) -> Self {
+        Self {
+            count: rows.len() as i64,
+            affected_ids,
+            rows,
+            warnings,
+        }
+    }
+}
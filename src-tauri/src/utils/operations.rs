@@ -0,0 +1,255 @@
+use std::collections::{HashMap, HashSet};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::AppError;
+
+/// Event emitted while a long-running operation makes progress
+pub const OPERATION_PROGRESS_EVENT: &str = "operation:progress";
+
+/// Error returned by a worker that stopped because cancellation was requested
+pub const OPERATION_CANCELLED: &str = "Operation cancelled";
+
+pub const OPERATION_RUNNING: &str = "running";
+pub const OPERATION_COMPLETED: &str = "completed";
+pub const OPERATION_FAILED: &str = "failed";
+pub const OPERATION_CANCELLED_STATE: &str = "cancelled";
+
+/// Default number of items processed between progress events
+pub const DEFAULT_PROGRESS_BATCH: i64 = 50;
+
+/// Finished operations kept around for polling before the oldest are dropped
+const MAX_FINISHED_OPERATIONS: usize = 100;
+
+/// Current state of a long-running operation, as returned to pollers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationStatus {
+    pub operation_id: String,
+    pub kind: String,
+    pub state: String,
+    pub current: i64,
+    pub total: i64,
+    pub message: Option<String>,
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub started_by: i64,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+/// Payload of the `operation:progress` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationProgress {
+    pub operation_id: String,
+    pub kind: String,
+    pub state: String,
+    pub current: i64,
+    pub total: i64,
+    pub message: Option<String>,
+}
+
+/// Result of a command that can run in the background: either the finished
+/// value, or the id of an operation that is still running
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum OperationOutcome<T> {
+    Completed(T),
+    Started { operation_id: String },
+}
+
+/// Managed state tracking every operation started in this session
+#[derive(Default)]
+pub struct OperationRegistry {
+    operations: Mutex<HashMap<String, OperationStatus>>,
+    cancel_requests: Mutex<HashSet<String>>,
+}
+
+impl OperationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn start(&self, kind: &str, started_by: i64) -> String {
+        let operation_id = uuid::Uuid::new_v4().to_string();
+        let status = OperationStatus {
+            operation_id: operation_id.clone(),
+            kind: kind.to_string(),
+            state: OPERATION_RUNNING.to_string(),
+            current: 0,
+            total: 0,
+            message: None,
+            result: None,
+            error: None,
+            started_by,
+            started_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            finished_at: None,
+        };
+        self.operations.lock().insert(operation_id.clone(), status);
+        operation_id
+    }
+
+    pub fn get(&self, operation_id: &str) -> Option<OperationStatus> {
+        self.operations.lock().get(operation_id).cloned()
+    }
+
+    /// Flag an operation for cancellation; returns false if it is not running
+    pub fn request_cancel(&self, operation_id: &str) -> bool {
+        let running = self
+            .operations
+            .lock()
+            .get(operation_id)
+            .is_some_and(|op| op.state == OPERATION_RUNNING);
+        if running {
+            self.cancel_requests.lock().insert(operation_id.to_string());
+        }
+        running
+    }
+
+    fn is_cancel_requested(&self, operation_id: &str) -> bool {
+        self.cancel_requests.lock().contains(operation_id)
+    }
+
+    fn update(&self, operation_id: &str, current: i64, total: i64, message: Option<String>) -> Option<OperationStatus> {
+        let mut operations = self.operations.lock();
+        let op = operations.get_mut(operation_id)?;
+        op.current = current;
+        op.total = total;
+        op.message = message;
+        Some(op.clone())
+    }
+
+    fn finish(&self, operation_id: &str, outcome: Result<serde_json::Value, String>) -> Option<OperationStatus> {
+        self.cancel_requests.lock().remove(operation_id);
+        let mut operations = self.operations.lock();
+
+        let op = operations.get_mut(operation_id)?;
+        match outcome {
+            Ok(value) => {
+                op.state = OPERATION_COMPLETED.to_string();
+                op.current = op.total.max(op.current);
+                op.result = Some(value);
+            }
+            Err(e) if e == OPERATION_CANCELLED => {
+                op.state = OPERATION_CANCELLED_STATE.to_string();
+                op.error = Some(e);
+            }
+            Err(e) => {
+                op.state = OPERATION_FAILED.to_string();
+                op.error = Some(e);
+            }
+        }
+        op.finished_at = Some(chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string());
+        let finished = op.clone();
+
+        // Drop the oldest finished operations once there are too many
+        let mut done: Vec<(String, String)> = operations
+            .values()
+            .filter_map(|o| o.finished_at.clone().map(|at| (at, o.operation_id.clone())))
+            .collect();
+        if done.len() > MAX_FINISHED_OPERATIONS {
+            done.sort();
+            for (_, id) in done.iter().take(done.len() - MAX_FINISHED_OPERATIONS) {
+                operations.remove(id);
+            }
+        }
+
+        Some(finished)
+    }
+}
+
+/// Handle given to an operation's worker for reporting progress and checking cancellation
+pub struct OperationContext {
+    app: AppHandle,
+    operation_id: String,
+    batch_size: i64,
+}
+
+impl OperationContext {
+    pub fn app(&self) -> &AppHandle {
+        &self.app
+    }
+
+    /// Record progress. Events are emitted every `batch_size` items and on the last one.
+    ///
+    /// Returns `Err(OPERATION_CANCELLED)` when a cancel was requested, so workers can
+    /// bail out with `?` and let their open transaction roll back.
+    pub fn progress(&self, current: i64, total: i64, message: &str) -> Result<(), String> {
+        let registry = self.app.state::<OperationRegistry>();
+        if let Some(status) = registry.update(&self.operation_id, current, total, Some(message.to_string())) {
+            if current == 0 || current >= total || current % self.batch_size == 0 {
+                emit_progress(&self.app, &status);
+            }
+        }
+        if registry.is_cancel_requested(&self.operation_id) {
+            return Err(OPERATION_CANCELLED.to_string());
+        }
+        Ok(())
+    }
+}
+
+fn emit_progress(app: &AppHandle, status: &OperationStatus) {
+    let payload = OperationProgress {
+        operation_id: status.operation_id.clone(),
+        kind: status.kind.clone(),
+        state: status.state.clone(),
+        current: status.current,
+        total: status.total,
+        message: status.message.clone(),
+    };
+    if let Err(e) = app.emit(OPERATION_PROGRESS_EVENT, payload) {
+        log::warn!("Failed to emit operation progress: {}", e);
+    }
+}
+
+/// Run a long operation, either inline or on the async runtime.
+///
+/// In the foreground the worker's result is returned directly. With `background`
+/// set the operation id is returned immediately and the result is stored in the
+/// registry for `get_operation_status`. Workers must lock the database themselves,
+/// so callers should release their own lock before calling this.
+pub fn run_operation<T, F>(
+    app: &AppHandle,
+    kind: &str,
+    started_by: i64,
+    background: bool,
+    work: F,
+) -> Result<OperationOutcome<T>, AppError>
+where
+    T: Serialize + Send + 'static,
+    F: FnOnce(&OperationContext) -> Result<T, String> + Send + 'static,
+{
+    let operation_id = app.state::<OperationRegistry>().start(kind, started_by);
+    let context = OperationContext {
+        app: app.clone(),
+        operation_id: operation_id.clone(),
+        batch_size: DEFAULT_PROGRESS_BATCH,
+    };
+
+    if background {
+        tauri::async_runtime::spawn_blocking(move || {
+            let result = work(&context);
+            complete(&context, result.and_then(|value| serde_json::to_value(value).map_err(|e| e.to_string())));
+        });
+        return Ok(OperationOutcome::Started { operation_id });
+    }
+
+    match work(&context) {
+        Ok(value) => {
+            complete(&context, serde_json::to_value(&value).map_err(|e| e.to_string()));
+            Ok(OperationOutcome::Completed(value))
+        }
+        Err(e) => {
+            complete(&context, Err(e.clone()));
+            Err(e.into())
+        }
+    }
+}
+
+fn complete(context: &OperationContext, outcome: Result<serde_json::Value, String>) {
+    let registry = context.app.state::<OperationRegistry>();
+    if let Some(status) = registry.finish(&context.operation_id, outcome) {
+        emit_progress(&context.app, &status);
+    }
+}
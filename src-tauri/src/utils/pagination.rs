@@ -0,0 +1,172 @@
+use rusqlite::{Connection, ToSql};
+use serde::{Deserialize, Serialize};
+
+use super::settings::{get_int, DEFAULT_MAX_PAGE_SIZE, MAX_PAGE_SIZE_KEY};
+
+/// Validated limit/offset pair, always bound as SQL parameters
+#[derive(Debug, Clone, Copy)]
+pub struct PageRequest {
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl PageRequest {
+    /// Validate caller-supplied paging. Negative values are rejected, a missing limit
+    /// uses `default_limit` (or the maximum when `None`), and every limit is clamped
+    /// to the configured maximum page size.
+    pub fn resolve(
+        conn: &Connection,
+        limit: Option<i64>,
+        offset: Option<i64>,
+        default_limit: Option<i64>,
+    ) -> Result<Self, String> {
+        let max_limit = get_int(conn, MAX_PAGE_SIZE_KEY, DEFAULT_MAX_PAGE_SIZE).max(1);
+
+        let limit = match limit {
+            Some(l) if l < 0 => return Err("Validation error: limit must not be negative".to_string()),
+            Some(l) => l,
+            None => default_limit.unwrap_or(max_limit),
+        };
+        let offset = match offset {
+            Some(o) if o < 0 => return Err("Validation error: offset must not be negative".to_string()),
+            Some(o) => o,
+            None => 0,
+        };
+
+        Ok(Self {
+            limit: limit.min(max_limit),
+            offset,
+        })
+    }
+
+    /// `LIMIT ? OFFSET ?` clause to append to a query bound with `params`
+    pub fn sql(&self) -> &'static str {
+        " LIMIT ? OFFSET ?"
+    }
+
+    /// Append the limit and offset to a query's bound parameters
+    pub fn bind(&self, params: &mut Vec<Box<dyn ToSql>>) {
+        params.push(Box::new(self.limit));
+        params.push(Box::new(self.offset));
+    }
+}
+
+/// Paging metadata returned with every list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageInfo {
+    pub limit: i64,
+    pub offset: i64,
+    pub total: i64,
+    pub has_more: bool,
+}
+
+/// One page of a list command's results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    pub pagination: PageInfo,
+}
+
+impl<T> Paginated<T> {
+    pub fn new(items: Vec<T>, page: PageRequest, total: i64) -> Self {
+        let has_more = page.offset + (items.len() as i64) < total;
+        Self {
+            items,
+            pagination: PageInfo {
+                limit: page.limit,
+                offset: page.offset,
+                total,
+                has_more,
+            },
+        }
+    }
+}
+
+/// Run a `SELECT COUNT(*)` query for the total behind a page
+pub fn count_rows(conn: &Connection, sql: &str, params: &[&dyn ToSql]) -> Result<i64, String> {
+    conn.query_row(sql, params, |row| row.get(0))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::params;
+
+    fn conn_with_max_page_size(max: Option<i64>) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+             CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+             INSERT INTO items (name) VALUES ('a'), ('b'), ('c'), ('d'), ('e');",
+        )
+        .unwrap();
+        if let Some(max) = max {
+            conn.execute(
+                "INSERT INTO settings (key, value) VALUES (?1, ?2)",
+                params![MAX_PAGE_SIZE_KEY, max.to_string()],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    fn page_of_ids(conn: &Connection, page: PageRequest) -> Vec<i64> {
+        let mut params: Vec<Box<dyn ToSql>> = Vec::new();
+        page.bind(&mut params);
+        let sql = format!("SELECT id FROM items ORDER BY id{}", page.sql());
+        let mut stmt = conn.prepare(&sql).unwrap();
+        let refs: Vec<&dyn ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        stmt.query_map(refs.as_slice(), |row| row.get(0)).unwrap().map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn clamps_limit_to_default_maximum() {
+        let conn = conn_with_max_page_size(None);
+        let page = PageRequest::resolve(&conn, Some(10_000_000), None, None).unwrap();
+        assert_eq!((page.limit, page.offset), (DEFAULT_MAX_PAGE_SIZE, 0));
+
+        let page = PageRequest::resolve(&conn, None, Some(20), None).unwrap();
+        assert_eq!((page.limit, page.offset), (DEFAULT_MAX_PAGE_SIZE, 20));
+
+        let page = PageRequest::resolve(&conn, None, None, Some(50)).unwrap();
+        assert_eq!(page.limit, 50);
+    }
+
+    #[test]
+    fn clamps_limit_to_configured_maximum() {
+        let conn = conn_with_max_page_size(Some(2));
+        assert_eq!(PageRequest::resolve(&conn, Some(3), None, None).unwrap().limit, 2);
+        assert_eq!(PageRequest::resolve(&conn, None, None, Some(100)).unwrap().limit, 2);
+        assert_eq!(PageRequest::resolve(&conn, Some(1), None, None).unwrap().limit, 1);
+
+        // A nonsensical maximum still allows one row per page
+        let conn = conn_with_max_page_size(Some(-5));
+        assert_eq!(PageRequest::resolve(&conn, Some(10), None, None).unwrap().limit, 1);
+    }
+
+    #[test]
+    fn rejects_negative_values() {
+        let conn = conn_with_max_page_size(None);
+        let error = PageRequest::resolve(&conn, Some(-1), None, None).unwrap_err();
+        assert!(error.starts_with("Validation error"));
+        let error = PageRequest::resolve(&conn, None, Some(-1), None).unwrap_err();
+        assert!(error.starts_with("Validation error"));
+    }
+
+    #[test]
+    fn binds_limit_and_offset_as_parameters() {
+        let conn = conn_with_max_page_size(None);
+        assert_eq!(page_of_ids(&conn, PageRequest { limit: 2, offset: 1 }), vec![2, 3]);
+        assert_eq!(page_of_ids(&conn, PageRequest { limit: 0, offset: 0 }), Vec::<i64>::new());
+        assert_eq!(page_of_ids(&conn, PageRequest { limit: 500, offset: i64::MAX }), Vec::<i64>::new());
+        assert_eq!(PageRequest { limit: 1, offset: 0 }.sql(), " LIMIT ? OFFSET ?");
+    }
+
+    #[test]
+    fn reports_has_more() {
+        let page = PageRequest { limit: 2, offset: 2 };
+        assert!(Paginated::new(vec![3, 4], page, 5).pagination.has_more);
+        assert!(!Paginated::new(vec![5], PageRequest { limit: 2, offset: 4 }, 5).pagination.has_more);
+    }
+}
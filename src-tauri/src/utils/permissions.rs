@@ -1,32 +1,141 @@
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::error::AppError;
 use crate::models::User;
+use crate::utils::audit::record_auth_event;
 
 /// Check if user has required role
-pub fn check_role(user: &User, required_roles: &[&str]) -> Result<(), String> {
+pub fn check_role(user: &User, required_roles: &[&str]) -> Result<(), AppError> {
     if required_roles.contains(&user.role.as_str()) {
         Ok(())
     } else {
-        Err(format!(
+        Err(AppError::Forbidden(format!(
             "Permission denied. Required role: {:?}, your role: {}",
             required_roles, user.role
-        ))
+        )))
     }
 }
 
-/// Check if user is admin
-pub fn require_admin(user: &User) -> Result<(), String> {
-    check_role(user, &["Admin"])
+/// Audit a refused action, then hand back the refusal
+fn record_denial(conn: &Connection, user: &User, required: &str, error: AppError) -> AppError {
+    let details = serde_json::json!({ "required": required, "role": user.role });
+    record_auth_event(conn, Some(user.id), &user.username, "permission_denied", Some(&details));
+    error
 }
 
-/// Check if user can edit (admin or operator)
-pub fn require_edit_permission(user: &User) -> Result<(), String> {
-    check_role(user, &["Admin", "Operator"])
+/// Check if user is admin; a refusal is audited
+pub fn require_admin(conn: &Connection, user: &User) -> Result<(), AppError> {
+    check_role(user, ADMIN).map_err(|e| record_denial(conn, user, "Admin", e))
 }
 
 /// Check if user can view (all roles)
-pub fn require_view_permission(user: &User) -> Result<(), String> {
+pub fn require_view_permission(user: &User) -> Result<(), AppError> {
     check_role(user, &["Admin", "Operator", "Viewer"])
 }
 
+const ADMIN: &[&str] = &["Admin"];
+const EDITORS: &[&str] = &["Admin", "Operator"];
+
+/// A permission that can be granted or revoked per role or per user
+#[derive(Debug, Clone, Copy)]
+pub struct PermissionDef {
+    pub key: &'static str,
+    pub description: &'static str,
+    /// Roles that hold the permission when nothing is stored for it
+    pub default_roles: &'static [&'static str],
+}
+
+const fn permission(
+    key: &'static str,
+    description: &'static str,
+    default_roles: &'static [&'static str],
+) -> PermissionDef {
+    PermissionDef { key, description, default_roles }
+}
+
+/// Every permission checked by `require_permission`. The defaults reproduce
+/// the fixed Admin/Operator/Viewer rules that came before. User management,
+/// sessions, the audit log and the permissions themselves stay Admin-only and
+/// are not listed, so no toggle can hand them out.
+pub const PERMISSIONS: &[PermissionDef] = &[
+    permission("alerts.manage", "Create and dismiss alerts", EDITORS),
+    permission("alerts.clear", "Delete alerts everyone has read, and old alerts", ADMIN),
+    permission("alerts.acknowledge", "Acknowledge alerts with a comment", EDITORS),
+    permission("checklists.edit", "Manage checklist templates and submit checklists", EDITORS),
+    permission("clients.manage", "Create, edit, archive and import clients and their contacts", ADMIN),
+    permission("clients.delete", "Delete clients", ADMIN),
+    permission("downtime.edit", "Record and close machine downtime", EDITORS),
+    permission("downtime.delete", "Delete downtime records", EDITORS),
+    permission("holidays.manage", "Manage holidays", ADMIN),
+    permission("locations.manage", "Manage shop-floor locations", ADMIN),
+    permission("machines.create", "Create, clone and import machines", ADMIN),
+    permission("machines.edit", "Edit machines, their status and documents", EDITORS),
+    permission("machines.manage", "Archive machines, set runtime hours and apply status reconciliation", ADMIN),
+    permission("machines.delete", "Delete machines", ADMIN),
+    permission("maintenance.edit", "Schedule and update maintenance", EDITORS),
+    permission("maintenance.delete", "Delete maintenance records", EDITORS),
+    permission("projects.create", "Create and clone projects", ADMIN),
+    permission("projects.edit", "Edit projects and change their status", EDITORS),
+    permission("projects.prioritize", "Change project priority", ADMIN),
+    permission("projects.assign", "Assign machines and team members to projects", ADMIN),
+    permission("projects.archive", "Archive and unarchive projects", ADMIN),
+    permission("projects.delete", "Delete projects", ADMIN),
+    permission("projects.log_hours", "Log, correct and recalculate project hours and progress", EDITORS),
+    permission("projects.details", "Manage project tasks, milestones, materials and notes", EDITORS),
+    permission("projects.costs", "View and edit project costs and financials", EDITORS),
+    permission("projects.budget", "Set project budgets", ADMIN),
+    permission("reports.overtime", "View the overtime report", EDITORS),
+    permission("reports.overtime_all", "View overtime for every operator, not just one's own", ADMIN),
+    permission("schedules.edit", "Create, edit, move and copy schedule entries and tags", EDITORS),
+    permission("schedules.log_hours", "Log actual hours and shift logs", EDITORS),
+    permission("schedules.delete", "Delete schedule entries", EDITORS),
+    permission("schedules.view_all", "View other operators' weekly schedules", EDITORS),
+    permission("schedules.override_lock", "Edit entries older than the edit cutoff, with a reason", ADMIN),
+    permission("shifts.manage", "Manage shifts", ADMIN),
+    permission("sites.manage", "Manage sites", ADMIN),
+    permission("weeks.lock", "Lock and unlock schedule weeks", ADMIN),
+];
+
+/// Look up a permission in the catalog
+pub fn find_permission(key: &str) -> Option<&'static PermissionDef> {
+    PERMISSIONS.iter().find(|p| p.key == key)
+}
+
+/// Whether the user holds a permission. A per-user entry wins over a per-role
+/// one, which wins over the catalog default for the role. Keys missing from
+/// the catalog are Admin-only.
+pub fn has_permission(conn: &Connection, user: &User, key: &str) -> Result<bool, String> {
+    let stored: Option<bool> = conn
+        .query_row(
+            "SELECT allowed FROM role_permissions
+             WHERE permission = ?1 AND (user_id = ?2 OR role = ?3)
+             ORDER BY user_id IS NULL LIMIT 1",
+            params![key, user.id, user.role],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    Ok(stored.unwrap_or_else(|| match find_permission(key) {
+        Some(permission) => permission.default_roles.contains(&user.role.as_str()),
+        None => user.is_admin(),
+    }))
+}
+
+/// Check that the user holds a permission (see `has_permission`); a refusal
+/// is audited
+pub fn require_permission(conn: &Connection, user: &User, key: &str) -> Result<(), AppError> {
+    if has_permission(conn, user, key)? {
+        Ok(())
+    } else {
+        let error = AppError::Forbidden(format!(
+            "Permission denied. Missing permission: {}, your role: {}",
+            key, user.role
+        ));
+        Err(record_denial(conn, user, key, error))
+    }
+}
+
 /// Role enum for type safety
 #[derive(Debug, Clone, PartialEq)]
 pub enum Role {
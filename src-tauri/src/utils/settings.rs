@@ -0,0 +1,296 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Rounding increment (in hours) applied to entered hour values; 0 disables rounding
+pub const HOURS_INCREMENT_KEY: &str = "hours_increment";
+pub const DEFAULT_HOURS_INCREMENT: f64 = 0.25;
+
+/// Maximum planned hours (including setup) per machine per day
+pub const MACHINE_DAILY_CAPACITY_KEY: &str = "machine_daily_capacity_hours";
+pub const DEFAULT_MACHINE_DAILY_CAPACITY: f64 = 24.0;
+
+/// Planned hours per operator per day above which a warning is raised
+pub const OPERATOR_DAILY_LIMIT_KEY: &str = "operator_daily_limit_hours";
+pub const DEFAULT_OPERATOR_DAILY_LIMIT: f64 = 12.0;
+
+/// Shop working hours (HH:MM); the default covers the whole day
+pub const WORKING_HOURS_START_KEY: &str = "working_hours_start";
+pub const WORKING_HOURS_END_KEY: &str = "working_hours_end";
+pub const DEFAULT_WORKING_HOURS_START: &str = "00:00";
+pub const DEFAULT_WORKING_HOURS_END: &str = "24:00";
+
+/// Hours of actual above planned before an entry shows up in the overtime report
+pub const OVERTIME_THRESHOLD_KEY: &str = "overtime_threshold_hours";
+pub const DEFAULT_OVERTIME_THRESHOLD: f64 = 0.5;
+
+/// Largest page size list commands will return, whatever the caller asks for
+pub const MAX_PAGE_SIZE_KEY: &str = "max_page_size";
+pub const DEFAULT_MAX_PAGE_SIZE: i64 = 500;
+
+/// Days after which schedule entries are locked against edits; 0 disables the lock
+pub const SCHEDULE_EDIT_CUTOFF_KEY: &str = "schedule_edit_cutoff_days";
+pub const DEFAULT_SCHEDULE_EDIT_CUTOFF: i64 = 45;
+
+/// Days after which clearing read alerts also removes alerts some users
+/// haven't read yet
+pub const CLEAR_ALERTS_AFTER_DAYS_KEY: &str = "clear_alerts_after_days";
+pub const DEFAULT_CLEAR_ALERTS_AFTER_DAYS: i64 = 30;
+
+/// bcrypt work factor for new password hashes, clamped to a safe range
+pub const BCRYPT_COST_KEY: &str = "bcrypt_cost";
+pub const DEFAULT_BCRYPT_COST: i64 = bcrypt::DEFAULT_COST as i64;
+pub const MIN_BCRYPT_COST: i64 = 10;
+pub const MAX_BCRYPT_COST: i64 = 14;
+
+/// Hours a new session lasts. Admin sessions have their own, shorter
+/// lifetime; remember-me sessions for other roles last days instead.
+pub const SESSION_LIFETIME_HOURS_KEY: &str = "session_lifetime_hours";
+pub const DEFAULT_SESSION_LIFETIME_HOURS: i64 = 24;
+pub const ADMIN_SESSION_LIFETIME_HOURS_KEY: &str = "admin_session_lifetime_hours";
+pub const DEFAULT_ADMIN_SESSION_LIFETIME_HOURS: i64 = 8;
+pub const REMEMBER_ME_LIFETIME_DAYS_KEY: &str = "remember_me_lifetime_days";
+pub const DEFAULT_REMEMBER_ME_LIFETIME_DAYS: i64 = 30;
+
+/// Valid sessions a user may hold at once; the oldest are signed out when a
+/// login goes over the cap. 0 disables the cap.
+pub const MAX_SESSIONS_PER_USER_KEY: &str = "max_sessions_per_user";
+pub const DEFAULT_MAX_SESSIONS_PER_USER: i64 = 10;
+
+/// First day of the week for the planner, week locks, conflict sweeps and weekly totals
+pub const WEEK_START_DAY_KEY: &str = "week_start_day";
+pub const DEFAULT_WEEK_START_DAY: &str = "monday";
+
+pub const WEEKDAY_NAMES: [&str; 7] = ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"];
+
+/// Set while the sample data from load_demo_data is in the database, so the
+/// dashboard can say so
+pub const DEMO_DATA_LOADED_KEY: &str = "demo_data_loaded";
+
+/// Read a raw setting value, if present
+pub fn get_setting(conn: &Connection, key: &str) -> Option<String> {
+    conn.query_row("SELECT value FROM settings WHERE key = ?1", [key], |row| row.get(0))
+        .optional()
+        .ok()
+        .flatten()
+}
+
+/// Read a floating point setting, falling back to the default when missing or malformed
+pub fn get_float(conn: &Connection, key: &str, default: f64) -> f64 {
+    get_setting(conn, key)
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|v| v.is_finite())
+        .unwrap_or(default)
+}
+
+/// Read an integer setting, falling back to the default when missing or malformed
+pub fn get_int(conn: &Connection, key: &str, default: i64) -> i64 {
+    get_setting(conn, key)
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .unwrap_or(default)
+}
+
+/// Read a string setting, falling back to the default when missing
+pub fn get_string(conn: &Connection, key: &str, default: &str) -> String {
+    get_setting(conn, key).unwrap_or_else(|| default.to_string())
+}
+
+/// Read a boolean setting ("true"/"false" or 1/0), falling back to the default when missing or malformed
+pub fn get_bool(conn: &Connection, key: &str, default: bool) -> bool {
+    match get_setting(conn, key).as_deref().map(str::trim) {
+        Some("true") | Some("1") => true,
+        Some("false") | Some("0") => false,
+        _ => default,
+    }
+}
+
+/// Store a setting, creating it if missing
+pub fn set_setting(
+    conn: &Connection,
+    key: &str,
+    value: &str,
+    value_type: &str,
+    updated_by: Option<i64>,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO settings (key, value, value_type, updated_by) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(key) DO UPDATE SET
+             value = excluded.value, value_type = excluded.value_type,
+             updated_by = excluded.updated_by, updated_at = CURRENT_TIMESTAMP",
+        params![key, value, value_type, updated_by],
+    )
+    .map_err(|e| format!("Failed to save setting {}: {}", key, e))?;
+    Ok(())
+}
+
+/// How values of an editable setting are checked
+#[derive(Debug, Clone, Copy)]
+pub enum SettingKind {
+    Int { min: i64, max: i64 },
+    Float { min: f64, max: f64 },
+    /// HH:MM, with 24:00 for the end of the day
+    Time,
+    Weekday,
+}
+
+/// A setting Admins can change through update_setting
+#[derive(Debug, Clone)]
+pub struct SettingDefinition {
+    pub key: &'static str,
+    pub kind: SettingKind,
+    pub default: String,
+    pub description: &'static str,
+}
+
+impl SettingDefinition {
+    fn new(key: &'static str, kind: SettingKind, default: impl ToString, description: &'static str) -> Self {
+        Self { key, kind, default: default.to_string(), description }
+    }
+
+    pub fn value_type(&self) -> &'static str {
+        match self.kind {
+            SettingKind::Int { .. } => "integer",
+            SettingKind::Float { .. } => "float",
+            SettingKind::Time | SettingKind::Weekday => "string",
+        }
+    }
+
+    /// Check `value` against the setting's kind and range, returning it in stored form
+    pub fn normalize(&self, value: &str) -> Result<String, String> {
+        let value = value.trim();
+        match self.kind {
+            SettingKind::Int { min, max } => match value.parse::<i64>() {
+                Ok(v) if (min..=max).contains(&v) => Ok(v.to_string()),
+                _ => Err(format!("must be a whole number from {} to {}", min, max)),
+            },
+            SettingKind::Float { min, max } => match value.parse::<f64>() {
+                Ok(v) if v.is_finite() && (min..=max).contains(&v) => Ok(v.to_string()),
+                _ => Err(format!("must be a number from {} to {}", min, max)),
+            },
+            SettingKind::Time => match NaiveTime::parse_from_str(value, "%H:%M") {
+                Ok(time) => Ok(time.format("%H:%M").to_string()),
+                Err(_) if value == "24:00" => Ok(value.to_string()),
+                Err(_) => Err("must be a time in HH:MM format".to_string()),
+            },
+            SettingKind::Weekday => value
+                .parse::<Weekday>()
+                .map(|day| WEEKDAY_NAMES[day.num_days_from_monday() as usize].to_string())
+                .map_err(|_| "must be a day of the week, e.g. monday".to_string()),
+        }
+    }
+}
+
+/// Every setting Admins can change, with its default and allowed values.
+/// Internal flags such as DEMO_DATA_LOADED_KEY are not listed.
+pub fn setting_definitions() -> Vec<SettingDefinition> {
+    use SettingKind::*;
+    vec![
+        SettingDefinition::new(
+            SESSION_LIFETIME_HOURS_KEY,
+            Int { min: 1, max: 720 },
+            DEFAULT_SESSION_LIFETIME_HOURS,
+            "Hours a sign-in lasts for Operators and Viewers",
+        ),
+        SettingDefinition::new(
+            ADMIN_SESSION_LIFETIME_HOURS_KEY,
+            Int { min: 1, max: 720 },
+            DEFAULT_ADMIN_SESSION_LIFETIME_HOURS,
+            "Hours an Admin sign-in lasts",
+        ),
+        SettingDefinition::new(
+            REMEMBER_ME_LIFETIME_DAYS_KEY,
+            Int { min: 1, max: 365 },
+            DEFAULT_REMEMBER_ME_LIFETIME_DAYS,
+            "Days a remember-me sign-in lasts",
+        ),
+        SettingDefinition::new(
+            MAX_SESSIONS_PER_USER_KEY,
+            Int { min: 0, max: 100 },
+            DEFAULT_MAX_SESSIONS_PER_USER,
+            "Sign-ins a user may hold at once; 0 for no limit",
+        ),
+        SettingDefinition::new(
+            BCRYPT_COST_KEY,
+            Int { min: MIN_BCRYPT_COST, max: MAX_BCRYPT_COST },
+            DEFAULT_BCRYPT_COST,
+            "Work factor for new password hashes",
+        ),
+        SettingDefinition::new(
+            HOURS_INCREMENT_KEY,
+            Float { min: 0.0, max: 4.0 },
+            DEFAULT_HOURS_INCREMENT,
+            "Hours entered are rounded to this increment; 0 turns rounding off",
+        ),
+        SettingDefinition::new(
+            MACHINE_DAILY_CAPACITY_KEY,
+            Float { min: 1.0, max: 24.0 },
+            DEFAULT_MACHINE_DAILY_CAPACITY,
+            "Planned hours, including setup, a machine can take per day",
+        ),
+        SettingDefinition::new(
+            OPERATOR_DAILY_LIMIT_KEY,
+            Float { min: 1.0, max: 24.0 },
+            DEFAULT_OPERATOR_DAILY_LIMIT,
+            "Planned hours per operator per day before a warning",
+        ),
+        SettingDefinition::new(
+            WORKING_HOURS_START_KEY,
+            Time,
+            DEFAULT_WORKING_HOURS_START,
+            "Start of the shop's working day",
+        ),
+        SettingDefinition::new(
+            WORKING_HOURS_END_KEY,
+            Time,
+            DEFAULT_WORKING_HOURS_END,
+            "End of the shop's working day",
+        ),
+        SettingDefinition::new(
+            OVERTIME_THRESHOLD_KEY,
+            Float { min: 0.0, max: 24.0 },
+            DEFAULT_OVERTIME_THRESHOLD,
+            "Hours over plan before an entry appears in the overtime report",
+        ),
+        SettingDefinition::new(
+            MAX_PAGE_SIZE_KEY,
+            Int { min: 10, max: 5000 },
+            DEFAULT_MAX_PAGE_SIZE,
+            "Most rows a list returns per page",
+        ),
+        SettingDefinition::new(
+            SCHEDULE_EDIT_CUTOFF_KEY,
+            Int { min: 0, max: 3650 },
+            DEFAULT_SCHEDULE_EDIT_CUTOFF,
+            "Days after which schedule entries can no longer be edited; 0 turns the lock off",
+        ),
+        SettingDefinition::new(
+            CLEAR_ALERTS_AFTER_DAYS_KEY,
+            Int { min: 1, max: 3650 },
+            DEFAULT_CLEAR_ALERTS_AFTER_DAYS,
+            "Days after which Clear Read removes an alert even if not everyone has read it",
+        ),
+        SettingDefinition::new(
+            WEEK_START_DAY_KEY,
+            Weekday,
+            DEFAULT_WEEK_START_DAY,
+            "First day of the week for the planner, week locks and weekly totals",
+        ),
+    ]
+}
+
+pub fn setting_definition(key: &str) -> Option<SettingDefinition> {
+    setting_definitions().into_iter().find(|definition| definition.key == key)
+}
+
+/// The configured first day of the week
+pub fn week_start_day(conn: &Connection) -> Weekday {
+    get_string(conn, WEEK_START_DAY_KEY, DEFAULT_WEEK_START_DAY)
+        .parse()
+        .unwrap_or(Weekday::Mon)
+}
+
+/// The first day of the week containing `date`
+pub fn start_of_week(conn: &Connection, date: NaiveDate) -> NaiveDate {
+    let first = week_start_day(conn).num_days_from_monday();
+    let offset = (date.weekday().num_days_from_monday() + 7 - first) % 7;
+    date - Duration::days(offset as i64)
+}
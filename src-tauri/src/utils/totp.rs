@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+/// Issuer shown next to the account in authenticator apps
+const TOTP_ISSUER: &str = "VMC Planner";
+
+const NONCE_LEN: usize = 12;
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| text.get(i..i + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()))
+        .collect()
+}
+
+/// Read the key that seals TOTP secrets, creating it on first use. It lives in
+/// its own file beside the database, so a copied database file or backup does
+/// not carry usable secrets.
+fn load_key(key_path: &Path) -> Result<Key, String> {
+    if key_path.exists() {
+        let text = std::fs::read_to_string(key_path).map_err(|e| format!("Cannot read secret key: {}", e))?;
+        let bytes = from_hex(text.trim()).filter(|b| b.len() == 32).ok_or("Secret key file is corrupt")?;
+        return Ok(*Key::from_slice(&bytes));
+    }
+
+    let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+    std::fs::write(key_path, to_hex(&key)).map_err(|e| format!("Cannot write secret key: {}", e))?;
+    Ok(key)
+}
+
+/// Encrypt a TOTP secret for storage as hex of nonce followed by ciphertext
+pub fn seal_totp_secret(key_path: &Path, secret: &str) -> Result<String, String> {
+    let cipher = ChaCha20Poly1305::new(&load_key(key_path)?);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let sealed = cipher
+        .encrypt(&nonce, secret.as_bytes())
+        .map_err(|_| "Failed to encrypt secret".to_string())?;
+    Ok(format!("{}{}", to_hex(&nonce), to_hex(&sealed)))
+}
+
+/// Decrypt a secret sealed by `seal_totp_secret`
+pub fn open_totp_secret(key_path: &Path, sealed: &str) -> Result<String, String> {
+    let bytes = from_hex(sealed).filter(|b| b.len() > NONCE_LEN).ok_or("Stored secret is corrupt")?;
+    let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+    let cipher = ChaCha20Poly1305::new(&load_key(key_path)?);
+    let secret = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Stored secret cannot be decrypted with this installation's key".to_string())?;
+    String::from_utf8(secret).map_err(|_| "Stored secret is corrupt".to_string())
+}
+
+/// A new random secret, base32 encoded as authenticator apps expect
+pub fn generate_totp_secret() -> String {
+    Secret::generate_secret().to_encoded().to_string()
+}
+
+/// Six-digit, 30-second SHA-1 codes, accepting one step either side of now
+/// to allow for clock skew
+fn build_totp(secret: &str, account: &str) -> Result<TOTP, String> {
+    let bytes = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|e| format!("Invalid TOTP secret: {}", e))?;
+    TOTP::new(Algorithm::SHA1, 6, 1, 30, bytes, Some(TOTP_ISSUER.to_string()), account.replace(':', "_"))
+        .map_err(|e| format!("Invalid TOTP secret: {}", e))
+}
+
+/// `otpauth://` link for adding the secret to an authenticator app
+pub fn totp_uri(secret: &str, account: &str) -> Result<String, String> {
+    Ok(build_totp(secret, account)?.get_url())
+}
+
+/// Check a code against the secret; malformed codes are simply wrong
+pub fn verify_totp(secret: &str, code: &str) -> bool {
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    build_totp(secret, "")
+        .ok()
+        .and_then(|totp| totp.check_current(&code).ok())
+        .unwrap_or(false)
+}
@@ -1,4 +1,4 @@
-import React, { useState, useEffect, useMemo } from 'react';
+import React, { useState, useEffect, useMemo, useCallback } from 'react';
 import {
   History,
   RefreshCw,
@@ -10,11 +10,18 @@ import {
   User,
   Database,
   Activity,
+  ShieldAlert,
+  RotateCcw,
 } from 'lucide-react';
-import { useAuditLog, AuditLog as AuditLogType } from '../hooks/useAuditLog';
-import { useTableState } from '../hooks/useTableState';
-import { TableFilters, FilterConfig } from './common/TableFilters';
-import { SortableHeader, TableHeader } from './common/SortableHeader';
+import {
+  useAuditLog,
+  AuditLog as AuditLogType,
+  AuditFilterOptions,
+  AuditFilters,
+  RESTORABLE_TABLES,
+} from '../hooks/useAuditLog';
+import { TableFilters, FilterConfig, FilterValues } from './common/TableFilters';
+import { TableHeader } from './common/SortableHeader';
 import { Pagination } from './common/Pagination';
 
 // ============================================
@@ -24,6 +31,7 @@ import { Pagination } from './common/Pagination';
 export function AuditLog() {
   const {
     logs,
+    pagination,
     stats,
     filterOptions,
     loading,
@@ -31,22 +39,78 @@ export function AuditLog() {
     fetchLogs,
     fetchStats,
     fetchFilterOptions,
+    restoreFromAudit,
     clearError,
   } = useAuditLog();
 
   const [expandedRows, setExpandedRows] = useState<Set<number>>(new Set());
+  const [filterValues, setFilterValues] = useState<FilterValues>({});
+  const [currentPage, setCurrentPage] = useState(1);
+  const [pageSize, setPageSize] = useState(25);
+
+  // Filtering and paging happen on the server, so page numbers cover every matching entry
+  const logFilters = useMemo((): AuditFilters => {
+    const selected = (key: string) => {
+      const value = filterValues[key];
+      return typeof value === 'string' && value !== '' ? value : undefined;
+    };
+    const range = filterValues.timestamp;
+    const userId = selected('user_id');
+    return {
+      action: selected('action'),
+      table_name: selected('table_name'),
+      user_id: userId ? Number(userId) : undefined,
+      from_date: typeof range === 'object' && range.from ? range.from : undefined,
+      to_date: typeof range === 'object' && range.to ? range.to : undefined,
+      limit: pageSize,
+      offset: (currentPage - 1) * pageSize,
+    };
+  }, [filterValues, currentPage, pageSize]);
+
+  useEffect(() => {
+    fetchLogs(logFilters);
+  }, [fetchLogs, logFilters]);
 
   useEffect(() => {
-    fetchLogs();
     fetchStats();
     fetchFilterOptions();
-  }, [fetchLogs, fetchStats, fetchFilterOptions]);
+  }, [fetchStats, fetchFilterOptions]);
 
   const handleRefresh = async () => {
-    await fetchLogs();
+    await fetchLogs(logFilters);
     await fetchStats();
   };
 
+  const handleFilterChange = useCallback((key: string, value: string | { from: string; to: string }) => {
+    setFilterValues((prev) => ({ ...prev, [key]: value }));
+    setCurrentPage(1);
+  }, []);
+
+  const handleClearFilters = useCallback(() => {
+    setFilterValues({});
+    setCurrentPage(1);
+  }, []);
+
+  const handlePageSizeChange = useCallback((size: number) => {
+    setPageSize(size);
+    setCurrentPage(1);
+  }, []);
+
+  const handleRestore = async (log: AuditLogType) => {
+    if (!confirm(`Restore this deleted ${formatTableName(log.table_name).toLowerCase()} record?`)) return;
+    const result = await restoreFromAudit(log.id);
+    if (!result) return;
+    const notes: string[] = [];
+    if (result.original_id !== null && result.record_id !== result.original_id) {
+      notes.push(`It was given the new ID ${result.record_id} because ${result.original_id} is in use.`);
+    }
+    if (result.cleared_references.length > 0) {
+      notes.push(`Links to deleted records were cleared: ${result.cleared_references.join(', ')}.`);
+    }
+    alert(['Record restored.', ...notes].join(' '));
+    await handleRefresh();
+  };
+
   const toggleRowExpansion = (id: number) => {
     setExpandedRows((prev) => {
       const newSet = new Set(prev);
@@ -85,7 +149,7 @@ export function AuditLog() {
 
       {/* Stats Cards */}
       {stats && (
-        <div className="grid grid-cols-1 md:grid-cols-3 gap-4">
+        <div className="grid grid-cols-1 md:grid-cols-4 gap-4">
           <StatCard
             title="Total Records"
             value={stats.total.toLocaleString()}
@@ -101,6 +165,11 @@ export function AuditLog() {
             value={stats.week_count.toLocaleString()}
             icon={<History className="text-purple-400" size={20} />}
           />
+          <StatCard
+            title="Failed Sign-ins Today"
+            value={stats.auth.failed_logins_today.toLocaleString()}
+            icon={<ShieldAlert className="text-red-400" size={20} />}
+          />
         </div>
       )}
 
@@ -123,9 +192,18 @@ export function AuditLog() {
       {/* Audit Table */}
       <AuditTable
         logs={logs}
+        totalItems={pagination?.total ?? 0}
         filterOptions={filterOptions}
+        filterValues={filterValues}
+        onFilterChange={handleFilterChange}
+        onClearFilters={handleClearFilters}
+        currentPage={currentPage}
+        pageSize={pageSize}
+        onPageChange={setCurrentPage}
+        onPageSizeChange={handlePageSizeChange}
         expandedRows={expandedRows}
         onToggleRow={toggleRowExpansion}
+        onRestore={handleRestore}
       />
     </div>
   );
@@ -158,13 +236,38 @@ function StatCard({ title, value, icon }: StatCardProps) {
 // ============================================
 
 interface AuditTableProps {
+  /** The current page of entries */
   logs: AuditLogType[];
-  filterOptions: { tables: string[]; actions: string[]; users: [number, string][] } | null;
+  /** Entries matching the filters across all pages */
+  totalItems: number;
+  filterOptions: AuditFilterOptions | null;
+  filterValues: FilterValues;
+  onFilterChange: (key: string, value: string | { from: string; to: string }) => void;
+  onClearFilters: () => void;
+  currentPage: number;
+  pageSize: number;
+  onPageChange: (page: number) => void;
+  onPageSizeChange: (size: number) => void;
   expandedRows: Set<number>;
   onToggleRow: (id: number) => void;
+  onRestore: (log: AuditLogType) => void;
 }
 
-function AuditTable({ logs, filterOptions, expandedRows, onToggleRow }: AuditTableProps) {
+function AuditTable({
+  logs,
+  totalItems,
+  filterOptions,
+  filterValues,
+  onFilterChange,
+  onClearFilters,
+  currentPage,
+  pageSize,
+  onPageChange,
+  onPageSizeChange,
+  expandedRows,
+  onToggleRow,
+  onRestore,
+}: AuditTableProps) {
   // Filter configuration
   const filterConfig: FilterConfig[] = useMemo(() => {
     const config: FilterConfig[] = [
@@ -189,49 +292,36 @@ function AuditTable({ logs, filterOptions, expandedRows, onToggleRow }: AuditTab
         placeholder: 'All Tables',
       },
       {
-        key: 'username',
+        key: 'user_id',
         label: 'User',
         type: 'select',
-        options: filterOptions?.users.map(([, name]) => ({
-          value: name,
+        options: filterOptions?.users.map(([id, name]) => ({
+          value: String(id),
           label: name,
         })) || [],
         placeholder: 'All Users',
       },
+      {
+        key: 'timestamp',
+        label: 'Date',
+        type: 'dateRange',
+      },
     ];
     return config;
   }, [filterOptions]);
 
-  const {
-    paginatedItems,
-    sort,
-    setSort,
-    filters,
-    setFilter,
-    clearFilters,
-    search,
-    setSearch,
-    currentPage,
-    pageSize,
-    totalItems,
-    setPage,
-    setPageSize,
-  } = useTableState(logs, {
-    storageKey: 'audit_log',
-    defaultPageSize: 25,
-  });
+  const hasFilters = Object.values(filterValues).some((v) =>
+    typeof v === 'string' ? v !== '' : v.from !== '' || v.to !== ''
+  );
 
   return (
     <div className="space-y-4">
       {/* Filter Bar */}
       <TableFilters
         filters={filterConfig}
-        values={filters}
-        onChange={setFilter}
-        onClear={clearFilters}
-        searchValue={search}
-        onSearchChange={setSearch}
-        searchPlaceholder="Search audit logs..."
+        values={filterValues}
+        onChange={onFilterChange}
+        onClear={onClearFilters}
       />
 
       {/* Table */}
@@ -241,22 +331,22 @@ function AuditTable({ logs, filterOptions, expandedRows, onToggleRow }: AuditTab
             <thead>
               <tr className="bg-gray-700">
                 <TableHeader label="" className="w-10" />
-                <SortableHeader label="Timestamp" sortKey="timestamp" currentSort={sort} onSort={setSort} />
-                <SortableHeader label="User" sortKey="username" currentSort={sort} onSort={setSort} />
-                <SortableHeader label="Action" sortKey="action" currentSort={sort} onSort={setSort} />
-                <SortableHeader label="Table" sortKey="table_name" currentSort={sort} onSort={setSort} />
-                <SortableHeader label="Record ID" sortKey="record_id" currentSort={sort} onSort={setSort} />
+                <TableHeader label="Timestamp" />
+                <TableHeader label="User" />
+                <TableHeader label="Action" />
+                <TableHeader label="Table" />
+                <TableHeader label="Record ID" />
               </tr>
             </thead>
             <tbody>
-              {paginatedItems.length === 0 ? (
+              {logs.length === 0 ? (
                 <tr>
                   <td colSpan={6} className="p-8 text-center text-gray-400">
-                    {logs.length === 0 ? 'No audit logs found' : 'No logs match your filters'}
+                    {hasFilters ? 'No logs match your filters' : 'No audit logs found'}
                   </td>
                 </tr>
               ) : (
-                paginatedItems.map((log) => (
+                logs.map((log) => (
                   <React.Fragment key={log.id}>
                     <tr
                       className={`border-t border-gray-700 hover:bg-gray-700/50 cursor-pointer ${
@@ -305,6 +395,15 @@ function AuditTable({ logs, filterOptions, expandedRows, onToggleRow }: AuditTab
                               </div>
                             )}
                           </div>
+                          {log.action === 'delete' && log.old_values && RESTORABLE_TABLES.includes(log.table_name) && (
+                            <button
+                              onClick={() => onRestore(log)}
+                              className="mt-4 bg-gray-700 hover:bg-gray-600 text-white px-3 py-1.5 rounded-lg flex items-center text-sm"
+                            >
+                              <RotateCcw size={14} className="mr-2" />
+                              Restore Record
+                            </button>
+                          )}
                         </td>
                       </tr>
                     )}
@@ -322,8 +421,8 @@ function AuditTable({ logs, filterOptions, expandedRows, onToggleRow }: AuditTab
           currentPage={currentPage}
           totalItems={totalItems}
           pageSize={pageSize}
-          onPageChange={setPage}
-          onPageSizeChange={setPageSize}
+          onPageChange={onPageChange}
+          onPageSizeChange={onPageSizeChange}
         />
       )}
     </div>
@@ -4,6 +4,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { useDashboard } from '../hooks/useDashboard';
 import { useMachines } from '../hooks/useMachines';
 import { useAlerts } from '../hooks/useAlerts';
+import { useDataChanged } from '../hooks/useDataChanged';
 import { useAuth } from '../context/AuthContext';
 import type { Machine, AlertWithDetails, MachineUtilization, ProjectProgress } from '../types';
 import { PieChart, Pie, Cell, ResponsiveContainer, AreaChart, Area, XAxis, YAxis, Tooltip, Legend, BarChart, Bar } from 'recharts';
@@ -16,6 +17,9 @@ const MACHINE_STATUS_COLORS: Record<string, string> = {
   error: '#ef4444',
 };
 
+// How often unacknowledged critical alerts are re-checked
+const CRITICAL_ALERT_POLL_MS = 60_000;
+
 const PROJECT_STATUS_COLORS: Record<string, string> = {
   planning: '#eab308',
   active: '#22c55e',
@@ -26,7 +30,14 @@ const PROJECT_STATUS_COLORS: Record<string, string> = {
 export function Dashboard() {
   const { stats, machineUtilization, projectProgress, loading, error, fetchAll, clearError } = useDashboard();
   const { machines, fetchMachines } = useMachines();
-  const { alerts, fetchAlerts, markAsRead } = useAlerts();
+  const {
+    alerts,
+    unacknowledgedCritical,
+    fetchAlerts,
+    fetchUnacknowledgedCritical,
+    acknowledgeAlert,
+    markAsRead,
+  } = useAlerts();
   const { token } = useAuth();
   const [lastUpdated, setLastUpdated] = useState<Date>(new Date());
   const [isRefreshing, setIsRefreshing] = useState(false);
@@ -48,6 +59,16 @@ export function Dashboard() {
     loadWeeklySchedule();
   }, [fetchAll, fetchMachines, fetchAlerts]);
 
+  useEffect(() => {
+    fetchUnacknowledgedCritical();
+    const interval = setInterval(fetchUnacknowledgedCritical, CRITICAL_ALERT_POLL_MS);
+    return () => clearInterval(interval);
+  }, [fetchUnacknowledgedCritical]);
+
+  useDataChanged(['alert:changed'], () => {
+    fetchUnacknowledgedCritical();
+  });
+
   const handleRefresh = async () => {
     setIsRefreshing(true);
     await Promise.all([fetchAll(), fetchMachines(), fetchAlerts(), loadWeeklySchedule()]);
@@ -86,6 +107,34 @@ export function Dashboard() {
         </div>
       )}
 
+      {stats?.demo_data_loaded && (
+        <div className="bg-amber-900/40 border border-amber-700 rounded-lg p-4 flex items-center">
+          <AlertCircle className="w-5 h-5 text-amber-400 mr-2" />
+          <span className="text-amber-200">
+            Demo data is loaded. Sample machines are mixed in with your own until an admin clears them.
+          </span>
+        </div>
+      )}
+
+      {unacknowledgedCritical.length > 0 && (
+        <div className="bg-red-900/40 border border-red-700 rounded-lg p-4 space-y-3">
+          <h2 className="font-semibold text-red-200 flex items-center">
+            <AlertCircle className="w-5 h-5 text-red-400 mr-2" />
+            {unacknowledgedCritical.length} critical alert{unacknowledgedCritical.length > 1 ? 's' : ''} waiting for
+            acknowledgement
+          </h2>
+          {unacknowledgedCritical.map(alert => (
+            <CriticalAlertItem
+              key={alert.id}
+              alert={alert}
+              onAcknowledge={async (comment) => {
+                await acknowledgeAlert(alert.id, comment);
+              }}
+            />
+          ))}
+        </div>
+      )}
+
       {/* Dashboard Header with Refresh */}
       <div className="flex items-center justify-between mb-2">
         <div>
@@ -567,12 +616,12 @@ function ProjectProgressItem({ project }: { project: ProjectProgress }) {
       <div className="mt-2">
         <div className="flex justify-between text-xs mb-1">
           <span className="text-gray-400">Progress</span>
-          <span>{project.progress_percentage}%</span>
+          <span>{project.effective_progress}%</span>
         </div>
         <div className="w-full bg-gray-700 rounded-full h-2">
           <div
             className="bg-blue-500 h-2 rounded-full transition-all"
-            style={{ width: `${Math.min(100, project.progress_percentage)}%` }}
+            style={{ width: `${Math.min(100, project.effective_progress)}%` }}
           />
         </div>
         <div className="flex justify-between text-xs mt-1 text-gray-500">
@@ -584,6 +633,63 @@ function ProjectProgressItem({ project }: { project: ProjectProgress }) {
   );
 }
 
+function CriticalAlertItem({
+  alert,
+  onAcknowledge,
+}: {
+  alert: AlertWithDetails;
+  onAcknowledge: (comment: string) => Promise<void>;
+}) {
+  const [comment, setComment] = useState('');
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!comment.trim()) return;
+    setSaving(true);
+    setError(null);
+    try {
+      await onAcknowledge(comment.trim());
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to acknowledge alert');
+      setSaving(false);
+    }
+  };
+
+  return (
+    <form onSubmit={handleSubmit} className="bg-gray-800/60 rounded-lg p-3 space-y-2">
+      <div className="flex justify-between gap-4">
+        <div className="min-w-0">
+          <p className="font-medium text-white">{alert.title}</p>
+          <p className="text-sm text-gray-300">{alert.message}</p>
+          {alert.machine_name && <p className="text-xs text-gray-400">Machine: {alert.machine_name}</p>}
+        </div>
+        <p className="text-xs text-gray-400 whitespace-nowrap">{new Date(alert.created_at).toLocaleString()}</p>
+      </div>
+      <div className="flex gap-2">
+        <input
+          type="text"
+          value={comment}
+          onChange={(e) => setComment(e.target.value)}
+          placeholder="What was done about it?"
+          className="flex-1 bg-gray-700 border border-gray-600 rounded-lg px-3 py-2 text-sm"
+          disabled={saving}
+        />
+        <button
+          type="submit"
+          disabled={saving || !comment.trim()}
+          className="bg-red-600 hover:bg-red-700 disabled:opacity-50 text-white px-3 py-2 rounded-lg flex items-center text-sm"
+        >
+          {saving ? <Loader2 size={16} className="mr-1 animate-spin" /> : <CheckCircle2 size={16} className="mr-1" />}
+          Acknowledge
+        </button>
+      </div>
+      {error && <p className="text-sm text-red-300">{error}</p>}
+    </form>
+  );
+}
+
 function AlertItem({ alert, onMarkRead }: { alert: AlertWithDetails; onMarkRead: () => void }) {
   const severityIcons: Record<string, React.ReactNode> = {
     critical: <AlertCircle className="text-red-500" size={18} />,
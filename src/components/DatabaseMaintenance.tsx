@@ -0,0 +1,260 @@
+import React, { useEffect, useState } from 'react';
+import {
+  Database,
+  HardDrive,
+  RefreshCw,
+  Loader2,
+  AlertCircle,
+  AlertTriangle,
+  CheckCircle,
+  X,
+  Zap,
+  Archive,
+} from 'lucide-react';
+import { useDatabaseHealth } from '../hooks/useDatabaseHealth';
+import type { OptimizeResult } from '../types';
+
+// ============================================
+// Main DatabaseMaintenance Component
+// ============================================
+
+export function DatabaseMaintenance() {
+  const { health, loading, optimizing, error, fetchHealth, optimize, clearError } = useDatabaseHealth();
+  const [lastRun, setLastRun] = useState<OptimizeResult | null>(null);
+
+  useEffect(() => {
+    fetchHealth();
+  }, [fetchHealth]);
+
+  const handleOptimize = async () => {
+    const result = await optimize();
+    if (result) setLastRun(result);
+  };
+
+  return (
+    <div className="space-y-6">
+      {error && (
+        <div className="p-4 bg-red-900/50 border border-red-700 rounded-lg flex items-center justify-between">
+          <div className="flex items-center">
+            <AlertCircle className="w-5 h-5 text-red-400 mr-2" />
+            <span className="text-red-200">{error}</span>
+          </div>
+          <button onClick={clearError} className="text-red-400 hover:text-red-300">
+            <X size={18} />
+          </button>
+        </div>
+      )}
+
+      {/* Header */}
+      <div className="flex justify-between items-center">
+        <h3 className="text-lg font-semibold flex items-center">
+          <Database size={20} className="mr-2" />
+          Database Maintenance
+        </h3>
+        <div className="flex gap-2">
+          <button
+            onClick={fetchHealth}
+            className="bg-gray-700 hover:bg-gray-600 text-white px-4 py-2 rounded-lg flex items-center"
+            disabled={loading || optimizing}
+          >
+            <RefreshCw size={18} className={`mr-2 ${loading ? 'animate-spin' : ''}`} />
+            Refresh
+          </button>
+          <button
+            onClick={handleOptimize}
+            className="bg-blue-600 hover:bg-blue-700 disabled:opacity-50 text-white px-4 py-2 rounded-lg flex items-center"
+            disabled={loading || optimizing || !health}
+          >
+            {optimizing ? <Loader2 size={18} className="mr-2 animate-spin" /> : <Zap size={18} className="mr-2" />}
+            {optimizing ? 'Optimizing...' : 'Optimize'}
+          </button>
+        </div>
+      </div>
+
+      {lastRun && (
+        <div className="p-3 bg-green-900/50 border border-green-700 rounded-lg text-green-200 flex items-center">
+          <CheckCircle size={18} className="mr-2" />
+          Optimized in {(lastRun.duration_ms / 1000).toFixed(1)}s: {formatBytes(lastRun.size_before)} to{' '}
+          {formatBytes(lastRun.size_after)}
+        </div>
+      )}
+
+      {!health && loading && (
+        <div className="flex items-center justify-center p-8 text-gray-400">
+          <Loader2 className="animate-spin mr-2" size={20} />
+          Checking database...
+        </div>
+      )}
+
+      {health && (
+        <>
+          {/* Stats Cards */}
+          <div className="grid grid-cols-1 md:grid-cols-4 gap-4">
+            <StatCard
+              title="File Size"
+              value={formatBytes(health.size_bytes)}
+              detail={health.wal_size_bytes ? `+ ${formatBytes(health.wal_size_bytes)} write-ahead log` : undefined}
+              icon={<HardDrive className="text-blue-400" size={20} />}
+            />
+            <StatCard
+              title="Free Space"
+              value={formatBytes(health.free_bytes)}
+              detail={`${health.freelist_count.toLocaleString()} of ${health.page_count.toLocaleString()} pages`}
+              icon={<Database className="text-purple-400" size={20} />}
+            />
+            <StatCard
+              title="Integrity"
+              value={health.integrity_ok ? 'OK' : 'Problems found'}
+              icon={
+                health.integrity_ok
+                  ? <CheckCircle className="text-green-400" size={20} />
+                  : <AlertTriangle className="text-red-400" size={20} />
+              }
+            />
+            <StatCard
+              title="Last Backup"
+              value={health.last_backup_at ? formatTimestamp(health.last_backup_at) : 'Never'}
+              detail="Latest full data export"
+              icon={<Archive className="text-yellow-400" size={20} />}
+            />
+          </div>
+
+          {health.vacuum_recommended && (
+            <div className="p-3 bg-yellow-900/50 border border-yellow-700 rounded-lg text-yellow-200 flex items-center">
+              <AlertTriangle size={18} className="mr-2" />
+              A large share of the file is unused. Optimizing will give {formatBytes(health.free_bytes)} back.
+            </div>
+          )}
+
+          {!health.integrity_ok && (
+            <div className="p-4 bg-red-900/50 border border-red-700 rounded-lg text-red-200">
+              <p className="font-medium mb-2">The integrity check reported:</p>
+              <ul className="list-disc list-inside text-sm space-y-1">
+                {health.integrity_messages.map((message, i) => (
+                  <li key={i}>{message}</li>
+                ))}
+              </ul>
+            </div>
+          )}
+
+          <div className="p-4 bg-gray-700/50 rounded-lg grid grid-cols-2 md:grid-cols-4 gap-4 text-sm">
+            <Detail label="Location" value={health.path} />
+            <Detail label="Schema Version" value={String(health.schema_version)} />
+            <Detail label="Journal Mode" value={health.journal_mode.toUpperCase()} />
+            <Detail
+              label="Last Optimized"
+              value={health.last_optimized_at ? formatTimestamp(health.last_optimized_at) : 'Never'}
+            />
+          </div>
+
+          <div className="grid grid-cols-1 md:grid-cols-2 gap-6">
+            {/* Tables */}
+            <div className="bg-gray-800 rounded-xl overflow-hidden">
+              <table className="w-full">
+                <thead>
+                  <tr className="bg-gray-700 text-left text-sm text-gray-300">
+                    <th className="p-3">Table</th>
+                    <th className="p-3 text-right">Rows</th>
+                  </tr>
+                </thead>
+                <tbody>
+                  {health.tables.map((table) => (
+                    <tr key={table.name} className="border-t border-gray-700 text-sm">
+                      <td className="p-3 font-mono">{table.name}</td>
+                      <td className="p-3 text-right">{table.rows.toLocaleString()}</td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            </div>
+
+            {/* Indexes */}
+            <div className="bg-gray-800 rounded-xl overflow-hidden">
+              <table className="w-full">
+                <thead>
+                  <tr className="bg-gray-700 text-left text-sm text-gray-300">
+                    <th className="p-3">Index</th>
+                    <th className="p-3">Table</th>
+                  </tr>
+                </thead>
+                <tbody>
+                  {health.indexes.map((index) => (
+                    <tr key={index.name} className="border-t border-gray-700 text-sm">
+                      <td className="p-3 font-mono">
+                        {index.name}
+                        {index.unique && (
+                          <span className="ml-2 px-2 py-0.5 rounded text-xs bg-blue-500/20 text-blue-400">unique</span>
+                        )}
+                      </td>
+                      <td className="p-3 font-mono text-gray-400">{index.table}</td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            </div>
+          </div>
+
+          <p className="text-xs text-gray-500">
+            Optimizing rebuilds the database file and refreshes query statistics. Other changes wait until it
+            finishes, and it needs free disk space about the size of the database.
+          </p>
+        </>
+      )}
+    </div>
+  );
+}
+
+// ============================================
+// Stat Card Component
+// ============================================
+
+interface StatCardProps {
+  title: string;
+  value: string;
+  detail?: string;
+  icon: React.ReactNode;
+}
+
+function StatCard({ title, value, detail, icon }: StatCardProps) {
+  return (
+    <div className="bg-gray-800 rounded-xl p-4 flex items-center">
+      <div className="p-3 bg-gray-700 rounded-lg mr-4">{icon}</div>
+      <div>
+        <p className="text-gray-400 text-sm">{title}</p>
+        <p className="text-xl font-semibold">{value}</p>
+        {detail && <p className="text-xs text-gray-500">{detail}</p>}
+      </div>
+    </div>
+  );
+}
+
+function Detail({ label, value }: { label: string; value: string }) {
+  return (
+    <div>
+      <p className="text-gray-400">{label}</p>
+      <p className="font-mono break-all">{value}</p>
+    </div>
+  );
+}
+
+// ============================================
+// Helper Functions
+// ============================================
+
+function formatBytes(bytes: number | null): string {
+  if (bytes === null) return 'Unknown';
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+function formatTimestamp(timestamp: string): string {
+  const date = new Date(timestamp);
+  return date.toLocaleString('en-US', {
+    month: 'short',
+    day: 'numeric',
+    year: 'numeric',
+    hour: '2-digit',
+    minute: '2-digit',
+  });
+}
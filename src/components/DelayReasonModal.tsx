@@ -4,6 +4,7 @@ import type { ScheduleEntry } from '../types';
 import { useSchedules } from '../hooks/useSchedules';
 import { useAlerts } from '../hooks/useAlerts';
 import { formatLocalDate } from '../hooks/useSchedules';
+import { errorMessage } from '../utils/errors';
 
 interface DelayReasonModalProps {
   pendingJobs: ScheduleEntry[];
@@ -59,7 +60,7 @@ export function DelayReasonModal({ pendingJobs, onClose }: DelayReasonModalProps
       }
       onClose();
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to process delay. Please try again.');
+      setError(errorMessage(err, 'Failed to process delay. Please try again.'));
     } finally {
       setSubmitting(false);
     }
@@ -105,7 +105,8 @@ export function Header({ activeTab, setActiveTab }: HeaderProps) {
       if (
         project.name.toLowerCase().includes(lowerQuery) ||
         (project.client_name && project.client_name.toLowerCase().includes(lowerQuery)) ||
-        (project.description && project.description.toLowerCase().includes(lowerQuery))
+        (project.description && project.description.toLowerCase().includes(lowerQuery)) ||
+        (project.external_ref && project.external_ref.toLowerCase().includes(lowerQuery))
       ) {
         results.push({
           type: 'project',
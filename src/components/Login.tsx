@@ -1,9 +1,13 @@
 import React, { useState } from "react";
 import { useAuth } from "../context/AuthContext";
+import { errorMessage, isAppError } from "../utils/errors";
 
 export function Login({ onLogin }: { onLogin: () => void }) {
   const [username, setUsername] = useState("");
   const [password, setPassword] = useState("");
+  const [rememberMe, setRememberMe] = useState(false);
+  const [totpCode, setTotpCode] = useState("");
+  const [needsTotp, setNeedsTotp] = useState(false);
   const [error, setError] = useState("");
   const [isSubmitting, setIsSubmitting] = useState(false);
   const { animatedLogin, isLoggingIn } = useAuth();
@@ -15,10 +19,17 @@ export function Login({ onLogin }: { onLogin: () => void }) {
 
     try {
       // animatedLogin handles all the timing and animation states
-      await animatedLogin(username, password);
+      await animatedLogin(username, password, rememberMe, needsTotp ? totpCode : undefined);
       onLogin();
     } catch (err) {
-      setError(err instanceof Error ? err.message : "Login failed. Please try again.");
+      if (isAppError(err) && err.code === "totp_required") {
+        // First time: ask for the code. After that: the code was wrong.
+        setError(needsTotp ? err.message : "");
+        setNeedsTotp(true);
+        setTotpCode("");
+      } else {
+        setError(errorMessage(err, "Login failed. Please try again."));
+      }
       setIsSubmitting(false);
     }
   };
@@ -70,6 +81,37 @@ export function Login({ onLogin }: { onLogin: () => void }) {
               />
             </div>
 
+            {needsTotp && (
+              <div>
+                <label className="block text-sm text-gray-400 mb-1">
+                  Authentication Code
+                </label>
+                <input
+                  type="text"
+                  inputMode="numeric"
+                  autoComplete="one-time-code"
+                  value={totpCode}
+                  onChange={(e) => setTotpCode(e.target.value)}
+                  className="w-full px-4 py-2 rounded-lg bg-gray-700 border border-gray-600 focus:outline-none focus:ring-2 focus:ring-blue-500"
+                  placeholder="6-digit code from your app"
+                  required
+                  autoFocus
+                  disabled={isDisabled}
+                />
+              </div>
+            )}
+
+            <label className="flex items-center text-sm text-gray-400">
+              <input
+                type="checkbox"
+                checked={rememberMe}
+                onChange={(e) => setRememberMe(e.target.checked)}
+                className="mr-2"
+                disabled={isDisabled}
+              />
+              Remember me on this computer
+            </label>
+
             <button
               type="submit"
               disabled={isDisabled}
@@ -1,6 +1,7 @@
 import React, { useState, useEffect, useMemo } from 'react';
 import { AlertCircle, CheckCircle2, Clock, Cog, Edit, Plus, Trash2, RefreshCw, Loader2, FileSpreadsheet, FileText } from 'lucide-react';
 import { useMachines } from '../hooks/useMachines';
+import { useDataChanged } from '../hooks/useDataChanged';
 import { useAuth } from '../context/AuthContext';
 import { exportMachinesToExcel, exportMachinesToPDF } from '../utils/export';
 import { useTableState } from '../hooks/useTableState';
@@ -25,6 +26,8 @@ export function Machines() {
     fetchMachines();
   }, [fetchMachines]);
 
+  useDataChanged(['machine:changed', 'machine:status'], () => fetchMachines());
+
   const handleViewMachine = async (machine: Machine) => {
     setSelectedMachine(machine);
     setIsEditing(false);
@@ -88,7 +91,7 @@ export function Machines() {
     setActionLoading(true);
     setActionError(null);
     try {
-      await deleteMachine(deleteModal.machine.id);
+      await deleteMachine(deleteModal.machine.id, deleteModal.cascadeEffects);
       setSelectedMachine(null);
       setIsEditing(false);
       setDeleteModal(null);
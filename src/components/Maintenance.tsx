@@ -15,6 +15,7 @@ import {
   AlertCircle,
 } from 'lucide-react';
 import { useMaintenance } from '../hooks/useMaintenance';
+import { useDataChanged } from '../hooks/useDataChanged';
 import { useMachines } from '../hooks/useMachines';
 import { useAuth } from '../context/AuthContext';
 import { useTableState } from '../hooks/useTableState';
@@ -64,6 +65,11 @@ export function Maintenance() {
     fetchMachines();
   }, [fetchAllMaintenance, fetchOverdueMaintenance, fetchMachines]);
 
+  useDataChanged(['maintenance:changed'], () => {
+    fetchAllMaintenance();
+    fetchOverdueMaintenance();
+  });
+
   const handleCreate = () => {
     setSelectedRecord(null);
     setIsCreating(true);
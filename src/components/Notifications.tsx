@@ -13,8 +13,12 @@ import {
   XCircle,
   Wrench,
   Calendar,
+  Monitor,
+  BellRing,
 } from 'lucide-react';
 import { useAlerts } from '../hooks/useAlerts';
+import { useDataChanged } from '../hooks/useDataChanged';
+import { useNotificationSettings } from '../hooks/useNotificationSettings';
 import { useTableState } from '../hooks/useTableState';
 import { TableFilters, FilterConfig } from './common/TableFilters';
 import { Pagination } from './common/Pagination';
@@ -40,8 +44,12 @@ export function Notifications() {
     createAlert,
   } = useAlerts();
 
+  const desktop = useNotificationSettings();
+  const { fetchSettings } = desktop;
+
   const [selectedAlerts, setSelectedAlerts] = useState<Set<number>>(new Set());
   const [bulkLoading, setBulkLoading] = useState(false);
+  const [testSent, setTestSent] = useState(false);
 
   useEffect(() => {
     const init = async () => {
@@ -65,6 +73,15 @@ export function Notifications() {
     init();
   }, [fetchAlerts, fetchAlertStats, createAlert]);
 
+  useEffect(() => {
+    fetchSettings();
+  }, [fetchSettings]);
+
+  useDataChanged(['alert:changed'], () => {
+    fetchAlerts();
+    fetchAlertStats();
+  });
+
   const handleRefresh = async () => {
     await fetchAlerts();
     await fetchAlertStats();
@@ -94,7 +111,7 @@ export function Notifications() {
   };
 
   const handleClearReadAlerts = async () => {
-    if (!confirm('Are you sure you want to delete all read notifications?')) return;
+    if (!confirm('Delete notifications everyone has read, and old notifications?')) return;
     setBulkLoading(true);
     try {
       await clearReadAlerts();
@@ -104,6 +121,22 @@ export function Notifications() {
     }
   };
 
+  const handleToggleDesktop = async () => {
+    const current = desktop.settings;
+    if (!current) return;
+    await desktop.updateSettings({
+      notify_priorities: current.notify_priorities,
+      sound_enabled: current.sound_enabled,
+      desktop_enabled: !current.desktop_enabled,
+      quiet_hours_start: current.quiet_hours_start,
+      quiet_hours_end: current.quiet_hours_end,
+    });
+  };
+
+  const handleTestNotification = async () => {
+    setTestSent(await desktop.testNotification());
+  };
+
   const handleSelectAll = () => {
     if (selectedAlerts.size === alerts.length) {
       setSelectedAlerts(new Set());
@@ -152,13 +185,28 @@ export function Notifications() {
   return (
     <div className="space-y-6">
       {/* Error Display */}
-      {error && (
+      {(error || desktop.error) && (
         <div className="bg-red-900/50 border border-red-700 rounded-lg p-4 flex items-center justify-between">
           <div className="flex items-center">
             <AlertCircle className="w-5 h-5 text-red-400 mr-2" />
-            <span className="text-red-200">{error}</span>
+            <span className="text-red-200">{error || desktop.error}</span>
           </div>
-          <button onClick={clearError} className="text-red-400 hover:text-red-300">
+          <button
+            onClick={() => {
+              clearError();
+              desktop.clearError();
+            }}
+            className="text-red-400 hover:text-red-300"
+          >
+            <X size={18} />
+          </button>
+        </div>
+      )}
+
+      {testSent && (
+        <div className="bg-green-900/50 border border-green-700 rounded-lg p-3 text-green-200 flex items-center justify-between">
+          <span>Test notification sent. If nothing appeared, check the system notification settings.</span>
+          <button onClick={() => setTestSent(false)} className="text-green-400 hover:text-green-300">
             <X size={18} />
           </button>
         </div>
@@ -218,6 +266,22 @@ export function Notifications() {
             <Trash2 size={16} className="mr-1" />
             Clear Read
           </button>
+          <button
+            onClick={handleToggleDesktop}
+            className="bg-gray-700 hover:bg-gray-600 text-white px-3 py-2 rounded-lg flex items-center text-sm"
+            disabled={!desktop.settings}
+            title="Show high and critical alerts as system notifications"
+          >
+            <Monitor size={16} className="mr-1" />
+            Desktop Alerts: {desktop.settings?.desktop_enabled === false ? 'Off' : 'On'}
+          </button>
+          <button
+            onClick={handleTestNotification}
+            className="bg-gray-700 hover:bg-gray-600 text-white px-3 py-2 rounded-lg flex items-center text-sm"
+          >
+            <BellRing size={16} className="mr-1" />
+            Test
+          </button>
           <button
             onClick={handleRefresh}
             className="bg-gray-700 hover:bg-gray-600 text-white px-4 py-2 rounded-lg flex items-center"
@@ -475,6 +539,12 @@ function NotificationItem({ alert, selected, onSelect, onMarkAsRead, onDismiss }
               {alert.machine_name && <span>Machine: {alert.machine_name}</span>}
               {alert.project_name && <span>Project: {alert.project_name}</span>}
             </div>
+            {alert.acknowledgement && (
+              <p className="text-xs text-green-400 mt-1">
+                Acknowledged by {alert.acknowledgement.user_name ?? 'a deleted user'},{' '}
+                {formatTimestamp(alert.acknowledgement.acknowledged_at)}: {alert.acknowledgement.comment}
+              </p>
+            )}
           </div>
         </div>
       </div>
@@ -80,7 +80,7 @@ export function Projects() {
   const handleDeleteProject = async () => {
     if (!deleteModal) return;
     try {
-      await deleteProject(deleteModal.project.id);
+      await deleteProject(deleteModal.project.id, undefined, deleteModal.cascadeEffects);
       if (selectedProject?.id === deleteModal.project.id) {
         setSelectedProject(null);
         setIsEditing(false);
@@ -107,6 +107,7 @@ export function Projects() {
       planned_hours: 0,
       actual_hours: 0,
       progress_percentage: 0,
+      effective_progress: 0,
       created_by: null,
       created_at: '',
       updated_at: '',
@@ -398,7 +399,7 @@ function ProjectTable({ projects, onView, onEdit, onDelete, canEdit, isAdmin }:
                 />
                 <SortableHeader
                   label="Progress"
-                  sortKey="progress_percentage"
+                  sortKey="effective_progress"
                   currentSort={sort}
                   onSort={setSort}
                 />
@@ -458,10 +459,10 @@ function ProjectTable({ projects, onView, onEdit, onDelete, canEdit, isAdmin }:
                         <div className="w-24 h-2 bg-gray-700 rounded-full overflow-hidden">
                           <div
                             className="h-full bg-blue-500 rounded-full transition-all"
-                            style={{ width: `${Math.min(100, project.progress_percentage)}%` }}
+                            style={{ width: `${Math.min(100, project.effective_progress)}%` }}
                           />
                         </div>
-                        <span className="text-sm text-gray-400">{project.progress_percentage}%</span>
+                        <span className="text-sm text-gray-400">{project.effective_progress}%</span>
                       </div>
                     </td>
                     <td className="p-4 text-sm">
@@ -592,10 +593,10 @@ function ProjectDetails({ project, machines, onBack, onEdit, onDelete, canEdit,
               <div className="flex-1 h-3 bg-gray-700 rounded-full overflow-hidden">
                 <div
                   className="h-full bg-blue-500 rounded-full transition-all"
-                  style={{ width: `${Math.min(100, project.progress_percentage)}%` }}
+                  style={{ width: `${Math.min(100, project.effective_progress)}%` }}
                 />
               </div>
-              <span className="text-sm font-medium">{project.progress_percentage}%</span>
+              <span className="text-sm font-medium">{project.effective_progress}%</span>
             </div>
           </div>
         </div>
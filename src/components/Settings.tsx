@@ -1,12 +1,15 @@
 import React, { useState, useEffect, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { User, Lock, Users, Building2, Info, Plus, Edit, Trash2, X, Loader2, AlertCircle, Check, Eye, EyeOff, History, Clock } from 'lucide-react';
+import { User, Lock, Users, Building2, Info, Plus, Edit, Trash2, X, Loader2, AlertCircle, Check, Eye, EyeOff, History, Clock, Database } from 'lucide-react';
 import { useAuth } from '../context/AuthContext';
 import { useClients } from '../hooks/useClients';
 import { AuditLog } from './AuditLog';
+import { DatabaseMaintenance } from './DatabaseMaintenance';
 import type { User as UserType, CreateUserInput, Client, CreateClientInput, UpdateClientInput } from '../types';
+import { errorMessage } from '../utils/errors';
+import type { CascadeEffect } from './common/DeleteConfirmModal';
 
-type SettingsTab = 'profile' | 'users' | 'clients' | 'audit' | 'shift' | 'about';
+type SettingsTab = 'profile' | 'users' | 'clients' | 'audit' | 'shift' | 'database' | 'about';
 
 export function Settings() {
   const { isAdmin } = useAuth();
@@ -18,6 +21,7 @@ export function Settings() {
     { id: 'clients' as const, label: 'Clients', icon: Building2 },
     ...(isAdmin ? [{ id: 'audit' as const, label: 'Audit Log', icon: History }] : []),
     ...(isAdmin ? [{ id: 'shift' as const, label: 'Shift', icon: Clock }] : []),
+    ...(isAdmin ? [{ id: 'database' as const, label: 'Database', icon: Database }] : []),
     { id: 'about' as const, label: 'About', icon: Info },
   ];
 
@@ -53,6 +57,7 @@ export function Settings() {
         {activeTab === 'clients' && <ClientManagement />}
         {activeTab === 'audit' && isAdmin && <AuditLog />}
         {activeTab === 'shift' && isAdmin && <ShiftSettings />}
+        {activeTab === 'database' && isAdmin && <DatabaseMaintenance />}
         {activeTab === 'about' && <AboutSection />}
       </div>
     </div>
@@ -93,7 +98,7 @@ function ProfileSettings() {
       setNewPassword('');
       setConfirmPassword('');
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to change password');
+      setError(errorMessage(err, 'Failed to change password'));
     } finally {
       setSaving(false);
     }
@@ -236,7 +241,7 @@ function UserManagement() {
   const [error, setError] = useState<string | null>(null);
   const [editingUser, setEditingUser] = useState<UserType | null>(null);
   const [showCreateModal, setShowCreateModal] = useState(false);
-  const [deleteConfirm, setDeleteConfirm] = useState<number | null>(null);
+  const [deleteConfirm, setDeleteConfirm] = useState<{ id: number; cascadeEffects: CascadeEffect[] } | null>(null);
 
   const fetchUsers = useCallback(async () => {
     if (!token) return;
@@ -245,7 +250,7 @@ function UserManagement() {
       const data = await invoke<UserType[]>('get_users', { token });
       setUsers(data);
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to fetch users');
+      setError(errorMessage(err, 'Failed to fetch users'));
     } finally {
       setLoading(false);
     }
@@ -267,13 +272,31 @@ function UserManagement() {
     setEditingUser(null);
   };
 
-  const handleDeleteUser = async (id: number) => {
+  const confirmDeleteUser = async (id: number) => {
+    try {
+      const impact = await invoke<{ cascade_effects: CascadeEffect[] }>('check_user_delete_impact', { token, userId: id });
+      setDeleteConfirm({ id, cascadeEffects: impact.cascade_effects });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to check what deleting this user affects'));
+    }
+  };
+
+  const handleDeleteUser = async (id: number, expectedEffects: CascadeEffect[]) => {
     try {
-      await invoke('delete_user', { token, id });
+      await invoke('delete_user', { token, id, expectedEffects });
       await fetchUsers();
       setDeleteConfirm(null);
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to delete user');
+      setError(errorMessage(err, 'Failed to delete user'));
+    }
+  };
+
+  const handleUnlockUser = async (userId: number) => {
+    try {
+      await invoke('unlock_user', { token, userId });
+      await fetchUsers();
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to unlock user'));
     }
   };
 
@@ -320,6 +343,7 @@ function UserManagement() {
               <th className="text-left p-3">Email</th>
               <th className="text-left p-3">Role</th>
               <th className="text-left p-3">Status</th>
+              <th className="text-left p-3">Last Login</th>
               <th className="text-left p-3 rounded-tr-lg">Actions</th>
             </tr>
           </thead>
@@ -342,9 +366,24 @@ function UserManagement() {
                   <span className={`px-2 py-1 rounded text-xs ${user.is_active ? 'bg-green-500/20 text-green-400' : 'bg-red-500/20 text-red-400'}`}>
                     {user.is_active ? 'Active' : 'Inactive'}
                   </span>
+                  {user.locked_until && (
+                    <span className="ml-2 px-2 py-1 rounded text-xs bg-orange-500/20 text-orange-400" title={`Locked until ${user.locked_until}`}>
+                      Locked
+                    </span>
+                  )}
                 </td>
+                <td className="p-3 text-sm text-gray-400">{user.last_login_at || 'Never'}</td>
                 <td className="p-3">
                   <div className="flex space-x-2">
+                    {user.locked_until && (
+                      <button
+                        onClick={() => handleUnlockUser(user.id)}
+                        className="p-1 text-gray-400 hover:text-orange-400"
+                        title="Unlock account"
+                      >
+                        <Lock size={16} />
+                      </button>
+                    )}
                     <button
                       onClick={() => setEditingUser(user)}
                       className="p-1 text-gray-400 hover:text-blue-400"
@@ -353,7 +392,7 @@ function UserManagement() {
                       <Edit size={16} />
                     </button>
                     <button
-                      onClick={() => setDeleteConfirm(user.id)}
+                      onClick={() => confirmDeleteUser(user.id)}
                       className="p-1 text-gray-400 hover:text-red-400"
                       title="Delete user"
                     >
@@ -391,9 +430,19 @@ function UserManagement() {
         <div className="fixed inset-0 bg-black/50 flex items-center justify-center z-50">
           <div className="bg-gray-800 rounded-xl p-6 max-w-md w-full mx-4">
             <h3 className="text-lg font-semibold mb-4">Confirm Delete</h3>
-            <p className="text-gray-400 mb-6">
+            <p className="text-gray-400 mb-4">
               Are you sure you want to delete this user? This action cannot be undone.
             </p>
+            {deleteConfirm.cascadeEffects.length > 0 && (
+              <ul className="mb-6 space-y-1 text-sm text-gray-300">
+                {deleteConfirm.cascadeEffects.map((effect) => (
+                  <li key={effect.table} className="flex justify-between">
+                    <span>{effect.label}</span>
+                    <span className="text-yellow-400">{effect.count}</span>
+                  </li>
+                ))}
+              </ul>
+            )}
             <div className="flex justify-end space-x-3">
               <button
                 onClick={() => setDeleteConfirm(null)}
@@ -402,7 +451,7 @@ function UserManagement() {
                 Cancel
               </button>
               <button
-                onClick={() => handleDeleteUser(deleteConfirm)}
+                onClick={() => handleDeleteUser(deleteConfirm.id, deleteConfirm.cascadeEffects)}
                 className="px-4 py-2 bg-red-600 hover:bg-red-700 text-white rounded-lg"
               >
                 Delete
@@ -464,7 +513,7 @@ function UserFormModal({ title, user, onSave, onClose }: UserFormModalProps) {
         });
       }
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Operation failed');
+      setError(errorMessage(err, 'Operation failed'));
     } finally {
       setSaving(false);
     }
@@ -775,7 +824,7 @@ function ClientFormModal({ client, onSave, onClose }: ClientFormModalProps) {
         notes: formData.notes || undefined,
       });
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Operation failed');
+      setError(errorMessage(err, 'Operation failed'));
     } finally {
       setSaving(false);
     }
@@ -1,6 +1,7 @@
 import React, { useState, useEffect } from 'react';
 import { ChevronLeft, ChevronRight, Plus, Trash2, X, Loader2, AlertCircle, Clock, Copy, FileSpreadsheet, FileText, AlertTriangle } from 'lucide-react';
 import { useSchedules, addWeeks, formatLocalDate } from '../hooks/useSchedules';
+import { useDataChanged } from '../hooks/useDataChanged';
 import { useProjects } from '../hooks/useProjects';
 import { useAuth } from '../context/AuthContext';
 import { exportWeeklyScheduleToExcel, exportWeeklyScheduleToPDF } from '../utils/export';
@@ -46,6 +47,8 @@ export function WeeklyPlanner() {
     fetchProjects();
   }, [fetchWeeklySchedule, fetchProjects]);
 
+  useDataChanged(['schedule:changed', 'machine:changed', 'machine:status'], () => fetchWeeklySchedule());
+
   // Generate dates for the current week view
   const getWeekDates = (): Date[] => {
     const start = new Date(currentWeekStart + 'T00:00:00');
@@ -109,10 +112,11 @@ export function WeeklyPlanner() {
     if (!copyTargetWeek) return;
     setCopyLoading(true);
     try {
-      const count = await copyWeekSchedule(currentWeekStart, copyTargetWeek);
+      const result = await copyWeekSchedule(currentWeekStart, copyTargetWeek);
       setShowCopyModal(false);
       setCopyTargetWeek('');
-      alert(`Successfully copied ${count} schedule entries to the target week.`);
+      const skipped = result?.warnings.length ? `\n\n${result.warnings.join('\n')}` : '';
+      alert(`Successfully copied ${result?.count ?? 0} schedule entries to the target week.${skipped}`);
     } catch {
       // Error handled in hook
     } finally {
@@ -359,7 +363,7 @@ export function WeeklyPlanner() {
             </p>
             <div className="mb-4">
               <label className="block text-sm font-medium text-gray-400 mb-1">
-                Target Week Start (Monday)
+                Any Day in the Target Week
               </label>
               <input
                 type="date"
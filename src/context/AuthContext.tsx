@@ -1,6 +1,7 @@
 import React, { createContext, useContext, useState, useEffect, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import type { User, AuthResponse } from '../types';
+import type { User, AuthResponse, StartupStatus } from '../types';
+import { errorMessage, isAppError } from '../utils/errors';
 
 interface AuthContextType {
   user: User | null;
@@ -9,8 +10,8 @@ interface AuthContextType {
   isLoading: boolean;
   isLoggingIn: boolean;
   isLoggingOut: boolean;
-  login: (username: string, password: string) => Promise<void>;
-  animatedLogin: (username: string, password: string) => Promise<void>;
+  login: (username: string, password: string, rememberMe?: boolean, totpCode?: string) => Promise<void>;
+  animatedLogin: (username: string, password: string, rememberMe?: boolean, totpCode?: string) => Promise<void>;
   logout: () => Promise<void>;
   animatedLogout: () => Promise<void>;
   changePassword: (oldPassword: string, newPassword: string) => Promise<void>;
@@ -31,14 +32,22 @@ export function AuthProvider({ children }: { children: React.ReactNode }) {
   const [isLoggingIn, setIsLoggingIn] = useState(false);
   const [isLoggingOut, setIsLoggingOut] = useState(false);
 
-  // Load stored token on mount
+  // Load stored token on mount, once the backend has opened the database
   useEffect(() => {
-    const storedToken = localStorage.getItem(TOKEN_KEY);
-    if (storedToken) {
-      validateAndRestoreSession(storedToken);
-    } else {
-      setIsLoading(false);
-    }
+    const restore = async () => {
+      try {
+        await invoke<StartupStatus>('get_startup_status');
+      } catch (error) {
+        console.error('Failed to get startup status:', error);
+      }
+      const storedToken = localStorage.getItem(TOKEN_KEY);
+      if (storedToken) {
+        await validateAndRestoreSession(storedToken);
+      } else {
+        setIsLoading(false);
+      }
+    };
+    restore();
   }, []);
 
   const validateAndRestoreSession = async (storedToken: string) => {
@@ -59,23 +68,25 @@ export function AuthProvider({ children }: { children: React.ReactNode }) {
     }
   };
 
-  const login = useCallback(async (username: string, password: string) => {
+  const login = useCallback(async (username: string, password: string, rememberMe = false, totpCode?: string) => {
     try {
-      const response = await invoke<AuthResponse>('login', { username, password });
+      const response = await invoke<AuthResponse>('login', { username, password, rememberMe, totpCode });
       setUser(response.user);
       setToken(response.token);
       localStorage.setItem(TOKEN_KEY, response.token);
     } catch (error) {
-      throw new Error(typeof error === 'string' ? error : 'Login failed');
+      // Passed through as is so callers can branch on its code
+      if (isAppError(error)) throw error;
+      throw new Error(errorMessage(error, 'Login failed'));
     }
   }, []);
 
   // Animated login - shows loading animation and delays before setting auth state
-  const animatedLogin = useCallback(async (username: string, password: string) => {
+  const animatedLogin = useCallback(async (username: string, password: string, rememberMe = false, totpCode?: string) => {
     setIsLoggingIn(true);
 
     try {
-      const response = await invoke<AuthResponse>('login', { username, password });
+      const response = await invoke<AuthResponse>('login', { username, password, rememberMe, totpCode });
 
       // Keep showing the loading animation for a smooth experience
       await new Promise(resolve => setTimeout(resolve, 2000));
@@ -90,7 +101,8 @@ export function AuthProvider({ children }: { children: React.ReactNode }) {
       setIsLoggingIn(false);
     } catch (error) {
       setIsLoggingIn(false);
-      throw new Error(typeof error === 'string' ? error : 'Login failed');
+      if (isAppError(error)) throw error;
+      throw new Error(errorMessage(error, 'Login failed'));
     }
   }, []);
 
@@ -141,7 +153,7 @@ export function AuthProvider({ children }: { children: React.ReactNode }) {
         newPassword
       });
     } catch (error) {
-      throw new Error(typeof error === 'string' ? error : 'Failed to change password');
+      throw new Error(errorMessage(error, 'Failed to change password'));
     }
   }, [token]);
 
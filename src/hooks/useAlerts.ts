@@ -1,13 +1,15 @@
 import { useState, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useAuth } from '../context/AuthContext';
-import type { AlertWithDetails, CreateAlertInput, AlertStats } from '../types';
+import type { AlertWithDetails, CreateAlertInput, AlertStats, Paginated } from '../types';
+import { errorMessage } from '../utils/errors';
 
 export function useAlerts() {
   const { token } = useAuth();
   const [alerts, setAlerts] = useState<AlertWithDetails[]>([]);
   const [stats, setStats] = useState<AlertStats | null>(null);
   const [unreadCount, setUnreadCount] = useState(0);
+  const [unacknowledgedCritical, setUnacknowledgedCritical] = useState<AlertWithDetails[]>([]);
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
 
@@ -20,15 +22,15 @@ export function useAlerts() {
     setLoading(true);
     setError(null);
     try {
-      const data = await invoke<AlertWithDetails[]>('get_alerts', {
+      const data = await invoke<Paginated<AlertWithDetails>>('get_alerts', {
         token,
         unreadOnly: options?.unreadOnly,
         alertType: options?.alertType,
         limit: options?.limit,
       });
-      setAlerts(data);
+      setAlerts(data.items);
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to fetch alerts');
+      setError(errorMessage(err, 'Failed to fetch alerts'));
     } finally {
       setLoading(false);
     }
@@ -41,7 +43,7 @@ export function useAlerts() {
       setStats(data);
       setUnreadCount(data.unread);
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to fetch alert stats');
+      setError(errorMessage(err, 'Failed to fetch alert stats'));
     }
   }, [token]);
 
@@ -63,7 +65,7 @@ export function useAlerts() {
       setUnreadCount(prev => prev + 1);
       return alert;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to create alert';
+      const errorMsg = errorMessage(err, 'Failed to create alert');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
@@ -77,7 +79,7 @@ export function useAlerts() {
       setUnreadCount(prev => Math.max(0, prev - 1));
       return true;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to mark alert as read';
+      const errorMsg = errorMessage(err, 'Failed to mark alert as read');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
@@ -91,7 +93,32 @@ export function useAlerts() {
       setUnreadCount(0);
       return count;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to mark all alerts as read';
+      const errorMsg = errorMessage(err, 'Failed to mark all alerts as read');
+      setError(errorMsg);
+      throw new Error(errorMsg);
+    }
+  }, [token]);
+
+  const fetchUnacknowledgedCritical = useCallback(async () => {
+    if (!token) return;
+    try {
+      setUnacknowledgedCritical(
+        await invoke<AlertWithDetails[]>('get_unacknowledged_critical_alerts', { token })
+      );
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch critical alerts'));
+    }
+  }, [token]);
+
+  const acknowledgeAlert = useCallback(async (id: number, comment: string): Promise<AlertWithDetails | null> => {
+    if (!token) return null;
+    try {
+      const alert = await invoke<AlertWithDetails>('acknowledge_alert', { token, id, comment });
+      setAlerts(prev => prev.map(a => a.id === id ? alert : a));
+      setUnacknowledgedCritical(prev => prev.filter(a => a.id !== id));
+      return alert;
+    } catch (err) {
+      const errorMsg = errorMessage(err, 'Failed to acknowledge alert');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
@@ -108,7 +135,7 @@ export function useAlerts() {
       }
       return true;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to dismiss alert';
+      const errorMsg = errorMessage(err, 'Failed to dismiss alert');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
@@ -118,19 +145,21 @@ export function useAlerts() {
     if (!token) return 0;
     try {
       const count = await invoke<number>('clear_read_alerts', { token });
-      setAlerts(prev => prev.filter(a => !a.is_read));
+      // What was cleared depends on everyone's reads, not just this user's
+      await fetchAlerts();
       return count;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to clear read alerts';
+      const errorMsg = errorMessage(err, 'Failed to clear read alerts');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
-  }, [token]);
+  }, [token, fetchAlerts]);
 
   return {
     alerts,
     stats,
     unreadCount,
+    unacknowledgedCritical,
     loading,
     error,
     fetchAlerts,
@@ -139,6 +168,8 @@ export function useAlerts() {
     createAlert,
     markAsRead,
     markAllAsRead,
+    fetchUnacknowledgedCritical,
+    acknowledgeAlert,
     dismissAlert,
     clearReadAlerts,
     clearError: () => setError(null),
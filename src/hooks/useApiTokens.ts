@@ -0,0 +1,59 @@
+import { useState, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { useAuth } from '../context/AuthContext';
+import type { ApiToken, CreatedApiToken } from '../types';
+import { errorMessage } from '../utils/errors';
+
+export function useApiTokens() {
+  const { token } = useAuth();
+  const [apiTokens, setApiTokens] = useState<ApiToken[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const fetchApiTokens = useCallback(async (userId?: number) => {
+    if (!token) return;
+    setLoading(true);
+    setError(null);
+    try {
+      setApiTokens(await invoke<ApiToken[]>('get_api_tokens', { token, userId: userId ?? null }));
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch API tokens'));
+    } finally {
+      setLoading(false);
+    }
+  }, [token]);
+
+  const createApiToken = useCallback(async (userId: number, name: string): Promise<CreatedApiToken | null> => {
+    if (!token) return null;
+    try {
+      const created = await invoke<CreatedApiToken>('create_api_token', { token, userId, name });
+      setApiTokens(prev => [created, ...prev]);
+      return created;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to create API token'));
+      return null;
+    }
+  }, [token]);
+
+  const revokeApiToken = useCallback(async (id: number): Promise<boolean> => {
+    if (!token) return false;
+    try {
+      const revoked = await invoke<ApiToken>('revoke_api_token', { token, id });
+      setApiTokens(prev => prev.map(t => (t.id === id ? revoked : t)));
+      return true;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to revoke API token'));
+      return false;
+    }
+  }, [token]);
+
+  return {
+    apiTokens,
+    loading,
+    error,
+    fetchApiTokens,
+    createApiToken,
+    revokeApiToken,
+    clearError: () => setError(null),
+  };
+}
@@ -0,0 +1,47 @@
+import { useState, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { useAuth } from '../context/AuthContext';
+import type { SettingValue } from '../types';
+import { errorMessage } from '../utils/errors';
+
+export function useAppSettings() {
+  const { token } = useAuth();
+  const [settings, setSettings] = useState<SettingValue[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const fetchSettings = useCallback(async () => {
+    if (!token) return;
+    setLoading(true);
+    setError(null);
+    try {
+      setSettings(await invoke<SettingValue[]>('get_settings', { token }));
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to load settings'));
+    } finally {
+      setLoading(false);
+    }
+  }, [token]);
+
+  const updateSetting = useCallback(async (key: string, value: string): Promise<SettingValue | null> => {
+    if (!token) return null;
+    setError(null);
+    try {
+      const updated = await invoke<SettingValue>('update_setting', { token, key, value });
+      setSettings((prev) => prev.map((s) => (s.key === updated.key ? updated : s)));
+      return updated;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to update setting'));
+      return null;
+    }
+  }, [token]);
+
+  return {
+    settings,
+    loading,
+    error,
+    fetchSettings,
+    updateSetting,
+    clearError: () => setError(null),
+  };
+}
@@ -1,5 +1,7 @@
 import { useState, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import type { PageInfo, Paginated } from '../types';
+import { errorMessage } from '../utils/errors';
 
 // Types
 export interface AuditLog {
@@ -31,6 +33,16 @@ export interface AuditStats {
   actions_breakdown: [string, number][];
   tables_breakdown: [string, number][];
   top_users: [string, number][];
+  /** Sign-ins, sign-outs and permission denials, left out of the figures above */
+  auth: AuthAuditStats;
+}
+
+export interface AuthAuditStats {
+  total: number;
+  today_count: number;
+  week_count: number;
+  failed_logins_today: number;
+  actions_breakdown: [string, number][];
 }
 
 export interface AuditFilterOptions {
@@ -39,9 +51,23 @@ export interface AuditFilterOptions {
   users: [number, string][];
 }
 
+export interface RestoreResult {
+  table_name: string;
+  /** Id of the recreated record; differs from original_id when that was reused */
+  record_id: number;
+  original_id: number | null;
+  /** References to since-deleted records that were cleared, e.g. "clients #4 (client_id)" */
+  cleared_references: string[];
+}
+
+/** Tables whose deleted records restore_from_audit can recreate */
+export const RESTORABLE_TABLES = ['clients', 'machines', 'projects', 'schedules', 'maintenance'];
+
 interface UseAuditLogReturn {
   // Data
   logs: AuditLog[];
+  /** Paging of the last fetchLogs call, including the total matching rows */
+  pagination: PageInfo | null;
   stats: AuditStats | null;
   filterOptions: AuditFilterOptions | null;
 
@@ -53,11 +79,13 @@ interface UseAuditLogReturn {
   fetchLogs: (filters?: AuditFilters) => Promise<void>;
   fetchStats: () => Promise<void>;
   fetchFilterOptions: () => Promise<void>;
+  restoreFromAudit: (auditLogId: number) => Promise<RestoreResult | null>;
   clearError: () => void;
 }
 
 export function useAuditLog(): UseAuditLogReturn {
   const [logs, setLogs] = useState<AuditLog[]>([]);
+  const [pagination, setPagination] = useState<PageInfo | null>(null);
   const [stats, setStats] = useState<AuditStats | null>(null);
   const [filterOptions, setFilterOptions] = useState<AuditFilterOptions | null>(null);
   const [loading, setLoading] = useState(false);
@@ -72,13 +100,14 @@ export function useAuditLog(): UseAuditLogReturn {
     setError(null);
     try {
       const token = getToken();
-      const result = await invoke<AuditLog[]>('get_audit_logs', {
+      const result = await invoke<Paginated<AuditLog>>('get_audit_logs', {
         token,
         filters: filters || null,
       });
-      setLogs(result);
+      setLogs(result.items);
+      setPagination(result.pagination);
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(errorMessage(err));
     } finally {
       setLoading(false);
     }
@@ -92,7 +121,7 @@ export function useAuditLog(): UseAuditLogReturn {
       const result = await invoke<AuditStats>('get_audit_stats', { token });
       setStats(result);
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(errorMessage(err));
     } finally {
       setLoading(false);
     }
@@ -106,18 +135,30 @@ export function useAuditLog(): UseAuditLogReturn {
       const result = await invoke<AuditFilterOptions>('get_audit_filter_options', { token });
       setFilterOptions(result);
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(errorMessage(err));
     } finally {
       setLoading(false);
     }
   }, []);
 
+  const restoreFromAudit = useCallback(async (auditLogId: number): Promise<RestoreResult | null> => {
+    setError(null);
+    try {
+      const token = getToken();
+      return await invoke<RestoreResult>('restore_from_audit', { token, auditLogId });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to restore record'));
+      return null;
+    }
+  }, []);
+
   const clearError = useCallback(() => {
     setError(null);
   }, []);
 
   return {
     logs,
+    pagination,
     stats,
     filterOptions,
     loading,
@@ -125,6 +166,7 @@ export function useAuditLog(): UseAuditLogReturn {
     fetchLogs,
     fetchStats,
     fetchFilterOptions,
+    restoreFromAudit,
     clearError,
   };
 }
@@ -1,7 +1,11 @@
 import { useState, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useAuth } from '../context/AuthContext';
-import type { Client, CreateClientInput, UpdateClientInput } from '../types';
+import type {
+  Client, CreateClientInput, UpdateClientInput, Paginated, ClientWithContacts, ClientContact, CreateContactInput,
+  UpdateContactInput, ClientWithStats, ClientImportResult, ClientActivityItem,
+} from '../types';
+import { errorMessage } from '../utils/errors';
 
 export function useClients() {
   const { token } = useAuth();
@@ -9,26 +13,50 @@ export function useClients() {
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
 
-  const fetchClients = useCallback(async () => {
+  const fetchClients = useCallback(async (includeArchived?: boolean) => {
     if (!token) return;
     setLoading(true);
     setError(null);
     try {
-      const data = await invoke<Client[]>('get_clients', { token });
-      setClients(data);
+      const data = await invoke<Paginated<Client>>('get_clients', { token, includeArchived });
+      setClients(data.items);
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to fetch clients');
+      setError(errorMessage(err, 'Failed to fetch clients'));
     } finally {
       setLoading(false);
     }
   }, [token]);
 
-  const getClient = useCallback(async (id: number): Promise<Client | null> => {
+  const getClientsWithStats = useCallback(async (includeArchived?: boolean): Promise<ClientWithStats[] | null> => {
     if (!token) return null;
     try {
-      return await invoke<Client>('get_client', { token, id });
+      return await invoke<ClientWithStats[]>('get_clients_with_stats', { token, includeArchived });
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to fetch client');
+      setError(errorMessage(err, 'Failed to fetch client statistics'));
+      return null;
+    }
+  }, [token]);
+
+  const getClient = useCallback(async (id: number): Promise<ClientWithContacts | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<ClientWithContacts>('get_client', { token, id });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch client'));
+      return null;
+    }
+  }, [token]);
+
+  const getClientActivity = useCallback(async (
+    clientId: number,
+    limit?: number,
+    offset?: number
+  ): Promise<Paginated<ClientActivityItem> | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<Paginated<ClientActivityItem>>('get_client_activity', { token, clientId, limit, offset });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch client activity'));
       return null;
     }
   }, [token]);
@@ -40,7 +68,7 @@ export function useClients() {
       setClients(prev => [...prev, client]);
       return client;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to create client';
+      const errorMsg = errorMessage(err, 'Failed to create client');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
@@ -53,7 +81,7 @@ export function useClients() {
       setClients(prev => prev.map(c => c.id === id ? client : c));
       return client;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to update client';
+      const errorMsg = errorMessage(err, 'Failed to update client');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
@@ -66,21 +94,118 @@ export function useClients() {
       setClients(prev => prev.filter(c => c.id !== id));
       return true;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to delete client';
+      const errorMsg = errorMessage(err, 'Failed to delete client');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
   }, [token]);
 
+  const importClientsCsv = useCallback(async (
+    filePath: string,
+    mergeDuplicates = false,
+    dryRun = false
+  ): Promise<ClientImportResult | null> => {
+    if (!token) return null;
+    try {
+      const result = await invoke<ClientImportResult>('import_clients_csv', { token, filePath, mergeDuplicates, dryRun });
+      if (!dryRun && result.imported.length > 0) {
+        const imported = new Map(result.imported.map(c => [c.id, c]));
+        setClients(prev => [
+          ...prev.filter(c => !imported.has(c.id)),
+          ...result.imported,
+        ].sort((a, b) => a.name.localeCompare(b.name)));
+      }
+      return result;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to import clients'));
+      return null;
+    }
+  }, [token]);
+
+  const archiveClient = useCallback(async (id: number): Promise<boolean> => {
+    if (!token) return false;
+    try {
+      await invoke<Client>('archive_client', { token, id });
+      setClients(prev => prev.filter(c => c.id !== id));
+      return true;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to archive client'));
+      return false;
+    }
+  }, [token]);
+
+  const unarchiveClient = useCallback(async (id: number): Promise<Client | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<Client>('unarchive_client', { token, id });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to unarchive client'));
+      return null;
+    }
+  }, [token]);
+
+  const getContacts = useCallback(async (clientId: number): Promise<ClientContact[] | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<ClientContact[]>('get_client_contacts', { token, clientId });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch contacts'));
+      return null;
+    }
+  }, [token]);
+
+  const createContact = useCallback(async (
+    clientId: number,
+    input: CreateContactInput
+  ): Promise<ClientContact | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<ClientContact>('create_client_contact', { token, clientId, input });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to create contact'));
+      return null;
+    }
+  }, [token]);
+
+  const updateContact = useCallback(async (id: number, input: UpdateContactInput): Promise<ClientContact | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<ClientContact>('update_client_contact', { token, id, input });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to update contact'));
+      return null;
+    }
+  }, [token]);
+
+  const deleteContact = useCallback(async (id: number): Promise<boolean> => {
+    if (!token) return false;
+    try {
+      await invoke('delete_client_contact', { token, id });
+      return true;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to delete contact'));
+      return false;
+    }
+  }, [token]);
+
   return {
     clients,
     loading,
     error,
     fetchClients,
+    getClientsWithStats,
     getClient,
+    getClientActivity,
     createClient,
     updateClient,
     deleteClient,
+    importClientsCsv,
+    archiveClient,
+    unarchiveClient,
+    getContacts,
+    createContact,
+    updateContact,
+    deleteContact,
     clearError: () => setError(null),
   };
 }
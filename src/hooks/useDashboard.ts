@@ -2,6 +2,7 @@ import { useState, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useAuth } from '../context/AuthContext';
 import type { DashboardStats, MachineUtilization, ProjectProgress } from '../types';
+import { errorMessage } from '../utils/errors';
 
 export function useDashboard() {
   const { token } = useAuth();
@@ -11,31 +12,32 @@ export function useDashboard() {
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
 
-  const fetchDashboardStats = useCallback(async () => {
+  const fetchDashboardStats = useCallback(async (siteId?: number) => {
     if (!token) return;
     setLoading(true);
     setError(null);
     try {
-      const data = await invoke<DashboardStats>('get_dashboard_stats', { token });
+      const data = await invoke<DashboardStats>('get_dashboard_stats', { token, siteId });
       setStats(data);
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to fetch dashboard stats');
+      setError(errorMessage(err, 'Failed to fetch dashboard stats'));
     } finally {
       setLoading(false);
     }
   }, [token]);
 
-  const fetchMachineUtilization = useCallback(async (startDate: string, endDate: string) => {
+  const fetchMachineUtilization = useCallback(async (startDate: string, endDate: string, siteId?: number) => {
     if (!token) return;
     try {
       const data = await invoke<MachineUtilization[]>('get_machine_utilization', {
         token,
         startDate,
-        endDate
+        endDate,
+        siteId
       });
       setMachineUtilization(data);
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to fetch machine utilization');
+      setError(errorMessage(err, 'Failed to fetch machine utilization'));
     }
   }, [token]);
 
@@ -45,7 +47,7 @@ export function useDashboard() {
       const data = await invoke<ProjectProgress[]>('get_project_progress', { token });
       setProjectProgress(data);
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to fetch project progress');
+      setError(errorMessage(err, 'Failed to fetch project progress'));
     }
   }, [token]);
 
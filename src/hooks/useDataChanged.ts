@@ -0,0 +1,22 @@
+import { useEffect, useRef } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import type { DataChangeEvent } from '../types';
+
+/** Call `onChange` whenever one of `events` fires, e.g. to refetch what a view shows */
+export function useDataChanged(events: DataChangeEvent[], onChange: () => void) {
+  const handler = useRef(onChange);
+  const eventKey = events.join(',');
+
+  useEffect(() => {
+    handler.current = onChange;
+  });
+
+  useEffect(() => {
+    const unlisten = eventKey
+      .split(',')
+      .map((event) => listen(event, () => handler.current()));
+    return () => {
+      unlisten.forEach((pending) => pending.then((stop) => stop()));
+    };
+  }, [eventKey]);
+}
@@ -0,0 +1,58 @@
+import { useState, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { useAuth } from '../context/AuthContext';
+import type { DataExportSummary, DataImportMode, DataImportResult } from '../types';
+import { errorMessage } from '../utils/errors';
+
+export function useDataTransfer() {
+  const { token, logout } = useAuth();
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const exportAllData = useCallback(async (
+    filePath: string,
+    includeCredentials = false
+  ): Promise<DataExportSummary | null> => {
+    if (!token) return null;
+    setLoading(true);
+    setError(null);
+    try {
+      return await invoke<DataExportSummary>('export_all_data', { token, filePath, includeCredentials });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to export data'));
+      return null;
+    } finally {
+      setLoading(false);
+    }
+  }, [token]);
+
+  /** A replace signs everyone out, so it ends this session too */
+  const importAllData = useCallback(async (
+    filePath: string,
+    mode: DataImportMode
+  ): Promise<DataImportResult | null> => {
+    if (!token) return null;
+    setLoading(true);
+    setError(null);
+    try {
+      const result = await invoke<DataImportResult>('import_all_data', { token, filePath, mode });
+      if (result.signed_out) {
+        await logout();
+      }
+      return result;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to import data'));
+      return null;
+    } finally {
+      setLoading(false);
+    }
+  }, [token, logout]);
+
+  return {
+    loading,
+    error,
+    exportAllData,
+    importAllData,
+    clearError: () => setError(null),
+  };
+}
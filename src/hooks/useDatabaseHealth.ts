@@ -0,0 +1,53 @@
+import { useState, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { useAuth } from '../context/AuthContext';
+import type { DatabaseHealth, OptimizeResult } from '../types';
+import { errorMessage } from '../utils/errors';
+
+export function useDatabaseHealth() {
+  const { token } = useAuth();
+  const [health, setHealth] = useState<DatabaseHealth | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [optimizing, setOptimizing] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const fetchHealth = useCallback(async () => {
+    if (!token) return;
+    setLoading(true);
+    setError(null);
+    try {
+      setHealth(await invoke<DatabaseHealth>('get_database_health', { token }));
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to load database health'));
+    } finally {
+      setLoading(false);
+    }
+  }, [token]);
+
+  /** VACUUM and ANALYZE, then reload the report so sizes reflect the result */
+  const optimize = useCallback(async (): Promise<OptimizeResult | null> => {
+    if (!token) return null;
+    setOptimizing(true);
+    setError(null);
+    try {
+      const result = await invoke<OptimizeResult>('optimize_database', { token });
+      setHealth(await invoke<DatabaseHealth>('get_database_health', { token }));
+      return result;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to optimize database'));
+      return null;
+    } finally {
+      setOptimizing(false);
+    }
+  }, [token]);
+
+  return {
+    health,
+    loading,
+    optimizing,
+    error,
+    fetchHealth,
+    optimize,
+    clearError: () => setError(null),
+  };
+}
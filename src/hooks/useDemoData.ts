@@ -0,0 +1,36 @@
+import { useState, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { useAuth } from '../context/AuthContext';
+import type { DemoDataSummary } from '../types';
+import { errorMessage } from '../utils/errors';
+
+export function useDemoData() {
+  const { token } = useAuth();
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const run = useCallback(async (command: string, fallback: string): Promise<DemoDataSummary | null> => {
+    if (!token) return null;
+    setLoading(true);
+    setError(null);
+    try {
+      return await invoke<DemoDataSummary>(command, { token });
+    } catch (err) {
+      setError(errorMessage(err, fallback));
+      return null;
+    } finally {
+      setLoading(false);
+    }
+  }, [token]);
+
+  const loadDemoData = useCallback(() => run('load_demo_data', 'Failed to load demo data'), [run]);
+  const clearDemoData = useCallback(() => run('clear_demo_data', 'Failed to clear demo data'), [run]);
+
+  return {
+    loading,
+    error,
+    loadDemoData,
+    clearDemoData,
+    clearError: () => setError(null),
+  };
+}
@@ -1,6 +1,7 @@
 import { useState, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useAuth } from '../context/AuthContext';
+import { errorMessage } from '../utils/errors';
 
 export interface DowntimeLog {
   id: number;
@@ -31,7 +32,7 @@ export function useDowntimeLog() {
       });
       setLogs(data);
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to load downtime logs');
+      setError(errorMessage(err, 'Failed to load downtime logs'));
     } finally {
       setLoading(false);
     }
@@ -50,7 +51,7 @@ export function useDowntimeLog() {
       setLogs(prev => [log, ...prev]);
       return log;
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to log downtime');
+      setError(errorMessage(err, 'Failed to log downtime'));
       return null;
     }
   }, [token]);
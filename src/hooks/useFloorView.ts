@@ -2,6 +2,7 @@ import { useState, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useAuth } from '../context/AuthContext';
 import { formatLocalDate } from './useSchedules';
+import { errorMessage } from '../utils/errors';
 
 export interface FloorSchedule {
   id: number;
@@ -38,7 +39,7 @@ export function useFloorView() {
       const data = await invoke<FloorSchedule[]>('get_operator_schedule', { token, date: today });
       setSchedules(data);
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to load schedule');
+      setError(errorMessage(err, 'Failed to load schedule'));
     } finally {
       setLoading(false);
     }
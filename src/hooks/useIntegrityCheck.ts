@@ -0,0 +1,51 @@
+import { useState, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { useAuth } from '../context/AuthContext';
+import type { IntegrityCategoryKey, IntegrityFix, IntegrityReport } from '../types';
+import { errorMessage } from '../utils/errors';
+
+export function useIntegrityCheck() {
+  const { token } = useAuth();
+  const [report, setReport] = useState<IntegrityReport | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const runCheck = useCallback(async () => {
+    if (!token) return;
+    setLoading(true);
+    setError(null);
+    try {
+      setReport(await invoke<IntegrityReport>('run_integrity_check', { token }));
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to run integrity check'));
+    } finally {
+      setLoading(false);
+    }
+  }, [token]);
+
+  /** Repair the given categories, then check again so the report stays current */
+  const fixIssues = useCallback(async (categories: IntegrityCategoryKey[]): Promise<IntegrityFix[] | null> => {
+    if (!token) return null;
+    setLoading(true);
+    setError(null);
+    try {
+      const fixes = await invoke<IntegrityFix[]>('fix_integrity_issues', { token, categories });
+      setReport(await invoke<IntegrityReport>('run_integrity_check', { token }));
+      return fixes;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fix integrity issues'));
+      return null;
+    } finally {
+      setLoading(false);
+    }
+  }, [token]);
+
+  return {
+    report,
+    loading,
+    error,
+    runCheck,
+    fixIssues,
+    clearError: () => setError(null),
+  };
+}
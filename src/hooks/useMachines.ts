@@ -1,7 +1,9 @@
 import { useState, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useAuth } from '../context/AuthContext';
-import type { Machine, CreateMachineInput, UpdateMachineInput, MachineHistoryResponse } from '../types';
+import type { Machine, MachineDocument, MachineDocumentContent, MachineSortField, MachineFilter, ExpiringContract, MachineQrPayload, MachineStatusTransition, MachineImportResult, MachineListItem, MachineWithStats, FleetMachineSummary, MachineDowntime, MachineOee, CreateMachineInput, UpdateMachineInput, MachineHistoryResponse, Paginated } from '../types';
+import type { CascadeEffect } from '../components/common/DeleteConfirmModal';
+import { errorMessage } from '../utils/errors';
 
 export function useMachines() {
   const { token } = useAuth();
@@ -9,26 +11,169 @@ export function useMachines() {
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
 
-  const fetchMachines = useCallback(async () => {
+  const fetchMachines = useCallback(async (includeArchived?: boolean) => {
     if (!token) return;
     setLoading(true);
     setError(null);
     try {
-      const data = await invoke<Machine[]>('get_machines', { token });
-      setMachines(data);
+      const data = await invoke<Paginated<Machine>>('get_machines', { token, includeArchived });
+      setMachines(data.items);
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to fetch machines');
+      setError(errorMessage(err, 'Failed to fetch machines'));
     } finally {
       setLoading(false);
     }
   }, [token]);
 
+  const fetchMachinesWithStats = useCallback(async (): Promise<MachineWithStats[] | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<MachineWithStats[]>('get_machines_with_stats', { token });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch machine stats'));
+      return null;
+    }
+  }, [token]);
+
+  const fetchFleetOverview = useCallback(async (): Promise<FleetMachineSummary[] | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<FleetMachineSummary[]>('get_fleet_overview', { token });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch fleet overview'));
+      return null;
+    }
+  }, [token]);
+
+  // Download the fleet overview as a CSV file
+  const exportFleetOverview = useCallback(async (): Promise<boolean> => {
+    if (!token) return false;
+    try {
+      const csv = await invoke<string>('export_fleet_overview_csv', { token });
+      const url = URL.createObjectURL(new Blob([csv], { type: 'text/csv' }));
+      const link = document.createElement('a');
+      link.href = url;
+      link.download = `fleet-overview-${new Date().toISOString().slice(0, 10)}.csv`;
+      link.click();
+      URL.revokeObjectURL(url);
+      return true;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to export fleet overview'));
+      return false;
+    }
+  }, [token]);
+
+  const getMachineDowntime = useCallback(async (
+    machineId: number,
+    startDate: string,
+    endDate: string
+  ): Promise<MachineDowntime | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<MachineDowntime>('get_machine_downtime', { token, machineId, startDate, endDate });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch machine downtime'));
+      return null;
+    }
+  }, [token]);
+
+  const getMachineOee = useCallback(async (
+    machineId: number,
+    startDate: string,
+    endDate: string
+  ): Promise<MachineOee | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<MachineOee>('get_machine_oee', { token, machineId, startDate, endDate });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch machine OEE'));
+      return null;
+    }
+  }, [token]);
+
+  const fetchMachinesSorted = useCallback(async (
+    sortBy: MachineSortField,
+    order: 'asc' | 'desc' = 'asc'
+  ): Promise<Machine[] | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<Machine[]>('get_machines_sorted', { token, sortBy, order });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch machines'));
+      return null;
+    }
+  }, [token]);
+
+  const queryMachines = useCallback(async (filter: MachineFilter): Promise<Paginated<MachineListItem> | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<Paginated<MachineListItem>>('query_machines', { token, filter });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to search machines'));
+      return null;
+    }
+  }, [token]);
+
+  const getExpiringContracts = useCallback(async (daysAhead?: number): Promise<ExpiringContract[] | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<ExpiringContract[]>('get_expiring_contracts', { token, daysAhead });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch expiring contracts'));
+      return null;
+    }
+  }, [token]);
+
+  const getMachineQrPayload = useCallback(async (machineId: number): Promise<MachineQrPayload | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<MachineQrPayload>('get_machine_qr_payload', { token, machineId });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to generate QR code'));
+      return null;
+    }
+  }, [token]);
+
+  const resolveMachineQr = useCallback(async (payload: string): Promise<Machine | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<Machine>('resolve_machine_qr', { token, payload });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to read QR code'));
+      return null;
+    }
+  }, [token]);
+
+  const syncMachineStatuses = useCallback(async (): Promise<MachineStatusTransition[] | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<MachineStatusTransition[]>('sync_machine_statuses', { token });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to sync machine statuses'));
+      return null;
+    }
+  }, [token]);
+
+  const importMachinesCsv = useCallback(async (filePath: string, dryRun = false): Promise<MachineImportResult | null> => {
+    if (!token) return null;
+    try {
+      const result = await invoke<MachineImportResult>('import_machines_csv', { token, filePath, dryRun });
+      if (!dryRun && result.imported.length > 0) {
+        setMachines(prev => [...prev, ...result.imported].sort((a, b) => a.name.localeCompare(b.name)));
+      }
+      return result;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to import machines'));
+      return null;
+    }
+  }, [token]);
+
   const getMachine = useCallback(async (id: number): Promise<Machine | null> => {
     if (!token) return null;
     try {
       return await invoke<Machine>('get_machine', { token, id });
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to fetch machine');
+      setError(errorMessage(err, 'Failed to fetch machine'));
       return null;
     }
   }, [token]);
@@ -40,7 +185,24 @@ export function useMachines() {
       setMachines(prev => [...prev, machine]);
       return machine;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to create machine';
+      const errorMsg = errorMessage(err, 'Failed to create machine');
+      setError(errorMsg);
+      throw new Error(errorMsg);
+    }
+  }, [token]);
+
+  const cloneMachine = useCallback(async (
+    sourceId: number,
+    newName: string,
+    newSerialNumber?: string
+  ): Promise<Machine | null> => {
+    if (!token) return null;
+    try {
+      const machine = await invoke<Machine>('clone_machine', { token, sourceId, newName, newSerialNumber });
+      setMachines(prev => [...prev, machine]);
+      return machine;
+    } catch (err) {
+      const errorMsg = errorMessage(err, 'Failed to clone machine');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
@@ -53,7 +215,7 @@ export function useMachines() {
       setMachines(prev => prev.map(m => m.id === id ? machine : m));
       return machine;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to update machine';
+      const errorMsg = errorMessage(err, 'Failed to update machine');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
@@ -66,20 +228,110 @@ export function useMachines() {
       setMachines(prev => prev.map(m => m.id === id ? machine : m));
       return machine;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to update machine status';
+      const errorMsg = errorMessage(err, 'Failed to update machine status');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
   }, [token]);
 
-  const deleteMachine = useCallback(async (id: number): Promise<boolean> => {
+  const deleteMachine = useCallback(async (id: number, expectedEffects?: CascadeEffect[]): Promise<boolean> => {
     if (!token) return false;
     try {
-      await invoke('delete_machine', { token, id });
+      await invoke('delete_machine', { token, id, expectedEffects: expectedEffects ?? null });
       setMachines(prev => prev.filter(m => m.id !== id));
       return true;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to delete machine';
+      const errorMsg = errorMessage(err, 'Failed to delete machine');
+      setError(errorMsg);
+      throw new Error(errorMsg);
+    }
+  }, [token]);
+
+  const setMachineRuntimeHours = useCallback(async (
+    machineId: number,
+    hours: number,
+    reason?: string
+  ): Promise<Machine | null> => {
+    if (!token) return null;
+    try {
+      const machine = await invoke<Machine>('set_machine_runtime_hours', { token, machineId, hours, reason });
+      setMachines(prev => prev.map(m => m.id === machineId ? machine : m));
+      return machine;
+    } catch (err) {
+      const errorMsg = errorMessage(err, 'Failed to set runtime hours');
+      setError(errorMsg);
+      throw new Error(errorMsg);
+    }
+  }, [token]);
+
+  const archiveMachine = useCallback(async (id: number): Promise<Machine | null> => {
+    if (!token) return null;
+    try {
+      const machine = await invoke<Machine>('archive_machine', { token, id });
+      setMachines(prev => prev.filter(m => m.id !== id));
+      return machine;
+    } catch (err) {
+      const errorMsg = errorMessage(err, 'Failed to archive machine');
+      setError(errorMsg);
+      throw new Error(errorMsg);
+    }
+  }, [token]);
+
+  const unarchiveMachine = useCallback(async (id: number): Promise<Machine | null> => {
+    if (!token) return null;
+    try {
+      const machine = await invoke<Machine>('unarchive_machine', { token, id });
+      setMachines(prev => prev.map(m => m.id === id ? machine : m));
+      return machine;
+    } catch (err) {
+      const errorMsg = errorMessage(err, 'Failed to unarchive machine');
+      setError(errorMsg);
+      throw new Error(errorMsg);
+    }
+  }, [token]);
+
+  const addMachineDocument = useCallback(async (machineId: number, filePath: string): Promise<MachineDocument | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<MachineDocument>('add_machine_document', { token, machineId, filePath });
+    } catch (err) {
+      const errorMsg = errorMessage(err, 'Failed to add document');
+      setError(errorMsg);
+      throw new Error(errorMsg);
+    }
+  }, [token]);
+
+  const listMachineDocuments = useCallback(async (machineId: number): Promise<MachineDocument[] | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<MachineDocument[]>('list_machine_documents', { token, machineId });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch documents'));
+      return null;
+    }
+  }, [token]);
+
+  // Without targetPath the document's bytes are returned
+  const getMachineDocument = useCallback(async (
+    id: number,
+    targetPath?: string
+  ): Promise<MachineDocumentContent | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<MachineDocumentContent>('get_machine_document', { token, id, targetPath });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to open document'));
+      return null;
+    }
+  }, [token]);
+
+  const deleteMachineDocument = useCallback(async (id: number): Promise<boolean> => {
+    if (!token) return false;
+    try {
+      await invoke('delete_machine_document', { token, id });
+      return true;
+    } catch (err) {
+      const errorMsg = errorMessage(err, 'Failed to delete document');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
@@ -90,7 +342,7 @@ export function useMachines() {
     try {
       return await invoke<MachineHistoryResponse>('get_machine_history', { token, machineId });
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to fetch machine history');
+      setError(errorMessage(err, 'Failed to fetch machine history'));
       return null;
     }
   }, [token]);
@@ -100,12 +352,32 @@ export function useMachines() {
     loading,
     error,
     fetchMachines,
+    fetchMachinesWithStats,
+    fetchMachinesSorted,
+    queryMachines,
+    getExpiringContracts,
+    getMachineQrPayload,
+    resolveMachineQr,
+    syncMachineStatuses,
+    importMachinesCsv,
+    fetchFleetOverview,
+    exportFleetOverview,
+    getMachineDowntime,
+    getMachineOee,
     getMachine,
     createMachine,
+    cloneMachine,
     updateMachine,
     updateMachineStatus,
     deleteMachine,
+    setMachineRuntimeHours,
+    archiveMachine,
+    unarchiveMachine,
     getMachineHistory,
+    addMachineDocument,
+    listMachineDocuments,
+    getMachineDocument,
+    deleteMachineDocument,
     clearError: () => setError(null),
   };
 }
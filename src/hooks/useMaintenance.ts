@@ -5,7 +5,10 @@ import type {
   CreateMaintenanceInput,
   UpdateMaintenanceInput,
   UpcomingMaintenance,
+  RuntimeMaintenanceDue,
+  Paginated,
 } from '../types';
+import { errorMessage } from '../utils/errors';
 
 interface UseMaintenanceReturn {
   // Data
@@ -23,8 +26,9 @@ interface UseMaintenanceReturn {
   fetchUpcomingMaintenance: (daysAhead?: number) => Promise<void>;
   fetchOverdueMaintenance: () => Promise<void>;
   createMaintenance: (input: CreateMaintenanceInput) => Promise<Maintenance | null>;
-  updateMaintenance: (id: number, input: UpdateMaintenanceInput) => Promise<Maintenance | null>;
+  updateMaintenance: (id: number, input: UpdateMaintenanceInput, forceTransition?: boolean) => Promise<Maintenance | null>;
   deleteMaintenance: (id: number) => Promise<boolean>;
+  checkRuntimeMaintenanceDue: () => Promise<RuntimeMaintenanceDue[]>;
   clearError: () => void;
 }
 
@@ -44,10 +48,10 @@ export function useMaintenance(): UseMaintenanceReturn {
     setError(null);
     try {
       const token = getToken();
-      const records = await invoke<Maintenance[]>('get_all_maintenance', { token });
-      setMaintenance(records);
+      const records = await invoke<Paginated<Maintenance>>('get_all_maintenance', { token });
+      setMaintenance(records.items);
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(errorMessage(err));
     } finally {
       setLoading(false);
     }
@@ -64,7 +68,7 @@ export function useMaintenance(): UseMaintenanceReturn {
       });
       return records;
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(errorMessage(err));
       return [];
     } finally {
       setLoading(false);
@@ -82,7 +86,7 @@ export function useMaintenance(): UseMaintenanceReturn {
       });
       setUpcomingMaintenance(records);
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(errorMessage(err));
     } finally {
       setLoading(false);
     }
@@ -96,7 +100,7 @@ export function useMaintenance(): UseMaintenanceReturn {
       const records = await invoke<UpcomingMaintenance[]>('get_overdue_maintenance', { token });
       setOverdueMaintenance(records);
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(errorMessage(err));
     } finally {
       setLoading(false);
     }
@@ -112,7 +116,7 @@ export function useMaintenance(): UseMaintenanceReturn {
         setMaintenance((prev) => [newRecord, ...prev]);
         return newRecord;
       } catch (err) {
-        setError(err instanceof Error ? err.message : String(err));
+        setError(errorMessage(err));
         return null;
       } finally {
         setLoading(false);
@@ -122,18 +126,18 @@ export function useMaintenance(): UseMaintenanceReturn {
   );
 
   const updateMaintenance = useCallback(
-    async (id: number, input: UpdateMaintenanceInput): Promise<Maintenance | null> => {
+    async (id: number, input: UpdateMaintenanceInput, forceTransition?: boolean): Promise<Maintenance | null> => {
       setLoading(true);
       setError(null);
       try {
         const token = getToken();
-        const updated = await invoke<Maintenance>('update_maintenance', { token, id, input });
+        const updated = await invoke<Maintenance>('update_maintenance', { token, id, input, forceTransition });
         setMaintenance((prev) =>
           prev.map((m) => (m.id === id ? updated : m))
         );
         return updated;
       } catch (err) {
-        setError(err instanceof Error ? err.message : String(err));
+        setError(errorMessage(err));
         return null;
       } finally {
         setLoading(false);
@@ -151,13 +155,23 @@ export function useMaintenance(): UseMaintenanceReturn {
       setMaintenance((prev) => prev.filter((m) => m.id !== id));
       return true;
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(errorMessage(err));
       return false;
     } finally {
       setLoading(false);
     }
   }, []);
 
+  const checkRuntimeMaintenanceDue = useCallback(async (): Promise<RuntimeMaintenanceDue[]> => {
+    try {
+      const token = getToken();
+      return await invoke<RuntimeMaintenanceDue[]>('check_runtime_maintenance_due', { token });
+    } catch (err) {
+      setError(errorMessage(err));
+      return [];
+    }
+  }, []);
+
   const clearError = useCallback(() => {
     setError(null);
   }, []);
@@ -175,6 +189,7 @@ export function useMaintenance(): UseMaintenanceReturn {
     createMaintenance,
     updateMaintenance,
     deleteMaintenance,
+    checkRuntimeMaintenanceDue,
     clearError,
   };
 }
@@ -0,0 +1,59 @@
+import { useState, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { useAuth } from '../context/AuthContext';
+import type { NotificationSettings, UpdateNotificationSettingsInput } from '../types';
+import { errorMessage } from '../utils/errors';
+
+export function useNotificationSettings() {
+  const { token } = useAuth();
+  const [settings, setSettings] = useState<NotificationSettings | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const fetchSettings = useCallback(async () => {
+    if (!token) return;
+    setLoading(true);
+    setError(null);
+    try {
+      setSettings(await invoke<NotificationSettings>('get_notification_settings', { token }));
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to load notification settings'));
+    } finally {
+      setLoading(false);
+    }
+  }, [token]);
+
+  const updateSettings = useCallback(async (input: UpdateNotificationSettingsInput): Promise<boolean> => {
+    if (!token) return false;
+    setError(null);
+    try {
+      setSettings(await invoke<NotificationSettings>('update_notification_settings', { token, input }));
+      return true;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to save notification settings'));
+      return false;
+    }
+  }, [token]);
+
+  const testNotification = useCallback(async (): Promise<boolean> => {
+    if (!token) return false;
+    setError(null);
+    try {
+      await invoke('test_notification', { token });
+      return true;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to show a test notification'));
+      return false;
+    }
+  }, [token]);
+
+  return {
+    settings,
+    loading,
+    error,
+    fetchSettings,
+    updateSettings,
+    testNotification,
+    clearError: () => setError(null),
+  };
+}
@@ -0,0 +1,61 @@
+import { useState, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { useAuth } from '../context/AuthContext';
+import type { PermissionState } from '../types';
+import { errorMessage } from '../utils/errors';
+
+export function usePermissions() {
+  const { token } = useAuth();
+  const [permissions, setPermissions] = useState<PermissionState[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const fetchPermissions = useCallback(async (userId?: number) => {
+    if (!token) return;
+    setLoading(true);
+    setError(null);
+    try {
+      setPermissions(await invoke<PermissionState[]>('get_permissions', { token, userId }));
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch permissions'));
+    } finally {
+      setLoading(false);
+    }
+  }, [token]);
+
+  const setPermission = useCallback(async (
+    permission: string,
+    target: { role: string } | { userId: number },
+    allowed: boolean | null
+  ): Promise<PermissionState | null> => {
+    if (!token) return null;
+    try {
+      const updated = await invoke<PermissionState>('set_permission', { token, permission, allowed, ...target });
+      setPermissions(prev => prev.map(p => (p.key === updated.key ? updated : p)));
+      return updated;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to update permission'));
+      return null;
+    }
+  }, [token]);
+
+  const getMyPermissions = useCallback(async (): Promise<string[] | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<string[]>('get_my_permissions', { token });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch your permissions'));
+      return null;
+    }
+  }, [token]);
+
+  return {
+    permissions,
+    loading,
+    error,
+    fetchPermissions,
+    setPermission,
+    getMyPermissions,
+    clearError: () => setError(null),
+  };
+}
@@ -1,7 +1,14 @@
 import { useState, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useAuth } from '../context/AuthContext';
-import type { ProjectWithDetails, CreateProjectInput, UpdateProjectInput } from '../types';
+import type {
+  Project, ProjectWithDetails, CreateProjectInput, UpdateProjectInput, Paginated, ProjectMilestone, CreateMilestoneInput,
+  UpdateMilestoneInput, ProjectTask, CreateTaskInput, UpdateTaskInput, ProjectCost, CreateCostInput, UpdateCostInput,
+  ProjectFinancials, DeadlineRisk, ProjectHourLog, ProjectFilter, ProjectNote, ProjectMachineHours,
+  TeamMemberInput,
+} from '../types';
+import type { CascadeEffect } from '../components/common/DeleteConfirmModal';
+import { errorMessage } from '../utils/errors';
 
 export function useProjects() {
   const { token } = useAuth();
@@ -9,26 +16,48 @@ export function useProjects() {
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
 
-  const fetchProjects = useCallback(async () => {
+  const fetchProjects = useCallback(async (includeArchived?: boolean) => {
     if (!token) return;
     setLoading(true);
     setError(null);
     try {
-      const data = await invoke<ProjectWithDetails[]>('get_projects', { token });
-      setProjects(data);
+      const data = await invoke<Paginated<ProjectWithDetails>>('get_projects', { token, includeArchived });
+      setProjects(data.items);
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to fetch projects');
+      setError(errorMessage(err, 'Failed to fetch projects'));
     } finally {
       setLoading(false);
     }
   }, [token]);
 
+  const queryProjects = useCallback(async (
+    filter: ProjectFilter
+  ): Promise<Paginated<ProjectWithDetails> | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<Paginated<ProjectWithDetails>>('query_projects', { token, filter });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to search projects'));
+      return null;
+    }
+  }, [token]);
+
   const getProject = useCallback(async (id: number): Promise<ProjectWithDetails | null> => {
     if (!token) return null;
     try {
       return await invoke<ProjectWithDetails>('get_project', { token, id });
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to fetch project');
+      setError(errorMessage(err, 'Failed to fetch project'));
+      return null;
+    }
+  }, [token]);
+
+  const getMachineBreakdown = useCallback(async (projectId: number): Promise<ProjectMachineHours[] | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<ProjectMachineHours[]>('get_project_machine_breakdown', { token, projectId });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch machine breakdown'));
       return null;
     }
   }, [token]);
@@ -40,33 +69,92 @@ export function useProjects() {
       setProjects(prev => [project, ...prev]);
       return project;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to create project';
+      const errorMsg = errorMessage(err, 'Failed to create project');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
   }, [token]);
 
-  const updateProject = useCallback(async (id: number, input: UpdateProjectInput): Promise<ProjectWithDetails | null> => {
+  const updateProject = useCallback(async (
+    id: number,
+    input: UpdateProjectInput,
+    forceTransition?: boolean
+  ): Promise<ProjectWithDetails | null> => {
     if (!token) return null;
     try {
-      const project = await invoke<ProjectWithDetails>('update_project', { token, id, input });
+      const project = await invoke<ProjectWithDetails>('update_project', { token, id, input, forceTransition });
       setProjects(prev => prev.map(p => p.id === id ? project : p));
       return project;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to update project';
+      const errorMsg = errorMessage(err, 'Failed to update project');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
   }, [token]);
 
-  const deleteProject = useCallback(async (id: number): Promise<boolean> => {
+  const cloneProject = useCallback(async (
+    sourceId: number,
+    newName: string,
+    options: { newStartDate?: string; newEndDate?: string; copyTeam?: boolean; copyMachines?: boolean } = {}
+  ): Promise<ProjectWithDetails | null> => {
+    if (!token) return null;
+    try {
+      const project = await invoke<ProjectWithDetails>('clone_project', {
+        token,
+        sourceId,
+        newName,
+        newStartDate: options.newStartDate,
+        newEndDate: options.newEndDate,
+        copyTeam: options.copyTeam ?? false,
+        copyMachines: options.copyMachines ?? false,
+      });
+      setProjects(prev => [project, ...prev]);
+      return project;
+    } catch (err) {
+      const errorMsg = errorMessage(err, 'Failed to clone project');
+      setError(errorMsg);
+      throw new Error(errorMsg);
+    }
+  }, [token]);
+
+  const deleteProject = useCallback(async (
+    id: number,
+    force?: boolean,
+    expectedEffects?: CascadeEffect[]
+  ): Promise<boolean> => {
     if (!token) return false;
     try {
-      await invoke('delete_project', { token, id });
+      await invoke('delete_project', { token, id, force, expectedEffects: expectedEffects ?? null });
       setProjects(prev => prev.filter(p => p.id !== id));
       return true;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to delete project';
+      const errorMsg = errorMessage(err, 'Failed to delete project');
+      setError(errorMsg);
+      throw new Error(errorMsg);
+    }
+  }, [token]);
+
+  const archiveProject = useCallback(async (id: number): Promise<Project | null> => {
+    if (!token) return null;
+    try {
+      const project = await invoke<Project>('archive_project', { token, id });
+      setProjects(prev => prev.filter(p => p.id !== id));
+      return project;
+    } catch (err) {
+      const errorMsg = errorMessage(err, 'Failed to archive project');
+      setError(errorMsg);
+      throw new Error(errorMsg);
+    }
+  }, [token]);
+
+  const unarchiveProject = useCallback(async (id: number): Promise<Project | null> => {
+    if (!token) return null;
+    try {
+      const project = await invoke<Project>('unarchive_project', { token, id });
+      setProjects(prev => prev.map(p => p.id === id ? { ...p, ...project } : p));
+      return project;
+    } catch (err) {
+      const errorMsg = errorMessage(err, 'Failed to unarchive project');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
@@ -79,50 +167,374 @@ export function useProjects() {
       await fetchProjects(); // Refresh to get updated data
       return true;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to assign machines';
+      const errorMsg = errorMessage(err, 'Failed to assign machines');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
   }, [token, fetchProjects]);
 
-  const assignTeam = useCallback(async (projectId: number, userIds: number[]): Promise<boolean> => {
+  const assignTeam = useCallback(async (projectId: number, members: TeamMemberInput[]): Promise<boolean> => {
     if (!token) return false;
     try {
-      await invoke('assign_team_to_project', { token, projectId, userIds });
+      await invoke('assign_team_to_project', { token, projectId, members });
       await fetchProjects(); // Refresh to get updated data
       return true;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to assign team';
+      const errorMsg = errorMessage(err, 'Failed to assign team');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
   }, [token, fetchProjects]);
 
-  const logHours = useCallback(async (projectId: number, hours: number): Promise<boolean> => {
+  const logHours = useCallback(async (
+    projectId: number,
+    hours: number,
+    date?: string,
+    note?: string
+  ): Promise<boolean> => {
     if (!token) return false;
     try {
-      await invoke('log_project_hours', { token, projectId, hours });
+      await invoke('log_project_hours', { token, projectId, hours, date, note });
       await fetchProjects(); // Refresh to get updated hours
       return true;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to log hours';
+      const errorMsg = errorMessage(err, 'Failed to log hours');
+      setError(errorMsg);
+      throw new Error(errorMsg);
+    }
+  }, [token, fetchProjects]);
+
+  const getHourLogs = useCallback(async (projectId: number): Promise<ProjectHourLog[] | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<ProjectHourLog[]>('get_project_hour_logs', { token, projectId });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch hour log'));
+      return null;
+    }
+  }, [token]);
+
+  const correctHourLog = useCallback(async (
+    id: number,
+    hours: number,
+    note?: string
+  ): Promise<ProjectHourLog | null> => {
+    if (!token) return null;
+    try {
+      const log = await invoke<ProjectHourLog>('correct_project_hour_log', { token, id, hours, note });
+      await fetchProjects();
+      return log;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to correct hours'));
+      return null;
+    }
+  }, [token, fetchProjects]);
+
+  const deleteHourLog = useCallback(async (id: number): Promise<boolean> => {
+    if (!token) return false;
+    try {
+      await invoke('delete_project_hour_log', { token, id });
+      await fetchProjects();
+      return true;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to delete hour log entry'));
+      return false;
+    }
+  }, [token, fetchProjects]);
+
+  const getNotes = useCallback(async (
+    projectId: number,
+    limit?: number,
+    offset?: number
+  ): Promise<Paginated<ProjectNote> | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<Paginated<ProjectNote>>('get_project_notes', { token, projectId, limit, offset });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch notes'));
+      return null;
+    }
+  }, [token]);
+
+  const addNote = useCallback(async (projectId: number, note: string): Promise<ProjectNote | null> => {
+    if (!token) return null;
+    try {
+      const created = await invoke<ProjectNote>('add_project_note', { token, projectId, note });
+      await fetchProjects();
+      return created;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to add note'));
+      return null;
+    }
+  }, [token, fetchProjects]);
+
+  const deleteNote = useCallback(async (id: number): Promise<boolean> => {
+    if (!token) return false;
+    try {
+      await invoke('delete_project_note', { token, id });
+      await fetchProjects();
+      return true;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to delete note'));
+      return false;
+    }
+  }, [token, fetchProjects]);
+
+  const recalculateHours = useCallback(async (projectId: number): Promise<boolean> => {
+    if (!token) return false;
+    try {
+      await invoke('recalculate_project_hours', { token, projectId });
+      await fetchProjects();
+      return true;
+    } catch (err) {
+      const errorMsg = errorMessage(err, 'Failed to recalculate hours');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
   }, [token, fetchProjects]);
 
+  const setProjectProgress = useCallback(async (
+    id: number,
+    pct: number | null,
+    note?: string
+  ): Promise<boolean> => {
+    if (!token) return false;
+    try {
+      await invoke('set_project_progress', { token, id, pct, note });
+      await fetchProjects();
+      return true;
+    } catch (err) {
+      const errorMsg = errorMessage(err, 'Failed to set progress');
+      setError(errorMsg);
+      throw new Error(errorMsg);
+    }
+  }, [token, fetchProjects]);
+
+  const getMilestones = useCallback(async (projectId: number): Promise<ProjectMilestone[] | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<ProjectMilestone[]>('get_project_milestones', { token, projectId });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch milestones'));
+      return null;
+    }
+  }, [token]);
+
+  const createMilestone = useCallback(async (
+    projectId: number,
+    input: CreateMilestoneInput
+  ): Promise<ProjectMilestone | null> => {
+    if (!token) return null;
+    try {
+      const milestone = await invoke<ProjectMilestone>('create_project_milestone', { token, projectId, input });
+      await fetchProjects();
+      return milestone;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to create milestone'));
+      return null;
+    }
+  }, [token, fetchProjects]);
+
+  const updateMilestone = useCallback(async (
+    id: number,
+    input: UpdateMilestoneInput
+  ): Promise<ProjectMilestone | null> => {
+    if (!token) return null;
+    try {
+      const milestone = await invoke<ProjectMilestone>('update_project_milestone', { token, id, input });
+      await fetchProjects();
+      return milestone;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to update milestone'));
+      return null;
+    }
+  }, [token, fetchProjects]);
+
+  const deleteMilestone = useCallback(async (id: number): Promise<boolean> => {
+    if (!token) return false;
+    try {
+      await invoke('delete_project_milestone', { token, id });
+      await fetchProjects();
+      return true;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to delete milestone'));
+      return false;
+    }
+  }, [token, fetchProjects]);
+
+  const getTasks = useCallback(async (projectId: number): Promise<ProjectTask[] | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<ProjectTask[]>('get_project_tasks', { token, projectId });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch tasks'));
+      return null;
+    }
+  }, [token]);
+
+  const createTask = useCallback(async (
+    projectId: number,
+    input: CreateTaskInput
+  ): Promise<ProjectTask | null> => {
+    if (!token) return null;
+    try {
+      const task = await invoke<ProjectTask>('create_project_task', { token, projectId, input });
+      await fetchProjects();
+      return task;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to create task'));
+      return null;
+    }
+  }, [token, fetchProjects]);
+
+  const updateTask = useCallback(async (
+    id: number,
+    input: UpdateTaskInput
+  ): Promise<ProjectTask | null> => {
+    if (!token) return null;
+    try {
+      const task = await invoke<ProjectTask>('update_project_task', { token, id, input });
+      await fetchProjects();
+      return task;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to update task'));
+      return null;
+    }
+  }, [token, fetchProjects]);
+
+  const deleteTask = useCallback(async (id: number): Promise<boolean> => {
+    if (!token) return false;
+    try {
+      await invoke('delete_project_task', { token, id });
+      await fetchProjects();
+      return true;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to delete task'));
+      return false;
+    }
+  }, [token, fetchProjects]);
+
+  const getCosts = useCallback(async (projectId: number): Promise<ProjectCost[] | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<ProjectCost[]>('get_project_costs', { token, projectId });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch costs'));
+      return null;
+    }
+  }, [token]);
+
+  const createCost = useCallback(async (
+    projectId: number,
+    input: CreateCostInput
+  ): Promise<ProjectCost | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<ProjectCost>('create_project_cost', { token, projectId, input });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to record cost'));
+      return null;
+    }
+  }, [token]);
+
+  const updateCost = useCallback(async (id: number, input: UpdateCostInput): Promise<ProjectCost | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<ProjectCost>('update_project_cost', { token, id, input });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to update cost'));
+      return null;
+    }
+  }, [token]);
+
+  const deleteCost = useCallback(async (id: number): Promise<boolean> => {
+    if (!token) return false;
+    try {
+      await invoke('delete_project_cost', { token, id });
+      return true;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to delete cost'));
+      return false;
+    }
+  }, [token]);
+
+  const setBudget = useCallback(async (
+    id: number,
+    budgetAmount: number | null,
+    currency?: string
+  ): Promise<ProjectFinancials | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<ProjectFinancials>('set_project_budget', { token, id, budgetAmount, currency });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to set budget'));
+      return null;
+    }
+  }, [token]);
+
+  const getFinancials = useCallback(async (
+    projectId: number,
+    includeMaintenance?: boolean
+  ): Promise<ProjectFinancials | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<ProjectFinancials>('get_project_financials', { token, projectId, includeMaintenance });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch financials'));
+      return null;
+    }
+  }, [token]);
+
+  const checkDeadlines = useCallback(async (): Promise<DeadlineRisk[] | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<DeadlineRisk[]>('check_project_deadlines', { token });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to check project deadlines'));
+      return null;
+    }
+  }, [token]);
+
   return {
     projects,
     loading,
     error,
     fetchProjects,
+    queryProjects,
     getProject,
+    getMachineBreakdown,
     createProject,
     updateProject,
+    cloneProject,
     deleteProject,
+    archiveProject,
+    unarchiveProject,
     assignMachines,
     assignTeam,
     logHours,
+    getHourLogs,
+    correctHourLog,
+    deleteHourLog,
+    getNotes,
+    addNote,
+    deleteNote,
+    recalculateHours,
+    setProjectProgress,
+    getMilestones,
+    createMilestone,
+    updateMilestone,
+    deleteMilestone,
+    getTasks,
+    createTask,
+    updateTask,
+    deleteTask,
+    getCosts,
+    createCost,
+    updateCost,
+    deleteCost,
+    setBudget,
+    getFinancials,
+    checkDeadlines,
     clearError: () => setError(null),
   };
 }
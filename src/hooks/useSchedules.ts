@@ -5,8 +5,15 @@ import type {
   WeeklyScheduleResponse,
   ScheduleWithDetails,
   CreateScheduleInput,
-  UpdateScheduleInput
+  UpdateScheduleInput,
+  Paginated,
+  OperatorWeekSchedule,
+  WeekDiff,
+  ReassignOperatorResult,
+  MutationResult,
+  StatusEntity
 } from '../types';
+import { errorMessage } from '../utils/errors';
 
 // Helper to format a Date as local YYYY-MM-DD (avoids UTC timezone shift)
 export function formatLocalDate(d: Date): string {
@@ -14,7 +21,7 @@ export function formatLocalDate(d: Date): string {
   return `${d.getFullYear()}-${pad(d.getMonth() + 1)}-${pad(d.getDate())}`;
 }
 
-// Helper to get Monday of current week
+// Helper to get Monday of current week; the backend moves it to the configured first day
 export function getWeekStart(date: Date = new Date()): string {
   const d = new Date(date);
   const day = d.getDay();
@@ -37,7 +44,7 @@ export function useSchedules() {
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
 
-  const fetchWeeklySchedule = useCallback(async (weekStart?: string) => {
+  const fetchWeeklySchedule = useCallback(async (weekStart?: string, machineIds?: number[], siteId?: number) => {
     if (!token) return;
     const week = weekStart || currentWeekStart;
     setLoading(true);
@@ -45,12 +52,14 @@ export function useSchedules() {
     try {
       const data = await invoke<WeeklyScheduleResponse>('get_weekly_schedule', {
         token,
-        weekStart: week
+        weekStart: week,
+        machineIds,
+        siteId
       });
       setWeeklySchedule(data);
-      setCurrentWeekStart(week);
+      setCurrentWeekStart(data.week_start);
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to fetch weekly schedule');
+      setError(errorMessage(err, 'Failed to fetch weekly schedule'));
     } finally {
       setLoading(false);
     }
@@ -79,7 +88,51 @@ export function useSchedules() {
     try {
       return await invoke<ScheduleWithDetails>('get_schedule', { token, id });
     } catch (err) {
-      setError(typeof err === 'string' ? err : 'Failed to fetch schedule');
+      setError(errorMessage(err, 'Failed to fetch schedule'));
+      return null;
+    }
+  }, [token]);
+
+  const fetchOperatorWeek = useCallback(async (
+    operatorId: number,
+    weekStart?: string
+  ): Promise<OperatorWeekSchedule | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<OperatorWeekSchedule>('get_operator_week', {
+        token,
+        operatorId,
+        weekStart: weekStart || currentWeekStart
+      });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch operator week'));
+      return null;
+    }
+  }, [token, currentWeekStart]);
+
+  const getAllowedTransitions = useCallback(async (
+    entity: StatusEntity,
+    currentStatus: string
+  ): Promise<string[] | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<string[]>('get_allowed_transitions', { token, entity, currentStatus });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch allowed statuses'));
+      return null;
+    }
+  }, [token]);
+
+  const searchSchedules = useCallback(async (
+    query: string,
+    limit?: number,
+    offset?: number
+  ): Promise<Paginated<ScheduleWithDetails> | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<Paginated<ScheduleWithDetails>>('search_schedules', { token, query, limit, offset });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to search schedules'));
       return null;
     }
   }, [token]);
@@ -92,69 +145,149 @@ export function useSchedules() {
       await fetchWeeklySchedule(currentWeekStart);
       return schedule;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to create schedule';
+      const errorMsg = errorMessage(err, 'Failed to create schedule');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
   }, [token, fetchWeeklySchedule, currentWeekStart]);
 
-  const updateSchedule = useCallback(async (id: number, input: UpdateScheduleInput): Promise<ScheduleWithDetails | null> => {
+  const updateSchedule = useCallback(async (
+    id: number,
+    input: UpdateScheduleInput,
+    overrideReason?: string,
+    forceTransition?: boolean
+  ): Promise<ScheduleWithDetails | null> => {
     if (!token) return null;
     try {
-      const schedule = await invoke<ScheduleWithDetails>('update_schedule', { token, id, input });
+      const schedule = await invoke<ScheduleWithDetails>('update_schedule', {
+        token,
+        id,
+        input,
+        overrideReason,
+        forceTransition
+      });
       // Refresh the weekly schedule to show the update
       await fetchWeeklySchedule(currentWeekStart);
       return schedule;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to update schedule';
+      const errorMsg = errorMessage(err, 'Failed to update schedule');
+      setError(errorMsg);
+      throw new Error(errorMsg);
+    }
+  }, [token, fetchWeeklySchedule, currentWeekStart]);
+
+  const moveSchedule = useCallback(async (
+    id: number,
+    newMachineId: number,
+    newDate: string,
+    newStartTime?: string,
+    overrideReason?: string
+  ): Promise<ScheduleWithDetails | null> => {
+    if (!token) return null;
+    try {
+      const schedule = await invoke<ScheduleWithDetails>('move_schedule', {
+        token,
+        id,
+        newMachineId,
+        newDate,
+        newStartTime,
+        overrideReason
+      });
+      await fetchWeeklySchedule(currentWeekStart);
+      return schedule;
+    } catch (err) {
+      const errorMsg = errorMessage(err, 'Failed to move schedule');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
   }, [token, fetchWeeklySchedule, currentWeekStart]);
 
-  const logActualHours = useCallback(async (scheduleId: number, hours: number): Promise<ScheduleWithDetails | null> => {
+  const logActualHours = useCallback(async (
+    scheduleId: number,
+    hours: number,
+    overrideReason?: string
+  ): Promise<ScheduleWithDetails | null> => {
     if (!token) return null;
     try {
-      const schedule = await invoke<ScheduleWithDetails>('log_actual_hours', { token, scheduleId, hours });
+      const schedule = await invoke<ScheduleWithDetails>('log_actual_hours', { token, scheduleId, hours, overrideReason });
       // Refresh to update totals
       await fetchWeeklySchedule(currentWeekStart);
       return schedule;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to log hours';
+      const errorMsg = errorMessage(err, 'Failed to log hours');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
   }, [token, fetchWeeklySchedule, currentWeekStart]);
 
-  const deleteSchedule = useCallback(async (id: number): Promise<boolean> => {
+  const deleteSchedule = useCallback(async (id: number, overrideReason?: string): Promise<boolean> => {
     if (!token) return false;
     try {
-      await invoke('delete_schedule', { token, id });
+      await invoke('delete_schedule', { token, id, overrideReason });
       // Refresh the weekly schedule
       await fetchWeeklySchedule(currentWeekStart);
       return true;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to delete schedule';
+      const errorMsg = errorMessage(err, 'Failed to delete schedule');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
   }, [token, fetchWeeklySchedule, currentWeekStart]);
 
-  const copyWeekSchedule = useCallback(async (sourceWeekStart: string, targetWeekStart: string): Promise<number> => {
-    if (!token) return 0;
+  const reassignOperator = useCallback(async (
+    fromUserId: number,
+    toUserId: number,
+    startDate: string,
+    endDate: string,
+    overrideReason?: string
+  ): Promise<ReassignOperatorResult | null> => {
+    if (!token) return null;
+    try {
+      const result = await invoke<ReassignOperatorResult>('reassign_operator', {
+        token,
+        fromUserId,
+        toUserId,
+        startDate,
+        endDate,
+        overrideReason
+      });
+      await fetchWeeklySchedule(currentWeekStart);
+      return result;
+    } catch (err) {
+      const errorMsg = errorMessage(err, 'Failed to reassign operator');
+      setError(errorMsg);
+      throw new Error(errorMsg);
+    }
+  }, [token, currentWeekStart, fetchWeeklySchedule]);
+
+  const diffWeeks = useCallback(async (weekAStart: string, weekBStart: string): Promise<WeekDiff | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<WeekDiff>('diff_weeks', { token, weekAStart, weekBStart });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to compare weeks'));
+      return null;
+    }
+  }, [token]);
+
+  const copyWeekSchedule = useCallback(async (
+    sourceWeekStart: string,
+    targetWeekStart: string
+  ): Promise<MutationResult<ScheduleWithDetails> | null> => {
+    if (!token) return null;
     try {
-      const count = await invoke<number>('copy_week_schedule', {
+      const result = await invoke<MutationResult<ScheduleWithDetails>>('copy_week_schedule', {
         token,
         sourceWeekStart,
         targetWeekStart
       });
       // Refresh if we're viewing the target week
-      if (currentWeekStart === targetWeekStart) {
-        await fetchWeeklySchedule(targetWeekStart);
+      if (targetWeekStart >= currentWeekStart && targetWeekStart < addWeeks(currentWeekStart, 1)) {
+        await fetchWeeklySchedule(currentWeekStart);
       }
-      return count;
+      return result;
     } catch (err) {
-      const errorMsg = typeof err === 'string' ? err : 'Failed to copy schedule';
+      const errorMsg = errorMessage(err, 'Failed to copy schedule');
       setError(errorMsg);
       throw new Error(errorMsg);
     }
@@ -170,11 +303,17 @@ export function useSchedules() {
     goToPreviousWeek,
     goToNextWeek,
     getSchedule,
+    fetchOperatorWeek,
+    searchSchedules,
+    getAllowedTransitions,
     createSchedule,
     updateSchedule,
+    moveSchedule,
     logActualHours,
     deleteSchedule,
     copyWeekSchedule,
+    diffWeeks,
+    reassignOperator,
     clearError: () => setError(null),
   };
 }
@@ -0,0 +1,81 @@
+import { useState, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { useAuth } from '../context/AuthContext';
+import type { SessionCleanup, SessionPublic } from '../types';
+import { errorMessage } from '../utils/errors';
+
+export function useSessions() {
+  const { token } = useAuth();
+  const [sessions, setSessions] = useState<SessionPublic[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const fetchMySessions = useCallback(async () => {
+    if (!token) return;
+    setLoading(true);
+    setError(null);
+    try {
+      setSessions(await invoke<SessionPublic[]>('get_my_sessions', { token }));
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch sessions'));
+    } finally {
+      setLoading(false);
+    }
+  }, [token]);
+
+  const getUserSessions = useCallback(async (userId: number): Promise<SessionPublic[] | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<SessionPublic[]>('get_user_sessions', { token, userId });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch user sessions'));
+      return null;
+    }
+  }, [token]);
+
+  const revokeSession = useCallback(async (sessionId: number): Promise<boolean> => {
+    if (!token) return false;
+    try {
+      await invoke('revoke_session', { token, sessionId });
+      setSessions(prev => prev.filter(s => s.id !== sessionId));
+      return true;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to revoke session'));
+      return false;
+    }
+  }, [token]);
+
+  const revokeOtherSessions = useCallback(async (): Promise<number | null> => {
+    if (!token) return null;
+    try {
+      const revoked = await invoke<number>('revoke_other_sessions', { token });
+      setSessions(prev => prev.filter(s => s.is_current));
+      return revoked;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to revoke sessions'));
+      return null;
+    }
+  }, [token]);
+
+  const cleanupSessions = useCallback(async (): Promise<SessionCleanup | null> => {
+    if (!token) return null;
+    try {
+      return await invoke<SessionCleanup>('cleanup_sessions', { token });
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to clean up sessions'));
+      return null;
+    }
+  }, [token]);
+
+  return {
+    sessions,
+    loading,
+    error,
+    fetchMySessions,
+    getUserSessions,
+    revokeSession,
+    revokeOtherSessions,
+    cleanupSessions,
+    clearError: () => setError(null),
+  };
+}
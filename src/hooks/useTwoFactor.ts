@@ -0,0 +1,55 @@
+import { useState, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { useAuth } from '../context/AuthContext';
+import type { TotpSetup, User } from '../types';
+import { errorMessage } from '../utils/errors';
+
+export function useTwoFactor() {
+  const { token } = useAuth();
+  const [setup, setSetup] = useState<TotpSetup | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const enableTotp = useCallback(async (): Promise<TotpSetup | null> => {
+    if (!token) return null;
+    try {
+      const result = await invoke<TotpSetup>('enable_totp', { token });
+      setSetup(result);
+      return result;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to start two-factor setup'));
+      return null;
+    }
+  }, [token]);
+
+  const confirmTotp = useCallback(async (code: string): Promise<User | null> => {
+    if (!token) return null;
+    try {
+      const user = await invoke<User>('confirm_totp', { token, code });
+      setSetup(null);
+      return user;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to confirm two-factor login'));
+      return null;
+    }
+  }, [token]);
+
+  const disableTotp = useCallback(async (password: string, userId?: number): Promise<boolean> => {
+    if (!token) return false;
+    try {
+      await invoke('disable_totp', { token, password, userId });
+      return true;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to disable two-factor login'));
+      return false;
+    }
+  }, [token]);
+
+  return {
+    setup,
+    error,
+    enableTotp,
+    confirmTotp,
+    disableTotp,
+    clearError: () => setError(null),
+  };
+}
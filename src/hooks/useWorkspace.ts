@@ -0,0 +1,52 @@
+import { useState, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { useAuth } from '../context/AuthContext';
+import type { DatabaseInfo, WorkspaceSwitch } from '../types';
+import { errorMessage } from '../utils/errors';
+
+export function useWorkspace() {
+  const { token, logout } = useAuth();
+  const [info, setInfo] = useState<DatabaseInfo | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const fetchInfo = useCallback(async () => {
+    if (!token) return;
+    setLoading(true);
+    setError(null);
+    try {
+      setInfo(await invoke<DatabaseInfo>('get_database_info', { token }));
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to fetch database info'));
+    } finally {
+      setLoading(false);
+    }
+  }, [token]);
+
+  /** Switching signs everyone out, so a successful switch ends this session too */
+  const switchWorkspace = useCallback(async (path: string): Promise<WorkspaceSwitch | null> => {
+    if (!token) return null;
+    setLoading(true);
+    setError(null);
+    try {
+      const result = await invoke<WorkspaceSwitch>('switch_workspace', { token, path });
+      setInfo(result.database);
+      await logout();
+      return result;
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to switch workspace'));
+      return null;
+    } finally {
+      setLoading(false);
+    }
+  }, [token, logout]);
+
+  return {
+    info,
+    loading,
+    error,
+    fetchInfo,
+    switchWorkspace,
+    clearError: () => setError(null),
+  };
+}
@@ -1,6 +1,7 @@
 import "./index.css";
 import { useEffect, useState } from "react";
 import { createRoot } from "react-dom/client";
+import { emit } from "@tauri-apps/api/event";
 import { App } from "./App";
 import { Login } from "./components/Login";
 import { AuthProvider, useAuth } from "./context/AuthContext";
@@ -71,6 +72,11 @@ function AuthenticatedApp() {
 }
 
 function Root() {
+  // Tell the backend the first render is on screen so the splash screen can close
+  useEffect(() => {
+    emit("frontend-ready").catch((err) => console.error("Failed to signal frontend ready:", err));
+  }, []);
+
   return (
     <AuthProvider>
       <ToastProvider>
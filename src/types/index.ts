@@ -6,6 +6,9 @@ export interface User {
   full_name: string | null;
   role: 'Admin' | 'Operator' | 'Viewer';
   is_active: boolean;
+  locked_until: string | null;
+  last_login_at: string | null;
+  totp_enabled: boolean;
   created_at: string;
   updated_at: string;
 }
@@ -16,6 +19,72 @@ export interface AuthResponse {
   expires_at: string;
 }
 
+export interface PermissionState {
+  key: string;
+  description: string;
+  default_roles: string[];
+  admin: boolean;
+  operator: boolean;
+  viewer: boolean;
+  user_allowed: boolean | null;
+  effective: boolean | null;
+}
+
+export interface SessionCleanup {
+  deleted: number;
+  remaining: number;
+}
+
+// Error returned by every backend command; branch on `code`, show `message`
+export type AppErrorCode =
+  'unauthorized' | 'forbidden' | 'totp_required' | 'not_found' | 'validation' | 'conflict' | 'database';
+
+export interface AppError {
+  code: AppErrorCode;
+  message: string;
+  field: string | null;
+}
+
+export interface ApiToken {
+  id: number;
+  user_id: number;
+  username: string;
+  name: string;
+  token_hint: string;
+  created_at: string;
+  last_used_at: string | null;
+  revoked: boolean;
+}
+
+// Returned once on creation; `token` cannot be retrieved again
+export interface CreatedApiToken extends ApiToken {
+  token: string;
+}
+
+export interface TotpSetup {
+  secret: string;
+  otpauth_uri: string;
+}
+
+export interface LoginAttempt {
+  id: number;
+  username: string;
+  user_id: number | null;
+  attempted_at: string;
+  success: boolean;
+  host: string | null;
+}
+
+export interface SessionPublic {
+  id: number;
+  user_id: number;
+  created_at: string;
+  expires_at: string;
+  remember_me: boolean;
+  device_name: string | null;
+  is_current: boolean;
+}
+
 export interface CreateUserInput {
   username: string;
   password: string;
@@ -31,6 +100,35 @@ export interface UpdateUserInput {
   is_active?: boolean;
 }
 
+export interface PasswordResetInput {
+  user_id: number;
+  new_password: string;
+}
+
+// Pagination Types
+export interface PageInfo {
+  limit: number;
+  offset: number;
+  total: number;
+  has_more: boolean;
+}
+
+export interface Paginated<T> {
+  items: T[];
+  pagination: PageInfo;
+}
+
+// Status Transition Types
+export type StatusEntity = 'schedule' | 'maintenance' | 'project';
+
+// Mutation Types
+export interface MutationResult<T> {
+  count: number;
+  affected_ids: number[];
+  rows: T[];
+  warnings: string[];
+}
+
 // Client Types
 export interface Client {
   id: number;
@@ -39,6 +137,7 @@ export interface Client {
   contact_phone: string | null;
   address: string | null;
   notes: string | null;
+  archived_at: string | null;
   created_at: string;
   updated_at: string;
 }
@@ -59,6 +158,64 @@ export interface UpdateClientInput {
   notes?: string;
 }
 
+export interface ClientContact {
+  id: number;
+  client_id: number;
+  name: string;
+  role: string | null;
+  email: string | null;
+  phone: string | null;
+  is_primary: boolean;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface ClientWithStats extends Client {
+  project_count: number;
+  active_projects: number;
+  planned_hours: number;
+  actual_hours: number;
+  last_project_end_date: string | null;
+  evaluated_projects: number;
+  late_projects: number;
+  on_time_percentage: number | null;
+}
+
+export type ClientActivityType = 'project_created' | 'project_status' | 'schedule' | 'alert';
+
+export interface ClientActivityItem {
+  item_type: ClientActivityType;
+  occurred_at: string;
+  item_id: number;
+  project_id: number;
+  project_name: string;
+  machine_id: number | null;
+  machine_name: string | null;
+  status: string | null;
+  title: string;
+  detail: string | null;
+}
+
+export interface ClientWithContacts extends Client {
+  contacts: ClientContact[];
+}
+
+export interface CreateContactInput {
+  name: string;
+  role?: string;
+  email?: string;
+  phone?: string;
+  is_primary?: boolean;
+}
+
+export interface UpdateContactInput {
+  name?: string;
+  role?: string;
+  email?: string;
+  phone?: string;
+  is_primary?: boolean;
+}
+
 // Machine Types
 export type MachineStatus = 'active' | 'idle' | 'maintenance' | 'error';
 export type MachineCapacity = 'Small' | 'Medium' | 'Large' | 'Extra Large';
@@ -71,12 +228,22 @@ export interface Machine {
   purchase_date: string | null;
   status: MachineStatus;
   location: string | null;
+  location_id: number | null;
   capacity: MachineCapacity | null;
   power_consumption: string | null;
   dimensions: string | null;
   weight: string | null;
   max_rpm: string | null;
   axis_travel: string | null;
+  power_kw: number | null;
+  weight_kg: number | null;
+  max_rpm_value: number | null;
+  runtime_hours: number;
+  maintenance_interval_hours: number | null;
+  site_id: number | null;
+  warranty_expiry: string | null;
+  amc_expiry: string | null;
+  archived_at: string | null;
   created_at: string;
   updated_at: string;
 }
@@ -94,6 +261,14 @@ export interface CreateMachineInput {
   weight?: string;
   max_rpm?: string;
   axis_travel?: string;
+  power_kw?: number;
+  weight_kg?: number;
+  max_rpm_value?: number;
+  maintenance_interval_hours?: number;
+  site_id?: number;
+  warranty_expiry?: string;
+  amc_expiry?: string;
+  location_id?: number;
 }
 
 export interface UpdateMachineInput {
@@ -109,6 +284,194 @@ export interface UpdateMachineInput {
   weight?: string;
   max_rpm?: string;
   axis_travel?: string;
+  power_kw?: number;
+  weight_kg?: number;
+  max_rpm_value?: number;
+  maintenance_interval_hours?: number;
+  clear_maintenance_interval?: boolean;
+  site_id?: number;
+  clear_site?: boolean;
+  warranty_expiry?: string;
+  clear_warranty_expiry?: boolean;
+  amc_expiry?: string;
+  clear_amc_expiry?: boolean;
+  location_id?: number;
+  clear_location?: boolean;
+}
+
+export type MachineSortField = 'name' | 'power_kw' | 'weight_kg' | 'max_rpm' | 'hourly_rate' | 'purchase_date';
+
+export interface ImportLineError {
+  line: number;
+  name: string | null;
+  error: string;
+}
+
+export interface MachineImportResult {
+  dry_run: boolean;
+  total_rows: number;
+  valid_rows: number;
+  imported: Machine[];
+  errors: ImportLineError[];
+}
+
+export interface ClientImportRow {
+  line: number;
+  name: string;
+  action: 'create' | 'merge' | 'skip';
+  client_id: number | null;
+}
+
+export interface ClientImportResult {
+  dry_run: boolean;
+  total_rows: number;
+  created: number;
+  merged: number;
+  skipped: number;
+  rows: ClientImportRow[];
+  imported: Client[];
+  errors: ImportLineError[];
+}
+
+export interface MachineQrPayload {
+  machine_id: number;
+  payload: string;
+  uses_serial_number: boolean;
+}
+
+export type ContractType = 'warranty' | 'amc';
+
+export interface ExpiringContract {
+  machine_id: number;
+  machine_name: string;
+  contract_type: ContractType;
+  expiry_date: string;
+  days_remaining: number;
+}
+
+export interface MachineFilter {
+  status?: MachineStatus;
+  capacity?: string;
+  location?: string;
+  location_id?: number;
+  search?: string;
+  include_archived?: boolean;
+  sort_by?: MachineSortField;
+  order?: 'asc' | 'desc';
+  include_week_counts?: boolean;
+  limit?: number;
+  offset?: number;
+}
+
+export interface MachineListItem extends Machine {
+  schedules_this_week?: number;
+}
+
+export interface MachineWithStats extends Machine {
+  current_project: string | null;
+  scheduled_hours_this_week: number;
+  actual_hours_this_week: number;
+  maintenance_due: string | null;
+}
+
+// Null figures mean no data was recorded, not zero
+export interface FleetMachineSummary {
+  machine_id: number;
+  machine_name: string;
+  model: string;
+  status: MachineStatus;
+  purchase_date: string | null;
+  age_years: number | null;
+  completed_maintenance_count: number;
+  maintenance_cost: number | null;
+  corrective_count: number;
+  preventive_count: number;
+  corrective_ratio: number | null;
+  downtime_hours: number | null;
+  runtime_hours: number | null;
+  cost_per_runtime_hour: number | null;
+}
+
+export interface StatusHours {
+  status: MachineStatus;
+  hours: number;
+}
+
+export interface MachineDowntime {
+  machine_id: number;
+  machine_name: string;
+  start_date: string;
+  end_date: string;
+  total_hours: number;
+  hours_by_status: StatusHours[];
+  downtime_hours: number;
+  availability_pct: number;
+}
+
+export interface MachineOee {
+  machine_id: number;
+  machine_name: string;
+  start_date: string;
+  end_date: string;
+  availability_pct: number;
+  planned_hours: number;
+  actual_hours: number;
+  efficiency_pct: number | null;
+  oee_pct: number | null;
+}
+
+export interface MachineDocument {
+  id: number;
+  machine_id: number;
+  file_name: string;
+  mime_type: string;
+  size_bytes: number;
+  uploaded_by: number | null;
+  uploaded_by_name: string | null;
+  uploaded_at: string;
+}
+
+export interface MachineDocumentContent {
+  document: MachineDocument;
+  bytes: number[] | null;
+  saved_to: string | null;
+}
+
+// Site Types
+export interface Site {
+  id: number;
+  name: string;
+  timezone: string;
+  created_at: string;
+}
+
+export interface CreateSiteInput {
+  name: string;
+  timezone: string;
+}
+
+export interface UpdateSiteInput {
+  name?: string;
+  timezone?: string;
+}
+
+// Location Types
+export interface Location {
+  id: number;
+  name: string;
+  description: string | null;
+  machine_count: number;
+  created_at: string;
+}
+
+export interface CreateLocationInput {
+  name: string;
+  description?: string;
+}
+
+export interface UpdateLocationInput {
+  name?: string;
+  description?: string;
 }
 
 export interface MachineHistoryResponse {
@@ -118,8 +481,31 @@ export interface MachineHistoryResponse {
   assigned_projects: { id: number; name: string }[];
 }
 
+export interface MachineStatusMismatch {
+  machine_id: number;
+  machine_name: string;
+  current_status: MachineStatus;
+  expected_status: MachineStatus;
+  reason: string;
+}
+
+export interface ReconcileResult {
+  checked: number;
+  mismatches: MachineStatusMismatch[];
+  applied: boolean;
+}
+
+export interface MachineStatusTransition {
+  machine_id: number;
+  machine_name: string;
+  old_status: MachineStatus;
+  new_status: MachineStatus;
+  reason: string;
+}
+
 // Project Types
 export type ProjectStatus = 'planning' | 'active' | 'completed' | 'on-hold';
+export type ProjectPriority = 'low' | 'normal' | 'high' | 'critical';
 
 export interface Project {
   id: number;
@@ -129,9 +515,14 @@ export interface Project {
   start_date: string | null;
   end_date: string | null;
   status: ProjectStatus;
+  priority: ProjectPriority;
   planned_hours: number;
   actual_hours: number;
   part_name?: string | null;
+  external_ref?: string | null;
+  progress_override?: number | null;
+  progress_note?: string | null;
+  archived_at?: string | null;
   created_by: number | null;
   created_at: string;
   updated_at: string;
@@ -141,7 +532,208 @@ export interface ProjectWithDetails extends Project {
   client_name: string | null;
   assigned_machines: number[];
   team_members: number[];
+  team: ProjectTeamMember[];
+  progress_percentage: number;
+  effective_progress: number;
+  milestones: MilestoneSummary;
+  task_hours: TaskHoursSummary;
+  latest_note: ProjectNote | null;
+  note_count: number;
+}
+
+export type TeamRole = 'lead' | 'member';
+
+export interface ProjectTeamMember {
+  user_id: number;
+  role: TeamRole;
+}
+
+export interface TeamMemberInput {
+  user_id: number;
+  // Omit to keep an existing member's role; new members default to member
+  role?: TeamRole;
+}
+
+export interface ProjectMachineHours {
+  machine_id: number;
+  machine_name: string;
+  assigned: boolean;
+  schedule_count: number;
+  planned_hours: number;
+  actual_hours: number;
+  efficiency_percentage: number;
+}
+
+export interface ProjectMilestone {
+  id: number;
+  project_id: number;
+  title: string;
+  due_date: string | null;
+  completed_at: string | null;
+  sort_order: number;
+  notes: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface MilestoneSummary {
+  total: number;
+  completed: number;
+  overdue: number;
+  next_due: ProjectMilestone | null;
+}
+
+export interface CreateMilestoneInput {
+  title: string;
+  due_date?: string;
+  sort_order?: number;
+  notes?: string;
+}
+
+export interface UpdateMilestoneInput {
+  title?: string;
+  due_date?: string;
+  clear_due_date?: boolean;
+  completed?: boolean;
+  sort_order?: number;
+  notes?: string;
+}
+
+export type TaskStatus = 'planning' | 'active' | 'completed' | 'on-hold';
+
+export interface ProjectTask {
+  id: number;
+  project_id: number;
+  name: string;
+  status: TaskStatus;
+  planned_hours: number;
+  actual_hours: number;
+  sort_order: number;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface TaskHoursSummary {
+  task_count: number;
+  completed_tasks: number;
+  planned_hours: number;
+  actual_hours: number;
+}
+
+export interface CreateTaskInput {
+  name: string;
+  status?: TaskStatus;
+  planned_hours?: number;
+  sort_order?: number;
+}
+
+export interface UpdateTaskInput {
+  name?: string;
+  status?: TaskStatus;
+  planned_hours?: number;
+  sort_order?: number;
+}
+
+export interface ProjectHourLog {
+  id: number;
+  project_id: number;
+  user_id: number | null;
+  user_name: string | null;
+  hours: number;
+  date: string;
+  note: string | null;
+  created_at: string;
+}
+
+export interface ProjectNote {
+  id: number;
+  project_id: number;
+  user_id: number | null;
+  user_name: string | null;
+  note: string;
+  created_at: string;
+}
+
+export type CostCategory = 'tooling' | 'material' | 'outsourcing' | 'other';
+
+export interface ProjectCost {
+  id: number;
+  project_id: number;
+  category: CostCategory;
+  description: string;
+  amount: number;
+  cost_date: string;
+  created_by: number | null;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface CreateCostInput {
+  category: CostCategory;
+  description: string;
+  amount: number;
+  cost_date?: string;
+}
+
+export interface UpdateCostInput {
+  category?: CostCategory;
+  description?: string;
+  amount?: number;
+  cost_date?: string;
+}
+
+export interface MachineCostLine {
+  machine_id: number;
+  machine_name: string;
+  hours: number;
+  hourly_rate: number | null;
+  cost: number;
+}
+
+export interface ProjectFinancials {
+  project_id: number;
+  project_name: string;
+  budget_amount: number | null;
+  currency: string | null;
+  machines: MachineCostLine[];
+  machine_cost: number;
+  unrated_hours: number;
+  maintenance_cost: number | null;
+  recorded_costs: { category: CostCategory; amount: number }[];
+  recorded_cost: number;
+  total_cost: number;
+  budget_remaining: number | null;
+  budget_used_percentage: number | null;
+}
+
+export interface DeadlineRisk {
+  project_id: number;
+  project_name: string;
+  end_date: string;
+  days_remaining: number;
   progress_percentage: number;
+  elapsed_percentage: number | null;
+  priority: 'high' | 'critical';
+  alerted: boolean;
+}
+
+export interface OverdueMilestone extends ProjectMilestone {
+  project_name: string;
+  days_overdue: number;
+}
+
+export interface ProjectFilter {
+  status?: ProjectStatus;
+  priority?: ProjectPriority;
+  client_id?: number;
+  search?: string;
+  date_from?: string;
+  date_to?: string;
+  include_archived?: boolean;
+  sort_by?: 'name' | 'status' | 'priority' | 'start_date' | 'end_date' | 'created_at';
+  order?: 'asc' | 'desc';
+  limit?: number;
+  offset?: number;
 }
 
 export interface CreateProjectInput {
@@ -151,8 +743,10 @@ export interface CreateProjectInput {
   start_date?: string;
   end_date?: string;
   status: ProjectStatus;
+  priority?: ProjectPriority;
   planned_hours: number;
   part_name?: string;
+  external_ref?: string;
   assigned_machines?: number[];
   team_members?: number[];
 }
@@ -164,13 +758,16 @@ export interface UpdateProjectInput {
   start_date?: string;
   end_date?: string;
   status?: ProjectStatus;
+  priority?: ProjectPriority;
   planned_hours?: number;
   actual_hours?: number;
   part_name?: string;
+  external_ref?: string;
 }
 
 // Schedule Types
 export type ScheduleStatus = 'scheduled' | 'in-progress' | 'completed' | 'cancelled';
+export type ScheduleSource = 'manual' | 'copy' | 'import' | 'auto' | 'maintenance' | 'api';
 
 export interface Schedule {
   id: number;
@@ -186,6 +783,12 @@ export interface Schedule {
   notes: string | null;
   status: ScheduleStatus;
   created_at: string;
+  updated_by?: number | null;
+  shift_id?: number | null;
+  tag_id?: number | null;
+  task_id?: number | null;
+  external_ref?: string | null;
+  source: ScheduleSource;
   updated_at: string;
 }
 
@@ -193,6 +796,11 @@ export interface ScheduleWithDetails extends Schedule {
   machine_name: string;
   project_name: string | null;
   operator_name: string | null;
+  updated_by_username?: string | null;
+  created_by_name?: string | null;
+  tag_name?: string | null;
+  tag_color?: string | null;
+  material_warnings?: LateMaterial[];
 }
 
 export interface CreateScheduleInput {
@@ -203,7 +811,7 @@ export interface CreateScheduleInput {
   end_time?: string;
   operator_id?: number;
   load_name?: string;
-  planned_hours: number;
+  planned_hours?: number;
   notes?: string;
   status?: ScheduleStatus;
   cam_planned_hours?: number;
@@ -215,6 +823,11 @@ export interface CreateScheduleInput {
   drawing_number?: string;
   revision?: string;
   material?: string;
+  shift_id?: number;
+  tag_id?: number;
+  task_id?: number;
+  external_ref?: string;
+  source?: ScheduleSource;
 }
 
 export interface UpdateScheduleInput {
@@ -237,11 +850,48 @@ export interface UpdateScheduleInput {
   drawing_number?: string;
   revision?: string;
   material?: string;
+  shift_id?: number;
+  tag_id?: number;
+  clear_tag?: boolean;
+  task_id?: number;
+  clear_task?: boolean;
+  external_ref?: string;
 }
 
-// Weekly Planner Types
+export interface ScheduleTag {
+  id: number;
+  name: string;
+  color: string;
+  created_at: string;
+}
+
+export interface CreateScheduleTagInput {
+  name: string;
+  color: string;
+}
+
+export interface UpdateScheduleTagInput {
+  name?: string;
+  color?: string;
+}
+
+export interface BatchStatusResult {
+  updated: number;
+  not_found: number[];
+}
+
+export interface ExternalRefMatches {
+  external_ref: string;
+  projects: Project[];
+  schedules: ScheduleWithDetails[];
+}
+
+// Weekly Planner Types
 export interface ScheduleEntry {
   id: number;
+  machine_id: number;
+  machine_name?: string;
+  site_timezone?: string;
   project_id: number | null;
   project_name: string | null;
   operator_id: number | null;
@@ -262,11 +912,22 @@ export interface ScheduleEntry {
   drawing_number?: string | null;
   revision?: string | null;
   material?: string | null;
+  shift_id?: number | null;
+  shift_name?: string | null;
+  source: ScheduleSource;
+  created_by_name?: string | null;
+  tag_id?: number | null;
+  tag_name?: string | null;
+  tag_color?: string | null;
+  task_id?: number | null;
+  external_ref?: string | null;
 }
 
 export interface DaySchedule {
   date: string;
   day_name: string;
+  is_holiday: boolean;
+  holiday_name: string | null;
   entries: ScheduleEntry[];
   total_planned_hours: number;
   total_actual_hours: number;
@@ -275,6 +936,9 @@ export interface DaySchedule {
 export interface MachineWeekSchedule {
   machine_id: number;
   machine_name: string;
+  site_id: number | null;
+  site_name: string | null;
+  site_timezone: string | null;
   days: DaySchedule[];
   weekly_planned_hours: number;
   weekly_actual_hours: number;
@@ -284,6 +948,69 @@ export interface WeeklyScheduleResponse {
   week_start: string;
   week_end: string;
   machines: MachineWeekSchedule[];
+  lock: WeekLock | null;
+}
+
+export interface OperatorWeekSchedule {
+  operator_id: number;
+  operator_name: string | null;
+  week_start: string;
+  week_end: string;
+  days: DaySchedule[];
+  weekly_planned_hours: number;
+  weekly_actual_hours: number;
+  lock: WeekLock | null;
+}
+
+export interface WeekDiffEntry {
+  machine_id: number;
+  machine_name: string;
+  weekday: number;
+  day_name: string;
+  entry: ScheduleEntry;
+}
+
+export interface ModifiedWeekEntry {
+  machine_id: number;
+  machine_name: string;
+  weekday: number;
+  day_name: string;
+  before: ScheduleEntry;
+  after: ScheduleEntry;
+  changed_fields: ('load_name' | 'operator' | 'planned_hours')[];
+}
+
+export interface WeekDiff {
+  week_a_start: string;
+  week_b_start: string;
+  added: WeekDiffEntry[];
+  removed: WeekDiffEntry[];
+  modified: ModifiedWeekEntry[];
+  unchanged: number;
+}
+
+export interface WeekLock {
+  week_start: string;
+  locked_by: number | null;
+  locked_by_name: string | null;
+  locked_at: string;
+}
+
+export interface ReassignOperatorResult {
+  reassigned: number;
+  schedule_ids: number[];
+}
+
+export interface StaleReference {
+  schedule_id: number;
+  date: string;
+  machine_id: number;
+  machine_name: string;
+  load_name: string | null;
+  field: 'operator' | 'project';
+  reference_id: number;
+  reference_name: string | null;
+  reason: string;
 }
 
 // Maintenance Types
@@ -300,10 +1027,21 @@ export interface Maintenance {
   cost: number | null;
   status: MaintenanceStatus;
   notes: string | null;
+  runtime_hours_at: number | null;
   created_at: string;
   updated_at: string;
 }
 
+export interface RuntimeMaintenanceDue {
+  machine_id: number;
+  machine_name: string;
+  runtime_hours: number;
+  maintenance_interval_hours: number;
+  last_maintenance_runtime: number | null;
+  hours_since_maintenance: number;
+  hours_overdue: number;
+}
+
 export interface CreateMaintenanceInput {
   machine_id: number;
   date: string;
@@ -347,9 +1085,44 @@ export interface Alert {
   created_at: string;
 }
 
+export interface AlertCue {
+  notify: boolean;
+  sound: boolean;
+  in_quiet_hours: boolean;
+}
+
 export interface AlertWithDetails extends Alert {
   machine_name: string | null;
   project_name: string | null;
+  cue: AlertCue | null;
+  acknowledgement: AlertAcknowledgement | null;
+}
+
+export interface AlertAcknowledgement {
+  id: number;
+  alert_id: number;
+  user_id: number | null;
+  user_name: string | null;
+  comment: string;
+  acknowledged_at: string;
+}
+
+export interface NotificationSettings {
+  device_name: string | null;
+  notify_priorities: AlertPriority[];
+  sound_enabled: boolean;
+  desktop_enabled: boolean;
+  quiet_hours_start: string | null;
+  quiet_hours_end: string | null;
+  is_default: boolean;
+}
+
+export interface UpdateNotificationSettingsInput {
+  notify_priorities: AlertPriority[];
+  sound_enabled: boolean;
+  desktop_enabled?: boolean;
+  quiet_hours_start: string | null;
+  quiet_hours_end: string | null;
 }
 
 export interface CreateAlertInput {
@@ -394,6 +1167,7 @@ export interface DashboardStats {
   project_status: [string, number][];
   top_machines_week: [string, number][];
   weekly_trend: [string, number, number][];
+  demo_data_loaded: boolean;
 }
 
 export interface MachineUtilization {
@@ -409,10 +1183,538 @@ export interface ProjectProgress {
   project_id: number;
   project_name: string;
   status: ProjectStatus;
+  priority: ProjectPriority;
   planned_hours: number;
   actual_hours: number;
   progress_percentage: number;
+  progress_override: number | null;
+  effective_progress: number;
   start_date: string | null;
   end_date: string | null;
   client_name: string | null;
 }
+
+export interface WidgetDefinition {
+  id: string;
+  title: string;
+  required_permission: 'view' | 'edit' | 'admin';
+  default_width: number;
+  default_height: number;
+}
+
+export interface DashboardLayoutItem {
+  widget_id: string;
+  x: number;
+  y: number;
+  w: number;
+  h: number;
+}
+
+export interface DashboardLayout {
+  items: DashboardLayoutItem[];
+  is_default: boolean;
+  warnings: string[];
+}
+
+// Report Types
+export interface DeliveryStats {
+  evaluated_projects: number;
+  on_time_projects: number;
+  late_projects: number;
+  on_time_percentage: number;
+  average_days_late: number;
+  projects_without_end_date: number;
+}
+
+export interface LateProject {
+  project_id: number;
+  project_name: string;
+  status: ProjectStatus;
+  end_date: string;
+  completion_date: string | null;
+  days_late: number;
+}
+
+export interface ClientDeliveryPerformance extends DeliveryStats {
+  client_id: number | null;
+  client_name: string | null;
+  late_project_list: LateProject[];
+}
+
+export interface SourceHours {
+  source: ScheduleSource;
+  entry_count: number;
+  planned_hours: number;
+  actual_hours: number;
+  planned_percentage: number;
+}
+
+export interface DeliveryPerformanceReport {
+  start_date: string;
+  end_date: string;
+  overall: DeliveryStats;
+  milestones: MilestoneDeliveryStats;
+  clients: ClientDeliveryPerformance[];
+}
+
+export interface MilestoneDeliveryStats {
+  evaluated_milestones: number;
+  on_time_milestones: number;
+  late_milestones: number;
+  on_time_percentage: number;
+}
+
+export interface OvertimeEntry {
+  schedule_id: number;
+  date: string;
+  machine_name: string | null;
+  project_name: string | null;
+  planned_hours: number;
+  actual_hours: number;
+  overtime_hours: number;
+}
+
+export interface OperatorOvertime {
+  operator_id: number;
+  operator_name: string;
+  total_planned_hours: number;
+  total_actual_hours: number;
+  delta_hours: number;
+  overtime_entries: OvertimeEntry[];
+}
+
+export interface OvertimeReport {
+  start_date: string;
+  end_date: string;
+  threshold_hours: number;
+  operators: OperatorOvertime[];
+}
+
+export interface WeekKpis {
+  week_start: string;
+  week_end: string;
+  planned_hours: number;
+  actual_hours: number;
+  efficiency: number;
+  top_machine: string | null;
+  top_machine_hours: number;
+  completed_loads: number;
+  maintenance_spend: number;
+}
+
+export type KpiDirection = 'up' | 'down' | 'flat';
+
+export interface KpiDelta {
+  metric: 'planned_hours' | 'actual_hours' | 'efficiency' | 'completed_loads' | 'maintenance_spend';
+  current: number;
+  previous: number;
+  trailing_average: number;
+  change_vs_previous: number;
+  change_vs_average: number;
+  direction_vs_previous: KpiDirection;
+  direction_vs_average: KpiDirection;
+}
+
+export interface WeeklyKpiComparison {
+  current: WeekKpis;
+  previous: WeekKpis;
+  trailing_average: WeekKpis;
+  deltas: KpiDelta[];
+}
+
+export type JobCostGroupBy = 'project' | 'machine' | 'client';
+
+export interface JobCostGroup {
+  group_id: number | null;
+  group_name: string | null;
+  rated_hours: number;
+  machine_cost: number;
+  unrated_hours: number;
+  maintenance_cost: number;
+  total_cost: number;
+}
+
+export interface UnratedMachineHours {
+  machine_id: number;
+  machine_name: string;
+  hours: number;
+}
+
+export interface JobCostReport {
+  start_date: string;
+  end_date: string;
+  group_by: JobCostGroupBy;
+  groups: JobCostGroup[];
+  total_machine_cost: number;
+  total_maintenance_cost: number;
+  unallocated_maintenance_cost: number;
+  unrated_machines: UnratedMachineHours[];
+}
+
+// Timeline Types
+export type TimelineGroupBy = 'machine' | 'project';
+
+export interface TimelineBlock {
+  kind: 'work' | 'gap';
+  start_date: string;
+  end_date: string;
+  days: number;
+  item_id: number | null;
+  item_name: string | null;
+  total_planned_hours: number;
+  total_actual_hours: number;
+  entry_count: number;
+  status_counts: Record<string, number>;
+}
+
+export interface TimelineGroup {
+  group_id: number;
+  group_name: string;
+  blocks: TimelineBlock[];
+  total_planned_hours: number;
+}
+
+export interface TimelineResponse {
+  start_date: string;
+  end_date: string;
+  group_by: TimelineGroupBy;
+  groups: TimelineGroup[];
+}
+
+// Operation Types
+export type OperationState = 'running' | 'completed' | 'failed' | 'cancelled';
+
+export interface OperationProgress {
+  operation_id: string;
+  kind: string;
+  state: OperationState;
+  current: number;
+  total: number;
+  message: string | null;
+}
+
+export interface OperationStatus extends OperationProgress {
+  result: unknown | null;
+  error: string | null;
+  started_by: number;
+  started_at: string;
+  finished_at: string | null;
+}
+
+export interface OperationStarted {
+  operation_id: string;
+}
+
+// Holiday Types
+export type HolidayMode = 'ignore' | 'skip' | 'shift';
+
+export interface Holiday {
+  id: number;
+  date: string;
+  name: string;
+  is_recurring: boolean;
+  created_by: number | null;
+  created_at: string;
+}
+
+export interface CreateHolidayInput {
+  date: string;
+  name: string;
+  is_recurring?: boolean;
+}
+
+export interface UpdateHolidayInput {
+  date?: string;
+  name?: string;
+  is_recurring?: boolean;
+}
+
+// Startup Types
+export type StartupState = 'ready' | 'incompatible' | 'failed';
+
+export interface StartupStatus {
+  state: StartupState;
+  message: string | null;
+  warnings: string[];
+  app_version: string;
+  supported_schema_version: number;
+  database_schema_version: number | null;
+  database_app_version: string | null;
+}
+
+export interface AppWindowInfo {
+  label: string;
+  title: string | null;
+  visible: boolean | null;
+}
+
+// Shift Types
+export interface Shift {
+  id: number;
+  name: string;
+  start_time: string;
+  end_time: string;
+  default_hours: number;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface CreateShiftInput {
+  name: string;
+  start_time: string;
+  end_time: string;
+  default_hours?: number;
+}
+
+export interface UpdateShiftInput {
+  name?: string;
+  start_time?: string;
+  end_time?: string;
+  default_hours?: number;
+}
+
+// Material Types
+export interface ProjectMaterial {
+  id: number;
+  project_id: number;
+  name: string;
+  quantity_required: number;
+  quantity_received: number;
+  unit: string | null;
+  expected_date: string | null;
+  received_date: string | null;
+  notes: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface CreateProjectMaterialInput {
+  project_id: number;
+  name: string;
+  quantity_required: number;
+  quantity_received?: number;
+  unit?: string;
+  expected_date?: string;
+  notes?: string;
+}
+
+export interface UpdateProjectMaterialInput {
+  name?: string;
+  quantity_required?: number;
+  quantity_received?: number;
+  unit?: string;
+  expected_date?: string;
+  received_date?: string;
+  notes?: string;
+}
+
+export interface LateMaterial {
+  material_id: number;
+  name: string;
+  expected_date: string;
+  quantity_required: number;
+  quantity_received: number;
+  shortfall: number;
+  unit: string | null;
+}
+
+export interface MaterialRiskEntry {
+  schedule_id: number;
+  date: string;
+  machine_id: number;
+  machine_name: string;
+  project_id: number;
+  project_name: string;
+  late_materials: LateMaterial[];
+}
+
+// Mobile Types
+export interface MobileAssignment {
+  id: number;
+  date: string;
+  start_time: string | null;
+  end_time: string | null;
+  machine_name: string;
+  project_name: string | null;
+  planned_hours: number;
+  actual_hours: number | null;
+  status: string;
+}
+
+export interface MobileMachine {
+  id: number;
+  name: string;
+}
+
+export interface MobileProgress {
+  date: string;
+  completed: number;
+  total: number;
+}
+
+export interface MobileSummary {
+  next_assignments: MobileAssignment[];
+  unread_alerts: number;
+  machines_in_error: MobileMachine[];
+  today: MobileProgress;
+}
+
+// Integrity Check Types
+export type IntegrityCategoryKey = 'orphaned_rows' | 'hour_mismatches' | 'maintenance_status' | 'orphaned_sessions';
+
+export interface IntegrityIssue {
+  table: string;
+  record_id: number;
+  description: string;
+}
+
+export interface IntegrityCategory {
+  category: IntegrityCategoryKey;
+  label: string;
+  issues: IntegrityIssue[];
+}
+
+export interface IntegrityReport {
+  total_issues: number;
+  categories: IntegrityCategory[];
+}
+
+export interface IntegrityFix {
+  category: IntegrityCategoryKey;
+  fixed: number;
+  changes: string[];
+}
+
+// Workspace (database location) types
+export interface DatabaseInfo {
+  path: string;
+  default_path: string;
+  is_default: boolean;
+  /** Configured database that couldn't be opened at startup, so the default was used */
+  unavailable_path: string | null;
+  size_bytes: number | null;
+  schema_version: number;
+  journal_mode: string;
+  recent_paths: string[];
+}
+
+export interface WorkspaceSwitch {
+  database: DatabaseInfo;
+  sessions_invalidated: number;
+}
+
+// Data export/import types
+export type DataImportMode = 'replace' | 'merge';
+
+export interface TableRowCount {
+  table: string;
+  rows: number;
+}
+
+export interface DataExportSummary {
+  file_path: string;
+  includes_credentials: boolean;
+  /** Attached document files left out of the export, to copy by hand */
+  document_files_not_included: number;
+  tables: TableRowCount[];
+}
+
+export interface TableImportCount {
+  table: string;
+  inserted: number;
+  /** Merge only: rows matched to an existing record on a unique key */
+  matched: number;
+}
+
+export interface DataImportResult {
+  mode: DataImportMode;
+  tables: TableImportCount[];
+  skipped_tables: { table: string; reason: string }[];
+  users_needing_password: string[];
+  signed_out: boolean;
+}
+
+// Demo data types
+export interface DemoDataSummary {
+  machines: number;
+  locations: number;
+  /** Demo machines kept because schedule entries or maintenance were recorded on them */
+  kept_machines: string[];
+}
+
+// Database health types
+export interface TableStats {
+  name: string;
+  rows: number;
+}
+
+export interface IndexStats {
+  name: string;
+  table: string;
+  unique: boolean;
+}
+
+export interface DatabaseHealth {
+  path: string;
+  size_bytes: number | null;
+  /** Write-ahead log not yet checkpointed into the main file */
+  wal_size_bytes: number | null;
+  page_size: number;
+  page_count: number;
+  /** Unused pages VACUUM would give back */
+  freelist_count: number;
+  free_bytes: number;
+  vacuum_recommended: boolean;
+  schema_version: number;
+  journal_mode: string;
+  tables: TableStats[];
+  indexes: IndexStats[];
+  /** Latest full data export, which doubles as the backup */
+  last_backup_at: string | null;
+  last_optimized_at: string | null;
+  integrity_ok: boolean;
+  integrity_messages: string[];
+}
+
+export interface OptimizeResult {
+  size_before: number | null;
+  size_after: number | null;
+  duration_ms: number;
+}
+
+// Application settings types
+export interface SettingValue {
+  key: string;
+  value: string;
+  default_value: string;
+  value_type: 'integer' | 'float' | 'string';
+  description: string;
+  min: number | null;
+  max: number | null;
+  /** Accepted values for settings with a fixed list, e.g. weekdays */
+  options: string[];
+  updated_by: number | null;
+  updated_by_name: string | null;
+  updated_at: string | null;
+}
+
+// Data change event types
+/** Events the backend emits after a change is saved, for `listen` from '@tauri-apps/api/event' */
+export type DataChangeEvent =
+  | 'schedule:changed'
+  | 'machine:changed'
+  | 'machine:status'
+  | 'maintenance:changed'
+  | 'alert:changed';
+
+/** Payload of the *:changed events; one event lists every row a command touched */
+export interface DataChanged {
+  action: 'created' | 'updated' | 'deleted';
+  ids: number[];
+}
+
+/** Payload of machine:status */
+export interface MachineStatusChanged {
+  machine_id: number;
+  status: string;
+}
@@ -0,0 +1,20 @@
+// Error handling for values thrown by Tauri commands
+
+import type { AppError } from '../types';
+
+/**
+ * Whether a thrown value is an error returned by a backend command
+ */
+export function isAppError(err: unknown): err is AppError {
+  return typeof err === 'object' && err !== null && 'code' in err && 'message' in err;
+}
+
+/**
+ * Text to show for a failed command, falling back when there is none
+ */
+export function errorMessage(err: unknown, fallback = 'Something went wrong'): string {
+  if (isAppError(err)) return err.message;
+  if (typeof err === 'string') return err;
+  if (err instanceof Error) return err.message;
+  return fallback;
+}
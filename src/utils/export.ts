@@ -1,7 +1,7 @@
 import * as XLSX from 'xlsx';
 import { jsPDF } from 'jspdf';
 import autoTable from 'jspdf-autotable';
-import type { WeeklyScheduleResponse, ProjectWithDetails, Machine } from '../types';
+import type { WeeklyScheduleResponse, ProjectWithDetails, Machine, DeliveryPerformanceReport } from '../types';
 
 type ExcelCell = string | number | null | undefined;
 type ExcelRow = ExcelCell[];
@@ -137,6 +137,58 @@ export function exportMachinesToExcel(machines: Machine[]): void {
   XLSX.writeFile(wb, `VMC_Machines_${new Date().toISOString().split('T')[0]}.xlsx`);
 }
 
+export function exportDeliveryPerformanceToExcel(report: DeliveryPerformanceReport): void {
+  const wb = XLSX.utils.book_new();
+
+  const summary: ExcelRow[] = [
+    ['Client', 'Evaluated', 'On Time', 'Late', 'On-Time %', 'Avg Days Late', 'No End Date'],
+  ];
+  report.clients.forEach(client => {
+    summary.push([
+      client.client_name || 'No client',
+      client.evaluated_projects,
+      client.on_time_projects,
+      client.late_projects,
+      client.on_time_percentage.toFixed(1),
+      client.average_days_late.toFixed(1),
+      client.projects_without_end_date,
+    ]);
+  });
+  summary.push([
+    'TOTAL',
+    report.overall.evaluated_projects,
+    report.overall.on_time_projects,
+    report.overall.late_projects,
+    report.overall.on_time_percentage.toFixed(1),
+    report.overall.average_days_late.toFixed(1),
+    report.overall.projects_without_end_date,
+  ]);
+  const summarySheet = XLSX.utils.aoa_to_sheet(summary);
+  summarySheet['!cols'] = [{ wch: 25 }, { wch: 10 }, { wch: 10 }, { wch: 8 }, { wch: 10 }, { wch: 14 }, { wch: 12 }];
+  XLSX.utils.book_append_sheet(wb, summarySheet, 'Delivery Summary');
+
+  const late: ExcelRow[] = [
+    ['Client', 'Project', 'Status', 'End Date', 'Completed', 'Days Late'],
+  ];
+  report.clients.forEach(client => {
+    client.late_project_list.forEach(project => {
+      late.push([
+        client.client_name || 'No client',
+        project.project_name,
+        project.status,
+        project.end_date,
+        project.completion_date || '-',
+        project.days_late,
+      ]);
+    });
+  });
+  const lateSheet = XLSX.utils.aoa_to_sheet(late);
+  lateSheet['!cols'] = [{ wch: 25 }, { wch: 30 }, { wch: 12 }, { wch: 12 }, { wch: 12 }, { wch: 10 }];
+  XLSX.utils.book_append_sheet(wb, lateSheet, 'Late Projects');
+
+  XLSX.writeFile(wb, `VMC_Delivery_Performance_${report.start_date}_to_${report.end_date}.xlsx`);
+}
+
 // PDF Export Functions
 
 export function exportWeeklyScheduleToPDF(schedule: WeeklyScheduleResponse): void {